@@ -0,0 +1,151 @@
+// Achievements module
+// Small milestones to celebrate: the first ten tasks done, a task done every
+// day for a week, a hundred voice notes. They're worked out from the task list
+// and the transcription log, and kept once unlocked in the `achievements`
+// table so each is only celebrated once (and stays unlocked if the tasks that
+// earned it are deleted). Purely local; there are no accounts or leaderboards.
+// Both apps call `check` after tasks are completed or a note is transcribed.
+
+use crate::database::{self, Database};
+use chrono::{Duration, Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Achievement {
+    /// Ten tasks completed
+    TenTasks,
+    /// A task completed on each of seven days in a row
+    WeekStreak,
+    /// A hundred voice notes transcribed
+    HundredCaptures,
+}
+
+pub const ALL: [Achievement; 3] = [Achievement::TenTasks, Achievement::WeekStreak, Achievement::HundredCaptures];
+
+impl Achievement {
+    /// Id as stored and sent to the frontend
+    pub fn id(&self) -> &'static str {
+        match self {
+            Achievement::TenTasks => "ten_tasks",
+            Achievement::WeekStreak => "week_streak",
+            Achievement::HundredCaptures => "hundred_captures",
+        }
+    }
+
+    /// Count needed to unlock it
+    pub fn goal(&self) -> u32 {
+        match self {
+            Achievement::TenTasks => 10,
+            Achievement::WeekStreak => 7,
+            Achievement::HundredCaptures => 100,
+        }
+    }
+}
+
+/// An achievement and how close it is
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AchievementStatus {
+    pub id: Achievement,
+    pub title: String,
+    pub description: String,
+    /// Up to `goal`
+    pub progress: u32,
+    pub goal: u32,
+    /// UTC "YYYY-MM-DD HH:MM:SS", None while locked
+    pub unlocked_at: Option<String>,
+}
+
+/// Every achievement with its progress, unlocked or not
+pub fn get_achievements(db: &Database) -> Result<Vec<AchievementStatus>, String> {
+    let unlocked = database::unlocked_achievements(db).map_err(|e| e.to_string())?;
+    ALL.iter()
+        .map(|achievement| {
+            let progress = progress(db, *achievement)?;
+            Ok(status(*achievement, progress, unlocked.get(achievement.id()).cloned()))
+        })
+        .collect()
+}
+
+/// Unlock whatever has been reached since the last check, returning only the
+/// newly unlocked ones (usually none)
+pub fn check(db: &Database) -> Vec<AchievementStatus> {
+    let unlocked = match database::unlocked_achievements(db) {
+        Ok(unlocked) => unlocked,
+        Err(e) => {
+            tracing::warn!("Failed to read achievements: {}", e);
+            return Vec::new();
+        }
+    };
+    let mut newly = Vec::new();
+    for achievement in ALL.iter().filter(|achievement| !unlocked.contains_key(achievement.id())) {
+        let progress = match progress(db, *achievement) {
+            Ok(progress) => progress,
+            Err(e) => {
+                tracing::warn!("Failed to check the {} achievement: {}", achievement.id(), e);
+                continue;
+            }
+        };
+        if progress < achievement.goal() {
+            continue;
+        }
+        match database::unlock_achievement(db, achievement.id()) {
+            Ok(true) => {
+                tracing::info!("Achievement unlocked: {}", achievement.id());
+                let now = chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string();
+                newly.push(status(*achievement, progress, Some(now)));
+            }
+            Ok(false) => {}
+            Err(e) => tracing::warn!("Failed to unlock the {} achievement: {}", achievement.id(), e),
+        }
+    }
+    newly
+}
+
+/// "Achievement unlocked: Ten down" for a notification or status line
+pub fn unlocked_message(achievement: &AchievementStatus) -> String {
+    crate::i18n::tf("achievements.unlocked", &[("title", &achievement.title)])
+}
+
+fn status(achievement: Achievement, progress: u32, unlocked_at: Option<String>) -> AchievementStatus {
+    let key = achievement.id();
+    AchievementStatus {
+        id: achievement,
+        title: crate::i18n::t(&format!("achievements.{}", key)),
+        description: crate::i18n::t(&format!("achievements.{}_description", key)),
+        progress: progress.min(achievement.goal()),
+        goal: achievement.goal(),
+        unlocked_at,
+    }
+}
+
+fn progress(db: &Database, achievement: Achievement) -> Result<u32, String> {
+    let count = match achievement {
+        Achievement::TenTasks => database::count_completed_tasks(db).map_err(|e| e.to_string())?,
+        Achievement::HundredCaptures => database::count_transcriptions(db).map_err(|e| e.to_string())?,
+        Achievement::WeekStreak => {
+            let dates = database::completion_dates(db).map_err(|e| e.to_string())?;
+            return Ok(streak(&dates, Local::now().date_naive()));
+        }
+    };
+    Ok(u32::try_from(count).unwrap_or(u32::MAX))
+}
+
+// Days in a row with a completed task, up to today (or yesterday, since today
+// isn't over yet); `dates` are local "YYYY-MM-DD", newest first
+fn streak(dates: &[String], today: NaiveDate) -> u32 {
+    let mut dates = dates.iter().filter_map(|date| NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()).peekable();
+    let mut expected = match dates.peek() {
+        Some(&first) if first == today || first == today - Duration::days(1) => first,
+        _ => return 0,
+    };
+    let mut streak = 0;
+    for date in dates {
+        if date != expected {
+            break;
+        }
+        streak += 1;
+        expected -= Duration::days(1);
+    }
+    streak
+}
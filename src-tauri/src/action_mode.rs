@@ -0,0 +1,125 @@
+// Action mode module
+// An opt-in split between commands and dictation that takes the guessing out
+// of voice notes. With `settings.action_mode` on, only a recording that starts
+// with "command:" (or "команда:") is a command: one of the app's voice
+// commands (timer, habits, navigation...) or "add / complete / delete <task>",
+// and a command nothing recognizes is an error rather than a task. Everything
+// else becomes a single task, word for word, without the keyword heuristics
+// or the parser splitting it up.
+
+/// How a transcript is read in action mode
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Utterance<'a> {
+    /// What followed "command:"
+    Command(&'a str),
+    /// The text of the one task to add
+    Dictation(&'a str),
+}
+
+/// A change to the task list spelled out after "command:"
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TaskCommand {
+    Add(String),
+    /// Complete the task best matching the text
+    Complete(String),
+    /// Delete the task best matching the text
+    Delete(String),
+}
+
+const PREFIXES: [&str; 2] = ["command", "команда"];
+// Longer phrases first, so "new task" wins over a task called "new ..."
+const VERBS: [(&str, fn(String) -> TaskCommand); 18] = [
+    ("new task", TaskCommand::Add),
+    ("add", TaskCommand::Add),
+    ("create", TaskCommand::Add),
+    ("добавь", TaskCommand::Add),
+    ("создай", TaskCommand::Add),
+    ("complete", TaskCommand::Complete),
+    ("finish", TaskCommand::Complete),
+    ("done with", TaskCommand::Complete),
+    ("done", TaskCommand::Complete),
+    ("check off", TaskCommand::Complete),
+    ("выполни", TaskCommand::Complete),
+    ("заверши", TaskCommand::Complete),
+    ("готово", TaskCommand::Complete),
+    ("delete", TaskCommand::Delete),
+    ("remove", TaskCommand::Delete),
+    ("cancel", TaskCommand::Delete),
+    ("удали", TaskCommand::Delete),
+    ("отмени", TaskCommand::Delete),
+];
+// "mark <task> done"
+const MARK: [&str; 2] = ["mark", "отметь"];
+const MARK_DONE: [&str; 6] = [
+    "as done",
+    "done",
+    "as complete",
+    "complete",
+    "выполненной",
+    "сделанной",
+];
+// Dropped between the verb and the task ("add task: ...", "delete the task ...")
+const TASK_WORDS: [&str; 4] = ["the task", "task", "задачу", "задача"];
+
+/// Whether a transcript is a command or dictation
+pub fn classify(transcript: &str) -> Utterance<'_> {
+    let text = transcript.trim();
+    match PREFIXES
+        .iter()
+        .find_map(|prefix| strip_phrase(text, prefix))
+    {
+        Some(rest) => Utterance::Command(trim_text(rest)),
+        None => Utterance::Dictation(trim_text(text)),
+    }
+}
+
+/// The task change a command spells out, or None when it isn't one
+pub fn task_command(command: &str) -> Option<TaskCommand> {
+    if let Some(rest) = MARK.iter().find_map(|verb| strip_phrase(command, verb)) {
+        let rest = trim_text(rest);
+        let lower = rest.to_lowercase();
+        let text = MARK_DONE.iter().find_map(|suffix| {
+            let head = lower.strip_suffix(suffix)?.strip_suffix(' ')?;
+            rest.get(..head.len())
+        })?;
+        return task_text(text).map(TaskCommand::Complete);
+    }
+    VERBS
+        .iter()
+        .find_map(|(verb, command_for)| Some(command_for(task_text(strip_phrase(command, verb)?)?)))
+}
+
+// The task after the verb, without "task" and the punctuation around it
+fn task_text(rest: &str) -> Option<String> {
+    let rest = trim_text(rest);
+    let text = trim_text(
+        TASK_WORDS
+            .iter()
+            .find_map(|word| strip_phrase(rest, word))
+            .unwrap_or(rest),
+    );
+    (!text.is_empty()).then(|| text.to_string())
+}
+
+// `text` without the words of `phrase` at its start, ignoring case; the words
+// must be whole ("address" doesn't start with "add")
+fn strip_phrase<'a>(text: &'a str, phrase: &str) -> Option<&'a str> {
+    let mut rest = text;
+    for word in phrase.split(' ') {
+        rest = rest.trim_start();
+        let end = rest
+            .find(|c: char| !c.is_alphanumeric())
+            .unwrap_or(rest.len());
+        if rest[..end].to_lowercase() != word {
+            return None;
+        }
+        rest = &rest[end..];
+    }
+    Some(rest)
+}
+
+// Without the separators after a prefix and the full stop Whisper ends on
+fn trim_text(text: &str) -> &str {
+    text.trim_start_matches(|c: char| c.is_whitespace() || [':', ',', '.', '-', '—'].contains(&c))
+        .trim_end_matches(|c: char| c.is_whitespace() || ['.', '!'].contains(&c))
+}
@@ -0,0 +1,254 @@
+// API tokens module
+// The authorization layer for clients that reach FlowState over the network
+// rather than through its own windows (an HTTP server, an MCP server, bots).
+// Each client gets its own token, issued from the settings with a name and
+// the scopes it needs: `read` to see tasks, `write` to change them, `voice` to
+// send recordings. The secret is shown once, when it's issued; only its
+// SHA-256 is stored, so a copy of the database can't be used to get in.
+// `authorize` is what a network surface calls for each request: the token
+// must exist (revoking deletes it) and carry the scope of the command asked
+// for, and commands not listed in `COMMAND_SCOPES` can't be called remotely
+// at all. Only surfaces that accept connections from clients need it; the
+// Telegram bridge, the MQTT publisher and the Todoist sync are connections
+// the app makes itself, set up in Settings, and hold no token.
+
+use crate::database::{self, ApiTokenRow, Database};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+// Marks FlowState secrets, so they're recognizable in a config file
+const SECRET_PREFIX: &str = "fst_";
+// Random bytes in a secret (hex-encoded after the prefix)
+const SECRET_BYTES: usize = 32;
+// Characters of the secret kept in the clear, to tell tokens apart
+const SHOWN_CHARS: usize = SECRET_PREFIX.len() + 6;
+
+/// What a token lets its client do
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Scope {
+    /// See tasks, habits, the journal and the timer
+    Read,
+    /// Add, change, complete and delete tasks
+    Write,
+    /// Send voice recordings to transcribe and apply
+    Voice,
+}
+
+impl Scope {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Scope::Read => "read",
+            Scope::Write => "write",
+            Scope::Voice => "voice",
+        }
+    }
+
+    pub fn from_str(scope: &str) -> Option<Self> {
+        match scope.trim() {
+            "read" => Some(Scope::Read),
+            "write" => Some(Scope::Write),
+            "voice" => Some(Scope::Voice),
+            _ => None,
+        }
+    }
+}
+
+/// The commands a network client may call, with the scope each needs
+pub const COMMAND_SCOPES: &[(&str, Scope)] = &[
+    ("get_tasks", Scope::Read),
+    ("get_habits", Scope::Read),
+    ("get_journal_entries", Scope::Read),
+    ("get_timer_status", Scope::Read),
+    ("get_inbox_count", Scope::Read),
+    ("add_task", Scope::Write),
+    ("add_tasks", Scope::Write),
+    ("update_task", Scope::Write),
+    ("toggle_task", Scope::Write),
+    ("complete_tasks", Scope::Write),
+    ("delete_task", Scope::Write),
+    ("delete_tasks", Scope::Write),
+    ("toggle_habit", Scope::Write),
+    ("process_voice_log", Scope::Write),
+    ("process_voice_recording", Scope::Voice),
+];
+
+/// The scope a command needs, or None when it can't be called remotely
+pub fn required_scope(command: &str) -> Option<Scope> {
+    COMMAND_SCOPES
+        .iter()
+        .find(|(name, _)| *name == command)
+        .map(|&(_, scope)| scope)
+}
+
+/// An issued token, without its secret
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiToken {
+    pub id: i64,
+    pub name: String,
+    /// The start of the secret ("fst_3fa9c1…")
+    pub prefix: String,
+    pub scopes: Vec<Scope>,
+    /// In UTC
+    pub created_at: String,
+    /// The last time a client sent it, in UTC
+    pub last_used_at: Option<String>,
+}
+
+impl ApiToken {
+    pub fn allows(&self, scope: Scope) -> bool {
+        self.scopes.contains(&scope)
+    }
+}
+
+/// A token just issued, with the secret to hand to the client. The secret
+/// isn't stored and can't be shown again.
+#[derive(Debug, Clone, Serialize)]
+pub struct IssuedToken {
+    pub token: ApiToken,
+    pub secret: String,
+}
+
+fn from_row(row: ApiTokenRow) -> ApiToken {
+    ApiToken {
+        id: row.id,
+        name: row.name,
+        prefix: row.prefix,
+        // Scopes that can't be read grant nothing
+        scopes: row.scopes.split(',').filter_map(Scope::from_str).collect(),
+        created_at: row.created_at,
+        last_used_at: row.last_used_at,
+    }
+}
+
+fn hash(secret: &str) -> String {
+    Sha256::digest(secret.trim().as_bytes())
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+/// Every token issued and not revoked, oldest first
+pub fn list(db: &Database) -> Result<Vec<ApiToken>, String> {
+    let rows = database::get_api_tokens(db).map_err(|e| e.to_string())?;
+    Ok(rows.into_iter().map(from_row).collect())
+}
+
+/// Issue a token with these scopes for the client called `name`
+pub fn issue(db: &Database, name: &str, scopes: &[Scope]) -> Result<IssuedToken, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(crate::i18n::t("error.token_name_empty"));
+    }
+    if scopes.is_empty() {
+        return Err(crate::i18n::t("error.token_no_scopes"));
+    }
+    let mut bytes = [0u8; SECRET_BYTES];
+    getrandom::getrandom(&mut bytes).map_err(|e| format!("Failed to generate a token: {}", e))?;
+    let secret: String = std::iter::once(SECRET_PREFIX.to_string())
+        .chain(bytes.iter().map(|byte| format!("{:02x}", byte)))
+        .collect();
+
+    // In a fixed order, without repeats
+    let scopes: Vec<&str> = [Scope::Read, Scope::Write, Scope::Voice]
+        .into_iter()
+        .filter(|scope| scopes.contains(scope))
+        .map(Scope::as_str)
+        .collect();
+    let row = database::add_api_token(
+        db,
+        name,
+        &hash(&secret),
+        &secret[..SHOWN_CHARS],
+        &scopes.join(","),
+    )
+    .map_err(|e| e.to_string())?;
+    tracing::info!("Issued API token {} ({}) for {}", row.id, row.scopes, name);
+    Ok(IssuedToken {
+        token: from_row(row),
+        secret,
+    })
+}
+
+/// Revoke a token; its client is turned away from the next request on
+pub fn revoke(db: &Database, id: i64) -> Result<(), String> {
+    if !database::delete_api_token(db, id).map_err(|e| e.to_string())? {
+        return Err(crate::i18n::tf("error.token_not_found", &[("id", &id)]));
+    }
+    tracing::info!("Revoked API token {}", id);
+    Ok(())
+}
+
+/// The token behind `secret`, if it may call `command`. Every network surface
+/// runs each request through this before doing anything.
+pub fn authorize(db: &Database, secret: &str, command: &str) -> Result<ApiToken, String> {
+    let scope = required_scope(command)
+        .ok_or_else(|| crate::i18n::tf("error.token_command", &[("command", &command)]))?;
+    if secret.trim().is_empty() {
+        return Err(crate::i18n::t("error.token_missing"));
+    }
+    let token = database::use_api_token(db, &hash(secret))
+        .map_err(|e| e.to_string())?
+        .map(from_row)
+        .ok_or_else(|| crate::i18n::t("error.token_invalid"))?;
+    if !token.allows(scope) {
+        tracing::warn!(
+            "API token {} ({}) was refused {}: it lacks the {} scope",
+            token.id,
+            token.name,
+            command,
+            scope.as_str()
+        );
+        return Err(crate::i18n::tf(
+            "error.token_scope",
+            &[("command", &command), ("scope", &scope.as_str())],
+        ));
+    }
+    Ok(token)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn api_tokens_are_stored_hashed_and_limited_to_their_scopes() {
+        let db = Database::new_in_memory().unwrap();
+        let issued = issue(&db, "Phone", &[Scope::Voice, Scope::Read]).unwrap();
+
+        assert_eq!(issued.token.scopes, [Scope::Read, Scope::Voice]);
+        assert!(issued.token.prefix.len() < issued.secret.len() && issued.secret.starts_with(&issued.token.prefix));
+        let stored: String = db.conn.lock().unwrap().query_row("SELECT token_hash FROM api_tokens", [], |row| row.get(0)).unwrap();
+        assert!(!stored.contains(&issued.secret["fst_".len()..]));
+
+        assert_eq!(authorize(&db, &issued.secret, "get_tasks").unwrap().name, "Phone");
+        assert!(authorize(&db, &issued.secret, "process_voice_recording").is_ok());
+        assert!(authorize(&db, &issued.secret, "add_task").is_err());
+        assert!(authorize(&db, &issued.secret, "update_settings").is_err());
+        assert!(authorize(&db, "fst_guess", "get_tasks").is_err());
+        assert!(list(&db).unwrap()[0].last_used_at.is_some());
+
+        revoke(&db, issued.token.id).unwrap();
+        assert!(authorize(&db, &issued.secret, "get_tasks").is_err());
+        assert!(list(&db).unwrap().is_empty());
+        assert!(issue(&db, "Nothing", &[]).is_err());
+    }
+
+    #[test]
+    fn network_surfaces_are_refused_without_a_token_that_has_their_scope() {
+        let db = Database::new_in_memory().unwrap();
+        let phone = issue(&db, "Phone", &[Scope::Voice]).unwrap();
+        let dashboard = issue(&db, "Dashboard", &[Scope::Read]).unwrap();
+
+        assert!(authorize(&db, &phone.secret, "process_voice_recording").is_ok());
+        assert!(authorize(&db, &phone.secret, "process_voice_log").is_err());
+        assert!(authorize(&db, "", "process_voice_recording").is_err());
+        assert!(authorize(&db, &dashboard.secret, "get_timer_status").is_ok());
+        assert!(authorize(&db, &dashboard.secret, "update_task").is_err());
+        // Not callable remotely, whatever the token
+        assert!(authorize(&db, &dashboard.secret, "switch_database").is_err());
+
+        revoke(&db, dashboard.token.id).unwrap();
+        assert!(authorize(&db, &dashboard.secret, "get_timer_status").is_err());
+    }
+}
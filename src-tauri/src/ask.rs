@@ -0,0 +1,146 @@
+// Ask module
+// Questions about the task history ("what did I finish last week related to
+// the website?"). The tasks that best match the question, plus the most recent
+// ones for questions about time, are handed to the Ollama model with their
+// dates and focus time; it answers and names the tasks it used.
+
+use crate::database::{self, Database, TaskHistory};
+use crate::settings::OllamaSettings;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+// Tasks given to the model; small models lose track of longer lists
+const MAX_CONTEXT_TASKS: usize = 40;
+// Of those, at most this many are picked by matching words
+const MAX_MATCHED_TASKS: usize = 25;
+// Words that say nothing about which tasks are meant
+const STOP_WORDS: &[&str] = &[
+    "the", "and", "for", "what", "which", "when", "did", "does", "have", "has", "how", "many", "much",
+    "that", "this", "with", "about", "related", "last", "week", "month", "today", "yesterday", "finish",
+    "finished", "done", "complete", "completed", "task", "tasks", "work", "worked", "spend", "spent",
+    "time", "any", "all", "are", "was", "were", "from", "since",
+];
+
+/// The model's answer and the tasks it is based on
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskAnswer {
+    pub answer: String,
+    pub task_ids: Vec<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ModelAnswer {
+    answer: String,
+    #[serde(default)]
+    task_ids: Vec<i64>,
+}
+
+/// Answer `question` from the task history with the Ollama model (None when
+/// Ollama is turned off in settings; there is no fallback without it)
+pub async fn ask_tasks(db: &Database, question: &str, ollama: Option<&OllamaSettings>) -> Result<TaskAnswer, String> {
+    let config = ollama.ok_or_else(|| crate::i18n::t("error.ask_needs_ollama"))?;
+    let question = question.trim();
+    if question.is_empty() {
+        return Err(crate::i18n::t("error.ask_empty"));
+    }
+    config.validate()?;
+    let history = database::get_task_history(db).map_err(|e| e.to_string())?;
+    let context = relevant_tasks(&history, question);
+
+    let prompt = format!(
+        r#"You answer questions about the user's task list. Use ONLY the tasks below.
+Each line: id | status | created date | completed date | focus minutes | text
+Today is {today}.
+
+{tasks}
+
+Question: "{question}"
+
+Answer in the language of the question, in one to three sentences.
+Return ONLY JSON: {{"answer": "...", "task_ids": [ids of the tasks the answer is based on]}}"#,
+        today = chrono::Local::now().format("%Y-%m-%d (%A)"),
+        tasks = context.iter().map(|item| context_line(item)).collect::<Vec<_>>().join("\n"),
+        question = question,
+    );
+    let response = crate::ollama::generate(&prompt, config).await?;
+
+    let json = extract_json_object(&response).ok_or_else(|| format!("Failed to parse answer: {}", response))?;
+    let parsed: ModelAnswer = serde_json::from_str(json).map_err(|e| format!("Failed to parse answer: {}", e))?;
+    // Models sometimes invent ids; keep the ones that were actually shown
+    let known: HashSet<i64> = context.iter().map(|item| item.task.id).collect();
+    let mut seen = HashSet::new();
+    let task_ids: Vec<i64> = parsed
+        .task_ids
+        .into_iter()
+        .filter(|id| known.contains(id) && seen.insert(*id))
+        .collect();
+    Ok(TaskAnswer {
+        answer: parsed.answer.trim().to_string(),
+        task_ids,
+    })
+}
+
+/// Tasks sharing words with the question first, then the most recent ones
+fn relevant_tasks<'a>(history: &'a [TaskHistory], question: &str) -> Vec<&'a TaskHistory> {
+    let keywords: Vec<String> = question
+        .split(|c: char| !c.is_alphanumeric())
+        .map(str::to_lowercase)
+        .filter(|word| word.chars().count() >= 3 && !STOP_WORDS.contains(&word.as_str()))
+        .map(|word| stem(&word))
+        .collect();
+
+    // History is newest first, and the sort is stable, so ties stay newest first
+    let mut scored: Vec<(usize, &TaskHistory)> = history
+        .iter()
+        .map(|item| {
+            let text = item.task.text.to_lowercase();
+            (keywords.iter().filter(|keyword| text.contains(keyword.as_str())).count(), item)
+        })
+        .filter(|(score, _)| *score > 0)
+        .collect();
+    scored.sort_by(|a, b| b.0.cmp(&a.0));
+
+    let mut context: Vec<&TaskHistory> = scored.into_iter().take(MAX_MATCHED_TASKS).map(|(_, item)| item).collect();
+    for item in history {
+        if context.len() >= MAX_CONTEXT_TASKS {
+            break;
+        }
+        if !context.iter().any(|known| known.task.id == item.task.id) {
+            context.push(item);
+        }
+    }
+    context
+}
+
+// Crude stem so "websites" still matches "website" (and "задачами" matches "задача")
+fn stem(word: &str) -> String {
+    let chars: Vec<char> = word.chars().collect();
+    if chars.len() > 6 {
+        chars[..chars.len() - 2].iter().collect()
+    } else {
+        word.to_string()
+    }
+}
+
+fn context_line(item: &TaskHistory) -> String {
+    let date = |timestamp: &str| timestamp.get(..10).unwrap_or(timestamp).to_string();
+    // The context goes after the text the way it's typed ("Buy batteries @errand")
+    let context = item.task.context.as_deref().map(|context| format!(" @{}", context)).unwrap_or_default();
+    format!(
+        "{} | {} | {} | {} | {} | {}{}",
+        item.task.id,
+        if item.task.completed { "done" } else { "open" },
+        date(&item.task.created_at),
+        item.task.completed_at.as_deref().map(date).unwrap_or_else(|| "-".to_string()),
+        item.focus_seconds / 60,
+        item.task.text.replace('\n', " "),
+        context,
+    )
+}
+
+// The JSON object in a reply that may be wrapped in a code block or prose
+fn extract_json_object(response: &str) -> Option<&str> {
+    let start = response.find('{')?;
+    let end = response.rfind('}')?;
+    (end > start).then(|| &response[start..=end])
+}
@@ -0,0 +1,212 @@
+// Audio module
+// Decodes imported voice memos (WAV/MP3/M4A/OGG) and Telegram voice notes
+// (Ogg/Opus) into mono f32 samples for Whisper
+// and checks captured recordings before they are sent for transcription
+
+use std::path::Path;
+use symphonia::core::audio::SampleBuffer;
+use symphonia::core::codecs::{DecoderOptions, CODEC_TYPE_NULL};
+use symphonia::core::errors::Error as SymphoniaError;
+use symphonia::core::formats::FormatOptions;
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::MetadataOptions;
+use symphonia::core::probe::Hint;
+
+/// File extensions accepted by `import_audio_file`
+pub const SUPPORTED_EXTENSIONS: [&str; 4] = ["wav", "mp3", "m4a", "ogg"];
+
+/// Decode an audio file into mono f32 samples. Returns (samples, sample_rate).
+pub fn decode_audio_file(path: &Path) -> Result<(Vec<f32>, u32), String> {
+    let extension = path.extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_lowercase())
+        .unwrap_or_default();
+
+    if !SUPPORTED_EXTENSIONS.contains(&extension.as_str()) {
+        return Err(format!(
+            "Unsupported audio format '{}'. Supported formats: {}",
+            extension,
+            SUPPORTED_EXTENSIONS.join(", ")
+        ));
+    }
+
+    let file = std::fs::File::open(path)
+        .map_err(|e| format!("Failed to open audio file: {}", e))?;
+    let mss = MediaSourceStream::new(Box::new(file), Default::default());
+
+    let mut hint = Hint::new();
+    hint.with_extension(&extension);
+
+    let probed = symphonia::default::get_probe()
+        .format(&hint, mss, &FormatOptions::default(), &MetadataOptions::default())
+        .map_err(|e| format!("Failed to read audio format: {}", e))?;
+    let mut format = probed.format;
+
+    let track = format.tracks()
+        .iter()
+        .find(|t| t.codec_params.codec != CODEC_TYPE_NULL)
+        .ok_or_else(|| "No audio track found in file".to_string())?;
+    let track_id = track.id;
+    let sample_rate = track.codec_params.sample_rate
+        .ok_or_else(|| "Audio file has unknown sample rate".to_string())?;
+
+    let mut decoder = symphonia::default::get_codecs()
+        .make(&track.codec_params, &DecoderOptions::default())
+        .map_err(|e| format!("Unsupported audio codec: {}", e))?;
+
+    let mut samples: Vec<f32> = Vec::new();
+
+    loop {
+        let packet = match format.next_packet() {
+            Ok(packet) => packet,
+            // End of stream
+            Err(SymphoniaError::IoError(ref e)) if e.kind() == std::io::ErrorKind::UnexpectedEof => break,
+            Err(e) => return Err(format!("Failed to read audio packet: {}", e)),
+        };
+
+        if packet.track_id() != track_id {
+            continue;
+        }
+
+        let decoded = match decoder.decode(&packet) {
+            Ok(decoded) => decoded,
+            // Skip corrupted frames instead of failing the whole memo
+            Err(SymphoniaError::DecodeError(e)) => {
+                tracing::warn!("Skipping undecodable audio frame: {}", e);
+                continue;
+            }
+            Err(e) => return Err(format!("Failed to decode audio: {}", e)),
+        };
+
+        let spec = *decoded.spec();
+        let channels = spec.channels.count().max(1);
+        let mut buffer = SampleBuffer::<f32>::new(decoded.capacity() as u64, spec);
+        buffer.copy_interleaved_ref(decoded);
+
+        // Downmix to mono
+        samples.extend(
+            buffer.samples()
+                .chunks(channels)
+                .map(|frame| frame.iter().sum::<f32>() / channels as f32)
+        );
+    }
+
+    if samples.is_empty() {
+        return Err("Audio file contains no samples".to_string());
+    }
+
+    tracing::debug!("Decoded {} samples at {} Hz from {}", samples.len(), sample_rate, path.display());
+    Ok((samples, sample_rate))
+}
+
+/// Sample rate of `decode_ogg_opus` output (Opus always decodes at 48 kHz)
+pub const OPUS_SAMPLE_RATE: u32 = 48000;
+// Longest Opus frame: 120 ms at 48 kHz
+const MAX_OPUS_FRAME: usize = 5760;
+
+/// Decode an Ogg/Opus stream (a Telegram voice note) into mono f32 samples at
+/// `OPUS_SAMPLE_RATE`. Symphonia has no Opus decoder, so this uses libopus.
+pub fn decode_ogg_opus(data: &[u8]) -> Result<Vec<f32>, String> {
+    let mut reader = ogg::reading::PacketReader::new(std::io::Cursor::new(data));
+    let mut decoder = opus::Decoder::new(OPUS_SAMPLE_RATE, opus::Channels::Mono)
+        .map_err(|e| format!("Failed to create Opus decoder: {}", e))?;
+
+    // The first packet is the OpusHead header, the second the tags
+    let head = reader.read_packet()
+        .map_err(|e| format!("Failed to read audio packet: {}", e))?
+        .filter(|packet| packet.data.starts_with(b"OpusHead") && packet.data.len() >= 12)
+        .ok_or_else(|| "Not an Ogg/Opus stream".to_string())?;
+    // Encoder delay to drop from the start
+    let pre_skip = u16::from_le_bytes([head.data[10], head.data[11]]) as usize;
+    reader.read_packet().map_err(|e| format!("Failed to read audio packet: {}", e))?;
+
+    let mut samples: Vec<f32> = Vec::new();
+    let mut frame = vec![0.0f32; MAX_OPUS_FRAME];
+    while let Some(packet) = reader.read_packet().map_err(|e| format!("Failed to read audio packet: {}", e))? {
+        match decoder.decode_float(&packet.data, &mut frame, false) {
+            Ok(count) => samples.extend_from_slice(&frame[..count]),
+            // Skip corrupted frames instead of failing the whole note
+            Err(e) => tracing::warn!("Skipping undecodable audio frame: {}", e),
+        }
+    }
+    samples.drain(..pre_skip.min(samples.len()));
+
+    if samples.is_empty() {
+        return Err("Audio file contains no samples".to_string());
+    }
+    Ok(samples)
+}
+
+/// Shortest recording worth transcribing
+pub const MIN_RECORDING_SECS: f32 = 0.3;
+/// Peak amplitude below which a recording is treated as silence
+pub const MIN_PEAK: f32 = 0.01;
+
+/// Level summary of a captured recording
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AudioStats {
+    pub duration_secs: f32,
+    pub min: f32,
+    pub max: f32,
+    pub rms: f32,
+}
+
+impl AudioStats {
+    /// Largest absolute amplitude (more reliable than RMS for short phrases)
+    pub fn peak(&self) -> f32 {
+        self.max.abs().max(self.min.abs())
+    }
+
+    /// Too short or too quiet to contain speech
+    pub fn is_silent(&self) -> bool {
+        self.duration_secs < MIN_RECORDING_SECS || self.peak() < MIN_PEAK
+    }
+}
+
+pub fn analyze_samples(samples: &[f32], sample_rate: u32) -> AudioStats {
+    if samples.is_empty() {
+        return AudioStats::default();
+    }
+    let sum_sq: f32 = samples.iter().map(|s| s * s).sum();
+    AudioStats {
+        duration_secs: samples.len() as f32 / sample_rate.max(1) as f32,
+        min: samples.iter().cloned().fold(f32::INFINITY, f32::min),
+        max: samples.iter().cloned().fold(f32::NEG_INFINITY, f32::max),
+        rms: (sum_sq / samples.len() as f32).sqrt(),
+    }
+}
+
+/// Longest piece a long memo is transcribed in
+pub const CHUNK_SECS: f32 = 30.0;
+// How far back from a piece's end a pause to cut at is looked for
+const PAUSE_SEARCH_SECS: f32 = 5.0;
+// Stretch of audio whose loudness is compared when looking for a pause
+const PAUSE_WINDOW_SECS: f32 = 0.1;
+
+/// Split `samples` into pieces of at most `max_secs`, each cut at the quietest
+/// moment of its last few seconds so a word isn't cut in half. A recording
+/// shorter than `max_secs` comes back whole.
+pub fn split_at_pauses(samples: &[f32], sample_rate: u32, max_secs: f32) -> Vec<&[f32]> {
+    let rate = sample_rate.max(1) as f32;
+    let max_len = ((max_secs * rate) as usize).max(1);
+    let search = (PAUSE_SEARCH_SECS * rate) as usize;
+    let window = ((PAUSE_WINDOW_SECS * rate) as usize).clamp(1, max_len);
+    let energy = |part: &[f32]| part.iter().map(|s| s * s).sum::<f32>();
+
+    let mut pieces = Vec::new();
+    let mut rest = samples;
+    while rest.len() > max_len {
+        let cut = (max_len.saturating_sub(search)..=max_len - window)
+            .step_by(window)
+            .min_by(|a, b| energy(&rest[*a..*a + window]).total_cmp(&energy(&rest[*b..*b + window])))
+            .map_or(max_len, |start| start + window / 2)
+            .max(1);
+        let (piece, tail) = rest.split_at(cut);
+        pieces.push(piece);
+        rest = tail;
+    }
+    if !rest.is_empty() {
+        pieces.push(rest);
+    }
+    pieces
+}
@@ -0,0 +1,160 @@
+// Benchmark module
+// Measures how each installed Whisper model performs on this machine, so a
+// model can be picked on numbers instead of guesses. The test clip is built in
+// (synthesized, so no audio file has to ship with the app); each model is
+// loaded fresh, timed on the clip, and the growth of the process's memory is
+// read around it.
+
+use crate::model_store::ModelStore;
+use crate::whisper::{self, WhisperModelSize};
+use serde::{Deserialize, Serialize};
+use std::f32::consts::{PI, TAU};
+use std::time::Instant;
+
+const SAMPLE_RATE: u32 = 16000;
+/// Length of the test clip
+pub const CLIP_SECS: f32 = 10.0;
+// First and second formants of a few vowels ("a", "i", "e", "o", "u")
+const VOWELS: [(f32, f32); 5] = [(730.0, 1090.0), (270.0, 2290.0), (530.0, 1840.0), (570.0, 840.0), (300.0, 870.0)];
+const SYLLABLE_SECS: f32 = 0.22;
+const PAUSE_SECS: f32 = 0.15;
+
+/// How one model did on the test clip
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BenchmarkResult {
+    pub model: String,
+    pub load_secs: f32,
+    pub transcribe_secs: f32,
+    /// Transcription time over clip length; below 1.0 is faster than real time
+    pub real_time_factor: f32,
+    /// Growth of resident memory with the model loaded, if the platform reports it
+    pub memory_mb: Option<f32>,
+}
+
+/// Benchmark one model; it has to be installed
+pub fn run(model: WhisperModelSize) -> Result<BenchmarkResult, String> {
+    let clip = test_clip();
+    let before = resident_bytes();
+
+    let started = Instant::now();
+    let ctx = whisper::load_uncached(model)?;
+    let load_secs = started.elapsed().as_secs_f32();
+
+    // English keeps detection out of the timing
+    let started = Instant::now();
+    whisper::transcribe_samples_with_context(&ctx, &clip, Some("en"))?;
+    let transcribe_secs = started.elapsed().as_secs_f32();
+
+    let memory_mb = before
+        .zip(resident_bytes())
+        .map(|(before, after)| after.saturating_sub(before) as f32 / 1_000_000.0);
+    tracing::info!(
+        model = model.name(),
+        load_secs,
+        transcribe_secs,
+        memory_mb,
+        "Benchmarked Whisper model"
+    );
+    Ok(BenchmarkResult {
+        model: model.name().to_string(),
+        load_secs,
+        transcribe_secs,
+        real_time_factor: transcribe_secs / CLIP_SECS,
+        memory_mb,
+    })
+}
+
+/// Benchmark every installed model, smallest first, one at a time so their
+/// memory doesn't add up
+pub fn run_installed() -> Result<Vec<BenchmarkResult>, String> {
+    let installed: Vec<_> = ModelStore::open()?
+        .list()
+        .into_iter()
+        .filter_map(|(model, installed)| installed.then_some(model))
+        .collect();
+    if installed.is_empty() {
+        return Err(crate::i18n::t("error.no_models_installed"));
+    }
+    installed.into_iter().map(run).collect()
+}
+
+/// The test clip: 16kHz speech-like sound, syllables of a voiced tone shaped
+/// by vowel formants, with a short pause after every third like between words
+pub fn test_clip() -> Vec<f32> {
+    let total = (CLIP_SECS * SAMPLE_RATE as f32) as usize;
+    let syllable = (SYLLABLE_SECS * SAMPLE_RATE as f32) as usize;
+    let pause = (PAUSE_SECS * SAMPLE_RATE as f32) as usize;
+
+    let mut clip = Vec::with_capacity(total + syllable + pause);
+    let mut index = 0;
+    while clip.len() < total {
+        let (first, second) = VOWELS[index % VOWELS.len()];
+        // Pitch drifts a little from syllable to syllable, like intonation
+        let pitch = 110.0 + 4.0 * (index % 7) as f32;
+        for n in 0..syllable {
+            let t = n as f32 / SAMPLE_RATE as f32;
+            let envelope = (PI * n as f32 / syllable as f32).sin();
+            let sample: f32 = (1..=30)
+                .map(|harmonic| {
+                    let freq = pitch * harmonic as f32;
+                    let gain = formant_gain(freq, first) + 0.5 * formant_gain(freq, second);
+                    gain * (TAU * freq * t).sin() / harmonic as f32
+                })
+                .sum();
+            clip.push(envelope * sample);
+        }
+        index += 1;
+        if index % 3 == 0 {
+            clip.resize(clip.len() + pause, 0.0);
+        }
+    }
+    clip.truncate(total);
+
+    // Normalize to a comfortable recording level
+    let peak = clip.iter().fold(0.0f32, |peak, sample| peak.max(sample.abs()));
+    if peak > 0.0 {
+        clip.iter_mut().for_each(|sample| *sample *= 0.5 / peak);
+    }
+    clip
+}
+
+// Resonance of a formant at `center`, 1.0 at its peak
+fn formant_gain(freq: f32, center: f32) -> f32 {
+    const BANDWIDTH: f32 = 90.0;
+    1.0 / (1.0 + ((freq - center) / BANDWIDTH).powi(2))
+}
+
+#[cfg(target_os = "windows")]
+fn resident_bytes() -> Option<u64> {
+    use windows::Win32::System::ProcessStatus::{GetProcessMemoryInfo, PROCESS_MEMORY_COUNTERS};
+    use windows::Win32::System::Threading::GetCurrentProcess;
+
+    let mut counters = PROCESS_MEMORY_COUNTERS::default();
+    let size = std::mem::size_of::<PROCESS_MEMORY_COUNTERS>() as u32;
+    unsafe { GetProcessMemoryInfo(GetCurrentProcess(), &mut counters, size) }.ok()?;
+    Some(counters.WorkingSetSize as u64)
+}
+
+#[cfg(target_os = "linux")]
+fn resident_bytes() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find(|line| line.starts_with("VmRSS:"))?;
+    let kilobytes: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kilobytes * 1024)
+}
+
+#[cfg(target_os = "macos")]
+fn resident_bytes() -> Option<u64> {
+    let output = std::process::Command::new("ps")
+        .args(["-o", "rss=", "-p", &std::process::id().to_string()])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())?;
+    let kilobytes: u64 = String::from_utf8_lossy(&output.stdout).trim().parse().ok()?;
+    Some(kilobytes * 1024)
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux", target_os = "macos")))]
+fn resident_bytes() -> Option<u64> {
+    None
+}
@@ -0,0 +1,358 @@
+// Headless command-line entry point: manage tasks and transcribe audio from
+// scripts and cron jobs without opening a window
+
+use flowstate_core::{audio, benchmark, config_file, content_filter, crash, database, databases, dates, estimates, history, inbox, logging, model_store, parser, paths, settings, speaker, whisper};
+use std::io::{BufRead, IsTerminal};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+const USAGE: &str = "\
+Usage: flowstate-cli [--data-dir <path>] <command> [args]
+
+Commands:
+  add <text>...              Add a task (use - to read one task per line from stdin;
+                             an @word sets its context, e.g. @phone, and a
+                             trailing ~30m its estimate)
+  list [--all|--completed|--inbox|--pinned] [@context]
+                             List open and recently completed tasks
+  done <id>...               Mark tasks as completed
+  delete <id>...             Delete tasks
+  keep <id>...               Move voice tasks from the inbox to the list
+  schedule <id> <when>       Move a voice task from the inbox to the list, due
+                             <when> (e.g. tomorrow, \"next friday\", 2026-05-12)
+  workload                   Sum today's estimates against the daily capacity
+  transcribe <file> [--model <name>] [--language <code>] [--apply]
+                             Print the transcript of an audio file; --apply also
+                             turns it into tasks like a voice log
+  reparse [--from <date>] [--to <date>] [--apply]
+                             Run kept voice notes (between local YYYY-MM-DD
+                             dates) through the current parser and show what it
+                             would do differently; --apply makes the changes
+  benchmark [<model>]        Time installed models (or just one) on a built-in
+                             test clip: real-time factor, load time and memory
+  database [<name>]          List the task databases (* marks the open one), or
+                             switch to one, creating it if it's new; later
+                             commands and the egui app use it from then on
+
+--data-dir (or FLOWSTATE_DATA_DIR) uses another data folder, e.g. a portable
+install's. Set RUST_LOG=info for progress output.";
+
+fn main() -> ExitCode {
+    let args = paths::init(std::env::args().skip(1));
+    // Only warnings and errors on stderr so stdout stays clean for pipes
+    let _log_guard = logging::init_with_default_level("warn");
+    crash::install_panic_hook();
+
+    let Some((command, rest)) = args.split_first() else {
+        eprintln!("{}", USAGE);
+        return ExitCode::FAILURE;
+    };
+
+    let result = match command.as_str() {
+        "add" => add(rest),
+        "list" => list(rest),
+        "done" => done(rest),
+        "delete" => delete(rest),
+        "keep" => keep(rest),
+        "schedule" => schedule(rest),
+        "workload" => workload(),
+        "transcribe" => transcribe(rest),
+        "reparse" => reparse(rest),
+        "benchmark" => run_benchmark(rest),
+        "database" => database_command(rest),
+        "help" | "--help" | "-h" => {
+            println!("{}", USAGE);
+            Ok(())
+        }
+        other => Err(format!("Unknown command: {}\n\n{}", other, USAGE)),
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn open_database() -> Result<database::Database, String> {
+    let db = database::Database::new().map_err(|e| format!("Failed to open database: {}", e))?;
+    // The same flowstate.toml defaults the apps start with
+    if let Err(e) = config_file::apply(&db) {
+        eprintln!("Warning: {}", e);
+    }
+    Ok(db)
+}
+
+fn print_task(task: &database::Task) {
+    let mark = if task.completed { "x" } else { " " };
+    let mut line = format!("{}\t[{}] {}", task.id, mark, task.text);
+    if let Some(context) = &task.context {
+        line.push_str(&format!(" @{}", context));
+    }
+    if let Some(minutes) = task.estimate_minutes {
+        line.push_str(&format!(" ~{}", estimates::format_estimate(minutes)));
+    }
+    if let Some(due) = task.due_at.as_deref().and_then(dates::label) {
+        line.push_str(&format!(" (due {})", due));
+    }
+    println!("{}", line);
+}
+
+fn parse_ids(args: &[String]) -> Result<Vec<i64>, String> {
+    if args.is_empty() {
+        return Err("Expected at least one task id".to_string());
+    }
+    args.iter()
+        .map(|arg| arg.parse().map_err(|_| format!("Invalid task id: {}", arg)))
+        .collect()
+}
+
+fn add(args: &[String]) -> Result<(), String> {
+    let texts: Vec<String> = if args.len() == 1 && args[0] == "-" {
+        let stdin = std::io::stdin();
+        if stdin.is_terminal() {
+            return Err("Expected tasks on stdin".to_string());
+        }
+        stdin
+            .lock()
+            .lines()
+            .map(|line| line.map_err(|e| format!("Failed to read stdin: {}", e)))
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .map(|line| line.trim().to_string())
+            .filter(|line| !line.is_empty())
+            .collect()
+    } else {
+        vec![args.join(" ").trim().to_string()]
+    };
+    if texts.iter().all(|text| text.is_empty()) {
+        return Err("Task text cannot be empty".to_string());
+    }
+
+    let db = open_database()?;
+    let tasks = database::add_tasks(&db, &texts)
+        .map_err(|e| format!("Failed to add tasks: {}", e))?;
+    tasks.iter().for_each(print_task);
+    Ok(())
+}
+
+fn list(args: &[String]) -> Result<(), String> {
+    let mut query = database::TaskQuery::default();
+    for arg in args {
+        match arg.as_str() {
+            "--all" => query.status = database::TaskStatus::All,
+            "--completed" => query.status = database::TaskStatus::Completed,
+            "--inbox" => query.status = database::TaskStatus::Inbox,
+            "--pinned" => query.status = database::TaskStatus::Pinned,
+            context if context.starts_with('@') => query.context = Some(context.to_string()),
+            other => return Err(format!("Unknown option: {}", other)),
+        }
+    }
+
+    let db = open_database()?;
+    let tasks = database::query_tasks(&db, &query)
+        .map_err(|e| format!("Failed to list tasks: {}", e))?;
+    tasks.iter().for_each(print_task);
+    Ok(())
+}
+
+fn done(args: &[String]) -> Result<(), String> {
+    let ids = parse_ids(args)?;
+    let db = open_database()?;
+    let tasks = database::complete_tasks(&db, &ids)
+        .map_err(|e| format!("Failed to complete tasks: {}", e))?;
+    tasks.iter().for_each(print_task);
+    Ok(())
+}
+
+fn keep(args: &[String]) -> Result<(), String> {
+    let ids = parse_ids(args)?;
+    let db = open_database()?;
+    let tasks = inbox::commit(&db, &ids)?;
+    tasks.iter().for_each(print_task);
+    Ok(())
+}
+
+fn schedule(args: &[String]) -> Result<(), String> {
+    let (id, when) = args.split_first().ok_or("Expected a task id and when it's due")?;
+    if when.is_empty() {
+        return Err("Expected when the task is due".to_string());
+    }
+    let id = id.parse().map_err(|_| format!("Invalid task id: {}", id))?;
+    let db = open_database()?;
+    let task = inbox::schedule(&db, id, &when.join(" "))?;
+    print_task(&task);
+    Ok(())
+}
+
+fn delete(args: &[String]) -> Result<(), String> {
+    let ids = parse_ids(args)?;
+    let db = open_database()?;
+    let tasks = database::delete_tasks(&db, &ids)
+        .map_err(|e| format!("Failed to delete tasks: {}", e))?;
+    for task in &tasks {
+        println!("Deleted {}\t{}", task.id, task.text);
+    }
+    Ok(())
+}
+
+fn workload() -> Result<(), String> {
+    let db = open_database()?;
+    let capacity = settings::load(&db).workload.capacity_minutes;
+    let workload = estimates::workload(&db, capacity)?;
+    println!("{}", estimates::summary(&workload));
+    Ok(())
+}
+
+fn database_command(args: &[String]) -> Result<(), String> {
+    let db = open_database()?;
+    let dir = paths::data_dir();
+    let list = match args {
+        [] => databases::list(&dir, &databases::current(&db)),
+        [name] => {
+            let name = databases::switch(&db, &dir, name)?;
+            databases::list(&dir, &name)
+        }
+        _ => return Err("Expected at most one database name".to_string()),
+    };
+    for name in &list.databases {
+        let mark = if *name == list.current { "*" } else { " " };
+        println!("{} {}", mark, name);
+    }
+    Ok(())
+}
+
+fn run_benchmark(args: &[String]) -> Result<(), String> {
+    let results = match args {
+        [] => benchmark::run_installed()?,
+        [name] => {
+            let model = whisper::WhisperModelSize::from_str(name)
+                .ok_or_else(|| format!("Invalid model name: {}", name))?;
+            vec![benchmark::run(model)?]
+        }
+        _ => return Err("Expected at most one model name".to_string()),
+    };
+    println!("model\treal-time\tload\tmemory");
+    for result in &results {
+        let memory = result.memory_mb.map_or("?".to_string(), |mb| format!("{:.0} MB", mb));
+        println!("{}\t{:.2}x\t{:.1}s\t{}", result.model, result.real_time_factor, result.load_secs, memory);
+    }
+    Ok(())
+}
+
+fn reparse(args: &[String]) -> Result<(), String> {
+    let mut range = history::HistoryRange::default();
+    let mut apply = false;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--from" => range.from = Some(args.next().ok_or("--from needs a date")?.clone()),
+            "--to" => range.to = Some(args.next().ok_or("--to needs a date")?.clone()),
+            "--apply" => apply = true,
+            other => return Err(format!("Unknown option: {}", other)),
+        }
+    }
+
+    let db = open_database()?;
+    let saved = settings::load(&db);
+    let runtime = tokio::runtime::Runtime::new()
+        .map_err(|e| format!("Failed to start runtime: {}", e))?;
+    let diffs = runtime.block_on(history::reparse(&db, &saved, &range))?;
+    if diffs.is_empty() {
+        println!("No voice notes would be parsed differently");
+        return Ok(());
+    }
+    for diff in &diffs {
+        println!("{}\t{}\t{}", diff.transcription_id, diff.created_at, diff.transcript);
+        for action in &diff.added {
+            println!("\t+ {}", action_line(action));
+        }
+        for action in &diff.removed {
+            println!("\t- {}", action_line(action));
+        }
+    }
+
+    if apply {
+        let ids: Vec<i64> = diffs.iter().map(|diff| diff.transcription_id).collect();
+        let applied = runtime.block_on(history::apply(&db, &saved, &ids))?;
+        for task in &applied.deleted {
+            println!("deleted\t{}", task.text);
+        }
+        applied.completed.iter().chain(&applied.added).for_each(print_task);
+    }
+    Ok(())
+}
+
+fn action_line(action: &history::HistoryAction) -> String {
+    match action.kind {
+        history::ActionKind::Add => format!("add {}", action.text),
+        history::ActionKind::Complete => format!("complete {}", action.text),
+    }
+}
+
+fn transcribe(args: &[String]) -> Result<(), String> {
+    let mut file = None;
+    let mut model_name = None;
+    let mut language = None;
+    let mut apply = false;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--model" => model_name = Some(args.next().ok_or("--model needs a value")?.clone()),
+            "--language" => language = Some(args.next().ok_or("--language needs a value")?.clone()),
+            "--apply" => apply = true,
+            other if file.is_none() => file = Some(PathBuf::from(other)),
+            other => return Err(format!("Unexpected argument: {}", other)),
+        }
+    }
+    let file = file.ok_or("Expected an audio file")?;
+
+    let db = open_database()?;
+    let saved = settings::load(&db);
+    // Default to the model picked in the app
+    let model_name = model_name.unwrap_or(saved.selected_model);
+    let model_size = whisper::WhisperModelSize::from_str(&model_name)
+        .ok_or_else(|| format!("Invalid model name: {}", model_name))?;
+    if !model_store::ModelStore::open_or_temp().is_installed(model_size) {
+        return Err(format!("Model '{}' is not installed - download it in the app first", model_name));
+    }
+
+    let (samples, sample_rate) = audio::decode_audio_file(&file)?;
+    // Whisper expects 16kHz
+    let samples = whisper::resample(&samples, sample_rate as usize, 16000);
+    let samples = speaker::gate(samples, &saved.speaker_gate);
+    let transcript = whisper::transcribe_audio(
+        &samples,
+        model_size.name(),
+        whisper::resolve_language(language.as_deref().or(Some(saved.recording_language()))),
+    )?;
+    let transcript = content_filter::filter(transcript.trim(), &saved.content_filter);
+    println!("{}", transcript);
+
+    if apply {
+        let runtime = tokio::runtime::Runtime::new()
+            .map_err(|e| format!("Failed to start runtime: {}", e))?;
+        // Timer commands are left out: the timer lives in the running app
+        let parsed = runtime
+            .block_on(parser::ParserChain::from_settings(&saved).parse(&transcript))
+            .map_err(|e| format!("Failed to parse transcript: {}", e))?;
+
+        let mut new_tasks = Vec::new();
+        for task in parsed.tasks {
+            if task.completed {
+                if let Ok(existing) = database::find_and_complete_task(&db, &task.text) {
+                    print_task(&existing);
+                }
+            } else {
+                new_tasks.push((task.text, task.due_at));
+            }
+        }
+        let added = database::add_tasks_with_due(&db, &new_tasks, saved.voice_inbox)
+            .map_err(|e| format!("Failed to add tasks: {}", e))?;
+        added.iter().for_each(print_task);
+    }
+    Ok(())
+}
@@ -0,0 +1,88 @@
+// Clipboard module
+// Opt-in quick capture: copying text that starts with the trigger (e.g.
+// "todo: call the bank") adds it as a task. arboard has no change
+// notifications, so the clipboard is polled.
+
+use crate::database::{self, Database, Task};
+use crate::settings::ClipboardSettings;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+pub type CaptureHandler = Box<dyn Fn(Task) + Send>;
+
+/// Running watcher thread; stops when dropped
+pub struct ClipboardWatcher {
+    stop: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+/// Managed state holding the active watcher (if it is enabled)
+#[derive(Default)]
+pub struct ClipboardWatcherState {
+    pub watcher: Mutex<Option<ClipboardWatcher>>,
+}
+
+impl ClipboardWatcher {
+    pub fn start(db: &Database, config: &ClipboardSettings, on_capture: CaptureHandler) -> Result<Self, String> {
+        config.validate()?;
+        let db = db.reopen().map_err(|e| e.to_string())?;
+        let mut clipboard = arboard::Clipboard::new().map_err(|e| e.to_string())?;
+        let trigger = config.trigger.trim().to_string();
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let stop_flag = stop.clone();
+        let handle = std::thread::spawn(move || {
+            tracing::info!("Clipboard watcher started (trigger \"{}\")", trigger);
+            // Whatever was copied before the watcher started isn't captured
+            let mut last = clipboard.get_text().ok();
+            while !stop_flag.load(Ordering::Relaxed) {
+                std::thread::sleep(POLL_INTERVAL);
+                // Fails for images and other non-text content
+                let Ok(text) = clipboard.get_text() else {
+                    continue;
+                };
+                if last.as_deref() == Some(text.as_str()) {
+                    continue;
+                }
+                if let Some(task_text) = task_text(&text, &trigger) {
+                    match database::add_task(&db, &task_text) {
+                        Ok(task) => on_capture(task),
+                        Err(e) => tracing::warn!("Failed to add clipboard task: {}", e),
+                    }
+                }
+                last = Some(text);
+            }
+            tracing::info!("Clipboard watcher stopped");
+        });
+
+        Ok(Self {
+            stop,
+            handle: Some(handle),
+        })
+    }
+}
+
+impl Drop for ClipboardWatcher {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+/// Task text if the copied text starts with the trigger (ignoring case).
+/// Line breaks from copying a wrapped paragraph are folded into spaces.
+pub fn task_text(copied: &str, trigger: &str) -> Option<String> {
+    let copied = copied.trim_start();
+    let prefix = copied.get(..trigger.len())?;
+    if !prefix.eq_ignore_ascii_case(trigger) {
+        return None;
+    }
+    let text = copied[trigger.len()..].split_whitespace().collect::<Vec<_>>().join(" ");
+    (!text.is_empty()).then_some(text)
+}
@@ -1,7 +1,12 @@
 use serde::{Deserialize, Serialize};
-use tauri::{State, AppHandle, Manager, Window, Emitter};
-use crate::database::Database;
-use crate::whisper::{WhisperModelSize, WhisperCache, download_model, check_model_exists, delete_model, transcribe_with_context};
+use tauri::{State, AppHandle, Manager, Runtime, Window, Emitter};
+use flowstate_core::database::Database;
+use flowstate_core::error::FlowStateError;
+use flowstate_core::model_store::ModelStore;
+use crate::processing::ProcessingState;
+use flowstate_core::settings::WindowGeometry;
+use flowstate_core::stt::SpeechToText;
+use flowstate_core::whisper::{WhisperModelSize, WhisperCache, resolve_language};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct TaskResponse {
@@ -10,207 +15,1031 @@ pub struct TaskResponse {
     pub completed: bool,
     pub created_at: String,
     pub completed_at: Option<String>,
+    /// GitHub issue filed for the task (only filled by get_tasks and GitHub capture)
+    pub issue_url: Option<String>,
+    /// GTD context without the "@"
+    pub context: Option<String>,
+    pub estimate_minutes: Option<i64>,
+    /// When it's due, in UTC
+    pub due_at: Option<String>,
+    /// Captured by voice and waiting in the inbox
+    pub inbox: bool,
+    /// Parser that turned the transcript into this action (only filled by voice parsing)
+    pub parsed_by: Option<flowstate_core::parser::ParserKind>,
 }
 
-#[tauri::command]
-pub fn get_tasks(db: State<Database>) -> Result<Vec<TaskResponse>, String> {
-    crate::database::get_all_tasks(&db)
-        .map_err(|e: rusqlite::Error| e.to_string())
-        .map(|tasks: Vec<crate::database::Task>| {
-            tasks.into_iter().map(|t| TaskResponse {
-                id: t.id,
-                text: t.text,
-                completed: t.completed,
-                created_at: t.created_at,
-                completed_at: t.completed_at,
-            }).collect()
-        })
-}
-
-#[tauri::command]
-pub fn add_task(text: String, db: State<Database>) -> Result<TaskResponse, String> {
-    crate::database::add_task(&db, &text)
-        .map_err(|e: rusqlite::Error| e.to_string())
-        .map(|task: crate::database::Task| TaskResponse {
+impl From<flowstate_core::database::Task> for TaskResponse {
+    fn from(task: flowstate_core::database::Task) -> Self {
+        TaskResponse {
             id: task.id,
             text: task.text,
             completed: task.completed,
             created_at: task.created_at,
             completed_at: task.completed_at,
-        })
+            issue_url: None,
+            context: task.context,
+            estimate_minutes: task.estimate_minutes,
+            due_at: task.due_at,
+            inbox: task.inbox,
+            parsed_by: None,
+        }
+    }
+}
+
+/// Result of a recording or import: what was heard and what it did to the task list
+#[derive(Debug, Serialize)]
+pub struct VoiceProcessingResult {
+    pub transcript: String,
+    pub tasks: Vec<TaskResponse>,
+    /// What the parser took from it, for the transcription history; None
+    /// when only commands were heard
+    #[serde(skip)]
+    pub actions: Option<Vec<flowstate_core::history::HistoryAction>>,
+    /// Set when a completion fit several tasks, none of which was completed;
+    /// answered with `resolve_disambiguation`
+    pub needs_disambiguation: Option<flowstate_core::disambiguation::NeedsDisambiguation>,
+}
+
+/// What one transcript (or piece of one) did
+struct AppliedTranscript {
+    tasks: Vec<TaskResponse>,
+    /// The parser's actions; None when a voice command handled it
+    actions: Option<Vec<flowstate_core::history::HistoryAction>>,
+}
+
+/// Steps of voice processing, reported through `voice-processing` events
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProcessingStage {
+    /// Reading the recording or an imported audio file
+    Decoding,
+    Transcribing,
+    Parsing,
+    Applying,
+}
+
+impl ProcessingStage {
+    fn step(&self) -> u32 {
+        match self {
+            ProcessingStage::Decoding => 1,
+            ProcessingStage::Transcribing => 2,
+            ProcessingStage::Parsing => 3,
+            ProcessingStage::Applying => 4,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProcessingProgress {
+    pub stage: ProcessingStage,
+    pub step: u32,
+    pub total_steps: u32,
+    /// Set from the parsing stage on, so the UI can show it before tasks are applied
+    pub transcript: Option<String>,
+    /// The piece being worked on, for a memo long enough to go through in pieces
+    pub chunk: Option<ChunkProgress>,
+}
+
+/// Which piece of a long memo a stage is about (`index` counts from 1)
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct ChunkProgress {
+    pub index: usize,
+    pub total: usize,
+}
+
+// Transcribed pieces waiting to be parsed; transcription waits while parsing catches up
+const CHUNK_QUEUE: usize = 2;
+
+fn emit_stage<R: Runtime>(app: &AppHandle<R>, stage: ProcessingStage, transcript: Option<&str>) {
+    emit_chunk_stage(app, stage, transcript, None);
+}
+
+fn emit_chunk_stage<R: Runtime>(app: &AppHandle<R>, stage: ProcessingStage, transcript: Option<&str>, chunk: Option<ChunkProgress>) {
+    let _ = app.emit("voice-processing", ProcessingProgress {
+        stage,
+        step: stage.step(),
+        total_steps: 4,
+        transcript: transcript.map(str::to_string),
+        chunk,
+    });
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskDeletedPayload {
+    pub id: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProfileChangedPayload {
+    pub name: Option<String>,
+    /// Recording language the profile sets, if any
+    pub language: Option<String>,
+}
+
+// Task mutation events let the frontend patch its list instead of re-fetching:
+// task-added, task-completed and task-updated carry the task, task-deleted its id
+fn emit_task_changed<R: Runtime>(app: &AppHandle<R>, event: &str, task: &TaskResponse) {
+    let _ = app.emit(event, task);
+    if event == "task-completed" {
+        if let Some(db) = app.try_state::<Database>() {
+            check_achievements(app, &db);
+        }
+    }
+}
+
+// Tells the windows about achievements the last change unlocked
+fn check_achievements<R: Runtime>(app: &AppHandle<R>, db: &Database) {
+    for achievement in flowstate_core::achievements::check(db) {
+        let _ = app.emit("achievement-unlocked", &achievement);
+    }
+}
+
+fn emit_task_deleted<R: Runtime>(app: &AppHandle<R>, id: i64) {
+    let _ = app.emit("task-deleted", TaskDeletedPayload { id });
+}
+
+fn emit_inbox_changed<R: Runtime>(app: &AppHandle<R>, db: &Database) {
+    let _ = app.emit("inbox-changed", flowstate_core::inbox::count(db));
+}
+
+fn emit_habits_changed<R: Runtime>(app: &AppHandle<R>) {
+    let _ = app.emit("habits-changed", ());
 }
 
+// Wakes the scheduler and tells the windows to reload reminders
+fn emit_reminders_changed<R: Runtime>(app: &AppHandle<R>) {
+    crate::scheduler::notify_reminders_changed();
+    let _ = app.emit("reminders-changed", ());
+}
+
+/// List tasks; without a query this is the main list (open + recently completed)
 #[tauri::command]
-pub fn update_task(id: i64, text: String, db: State<Database>) -> Result<(), String> {
-    crate::database::update_task(&db, id, &text)
-        .map_err(|e: rusqlite::Error| e.to_string())
+pub fn get_tasks(query: Option<flowstate_core::database::TaskQuery>, db: State<Database>) -> Result<Vec<TaskResponse>, FlowStateError> {
+    let mut query = query.unwrap_or_default();
+    // The active profile narrows the list unless the query brings its own scope
+    if query.scope.is_none() {
+        query.scope = flowstate_core::settings::load(&db).task_scope();
+    }
+    let tasks = flowstate_core::database::query_tasks(&db, &query)?;
+    let mut issue_urls = flowstate_core::database::task_issue_urls(&db).unwrap_or_default();
+    Ok(tasks.into_iter().map(|task| TaskResponse {
+        issue_url: issue_urls.remove(&task.id),
+        ..TaskResponse::from(task)
+    }).collect())
 }
 
 #[tauri::command]
-pub fn delete_task(id: i64, db: State<Database>) -> Result<(), String> {
-    crate::database::delete_task(&db, id)
-        .map_err(|e: rusqlite::Error| e.to_string())
+pub fn add_task<R: Runtime>(app: AppHandle<R>, text: String, db: State<Database>) -> Result<TaskResponse, FlowStateError> {
+    let task = TaskResponse::from(
+        flowstate_core::database::add_task(&db, &text)?,
+    );
+    emit_task_changed(&app, "task-added", &task);
+    Ok(task)
 }
 
 #[tauri::command]
-pub fn toggle_task(id: i64, db: State<Database>) -> Result<TaskResponse, String> {
-    crate::database::toggle_task(&db, id)
-        .map_err(|e: rusqlite::Error| e.to_string())
-        .map(|task: crate::database::Task| TaskResponse {
-            id: task.id,
-            text: task.text,
-            completed: task.completed,
-            created_at: task.created_at,
-            completed_at: task.completed_at,
-        })
+pub fn update_task<R: Runtime>(app: AppHandle<R>, id: i64, text: String, db: State<Database>) -> Result<(), FlowStateError> {
+    flowstate_core::database::update_task(&db, id, &text)?;
+    if let Ok(task) = flowstate_core::database::get_task_by_id(&db, id) {
+        emit_task_changed(&app, "task-updated", &TaskResponse::from(task));
+    }
+    Ok(())
 }
 
+/// Set or clear a task's due date. `due_at` is UTC "YYYY-MM-DD HH:MM:SS"; a
+/// day without a time is sent as 23:59 local time on that day
 #[tauri::command]
-pub async fn process_voice_log(transcript: String, db: State<'_, Database>) -> Result<Vec<TaskResponse>, String> {
-    // Use local LLM to parse transcript
-    let ollama_enabled = crate::database::get_ollama_enabled(&db).unwrap_or(false);
-    let parsed_tasks: Vec<crate::database::Task> = crate::ollama::parse_transcript(&transcript, ollama_enabled).await
-        .map_err(|e| format!("Failed to parse transcript: {}", e))?;
-    
-    // Update database with parsed tasks
-    let mut results = Vec::new();
-    for task in parsed_tasks {
-        if task.completed {
-            // Mark existing task as completed or create new one
-            if let Ok(existing) = crate::database::find_and_complete_task(&db, &task.text) {
-                results.push(TaskResponse {
-                    id: existing.id,
-                    text: existing.text,
-                    completed: existing.completed,
-                    created_at: existing.created_at,
-                    completed_at: existing.completed_at,
-                });
+pub fn set_task_due(app: AppHandle, id: i64, due_at: Option<String>, db: State<Database>) -> Result<(), FlowStateError> {
+    let due_at = due_at.map(|due| due.trim().to_string()).filter(|due| !due.is_empty());
+    if let Some(due) = &due_at {
+        chrono::NaiveDateTime::parse_from_str(due, "%Y-%m-%d %H:%M:%S")
+            .map_err(|_| flowstate_core::i18n::tf("error.due_format", &[("due", due)]))?;
+    }
+    flowstate_core::database::set_task_due(&db, id, due_at.as_deref())?;
+    if let Ok(task) = flowstate_core::database::get_task_by_id(&db, id) {
+        emit_task_changed(&app, "task-updated", &TaskResponse::from(task));
+    }
+    Ok(())
+}
+
+/// Store the manual order of open tasks (ids top to bottom)
+#[tauri::command]
+pub fn reorder_tasks<R: Runtime>(app: AppHandle<R>, ids: Vec<i64>, db: State<Database>) -> Result<(), FlowStateError> {
+    flowstate_core::database::reorder_tasks(&db, &ids)?;
+    let _ = app.emit("tasks-reordered", &ids);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn delete_task<R: Runtime>(app: AppHandle<R>, id: i64, db: State<Database>) -> Result<(), FlowStateError> {
+    flowstate_core::database::delete_task(&db, id)?;
+    emit_task_deleted(&app, id);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn toggle_task<R: Runtime>(app: AppHandle<R>, id: i64, db: State<Database>) -> Result<TaskResponse, FlowStateError> {
+    let task = TaskResponse::from(
+        flowstate_core::database::toggle_task(&db, id)?,
+    );
+    // Re-opening a task is an ordinary update
+    let event = if task.completed { "task-completed" } else { "task-updated" };
+    emit_task_changed(&app, event, &task);
+    Ok(task)
+}
+
+/// Add a task typed into the quick-add box, then close the box
+#[tauri::command]
+pub fn quick_add_task(app: AppHandle, text: String, db: State<Database>) -> Result<TaskResponse, FlowStateError> {
+    let text = text.trim().to_string();
+    if text.is_empty() {
+        return Err(flowstate_core::i18n::t("error.task_empty").into());
+    }
+    let task = add_task(app.clone(), text, db)?;
+    close_quick_add(app);
+    Ok(task)
+}
+
+#[tauri::command]
+pub fn close_quick_add(app: AppHandle) {
+    if let Some(window) = app.get_webview_window("quick-add") {
+        let _ = window.close();
+    }
+}
+
+/// Open the quick-add box, or close it if it is already open
+pub fn toggle_quick_add(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("quick-add") {
+        let _ = window.close();
+        return;
+    }
+
+    let built = tauri::WebviewWindowBuilder::new(app, "quick-add", tauri::WebviewUrl::App("index.html?view=quick-add".into()))
+        .title("Quick add")
+        .inner_size(380.0, 56.0)
+        .resizable(false)
+        .center()
+        .always_on_top(true)
+        .decorations(false)
+        .skip_taskbar(true)
+        .focused(true)
+        .build();
+    if let Err(e) = built {
+        tracing::error!("Failed to open quick add: {}", e);
+    }
+}
+
+/// Swap a global shortcut (`previous` is unregistered first)
+pub fn register_global_shortcut(app: &AppHandle, previous: Option<&str>, shortcut: &str) -> Result<(), String> {
+    use tauri_plugin_global_shortcut::GlobalShortcutExt;
+
+    let shortcuts = app.global_shortcut();
+    if let Some(previous) = previous {
+        let _ = shortcuts.unregister(previous);
+    }
+    shortcuts.register(shortcut)
+        .map_err(|e| flowstate_core::i18n::tf("error.register_shortcut", &[("shortcut", &shortcut), ("error", &e)]))
+}
+
+/// Add a whole dictated list in one transaction and one round trip
+#[tauri::command]
+pub fn add_tasks<R: Runtime>(app: AppHandle<R>, texts: Vec<String>, db: State<Database>) -> Result<Vec<TaskResponse>, FlowStateError> {
+    let tasks = flowstate_core::database::add_tasks(&db, &texts)?;
+    Ok(tasks.into_iter().map(|task| {
+        let task = TaskResponse::from(task);
+        emit_task_changed(&app, "task-added", &task);
+        task
+    }).collect())
+}
+
+#[tauri::command]
+pub fn delete_tasks<R: Runtime>(app: AppHandle<R>, ids: Vec<i64>, db: State<Database>) -> Result<Vec<TaskResponse>, FlowStateError> {
+    let tasks = flowstate_core::database::delete_tasks(&db, &ids)?;
+    Ok(tasks.into_iter().map(|task| {
+        emit_task_deleted(&app, task.id);
+        TaskResponse::from(task)
+    }).collect())
+}
+
+#[tauri::command]
+pub fn complete_tasks<R: Runtime>(app: AppHandle<R>, ids: Vec<i64>, db: State<Database>) -> Result<Vec<TaskResponse>, FlowStateError> {
+    let tasks = flowstate_core::database::complete_tasks(&db, &ids)?;
+    Ok(tasks.into_iter().map(|task| {
+        let task = TaskResponse::from(task);
+        emit_task_changed(&app, "task-completed", &task);
+        task
+    }).collect())
+}
+
+/// Move tasks to a project (`+project` in their text); null or blank removes it
+#[tauri::command]
+pub fn set_tasks_project<R: Runtime>(app: AppHandle<R>, ids: Vec<i64>, project: Option<String>, db: State<Database>) -> Result<Vec<TaskResponse>, FlowStateError> {
+    let tasks = flowstate_core::database::set_tasks_project(&db, &ids, project.as_deref())?;
+    Ok(tasks.into_iter().map(|task| {
+        let task = TaskResponse::from(task);
+        emit_task_changed(&app, "task-updated", &task);
+        task
+    }).collect())
+}
+
+#[tauri::command]
+pub fn tag_tasks<R: Runtime>(app: AppHandle<R>, ids: Vec<i64>, tag: String, db: State<Database>) -> Result<Vec<TaskResponse>, FlowStateError> {
+    let tasks = flowstate_core::database::tag_tasks(&db, &ids, &tag)?;
+    Ok(tasks.into_iter().map(|task| {
+        let task = TaskResponse::from(task);
+        emit_task_changed(&app, "task-updated", &task);
+        task
+    }).collect())
+}
+
+/// Apply a typed memo the way a transcribed one is: filtered, queued behind
+/// any voice job, then run through commands, removals and task parsing
+#[tauri::command]
+pub async fn process_voice_log(
+    app: AppHandle,
+    transcript: String,
+    db: State<'_, Database>,
+    processing: State<'_, ProcessingState>,
+) -> Result<Vec<TaskResponse>, FlowStateError> {
+    let _processing = crate::shutdown::begin_processing()?;
+    let _turn = processing.begin("text", None).await?;
+
+    let settings = flowstate_core::settings::load(&db);
+    let text = flowstate_core::content_filter::filter(transcript.trim(), &settings.content_filter);
+    let text = text.trim();
+    if text.is_empty() {
+        return Ok(Vec::new());
+    }
+    let applied = apply_transcript(&app, &db, text, text, None).await?;
+    Ok(applied.tasks)
+}
+
+/// Answer a question about the task history with Ollama, naming the tasks used
+#[tauri::command]
+pub async fn ask_tasks(question: String, db: State<'_, Database>) -> Result<flowstate_core::ask::TaskAnswer, FlowStateError> {
+    let settings = flowstate_core::settings::load(&db);
+    Ok(flowstate_core::ask::ask_tasks(&db, &question, settings.ollama_enabled.then_some(&settings.ollama)).await?)
+}
+
+/// Write the weekly review for this week (or `weeks_ago` weeks back) to the report folder
+#[tauri::command]
+pub fn generate_weekly_report(weeks_ago: Option<u32>, db: State<Database>) -> Result<flowstate_core::report::WeeklyReport, FlowStateError> {
+    let settings = flowstate_core::settings::load(&db);
+    Ok(flowstate_core::report::generate_weekly_report(&db, &settings.report, weeks_ago.unwrap_or(0))?)
+}
+
+/// Transcription and parser timings logged over the last `days` days (all of them without)
+#[tauri::command]
+pub fn get_usage_metrics(days: Option<u32>, db: State<Database>) -> Result<flowstate_core::metrics::UsageMetrics, FlowStateError> {
+    Ok(flowstate_core::metrics::usage_metrics(&db, days)?)
+}
+
+/// Run the transcripts kept from `range` through the current parser chain
+/// without changing anything, listing the notes it reads differently
+#[tauri::command]
+pub async fn reparse_history(
+    range: Option<flowstate_core::history::HistoryRange>,
+    db: State<'_, Database>,
+) -> Result<Vec<flowstate_core::history::ReparseDiff>, FlowStateError> {
+    let settings = flowstate_core::settings::load(&db);
+    Ok(flowstate_core::history::reparse(&db, &settings, &range.unwrap_or_default()).await?)
+}
+
+/// Apply the current parser's reading of these notes (ids from `reparse_history`)
+#[tauri::command]
+pub async fn apply_reparse<R: Runtime>(
+    app: AppHandle<R>,
+    ids: Vec<i64>,
+    db: State<'_, Database>,
+) -> Result<Vec<TaskResponse>, FlowStateError> {
+    let settings = flowstate_core::settings::load(&db);
+    let applied = flowstate_core::history::apply(&db, &settings, &ids).await?;
+    for task in &applied.deleted {
+        emit_task_deleted(&app, task.id);
+    }
+    let mut tasks = Vec::new();
+    for task in applied.completed {
+        let task = TaskResponse::from(task);
+        emit_task_changed(&app, "task-completed", &task);
+        tasks.push(task);
+    }
+    let any_added = !applied.added.is_empty();
+    for task in applied.added {
+        let task = TaskResponse::from(task);
+        emit_task_changed(&app, "task-added", &task);
+        tasks.push(task);
+    }
+    if settings.voice_inbox && any_added {
+        emit_inbox_changed(&app, &db);
+    }
+    Ok(tasks)
+}
+
+/// Today's estimated work against the capacity in the settings
+#[tauri::command]
+pub fn get_workload(db: State<Database>) -> Result<flowstate_core::estimates::Workload, FlowStateError> {
+    let settings = flowstate_core::settings::load(&db);
+    Ok(flowstate_core::estimates::workload(&db, settings.workload.capacity_minutes)?)
+}
+
+/// Make `name` the active profile (None goes back to no profile)
+#[tauri::command]
+pub fn switch_profile(app: AppHandle, name: Option<String>, db: State<Database>) -> Result<flowstate_core::settings::Settings, FlowStateError> {
+    Ok(apply_profile(&app, &db, name.as_deref())?)
+}
+
+/// The task databases in the data folder and the one that's open
+#[tauri::command]
+pub fn list_databases(app: AppHandle, db: State<Database>) -> Result<flowstate_core::databases::DatabaseList, FlowStateError> {
+    let dir = flowstate_core::paths::app_data_dir(&app).map_err(|e| e.to_string())?;
+    Ok(flowstate_core::databases::list(&dir, &flowstate_core::databases::current(&db)))
+}
+
+/// Open the task database called `name` (created if it's new) in place of the
+/// current one; the next launch opens it too
+#[tauri::command]
+pub fn switch_database(app: AppHandle, name: String, db: State<Database>) -> Result<flowstate_core::databases::DatabaseList, FlowStateError> {
+    let dir = flowstate_core::paths::app_data_dir(&app).map_err(|e| e.to_string())?;
+    let previous = flowstate_core::settings::load(&db);
+    let name = flowstate_core::databases::switch(&db, &dir, &name)?;
+
+    let settings = flowstate_core::settings::load(&db);
+    apply_database_settings(&app, &previous, &settings);
+    let _ = app.emit("settings-changed", &settings);
+    let _ = app.emit("database-switched", &name);
+    Ok(flowstate_core::databases::list(&dir, &name))
+}
+
+// Each database has its own settings: bring what they drive in line after a
+// switch, and restart the services that keep a connection of their own
+fn apply_database_settings(app: &AppHandle, previous: &flowstate_core::settings::Settings, settings: &flowstate_core::settings::Settings) {
+    flowstate_core::earcon::set_muted(settings.earcons_muted);
+    flowstate_core::i18n::set_locale(settings.ui_language);
+
+    if settings.timer_duration_minutes != previous.timer_duration_minutes {
+        match crate::timer::apply_timer_duration(settings.timer_duration_minutes) {
+            Ok(()) => {
+                if let Some(window) = app.get_webview_window("main") {
+                    let _ = window.emit("timer-reset", ());
+                }
             }
-        } else {
-            // Add new task
-            if let Ok(new_task) = crate::database::add_task(&db, &task.text) {
-                results.push(TaskResponse {
-                    id: new_task.id,
-                    text: new_task.text,
-                    completed: new_task.completed,
-                    created_at: new_task.created_at,
-                    completed_at: new_task.completed_at,
-                });
+            Err(e) => tracing::warn!("Failed to apply the timer duration: {}", e),
+        }
+    }
+    // Working hours and profiles may differ too
+    crate::timer::notify_timer_changed();
+
+    if (settings.wake_word_enabled, &settings.wake_word_phrase) != (previous.wake_word_enabled, &previous.wake_word_phrase) {
+        if let Err(e) = sync_wake_word_listener(app, settings) {
+            tracing::warn!("Failed to start the wake-word listener: {}", e);
+        }
+    }
+
+    let restarts = [
+        ("Markdown sync", restart_markdown_sync(app, settings)),
+        ("calendar export", restart_calendar_export(app, settings)),
+        ("Google Calendar push", restart_google_calendar_sync(app, settings)),
+        ("Todoist sync", restart_todoist_sync(app, settings)),
+        ("clipboard watcher", restart_clipboard_watcher(app, settings)),
+        ("MQTT publisher", if settings.mqtt != previous.mqtt { restart_mqtt_publisher(app, settings) } else { Ok(()) }),
+        ("Telegram bridge", if settings.telegram != previous.telegram { restart_telegram_bridge(app, settings) } else { Ok(()) }),
+    ];
+    for (service, result) in restarts {
+        if let Err(e) = result {
+            tracing::warn!("Failed to start the {}: {}", service, e);
+        }
+    }
+
+    for (old, new) in [
+        (&previous.quick_add_shortcut, &settings.quick_add_shortcut),
+        (&previous.compact_mode_shortcut, &settings.compact_mode_shortcut),
+    ] {
+        if old != new {
+            if let Err(e) = register_global_shortcut(app, Some(old), new) {
+                tracing::warn!("Failed to register {}: {}", new, e);
             }
         }
     }
-    
-    Ok(results)
 }
 
+/// Habits with their streaks and the last seven days
+#[tauri::command]
+pub fn get_habits(db: State<Database>) -> Result<Vec<flowstate_core::habits::HabitStatus>, FlowStateError> {
+    Ok(flowstate_core::habits::get_habits(&db)?)
+}
+
+/// Add a habit; `schedule` is "daily" or weekdays like "mon,wed,fri"
 #[tauri::command]
-pub fn get_timer_status() -> Result<u64, String> {
-    crate::timer::get_remaining_time()
-        .map_err(|e: String| e)
+pub fn add_habit(app: AppHandle, name: String, schedule: String, db: State<Database>) -> Result<flowstate_core::habits::HabitStatus, FlowStateError> {
+    let habit = flowstate_core::habits::add_habit(&db, &name, &schedule)?;
+    emit_habits_changed(&app);
+    Ok(habit)
 }
 
 #[tauri::command]
-pub fn reset_timer() -> Result<(), String> {
-    crate::timer::reset_timer()
-        .map_err(|e: String| e)
+pub fn delete_habit(app: AppHandle, id: i64, db: State<Database>) -> Result<(), FlowStateError> {
+    flowstate_core::habits::delete_habit(&db, id)?;
+    emit_habits_changed(&app);
+    Ok(())
 }
 
+/// Tick today off for a habit, or un-tick it
 #[tauri::command]
-pub fn get_timer_duration(_app: AppHandle) -> Result<u64, String> {
-    crate::timer::get_timer_duration_minutes()
-        .map_err(|e: String| e)
+pub fn toggle_habit(app: AppHandle, id: i64, db: State<Database>) -> Result<flowstate_core::habits::HabitStatus, FlowStateError> {
+    let habit = flowstate_core::habits::toggle_today(&db, id)?;
+    emit_habits_changed(&app);
+    Ok(habit)
 }
 
+/// Voice macros in the order they were added
 #[tauri::command]
-pub fn set_timer_duration(app: AppHandle, minutes: u64) -> Result<(), String> {
-    if minutes > 60 {
-        return Err("Timer duration cannot exceed 60 minutes".to_string());
+pub fn get_macros(db: State<Database>) -> Result<Vec<flowstate_core::macros::VoiceMacro>, FlowStateError> {
+    Ok(flowstate_core::macros::get_macros(&db)?)
+}
+
+/// Add a macro: saying `phrase` runs `actions` instead of adding a task
+#[tauri::command]
+pub fn add_macro(phrase: String, actions: Vec<flowstate_core::macros::MacroAction>, db: State<Database>) -> Result<flowstate_core::macros::VoiceMacro, FlowStateError> {
+    Ok(flowstate_core::macros::add_macro(&db, &phrase, actions)?)
+}
+
+#[tauri::command]
+pub fn update_macro(
+    id: i64,
+    phrase: String,
+    actions: Vec<flowstate_core::macros::MacroAction>,
+    db: State<Database>,
+) -> Result<flowstate_core::macros::VoiceMacro, FlowStateError> {
+    Ok(flowstate_core::macros::update_macro(&db, id, &phrase, actions)?)
+}
+
+#[tauri::command]
+pub fn delete_macro(id: i64, db: State<Database>) -> Result<(), FlowStateError> {
+    Ok(flowstate_core::macros::delete_macro(&db, id)?)
+}
+
+/// API tokens issued for network clients, without their secrets
+#[tauri::command]
+pub fn get_api_tokens(db: State<Database>) -> Result<Vec<flowstate_core::api_tokens::ApiToken>, FlowStateError> {
+    Ok(flowstate_core::api_tokens::list(&db)?)
+}
+
+/// Issue a token for the client called `name`; the secret in the result is
+/// shown once and can't be read back
+#[tauri::command]
+pub fn issue_api_token(
+    name: String,
+    scopes: Vec<flowstate_core::api_tokens::Scope>,
+    db: State<Database>,
+) -> Result<flowstate_core::api_tokens::IssuedToken, FlowStateError> {
+    Ok(flowstate_core::api_tokens::issue(&db, &name, &scopes)?)
+}
+
+#[tauri::command]
+pub fn revoke_api_token(id: i64, db: State<Database>) -> Result<(), FlowStateError> {
+    Ok(flowstate_core::api_tokens::revoke(&db, id)?)
+}
+
+/// Open tasks waiting in the inbox
+#[tauri::command]
+pub fn get_inbox_count(db: State<Database>) -> usize {
+    flowstate_core::inbox::count(&db)
+}
+
+/// Move inbox tasks to the main list as they are
+#[tauri::command]
+pub fn commit_inbox_tasks<R: Runtime>(app: AppHandle<R>, ids: Vec<i64>, db: State<Database>) -> Result<Vec<TaskResponse>, FlowStateError> {
+    let tasks = flowstate_core::inbox::commit(&db, &ids)?;
+    let tasks = tasks.into_iter().map(|task| {
+        let task = TaskResponse::from(task);
+        emit_task_changed(&app, "task-updated", &task);
+        task
+    }).collect();
+    emit_inbox_changed(&app, &db);
+    Ok(tasks)
+}
+
+/// Move an inbox task to the main list, due `when` ("tomorrow", "friday at 3pm", "2026-05-12")
+#[tauri::command]
+pub fn schedule_inbox_task<R: Runtime>(app: AppHandle<R>, id: i64, when: String, db: State<Database>) -> Result<TaskResponse, FlowStateError> {
+    let task = TaskResponse::from(flowstate_core::inbox::schedule(&db, id, &when)?);
+    emit_task_changed(&app, "task-updated", &task);
+    emit_inbox_changed(&app, &db);
+    Ok(task)
+}
+
+/// Delete inbox tasks
+#[tauri::command]
+pub fn discard_inbox_tasks<R: Runtime>(app: AppHandle<R>, ids: Vec<i64>, db: State<Database>) -> Result<Vec<TaskResponse>, FlowStateError> {
+    let tasks = flowstate_core::inbox::discard(&db, &ids)?;
+    let tasks = tasks.into_iter().map(|task| {
+        emit_task_deleted(&app, task.id);
+        TaskResponse::from(task)
+    }).collect();
+    emit_inbox_changed(&app, &db);
+    Ok(tasks)
+}
+
+/// Every achievement with its progress, unlocked or not
+#[tauri::command]
+pub fn get_achievements(db: State<Database>) -> Result<Vec<flowstate_core::achievements::AchievementStatus>, FlowStateError> {
+    Ok(flowstate_core::achievements::get_achievements(&db)?)
+}
+
+/// Remind about the task at `when`: local "YYYY-MM-DD HH:MM" or a phrase like
+/// "6pm", "tomorrow at 9", "in 20 minutes". Returns the local time it's set for.
+#[tauri::command]
+pub fn set_reminder(app: AppHandle, task_id: i64, when: String, db: State<Database>) -> Result<String, FlowStateError> {
+    let at = flowstate_core::reminders::set_reminder(&db, task_id, &when)?;
+    emit_reminders_changed(&app);
+    Ok(at.format(flowstate_core::reminders::LOCAL_FORMAT).to_string())
+}
+
+#[tauri::command]
+pub fn clear_reminder(app: AppHandle, task_id: i64, db: State<Database>) -> Result<(), FlowStateError> {
+    flowstate_core::reminders::clear_reminder(&db, task_id)?;
+    emit_reminders_changed(&app);
+    Ok(())
+}
+
+/// Reminders that haven't fired yet, soonest first, in local time
+#[tauri::command]
+pub fn get_reminders(db: State<Database>) -> Result<Vec<flowstate_core::database::Reminder>, FlowStateError> {
+    Ok(flowstate_core::reminders::get_reminders(&db)?)
+}
+
+/// Read the open tasks aloud with the system voice
+#[tauri::command]
+pub fn speak_tasks(db: State<Database>) -> Result<(), FlowStateError> {
+    Ok(flowstate_core::speech::speak_tasks(&db)?)
+}
+
+#[tauri::command]
+pub fn get_timer_status() -> Result<u64, FlowStateError> {
+    Ok(crate::timer::get_remaining_time()?)
+}
+
+#[tauri::command]
+pub fn reset_timer() -> Result<(), FlowStateError> {
+    Ok(crate::timer::reset_timer()?)
+}
+
+#[tauri::command]
+pub fn get_break_remaining() -> Result<Option<u64>, FlowStateError> {
+    Ok(crate::timer::get_break_remaining())
+}
+
+/// Skip button on the break overlay
+#[tauri::command]
+pub async fn end_break(app: AppHandle) -> Result<(), FlowStateError> {
+    crate::timer::end_break(&app);
+    Ok(())
+}
+
+/// Explicit "got it" for the last timer alert - stops escalation
+#[tauri::command]
+pub fn acknowledge_alert(app: AppHandle) -> Result<(), FlowStateError> {
+    crate::timer::acknowledge_alert(&app);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_timer_paused() -> Result<bool, FlowStateError> {
+    Ok(crate::timer::is_timer_paused()?)
+}
+
+#[tauri::command]
+pub fn start_focus(app: AppHandle, task_id: i64, minutes: u64, db: State<Database>) -> Result<crate::timer::FocusSession, FlowStateError> {
+    if minutes == 0 || minutes > 180 {
+        return Err(flowstate_core::i18n::t("error.focus_duration").into());
     }
-    crate::timer::set_timer_duration(&app, minutes)
-        .map_err(|e: String| e)
+    let task = flowstate_core::database::get_task_by_id(&db, task_id)?;
+
+    let session = crate::timer::FocusSession {
+        task_id: task.id,
+        task_text: task.text,
+        minutes,
+        started_at: chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+    };
+    let monitor = flowstate_core::settings::load(&db).focus_monitor_enabled;
+    crate::timer::start_focus(session.clone(), monitor)?;
+    crate::timer::acknowledge_alert(&app);
+    Ok(session)
 }
 
 #[tauri::command]
-pub fn set_always_on_top(window: Window, always_on_top: bool) -> Result<(), String> {
-    window.set_always_on_top(always_on_top)
-        .map_err(|e| e.to_string())
+pub fn stop_focus() -> Result<(), FlowStateError> {
+    crate::timer::stop_focus()?;
+    Ok(())
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct WindowState {
-    pub x: f64,
-    pub y: f64,
-    pub width: f64,
-    pub height: f64,
+#[tauri::command]
+pub fn get_focus_session() -> Result<Option<crate::timer::FocusSession>, FlowStateError> {
+    Ok(crate::timer::get_focus_session()?)
 }
 
 #[tauri::command]
-pub fn get_window_state(window: Window) -> Result<WindowState, String> {
-    let position = window.outer_position()
-        .map_err(|e| format!("Failed to get window position: {}", e))?;
-    // Use inner_size to match what set_size expects (inner size)
-    let size = window.inner_size()
-        .map_err(|e| format!("Failed to get window size: {}", e))?;
-    
-    Ok(WindowState {
-        x: position.x as f64,
-        y: position.y as f64,
-        width: size.width as f64,
-        height: size.height as f64,
-    })
+pub fn get_task_time_entries(task_id: i64, db: State<Database>) -> Result<Vec<flowstate_core::database::TimeEntry>, FlowStateError> {
+    Ok(flowstate_core::database::get_time_entries_for_task(&db, task_id)?)
 }
 
+/// Checkpoint response rate per day; `range` is "today", "week" or "month"
 #[tauri::command]
-pub fn set_window_state(window: Window, state: WindowState) -> Result<(), String> {
-    use tauri::LogicalPosition;
-    use tauri::LogicalSize;
-    
-    window.set_position(LogicalPosition::new(state.x as f64, state.y as f64))
-        .map_err(|e| format!("Failed to set window position: {}", e))?;
-    // set_size sets inner size, which matches what we're storing
-    window.set_size(LogicalSize::new(state.width as f64, state.height as f64))
-        .map_err(|e| format!("Failed to set window size: {}", e))?;
+pub fn get_focus_history(range: String, db: State<Database>) -> Result<Vec<flowstate_core::database::FocusDay>, FlowStateError> {
+    let days = match range.as_str() {
+        "today" => 1,
+        "week" => 7,
+        "month" => 30,
+        other => return Err(flowstate_core::i18n::tf("error.invalid_range", &[("range", &other)]).into()),
+    };
+    Ok(flowstate_core::database::get_focus_history(&db, days)?)
+}
+
+#[tauri::command]
+pub fn get_timer_duration(_app: AppHandle) -> Result<u64, FlowStateError> {
+    Ok(crate::timer::get_timer_duration_minutes()?)
+}
+
+#[tauri::command]
+pub fn set_timer_duration(app: AppHandle, minutes: u64, db: State<'_, Database>) -> Result<(), FlowStateError> {
+    update_settings(app, serde_json::json!({ "timer_duration_minutes": minutes }), db)?;
     Ok(())
 }
 
 #[tauri::command]
-pub fn save_window_state(app: AppHandle, state: WindowState) -> Result<(), String> {
-    let app_data_dir = app.path()
-        .app_data_dir()
-        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
-    
-    std::fs::create_dir_all(&app_data_dir)
-        .map_err(|e| format!("Failed to create app data directory: {}", e))?;
-    
-    let config_path = app_data_dir.join("window_state.json");
-    let json = serde_json::to_string_pretty(&state)
-        .map_err(|e| format!("Failed to serialize window state: {}", e))?;
-    
-    std::fs::write(&config_path, json)
-        .map_err(|e| format!("Failed to write window state: {}", e))?;
-    
+pub fn get_settings(db: State<Database>) -> Result<flowstate_core::settings::Settings, FlowStateError> {
+    Ok(flowstate_core::settings::load(&db))
+}
+
+/// Save any subset of settings, apply the ones that affect running state and broadcast the result
+#[tauri::command]
+pub fn update_settings(
+    app: AppHandle,
+    patch: serde_json::Value,
+    db: State<'_, Database>,
+) -> Result<flowstate_core::settings::Settings, FlowStateError> {
+    let previous = flowstate_core::settings::load(&db);
+    let mut settings = flowstate_core::settings::update(&db, patch)?;
+
+    flowstate_core::earcon::set_muted(settings.earcons_muted);
+    flowstate_core::i18n::set_locale(settings.ui_language);
+
+    if settings.timer_duration_minutes != previous.timer_duration_minutes {
+        crate::timer::apply_timer_duration(settings.timer_duration_minutes)?;
+
+        // Let the main window pick up the new interval without a restart
+        if let Some(window) = app.get_webview_window("main") {
+            let _ = window.emit("timer-reset", ());
+        }
+    }
+
+    if settings.wake_word_enabled != previous.wake_word_enabled
+        || settings.wake_word_phrase != previous.wake_word_phrase
+    {
+        if let Err(e) = sync_wake_word_listener(&app, &settings) {
+            // Don't leave the setting on when the microphone couldn't be opened
+            settings.wake_word_enabled = false;
+            flowstate_core::settings::save(&db, &settings)?;
+            let _ = app.emit("settings-changed", &settings);
+            return Err(e);
+        }
+    }
+
+    if settings.markdown_sync != previous.markdown_sync {
+        if let Err(e) = restart_markdown_sync(&app, &settings) {
+            // Keep the note settings but stop trying until they are fixed
+            settings.markdown_sync.enabled = false;
+            flowstate_core::settings::save(&db, &settings)?;
+            let _ = app.emit("settings-changed", &settings);
+            return Err(e.into());
+        }
+    }
+
+    if settings.calendar_export != previous.calendar_export {
+        if let Err(e) = restart_calendar_export(&app, &settings) {
+            settings.calendar_export.enabled = false;
+            flowstate_core::settings::save(&db, &settings)?;
+            let _ = app.emit("settings-changed", &settings);
+            return Err(e.into());
+        }
+    }
+
+    if settings.google_calendar != previous.google_calendar {
+        if let Err(e) = restart_google_calendar_sync(&app, &settings) {
+            settings.google_calendar.enabled = false;
+            flowstate_core::settings::save(&db, &settings)?;
+            let _ = app.emit("settings-changed", &settings);
+            return Err(e.into());
+        }
+    }
+
+    if settings.todoist != previous.todoist {
+        if let Err(e) = restart_todoist_sync(&app, &settings) {
+            settings.todoist.enabled = false;
+            flowstate_core::settings::save(&db, &settings)?;
+            let _ = app.emit("settings-changed", &settings);
+            return Err(e.into());
+        }
+    }
+
+    if settings.mqtt != previous.mqtt {
+        if let Err(e) = restart_mqtt_publisher(&app, &settings) {
+            settings.mqtt.enabled = false;
+            flowstate_core::settings::save(&db, &settings)?;
+            let _ = app.emit("settings-changed", &settings);
+            return Err(e.into());
+        }
+    }
+
+    if settings.clipboard != previous.clipboard {
+        if let Err(e) = restart_clipboard_watcher(&app, &settings) {
+            settings.clipboard.enabled = false;
+            flowstate_core::settings::save(&db, &settings)?;
+            let _ = app.emit("settings-changed", &settings);
+            return Err(e.into());
+        }
+    }
+
+    if settings.telegram != previous.telegram {
+        if let Err(e) = restart_telegram_bridge(&app, &settings) {
+            settings.telegram.enabled = false;
+            flowstate_core::settings::save(&db, &settings)?;
+            let _ = app.emit("settings-changed", &settings);
+            return Err(e.into());
+        }
+    }
+
+    if settings.quick_add_shortcut != previous.quick_add_shortcut {
+        if let Err(e) = register_global_shortcut(&app, Some(&previous.quick_add_shortcut), &settings.quick_add_shortcut) {
+            // Keep the shortcut that still works
+            settings.quick_add_shortcut = previous.quick_add_shortcut.clone();
+            let _ = register_global_shortcut(&app, None, &settings.quick_add_shortcut);
+            flowstate_core::settings::save(&db, &settings)?;
+            let _ = app.emit("settings-changed", &settings);
+            return Err(e.into());
+        }
+    }
+
+    if settings.compact_mode_shortcut != previous.compact_mode_shortcut {
+        if let Err(e) = register_global_shortcut(&app, Some(&previous.compact_mode_shortcut), &settings.compact_mode_shortcut) {
+            settings.compact_mode_shortcut = previous.compact_mode_shortcut.clone();
+            let _ = register_global_shortcut(&app, None, &settings.compact_mode_shortcut);
+            flowstate_core::settings::save(&db, &settings)?;
+            let _ = app.emit("settings-changed", &settings);
+            return Err(e.into());
+        }
+    }
+
+    if settings.window.compact != previous.window.compact {
+        apply_compact_mode(&app, &settings)?;
+    } else if settings.window.always_on_top != previous.window.always_on_top && !settings.window.compact {
+        // The compact strip stays on top regardless; the flag applies again when it expands
+        if let Some(window) = app.get_webview_window("main") {
+            window.set_always_on_top(settings.window.always_on_top)
+                .map_err(|e| format!("Failed to set always on top: {}", e))?;
+        }
+    }
+
+    // Auto-pause and schedule changes take effect on the next timer check
+    crate::timer::notify_timer_changed();
+
+    let _ = app.emit("settings-changed", &settings);
+    Ok(settings)
+}
+
+/// Apply an edited flowstate.toml the way the same change made in Settings is applied
+pub fn apply_config_file(app: &AppHandle, file: flowstate_core::config_file::ConfigFile) {
+    if let Err(e) = update_settings(app.clone(), file.patch(), app.state()) {
+        tracing::warn!("Failed to apply {}: {}", flowstate_core::config_file::FILE_NAME, e);
+    }
+}
+
+#[tauri::command]
+pub fn set_always_on_top(app: AppHandle, always_on_top: bool, db: State<'_, Database>) -> Result<(), FlowStateError> {
+    update_settings(app, serde_json::json!({ "window": { "always_on_top": always_on_top } }), db)?;
     Ok(())
 }
 
 #[tauri::command]
-pub fn load_window_state(app: AppHandle) -> Result<Option<WindowState>, String> {
-    let app_data_dir = app.path()
-        .app_data_dir()
-        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
-    
-    let config_path = app_data_dir.join("window_state.json");
-    
-    if !config_path.exists() {
-        return Ok(None);
+pub fn set_compact_mode(app: AppHandle, compact: bool, db: State<'_, Database>) -> Result<(), FlowStateError> {
+    update_settings(app, serde_json::json!({ "window": { "compact": compact } }), db)?;
+    Ok(())
+}
+
+/// Switch between the full window and the compact strip (global shortcut handler)
+pub fn toggle_compact_mode(app: &AppHandle) {
+    let db = app.state::<Database>();
+    let compact = !flowstate_core::settings::load(&db).window.compact;
+    if let Err(e) = set_compact_mode(app.clone(), compact, db) {
+        tracing::error!("Failed to toggle compact mode: {}", e);
+    }
+}
+
+/// Resize the main window to the compact strip or back to its saved geometry
+fn apply_compact_mode(app: &AppHandle, settings: &flowstate_core::settings::Settings) -> Result<(), String> {
+    use tauri::{LogicalPosition, LogicalSize, Size};
+
+    let Some(window) = app.get_webview_window("main") else {
+        return Ok(());
+    };
+    let map_err = |e: tauri::Error| format!("Failed to switch compact mode: {}", e);
+
+    if settings.window.compact {
+        let (width, height) = flowstate_core::settings::WindowSettings::COMPACT_SIZE;
+        window.set_min_size(None::<Size>).map_err(map_err)?;
+        window.set_size(LogicalSize::new(width, height)).map_err(map_err)?;
+        window.set_resizable(false).map_err(map_err)?;
+        window.set_always_on_top(true).map_err(map_err)?;
+        if let Some(geometry) = settings.window.compact_geometry.filter(|g| g.is_plausible()) {
+            window.set_position(LogicalPosition::new(geometry.x, geometry.y)).map_err(map_err)?;
+        }
+    } else {
+        // Matches minWidth/minHeight in tauri.conf.json
+        window.set_resizable(true).map_err(map_err)?;
+        window.set_min_size(Some(LogicalSize::new(250.0, 400.0))).map_err(map_err)?;
+        match settings.window.geometry.filter(|g| g.is_plausible()) {
+            Some(geometry) => {
+                window.set_position(LogicalPosition::new(geometry.x, geometry.y)).map_err(map_err)?;
+                window.set_size(LogicalSize::new(geometry.width, geometry.height)).map_err(map_err)?;
+            }
+            None => window.set_size(LogicalSize::new(300.0, 600.0)).map_err(map_err)?,
+        }
+        window.set_always_on_top(settings.window.always_on_top).map_err(map_err)?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_window_state(window: Window) -> Result<WindowGeometry, FlowStateError> {
+    let scale = window.scale_factor()
+        .map_err(|e| format!("Failed to get window scale factor: {}", e))?;
+    let position = window.outer_position()
+        .map_err(|e| format!("Failed to get window position: {}", e))?
+        .to_logical::<f64>(scale);
+    // Use inner_size to match what set_size expects (inner size)
+    let size = window.inner_size()
+        .map_err(|e| format!("Failed to get window size: {}", e))?
+        .to_logical::<f64>(scale);
+
+    Ok(WindowGeometry {
+        x: position.x,
+        y: position.y,
+        width: size.width,
+        height: size.height,
+    })
+}
+
+#[tauri::command]
+pub fn save_window_state(state: WindowGeometry, db: State<'_, Database>) -> Result<(), FlowStateError> {
+    // Saved straight to the store: moves are frequent and nothing else needs to react
+    let mut settings = flowstate_core::settings::load(&db);
+    if settings.window.compact {
+        settings.window.compact_geometry = Some(state);
+    } else {
+        settings.window.geometry = Some(state);
+    }
+    Ok(flowstate_core::settings::save(&db, &settings)?)
+}
+
+/// Put the main window back where it was last time, with its always-on-top flag
+/// (or as the compact strip if it was closed that way)
+pub fn restore_window_state(app: &AppHandle, db: &Database) {
+    let mut settings = flowstate_core::settings::load(db);
+
+    // Geometry used to live in window_state.json; move it into the settings store
+    if let Ok(app_data_dir) = flowstate_core::paths::app_data_dir(app) {
+        let legacy_path = app_data_dir.join("window_state.json");
+        if let Ok(json) = std::fs::read_to_string(&legacy_path) {
+            if settings.window.geometry.is_none() {
+                settings.window.geometry = serde_json::from_str(&json).ok();
+                if let Err(e) = flowstate_core::settings::save(db, &settings) {
+                    tracing::warn!("Failed to migrate window state: {}", e);
+                }
+            }
+            let _ = std::fs::remove_file(&legacy_path);
+        }
+    }
+
+    let Some(window) = app.get_webview_window("main") else {
+        return;
+    };
+    if let Some(geometry) = settings.window.geometry.filter(|g| g.is_plausible()) {
+        use tauri::{LogicalPosition, LogicalSize};
+
+        let _ = window.set_position(LogicalPosition::new(geometry.x, geometry.y));
+        // set_size sets inner size, which matches what we're storing
+        let _ = window.set_size(LogicalSize::new(geometry.width, geometry.height));
+    }
+    if settings.window.always_on_top {
+        if let Err(e) = window.set_always_on_top(true) {
+            tracing::warn!("Failed to restore always on top: {}", e);
+        }
+    }
+    if settings.window.compact {
+        if let Err(e) = apply_compact_mode(app, &settings) {
+            tracing::warn!("Failed to restore compact mode: {}", e);
+        }
     }
-    
-    let json = std::fs::read_to_string(&config_path)
-        .map_err(|e| format!("Failed to read window state: {}", e))?;
-    
-    let state: WindowState = serde_json::from_str(&json)
-        .map_err(|e| format!("Failed to parse window state: {}", e))?;
-    
-    Ok(Some(state))
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -222,209 +1051,815 @@ pub struct ModelInfo {
 }
 
 #[tauri::command]
-pub fn list_whisper_models(app: AppHandle) -> Result<Vec<ModelInfo>, String> {
-    // This command needs AppHandle to access app data directory
-    let models = vec![
-        (WhisperModelSize::Tiny, "Tiny"),
-        (WhisperModelSize::Base, "Base"),
-        (WhisperModelSize::Small, "Small"),
-        (WhisperModelSize::Medium, "Medium"),
-        (WhisperModelSize::Large, "Large"),
-    ];
-
-    Ok(models.into_iter().map(|(size, name)| {
+pub fn list_whisper_models() -> Result<Vec<ModelInfo>, FlowStateError> {
+    Ok(ModelStore::open()?.list().into_iter().map(|(size, installed)| {
         ModelInfo {
-            name: name.to_string(),
+            name: size.name().to_string(),
             filename: size.filename().to_string(),
             size_mb: size.size_mb(),
-            installed: check_model_exists(&app, size),
+            installed,
         }
     }).collect())
 }
 
+/// Queue a model download; progress and the result arrive as "model-download" events
 #[tauri::command]
-pub async fn download_whisper_model(
-    app: AppHandle,
+pub fn download_whisper_model(
     model_name: String,
-) -> Result<String, String> {
+    downloads: State<'_, flowstate_core::downloads::DownloadManager>,
+) -> Result<(), FlowStateError> {
     let model_size = WhisperModelSize::from_str(&model_name)
-        .ok_or_else(|| format!("Invalid model name: {}", model_name))?;
-
-    // Emit progress events
-    let app_handle = app.clone();
-    let progress_callback = Box::new(move |downloaded: u64, total: u64| {
-        if let Some(window) = app_handle.get_webview_window("main") {
-            let progress = if total > 0 {
-                (downloaded as f64 / total as f64 * 100.0) as u32
-            } else {
-                0
-            };
-            let _ = window.emit("model-download-progress", serde_json::json!({
-                "model": model_name,
-                "downloaded": downloaded,
-                "total": total,
-                "progress": progress,
-            }));
-        }
-    });
+        .ok_or_else(|| flowstate_core::i18n::tf("error.invalid_model", &[("model", &model_name)]))?;
+    downloads.enqueue(model_size);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn cancel_model_download(
+    model_name: String,
+    downloads: State<'_, flowstate_core::downloads::DownloadManager>,
+) -> Result<(), FlowStateError> {
+    let model_size = WhisperModelSize::from_str(&model_name)
+        .ok_or_else(|| flowstate_core::i18n::tf("error.invalid_model", &[("model", &model_name)]))?;
+    downloads.cancel(model_size);
+    Ok(())
+}
+
+/// Status of every download started this run (queued, in progress, finished)
+#[tauri::command]
+pub fn get_model_downloads(downloads: State<'_, flowstate_core::downloads::DownloadManager>) -> Vec<flowstate_core::downloads::DownloadEvent> {
+    downloads.statuses()
+}
 
-    let path = download_model(&app, model_size, Some(progress_callback)).await?;
+#[tauri::command]
+pub fn check_whisper_model(model_name: String) -> Result<bool, FlowStateError> {
+    let model_size = WhisperModelSize::parse(&model_name)?;
     
-    Ok(format!("Model downloaded successfully to: {}", path.to_string_lossy()))
+    Ok(ModelStore::open()?.is_installed(model_size))
+}
+
+#[tauri::command]
+pub fn delete_whisper_model(
+    model_name: String,
+    whisper_cache: State<'_, WhisperCache>,
+) -> Result<(), FlowStateError> {
+    let model_size = WhisperModelSize::parse(&model_name)?;
+
+    // Clear the cache to avoid using stale model reference
+    whisper_cache.clear();
+
+    Ok(ModelStore::open()?.delete(model_size)?)
+}
+
+/// Replace a damaged model (see `FlowStateError::ModelCorrupt`): delete the
+/// file and queue a fresh download
+#[tauri::command]
+pub fn repair_whisper_model(
+    model_name: String,
+    whisper_cache: State<'_, WhisperCache>,
+    downloads: State<'_, flowstate_core::downloads::DownloadManager>,
+) -> Result<(), FlowStateError> {
+    let model_size = WhisperModelSize::parse(&model_name)?;
+    whisper_cache.clear();
+    ModelStore::open()?.delete(model_size)?;
+    tracing::info!("Downloading the {} model again", model_size.name());
+    downloads.enqueue(model_size);
+    Ok(())
+}
+
+/// Free the loaded model's memory now; the next recording loads it again
+#[tauri::command]
+pub fn unload_whisper_model(whisper_cache: State<'_, WhisperCache>) {
+    whisper_cache.clear();
+}
+
+/// Time `model` (or, without one, every installed model) on the built-in test clip
+#[tauri::command]
+pub async fn benchmark_whisper(model: Option<String>) -> Result<Vec<flowstate_core::benchmark::BenchmarkResult>, FlowStateError> {
+    let model = model.as_deref().map(WhisperModelSize::parse).transpose()?;
+    let results = tauri::async_runtime::spawn_blocking(move || {
+        flowstate_core::crash::catch_panic(|| match model {
+            Some(model) => flowstate_core::benchmark::run(model).map(|result| vec![result]),
+            None => flowstate_core::benchmark::run_installed(),
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+    Ok(results)
+}
+
+#[tauri::command]
+pub async fn transcribe_audio(
+    audio_path: String,
+    model_name: String,
+    language: Option<String>,
+    whisper_cache: State<'_, WhisperCache>,
+    db: State<'_, Database>,
+) -> Result<String, FlowStateError> {
+    let language = recording_language(&db, language);
+    let engine = flowstate_core::stt::engine(&whisper_cache, &model_name)?;
+    let samples = flowstate_core::whisper::read_wav(&audio_path)?;
+    Ok(flowstate_core::crash::catch_panic(|| {
+        engine.transcribe(&samples, resolve_language(language.as_deref()))
+    })?)
 }
 
 #[tauri::command]
-pub fn check_whisper_model(app: AppHandle, model_name: String) -> Result<bool, String> {
-    let model_size = WhisperModelSize::from_str(&model_name)
-        .ok_or_else(|| format!("Invalid model name: {}", model_name))?;
-    
-    Ok(check_model_exists(&app, model_size))
+pub async fn save_audio_file(
+    app: AppHandle,
+    audio_data: Vec<u8>,
+) -> Result<String, FlowStateError> {
+    // The caller owns this file; it is swept on the next startup if left behind
+    let file = crate::shutdown::write_temp_audio(&app, &audio_data)?;
+    Ok(file.keep().to_string_lossy().to_string())
 }
 
 #[tauri::command]
-pub fn delete_whisper_model(
+pub async fn process_voice_recording(
     app: AppHandle,
+    audio_data: Vec<u8>,
     model_name: String,
+    language: Option<String>,
+    db: State<'_, Database>,
     whisper_cache: State<'_, WhisperCache>,
-) -> Result<(), String> {
-    let model_size = WhisperModelSize::from_str(&model_name)
-        .ok_or_else(|| format!("Invalid model name: {}", model_name))?;
-
-    // Clear the cache to avoid using stale model reference
-    whisper_cache.clear();
+    processing: State<'_, ProcessingState>,
+) -> Result<VoiceProcessingResult, FlowStateError> {
+    let _processing = crate::shutdown::begin_processing()?;
+    let _turn = processing.begin("recording", Some(crate::processing::fingerprint(&audio_data))).await?;
+
+    // Decoded in memory; the recording never touches the disk
+    emit_stage(&app, ProcessingStage::Decoding, None);
+    let samples = flowstate_core::whisper::decode_wav(&audio_data)?;
+    drop(audio_data);
+    process_voice_samples(&app, &db, &whisper_cache, samples, &model_name, language).await
+}
 
-    delete_model(&app, model_size)
+/// Whether a voice job is being processed, and how many are waiting
+#[tauri::command]
+pub fn get_processing_status(processing: State<'_, ProcessingState>) -> crate::processing::ProcessingStatus {
+    processing.status()
 }
 
+/// The oldest question about which task a spoken completion meant, if one is
+/// waiting
 #[tauri::command]
-pub async fn transcribe_audio(
-    app: AppHandle,
-    audio_path: String,
-    model_name: String,
-    whisper_cache: State<'_, WhisperCache>,
-) -> Result<String, String> {
-    let model_size = WhisperModelSize::from_str(&model_name)
-        .ok_or_else(|| format!("Invalid model name: {}", model_name))?;
+pub fn get_disambiguation(
+    state: State<'_, flowstate_core::disambiguation::DisambiguationState>,
+) -> Option<flowstate_core::disambiguation::NeedsDisambiguation> {
+    state.current()
+}
 
-    // Get cached Whisper context
-    let ctx = whisper_cache.get_or_create(&app, model_size)?;
-    transcribe_with_context(&ctx, &audio_path)
+/// Answer the oldest waiting question with the id of the task meant (`None`
+/// for none of them); the result carries the next question, if any
+#[tauri::command]
+pub fn resolve_disambiguation<R: Runtime>(
+    app: AppHandle<R>,
+    choice: Option<i64>,
+    db: State<'_, Database>,
+    state: State<'_, flowstate_core::disambiguation::DisambiguationState>,
+) -> Result<flowstate_core::disambiguation::Resolved, FlowStateError> {
+    let resolved = state.resolve(&db, choice)?;
+    if let Some(task) = &resolved.completed {
+        emit_task_changed(&app, "task-completed", &TaskResponse::from(task.clone()));
+        flowstate_core::earcon::play(flowstate_core::earcon::Earcon::Success);
+    }
+    Ok(resolved)
 }
 
+/// Enroll the user's voice for the speaker gate from a few seconds of them
+/// speaking. The profile is saved; turning the gate on is a separate setting.
 #[tauri::command]
-pub async fn save_audio_file(
+pub fn enroll_speaker(
     app: AppHandle,
     audio_data: Vec<u8>,
-) -> Result<String, String> {
-    use std::io::Write;
+    db: State<'_, Database>,
+) -> Result<flowstate_core::speaker::VoiceProfile, FlowStateError> {
+    let samples = flowstate_core::whisper::decode_wav(&audio_data)?;
+    let profile = flowstate_core::speaker::enroll(&samples)?;
+    update_settings(app, serde_json::json!({ "speaker_gate": { "profile": profile } }), db)?;
+    Ok(profile)
+}
 
-    let app_data_dir = app.path()
-        .app_data_dir()
-        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+/// Transcribe and apply 16kHz mono samples. The samples are moved through to
+/// Whisper without a copy, so audio captured in the backend can come straight here.
+pub async fn process_voice_samples(
+    app: &AppHandle,
+    db: &Database,
+    whisper_cache: &WhisperCache,
+    samples: Vec<f32>,
+    model_name: &str,
+    language: Option<String>,
+) -> Result<VoiceProcessingResult, FlowStateError> {
+    // The model stays cached, so it isn't reloaded for every recording
+    let engine = flowstate_core::stt::engine(whisper_cache, model_name)?;
+    Ok(process_samples_with(app, db, engine, samples, language).await?)
+}
 
-    let audio_temp_dir = app_data_dir.join("audio_temp");
-    std::fs::create_dir_all(&audio_temp_dir)
-        .map_err(|e| format!("Failed to create audio temp directory: {}", e))?;
+/// The recording pipeline after the model lookup: transcribe with `engine`
+/// and apply what was said. Tests drive it with canned transcripts.
+pub async fn process_samples_with<R: Runtime>(
+    app: &AppHandle<R>,
+    db: &Database,
+    engine: std::sync::Arc<dyn SpeechToText>,
+    samples: Vec<f32>,
+    language: Option<String>,
+) -> Result<VoiceProcessingResult, String> {
+    crate::timer::acknowledge_alert(app);
+    let language = recording_language(db, language);
+    let result = transcribe_and_apply(app, db, "recording", engine, samples, language).await?;
+    tracing::debug!("Transcription complete: \"{}\"", result.transcript);
+    record_transcription(app, db, "recording", &result.transcript, result.actions.as_deref());
+    Ok(result)
+}
 
-    // Generate unique filename with timestamp
-    let timestamp = chrono::Utc::now().format("%Y%m%d_%H%M%S_%f");
-    let filename = format!("recording_{}.wav", timestamp);
-    let file_path = audio_temp_dir.join(&filename);
+/// Transcribe the answer to the timer-expiry prompt and log it against the interval
+#[tauri::command]
+pub async fn process_journal_recording(
+    app: AppHandle,
+    audio_data: Vec<u8>,
+    model_name: String,
+    language: Option<String>,
+    interval_start: String,
+    interval_end: String,
+    db: State<'_, Database>,
+    whisper_cache: State<'_, WhisperCache>,
+    processing: State<'_, ProcessingState>,
+) -> Result<Option<flowstate_core::database::JournalEntry>, FlowStateError> {
+    let _processing = crate::shutdown::begin_processing()?;
+    let _turn = processing.begin("journal", Some(crate::processing::fingerprint(&audio_data))).await?;
+    let samples = flowstate_core::whisper::decode_wav(&audio_data)?;
+    let settings = flowstate_core::settings::load(&db);
+    let samples = flowstate_core::speaker::gate(samples, &settings.speaker_gate);
+
+    let engine = flowstate_core::stt::engine(&whisper_cache, &model_name)?;
+
+    let language = recording_language(&db, language);
+    let started = std::time::Instant::now();
+    let transcript = flowstate_core::crash::catch_panic(|| {
+        engine.transcribe(&samples, resolve_language(language.as_deref()))
+    })?;
+    let audio = std::time::Duration::from_secs_f64(samples.len() as f64 / 16000.0);
+    flowstate_core::metrics::record_transcription(&db, "journal", audio, started.elapsed());
+
+    // Silence means the prompt was ignored - nothing to log
+    let text = flowstate_core::content_filter::filter(transcript.trim(), &settings.content_filter);
+    let text = text.trim();
+    if text.is_empty() {
+        return Ok(None);
+    }
 
-    // Write audio data to file
-    let mut file = std::fs::File::create(&file_path)
-        .map_err(|e| format!("Failed to create audio file: {}", e))?;
+    tracing::debug!("Journal entry: \"{}\"", text);
+    record_transcription(&app, &db, "journal", text, None);
+    crate::timer::acknowledge_alert(&app);
 
-    file.write_all(&audio_data)
-        .map_err(|e| format!("Failed to write audio data: {}", e))?;
+    Ok(flowstate_core::database::add_journal_entry(&db, text, &interval_start, &interval_end).map(Some)?)
+}
 
-    Ok(file_path.to_string_lossy().to_string())
+#[tauri::command]
+pub fn get_journal_entries(limit: Option<i64>, db: State<Database>) -> Result<Vec<flowstate_core::database::JournalEntry>, FlowStateError> {
+    Ok(flowstate_core::database::get_journal_entries(&db, limit.unwrap_or(50))?)
 }
 
 #[tauri::command]
-pub async fn process_voice_recording(
+pub async fn import_audio_file(
     app: AppHandle,
-    audio_data: Vec<u8>,
+    file_path: String,
     model_name: String,
+    language: Option<String>,
     db: State<'_, Database>,
     whisper_cache: State<'_, WhisperCache>,
-) -> Result<Vec<TaskResponse>, String> {
-    // Save audio to temporary file
-    let audio_path = save_audio_file(app.clone(), audio_data).await?;
+    processing: State<'_, ProcessingState>,
+) -> Result<VoiceProcessingResult, FlowStateError> {
+    let _processing = crate::shutdown::begin_processing()?;
+    let _turn = processing.begin("import", Some(crate::processing::fingerprint(&file_path))).await?;
+    let engine = flowstate_core::stt::engine(&whisper_cache, &model_name)?;
+
+    // Decode and downmix the imported file (WAV/MP3/M4A/OGG)
+    emit_stage(&app, ProcessingStage::Decoding, None);
+    let path = std::path::PathBuf::from(&file_path);
+    let (samples, sample_rate) = tauri::async_runtime::spawn_blocking(move || {
+        flowstate_core::audio::decode_audio_file(&path)
+    })
+    .await
+    .map_err(|e| format!("Audio decoding task failed: {}", e))??;
 
-    // Ensure we have a model
-    let model_size = WhisperModelSize::from_str(&model_name)
-        .ok_or_else(|| format!("Invalid model name: {}", model_name))?;
+    // Whisper expects 16kHz
+    let samples = flowstate_core::whisper::resample(&samples, sample_rate as usize, 16000);
+
+    let language = recording_language(&db, language);
+    let result = transcribe_and_apply(&app, &db, "import", engine, samples, language).await?;
+    tracing::debug!("Imported audio transcribed: \"{}\"", result.transcript);
+    record_transcription(&app, &db, "import", &result.transcript, result.actions.as_deref());
+    Ok(result)
+}
+
+/// Transcribe 16kHz samples and apply what they say. A long memo goes through
+/// in pieces (see `audio::split_at_pauses`): while one piece is parsed and
+/// applied, the next is transcribed, so the first tasks show up before the
+/// rest is decoded. Tasks are announced through the usual task events as each
+/// piece is applied; in action mode the memo is applied once, whole. The time
+/// spent transcribing is logged under `source`.
+async fn transcribe_and_apply<R: Runtime>(
+    app: &AppHandle<R>,
+    db: &Database,
+    source: &str,
+    engine: std::sync::Arc<dyn SpeechToText>,
+    samples: Vec<f32>,
+    language: Option<String>,
+) -> Result<VoiceProcessingResult, String> {
+    let settings = flowstate_core::settings::load(db);
+    let first_question = app.state::<flowstate_core::disambiguation::DisambiguationState>().next_id();
+    // Other voices are cut before anything hears them
+    let samples = flowstate_core::speaker::gate(samples, &settings.speaker_gate);
+    let audio = std::time::Duration::from_secs_f64(samples.len() as f64 / 16000.0);
+    let (tx, mut rx) = tokio::sync::mpsc::channel::<Result<(Option<ChunkProgress>, String), String>>(CHUNK_QUEUE);
+    let transcriber_app = app.clone();
+    let transcribing = tauri::async_runtime::spawn_blocking(move || {
+        let pieces = flowstate_core::audio::split_at_pauses(&samples, 16000, flowstate_core::audio::CHUNK_SECS);
+        let total = pieces.len();
+        let mut elapsed = std::time::Duration::ZERO;
+        for (index, piece) in pieces.into_iter().enumerate() {
+            let chunk = (total > 1).then_some(ChunkProgress { index: index + 1, total });
+            emit_chunk_stage(&transcriber_app, ProcessingStage::Transcribing, None, chunk);
+            let started = std::time::Instant::now();
+            let text = flowstate_core::crash::catch_panic(|| {
+                engine.transcribe(piece, resolve_language(language.as_deref()))
+            });
+            elapsed += started.elapsed();
+            let failed = text.is_err();
+            // A closed channel means parsing failed; there's no point going on
+            if tx.blocking_send(text.map(|text| (chunk, text))).is_err() || failed {
+                return None;
+            }
+        }
+        Some(elapsed)
+    });
+
+    let mut transcript = String::new();
+    let mut tasks = Vec::new();
+    let mut actions: Option<Vec<flowstate_core::history::HistoryAction>> = None;
+    while let Some(piece) = rx.recv().await {
+        let (chunk, text) = piece?;
+        // Filtered words never reach the parser, the tasks or the kept transcript
+        let text = flowstate_core::content_filter::filter(text.trim(), &settings.content_filter);
+        let text = text.trim();
+        if text.is_empty() {
+            continue;
+        }
+        if !transcript.is_empty() {
+            transcript.push(' ');
+        }
+        transcript.push_str(text);
+        // In action mode the whole recording is one command or one task
+        if settings.action_mode {
+            continue;
+        }
+        let applied = apply_transcript(app, db, text, &transcript, chunk).await?;
+        tasks.extend(applied.tasks);
+        if let Some(parsed) = applied.actions {
+            actions.get_or_insert_with(Vec::new).extend(parsed);
+        }
+    }
+    let elapsed = transcribing.await.map_err(|e| format!("Transcription task failed: {}", e))?;
+    if settings.action_mode && !transcript.is_empty() {
+        tasks = apply_transcript(app, db, &transcript, &transcript, None).await?.tasks;
+    }
+    if let Some(elapsed) = elapsed {
+        flowstate_core::metrics::record_transcription(db, source, audio, elapsed);
+    }
+    let needs_disambiguation = app.state::<flowstate_core::disambiguation::DisambiguationState>().asked_since(first_question);
+    Ok(VoiceProcessingResult { transcript, tasks, actions, needs_disambiguation })
+}
 
-    // Get cached Whisper context (avoids reloading model on every recording)
-    let ctx = whisper_cache.get_or_create(&app, model_size)?;
+/// The recording's language, or else the active profile's or the settings'
+fn recording_language(db: &Database, language: Option<String>) -> Option<String> {
+    language.or_else(|| Some(flowstate_core::settings::load(db).recording_language().to_string()))
+}
 
-    // Transcribe audio using cached context
-    let transcript = transcribe_with_context(&ctx, &audio_path)
-        .map_err(|e| {
-            // Clean up temp file even on error
-            let _ = std::fs::remove_file(&audio_path);
-            e
-        })?;
+/// Keep a transcribed voice note for the weekly report and re-parsing, with
+/// the parser's actions if it was parsed
+fn record_transcription<R: Runtime>(
+    app: &AppHandle<R>,
+    db: &Database,
+    source: &str,
+    transcript: &str,
+    actions: Option<&[flowstate_core::history::HistoryAction]>,
+) {
+    if transcript.trim().is_empty() {
+        return;
+    }
+    let actions = actions.map(flowstate_core::history::to_json);
+    if let Err(e) = flowstate_core::database::log_transcription(db, source, transcript, actions.as_deref()) {
+        tracing::warn!("Failed to log transcription: {}", e);
+    }
+    check_achievements(app, db);
+}
 
-    // Clean up temp file after successful transcription
-    let _ = std::fs::remove_file(&audio_path);
+/// Parse with the chain in the settings, logging how long each parser took
+async fn parse_transcript(
+    db: &Database,
+    settings: &flowstate_core::settings::Settings,
+    transcript: &str,
+) -> Result<flowstate_core::parser::ParsedMemo, String> {
+    let (parsed, attempts) = flowstate_core::parser::ParserChain::from_settings(settings).parse_timed(transcript).await;
+    flowstate_core::metrics::record_parse(db, &attempts);
+    parsed.map_err(|e| flowstate_core::i18n::tf("error.parse_transcript", &[("error", &e)]))
+}
 
-    eprintln!("🎤 Transcription complete: \"{}\"", transcript);
+/// Run a transcript (or a piece of a long one) through removal detection and
+/// task parsing, then apply the resulting actions to the database. `heard` is
+/// everything transcribed so far, for the progress events.
+async fn apply_transcript<R: Runtime>(
+    app: &AppHandle<R>,
+    db: &Database,
+    transcript: &str,
+    heard: &str,
+    chunk: Option<ChunkProgress>,
+) -> Result<AppliedTranscript, String> {
+    emit_chunk_stage(app, ProcessingStage::Parsing, Some(heard), chunk);
+
+    let settings = flowstate_core::settings::load(db);
+    if settings.action_mode {
+        let tasks = action_mode_transcript(app, db, &settings, transcript).await?;
+        return Ok(AppliedTranscript { tasks, actions: None });
+    }
+    if let Some(result) = voice_command(app, db, &settings, transcript).await {
+        return result.map(|tasks| AppliedTranscript { tasks, actions: None });
+    }
 
     // First, handle removal actions using simple parser (fast, no network)
     // Only use Ollama for removal if simple parser detects removal keywords
-    let transcript_lower = transcript.to_lowercase();
-    let has_removal_keywords = ["delete", "remove", "cancel", "drop", "forget", "scratch", "erase"]
-        .iter()
-        .any(|kw| transcript_lower.contains(kw));
-
-    if has_removal_keywords {
-        eprintln!("🔍 Checking for removal actions...");
-        let removal_texts = crate::ollama::get_removal_actions(&transcript);
+    if flowstate_core::ollama::has_removal_keywords(transcript) {
+        tracing::debug!("Checking for removal actions...");
+        let removal_texts = flowstate_core::ollama::get_removal_actions(transcript);
         for removal_text in removal_texts {
-            if let Ok(Some(deleted_task)) = crate::database::find_and_delete_task(&db, &removal_text) {
-                eprintln!("🗑️ Deleted task: {}", deleted_task.text);
+            if let Ok(Some(deleted_task)) = flowstate_core::database::find_and_delete_task(db, &removal_text) {
+                tracing::debug!("Deleted task: {}", deleted_task.text);
+                emit_task_deleted(app, deleted_task.id);
             }
         }
     }
 
     // Parse transcript for add/complete actions
-    eprintln!("📝 Parsing transcript for tasks...");
-    let ollama_enabled = crate::database::get_ollama_enabled(&db).unwrap_or(false);
-    let parsed_tasks = crate::ollama::parse_transcript(&transcript, ollama_enabled).await
-        .map_err(|e| format!("Failed to parse transcript: {}", e))?;
-    eprintln!("✅ Found {} tasks", parsed_tasks.len());
+    tracing::debug!("Parsing transcript for tasks...");
+    let parsed = parse_transcript(db, &settings, transcript).await?;
+    tracing::debug!("Found {} tasks with the {} parser", parsed.tasks.len(), parsed.parser.as_str());
+
+    emit_chunk_stage(app, ProcessingStage::Applying, Some(heard), chunk);
+    let timer = parsed.timer;
+    if let Some(command) = timer {
+        apply_timer_command(app, db, command)?;
+    }
+    let applied = apply_parsed_tasks(app, db, parsed);
+
+    if !applied.tasks.is_empty() || timer.is_some() {
+        flowstate_core::earcon::play(flowstate_core::earcon::Earcon::Success);
+    }
+
+    Ok(applied)
+}
+
+/// Apply a transcript in action mode: after "command:" it must be a voice
+/// command or an add/complete/delete, and anything else is added as one task
+/// as it was said. Neither goes through the parser, so no actions are kept.
+async fn action_mode_transcript<R: Runtime>(
+    app: &AppHandle<R>,
+    db: &Database,
+    settings: &flowstate_core::settings::Settings,
+    transcript: &str,
+) -> Result<Vec<TaskResponse>, String> {
+    use flowstate_core::action_mode::{TaskCommand, Utterance};
+
+    let command = match flowstate_core::action_mode::classify(transcript) {
+        Utterance::Dictation(text) => {
+            emit_stage(app, ProcessingStage::Applying, Some(transcript));
+            let added = add_voice_tasks(app, db, settings, &[(text.to_string(), None)])?;
+            flowstate_core::earcon::play(flowstate_core::earcon::Earcon::Success);
+            return Ok(added);
+        }
+        Utterance::Command(command) => command,
+    };
+    if let Some(result) = voice_command(app, db, settings, command).await {
+        return result;
+    }
+    let tasks = match flowstate_core::action_mode::task_command(command) {
+        Some(TaskCommand::Add(text)) => add_voice_tasks(app, db, settings, &[(text, None)])?,
+        Some(TaskCommand::Complete(text)) => complete_by_voice(app, db, &text, None)
+            .map_err(|_| flowstate_core::i18n::tf("error.no_matching_task", &[("text", &text)]))?
+            .into_iter()
+            .collect(),
+        Some(TaskCommand::Delete(text)) => {
+            let task = flowstate_core::database::find_and_delete_task(db, &text)
+                .map_err(|e| e.to_string())?
+                .ok_or_else(|| flowstate_core::i18n::tf("error.no_matching_task", &[("text", &text)]))?;
+            emit_task_deleted(app, task.id);
+            Vec::new()
+        }
+        None => return Err(flowstate_core::i18n::tf("error.unknown_command", &[("command", &command)])),
+    };
+    flowstate_core::earcon::play(flowstate_core::earcon::Earcon::Success);
+    Ok(tasks)
+}
+
+/// Add tasks captured by voice, to the inbox when it's on, and announce them
+fn add_voice_tasks<R: Runtime>(
+    app: &AppHandle<R>,
+    db: &Database,
+    settings: &flowstate_core::settings::Settings,
+    tasks: &[(String, Option<String>)],
+) -> Result<Vec<TaskResponse>, String> {
+    let added = flowstate_core::database::add_tasks_with_due(db, tasks, settings.voice_inbox).map_err(|e| e.to_string())?;
+    let added: Vec<TaskResponse> = added.into_iter().map(TaskResponse::from).collect();
+    for task in &added {
+        emit_task_changed(app, "task-added", task);
+    }
+    if settings.voice_inbox && !added.is_empty() {
+        emit_inbox_changed(app, db);
+    }
+    Ok(added)
+}
+
+/// Carry out the command a transcript is (a macro, habit, profile switch,
+/// reminder, timer, navigation, workload or GitHub capture). None when it
+/// isn't one and should go to the parser.
+async fn voice_command<R: Runtime>(
+    app: &AppHandle<R>,
+    db: &Database,
+    settings: &flowstate_core::settings::Settings,
+    transcript: &str,
+) -> Option<Result<Vec<TaskResponse>, String>> {
+    if let Some(result) = macro_command(app, db, transcript) {
+        return result;
+    }
+    if let Some(result) = log_habit_command(app, db, transcript) {
+        return result;
+    }
+    if let Some(result) = switch_profile_command(app, db, transcript) {
+        return result;
+    }
+    if let Some(result) = remind_command(app, db, transcript) {
+        return result;
+    }
+    if let Some(result) = timer_command(app, db, transcript) {
+        return result;
+    }
+    if let Some(result) = app_command(app, transcript) {
+        return result;
+    }
+    if let Some(result) = workload_command(app, db, transcript) {
+        return result;
+    }
+    capture_github_issue(app, db, &settings.github, transcript).await
+}
+
+/// Tick off the habit in a "log habit ..." transcript for today. None when the
+/// transcript isn't a habit command; no tasks are added either way.
+fn log_habit_command<R: Runtime>(app: &AppHandle<R>, db: &Database, transcript: &str) -> Option<Result<Vec<TaskResponse>, String>> {
+    let name = flowstate_core::habits::habit_command(transcript)?;
+    Some(flowstate_core::habits::log_by_name(db, &name).map(|habit| {
+        tracing::debug!("Logged habit {} (streak {})", habit.name, habit.streak);
+        emit_habits_changed(app);
+        flowstate_core::earcon::play(flowstate_core::earcon::Earcon::Success);
+        Vec::new()
+    }))
+}
+
+/// Run the macro whose phrase is the transcript, announcing what it changed.
+/// None when the transcript isn't a macro phrase.
+fn macro_command<R: Runtime>(app: &AppHandle<R>, db: &Database, transcript: &str) -> Option<Result<Vec<TaskResponse>, String>> {
+    let voice_macro = flowstate_core::macros::find_macro(db, transcript)?;
+    let run = match flowstate_core::macros::run(db, &voice_macro) {
+        Ok(run) => run,
+        Err(e) => return Some(Err(e)),
+    };
+    if run.archived > 0 {
+        let _ = app.emit("tasks-archived", run.archived);
+    }
+    for command in run.timer {
+        if let Err(e) = apply_timer_command(app, db, command) {
+            return Some(Err(e));
+        }
+    }
+    let added: Vec<TaskResponse> = run.added.into_iter().map(TaskResponse::from).collect();
+    for task in &added {
+        emit_task_changed(app, "task-added", task);
+    }
+    flowstate_core::earcon::play(flowstate_core::earcon::Earcon::Success);
+    Some(Ok(added))
+}
+
+/// Switch to the profile in a "switch to work mode" transcript. None when the
+/// transcript isn't a profile command.
+fn switch_profile_command<R: Runtime>(app: &AppHandle<R>, db: &Database, transcript: &str) -> Option<Result<Vec<TaskResponse>, String>> {
+    let name = flowstate_core::profiles::profile_command(transcript)?;
+    Some(apply_profile(app, db, Some(&name)).map(|_| {
+        flowstate_core::earcon::play(flowstate_core::earcon::Earcon::Success);
+        Vec::new()
+    }))
+}
+
+/// Add the task in a "remind me to call mom at 6pm" transcript with a reminder
+/// on it. None when the transcript isn't a reminder.
+fn remind_command<R: Runtime>(app: &AppHandle<R>, db: &Database, transcript: &str) -> Option<Result<Vec<TaskResponse>, String>> {
+    let (text, at) = flowstate_core::reminders::reminder_command(transcript)?;
+    let task = match flowstate_core::database::add_task(db, &text) {
+        Ok(task) => TaskResponse::from(task),
+        Err(e) => return Some(Err(e.to_string())),
+    };
+    emit_task_changed(app, "task-added", &task);
+    Some(flowstate_core::reminders::set_reminder_at(db, task.id, at).map(|_| {
+        emit_reminders_changed(app);
+        flowstate_core::earcon::play(flowstate_core::earcon::Earcon::Success);
+        vec![task]
+    }))
+}
+
+/// Carry out a "pause the timer" / "set the timer to 25 minutes" transcript.
+/// None when the transcript isn't a timer command.
+fn timer_command<R: Runtime>(app: &AppHandle<R>, db: &Database, transcript: &str) -> Option<Result<Vec<TaskResponse>, String>> {
+    let command = flowstate_core::timer_control::timer_command(transcript)?;
+    Some(apply_timer_command(app, db, command).map(|()| {
+        flowstate_core::earcon::play(flowstate_core::earcon::Earcon::Success);
+        Vec::new()
+    }))
+}
+
+/// Apply a spoken timer command; pausing and resuming are announced by the timer loop
+fn apply_timer_command<R: Runtime>(app: &AppHandle<R>, db: &Database, command: flowstate_core::timer_control::TimerCommand) -> Result<(), String> {
+    use flowstate_core::timer_control::TimerCommand;
+    tracing::info!("Timer command: {:?}", command);
+    match command {
+        TimerCommand::Reset => crate::timer::reset_timer()?,
+        TimerCommand::Pause | TimerCommand::Resume => {
+            crate::timer::set_paused_by_user(command == TimerCommand::Pause);
+            return Ok(());
+        }
+        TimerCommand::SetMinutes(minutes) => {
+            let settings = flowstate_core::settings::update(db, serde_json::json!({ "timer_duration_minutes": minutes }))?;
+            crate::timer::apply_timer_duration(settings.timer_duration_minutes)?;
+            let _ = app.emit("settings-changed", &settings);
+        }
+    }
+    // Let the main window pick up the new interval
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.emit("timer-reset", ());
+    }
+    Ok(())
+}
+
+/// Pass an "open settings" / "show completed" transcript on to the windows as an
+/// `app-command` event. None when the transcript isn't a navigation command.
+fn app_command<R: Runtime>(app: &AppHandle<R>, transcript: &str) -> Option<Result<Vec<TaskResponse>, String>> {
+    let command = flowstate_core::navigation::app_command(transcript)?;
+    tracing::info!("App command: {:?}", command);
+    // Voice can come in while the window is hidden in the tray
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+    let _ = app.emit("app-command", &command);
+    flowstate_core::earcon::play(flowstate_core::earcon::Earcon::Success);
+    Some(Ok(Vec::new()))
+}
+
+/// Answer "what's my workload" with a notification and a `workload` event.
+/// None when the transcript doesn't ask for it.
+fn workload_command<R: Runtime>(app: &AppHandle<R>, db: &Database, transcript: &str) -> Option<Result<Vec<TaskResponse>, String>> {
+    use tauri_plugin_notification::NotificationExt;
+
+    if !flowstate_core::estimates::workload_command(transcript) {
+        return None;
+    }
+    let capacity = flowstate_core::settings::load(db).workload.capacity_minutes;
+    Some(flowstate_core::estimates::workload(db, capacity).map(|workload| {
+        if let Err(e) = app
+            .notification()
+            .builder()
+            .title("FlowState")
+            .body(flowstate_core::estimates::summary(&workload))
+            .show()
+        {
+            tracing::warn!("Failed to show notification: {}", e);
+        }
+        let _ = app.emit("workload", &workload);
+        flowstate_core::earcon::play(flowstate_core::earcon::Earcon::Success);
+        Vec::new()
+    }))
+}
+
+/// Switch profiles and let the timer and the windows pick up the change
+fn apply_profile<R: Runtime>(app: &AppHandle<R>, db: &Database, name: Option<&str>) -> Result<flowstate_core::settings::Settings, String> {
+    let settings = flowstate_core::profiles::switch_profile(db, name)?;
+    // The profile's working hours take effect on the next timer check
+    crate::timer::notify_timer_changed();
+    let _ = app.emit("settings-changed", &settings);
+    let _ = app.emit("profile-changed", ProfileChangedPayload {
+        name: settings.profiles.active.clone(),
+        language: settings.profile_language().map(str::to_string),
+    });
+    Ok(settings)
+}
 
-    // Update database with parsed tasks
+/// File a "github: ..." transcript as an issue and add it as a task linked to
+/// the issue. None when GitHub capture is off or the transcript isn't a command.
+/// The task is added even if the issue fails, so the capture isn't lost.
+async fn capture_github_issue<R: Runtime>(
+    app: &AppHandle<R>,
+    db: &Database,
+    config: &flowstate_core::settings::GithubSettings,
+    transcript: &str,
+) -> Option<Result<Vec<TaskResponse>, String>> {
+    let title = flowstate_core::github::issue_title(transcript).filter(|_| config.enabled)?;
+    let issue = flowstate_core::github::create_issue(config, &title).await;
+    let task = match flowstate_core::database::add_task(db, &title) {
+        Ok(task) => task,
+        Err(e) => return Some(Err(e.to_string())),
+    };
+    let issue_url = match issue {
+        Ok(url) => url,
+        Err(e) => {
+            emit_task_changed(app, "task-added", &TaskResponse::from(task));
+            return Some(Err(flowstate_core::i18n::tf("error.github_issue", &[("error", &e)])));
+        }
+    };
+    if let Err(e) = flowstate_core::database::set_task_issue_url(db, task.id, &issue_url) {
+        tracing::warn!("Failed to store issue URL: {}", e);
+    }
+    let task = TaskResponse {
+        issue_url: Some(issue_url),
+        ..TaskResponse::from(task)
+    };
+    emit_task_changed(app, "task-added", &task);
+    flowstate_core::earcon::play(flowstate_core::earcon::Earcon::Success);
+    Some(Ok(vec![task]))
+}
+
+/// Complete the task a spoken phrase names. When several fit, none is
+/// completed: the question goes out as `disambiguation-needed` and waits for
+/// `resolve_disambiguation`, and this returns None.
+fn complete_by_voice<R: Runtime>(
+    app: &AppHandle<R>,
+    db: &Database,
+    phrase: &str,
+    parsed_by: Option<flowstate_core::parser::ParserKind>,
+) -> Result<Option<TaskResponse>, String> {
+    use flowstate_core::disambiguation::{Completion, DisambiguationState};
+
+    match app.state::<DisambiguationState>().complete(db, phrase)? {
+        Completion::Completed(task) => {
+            let task = TaskResponse { parsed_by, ..TaskResponse::from(task) };
+            emit_task_changed(app, "task-completed", &task);
+            Ok(Some(task))
+        }
+        Completion::NeedsDisambiguation(question) => {
+            let _ = app.emit("disambiguation-needed", &question);
+            Ok(None)
+        }
+    }
+}
+
+/// Add new tasks and complete existing ones, emitting an event for each change
+fn apply_parsed_tasks<R: Runtime>(
+    app: &AppHandle<R>,
+    db: &Database,
+    parsed: flowstate_core::parser::ParsedMemo,
+) -> AppliedTranscript {
+    let parsed_by = Some(parsed.parser);
+    let mut actions = flowstate_core::history::actions_from(&parsed);
     let mut results = Vec::new();
-    for task in parsed_tasks {
+    let mut new_tasks = Vec::new();
+    let mut new_actions = Vec::new();
+    for (task, action) in parsed.tasks.into_iter().zip(actions.iter_mut()) {
         if task.completed {
-            // Mark existing task as completed or create new one
-            if let Ok(existing) = crate::database::find_and_complete_task(&db, &task.text) {
-                results.push(TaskResponse {
-                    id: existing.id,
-                    text: existing.text,
-                    completed: existing.completed,
-                    created_at: existing.created_at,
-                    completed_at: existing.completed_at,
-                });
+            // Mark existing task as completed, unless it's unclear which
+            if let Ok(Some(existing)) = complete_by_voice(app, db, &task.text, parsed_by) {
+                action.task_id = Some(existing.id);
+                results.push(existing);
             }
         } else {
-            // Add new task
-            if let Ok(new_task) = crate::database::add_task(&db, &task.text) {
-                results.push(TaskResponse {
-                    id: new_task.id,
-                    text: new_task.text,
-                    completed: new_task.completed,
-                    created_at: new_task.created_at,
-                    completed_at: new_task.completed_at,
-                });
-            }
+            new_tasks.push((task.text, task.due_at));
+            new_actions.push(action);
         }
     }
 
-    Ok(results)
+    // New tasks from one utterance go in together
+    let inbox = flowstate_core::settings::load(db).voice_inbox;
+    match flowstate_core::database::add_tasks_with_due(db, &new_tasks, inbox) {
+        Ok(added) => {
+            let any_added = !added.is_empty();
+            for (task, action) in added.into_iter().zip(new_actions) {
+                action.task_id = Some(task.id);
+                let task = TaskResponse { parsed_by, ..TaskResponse::from(task) };
+                emit_task_changed(app, "task-added", &task);
+                results.push(task);
+            }
+            if inbox && any_added {
+                emit_inbox_changed(app, db);
+            }
+        }
+        Err(e) => tracing::error!("Failed to add tasks: {}", e),
+    }
+    AppliedTranscript { tasks: results, actions: Some(actions) }
 }
 
 // Windows auto-start functionality
@@ -532,10 +1967,45 @@ mod autostart {
 }
 
 #[tauri::command]
-pub fn get_autostart_enabled() -> Result<bool, String> {
+pub async fn check_for_updates() -> Result<Option<flowstate_core::updates::UpdateInfo>, FlowStateError> {
+    Ok(flowstate_core::updates::check_for_updates().await?)
+}
+
+/// Install the latest release through the updater plugin, then quit so the
+/// installer can replace the app. The release is looked up again here and its
+/// installer only runs if the signature matches the build's key.
+#[tauri::command]
+pub async fn install_update(app: AppHandle) -> Result<(), FlowStateError> {
+    use tauri_plugin_updater::UpdaterExt;
+
+    let pubkey = flowstate_core::updates::pubkey().ok_or_else(|| flowstate_core::i18n::t("error.update_unsigned"))?;
+    let updater = app.updater_builder().pubkey(pubkey).build().map_err(|e| e.to_string())?;
+    let update = updater
+        .check()
+        .await
+        .map_err(|e| flowstate_core::i18n::tf("error.check_updates", &[("error", &e)]))?
+        .ok_or_else(|| flowstate_core::i18n::t("updates.up_to_date"))?;
+    update
+        .download_and_install(|_, _| {}, || {})
+        .await
+        .map_err(|e| flowstate_core::i18n::tf("error.update_install", &[("error", &e)]))?;
+    tracing::info!("Installed FlowState {}", update.version);
+    crate::shutdown::quit(app).await;
+    Ok(())
+}
+
+/// Quit for real (closing the window may only hide it), after in-flight recordings finish
+#[tauri::command]
+pub async fn exit_app(app: AppHandle) -> Result<(), FlowStateError> {
+    crate::shutdown::quit(app).await;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_autostart_enabled() -> Result<bool, FlowStateError> {
     #[cfg(target_os = "windows")]
     {
-        autostart::get_autostart()
+        Ok(autostart::get_autostart()?)
     }
     #[cfg(not(target_os = "windows"))]
     {
@@ -544,30 +2014,382 @@ pub fn get_autostart_enabled() -> Result<bool, String> {
 }
 
 #[tauri::command]
-pub fn set_autostart_enabled(enabled: bool) -> Result<(), String> {
+pub fn set_autostart_enabled(enabled: bool) -> Result<(), FlowStateError> {
     #[cfg(target_os = "windows")]
     {
         let exe_path = std::env::current_exe()
             .map_err(|e| format!("Failed to get executable path: {}", e))?
             .to_string_lossy()
             .to_string();
-        autostart::set_autostart(enabled, &exe_path)
+        Ok(autostart::set_autostart(enabled, &exe_path)?)
     }
     #[cfg(not(target_os = "windows"))]
     {
         let _ = enabled;
-        Err("Auto-start is only supported on Windows".to_string())
+        Err(flowstate_core::i18n::t("error.autostart_windows_only").into())
+    }
+}
+
+#[tauri::command]
+pub fn play_earcon(kind: String) -> Result<(), FlowStateError> {
+    let earcon = flowstate_core::earcon::Earcon::from_str(&kind)
+        .ok_or_else(|| flowstate_core::i18n::tf("error.unknown_earcon", &[("earcon", &kind)]))?;
+    flowstate_core::earcon::play(earcon);
+    Ok(())
+}
+
+/// Start the wake-word listener, wiring detections to the frontend recording events
+pub fn start_wake_word_listener(app: &AppHandle) -> Result<flowstate_core::wakeword::WakeWordListener, FlowStateError> {
+    use flowstate_core::wakeword::{WakeWordEvent, WakeWordListener};
+
+    let phrase = flowstate_core::settings::load(&app.state::<Database>()).wake_word_phrase;
+
+    // Dedicated cache so the tiny wake-word model doesn't evict the dictation model
+    let wake_cache = WhisperCache::new();
+    let transcribe = Box::new(move |samples: &[f32]| {
+        let engine = flowstate_core::stt::engine(&wake_cache, WhisperModelSize::Tiny.name())?;
+        // Auto-detect so the wake phrase is recognized regardless of dictation language
+        engine.transcribe(samples, None)
+    });
+
+    let app_for_events = app.clone();
+    let on_event = Box::new(move |event: WakeWordEvent| {
+        if let Some(window) = app_for_events.get_webview_window("main") {
+            match event {
+                WakeWordEvent::Detected => {
+                    let _ = window.show();
+                    let _ = window.emit("start-recording", ());
+                }
+                WakeWordEvent::SpeechEnded => {
+                    let _ = window.emit("stop-recording", ());
+                }
+            }
+        }
+    });
+
+    WakeWordListener::start(&phrase, transcribe, on_event)
+}
+
+/// Start, restart or stop the wake-word listener to match the settings
+fn sync_wake_word_listener(app: &AppHandle, settings: &flowstate_core::settings::Settings) -> Result<(), FlowStateError> {
+    let wake_word = app.state::<flowstate_core::wakeword::WakeWordState>();
+    let mut listener = wake_word.listener.lock().map_err(|e| e.to_string())?;
+    // Dropping the listener stops the capture thread
+    *listener = None;
+    if settings.wake_word_enabled {
+        *listener = Some(start_wake_word_listener(app)?);
+    }
+    Ok(())
+}
+
+/// Start syncing tasks with a Markdown note, turning edits made there into task events
+pub fn start_markdown_sync(
+    app: &AppHandle,
+    config: &flowstate_core::settings::MarkdownSyncSettings,
+) -> Result<flowstate_core::sync::markdown::MarkdownSync, String> {
+    use flowstate_core::sync::markdown::{Imported, MarkdownSync};
+
+    let app_for_events = app.clone();
+    let on_import = Box::new(move |imported: Imported| match imported {
+        Imported::Added(task) => emit_task_changed(&app_for_events, "task-added", &TaskResponse::from(task)),
+        Imported::Toggled(task) => {
+            let event = if task.completed { "task-completed" } else { "task-updated" };
+            emit_task_changed(&app_for_events, event, &TaskResponse::from(task));
+        }
+    });
+    MarkdownSync::start(&app.state::<Database>(), config, on_import)
+}
+
+/// Start, restart or stop the Markdown sync to match the settings
+fn restart_markdown_sync(app: &AppHandle, settings: &flowstate_core::settings::Settings) -> Result<(), String> {
+    let state = app.state::<flowstate_core::sync::markdown::MarkdownSyncState>();
+    let mut sync = state.sync.lock().map_err(|e| e.to_string())?;
+    // Dropping the sync stops its thread
+    *sync = None;
+    if settings.markdown_sync.enabled {
+        *sync = Some(start_markdown_sync(app, &settings.markdown_sync)?);
+    }
+    Ok(())
+}
+
+/// Start, restart or stop the calendar feed to match the settings
+fn restart_calendar_export(app: &AppHandle, settings: &flowstate_core::settings::Settings) -> Result<(), String> {
+    use flowstate_core::sync::calendar::{CalendarExport, CalendarExportState};
+
+    let state = app.state::<CalendarExportState>();
+    let mut export = state.export.lock().map_err(|e| e.to_string())?;
+    *export = None;
+    if settings.calendar_export.enabled {
+        *export = Some(CalendarExport::start(&app.state::<Database>(), &settings.calendar_export)?);
+    }
+    Ok(())
+}
+
+/// Start the Google Calendar push
+pub fn start_google_calendar_sync(
+    app: &AppHandle,
+    config: &flowstate_core::settings::GoogleCalendarSettings,
+) -> Result<flowstate_core::sync::google_calendar::GoogleCalendarSync, String> {
+    use flowstate_core::sync::google_calendar::GoogleCalendarSync;
+
+    // Failures are already logged by the push thread
+    GoogleCalendarSync::start(&app.state::<Database>(), config, Box::new(|_: String| {}))
+}
+
+/// Start, restart or stop the Google Calendar push to match the settings
+fn restart_google_calendar_sync(app: &AppHandle, settings: &flowstate_core::settings::Settings) -> Result<(), String> {
+    let state = app.state::<flowstate_core::sync::google_calendar::GoogleCalendarSyncState>();
+    let mut sync = state.sync.lock().map_err(|e| e.to_string())?;
+    // Dropping the sync stops its thread
+    *sync = None;
+    if settings.google_calendar.enabled {
+        *sync = Some(start_google_calendar_sync(app, &settings.google_calendar)?);
+    }
+    Ok(())
+}
+
+/// Sign in to Google in the browser and keep the refresh token. The consent
+/// page's URL goes out as a "google-sign-in" event for the window to open
+#[tauri::command]
+pub async fn connect_google_calendar(
+    app: AppHandle,
+    client_id: String,
+    client_secret: String,
+) -> Result<flowstate_core::settings::Settings, FlowStateError> {
+    let app_for_url = app.clone();
+    let refresh_token = flowstate_core::sync::google_calendar::connect(&client_id, &client_secret, move |url| {
+        app_for_url.emit("google-sign-in", url).map_err(|e| e.to_string())
+    })
+    .await?;
+
+    let db = app.state::<Database>();
+    let mut settings = flowstate_core::settings::load(&db);
+    settings.google_calendar.client_id = client_id.trim().to_string();
+    settings.google_calendar.client_secret = client_secret.trim().to_string();
+    settings.google_calendar.refresh_token = refresh_token;
+    settings.google_calendar.enabled = true;
+    flowstate_core::settings::save(&db, &settings)?;
+    let _ = app.emit("settings-changed", &settings);
+    restart_google_calendar_sync(&app, &settings)?;
+    Ok(settings)
+}
+
+/// Start the scheduled Todoist sync, turning tasks it changes into task events
+pub fn start_todoist_sync(
+    app: &AppHandle,
+    config: &flowstate_core::settings::TodoistSettings,
+) -> Result<flowstate_core::sync::todoist::TodoistSync, String> {
+    use flowstate_core::sync::todoist::{SyncEvent, TodoistSync};
+
+    let app_for_events = app.clone();
+    let on_event = Box::new(move |event: SyncEvent| match event {
+        SyncEvent::TaskChanged(task) => {
+            let event = if task.completed { "task-completed" } else { "task-updated" };
+            emit_task_changed(&app_for_events, event, &TaskResponse::from(task));
+        }
+        // Already logged by the sync thread
+        SyncEvent::Failed(_) => {}
+    });
+    TodoistSync::start(&app.state::<Database>(), config, on_event)
+}
+
+/// Start, restart or stop the Todoist sync to match the settings
+fn restart_todoist_sync(app: &AppHandle, settings: &flowstate_core::settings::Settings) -> Result<(), String> {
+    let state = app.state::<flowstate_core::sync::todoist::TodoistSyncState>();
+    let mut sync = state.sync.lock().map_err(|e| e.to_string())?;
+    // Dropping the sync stops its thread
+    *sync = None;
+    if settings.todoist.enabled {
+        *sync = Some(start_todoist_sync(app, &settings.todoist)?);
+    }
+    Ok(())
+}
+
+/// Start, restart or stop the MQTT publisher to match the settings
+fn restart_mqtt_publisher(app: &AppHandle, settings: &flowstate_core::settings::Settings) -> Result<(), String> {
+    let state = app.state::<flowstate_core::mqtt::MqttState>();
+    let mut publisher = state.publisher.lock().map_err(|e| e.to_string())?;
+    // Dropping the publisher disconnects it
+    *publisher = None;
+    if settings.mqtt.enabled {
+        *publisher = Some(flowstate_core::mqtt::MqttPublisher::start(&settings.mqtt)?);
+    }
+    Ok(())
+}
+
+/// Publish timer, recording and task state every second while MQTT is on
+pub async fn run_mqtt_status(app: AppHandle) {
+    use flowstate_core::mqtt::{MqttState, Status, TimerState};
+    use std::sync::atomic::Ordering;
+
+    let mut interval = tokio::time::interval(std::time::Duration::from_secs(1));
+    loop {
+        interval.tick().await;
+        let state = app.state::<MqttState>();
+        let Ok(publisher) = state.publisher.lock() else {
+            continue;
+        };
+        let Some(publisher) = publisher.as_ref() else {
+            continue;
+        };
+        let (timer, remaining_secs) = match crate::timer::get_break_remaining() {
+            Some(remaining) => (TimerState::Break, remaining),
+            None if crate::timer::is_timer_paused().unwrap_or(false) => {
+                (TimerState::Paused, crate::timer::get_remaining_time().unwrap_or(0))
+            }
+            None => (TimerState::Running, crate::timer::get_remaining_time().unwrap_or(0)),
+        };
+        publisher.publish(&Status {
+            recording: state.recording.load(Ordering::Relaxed),
+            timer,
+            remaining_secs,
+            open_tasks: flowstate_core::database::count_open_tasks(&app.state::<Database>()).unwrap_or(0),
+        });
+    }
+}
+
+/// Recording started or stopped in the frontend (published over MQTT)
+#[tauri::command]
+pub fn set_recording_state(recording: bool, state: State<flowstate_core::mqtt::MqttState>) {
+    state.recording.store(recording, std::sync::atomic::Ordering::Relaxed);
+}
+
+/// Start the clipboard watcher, announcing each captured task
+pub fn start_clipboard_watcher(
+    app: &AppHandle,
+    config: &flowstate_core::settings::ClipboardSettings,
+) -> Result<flowstate_core::clipboard::ClipboardWatcher, String> {
+    use tauri_plugin_notification::NotificationExt;
+
+    let app_for_events = app.clone();
+    let on_capture = Box::new(move |task: flowstate_core::database::Task| {
+        if let Err(e) = app_for_events
+            .notification()
+            .builder()
+            .title("FlowState")
+            .body(flowstate_core::i18n::tf("clipboard.added", &[("task", &task.text)]))
+            .show()
+        {
+            tracing::warn!("Failed to show notification: {}", e);
+        }
+        emit_task_changed(&app_for_events, "task-added", &TaskResponse::from(task));
+    });
+    flowstate_core::clipboard::ClipboardWatcher::start(&app.state::<Database>(), config, on_capture)
+}
+
+/// Start, restart or stop the clipboard watcher to match the settings
+fn restart_clipboard_watcher(app: &AppHandle, settings: &flowstate_core::settings::Settings) -> Result<(), String> {
+    let state = app.state::<flowstate_core::clipboard::ClipboardWatcherState>();
+    let mut watcher = state.watcher.lock().map_err(|e| e.to_string())?;
+    // Dropping the watcher stops its thread
+    *watcher = None;
+    if settings.clipboard.enabled {
+        *watcher = Some(start_clipboard_watcher(app, &settings.clipboard)?);
+    }
+    Ok(())
+}
+
+/// Start the Telegram bridge, running its messages through the voice pipeline
+pub fn start_telegram_bridge(
+    app: &AppHandle,
+    config: &flowstate_core::settings::TelegramSettings,
+) -> Result<flowstate_core::telegram::TelegramBridge, String> {
+    use flowstate_core::telegram::{Incoming, TelegramBridge};
+
+    let app = app.clone();
+    let on_message = Box::new(move |message: Incoming| {
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            if let Err(e) = apply_remote_message(&app, message).await {
+                tracing::warn!("Failed to apply Telegram message: {}", e);
+            }
+        });
+    });
+    TelegramBridge::start(config, on_message)
+}
+
+// Transcribe a voice note if needed, then apply it like a local memo
+async fn apply_remote_message(app: &AppHandle, message: flowstate_core::telegram::Incoming) -> Result<(), String> {
+    let _processing = crate::shutdown::begin_processing()?;
+    let processing = app.state::<ProcessingState>();
+    let _turn = processing.begin("telegram", None).await?;
+    let db = app.state::<Database>();
+    match message {
+        flowstate_core::telegram::Incoming::Text(text) => {
+            tracing::debug!("Telegram message: \"{}\"", text);
+            apply_transcript(app, &db, &text, &text, None).await.map(|_| ())
+        }
+        flowstate_core::telegram::Incoming::Voice(samples) => {
+            let settings = flowstate_core::settings::load(&db);
+            let engine = flowstate_core::stt::engine(&app.state::<WhisperCache>(), &settings.selected_model)?;
+            let result = transcribe_and_apply(app, &db, "telegram", engine, samples, None).await?;
+            tracing::debug!("Telegram voice note: \"{}\"", result.transcript);
+            record_transcription(app, &db, "telegram", &result.transcript, result.actions.as_deref());
+            Ok(())
+        }
+        // Already logged by the bridge thread
+        flowstate_core::telegram::Incoming::Failed(_) => Ok(()),
+    }
+}
+
+/// Start, restart or stop the Telegram bridge to match the settings
+fn restart_telegram_bridge(app: &AppHandle, settings: &flowstate_core::settings::Settings) -> Result<(), String> {
+    let state = app.state::<flowstate_core::telegram::TelegramBridgeState>();
+    let mut bridge = state.bridge.lock().map_err(|e| e.to_string())?;
+    // Dropping the bridge stops its thread
+    *bridge = None;
+    if settings.telegram.enabled {
+        *bridge = Some(start_telegram_bridge(app, &settings.telegram)?);
     }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn preview_alert_sound(sound: String) -> Result<(), FlowStateError> {
+    let sound = flowstate_core::earcon::AlertSound::from_setting(&sound)
+        .ok_or_else(|| flowstate_core::i18n::tf("error.invalid_alert_sound", &[("sound", &sound)]))?;
+    flowstate_core::earcon::play_alert_sound(&sound);
+    Ok(())
+}
+
+/// Whether timer alerts are currently held back by do-not-disturb or a full-screen app
+#[tauri::command]
+pub fn get_dnd_state(db: State<Database>) -> flowstate_core::dnd::DndState {
+    flowstate_core::dnd::get_dnd_state(&flowstate_core::settings::load(&db).dnd)
+}
+
+/// Microphone, models, Ollama, free disk space and database integrity, for "Run checks"
+#[tauri::command]
+pub async fn run_diagnostics(db: State<'_, Database>) -> Result<flowstate_core::diagnostics::DiagnosticsReport, FlowStateError> {
+    let settings = flowstate_core::settings::load(&db);
+    Ok(flowstate_core::diagnostics::run(&db, &settings.selected_model, settings.ollama_enabled, &settings.ollama).await)
+}
+
+/// Check (and load) the selected model, then publish the startup report with
+/// the checks done in setup
+pub fn finish_startup_report(app: &AppHandle, mut checks: Vec<flowstate_core::diagnostics::DiagnosticCheck>) {
+    let settings = flowstate_core::settings::load(&app.state::<Database>());
+    let whisper_cache = app.state::<WhisperCache>();
+    checks.push(flowstate_core::startup::check_model(&ModelStore::open_or_temp(), &settings.selected_model, |model| {
+        flowstate_core::crash::catch_panic(|| whisper_cache.get_or_create(model).map(drop).map_err(|e| e.to_string()))
+    }));
+
+    let report = flowstate_core::startup::report(checks);
+    *app.state::<flowstate_core::startup::StartupReportState>().report.lock().unwrap() = Some(report.clone());
+    let _ = app.emit(flowstate_core::startup::EVENT, report);
 }
 
+/// The startup report, once the checks are done
 #[tauri::command]
-pub fn get_ollama_enabled(db: State<Database>) -> Result<bool, String> {
-    crate::database::get_ollama_enabled(&db)
-        .map_err(|e: rusqlite::Error| e.to_string())
+pub fn get_startup_report(state: State<flowstate_core::startup::StartupReportState>) -> Option<flowstate_core::diagnostics::DiagnosticsReport> {
+    state.report.lock().unwrap().clone()
 }
 
+/// Version, platform and the tail of the log file, for "Copy diagnostics"
 #[tauri::command]
-pub fn set_ollama_enabled(enabled: bool, db: State<Database>) -> Result<(), String> {
-    crate::database::set_ollama_enabled(&db, enabled)
-        .map_err(|e: rusqlite::Error| e.to_string())
+pub fn get_recent_logs(max_lines: Option<usize>) -> Result<String, FlowStateError> {
+    Ok(flowstate_core::logging::diagnostics_report(max_lines.unwrap_or(500)))
 }
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,588 @@
+// End-to-end tests for the voice pipeline: canned transcripts go through
+// `process_samples_with` on a mock app with an in-memory database, the same way
+// a recording does once Whisper has heard it.
+
+use super::*;
+use flowstate_core::database::{TaskQuery, TaskStatus};
+use flowstate_core::parser::{ParsedMemo, ParserChain, ParserKind, RuleBased, TranscriptParser};
+use futures_util::future::BoxFuture;
+use std::collections::VecDeque;
+use std::sync::{mpsc, Arc, Mutex};
+use std::time::Duration;
+use tauri::test::{mock_app, MockRuntime};
+use tauri::{App, Listener};
+
+/// Hands out canned transcripts in order, one per transcribed piece; an
+/// empty transcript once they run out, like silence
+struct CannedTranscriber {
+    transcripts: Mutex<VecDeque<Result<String, String>>>,
+}
+
+impl CannedTranscriber {
+    fn new(transcripts: &[&str]) -> Arc<Self> {
+        Self::with_results(transcripts.iter().map(|text| Ok(text.to_string())).collect())
+    }
+
+    fn with_results(results: Vec<Result<String, String>>) -> Arc<Self> {
+        Arc::new(Self {
+            transcripts: Mutex::new(results.into()),
+        })
+    }
+}
+
+impl SpeechToText for CannedTranscriber {
+    fn transcribe(&self, _samples: &[f32], _language: Option<&str>) -> Result<String, String> {
+        self.transcripts.lock().unwrap().pop_front().unwrap_or_else(|| Ok(String::new()))
+    }
+}
+
+fn test_app() -> App<MockRuntime> {
+    flowstate_core::earcon::set_muted(true);
+    let app = mock_app();
+    app.manage(Database::new_in_memory().expect("in-memory database"));
+    app.manage(flowstate_core::disambiguation::DisambiguationState::default());
+    app
+}
+
+/// Silence of the given length at 16kHz; the transcriber decides what was said
+fn recording(secs: usize) -> Vec<f32> {
+    vec![0.0; secs * 16000]
+}
+
+async fn say(app: &App<MockRuntime>, transcript: &str) -> VoiceProcessingResult {
+    process(app, CannedTranscriber::new(&[transcript]), recording(3))
+        .await
+        .expect("voice pipeline")
+}
+
+async fn process(
+    app: &App<MockRuntime>,
+    transcriber: Arc<CannedTranscriber>,
+    samples: Vec<f32>,
+) -> Result<VoiceProcessingResult, String> {
+    let db = app.state::<Database>();
+    process_samples_with(app.handle(), &db, transcriber, samples, Some("en".to_string())).await
+}
+
+fn tasks(app: &App<MockRuntime>, status: TaskStatus) -> Vec<TaskResponse> {
+    let query = TaskQuery { status, ..Default::default() };
+    get_tasks(Some(query), app.state()).expect("task query")
+}
+
+fn texts(tasks: &[TaskResponse]) -> Vec<&str> {
+    tasks.iter().map(|task| task.text.as_str()).collect()
+}
+
+#[tokio::test]
+async fn adds_a_task_for_each_part_of_a_memo() {
+    let app = test_app();
+    let result = say(&app, "Buy milk, call mom").await;
+
+    assert_eq!(result.transcript, "Buy milk, call mom");
+    assert_eq!(texts(&result.tasks), ["Buy milk", "Call mom"]);
+    let mut open: Vec<String> = tasks(&app, TaskStatus::Open).into_iter().map(|task| task.text).collect();
+    open.sort();
+    assert_eq!(open, ["Buy milk", "Call mom"]);
+}
+
+#[tokio::test]
+async fn keeps_a_spoken_estimate_with_its_task() {
+    let app = test_app();
+    let result = say(&app, "Write the report, about an hour").await;
+
+    assert_eq!(texts(&result.tasks), ["Write the report"]);
+    assert_eq!(result.tasks[0].estimate_minutes, Some(60));
+}
+
+#[tokio::test]
+async fn a_run_on_transcript_is_punctuated_before_the_rules_split_it() {
+    let app = test_app();
+    let result = say(&app, "buy milk then call mom also pick up the kids").await;
+
+    assert_eq!(texts(&result.tasks), ["Buy milk", "Call mom", "Pick up the kids"]);
+}
+
+#[test]
+fn punctuation_leaves_commands_and_punctuated_text_alone() {
+    assert_eq!(flowstate_core::punctuation::restore("Done with buy milk"), "Done with buy milk");
+    assert_eq!(flowstate_core::punctuation::restore("Call mom, then the bank"), "Call mom, then the bank");
+}
+
+#[tokio::test]
+async fn the_content_filter_masks_words_before_anything_is_stored() {
+    let app = test_app();
+    let db = app.state::<Database>();
+    flowstate_core::settings::update(&db, serde_json::json!({ "content_filter": { "enabled": true, "words": ["Acme Corp"] } })).unwrap();
+
+    let result = say(&app, "Email acme corp about the fucking invoice.").await;
+
+    assert_eq!(texts(&result.tasks), ["Email **** **** about the ******* invoice"]);
+    let note = flowstate_core::database::parsed_transcriptions_between(&db, None, None).unwrap().remove(0);
+    assert_eq!(note.transcript, "Email **** **** about the ******* invoice.");
+
+    let mut settings = flowstate_core::settings::load(&db).content_filter;
+    settings.mode = flowstate_core::content_filter::FilterMode::Drop;
+    assert_eq!(flowstate_core::content_filter::filter("Damn, call Acme Corp back", &settings), "call back");
+}
+
+#[tokio::test]
+async fn completes_a_task_by_voice() {
+    let app = test_app();
+    let task = add_task(app.handle().clone(), "Buy milk".to_string(), app.state()).unwrap();
+
+    let result = say(&app, "Done with buy milk").await;
+
+    assert_eq!(result.tasks.len(), 1);
+    assert_eq!(result.tasks[0].id, task.id);
+    assert!(result.tasks[0].completed);
+    assert!(tasks(&app, TaskStatus::Open).is_empty());
+}
+
+#[tokio::test]
+async fn asks_which_task_when_a_completion_fits_several() {
+    let app = test_app();
+    add_task(app.handle().clone(), "Write the report".to_string(), app.state()).unwrap();
+    add_task(app.handle().clone(), "Send report summary".to_string(), app.state()).unwrap();
+    add_task(app.handle().clone(), "Call mom".to_string(), app.state()).unwrap();
+
+    let result = say(&app, "Done with report").await;
+
+    assert!(result.tasks.is_empty());
+    assert_eq!(tasks(&app, TaskStatus::Open).len(), 3);
+    let question = result.needs_disambiguation.expect("a question");
+    let candidates: Vec<&str> = question.candidates.iter().map(|task| task.text.as_str()).collect();
+    assert_eq!(candidates, ["Send report summary", "Write the report"]);
+
+    let choice = Some(question.candidates[1].id);
+    let resolved = resolve_disambiguation(app.handle().clone(), choice, app.state(), app.state()).unwrap();
+    assert_eq!(resolved.completed.expect("completed").text, "Write the report");
+    assert!(resolved.next.is_none());
+    assert!(resolve_disambiguation(app.handle().clone(), None, app.state(), app.state()).is_err());
+
+    // One task left to fit, so it's completed straight away
+    let result = say(&app, "Done with report").await;
+    assert_eq!(texts(&result.tasks), ["Send report summary"]);
+    assert!(result.needs_disambiguation.is_none());
+}
+
+#[tokio::test]
+async fn deletes_a_task_by_voice() {
+    let app = test_app();
+    add_task(app.handle().clone(), "Buy milk".to_string(), app.state()).unwrap();
+    add_task(app.handle().clone(), "Call mom".to_string(), app.state()).unwrap();
+
+    let result = say(&app, "Delete buy milk").await;
+
+    assert!(result.tasks.is_empty());
+    assert_eq!(texts(&tasks(&app, TaskStatus::All)), ["Call mom"]);
+}
+
+#[tokio::test]
+async fn sets_a_reminder_on_the_task() {
+    let app = test_app();
+    let result = say(&app, "Remind me to call mom in 20 minutes").await;
+
+    assert_eq!(texts(&result.tasks), ["Call mom"]);
+    let reminders = flowstate_core::reminders::get_reminders(&app.state::<Database>()).unwrap();
+    assert_eq!(reminders.len(), 1);
+    assert_eq!(reminders[0].task_id, result.tasks[0].id);
+}
+
+#[tokio::test]
+async fn logs_a_habit_without_adding_a_task() {
+    let app = test_app();
+    flowstate_core::habits::add_habit(&app.state::<Database>(), "Meditate", "daily").unwrap();
+
+    let result = say(&app, "Log habit meditate").await;
+
+    assert!(result.tasks.is_empty());
+    assert!(tasks(&app, TaskStatus::All).is_empty());
+    let habits = flowstate_core::habits::get_habits(&app.state::<Database>()).unwrap();
+    assert!(habits[0].done_today);
+}
+
+#[tokio::test]
+async fn a_macro_phrase_runs_the_macro_instead_of_the_parser() {
+    use flowstate_core::macros::MacroAction;
+
+    let app = test_app();
+    let db = app.state::<Database>();
+    let done = flowstate_core::database::add_task(&db, "Old task").unwrap();
+    flowstate_core::database::complete_tasks(&db, &[done.id]).unwrap();
+    let actions = vec![
+        MacroAction::ArchiveCompleted,
+        MacroAction::AddTask { text: "Check the build".to_string() },
+        MacroAction::AddTask { text: "Review PRs".to_string() },
+    ];
+    flowstate_core::macros::add_macro(&db, "Standup", actions).unwrap();
+
+    let result = say(&app, "Standup.").await;
+
+    assert_eq!(texts(&result.tasks), ["Check the build", "Review PRs"]);
+    assert_eq!(texts(&tasks(&app, TaskStatus::Recent)), ["Check the build", "Review PRs"]);
+    assert!(flowstate_core::macros::add_macro(&db, "standup", vec![MacroAction::ArchiveCompleted]).is_err());
+}
+
+#[tokio::test]
+async fn a_due_date_in_a_memo_becomes_the_tasks_due_at() {
+    let app = test_app();
+
+    let result = say(&app, "Call the dentist tomorrow").await;
+
+    assert_eq!(texts(&result.tasks), ["Call the dentist"]);
+    let due = result.tasks[0].due_at.as_deref().and_then(flowstate_core::dates::from_db).expect("due date");
+    let tomorrow = chrono::Local::now().date_naive().succ_opt().unwrap();
+    assert_eq!(due, tomorrow.and_hms_opt(23, 59, 0).unwrap());
+}
+
+#[tokio::test]
+async fn voice_tasks_wait_in_the_inbox_until_kept() {
+    let app = test_app();
+    let db = app.state::<Database>();
+    flowstate_core::settings::update(&db, serde_json::json!({ "voice_inbox": true })).unwrap();
+
+    let result = say(&app, "Buy milk, call mom").await;
+
+    assert!(result.tasks.iter().all(|task| task.inbox));
+    assert!(tasks(&app, TaskStatus::Open).is_empty());
+    assert_eq!(flowstate_core::inbox::count(&db), 2);
+    flowstate_core::inbox::commit(&db, &[result.tasks[0].id]).unwrap();
+    flowstate_core::inbox::schedule(&db, result.tasks[1].id, "tomorrow").unwrap();
+    let mut open: Vec<String> = tasks(&app, TaskStatus::Open).into_iter().map(|task| task.text).collect();
+    open.sort();
+    assert_eq!(open, ["Buy milk", "Call mom"]);
+    assert_eq!(flowstate_core::inbox::count(&db), 0);
+}
+
+#[tokio::test]
+async fn action_mode_only_runs_commands_after_the_prefix() {
+    let app = test_app();
+    let db = app.state::<Database>();
+    flowstate_core::settings::update(&db, serde_json::json!({ "action_mode": true })).unwrap();
+
+    let result = say(&app, "Buy milk, call mom and delete the old draft.").await;
+    assert_eq!(texts(&result.tasks), ["Buy milk, call mom and delete the old draft"]);
+
+    say(&app, "Command: add call the bank").await;
+    let result = say(&app, "Command, mark call the bank as done.").await;
+    assert_eq!(texts(&result.tasks), ["call the bank"]);
+    assert!(result.tasks[0].completed);
+    say(&app, "Command: delete buy milk").await;
+    assert!(tasks(&app, TaskStatus::Open).is_empty());
+
+    let unknown = process(&app, CannedTranscriber::new(&["Command: make coffee"]), recording(3)).await;
+    assert!(unknown.is_err());
+    assert!(tasks(&app, TaskStatus::All).iter().all(|task| task.text != "make coffee"));
+}
+
+#[tokio::test]
+async fn reparsing_history_catches_up_on_what_an_older_parser_got_wrong() {
+    use flowstate_core::history::{ActionKind, HistoryAction};
+    let app = test_app();
+    let db = app.state::<Database>();
+    let result = say(&app, "Buy milk, call mom").await;
+
+    // As if an older parser had read it as "Buy milk" and "Buy milk call mom"
+    let note = flowstate_core::database::parsed_transcriptions_between(&db, None, None).unwrap().remove(0);
+    let call = result.tasks.iter().find(|task| task.text == "Call mom").unwrap().id;
+    flowstate_core::database::delete_tasks(&db, &[call]).unwrap();
+    let wrong = add_task(app.handle().clone(), "Buy milk call mom".to_string(), app.state()).unwrap();
+    let old = [("Buy milk", result.tasks[0].id), ("Buy milk call mom", wrong.id)].map(|(text, id)| HistoryAction {
+        kind: ActionKind::Add,
+        text: text.to_string(),
+        due_at: None,
+        task_id: Some(id),
+    });
+    flowstate_core::database::set_transcription_actions(&db, note.id, &flowstate_core::history::to_json(&old)).unwrap();
+
+    let diffs = reparse_history(None, app.state()).await.unwrap();
+    assert_eq!(diffs.len(), 1);
+    assert_eq!(diffs[0].added.iter().map(|action| action.text.as_str()).collect::<Vec<_>>(), ["Call mom"]);
+    assert_eq!(diffs[0].removed.iter().map(|action| action.text.as_str()).collect::<Vec<_>>(), ["Buy milk call mom"]);
+    assert_eq!(diffs[0].unchanged, 1);
+    // A dry run leaves the list alone
+    assert_eq!(tasks(&app, TaskStatus::Open).len(), 2);
+
+    apply_reparse(app.handle().clone(), vec![note.id], app.state()).await.unwrap();
+    let mut open: Vec<String> = tasks(&app, TaskStatus::Open).into_iter().map(|task| task.text).collect();
+    open.sort();
+    assert_eq!(open, ["Buy milk", "Call mom"]);
+    assert!(reparse_history(None, app.state()).await.unwrap().is_empty());
+}
+
+#[test]
+fn completing_ten_tasks_unlocks_an_achievement_once() {
+    use flowstate_core::achievements::Achievement;
+
+    let app = test_app();
+    let db = app.state::<Database>();
+    let texts: Vec<String> = (1..=10).map(|n| format!("Task {}", n)).collect();
+    let added = flowstate_core::database::add_tasks(&db, &texts).unwrap();
+    let ids: Vec<i64> = added.iter().map(|task| task.id).collect();
+
+    flowstate_core::database::complete_tasks(&db, &ids[..9]).unwrap();
+    assert!(flowstate_core::achievements::check(&db).is_empty());
+    flowstate_core::database::complete_tasks(&db, &ids[9..]).unwrap();
+    let unlocked = flowstate_core::achievements::check(&db);
+
+    assert_eq!(unlocked.iter().map(|achievement| achievement.id).collect::<Vec<_>>(), [Achievement::TenTasks]);
+    assert!(flowstate_core::achievements::check(&db).is_empty());
+    let all = flowstate_core::achievements::get_achievements(&db).unwrap();
+    assert!(all.iter().find(|achievement| achievement.id == Achievement::TenTasks).unwrap().unlocked_at.is_some());
+}
+
+#[tokio::test]
+async fn applies_a_long_memo_piece_by_piece() {
+    let app = test_app();
+    // Longer than audio::CHUNK_SECS, so it goes through in two pieces
+    let transcriber = CannedTranscriber::new(&["Buy milk", "Call mom"]);
+    let result = process(&app, transcriber, recording(45)).await.unwrap();
+
+    assert_eq!(result.transcript, "Buy milk Call mom");
+    assert_eq!(texts(&result.tasks), ["Buy milk", "Call mom"]);
+}
+
+#[tokio::test]
+async fn silence_changes_nothing() {
+    let app = test_app();
+    let result = say(&app, "").await;
+
+    assert!(result.transcript.is_empty());
+    assert!(result.tasks.is_empty());
+    assert!(tasks(&app, TaskStatus::All).is_empty());
+}
+
+#[tokio::test]
+async fn a_transcription_error_is_returned() {
+    let app = test_app();
+    let transcriber = CannedTranscriber::with_results(vec![Err("Transcription failed".to_string())]);
+    let result = process(&app, transcriber, recording(3)).await;
+
+    assert_eq!(result.err().as_deref(), Some("Transcription failed"));
+    assert!(tasks(&app, TaskStatus::All).is_empty());
+}
+
+#[tokio::test]
+async fn emits_an_event_for_each_added_task() {
+    let app = test_app();
+    let (tx, rx) = mpsc::channel();
+    app.listen_any("task-added", move |event| {
+        let task: serde_json::Value = serde_json::from_str(event.payload()).unwrap();
+        let _ = tx.send(task["text"].as_str().unwrap_or_default().to_string());
+    });
+
+    say(&app, "Buy milk, call mom").await;
+
+    let added: Vec<String> = (0..2)
+        .map(|_| rx.recv_timeout(Duration::from_secs(1)).expect("task-added event"))
+        .collect();
+    assert_eq!(added, ["Buy milk", "Call mom"]);
+}
+
+#[test]
+fn toggling_a_task_completes_and_reopens_it() {
+    let app = test_app();
+    let task = add_task(app.handle().clone(), "Buy milk".to_string(), app.state()).unwrap();
+
+    let done = toggle_task(app.handle().clone(), task.id, app.state()).unwrap();
+    assert!(done.completed);
+    assert_eq!(texts(&tasks(&app, TaskStatus::CompletedToday)), ["Buy milk"]);
+
+    let reopened = toggle_task(app.handle().clone(), task.id, app.state()).unwrap();
+    assert!(!reopened.completed);
+    assert_eq!(texts(&tasks(&app, TaskStatus::Open)), ["Buy milk"]);
+}
+
+#[tokio::test]
+async fn the_same_recording_is_only_processed_once() {
+    let state = ProcessingState::default();
+    let fingerprint = Some(crate::processing::fingerprint(b"recording"));
+    let _first = state.begin("recording", fingerprint).await.unwrap();
+
+    assert_eq!(state.begin("recording", fingerprint).await.err(), Some(FlowStateError::DuplicateRecording));
+}
+
+#[tokio::test]
+async fn overlapping_jobs_wait_their_turn() {
+    let state = Arc::new(ProcessingState::default());
+    let first = state.begin("recording", None).await.unwrap();
+    let second = {
+        let state = state.clone();
+        tokio::spawn(async move {
+            let _turn = state.begin("import", None).await.unwrap();
+            state.status()
+        })
+    };
+    while state.status().queued == 0 {
+        tokio::task::yield_now().await;
+    }
+
+    let status = state.status();
+    assert_eq!(status.source.as_deref(), Some("recording"));
+    assert_eq!(status.queued, 1);
+
+    drop(first);
+    let status = second.await.unwrap();
+    assert_eq!(status.source.as_deref(), Some("import"));
+    assert_eq!(status.queued, 0);
+    assert!(!state.status().busy);
+}
+
+/// An LLM parser whose server is down
+struct UnreachableParser;
+
+impl TranscriptParser for UnreachableParser {
+    fn kind(&self) -> ParserKind {
+        ParserKind::Ollama
+    }
+
+    fn parse<'a>(&'a self, _transcript: &'a str, _instructions: Option<&'a str>) -> BoxFuture<'a, Result<ParsedMemo, String>> {
+        Box::pin(async { Err("Ollama not available".to_string()) })
+    }
+}
+
+#[tokio::test]
+async fn voice_tasks_say_which_parser_made_them() {
+    let app = test_app();
+    let result = say(&app, "Buy milk").await;
+
+    assert_eq!(result.tasks[0].parsed_by, Some(ParserKind::Rules));
+}
+
+#[tokio::test]
+async fn a_failing_parser_falls_through_to_the_next_one() {
+    let chain = ParserChain::new(vec![Box::new(UnreachableParser), Box::new(RuleBased)], None);
+    let parsed = chain.parse("Buy milk, call mom").await.unwrap();
+
+    assert_eq!(parsed.parser, ParserKind::Rules);
+    assert_eq!(parsed.tasks.len(), 2);
+
+    let llm_only = ParserChain::new(vec![Box::new(UnreachableParser)], None);
+    assert_eq!(llm_only.parse("Buy milk").await.err().as_deref(), Some("Ollama not available"));
+}
+
+#[tokio::test]
+async fn usage_metrics_count_recordings_and_parser_fallbacks() {
+    let app = test_app();
+    say(&app, "Buy milk").await;
+    let chain = ParserChain::new(vec![Box::new(UnreachableParser), Box::new(RuleBased)], None);
+    let (_, attempts) = chain.parse_timed("Call mom").await;
+    flowstate_core::metrics::record_parse(&app.state::<Database>(), &attempts);
+
+    let metrics = get_usage_metrics(Some(7), app.state()).unwrap();
+    assert_eq!(metrics.recordings, 1);
+    assert_eq!(metrics.transcriptions[0].source, "recording");
+    assert_eq!(metrics.transcriptions[0].average_audio_ms, 3000);
+
+    let parser = |kind: ParserKind| metrics.parsers.iter().find(|parser| parser.parser == kind.as_str()).unwrap();
+    assert_eq!(parser(ParserKind::Rules).parsed, 2);
+    assert_eq!(parser(ParserKind::Ollama).fallbacks, 1);
+}
+
+#[test]
+fn a_damaged_database_is_set_aside_and_replaced() {
+    use flowstate_core::diagnostics::CheckStatus;
+
+    let dir = std::env::temp_dir().join(format!("flowstate-startup-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join(flowstate_core::database::FILE_NAME), "not a database").unwrap();
+
+    let (db, check) = flowstate_core::startup::open_database(&dir);
+    assert_eq!(check.status, CheckStatus::Warning);
+    assert!(flowstate_core::database::add_task(&db, "Buy milk").is_ok());
+    drop(db);
+
+    let backups = std::fs::read_dir(&dir)
+        .unwrap()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_name().to_string_lossy().contains(".corrupt-"))
+        .count();
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(backups, 1);
+}
+
+#[test]
+fn switching_databases_keeps_tasks_apart_and_is_remembered() {
+    use flowstate_core::databases;
+
+    let dir = std::env::temp_dir().join(format!("flowstate-databases-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let db = Database::open(&dir.join(flowstate_core::database::FILE_NAME)).unwrap();
+    flowstate_core::database::add_task(&db, "Water the plants").unwrap();
+
+    assert_eq!(databases::switch(&db, &dir, " Work ").unwrap(), "work");
+    assert!(flowstate_core::database::get_all_tasks(&db).unwrap().is_empty());
+    flowstate_core::database::add_task(&db, "Send the invoice").unwrap();
+    let list = databases::list(&dir, &databases::current(&db));
+    let last_used = databases::last_used(&dir);
+
+    databases::switch(&db, &dir, databases::DEFAULT).unwrap();
+    let tasks = flowstate_core::database::get_all_tasks(&db).unwrap();
+    let invalid = databases::switch(&db, &dir, "../work");
+    drop(db);
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(list.current, "work");
+    assert_eq!(list.databases, ["default", "work"]);
+    assert_eq!(last_used, "work");
+    assert_eq!(tasks.iter().map(|task| task.text.as_str()).collect::<Vec<_>>(), ["Water the plants"]);
+    assert!(invalid.is_err());
+}
+
+#[test]
+fn removal_keywords_count_only_as_whole_words() {
+    use flowstate_core::ollama::has_removal_keywords;
+
+    assert!(has_removal_keywords("drop the weekly report"));
+    assert!(has_removal_keywords("Never mind the dentist"));
+    assert!(!has_removal_keywords("upload the photos to Dropbox"));
+    assert!(!has_removal_keywords("confirm the hotel cancellation policy"));
+}
+
+#[test]
+fn deleting_a_task_or_habit_deletes_the_rows_that_refer_to_it() {
+    use flowstate_core::database::{self, TodoistLink};
+
+    let db = Database::new_in_memory().unwrap();
+    let task = database::add_task(&db, "Send the invoice").unwrap();
+    database::save_todoist_link(
+        &db,
+        &TodoistLink {
+            task_id: task.id,
+            todoist_id: "123".to_string(),
+            synced_text: task.text.clone(),
+            synced_completed: false,
+        },
+    )
+    .unwrap();
+    database::set_task_issue_url(&db, task.id, "https://github.com/o/r/issues/1").unwrap();
+    let habit = database::add_habit(&db, "Stretch", "daily").unwrap();
+    database::set_habit_done(&db, habit.id, "2026-05-12", true).unwrap();
+
+    database::delete_task(&db, task.id).unwrap();
+    database::delete_habit(&db, habit.id).unwrap();
+
+    assert_eq!(database::todoist_links(&db).unwrap().len(), 0);
+    assert_eq!(database::task_issue_urls(&db).unwrap().len(), 0);
+    assert_eq!(database::habit_log_dates(&db, habit.id).unwrap().len(), 0);
+}
+
+#[test]
+fn reminders_of_completed_tasks_stay_pending_until_the_task_is_reopened() {
+    use flowstate_core::database;
+
+    let db = Database::new_in_memory().unwrap();
+    let open = database::add_task(&db, "Call the bank").unwrap();
+    let done = database::add_task(&db, "Water the plants").unwrap();
+    database::set_reminder(&db, open.id, "2026-05-12 09:00:00").unwrap();
+    database::set_reminder(&db, done.id, "2026-05-12 09:00:00").unwrap();
+    database::toggle_task(&db, done.id).unwrap();
+
+    let due = database::take_due_reminders(&db, "2026-05-12 10:00:00").unwrap();
+    assert_eq!(due.iter().map(|r| r.task_id).collect::<Vec<_>>(), vec![open.id]);
+
+    // Not marked fired while the task was done, so it goes off once reopened
+    database::toggle_task(&db, done.id).unwrap();
+    let due = database::take_due_reminders(&db, "2026-05-12 10:00:00").unwrap();
+    assert_eq!(due.iter().map(|r| r.task_id).collect::<Vec<_>>(), vec![done.id]);
+}
@@ -0,0 +1,279 @@
+// Config file module
+// An optional `flowstate.toml` next to the database that sets a few settings
+// from a text file, kept in step both ways (see `ConfigFile` and `write_back`).
+
+use crate::database::Database;
+use crate::i18n::Locale;
+use crate::settings::Settings;
+use notify::{RecursiveMode, Watcher};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc};
+use std::thread::JoinHandle;
+use std::time::Duration;
+use toml_edit::{DocumentMut, Item, TableLike, Value};
+
+pub const FILE_NAME: &str = "flowstate.toml";
+// How often the watcher thread checks whether it should stop
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+// Editors save in several steps; read the file once they're done
+const SETTLE: Duration = Duration::from_millis(200);
+
+/// What the file sets; everything is optional
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ConfigFile {
+    /// Whisper model ("tiny", "base", ...)
+    pub model: Option<String>,
+    /// Language of the UI and messages ("en", "ru")
+    pub language: Option<Locale>,
+    /// Language Whisper transcribes in ("en", "ru", "auto", ...)
+    pub transcription_language: Option<String>,
+    /// Awareness interval (0 disables the alert)
+    pub timer_minutes: Option<u64>,
+    pub ollama: Endpoint,
+    pub openai: Endpoint,
+}
+
+/// Server and model of an LLM used for parsing
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Endpoint {
+    pub url: Option<String>,
+    pub model: Option<String>,
+}
+
+impl ConfigFile {
+    /// The file's values as a partial update for `settings::update`
+    pub fn patch(&self) -> serde_json::Value {
+        let mut patch = serde_json::Map::new();
+        if let Some(model) = &self.model {
+            patch.insert("selected_model".into(), model.as_str().into());
+        }
+        if let Some(language) = self.language {
+            patch.insert("ui_language".into(), language.code().into());
+        }
+        if let Some(language) = &self.transcription_language {
+            patch.insert("transcription_language".into(), language.as_str().into());
+        }
+        if let Some(minutes) = self.timer_minutes {
+            patch.insert("timer_duration_minutes".into(), minutes.into());
+        }
+        for (key, endpoint) in [("ollama", &self.ollama), ("openai", &self.openai)] {
+            let mut fields = serde_json::Map::new();
+            if let Some(url) = &endpoint.url {
+                fields.insert("url".into(), url.as_str().into());
+            }
+            if let Some(model) = &endpoint.model {
+                fields.insert("model".into(), model.as_str().into());
+            }
+            if !fields.is_empty() {
+                patch.insert(key.into(), fields.into());
+            }
+        }
+        serde_json::Value::Object(patch)
+    }
+}
+
+/// Where the file is looked for: next to the database (None for an in-memory one)
+pub fn path(db: &Database) -> Option<PathBuf> {
+    db.data_dir().map(|dir| dir.join(FILE_NAME))
+}
+
+/// The file's contents, or None when there is no file
+pub fn read(path: &Path) -> Result<Option<ConfigFile>, String> {
+    let text = match std::fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(file_error(path, e)),
+    };
+    toml_edit::de::from_str(&text)
+        .map(Some)
+        .map_err(|e| file_error(path, e))
+}
+
+/// Put the file's values into the stored settings (at startup, before they're
+/// read). Returns the settings when there is a file.
+pub fn apply(db: &Database) -> Result<Option<Settings>, String> {
+    let Some(path) = path(db) else {
+        return Ok(None);
+    };
+    let Some(file) = read(&path)? else {
+        return Ok(None);
+    };
+    let settings = crate::settings::update(db, file.patch()).map_err(|e| file_error(&path, e))?;
+    tracing::info!("Applied {}", path.display());
+    Ok(Some(settings))
+}
+
+/// Update the keys the file already has to `settings`' values, keeping its
+/// comments and layout. Does nothing when there is no file.
+pub fn write_back(path: &Path, settings: &Settings) -> Result<(), String> {
+    let text = match std::fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(file_error(path, e)),
+    };
+    let mut doc: DocumentMut = text.parse().map_err(|e| file_error(path, e))?;
+
+    let root: &mut dyn TableLike = doc.as_table_mut();
+    set_str(root, "model", Some(settings.selected_model.as_str()));
+    set_str(root, "language", settings.ui_language.map(|language| language.code()));
+    set_str(root, "transcription_language", Some(settings.transcription_language.as_str()));
+    set_int(root, "timer_minutes", settings.timer_duration_minutes as i64);
+    if let Some(ollama) = doc.get_mut("ollama").and_then(Item::as_table_like_mut) {
+        set_str(ollama, "url", Some(settings.ollama.url.as_str()));
+        set_str(ollama, "model", Some(settings.ollama.model.as_str()));
+    }
+    if let Some(openai) = doc.get_mut("openai").and_then(Item::as_table_like_mut) {
+        set_str(openai, "url", Some(settings.openai.url.as_str()));
+        set_str(openai, "model", Some(settings.openai.model.as_str()));
+    }
+
+    let updated = doc.to_string();
+    if updated != text {
+        std::fs::write(path, updated).map_err(|e| file_error(path, e))?;
+        tracing::info!("Updated {}", path.display());
+    }
+    Ok(())
+}
+
+// Change a key the file has (None removes it, like a language back to "follow the system")
+fn set_str(table: &mut dyn TableLike, key: &str, value: Option<&str>) {
+    let Some(item) = table.get_mut(key) else {
+        return;
+    };
+    match value {
+        Some(value) if item.as_str() != Some(value) => replace(item, value.into()),
+        Some(_) => {}
+        None => {
+            table.remove(key);
+        }
+    }
+}
+
+fn set_int(table: &mut dyn TableLike, key: &str, value: i64) {
+    if let Some(item) = table.get_mut(key).filter(|item| item.as_integer() != Some(value)) {
+        replace(item, value.into());
+    }
+}
+
+// Keep the comment after the old value
+fn replace(item: &mut Item, mut value: Value) {
+    if let Some(old) = item.as_value() {
+        *value.decor_mut() = old.decor().clone();
+    }
+    *item = Item::Value(value);
+}
+
+fn file_error(path: &Path, error: impl std::fmt::Display) -> String {
+    crate::i18n::tf("error.config_file", &[("path", &path.display()), ("error", &error)])
+}
+
+pub type ChangeHandler = Box<dyn Fn(ConfigFile) + Send>;
+
+/// Running file watcher; stops when dropped
+pub struct ConfigWatcher {
+    stop: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl ConfigWatcher {
+    /// Watch the file at `path` (it doesn't have to exist yet) and pass its
+    /// contents to `on_change` each time they change
+    pub fn start(path: PathBuf, on_change: ChangeHandler) -> Result<Self, String> {
+        let folder = path
+            .parent()
+            .ok_or_else(|| format!("{} has no folder", path.display()))?
+            .to_path_buf();
+
+        // Editors often save by replacing the file, so watch its folder
+        let (event_tx, event_rx) = mpsc::channel();
+        let mut watcher = notify::recommended_watcher(event_tx).map_err(|e| e.to_string())?;
+        watcher
+            .watch(&folder, RecursiveMode::NonRecursive)
+            .map_err(|e| e.to_string())?;
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let stop_flag = stop.clone();
+        let handle = std::thread::spawn(move || {
+            // Keep the watcher alive for the life of the thread
+            let _watcher = watcher;
+            // Applied at startup already
+            let mut last = read(&path).ok().flatten();
+            while !stop_flag.load(Ordering::Relaxed) {
+                match event_rx.recv_timeout(POLL_INTERVAL) {
+                    Ok(Ok(event)) if event.paths.iter().any(|p| p.file_name() == path.file_name()) => {
+                        std::thread::sleep(SETTLE);
+                        while event_rx.try_recv().is_ok() {}
+                        match read(&path) {
+                            Ok(Some(file)) if last.as_ref() != Some(&file) => {
+                                tracing::info!("{} changed", path.display());
+                                last = Some(file.clone());
+                                on_change(file);
+                            }
+                            // Unchanged (our own write-back) or deleted; the settings stay as they are
+                            Ok(file) => last = file,
+                            Err(e) => tracing::warn!("{}", e),
+                        }
+                    }
+                    Ok(Err(e)) => tracing::warn!("Config file watcher error: {}", e),
+                    Ok(Ok(_)) | Err(mpsc::RecvTimeoutError::Timeout) => {}
+                    Err(mpsc::RecvTimeoutError::Disconnected) => break,
+                }
+            }
+        });
+
+        Ok(Self {
+            stop,
+            handle: Some(handle),
+        })
+    }
+}
+
+impl Drop for ConfigWatcher {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_config_file_sets_defaults_and_takes_changes_back() {
+        let path = std::env::temp_dir().join(format!("flowstate-test-{}.toml", std::process::id()));
+        std::fs::write(
+            &path,
+            "# Set up by hand\nmodel = \"base\" # fast enough\nlanguage = \"ru\"\ntranscription_language = \"en\"\n\n[ollama]\nurl = \"http://gpu-box:11434\"\n",
+        )
+        .unwrap();
+        let file = read(&path).unwrap().expect("config file");
+
+        let db = Database::new_in_memory().unwrap();
+        let settings = crate::settings::update(&db, file.patch()).unwrap();
+        assert_eq!(settings.selected_model, "base");
+        assert_eq!(settings.ollama.url, "http://gpu-box:11434");
+        assert_eq!(settings.ollama.model, crate::settings::OllamaSettings::default().model);
+        // The UI and Whisper languages are separate keys
+        assert_eq!(settings.ui_language, Some(Locale::Russian));
+        assert_eq!(settings.transcription_language, "en");
+        assert_eq!(settings.recording_language(), "en");
+
+        // A model picked in the UI goes into the file; keys it doesn't have stay out
+        let picked = Settings { selected_model: "small".to_string(), ..settings };
+        write_back(&path, &picked).unwrap();
+        let text = std::fs::read_to_string(&path).unwrap();
+        let file = read(&path).unwrap().expect("config file");
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(file.model.as_deref(), Some("small"));
+        assert_eq!(file.timer_minutes, None);
+        assert!(text.contains("# Set up by hand") && text.contains("# fast enough"));
+    }
+}
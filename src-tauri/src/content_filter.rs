@@ -0,0 +1,157 @@
+// Content filter module
+// Keeps swear words and other words the user lists (client names, health
+// matters) out of what's stored, for people who share their screen with the
+// task list open. Right after transcription, `filter` masks them with
+// asterisks or drops them, so the tasks, the journal and the kept transcripts
+// never see them. Matching is by whole word and ignores case; a listed phrase
+// of several words matches those words in a row.
+
+use crate::settings::ContentFilterSettings;
+use serde::{Deserialize, Serialize};
+
+/// What happens to a filtered word
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FilterMode {
+    /// "****", one asterisk a letter
+    #[default]
+    Mask,
+    /// Left out, along with the space before it
+    Drop,
+}
+
+// Swear words caught wherever they occur in a word ("motherfucker", "распиздяй")
+const ROOTS: [&str; 10] = [
+    "fuck", "shit", "cunt", "пизд", "хуй", "хуе", "хуё", "ебал", "ебан", "ебат",
+];
+// Swear words caught only as whole words ("dick" but not "dickens")
+const WORDS: [&str; 24] = [
+    "ass",
+    "asshole",
+    "assholes",
+    "bastard",
+    "bastards",
+    "bitch",
+    "bitches",
+    "bollocks",
+    "crap",
+    "damn",
+    "dammit",
+    "dick",
+    "piss",
+    "pissed",
+    "wanker",
+    "бля",
+    "блядь",
+    "блять",
+    "говно",
+    "дерьмо",
+    "мудак",
+    "мудаки",
+    "сука",
+    "суки",
+];
+
+/// `text` with the filtered words masked or dropped; unchanged while the
+/// filter is off
+pub fn filter(text: &str, settings: &ContentFilterSettings) -> String {
+    if !settings.enabled {
+        return text.to_string();
+    }
+    let spans = word_spans(text);
+    let lower: Vec<String> = spans
+        .iter()
+        .map(|&(start, end)| text[start..end].to_lowercase())
+        .collect();
+    let phrases: Vec<Vec<String>> = settings
+        .words
+        .iter()
+        .map(|phrase| {
+            word_spans(phrase)
+                .into_iter()
+                .map(|(start, end)| phrase[start..end].to_lowercase())
+                .collect::<Vec<_>>()
+        })
+        .filter(|words| !words.is_empty())
+        .collect();
+
+    // Byte ranges to filter, in order and not overlapping
+    let mut matches = Vec::new();
+    let mut index = 0;
+    while index < spans.len() {
+        let length = phrases
+            .iter()
+            .filter(|phrase| lower[index..].starts_with(phrase))
+            .map(Vec::len)
+            .max()
+            .or_else(|| (settings.profanity && is_swear_word(&lower[index])).then_some(1));
+        match length {
+            Some(length) => {
+                matches.push((spans[index].0, spans[index + length - 1].1));
+                index += length;
+            }
+            None => index += 1,
+        }
+    }
+    if matches.is_empty() {
+        return text.to_string();
+    }
+
+    let mut filtered = String::with_capacity(text.len());
+    let mut last = 0;
+    for (start, end) in matches {
+        filtered.push_str(&text[last..start]);
+        match settings.mode {
+            FilterMode::Mask => filtered.extend(text[start..end].chars().map(|c| {
+                if c.is_alphanumeric() {
+                    '*'
+                } else {
+                    c
+                }
+            })),
+            FilterMode::Drop => {
+                // A comma before the word goes too when punctuation follows it
+                let rest = text[end..].trim_start();
+                let gap: &[char] = if rest.starts_with(|c: char| c.is_alphanumeric()) {
+                    &[' ']
+                } else {
+                    &[' ', ',']
+                };
+                let kept = filtered.trim_end_matches(gap).len();
+                filtered.truncate(kept);
+            }
+        }
+        last = end;
+    }
+    filtered.push_str(&text[last..]);
+    if settings.mode == FilterMode::Drop {
+        // Nothing dropped at the start leaves a gap or a stray comma behind
+        return filtered.trim_start_matches([' ', ',']).to_string();
+    }
+    filtered
+}
+
+fn is_swear_word(word: &str) -> bool {
+    WORDS.contains(&word) || ROOTS.iter().any(|root| word.contains(root))
+}
+
+// [start, end) byte ranges of the words in `text`, apostrophes included
+fn word_spans(text: &str) -> Vec<(usize, usize)> {
+    let mut spans = Vec::new();
+    let mut start = None;
+    for (index, c) in text.char_indices() {
+        let in_word = c.is_alphanumeric() || (c == '\'' && start.is_some());
+        match (in_word, start) {
+            (true, None) => start = Some(index),
+            (false, Some(begin)) => {
+                spans.push((begin, index));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(begin) = start {
+        spans.push((begin, text.len()));
+    }
+    spans
+}
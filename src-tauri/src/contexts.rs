@@ -0,0 +1,105 @@
+// Contexts module
+// GTD-style contexts: what a task needs rather than where it belongs (@phone,
+// @errand, @computer). Unlike projects and tags they aren't written into the
+// task text but kept in their own indexed column, so the list can be narrowed
+// to "things I can do on the phone" quickly. A context comes from an "@word"
+// in the text, or from a spoken phrase at the start or end of it: "at the store
+// buy batteries" becomes "Buy batteries" in @errand.
+
+const PHONE: &str = "phone";
+const ERRAND: &str = "errand";
+const COMPUTER: &str = "computer";
+const HOME: &str = "home";
+const OFFICE: &str = "office";
+
+// Spoken phrases and the context they stand for, longest first within a context
+const PHRASES: [(&str, &str); 22] = [
+    ("on the phone", PHONE),
+    ("by phone", PHONE),
+    ("по телефону", PHONE),
+    ("at the supermarket", ERRAND),
+    ("at the pharmacy", ERRAND),
+    ("at the store", ERRAND),
+    ("at the shop", ERRAND),
+    ("while i'm out", ERRAND),
+    ("when i'm out", ERRAND),
+    ("в магазине", ERRAND),
+    ("в аптеке", ERRAND),
+    ("at the computer", COMPUTER),
+    ("on the computer", COMPUTER),
+    ("on my computer", COMPUTER),
+    ("за компьютером", COMPUTER),
+    ("на компьютере", COMPUTER),
+    ("at home", HOME),
+    ("дома", HOME),
+    ("at the office", OFFICE),
+    ("at work", OFFICE),
+    ("в офисе", OFFICE),
+    ("на работе", OFFICE),
+];
+
+/// Context in its stored form: lowercase, without the "@"; None if empty
+pub fn normalize(context: &str) -> Option<String> {
+    let context = context.trim().trim_start_matches('@').trim().to_lowercase();
+    (!context.is_empty()).then_some(context)
+}
+
+/// The task text without its context, and the context: the first "@word" in
+/// the text, or a known phrase at its start or end. Text that would be left
+/// empty keeps its words and gets no context.
+pub fn split_context(text: &str) -> (String, Option<String>) {
+    let text = text.trim();
+
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let mention = words.iter().position(|word| {
+        let name = word.trim_end_matches([',', '.', '!', '?']);
+        name.len() > 1
+            && name.starts_with('@')
+            && name[1..].chars().all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+    });
+    if let Some(index) = mention {
+        let context = normalize(words[index].trim_end_matches([',', '.', '!', '?']));
+        let rest: Vec<&str> = words.iter().enumerate().filter(|(i, _)| *i != index).map(|(_, word)| *word).collect();
+        let rest = rest.join(" ");
+        if !rest.is_empty() {
+            return (rest, context);
+        }
+        return (text.to_string(), None);
+    }
+
+    for (phrase, context) in PHRASES {
+        let rest = strip_prefix_ci(text, phrase)
+            .map(capitalize)
+            .or_else(|| strip_suffix_ci(text, phrase).map(str::to_string));
+        if let Some(rest) = rest.filter(|rest| !rest.is_empty()) {
+            return (rest, Some(context.to_string()));
+        }
+    }
+    (text.to_string(), None)
+}
+
+// `text` without a leading `phrase` (case-insensitive, whole words)
+fn strip_prefix_ci<'a>(text: &'a str, phrase: &str) -> Option<&'a str> {
+    let head = text.get(..phrase.len())?;
+    let rest = &text[phrase.len()..];
+    (head.to_lowercase() == phrase && (rest.is_empty() || rest.starts_with([' ', ','])))
+        .then(|| rest.trim_start_matches([' ', ',']))
+}
+
+// `text` without a trailing `phrase` (case-insensitive, whole words)
+fn strip_suffix_ci<'a>(text: &'a str, phrase: &str) -> Option<&'a str> {
+    let text = text.trim_end_matches(['.', '!', '?']);
+    let start = text.len().checked_sub(phrase.len())?;
+    let tail = text.get(start..)?;
+    let rest = &text[..start];
+    (tail.to_lowercase() == phrase && (rest.is_empty() || rest.ends_with([' ', ','])))
+        .then(|| rest.trim_end_matches([' ', ',']))
+}
+
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
@@ -0,0 +1,107 @@
+// Crash module
+// Panic hook that writes a report file users can attach to bug reports, plus a
+// helper that turns panics in worker code into ordinary errors
+
+use std::fs;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+// Holds the path of a report the user hasn't been told about yet
+const PENDING_MARKER: &str = "pending";
+
+/// Directory holding crash reports (`<data dir>/flowstate/crashes`)
+pub fn crash_dir() -> PathBuf {
+    crate::paths::data_dir().join("crashes")
+}
+
+/// Install the panic hook. Call once at startup, after logging is initialised.
+pub fn install_panic_hook() {
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        let thread = std::thread::current();
+        let thread_name = thread.name().unwrap_or("unnamed");
+        let location = info
+            .location()
+            .map(|l| format!("{}:{}", l.file(), l.line()))
+            .unwrap_or_else(|| "unknown location".to_string());
+        let message = panic_message(info.payload());
+
+        tracing::error!("Panic in thread '{}' at {}: {}", thread_name, location, message);
+
+        let report = format!(
+            "FlowState {} crash report\nTime: {}\nOS: {} ({})\nThread: {}\nLocation: {}\nMessage: {}\n\nBacktrace:\n{}\n",
+            env!("CARGO_PKG_VERSION"),
+            chrono::Local::now().format("%Y-%m-%d %H:%M:%S"),
+            std::env::consts::OS,
+            std::env::consts::ARCH,
+            thread_name,
+            location,
+            message,
+            std::backtrace::Backtrace::force_capture(),
+        );
+        if let Err(e) = write_report(&report) {
+            tracing::error!("Failed to write crash report: {}", e);
+        }
+
+        default_hook(info);
+    }));
+}
+
+fn write_report(report: &str) -> std::io::Result<()> {
+    let dir = crash_dir();
+    fs::create_dir_all(&dir)?;
+    let path = dir.join(format!("crash-{}.txt", chrono::Local::now().format("%Y%m%d_%H%M%S_%f")));
+    fs::write(&path, report)?;
+    fs::write(dir.join(PENDING_MARKER), path.to_string_lossy().as_bytes())
+}
+
+/// Report from a previous run the user hasn't seen yet (cleared once returned)
+pub fn take_pending_report() -> Option<PathBuf> {
+    let marker = crash_dir().join(PENDING_MARKER);
+    let path = PathBuf::from(fs::read_to_string(&marker).ok()?.trim());
+    let _ = fs::remove_file(&marker);
+    path.exists().then_some(path)
+}
+
+/// Open a report in the system's default text viewer
+pub fn open_report(path: &Path) -> Result<(), String> {
+    #[cfg(target_os = "windows")]
+    let mut command = {
+        let mut command = Command::new("cmd");
+        command.args(["/C", "start", ""]);
+        command
+    };
+    #[cfg(target_os = "macos")]
+    let mut command = Command::new("open");
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    let mut command = Command::new("xdg-open");
+
+    command
+        .arg(path)
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| format!("Failed to open crash report: {}", e))
+}
+
+/// Run worker code, reporting a panic as an error instead of killing the caller.
+/// The hook has already written the crash report by the time this returns.
+pub fn catch_panic<T>(f: impl FnOnce() -> Result<T, String>) -> Result<T, String> {
+    panic::catch_unwind(AssertUnwindSafe(f)).unwrap_or_else(|payload| {
+        Err(format!(
+            "Internal error: {} (a crash report was saved to {})",
+            panic_message(payload.as_ref()),
+            crash_dir().display()
+        ))
+    })
+}
+
+fn panic_message(payload: &(dyn std::any::Any + Send)) -> String {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        s.to_string()
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
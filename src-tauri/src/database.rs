@@ -1,11 +1,17 @@
-use rusqlite::{Connection, Result, params};
+use rusqlite::{Connection, OpenFlags, OptionalExtension, Result, params};
+use crate::focus_monitor::ContextSwitches;
 use serde::{Deserialize, Serialize};
-use std::sync::Mutex;
-#[cfg(feature = "tauri-ui")]
-use tauri::{AppHandle, Manager};
+use std::path::{Path, PathBuf};
+use std::ops::Deref;
+use std::sync::{Mutex, MutexGuard};
 
+/// The database file opened twice in WAL mode: `conn` for writes (and reads
+/// that must see them in the same step), and a read-only connection for
+/// queries, so a long stats or search query doesn't hold up a voice insert.
 pub struct Database {
     pub conn: Mutex<Connection>,
+    // None for an in-memory database, which a second connection can't see
+    reader: Mutex<Option<Connection>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -15,27 +21,119 @@ pub struct Task {
     pub completed: bool,
     pub created_at: String,
     pub completed_at: Option<String>,
+    /// GTD context without the "@" ("phone", "errand"); see `contexts`
+    pub context: Option<String>,
+    /// How long the task should take, in minutes; see `estimates`
+    pub estimate_minutes: Option<i64>,
+    /// When it's due, in UTC ("YYYY-MM-DD HH:MM:SS"); see `set_task_due`
+    pub due_at: Option<String>,
+    /// Captured by voice and not triaged yet; kept out of the main list
+    pub inbox: bool,
 }
 
+/// Name of the database file in the data folder
+pub const FILE_NAME: &str = "flowstate.db";
+
 impl Database {
-    /// Create database without Tauri (for native UI)
+    /// Create database without Tauri (for native UI): the one last switched
+    /// to, see `databases`
     pub fn new() -> Result<Self> {
-        let app_data_dir = dirs::data_dir()
-            .unwrap_or_else(|| std::path::PathBuf::from("."))
-            .join("flowstate");
+        let app_data_dir = crate::paths::data_dir();
 
         std::fs::create_dir_all(&app_data_dir)
             .expect("Failed to create app data directory");
 
-        let db_path = app_data_dir.join("flowstate.db");
-        let conn = Connection::open(db_path)?;
+        let name = crate::databases::last_used(&app_data_dir);
+        Self::open(&app_data_dir.join(crate::databases::file_name(&name)))
+    }
+
+    /// A fresh database that lives only as long as the value (tests, dry runs)
+    pub fn new_in_memory() -> Result<Self> {
+        let conn = Connection::open_in_memory()?;
+        enable_foreign_keys(&conn)?;
+        init_tables(&conn)?;
+        Ok(Database {
+            conn: Mutex::new(conn),
+            reader: Mutex::new(None),
+        })
+    }
 
+    pub fn open(path: &Path) -> Result<Self> {
+        let conn = Connection::open(path)?;
+        // Readers don't block the writer (or each other) in WAL mode
+        conn.pragma_update(None, "journal_mode", "WAL")?;
+        enable_foreign_keys(&conn)?;
         init_tables(&conn)?;
 
+        let reader = Connection::open_with_flags(
+            path,
+            OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_URI | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+        )?;
+        enable_foreign_keys(&reader)?;
         Ok(Database {
             conn: Mutex::new(conn),
+            reader: Mutex::new(Some(reader)),
         })
     }
+
+    /// Folder the database file is in (None for an in-memory database)
+    pub fn data_dir(&self) -> Option<PathBuf> {
+        let conn = self.conn.lock().unwrap();
+        conn.path().and_then(Path::parent).map(Path::to_path_buf)
+    }
+
+    /// Second connection to the same file, for background threads that
+    /// can't borrow the app's (e.g. vault sync)
+    pub fn reopen(&self) -> Result<Self> {
+        let path = self.conn.lock().unwrap().path().map(Path::to_path_buf);
+        match path {
+            Some(path) => Self::open(&path),
+            None => Self::new_in_memory(),
+        }
+    }
+
+    /// Take over `other`'s connections, closing these. The managed state stays
+    /// the same value, so this is how another database file is switched to.
+    pub fn replace_with(&self, other: Database) {
+        let Database { conn, reader } = other;
+        let mut current = self.conn.lock().unwrap();
+        let mut current_reader = self.reader.lock().unwrap();
+        *current = conn.into_inner().unwrap();
+        *current_reader = reader.into_inner().unwrap();
+    }
+
+    /// The connection for queries that only read
+    fn reader(&self) -> Reader<'_> {
+        let reader = self.reader.lock().unwrap();
+        if reader.is_some() {
+            return Reader::ReadOnly(reader);
+        }
+        drop(reader);
+        Reader::Writer(self.conn.lock().unwrap())
+    }
+}
+
+// The read-only connection, or the one connection of an in-memory database
+enum Reader<'a> {
+    ReadOnly(MutexGuard<'a, Option<Connection>>),
+    Writer(MutexGuard<'a, Connection>),
+}
+
+impl Deref for Reader<'_> {
+    type Target = Connection;
+
+    fn deref(&self) -> &Connection {
+        match self {
+            Reader::ReadOnly(guard) => guard.as_ref().expect("checked when locked"),
+            Reader::Writer(guard) => guard,
+        }
+    }
+}
+
+// SQLite ignores `REFERENCES ... ON DELETE` unless this is on, and it's off
+// for every new connection
+fn enable_foreign_keys(conn: &Connection) -> Result<()> {
+    conn.pragma_update(None, "foreign_keys", true)
 }
 
 fn init_tables(conn: &Connection) -> Result<()> {
@@ -57,6 +155,231 @@ fn init_tables(conn: &Connection) -> Result<()> {
         [],
     )?;
 
+    // Manual order from drag-to-reorder (NULL until a task has been placed)
+    if !has_column(conn, "tasks", "position")? {
+        conn.execute("ALTER TABLE tasks ADD COLUMN position INTEGER", [])?;
+    }
+
+    // Set by the end-of-day rollover on old completed tasks; they leave every list but "all"
+    if !has_column(conn, "tasks", "archived_at")? {
+        conn.execute("ALTER TABLE tasks ADD COLUMN archived_at TEXT", [])?;
+    }
+
+    // GTD context ("phone", "errand"), kept out of the text so it can be indexed
+    if !has_column(conn, "tasks", "context")? {
+        conn.execute("ALTER TABLE tasks ADD COLUMN context TEXT", [])?;
+    }
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_tasks_context ON tasks(context)",
+        [],
+    )?;
+
+    // Estimated minutes ("about an hour"), summed by the workload summary
+    if !has_column(conn, "tasks", "estimate_minutes")? {
+        conn.execute("ALTER TABLE tasks ADD COLUMN estimate_minutes INTEGER", [])?;
+    }
+
+    // When the task is due, in UTC (a day without a time is due at 23:59 local)
+    if !has_column(conn, "tasks", "due_at")? {
+        conn.execute("ALTER TABLE tasks ADD COLUMN due_at TEXT", [])?;
+    }
+
+    // Voice-captured tasks waiting to be triaged (`settings.voice_inbox`)
+    if !has_column(conn, "tasks", "inbox")? {
+        conn.execute("ALTER TABLE tasks ADD COLUMN inbox INTEGER NOT NULL DEFAULT 0", [])?;
+    }
+
+    // Create time tracking table (completed focus sessions)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS time_entries (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            task_id INTEGER REFERENCES tasks(id) ON DELETE SET NULL,
+            task_text TEXT NOT NULL,
+            started_at TEXT NOT NULL,
+            ended_at TEXT NOT NULL,
+            duration_seconds INTEGER NOT NULL
+        )",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_time_entries_task ON time_entries(task_id)",
+        [],
+    )?;
+
+    // Focus monitor results (NULL when the monitor was off); top_apps is JSON
+    if !has_column(conn, "time_entries", "context_switches")? {
+        conn.execute("ALTER TABLE time_entries ADD COLUMN context_switches INTEGER", [])?;
+        conn.execute("ALTER TABLE time_entries ADD COLUMN top_apps TEXT", [])?;
+    }
+
+    // Create journal table (answers to the timer-expiry prompt)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS journal_entries (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            text TEXT NOT NULL,
+            interval_start TEXT NOT NULL,
+            interval_end TEXT NOT NULL,
+            created_at TEXT NOT NULL DEFAULT (datetime('now'))
+        )",
+        [],
+    )?;
+
+    // Create session log (one row per expired timer interval)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS sessions (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            started_at TEXT NOT NULL,
+            ended_at TEXT NOT NULL,
+            task_id INTEGER REFERENCES tasks(id) ON DELETE SET NULL,
+            task_text TEXT,
+            acknowledged INTEGER NOT NULL DEFAULT 0,
+            acknowledged_at TEXT
+        )",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_sessions_ended ON sessions(ended_at)",
+        [],
+    )?;
+
+    // Tasks pushed to Todoist, with the state both sides had after the last sync
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS todoist_links (
+            task_id INTEGER PRIMARY KEY REFERENCES tasks(id) ON DELETE CASCADE,
+            todoist_id TEXT NOT NULL UNIQUE,
+            synced_text TEXT NOT NULL,
+            synced_completed INTEGER NOT NULL
+        )",
+        [],
+    )?;
+
+    // Events pushed to Google Calendar, with the event body last sent. No
+    // foreign key: the event of a deleted task still has to be deleted there
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS google_calendar_events (
+            task_id INTEGER PRIMARY KEY,
+            event_id TEXT NOT NULL UNIQUE,
+            synced TEXT NOT NULL
+        )",
+        [],
+    )?;
+
+    // Issues filed from "github: ..." voice commands
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS task_issues (
+            task_id INTEGER PRIMARY KEY REFERENCES tasks(id) ON DELETE CASCADE,
+            issue_url TEXT NOT NULL
+        )",
+        [],
+    )?;
+
+    // Tasks pinned to stay in view
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS pinned_tasks (
+            task_id INTEGER PRIMARY KEY REFERENCES tasks(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+
+    // One pending reminder per task; remind_at is UTC like the other timestamps
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS reminders (
+            task_id INTEGER PRIMARY KEY REFERENCES tasks(id) ON DELETE CASCADE,
+            remind_at TEXT NOT NULL,
+            fired INTEGER NOT NULL DEFAULT 0
+        )",
+        [],
+    )?;
+
+    // Recurring habits and the local dates they were done on
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS habits (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL UNIQUE COLLATE NOCASE,
+            schedule TEXT NOT NULL,
+            created_at TEXT NOT NULL DEFAULT (datetime('now'))
+        )",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS habit_logs (
+            habit_id INTEGER NOT NULL REFERENCES habits(id) ON DELETE CASCADE,
+            date TEXT NOT NULL,
+            PRIMARY KEY (habit_id, date)
+        )",
+        [],
+    )?;
+
+    // One row per transcribed voice note, for the weekly report
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS transcriptions (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            source TEXT NOT NULL,
+            words INTEGER NOT NULL,
+            created_at TEXT NOT NULL DEFAULT (datetime('now'))
+        )",
+        [],
+    )?;
+
+    // The transcript itself and the parser's actions (JSON, see `history`), for re-parsing
+    if !has_column(conn, "transcriptions", "transcript")? {
+        conn.execute("ALTER TABLE transcriptions ADD COLUMN transcript TEXT", [])?;
+    }
+    if !has_column(conn, "transcriptions", "actions")? {
+        conn.execute("ALTER TABLE transcriptions ADD COLUMN actions TEXT", [])?;
+    }
+
+    // Timings of transcriptions and parser runs, for the usage metrics
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS usage_metrics (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            kind TEXT NOT NULL,
+            detail TEXT NOT NULL,
+            duration_ms INTEGER NOT NULL,
+            audio_ms INTEGER,
+            succeeded INTEGER NOT NULL DEFAULT 1,
+            created_at TEXT NOT NULL DEFAULT (datetime('now'))
+        )",
+        [],
+    )?;
+
+    // Voice macros: a spoken phrase and the actions it runs (JSON, see `macros`)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS macros (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            phrase TEXT NOT NULL UNIQUE COLLATE NOCASE,
+            actions TEXT NOT NULL,
+            created_at TEXT NOT NULL DEFAULT (datetime('now'))
+        )",
+        [],
+    )?;
+
+    // Achievements unlocked so far, by id (see `achievements`)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS achievements (
+            id TEXT PRIMARY KEY,
+            unlocked_at TEXT NOT NULL DEFAULT (datetime('now'))
+        )",
+        [],
+    )?;
+
+    // Tokens for network clients: the SHA-256 of each secret, never the secret (see `api_tokens`)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS api_tokens (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL,
+            token_hash TEXT NOT NULL UNIQUE,
+            prefix TEXT NOT NULL,
+            scopes TEXT NOT NULL,
+            created_at TEXT NOT NULL DEFAULT (datetime('now')),
+            last_used_at TEXT
+        )",
+        [],
+    )?;
+
     // Create settings table
     conn.execute(
         "CREATE TABLE IF NOT EXISTS settings (
@@ -66,53 +389,240 @@ fn init_tables(conn: &Connection) -> Result<()> {
         [],
     )?;
 
-    // Set default Ollama enabled to false (fast mode by default)
+    // The Telegram, MQTT and Todoist settings used to hold an API token in
+    // the clear; drop it so only the hashes in api_tokens are left
     conn.execute(
-        "INSERT OR IGNORE INTO settings (key, value) VALUES ('ollama_enabled', 'false')",
+        "UPDATE settings
+         SET value = json_remove(value, '$.telegram.api_token', '$.mqtt.api_token', '$.todoist.api_token')
+         WHERE json_valid(value)
+           AND (json_type(value, '$.telegram.api_token') IS NOT NULL
+             OR json_type(value, '$.mqtt.api_token') IS NOT NULL
+             OR json_type(value, '$.todoist.api_token') IS NOT NULL)",
         [],
     )?;
 
     Ok(())
 }
 
-#[cfg(feature = "tauri-ui")]
-pub fn init_database(app: &AppHandle) -> Result<Database> {
-    let app_data_dir = app.path()
-        .app_data_dir()
-        .expect("Failed to get app data directory");
+fn has_column(conn: &Connection, table: &str, column: &str) -> Result<bool> {
+    Ok(table_columns(conn, "main", table)?.iter().any(|name| name == column))
+}
 
-    std::fs::create_dir_all(&app_data_dir)
-        .expect("Failed to create app data directory");
+/// Whether an error means the file is damaged (rather than busy or unreadable)
+pub fn is_corruption(error: &rusqlite::Error) -> bool {
+    matches!(
+        error.sqlite_error_code(),
+        Some(rusqlite::ErrorCode::DatabaseCorrupt | rusqlite::ErrorCode::NotADatabase)
+    )
+}
 
-    let db_path = app_data_dir.join("flowstate.db");
-    let conn = Connection::open(db_path)?;
+/// Copy whatever can still be read from the damaged database at `damaged`
+/// into `db`, a table at a time (the columns both have). Returns the tables
+/// that couldn't be read.
+pub fn salvage_from(db: &Database, damaged: &Path) -> Result<Vec<String>> {
+    let conn = db.conn.lock().unwrap();
+    conn.execute("ATTACH DATABASE ?1 AS damaged", params![damaged.to_string_lossy()])?;
+    // Tables are copied in no particular order, so a row may come before the
+    // one it refers to
+    conn.pragma_update(None, "foreign_keys", false)?;
+    let tables = conn
+        .prepare("SELECT name FROM main.sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%'")?
+        .query_map([], |row| row.get::<_, String>(0))?
+        .collect::<Result<Vec<_>>>()?;
 
-    init_tables(&conn)?;
+    let mut lost = Vec::new();
+    for table in tables {
+        let copied = table_columns(&conn, "damaged", &table).and_then(|old| {
+            let shared: Vec<String> = table_columns(&conn, "main", &table)?
+                .into_iter()
+                .filter(|column| old.contains(column))
+                .map(|column| format!("\"{}\"", column))
+                .collect();
+            // A table the damaged file didn't have yet
+            if shared.is_empty() {
+                return Ok(0);
+            }
+            let columns = shared.join(", ");
+            conn.execute(
+                &format!("INSERT OR IGNORE INTO main.\"{table}\" ({columns}) SELECT {columns} FROM damaged.\"{table}\""),
+                [],
+            )
+        });
+        match copied {
+            Ok(rows) => tracing::info!("Recovered {} row(s) of {}", rows, table),
+            Err(e) => {
+                tracing::warn!("Couldn't recover {}: {}", table, e);
+                lost.push(table);
+            }
+        }
+    }
+    conn.execute("DETACH DATABASE damaged", [])?;
+    enable_foreign_keys(&conn)?;
+    Ok(lost)
+}
 
-    Ok(Database {
-        conn: Mutex::new(conn),
-    })
+fn table_columns(conn: &Connection, schema: &str, table: &str) -> Result<Vec<String>> {
+    let mut stmt = conn.prepare(&format!("PRAGMA {}.table_info(\"{}\")", schema, table))?;
+    let names = stmt
+        .query_map([], |row| row.get::<_, String>(1))?
+        .collect::<Result<Vec<_>>>()?;
+    Ok(names)
+}
+
+/// Which tasks a query returns
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TaskStatus {
+    /// Open tasks plus those completed in the last 7 days (the main list)
+    #[default]
+    Recent,
+    Open,
+    Completed,
+    /// Completed since local midnight
+    #[serde(rename = "completed_today")]
+    CompletedToday,
+    /// Open voice-captured tasks waiting to be triaged
+    Inbox,
+    /// Open tasks that are pinned
+    Pinned,
+    All,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskSort {
+    /// Open first; new tasks on top, then the manual (drag) order, then newest first
+    #[default]
+    Default,
+    Newest,
+    Oldest,
+    /// Most recently completed first
+    RecentlyCompleted,
+    Alphabetical,
+}
+
+impl TaskSort {
+    fn order_by(&self) -> &'static str {
+        match self {
+            TaskSort::Default => "completed ASC, position IS NOT NULL, position ASC, created_at DESC, id DESC",
+            TaskSort::Newest => "created_at DESC, id DESC",
+            TaskSort::Oldest => "created_at ASC, id ASC",
+            TaskSort::RecentlyCompleted => "completed_at IS NULL, completed_at DESC, id DESC",
+            TaskSort::Alphabetical => "text COLLATE NOCASE ASC, id ASC",
+        }
+    }
+}
+
+/// Filters for listing tasks. Projects and tags are written inline in the task
+/// text as `+project` and `#tag`, so they match on the text itself.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TaskQuery {
+    pub status: TaskStatus,
+    pub project: Option<String>,
+    pub tag: Option<String>,
+    /// Case-insensitive substring of the task text
+    pub search: Option<String>,
+    /// GTD context, with or without the "@"
+    pub context: Option<String>,
+    /// Set by the active profile to narrow the list
+    pub scope: Option<TaskScope>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+    pub sort: TaskSort,
+}
+
+/// Projects and tags a profile shows (without the "+" and "#"). Tasks with
+/// neither a project nor a tag are always shown, so fresh captures don't vanish.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TaskScope {
+    pub projects: Vec<String>,
+    pub tags: Vec<String>,
+}
+
+// Escape LIKE wildcards so user input matches literally
+fn like_pattern(prefix: &str, value: &str) -> String {
+    let escaped = value
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
+    format!("%{}{}%", prefix, escaped)
 }
 
 pub fn get_all_tasks(db: &Database) -> Result<Vec<Task>> {
-    let conn = db.conn.lock().unwrap();
-    let mut stmt = conn.prepare(
-        "SELECT id, text, completed, created_at, completed_at 
-         FROM tasks 
-         WHERE completed = 0 OR completed_at > datetime('now', '-7 days')
-         ORDER BY completed ASC, created_at DESC"
-    )?;
-    
-    let task_iter = stmt.query_map([], |row| {
-        Ok(Task {
-            id: row.get(0)?,
-            text: row.get(1)?,
-            completed: row.get::<_, i32>(2)? != 0,
-            created_at: row.get(3)?,
-            completed_at: row.get(4)?,
-        })
-    })?;
-    
+    query_tasks(db, &TaskQuery::default())
+}
+
+pub fn query_tasks(db: &Database, query: &TaskQuery) -> Result<Vec<Task>> {
+    let scope_condition;
+    let mut conditions: Vec<&str> = Vec::new();
+    let mut values: Vec<String> = Vec::new();
+
+    match query.status {
+        TaskStatus::Recent => {
+            conditions.push("((completed = 0 AND inbox = 0) OR completed_at > datetime('now', '-7 days'))")
+        }
+        TaskStatus::Open => conditions.push("completed = 0 AND inbox = 0"),
+        TaskStatus::Completed => conditions.push("completed = 1"),
+        // completed_at is UTC, so compare with local midnight converted to UTC
+        TaskStatus::CompletedToday => {
+            conditions.push("completed = 1 AND completed_at >= datetime('now', 'localtime', 'start of day', 'utc')")
+        }
+        TaskStatus::Inbox => conditions.push("completed = 0 AND inbox = 1"),
+        TaskStatus::Pinned => {
+            conditions.push("completed = 0 AND id IN (SELECT task_id FROM pinned_tasks)")
+        }
+        TaskStatus::All => {}
+    }
+    if query.status != TaskStatus::All {
+        conditions.push("archived_at IS NULL");
+    }
+    let filters = [("+", &query.project), ("#", &query.tag), ("", &query.search)];
+    for (prefix, value) in filters {
+        if let Some(value) = value.as_deref().map(str::trim).filter(|v| !v.is_empty()) {
+            conditions.push("text LIKE ? ESCAPE '\\'");
+            values.push(like_pattern(prefix, value));
+        }
+    }
+    if let Some(context) = query.context.as_deref().and_then(crate::contexts::normalize) {
+        conditions.push("context = ?");
+        values.push(context);
+    }
+    if let Some(scope) = &query.scope {
+        let patterns: Vec<String> = scope
+            .projects
+            .iter()
+            .map(|project| like_pattern("+", project))
+            .chain(scope.tags.iter().map(|tag| like_pattern("#", tag)))
+            .collect();
+        // A project or tag is a "+"/"#" at the start of a word
+        scope_condition = format!(
+            "({}((' ' || text) NOT LIKE '% +%' AND (' ' || text) NOT LIKE '% #%'))",
+            "text LIKE ? ESCAPE '\\' OR ".repeat(patterns.len()),
+        );
+        conditions.push(&scope_condition);
+        values.extend(patterns);
+    }
+
+    let mut sql = String::from("SELECT id, text, completed, created_at, completed_at, context, estimate_minutes, due_at, inbox FROM tasks");
+    if !conditions.is_empty() {
+        sql.push_str(" WHERE ");
+        sql.push_str(&conditions.join(" AND "));
+    }
+    sql.push_str(" ORDER BY ");
+    sql.push_str(query.sort.order_by());
+    // SQLite needs a LIMIT for OFFSET; -1 means no limit
+    sql.push_str(&format!(
+        " LIMIT {} OFFSET {}",
+        query.limit.filter(|l| *l >= 0).unwrap_or(-1),
+        query.offset.unwrap_or(0).max(0),
+    ));
+
+    let conn = db.reader();
+    let mut stmt = conn.prepare(&sql)?;
+    let task_iter = stmt.query_map(rusqlite::params_from_iter(values.iter()), task_from_row)?;
+
     let mut tasks = Vec::new();
     for task in task_iter {
         tasks.push(task?);
@@ -120,16 +630,27 @@ pub fn get_all_tasks(db: &Database) -> Result<Vec<Task>> {
     Ok(tasks)
 }
 
+// The task text without its estimate and context, and those two
+fn split_task_text(text: &str) -> (String, Option<String>, Option<i64>) {
+    let (text, estimate) = crate::estimates::split_estimate(text);
+    let (text, context) = crate::contexts::split_context(&text);
+    (text, context, estimate)
+}
+
+/// Add an open task; an "@context" or spoken context phrase in `text` goes
+/// into the context column, a trailing estimate ("about an hour") into
+/// estimate_minutes
 pub fn add_task(db: &Database, text: &str) -> Result<Task> {
+    let (text, context, estimate) = split_task_text(text);
     let conn = db.conn.lock().unwrap();
     conn.execute(
-        "INSERT INTO tasks (text, completed) VALUES (?1, 0)",
-        params![text],
+        "INSERT INTO tasks (text, completed, context, estimate_minutes) VALUES (?1, 0, ?2, ?3)",
+        params![text, context, estimate],
     )?;
     
     let id = conn.last_insert_rowid();
     let mut stmt = conn.prepare(
-        "SELECT id, text, completed, created_at, completed_at FROM tasks WHERE id = ?1"
+        "SELECT id, text, completed, created_at, completed_at, context, estimate_minutes, due_at, inbox FROM tasks WHERE id = ?1"
     )?;
     stmt.query_row(params![id], |row| {
         Ok(Task {
@@ -138,15 +659,33 @@ pub fn add_task(db: &Database, text: &str) -> Result<Task> {
             completed: row.get::<_, i32>(2)? != 0,
             created_at: row.get(3)?,
             completed_at: row.get(4)?,
+            context: row.get(5)?,
+            estimate_minutes: row.get(6)?,
+            due_at: row.get(7)?,
+            inbox: row.get::<_, i32>(8)? != 0,
         })
     })
 }
 
+/// Change a task's text; an "@context" or estimate typed into it replaces the
+/// task's, otherwise they are kept
 pub fn update_task(db: &Database, id: i64, text: &str) -> Result<()> {
+    let (text, context, estimate) = split_task_text(text);
     let conn = db.conn.lock().unwrap();
     conn.execute(
-        "UPDATE tasks SET text = ?1 WHERE id = ?2",
-        params![text, id],
+        "UPDATE tasks SET text = ?1, context = COALESCE(?2, context),
+                          estimate_minutes = COALESCE(?3, estimate_minutes) WHERE id = ?4",
+        params![text, context, estimate, id],
+    )?;
+    Ok(())
+}
+
+/// Set or clear when a task is due (UTC, as in `Task::due_at`)
+pub fn set_task_due(db: &Database, id: i64, due_at: Option<&str>) -> Result<()> {
+    let conn = db.conn.lock().unwrap();
+    conn.execute(
+        "UPDATE tasks SET due_at = ?1 WHERE id = ?2",
+        params![due_at, id],
     )?;
     Ok(())
 }
@@ -157,6 +696,29 @@ pub fn delete_task(db: &Database, id: i64) -> Result<()> {
     Ok(())
 }
 
+/// Put a deleted task back with its original id and timestamps (for undo).
+/// Its manual position is not restored, its time entries stay unlinked, and
+/// its reminder, Todoist link and issue link went with the delete.
+pub fn restore_task(db: &Database, task: &Task) -> Result<()> {
+    let conn = db.conn.lock().unwrap();
+    conn.execute(
+        "INSERT INTO tasks (id, text, completed, created_at, completed_at, context, estimate_minutes, due_at, inbox)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+        params![
+            task.id,
+            task.text,
+            task.completed as i32,
+            task.created_at,
+            task.completed_at,
+            task.context,
+            task.estimate_minutes,
+            task.due_at,
+            task.inbox as i32
+        ],
+    )?;
+    Ok(())
+}
+
 pub fn toggle_task(db: &Database, id: i64) -> Result<Task> {
     let conn = db.conn.lock().unwrap();
     
@@ -173,13 +735,13 @@ pub fn toggle_task(db: &Database, id: i64) -> Result<Task> {
     };
     
     conn.execute(
-        "UPDATE tasks SET completed = ?1, completed_at = ?2 WHERE id = ?3",
+        "UPDATE tasks SET completed = ?1, completed_at = ?2, archived_at = NULL WHERE id = ?3",
         params![new_state, completed_at, id],
     )?;
     
     // Return updated task
     let mut stmt = conn.prepare(
-        "SELECT id, text, completed, created_at, completed_at FROM tasks WHERE id = ?1"
+        "SELECT id, text, completed, created_at, completed_at, context, estimate_minutes, due_at, inbox FROM tasks WHERE id = ?1"
     )?;
     stmt.query_row(params![id], |row| {
         Ok(Task {
@@ -188,31 +750,232 @@ pub fn toggle_task(db: &Database, id: i64) -> Result<Task> {
             completed: row.get::<_, i32>(2)? != 0,
             created_at: row.get(3)?,
             completed_at: row.get(4)?,
+            context: row.get(5)?,
+            estimate_minutes: row.get(6)?,
+            due_at: row.get(7)?,
+            inbox: row.get::<_, i32>(8)? != 0,
         })
     })
 }
 
+fn task_from_row(row: &rusqlite::Row) -> Result<Task> {
+    Ok(Task {
+        id: row.get(0)?,
+        text: row.get(1)?,
+        completed: row.get::<_, i32>(2)? != 0,
+        created_at: row.get(3)?,
+        completed_at: row.get(4)?,
+        context: row.get(5)?,
+        estimate_minutes: row.get(6)?,
+        due_at: row.get(7)?,
+        inbox: row.get::<_, i32>(8)? != 0,
+    })
+}
+
+/// Insert several tasks in one transaction (all or nothing)
+pub fn add_tasks(db: &Database, texts: &[String]) -> Result<Vec<Task>> {
+    let tasks: Vec<(String, Option<String>)> = texts.iter().map(|text| (text.clone(), None)).collect();
+    add_tasks_with_due(db, &tasks, false)
+}
+
+/// `add_tasks` for tasks that may have a due time (UTC, as in `due_at`),
+/// optionally into the inbox
+pub fn add_tasks_with_due(db: &Database, tasks_due: &[(String, Option<String>)], inbox: bool) -> Result<Vec<Task>> {
+    let mut conn = db.conn.lock().unwrap();
+    let tx = conn.transaction()?;
+    let mut tasks = Vec::with_capacity(tasks_due.len());
+    {
+        let mut insert = tx.prepare(
+            "INSERT INTO tasks (text, completed, context, estimate_minutes, due_at, inbox) VALUES (?1, 0, ?2, ?3, ?4, ?5)"
+        )?;
+        let mut select = tx.prepare(
+            "SELECT id, text, completed, created_at, completed_at, context, estimate_minutes, due_at, inbox FROM tasks WHERE id = ?1"
+        )?;
+        for (text, due_at) in tasks_due {
+            let (text, context, estimate) = split_task_text(text);
+            let id = insert.insert(params![text, context, estimate, due_at, inbox as i32])?;
+            tasks.push(select.query_row(params![id], task_from_row)?);
+        }
+    }
+    tx.commit()?;
+    Ok(tasks)
+}
+
+/// Delete several tasks in one transaction, returning the rows that existed
+pub fn delete_tasks(db: &Database, ids: &[i64]) -> Result<Vec<Task>> {
+    let mut conn = db.conn.lock().unwrap();
+    let tx = conn.transaction()?;
+    let mut tasks = Vec::with_capacity(ids.len());
+    {
+        let mut select = tx.prepare(
+            "SELECT id, text, completed, created_at, completed_at, context, estimate_minutes, due_at, inbox FROM tasks WHERE id = ?1"
+        )?;
+        let mut delete = tx.prepare("DELETE FROM tasks WHERE id = ?1")?;
+        for id in ids {
+            if let Some(task) = select.query_row(params![id], task_from_row).optional()? {
+                delete.execute(params![id])?;
+                tasks.push(task);
+            }
+        }
+    }
+    tx.commit()?;
+    Ok(tasks)
+}
+
+/// Complete several tasks in one transaction, returning the rows that changed
+/// (missing and already-completed tasks are skipped)
+pub fn complete_tasks(db: &Database, ids: &[i64]) -> Result<Vec<Task>> {
+    let completed_at = chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string();
+    let mut conn = db.conn.lock().unwrap();
+    let tx = conn.transaction()?;
+    let mut tasks = Vec::with_capacity(ids.len());
+    {
+        let mut update = tx.prepare(
+            "UPDATE tasks SET completed = 1, completed_at = ?1 WHERE id = ?2 AND completed = 0"
+        )?;
+        let mut select = tx.prepare(
+            "SELECT id, text, completed, created_at, completed_at, context, estimate_minutes, due_at, inbox FROM tasks WHERE id = ?1"
+        )?;
+        for id in ids {
+            if update.execute(params![completed_at, id])? > 0 {
+                tasks.push(select.query_row(params![id], task_from_row)?);
+            }
+        }
+    }
+    tx.commit()?;
+    Ok(tasks)
+}
+
+/// Move tasks out of the inbox, returning the ones that were in it
+pub fn commit_inbox_tasks(db: &Database, ids: &[i64]) -> Result<Vec<Task>> {
+    let mut conn = db.conn.lock().unwrap();
+    let tx = conn.transaction()?;
+    let mut tasks = Vec::with_capacity(ids.len());
+    {
+        let mut update = tx.prepare("UPDATE tasks SET inbox = 0 WHERE id = ?1 AND inbox = 1")?;
+        let mut select = tx.prepare(
+            "SELECT id, text, completed, created_at, completed_at, context, estimate_minutes, due_at, inbox FROM tasks WHERE id = ?1"
+        )?;
+        for id in ids {
+            if update.execute(params![id])? > 0 {
+                tasks.push(select.query_row(params![id], task_from_row)?);
+            }
+        }
+    }
+    tx.commit()?;
+    Ok(tasks)
+}
+
+/// Move a task out of the inbox with a due time (UTC)
+pub fn schedule_inbox_task(db: &Database, id: i64, due_at: &str) -> Result<Task> {
+    let conn = db.conn.lock().unwrap();
+    conn.execute("UPDATE tasks SET inbox = 0, due_at = ?1 WHERE id = ?2", params![due_at, id])?;
+    conn.query_row(
+        "SELECT id, text, completed, created_at, completed_at, context, estimate_minutes, due_at, inbox FROM tasks WHERE id = ?1",
+        params![id],
+        task_from_row,
+    )
+}
+
+pub fn count_inbox_tasks(db: &Database) -> Result<usize> {
+    let conn = db.reader();
+    conn.query_row("SELECT COUNT(*) FROM tasks WHERE completed = 0 AND inbox = 1", [], |row| row.get::<_, i64>(0))
+        .map(|count| count as usize)
+}
+
+// `prefix` + name as one inline word, or None for a blank name
+fn inline_word(prefix: char, name: &str) -> Option<String> {
+    let words: Vec<&str> = name.trim_start_matches(prefix).split_whitespace().collect();
+    (!words.is_empty()).then(|| format!("{}{}", prefix, words.join("-")))
+}
+
+/// Task text with its `+project` words replaced by `project`, or dropped for None
+pub fn with_project(text: &str, project: Option<&str>) -> String {
+    let mut words: Vec<String> = text
+        .split_whitespace()
+        .filter(|w| !(w.starts_with('+') && w.len() > 1))
+        .map(str::to_string)
+        .collect();
+    words.extend(project.and_then(|p| inline_word('+', p)));
+    words.join(" ")
+}
+
+/// Task text with `#tag` appended, unless it already has it
+pub fn with_tag(text: &str, tag: &str) -> String {
+    match inline_word('#', tag) {
+        Some(tag) if !text.split_whitespace().any(|w| w.eq_ignore_ascii_case(&tag)) => {
+            format!("{} {}", text.trim_end(), tag)
+        }
+        _ => text.to_string(),
+    }
+}
+
+// Rewrite the text of several tasks in one transaction, returning the rows that changed
+fn edit_tasks(db: &Database, ids: &[i64], edit: impl Fn(&str) -> String) -> Result<Vec<Task>> {
+    let mut conn = db.conn.lock().unwrap();
+    let tx = conn.transaction()?;
+    let mut tasks = Vec::with_capacity(ids.len());
+    {
+        let mut select = tx.prepare(
+            "SELECT id, text, completed, created_at, completed_at, context, estimate_minutes, due_at, inbox FROM tasks WHERE id = ?1"
+        )?;
+        let mut update = tx.prepare("UPDATE tasks SET text = ?1 WHERE id = ?2")?;
+        for id in ids {
+            if let Some(mut task) = select.query_row(params![id], task_from_row).optional()? {
+                let text = edit(&task.text);
+                if text != task.text {
+                    update.execute(params![text, id])?;
+                    task.text = text;
+                    tasks.push(task);
+                }
+            }
+        }
+    }
+    tx.commit()?;
+    Ok(tasks)
+}
+
+/// Move several tasks to `project` (None takes them out of any project) in one
+/// transaction, returning the rows that changed
+pub fn set_tasks_project(db: &Database, ids: &[i64], project: Option<&str>) -> Result<Vec<Task>> {
+    edit_tasks(db, ids, |text| with_project(text, project))
+}
+
+/// Tag several tasks with `#tag` in one transaction, returning the rows that changed
+pub fn tag_tasks(db: &Database, ids: &[i64], tag: &str) -> Result<Vec<Task>> {
+    edit_tasks(db, ids, |text| with_tag(text, tag))
+}
+
 pub fn find_and_complete_task(db: &Database, text: &str) -> Result<Task> {
     let conn = db.conn.lock().unwrap();
     
     // Try to find matching task (fuzzy match)
     let search_pattern = format!("%{}%", text);
     let mut stmt = conn.prepare(
-        "SELECT id, text, completed, created_at, completed_at 
+        "SELECT id, text, completed, created_at, completed_at, context, estimate_minutes, due_at, inbox
          FROM tasks 
          WHERE text LIKE ?1 AND completed = 0 
          LIMIT 1"
     )?;
     
-    if let Ok(task) = stmt.query_row(params![search_pattern], |row| {
+    let found = stmt.query_row(params![search_pattern], |row| {
         Ok(Task {
             id: row.get(0)?,
             text: row.get(1)?,
             completed: row.get::<_, i32>(2)? != 0,
             created_at: row.get(3)?,
             completed_at: row.get(4)?,
+            context: row.get(5)?,
+            estimate_minutes: row.get(6)?,
+            due_at: row.get(7)?,
+            inbox: row.get::<_, i32>(8)? != 0,
         })
-    }) {
+    });
+    // toggle_task and add_task take the connection themselves
+    drop(stmt);
+    drop(conn);
+
+    if let Ok(task) = found {
         // Mark as completed
         toggle_task(db, task.id)?;
         get_task_by_id(db, task.id)
@@ -225,10 +988,26 @@ pub fn find_and_complete_task(db: &Database, text: &str) -> Result<Task> {
     }
 }
 
-fn get_task_by_id(db: &Database, id: i64) -> Result<Task> {
-    let conn = db.conn.lock().unwrap();
+/// Open tasks whose text contains `text` (any case), the exact match first,
+/// then the newest
+pub fn find_open_tasks_matching(db: &Database, text: &str, limit: usize) -> Result<Vec<Task>> {
+    let conn = db.reader();
+    let text = text.trim().to_lowercase();
     let mut stmt = conn.prepare(
-        "SELECT id, text, completed, created_at, completed_at FROM tasks WHERE id = ?1"
+        "SELECT id, text, completed, created_at, completed_at, context, estimate_minutes, due_at, inbox
+         FROM tasks
+         WHERE LOWER(text) LIKE ?1 AND completed = 0
+         ORDER BY CASE WHEN LOWER(text) = ?2 THEN 0 ELSE 1 END, created_at DESC, id DESC
+         LIMIT ?3"
+    )?;
+    let rows = stmt.query_map(params![format!("%{}%", text), text, limit as i64], task_from_row)?;
+    rows.collect()
+}
+
+pub fn get_task_by_id(db: &Database, id: i64) -> Result<Task> {
+    let conn = db.reader();
+    let mut stmt = conn.prepare(
+        "SELECT id, text, completed, created_at, completed_at, context, estimate_minutes, due_at, inbox FROM tasks WHERE id = ?1"
     )?;
     stmt.query_row(params![id], |row| {
         Ok(Task {
@@ -237,6 +1016,10 @@ fn get_task_by_id(db: &Database, id: i64) -> Result<Task> {
             completed: row.get::<_, i32>(2)? != 0,
             created_at: row.get(3)?,
             completed_at: row.get(4)?,
+            context: row.get(5)?,
+            estimate_minutes: row.get(6)?,
+            due_at: row.get(7)?,
+            inbox: row.get::<_, i32>(8)? != 0,
         })
     })
 }
@@ -248,7 +1031,7 @@ pub fn find_and_delete_task(db: &Database, search_text: &str) -> Result<Option<T
     // Try to find matching task (fuzzy match using LIKE)
     let search_pattern = format!("%{}%", search_text.to_lowercase());
     let mut stmt = conn.prepare(
-        "SELECT id, text, completed, created_at, completed_at
+        "SELECT id, text, completed, created_at, completed_at, context, estimate_minutes, due_at, inbox
          FROM tasks
          WHERE LOWER(text) LIKE ?1
          ORDER BY
@@ -266,6 +1049,10 @@ pub fn find_and_delete_task(db: &Database, search_text: &str) -> Result<Option<T
             completed: row.get::<_, i32>(2)? != 0,
             created_at: row.get(3)?,
             completed_at: row.get(4)?,
+            context: row.get(5)?,
+            estimate_minutes: row.get(6)?,
+            due_at: row.get(7)?,
+            inbox: row.get::<_, i32>(8)? != 0,
         })
     }) {
         // Delete the task
@@ -277,21 +1064,1041 @@ pub fn find_and_delete_task(db: &Database, search_text: &str) -> Result<Option<T
     }
 }
 
-pub fn get_ollama_enabled(db: &Database) -> Result<bool> {
-    let conn = db.conn.lock().unwrap();
-    let value: String = conn.query_row(
-        "SELECT value FROM settings WHERE key = 'ollama_enabled'",
-        [],
+/// Read a raw value from the settings table
+pub fn get_setting(db: &Database, key: &str) -> Result<Option<String>> {
+    let conn = db.reader();
+    match conn.query_row(
+        "SELECT value FROM settings WHERE key = ?1",
+        params![key],
         |row| row.get(0),
-    ).unwrap_or_else(|_| "false".to_string());
-    Ok(value == "true")
+    ) {
+        Ok(value) => Ok(Some(value)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(e),
+    }
 }
 
-pub fn set_ollama_enabled(db: &Database, enabled: bool) -> Result<()> {
+/// Write a raw value to the settings table
+pub fn set_setting(db: &Database, key: &str, value: &str) -> Result<()> {
     let conn = db.conn.lock().unwrap();
     conn.execute(
-        "INSERT OR REPLACE INTO settings (key, value) VALUES ('ollama_enabled', ?1)",
-        params![if enabled { "true" } else { "false" }],
+        "INSERT OR REPLACE INTO settings (key, value) VALUES (?1, ?2)",
+        params![key, value],
     )?;
     Ok(())
 }
+
+/// Remove a value from the settings table
+pub fn delete_setting(db: &Database, key: &str) -> Result<()> {
+    let conn = db.conn.lock().unwrap();
+    conn.execute("DELETE FROM settings WHERE key = ?1", params![key])?;
+    Ok(())
+}
+
+/// Problems reported by `PRAGMA integrity_check` (empty when the database is healthy)
+pub fn integrity_check(db: &Database) -> Result<Vec<String>> {
+    let conn = db.conn.lock().unwrap();
+    let mut stmt = conn.prepare("PRAGMA integrity_check")?;
+    let rows = stmt
+        .query_map([], |row| row.get::<_, String>(0))?
+        .collect::<Result<Vec<_>>>()?;
+    Ok(rows.into_iter().filter(|row| row != "ok").collect())
+}
+
+/// Store a manual order: `ids` are placed in sequence, in one transaction.
+/// Tasks not listed keep their position (new tasks have none and stay on top).
+pub fn reorder_tasks(db: &Database, ids: &[i64]) -> Result<()> {
+    let mut conn = db.conn.lock().unwrap();
+    let tx = conn.transaction()?;
+    {
+        let mut update = tx.prepare("UPDATE tasks SET position = ?1 WHERE id = ?2")?;
+        for (position, id) in ids.iter().enumerate() {
+            update.execute(params![position as i64, id])?;
+        }
+    }
+    tx.commit()
+}
+
+/// First open task in list order, used as the body of timer notifications
+pub fn get_top_open_task(db: &Database) -> Result<Option<Task>> {
+    Ok(get_all_tasks(db)?.into_iter().find(|t| !t.completed))
+}
+
+/// Number of open tasks, for the MQTT status
+pub fn count_open_tasks(db: &Database) -> Result<usize> {
+    let conn = db.reader();
+    conn.query_row("SELECT COUNT(*) FROM tasks WHERE completed = 0", [], |row| row.get::<_, i64>(0))
+        .map(|count| count as usize)
+}
+
+/// A task with the focus time spent on it, for questions about the history
+#[derive(Debug, Clone)]
+pub struct TaskHistory {
+    pub task: Task,
+    pub focus_seconds: i64,
+}
+
+/// Contexts of open tasks with how many each has, by name
+pub fn task_contexts(db: &Database) -> Result<Vec<(String, i64)>> {
+    let conn = db.reader();
+    let mut stmt = conn.prepare(
+        "SELECT context, COUNT(*) FROM tasks
+         WHERE completed = 0 AND context IS NOT NULL
+         GROUP BY context ORDER BY context"
+    )?;
+    let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+    rows.collect()
+}
+
+/// Every task with its logged focus time, most recently touched first
+pub fn get_task_history(db: &Database) -> Result<Vec<TaskHistory>> {
+    let conn = db.reader();
+    let mut stmt = conn.prepare(
+        "SELECT t.id, t.text, t.completed, t.created_at, t.completed_at, t.context, t.estimate_minutes, t.due_at, t.inbox,
+                COALESCE((SELECT SUM(duration_seconds) FROM time_entries WHERE task_id = t.id), 0)
+         FROM tasks t
+         ORDER BY COALESCE(t.completed_at, t.created_at) DESC, t.id DESC"
+    )?;
+    let rows = stmt.query_map([], |row| {
+        Ok(TaskHistory {
+            task: task_from_row(row)?,
+            focus_seconds: row.get(9)?,
+        })
+    })?;
+    rows.collect()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimeEntry {
+    pub id: i64,
+    pub task_id: Option<i64>,
+    pub task_text: String,
+    pub started_at: String,
+    pub ended_at: String,
+    pub duration_seconds: i64,
+    /// Set when the focus monitor was on for the session
+    pub context_switches: Option<ContextSwitches>,
+}
+
+/// Record a completed focus session against a task (by text only if the
+/// task was deleted meanwhile)
+pub fn log_time_entry(
+    db: &Database,
+    task_id: i64,
+    task_text: &str,
+    started_at: &str,
+    ended_at: &str,
+    duration_seconds: i64,
+    context_switches: Option<&ContextSwitches>,
+) -> Result<()> {
+    let top_apps = context_switches
+        .map(|switches| serde_json::to_string(&switches.top_apps).unwrap_or_default());
+    let conn = db.conn.lock().unwrap();
+    conn.execute(
+        "INSERT INTO time_entries (task_id, task_text, started_at, ended_at, duration_seconds, context_switches, top_apps)
+         VALUES ((SELECT id FROM tasks WHERE id = ?1), ?2, ?3, ?4, ?5, ?6, ?7)",
+        params![
+            task_id,
+            task_text,
+            started_at,
+            ended_at,
+            duration_seconds,
+            context_switches.map(|switches| switches.switches),
+            top_apps,
+        ],
+    )?;
+    Ok(())
+}
+
+pub fn get_time_entries_for_task(db: &Database, task_id: i64) -> Result<Vec<TimeEntry>> {
+    let conn = db.reader();
+    let mut stmt = conn.prepare(
+        "SELECT id, task_id, task_text, started_at, ended_at, duration_seconds, context_switches, top_apps
+         FROM time_entries
+         WHERE task_id = ?1
+         ORDER BY started_at DESC"
+    )?;
+
+    let entry_iter = stmt.query_map(params![task_id], time_entry_from_row)?;
+
+    let mut entries = Vec::new();
+    for entry in entry_iter {
+        entries.push(entry?);
+    }
+    Ok(entries)
+}
+
+fn time_entry_from_row(row: &rusqlite::Row) -> Result<TimeEntry> {
+    Ok(TimeEntry {
+        id: row.get(0)?,
+        task_id: row.get(1)?,
+        task_text: row.get(2)?,
+        started_at: row.get(3)?,
+        ended_at: row.get(4)?,
+        duration_seconds: row.get(5)?,
+        context_switches: row.get::<_, Option<u32>>(6)?.map(|switches| ContextSwitches {
+            switches,
+            top_apps: row
+                .get::<_, Option<String>>(7)
+                .ok()
+                .flatten()
+                .and_then(|json| serde_json::from_str(&json).ok())
+                .unwrap_or_default(),
+        }),
+    })
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JournalEntry {
+    pub id: i64,
+    pub text: String,
+    pub interval_start: String,
+    pub interval_end: String,
+    pub created_at: String,
+}
+
+/// Record what the user said they did during a timer interval
+pub fn add_journal_entry(db: &Database, text: &str, interval_start: &str, interval_end: &str) -> Result<JournalEntry> {
+    let conn = db.conn.lock().unwrap();
+    conn.execute(
+        "INSERT INTO journal_entries (text, interval_start, interval_end) VALUES (?1, ?2, ?3)",
+        params![text, interval_start, interval_end],
+    )?;
+
+    let id = conn.last_insert_rowid();
+    let created_at: String = conn.query_row(
+        "SELECT created_at FROM journal_entries WHERE id = ?1",
+        params![id],
+        |row| row.get(0),
+    )?;
+
+    Ok(JournalEntry {
+        id,
+        text: text.to_string(),
+        interval_start: interval_start.to_string(),
+        interval_end: interval_end.to_string(),
+        created_at,
+    })
+}
+
+pub fn get_journal_entries(db: &Database, limit: i64) -> Result<Vec<JournalEntry>> {
+    let conn = db.reader();
+    let mut stmt = conn.prepare(
+        "SELECT id, text, interval_start, interval_end, created_at
+         FROM journal_entries
+         ORDER BY interval_end DESC
+         LIMIT ?1"
+    )?;
+
+    let entry_iter = stmt.query_map(params![limit], |row| {
+        Ok(JournalEntry {
+            id: row.get(0)?,
+            text: row.get(1)?,
+            interval_start: row.get(2)?,
+            interval_end: row.get(3)?,
+            created_at: row.get(4)?,
+        })
+    })?;
+
+    let mut entries = Vec::new();
+    for entry in entry_iter {
+        entries.push(entry?);
+    }
+    Ok(entries)
+}
+
+/// Record an expired timer interval and the task that was in focus
+pub fn log_session(
+    db: &Database,
+    started_at: &str,
+    ended_at: &str,
+    task_id: Option<i64>,
+    task_text: Option<&str>,
+) -> Result<i64> {
+    let conn = db.conn.lock().unwrap();
+    conn.execute(
+        "INSERT INTO sessions (started_at, ended_at, task_id, task_text)
+         VALUES (?1, ?2, (SELECT id FROM tasks WHERE id = ?3), ?4)",
+        params![started_at, ended_at, task_id, task_text],
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+/// Mark a checkpoint as responded to (first response wins)
+pub fn acknowledge_session(db: &Database, id: i64) -> Result<()> {
+    let conn = db.conn.lock().unwrap();
+    conn.execute(
+        "UPDATE sessions SET acknowledged = 1, acknowledged_at = datetime('now')
+         WHERE id = ?1 AND acknowledged = 0",
+        params![id],
+    )?;
+    Ok(())
+}
+
+/// Per-day checkpoint response rate and focused time
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FocusDay {
+    pub date: String,
+    pub checkpoints: i64,
+    pub acknowledged: i64,
+    pub focus_seconds: i64,
+}
+
+pub fn get_focus_history(db: &Database, days: u32) -> Result<Vec<FocusDay>> {
+    let conn = db.reader();
+    let since = format!("-{} days", days.saturating_sub(1));
+
+    let mut history: Vec<FocusDay> = Vec::new();
+    {
+        let mut stmt = conn.prepare(
+            "SELECT date(ended_at, 'localtime') AS day, COUNT(*), SUM(acknowledged)
+             FROM sessions
+             WHERE date(ended_at, 'localtime') >= date('now', 'localtime', ?1)
+             GROUP BY day"
+        )?;
+        let rows = stmt.query_map(params![since], |row| {
+            Ok(FocusDay {
+                date: row.get(0)?,
+                checkpoints: row.get(1)?,
+                acknowledged: row.get(2)?,
+                focus_seconds: 0,
+            })
+        })?;
+        for row in rows {
+            history.push(row?);
+        }
+    }
+
+    let mut stmt = conn.prepare(
+        "SELECT date(ended_at, 'localtime') AS day, SUM(duration_seconds)
+         FROM time_entries
+         WHERE date(ended_at, 'localtime') >= date('now', 'localtime', ?1)
+         GROUP BY day"
+    )?;
+    let rows = stmt.query_map(params![since], |row| {
+        Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
+    })?;
+    for row in rows {
+        let (date, seconds) = row?;
+        match history.iter_mut().find(|d| d.date == date) {
+            Some(day) => day.focus_seconds = seconds,
+            None => history.push(FocusDay {
+                date,
+                checkpoints: 0,
+                acknowledged: 0,
+                focus_seconds: seconds,
+            }),
+        }
+    }
+
+    history.sort_by(|a, b| b.date.cmp(&a.date));
+    Ok(history)
+}
+
+/// A recurring habit (see `habits`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Habit {
+    pub id: i64,
+    pub name: String,
+    /// "daily" or weekday abbreviations, e.g. "mon,wed,fri"
+    pub schedule: String,
+    pub created_at: String,
+}
+
+fn habit_from_row(row: &rusqlite::Row) -> Result<Habit> {
+    Ok(Habit {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        schedule: row.get(2)?,
+        created_at: row.get(3)?,
+    })
+}
+
+/// Habits in the order they were added
+pub fn get_habits(db: &Database) -> Result<Vec<Habit>> {
+    let conn = db.reader();
+    let mut stmt = conn.prepare("SELECT id, name, schedule, created_at FROM habits ORDER BY id")?;
+    let rows = stmt.query_map([], habit_from_row)?;
+    rows.collect()
+}
+
+pub fn get_habit(db: &Database, id: i64) -> Result<Habit> {
+    let conn = db.reader();
+    conn.query_row(
+        "SELECT id, name, schedule, created_at FROM habits WHERE id = ?1",
+        params![id],
+        habit_from_row,
+    )
+}
+
+pub fn add_habit(db: &Database, name: &str, schedule: &str) -> Result<Habit> {
+    let conn = db.conn.lock().unwrap();
+    conn.execute(
+        "INSERT INTO habits (name, schedule) VALUES (?1, ?2)",
+        params![name, schedule],
+    )?;
+    conn.query_row(
+        "SELECT id, name, schedule, created_at FROM habits WHERE id = ?1",
+        params![conn.last_insert_rowid()],
+        habit_from_row,
+    )
+}
+
+pub fn delete_habit(db: &Database, id: i64) -> Result<()> {
+    let conn = db.conn.lock().unwrap();
+    // Its log goes with it (ON DELETE CASCADE)
+    conn.execute("DELETE FROM habits WHERE id = ?1", params![id])?;
+    Ok(())
+}
+
+/// Local dates ("YYYY-MM-DD") the habit was done on, newest first
+pub fn habit_log_dates(db: &Database, habit_id: i64) -> Result<Vec<String>> {
+    let conn = db.reader();
+    let mut stmt = conn.prepare("SELECT date FROM habit_logs WHERE habit_id = ?1 ORDER BY date DESC")?;
+    let rows = stmt.query_map(params![habit_id], |row| row.get(0))?;
+    rows.collect()
+}
+
+/// Mark the habit done (or not) on a local date
+pub fn set_habit_done(db: &Database, habit_id: i64, date: &str, done: bool) -> Result<()> {
+    let conn = db.conn.lock().unwrap();
+    if done {
+        conn.execute(
+            "INSERT OR IGNORE INTO habit_logs (habit_id, date) VALUES (?1, ?2)",
+            params![habit_id, date],
+        )?;
+    } else {
+        conn.execute(
+            "DELETE FROM habit_logs WHERE habit_id = ?1 AND date = ?2",
+            params![habit_id, date],
+        )?;
+    }
+    Ok(())
+}
+
+/// A voice macro as stored; `actions` is the JSON list `macros` reads
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MacroRow {
+    pub id: i64,
+    pub phrase: String,
+    pub actions: String,
+    pub created_at: String,
+}
+
+fn macro_from_row(row: &rusqlite::Row) -> Result<MacroRow> {
+    Ok(MacroRow {
+        id: row.get(0)?,
+        phrase: row.get(1)?,
+        actions: row.get(2)?,
+        created_at: row.get(3)?,
+    })
+}
+
+/// Macros in the order they were added
+pub fn get_macros(db: &Database) -> Result<Vec<MacroRow>> {
+    let conn = db.reader();
+    let mut stmt = conn.prepare("SELECT id, phrase, actions, created_at FROM macros ORDER BY id")?;
+    let rows = stmt.query_map([], macro_from_row)?;
+    rows.collect()
+}
+
+pub fn add_macro(db: &Database, phrase: &str, actions: &str) -> Result<MacroRow> {
+    let conn = db.conn.lock().unwrap();
+    conn.execute(
+        "INSERT INTO macros (phrase, actions) VALUES (?1, ?2)",
+        params![phrase, actions],
+    )?;
+    conn.query_row(
+        "SELECT id, phrase, actions, created_at FROM macros WHERE id = ?1",
+        params![conn.last_insert_rowid()],
+        macro_from_row,
+    )
+}
+
+pub fn update_macro(db: &Database, id: i64, phrase: &str, actions: &str) -> Result<MacroRow> {
+    let conn = db.conn.lock().unwrap();
+    conn.execute(
+        "UPDATE macros SET phrase = ?1, actions = ?2 WHERE id = ?3",
+        params![phrase, actions, id],
+    )?;
+    conn.query_row(
+        "SELECT id, phrase, actions, created_at FROM macros WHERE id = ?1",
+        params![id],
+        macro_from_row,
+    )
+}
+
+pub fn delete_macro(db: &Database, id: i64) -> Result<()> {
+    let conn = db.conn.lock().unwrap();
+    conn.execute("DELETE FROM macros WHERE id = ?1", params![id])?;
+    Ok(())
+}
+
+/// An API token as stored; `scopes` is the comma-separated list `api_tokens` reads
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiTokenRow {
+    pub id: i64,
+    pub name: String,
+    /// The first characters of the secret, to tell tokens apart
+    pub prefix: String,
+    pub scopes: String,
+    pub created_at: String,
+    pub last_used_at: Option<String>,
+}
+
+const API_TOKEN_COLUMNS: &str = "id, name, prefix, scopes, created_at, last_used_at";
+
+fn api_token_from_row(row: &rusqlite::Row) -> Result<ApiTokenRow> {
+    Ok(ApiTokenRow {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        prefix: row.get(2)?,
+        scopes: row.get(3)?,
+        created_at: row.get(4)?,
+        last_used_at: row.get(5)?,
+    })
+}
+
+/// API tokens in the order they were issued
+pub fn get_api_tokens(db: &Database) -> Result<Vec<ApiTokenRow>> {
+    let conn = db.reader();
+    let mut stmt = conn.prepare(&format!("SELECT {} FROM api_tokens ORDER BY id", API_TOKEN_COLUMNS))?;
+    let rows = stmt.query_map([], api_token_from_row)?;
+    rows.collect()
+}
+
+pub fn add_api_token(db: &Database, name: &str, token_hash: &str, prefix: &str, scopes: &str) -> Result<ApiTokenRow> {
+    let conn = db.conn.lock().unwrap();
+    conn.execute(
+        "INSERT INTO api_tokens (name, token_hash, prefix, scopes) VALUES (?1, ?2, ?3, ?4)",
+        params![name, token_hash, prefix, scopes],
+    )?;
+    conn.query_row(
+        &format!("SELECT {} FROM api_tokens WHERE id = ?1", API_TOKEN_COLUMNS),
+        params![conn.last_insert_rowid()],
+        api_token_from_row,
+    )
+}
+
+/// The token with this hash, marked as used now
+pub fn use_api_token(db: &Database, token_hash: &str) -> Result<Option<ApiTokenRow>> {
+    let conn = db.conn.lock().unwrap();
+    conn.execute(
+        "UPDATE api_tokens SET last_used_at = datetime('now') WHERE token_hash = ?1",
+        params![token_hash],
+    )?;
+    conn.query_row(
+        &format!("SELECT {} FROM api_tokens WHERE token_hash = ?1", API_TOKEN_COLUMNS),
+        params![token_hash],
+        api_token_from_row,
+    )
+    .optional()
+}
+
+/// Whether a token with this id was there to delete
+pub fn delete_api_token(db: &Database, id: i64) -> Result<bool> {
+    let conn = db.conn.lock().unwrap();
+    Ok(conn.execute("DELETE FROM api_tokens WHERE id = ?1", params![id])? > 0)
+}
+
+/// Archive every completed task still in the lists, returning how many
+pub fn archive_completed(db: &Database) -> Result<usize> {
+    let conn = db.conn.lock().unwrap();
+    conn.execute(
+        "UPDATE tasks SET archived_at = datetime('now') WHERE completed = 1 AND archived_at IS NULL",
+        [],
+    )
+}
+
+/// Unlocked achievement ids with when they were unlocked (UTC)
+pub fn unlocked_achievements(db: &Database) -> Result<std::collections::HashMap<String, String>> {
+    let conn = db.reader();
+    let mut stmt = conn.prepare("SELECT id, unlocked_at FROM achievements")?;
+    let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+    rows.collect()
+}
+
+/// Unlock an achievement; false if it already was
+pub fn unlock_achievement(db: &Database, id: &str) -> Result<bool> {
+    let conn = db.conn.lock().unwrap();
+    let inserted = conn.execute("INSERT OR IGNORE INTO achievements (id) VALUES (?1)", params![id])?;
+    Ok(inserted > 0)
+}
+
+/// Tasks ever completed, archived ones included
+pub fn count_completed_tasks(db: &Database) -> Result<i64> {
+    let conn = db.reader();
+    conn.query_row("SELECT COUNT(*) FROM tasks WHERE completed = 1", [], |row| row.get(0))
+}
+
+/// Local dates ("YYYY-MM-DD") a task was completed on, newest first
+pub fn completion_dates(db: &Database) -> Result<Vec<String>> {
+    let conn = db.reader();
+    let mut stmt = conn.prepare(
+        "SELECT DISTINCT date(completed_at, 'localtime') AS day FROM tasks
+         WHERE completed = 1 AND completed_at IS NOT NULL
+         ORDER BY day DESC"
+    )?;
+    let rows = stmt.query_map([], |row| row.get(0))?;
+    rows.collect()
+}
+
+/// Voice notes transcribed, from any source
+pub fn count_transcriptions(db: &Database) -> Result<i64> {
+    let conn = db.reader();
+    conn.query_row("SELECT COUNT(*) FROM transcriptions", [], |row| row.get(0))
+}
+
+/// Record that a voice note was transcribed; `source` is where it came from
+/// ("recording", "journal", "import", "telegram"). `actions` is the JSON list
+/// of what the parser took from it (see `history`), None when it wasn't parsed.
+pub fn log_transcription(db: &Database, source: &str, transcript: &str, actions: Option<&str>) -> Result<()> {
+    let words = transcript.split_whitespace().count() as i64;
+    let conn = db.conn.lock().unwrap();
+    conn.execute(
+        "INSERT INTO transcriptions (source, words, transcript, actions) VALUES (?1, ?2, ?3, ?4)",
+        params![source, words, transcript, actions],
+    )?;
+    Ok(())
+}
+
+/// A parsed voice note as stored; `actions` is the JSON list `history` reads
+#[derive(Debug, Clone)]
+pub struct TranscriptionRow {
+    pub id: i64,
+    pub source: String,
+    pub transcript: String,
+    pub actions: String,
+    pub created_at: String,
+}
+
+fn transcription_from_row(row: &rusqlite::Row) -> Result<TranscriptionRow> {
+    Ok(TranscriptionRow {
+        id: row.get(0)?,
+        source: row.get(1)?,
+        transcript: row.get(2)?,
+        actions: row.get(3)?,
+        created_at: row.get(4)?,
+    })
+}
+
+/// Parsed voice notes transcribed in [start, end) (UTC timestamps; None leaves
+/// that end open), oldest first. Notes from before transcripts were kept, and
+/// ones a command handled, have nothing to re-parse and are left out.
+pub fn parsed_transcriptions_between(db: &Database, start: Option<&str>, end: Option<&str>) -> Result<Vec<TranscriptionRow>> {
+    let conn = db.reader();
+    let mut stmt = conn.prepare(
+        "SELECT id, source, transcript, actions, created_at FROM transcriptions
+         WHERE transcript IS NOT NULL AND actions IS NOT NULL
+           AND (?1 IS NULL OR created_at >= ?1) AND (?2 IS NULL OR created_at < ?2)
+         ORDER BY created_at, id"
+    )?;
+    let rows = stmt.query_map(params![start, end], transcription_from_row)?;
+    rows.collect()
+}
+
+pub fn get_parsed_transcription(db: &Database, id: i64) -> Result<TranscriptionRow> {
+    let conn = db.reader();
+    conn.query_row(
+        "SELECT id, source, transcript, actions, created_at FROM transcriptions
+         WHERE id = ?1 AND transcript IS NOT NULL AND actions IS NOT NULL",
+        params![id],
+        transcription_from_row,
+    )
+}
+
+/// Replace the actions kept for a note, after its re-parse was applied
+pub fn set_transcription_actions(db: &Database, id: i64, actions: &str) -> Result<()> {
+    let conn = db.conn.lock().unwrap();
+    conn.execute("UPDATE transcriptions SET actions = ?1 WHERE id = ?2", params![actions, id])?;
+    Ok(())
+}
+
+/// Transcriptions from one source in a period
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranscriptionStats {
+    pub source: String,
+    pub count: i64,
+    pub words: i64,
+}
+
+/// One timed step of the voice pipeline, for `metrics`
+pub fn log_metric(db: &Database, kind: &str, detail: &str, duration_ms: i64, audio_ms: Option<i64>, succeeded: bool) -> Result<()> {
+    let conn = db.conn.lock().unwrap();
+    conn.execute(
+        "INSERT INTO usage_metrics (kind, detail, duration_ms, audio_ms, succeeded) VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![kind, detail, duration_ms, audio_ms, succeeded],
+    )?;
+    Ok(())
+}
+
+/// Logged steps of one kind and detail, split by outcome
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetricStats {
+    pub kind: String,
+    pub detail: String,
+    pub succeeded: bool,
+    pub count: i64,
+    pub total_ms: i64,
+    pub audio_ms: i64,
+}
+
+/// Logged steps since `start` (a UTC timestamp; None for all of them)
+pub fn metric_stats_since(db: &Database, start: Option<&str>) -> Result<Vec<MetricStats>> {
+    let conn = db.reader();
+    let mut stmt = conn.prepare(
+        "SELECT kind, detail, succeeded, COUNT(*), SUM(duration_ms), COALESCE(SUM(audio_ms), 0) FROM usage_metrics
+         WHERE ?1 IS NULL OR created_at >= ?1
+         GROUP BY kind, detail, succeeded
+         ORDER BY kind, detail"
+    )?;
+    let rows = stmt.query_map(params![start], |row| {
+        Ok(MetricStats {
+            kind: row.get(0)?,
+            detail: row.get(1)?,
+            succeeded: row.get(2)?,
+            count: row.get(3)?,
+            total_ms: row.get(4)?,
+            audio_ms: row.get(5)?,
+        })
+    })?;
+    rows.collect()
+}
+
+/// Tasks completed in [start, end) (UTC timestamps), oldest first
+pub fn completed_tasks_between(db: &Database, start: &str, end: &str) -> Result<Vec<Task>> {
+    let conn = db.reader();
+    let mut stmt = conn.prepare(
+        "SELECT id, text, completed, created_at, completed_at, context, estimate_minutes, due_at, inbox FROM tasks
+         WHERE completed = 1 AND completed_at >= ?1 AND completed_at < ?2
+         ORDER BY completed_at"
+    )?;
+    let rows = stmt.query_map(params![start, end], task_from_row)?;
+    rows.collect()
+}
+
+/// Focus sessions that ended in [start, end) (UTC timestamps), oldest first
+pub fn time_entries_between(db: &Database, start: &str, end: &str) -> Result<Vec<TimeEntry>> {
+    let conn = db.reader();
+    let mut stmt = conn.prepare(
+        "SELECT id, task_id, task_text, started_at, ended_at, duration_seconds, context_switches, top_apps
+         FROM time_entries
+         WHERE ended_at >= ?1 AND ended_at < ?2
+         ORDER BY ended_at"
+    )?;
+    let rows = stmt.query_map(params![start, end], time_entry_from_row)?;
+    rows.collect()
+}
+
+/// Checkpoints (expired intervals) in [start, end) and how many were responded to
+pub fn checkpoint_counts_between(db: &Database, start: &str, end: &str) -> Result<(i64, i64)> {
+    let conn = db.reader();
+    conn.query_row(
+        "SELECT COUNT(*), COALESCE(SUM(acknowledged), 0) FROM sessions WHERE ended_at >= ?1 AND ended_at < ?2",
+        params![start, end],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )
+}
+
+/// Transcriptions per source in [start, end), busiest source first
+pub fn transcription_stats_between(db: &Database, start: &str, end: &str) -> Result<Vec<TranscriptionStats>> {
+    let conn = db.reader();
+    let mut stmt = conn.prepare(
+        "SELECT source, COUNT(*), SUM(words) FROM transcriptions
+         WHERE created_at >= ?1 AND created_at < ?2
+         GROUP BY source
+         ORDER BY COUNT(*) DESC"
+    )?;
+    let rows = stmt.query_map(params![start, end], |row| {
+        Ok(TranscriptionStats {
+            source: row.get(0)?,
+            count: row.get(1)?,
+            words: row.get(2)?,
+        })
+    })?;
+    rows.collect()
+}
+
+/// A FlowState task paired with a Todoist task (see `sync::todoist`)
+#[derive(Debug, Clone)]
+pub struct TodoistLink {
+    pub task_id: i64,
+    pub todoist_id: String,
+    /// Text and completion both sides had after the last sync
+    pub synced_text: String,
+    pub synced_completed: bool,
+}
+
+pub fn todoist_links(db: &Database) -> Result<Vec<TodoistLink>> {
+    let conn = db.reader();
+    let mut stmt = conn.prepare(
+        "SELECT task_id, todoist_id, synced_text, synced_completed FROM todoist_links"
+    )?;
+    let links = stmt.query_map([], |row| {
+        Ok(TodoistLink {
+            task_id: row.get(0)?,
+            todoist_id: row.get(1)?,
+            synced_text: row.get(2)?,
+            synced_completed: row.get::<_, i32>(3)? != 0,
+        })
+    })?;
+    links.collect()
+}
+
+pub fn save_todoist_link(db: &Database, link: &TodoistLink) -> Result<()> {
+    let conn = db.conn.lock().unwrap();
+    conn.execute(
+        "INSERT OR REPLACE INTO todoist_links (task_id, todoist_id, synced_text, synced_completed)
+         VALUES (?1, ?2, ?3, ?4)",
+        params![link.task_id, link.todoist_id, link.synced_text, link.synced_completed as i32],
+    )?;
+    Ok(())
+}
+
+pub fn delete_todoist_link(db: &Database, task_id: i64) -> Result<()> {
+    let conn = db.conn.lock().unwrap();
+    conn.execute("DELETE FROM todoist_links WHERE task_id = ?1", params![task_id])?;
+    Ok(())
+}
+
+/// Open tasks not yet pushed to Todoist, oldest first
+pub fn unlinked_open_tasks(db: &Database) -> Result<Vec<Task>> {
+    let conn = db.reader();
+    let mut stmt = conn.prepare(
+        "SELECT id, text, completed, created_at, completed_at, context, estimate_minutes, due_at, inbox FROM tasks
+         WHERE completed = 0 AND id NOT IN (SELECT task_id FROM todoist_links)
+         ORDER BY id"
+    )?;
+    let tasks = stmt.query_map([], task_from_row)?;
+    tasks.collect()
+}
+
+/// A task's event in Google Calendar (see `sync::google_calendar`)
+#[derive(Debug, Clone)]
+pub struct GoogleCalendarEvent {
+    pub task_id: i64,
+    pub event_id: String,
+    /// The event body as last pushed, to tell when the task changed
+    pub synced: String,
+}
+
+pub fn google_calendar_events(db: &Database) -> Result<Vec<GoogleCalendarEvent>> {
+    let conn = db.reader();
+    let mut stmt = conn.prepare("SELECT task_id, event_id, synced FROM google_calendar_events")?;
+    let events = stmt.query_map([], |row| {
+        Ok(GoogleCalendarEvent {
+            task_id: row.get(0)?,
+            event_id: row.get(1)?,
+            synced: row.get(2)?,
+        })
+    })?;
+    events.collect()
+}
+
+pub fn save_google_calendar_event(db: &Database, event: &GoogleCalendarEvent) -> Result<()> {
+    let conn = db.conn.lock().unwrap();
+    conn.execute(
+        "INSERT OR REPLACE INTO google_calendar_events (task_id, event_id, synced) VALUES (?1, ?2, ?3)",
+        params![event.task_id, event.event_id, event.synced],
+    )?;
+    Ok(())
+}
+
+pub fn delete_google_calendar_event(db: &Database, task_id: i64) -> Result<()> {
+    let conn = db.conn.lock().unwrap();
+    conn.execute("DELETE FROM google_calendar_events WHERE task_id = ?1", params![task_id])?;
+    Ok(())
+}
+
+pub fn set_task_issue_url(db: &Database, task_id: i64, url: &str) -> Result<()> {
+    let conn = db.conn.lock().unwrap();
+    conn.execute(
+        "INSERT OR REPLACE INTO task_issues (task_id, issue_url) VALUES (?1, ?2)",
+        params![task_id, url],
+    )?;
+    Ok(())
+}
+
+/// Issue URL of every task that has one, by task id
+pub fn task_issue_urls(db: &Database) -> Result<std::collections::HashMap<i64, String>> {
+    let conn = db.reader();
+    let mut stmt = conn.prepare("SELECT task_id, issue_url FROM task_issues")?;
+    let urls = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+    urls.collect()
+}
+
+pub fn set_task_pinned(db: &Database, task_id: i64, pinned: bool) -> Result<()> {
+    let conn = db.conn.lock().unwrap();
+    if pinned {
+        conn.execute(
+            "INSERT OR IGNORE INTO pinned_tasks (task_id) VALUES (?1)",
+            params![task_id],
+        )?;
+    } else {
+        conn.execute(
+            "DELETE FROM pinned_tasks WHERE task_id = ?1",
+            params![task_id],
+        )?;
+    }
+    Ok(())
+}
+
+/// Ids of the pinned tasks
+pub fn pinned_task_ids(db: &Database) -> Result<std::collections::HashSet<i64>> {
+    let conn = db.reader();
+    let mut stmt = conn.prepare("SELECT task_id FROM pinned_tasks")?;
+    let ids = stmt.query_map([], |row| row.get(0))?;
+    ids.collect()
+}
+
+/// A reminder on an open task (see `reminders`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Reminder {
+    pub task_id: i64,
+    pub task_text: String,
+    /// UTC, "YYYY-MM-DD HH:MM:SS"
+    pub remind_at: String,
+}
+
+fn reminder_from_row(row: &rusqlite::Row) -> Result<Reminder> {
+    Ok(Reminder {
+        task_id: row.get(0)?,
+        task_text: row.get(1)?,
+        remind_at: row.get(2)?,
+    })
+}
+
+/// Set (or move) the task's reminder; a moved reminder fires again
+pub fn set_reminder(db: &Database, task_id: i64, remind_at: &str) -> Result<()> {
+    let conn = db.conn.lock().unwrap();
+    conn.execute(
+        "INSERT OR REPLACE INTO reminders (task_id, remind_at, fired) VALUES (?1, ?2, 0)",
+        params![task_id, remind_at],
+    )?;
+    Ok(())
+}
+
+pub fn clear_reminder(db: &Database, task_id: i64) -> Result<()> {
+    let conn = db.conn.lock().unwrap();
+    conn.execute("DELETE FROM reminders WHERE task_id = ?1", params![task_id])?;
+    Ok(())
+}
+
+/// Reminders that haven't fired yet, soonest first. Completed tasks drop out
+/// here rather than being cleaned up, so reopening one brings its reminder
+/// back; deleting a task deletes its reminder.
+pub fn pending_reminders(db: &Database) -> Result<Vec<Reminder>> {
+    let conn = db.reader();
+    let mut stmt = conn.prepare(
+        "SELECT r.task_id, t.text, r.remind_at FROM reminders r
+         JOIN tasks t ON t.id = r.task_id
+         WHERE r.fired = 0 AND t.completed = 0
+         ORDER BY r.remind_at",
+    )?;
+    let rows = stmt.query_map([], reminder_from_row)?;
+    rows.collect()
+}
+
+/// Pending reminders on open tasks due at or before `now` (UTC), marked fired
+/// in the same transaction so each one goes off once. Reminders of completed
+/// tasks are left pending, so they still go off if the task is reopened.
+pub fn take_due_reminders(db: &Database, now: &str) -> Result<Vec<Reminder>> {
+    let mut conn = db.conn.lock().unwrap();
+    let tx = conn.transaction()?;
+    let due = {
+        let mut stmt = tx.prepare(
+            "SELECT r.task_id, t.text, r.remind_at FROM reminders r
+             JOIN tasks t ON t.id = r.task_id
+             WHERE r.fired = 0 AND t.completed = 0 AND r.remind_at <= ?1
+             ORDER BY r.remind_at",
+        )?;
+        let rows = stmt.query_map(params![now], reminder_from_row)?;
+        rows.collect::<Result<Vec<_>>>()?
+    };
+    tx.execute(
+        "UPDATE reminders SET fired = 1
+         WHERE fired = 0 AND remind_at <= ?1
+           AND task_id IN (SELECT id FROM tasks WHERE completed = 0)",
+        params![now],
+    )?;
+    tx.commit()?;
+    Ok(due)
+}
+
+/// Archive completed tasks finished before `cutoff` (UTC), returning how many
+pub fn archive_completed_before(db: &Database, cutoff: &str) -> Result<usize> {
+    let conn = db.conn.lock().unwrap();
+    conn.execute(
+        "UPDATE tasks SET archived_at = datetime('now')
+         WHERE completed = 1 AND archived_at IS NULL AND completed_at < ?1",
+        params![cutoff],
+    )
+}
+
+/// Reminders on open tasks at or before `now` (UTC), fired or not
+pub fn overdue_reminders(db: &Database, now: &str) -> Result<Vec<Reminder>> {
+    let conn = db.reader();
+    let mut stmt = conn.prepare(
+        "SELECT r.task_id, t.text, r.remind_at FROM reminders r
+         JOIN tasks t ON t.id = r.task_id
+         WHERE t.completed = 0 AND r.remind_at <= ?1
+         ORDER BY r.remind_at",
+    )?;
+    let rows = stmt.query_map(params![now], reminder_from_row)?;
+    rows.collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn salvage_copies_the_readable_tasks() {
+        let path = std::env::temp_dir().join(format!("flowstate-salvage-{}.db", std::process::id()));
+        let old = Database::open(&path).unwrap();
+        add_task(&old, "Call mom").unwrap();
+        drop(old);
+
+        let db = Database::new_in_memory().unwrap();
+        let lost = salvage_from(&db, &path).unwrap();
+        for suffix in ["", "-wal", "-shm"] {
+            let _ = std::fs::remove_file(format!("{}{}", path.display(), suffix));
+        }
+
+        assert!(lost.is_empty());
+        let tasks = get_all_tasks(&db).unwrap();
+        assert_eq!(tasks.iter().map(|task| task.text.as_str()).collect::<Vec<_>>(), ["Call mom"]);
+    }
+
+    #[test]
+    fn the_pinned_filter_lists_open_pinned_tasks() {
+        let db = Database::new_in_memory().unwrap();
+        let pinned = add_task(&db, "Renew the passport").unwrap();
+        let other = add_task(&db, "Buy coffee").unwrap();
+        set_task_pinned(&db, pinned.id, true).unwrap();
+        // Pinning twice is harmless
+        set_task_pinned(&db, pinned.id, true).unwrap();
+        let query = TaskQuery {
+            status: TaskStatus::Pinned,
+            ..Default::default()
+        };
+        let ids = |db: &Database| {
+            let tasks = query_tasks(db, &query).unwrap();
+            tasks.iter().map(|t| t.id).collect::<Vec<_>>()
+        };
+        assert_eq!(ids(&db), vec![pinned.id]);
+
+        // Done tasks leave the filter but stay pinned
+        toggle_task(&db, pinned.id).unwrap();
+        assert!(ids(&db).is_empty());
+        toggle_task(&db, pinned.id).unwrap();
+        assert_eq!(ids(&db), vec![pinned.id]);
+
+        set_task_pinned(&db, pinned.id, false).unwrap();
+        set_task_pinned(&db, other.id, true).unwrap();
+        assert_eq!(ids(&db), vec![other.id]);
+
+        delete_task(&db, other.id).unwrap();
+        assert!(pinned_task_ids(&db).unwrap().is_empty());
+    }
+}
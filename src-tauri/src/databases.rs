@@ -0,0 +1,110 @@
+// Databases module
+// Separate task databases side by side in the data folder, e.g. one for work
+// and one for personal tasks: "work" is `work.db`, and the default one is the
+// usual `flowstate.db`. Each keeps its own tasks, history and settings. One is
+// open at a time; switching swaps the managed `Database` in place, and the one
+// last used is noted in the data folder so the next launch opens it again.
+// Not to be confused with `profiles`, which are modes within one database.
+
+use crate::database::{self, Database};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Name of the database in `flowstate.db`
+pub const DEFAULT: &str = "default";
+// Holds the name of the database last switched to
+const LAST_USED_FILE: &str = "last_database";
+const MAX_NAME_LEN: usize = 40;
+
+/// The databases in the data folder and the open one
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DatabaseList {
+    pub current: String,
+    /// Always has the default database first, then the others by name
+    pub databases: Vec<String>,
+}
+
+/// File name of the database called `name`
+pub fn file_name(name: &str) -> String {
+    if name == DEFAULT {
+        database::FILE_NAME.to_string()
+    } else {
+        format!("{}.db", name)
+    }
+}
+
+/// `name` trimmed and lowercased, if it's usable as a file name: letters,
+/// digits, "-" and "_" (not "default", which is taken)
+pub fn validate_name(name: &str) -> Result<String, String> {
+    let name = name.trim().to_lowercase();
+    if name.is_empty() {
+        return Err(crate::i18n::t("error.database_name_empty"));
+    }
+    let valid = name.chars().count() <= MAX_NAME_LEN
+        && name.chars().all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+        // Both would be the default database under another name
+        && name != DEFAULT
+        && file_name(&name) != database::FILE_NAME;
+    if !valid {
+        return Err(crate::i18n::tf("error.database_name_invalid", &[("name", &name)]));
+    }
+    Ok(name)
+}
+
+/// The databases in `dir`
+pub fn list(dir: &Path, current: &str) -> DatabaseList {
+    let mut names: Vec<String> = std::fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .filter_map(|entry| entry.file_name().to_str().and_then(|name| name.strip_suffix(".db")).map(str::to_string))
+                .filter(|name| validate_name(name).as_deref() == Ok(name.as_str()))
+                .collect()
+        })
+        .unwrap_or_default();
+    names.sort();
+    names.insert(0, DEFAULT.to_string());
+    DatabaseList {
+        current: current.to_string(),
+        databases: names,
+    }
+}
+
+/// Name of the database `db` has open (the default one when it's in memory)
+pub fn current(db: &Database) -> String {
+    let conn = db.conn.lock().unwrap();
+    let file = conn.path().and_then(|path| Path::new(path).file_name()?.to_str().map(str::to_string));
+    match file.as_deref().and_then(|file| file.strip_suffix(".db")) {
+        Some(name) if file.as_deref() != Some(database::FILE_NAME) => name.to_string(),
+        _ => DEFAULT.to_string(),
+    }
+}
+
+/// Name of the database to open at launch: the last one switched to
+pub fn last_used(dir: &Path) -> String {
+    std::fs::read_to_string(dir.join(LAST_USED_FILE))
+        .ok()
+        .and_then(|name| validate_name(&name).ok())
+        .unwrap_or_else(|| DEFAULT.to_string())
+}
+
+/// Close the database `db` has open and open (or create) the one called
+/// `name` in `dir` in its place, remembering it for the next launch.
+/// Returns the name as saved.
+pub fn switch(db: &Database, dir: &Path, name: &str) -> Result<String, String> {
+    let name = if name.trim().eq_ignore_ascii_case(DEFAULT) { DEFAULT.to_string() } else { validate_name(name)? };
+    if name != current(db) {
+        let opened = Database::open(&dir.join(file_name(&name)))
+            .map_err(|e| crate::i18n::tf("error.database_open", &[("name", &name), ("error", &e)]))?;
+        db.replace_with(opened);
+        // flowstate.toml is shared, so its defaults apply to this database too
+        if let Err(e) = crate::config_file::apply(db) {
+            tracing::warn!("{}", e);
+        }
+    }
+    if let Err(e) = std::fs::write(dir.join(LAST_USED_FILE), &name) {
+        tracing::warn!("Failed to remember the database: {}", e);
+    }
+    tracing::info!("Database: {}", name);
+    Ok(name)
+}
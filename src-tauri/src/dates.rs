@@ -0,0 +1,203 @@
+// Dates module
+// Due dates said as part of a task, for the rule-based parser: "call the bank
+// tomorrow", "send the report by Friday at 3pm", "renew the passport next
+// week", "позвонить в банк в пятницу", "сдать отчёт через два дня". The phrase
+// is taken off the end (or the start) of the task text and kept as `due_at`.
+// Clock times and "in 20 minutes" are read by `reminders::parse_when`; this
+// adds the days it doesn't know. A due date with no time means the end of
+// that day.
+
+use chrono::{Datelike, Duration, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Timelike, Utc, Weekday};
+
+const DB_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+// Longest date phrase looked for at either end of a task, in words
+const MAX_WORDS: usize = 6;
+// Stand-in time for a date said without one; shown as just the date
+const END_OF_DAY: (u32, u32) = (23, 59);
+// Words before a date phrase that go with it ("by Friday", "до пятницы")
+const CONNECTORS: [&str; 9] = ["by", "on", "due", "until", "before", "for", "до", "к", "ко"];
+// Words that don't change the day ("this Friday", "next Tuesday", "в эту пятницу")
+const NEAREST: [&str; 9] = ["this", "next", "coming", "эту", "этот", "это", "следующий", "следующую", "следующее"];
+
+// Weekday names as said after "on", "в", "до" and "к"
+const WEEKDAYS: [(&str, Weekday); 31] = [
+    ("monday", Weekday::Mon),
+    ("tuesday", Weekday::Tue),
+    ("wednesday", Weekday::Wed),
+    ("thursday", Weekday::Thu),
+    ("friday", Weekday::Fri),
+    ("saturday", Weekday::Sat),
+    ("sunday", Weekday::Sun),
+    ("понедельник", Weekday::Mon),
+    ("понедельника", Weekday::Mon),
+    ("понедельнику", Weekday::Mon),
+    ("вторник", Weekday::Tue),
+    ("вторника", Weekday::Tue),
+    ("вторнику", Weekday::Tue),
+    ("среда", Weekday::Wed),
+    ("среду", Weekday::Wed),
+    ("среды", Weekday::Wed),
+    ("среде", Weekday::Wed),
+    ("четверг", Weekday::Thu),
+    ("четверга", Weekday::Thu),
+    ("четвергу", Weekday::Thu),
+    ("пятница", Weekday::Fri),
+    ("пятницу", Weekday::Fri),
+    ("пятницы", Weekday::Fri),
+    ("пятнице", Weekday::Fri),
+    ("суббота", Weekday::Sat),
+    ("субботу", Weekday::Sat),
+    ("субботы", Weekday::Sat),
+    ("субботе", Weekday::Sat),
+    ("воскресенье", Weekday::Sun),
+    ("воскресенья", Weekday::Sun),
+    ("воскресенью", Weekday::Sun),
+];
+
+/// The task text without its due date phrase, and the local due time. The
+/// text is returned as given when there's no phrase, or nothing else in it.
+pub fn split_due(text: &str, now: NaiveDateTime) -> (String, Option<NaiveDateTime>) {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let max = MAX_WORDS.min(words.len().saturating_sub(1));
+    // Longest phrase first, so "next Friday at 3pm" isn't read as just "3pm"
+    let found = (1..=max)
+        .rev()
+        .find_map(|n| parse_due(&words[words.len() - n..].join(" "), now).map(|at| (&words[..words.len() - n], at)))
+        .or_else(|| (1..=max).rev().find_map(|n| parse_due(&words[..n].join(" "), now).map(|at| (&words[n..], at))));
+    let Some((rest, at)) = found else {
+        return (text.to_string(), None);
+    };
+
+    let mut rest = rest.to_vec();
+    while rest.last().is_some_and(|word| CONNECTORS.contains(&word.trim_matches(',').to_lowercase().as_str())) {
+        rest.pop();
+    }
+    let rest = rest.join(" ").trim_matches([',', ' ']).to_string();
+    let mut chars = rest.chars();
+    match chars.next() {
+        Some(first) => (first.to_uppercase().chain(chars).collect(), Some(at)),
+        None => (text.to_string(), None),
+    }
+}
+
+/// Local due time for a phrase like "tomorrow", "friday at 3pm", "next week",
+/// "in 3 days", "послезавтра", "в следующий вторник", "через неделю", or a
+/// date ("2026-05-12")
+pub fn parse_due(phrase: &str, now: NaiveDateTime) -> Option<NaiveDateTime> {
+    let phrase = phrase.trim().trim_end_matches(['.', '!', '?', ',']).to_lowercase();
+    // A picked date ("2026-05-12") is due at the end of that day
+    if let Ok(date) = NaiveDate::parse_from_str(&phrase, "%Y-%m-%d") {
+        return Some(end_of_day(date));
+    }
+    let mut words: Vec<&str> = phrase.split_whitespace().map(|word| word.trim_matches(',')).collect();
+    while words.first().is_some_and(|word| CONNECTORS.contains(word)) {
+        words.remove(0);
+    }
+    if words.is_empty() {
+        return None;
+    }
+    let today = now.date();
+
+    // "in 3 days", "через неделю": whole days land at the end of the day
+    if matches!(words[0], "in" | "через") {
+        let duration = crate::reminders::parse_duration(&words[1..])?;
+        let at = now + duration;
+        return Some(if duration.num_hours() >= 24 { end_of_day(at.date()) } else { at });
+    }
+
+    let (day, rest) = match words.as_slice() {
+        ["today", rest @ ..] | ["сегодня", rest @ ..] => (today, rest),
+        ["tomorrow", rest @ ..] | ["завтра", rest @ ..] => (today + Duration::days(1), rest),
+        ["day", "after", "tomorrow", rest @ ..] | ["послезавтра", rest @ ..] => (today + Duration::days(2), rest),
+        ["next", "week"] | ["на", "следующей", "неделе"] => return Some(end_of_day(next_weekday(today, Weekday::Mon))),
+        ["this", "weekend"] | ["the", "weekend"] | ["на", "выходных"] => {
+            return Some(end_of_day(next_or_today(today, Weekday::Sat)));
+        }
+        _ => {
+            // "friday", "on friday", "next friday", "в пятницу", "во вторник", "в следующий вторник"
+            let start = words.iter().take_while(|word| matches!(**word, "в" | "во") || NEAREST.contains(*word)).count();
+            match WEEKDAYS.iter().find(|(name, _)| words.get(start) == Some(name)) {
+                Some((_, weekday)) => (next_weekday(today, *weekday), &words[start + 1..]),
+                // "tonight", "at 6pm", "сегодня вечером"
+                None => return crate::reminders::parse_when(&words.join(" "), now),
+            }
+        }
+    };
+
+    if rest.is_empty() {
+        return Some(end_of_day(day)).filter(|at| *at > now);
+    }
+    // A clock time after the day ("tomorrow at 9", "в пятницу в 15:00") is on that day
+    let midnight = day.and_time(NaiveTime::MIN);
+    crate::reminders::parse_when(&rest.join(" "), midnight).filter(|at| at.date() == day && *at > now)
+}
+
+/// A local due time as stored in `tasks.due_at`
+pub fn to_db(at: NaiveDateTime) -> Option<String> {
+    crate::reminders::to_utc(at).map(|utc| utc.format(DB_FORMAT).to_string())
+}
+
+/// A stored `due_at` in local time
+pub fn from_db(due_at: &str) -> Option<NaiveDateTime> {
+    let utc = NaiveDateTime::parse_from_str(due_at, DB_FORMAT).ok()?;
+    Some(Utc.from_utc_datetime(&utc).with_timezone(&Local).naive_local())
+}
+
+/// Short label for a stored `due_at`: "Tomorrow", "Fr", "Fr 15:00", "12 May"
+/// (day and month names in the UI language)
+pub fn label(due_at: &str) -> Option<String> {
+    from_db(due_at).map(|at| due_label(at, Local::now().naive_local()))
+}
+
+fn due_label(at: NaiveDateTime, now: NaiveDateTime) -> String {
+    let days = (at.date() - now.date()).num_days();
+    let date = if days == 0 {
+        crate::i18n::t("dates.today")
+    } else if days == 1 {
+        crate::i18n::t("dates.tomorrow")
+    } else if (2..7).contains(&days) {
+        weekday_name(at.weekday())
+    } else {
+        format!("{} {}", at.day(), month_name(at.month()))
+    };
+    if has_time(at) {
+        format!("{} {}", date, at.format("%H:%M"))
+    } else {
+        date
+    }
+}
+
+/// Short weekday name in the UI language ("Fr", "Пт"); chrono's `%a` is English only
+pub fn weekday_name(day: Weekday) -> String {
+    const KEYS: [&str; 7] = ["day.mo", "day.tu", "day.we", "day.th", "day.fr", "day.sa", "day.su"];
+    crate::i18n::t(KEYS[day.num_days_from_monday() as usize])
+}
+
+/// Short month name (1-12) in the UI language, as it reads after a day
+/// number ("May", "мая")
+pub fn month_name(month: u32) -> String {
+    crate::i18n::t(&format!("month.{}", month))
+}
+
+/// Whether the due time was said, rather than the end of the day standing in
+pub fn has_time(at: NaiveDateTime) -> bool {
+    (at.hour(), at.minute()) != END_OF_DAY
+}
+
+fn end_of_day(date: NaiveDate) -> NaiveDateTime {
+    date.and_hms_opt(END_OF_DAY.0, END_OF_DAY.1, 0).expect("valid time")
+}
+
+// The next `weekday` after today (a week away when it's today)
+fn next_weekday(today: NaiveDate, weekday: Weekday) -> NaiveDate {
+    let ahead = (weekday.num_days_from_monday() + 7 - today.weekday().num_days_from_monday()) % 7;
+    today + Duration::days(if ahead == 0 { 7 } else { ahead.into() })
+}
+
+fn next_or_today(today: NaiveDate, weekday: Weekday) -> NaiveDate {
+    if today.weekday() == weekday {
+        today
+    } else {
+        next_weekday(today, weekday)
+    }
+}
@@ -0,0 +1,294 @@
+// Diagnostics module
+// Health checks behind "Run checks" in Settings: microphone, Whisper models,
+// Ollama, free disk space and database integrity. Most support issues are one
+// of these, so the report names the problem and what to do about it.
+
+use crate::database::{self, Database};
+use crate::i18n;
+use crate::model_store::ModelStore;
+use crate::settings::OllamaSettings;
+use crate::whisper::WhisperModelSize;
+use cpal::traits::{DeviceTrait, HostTrait};
+use serde::Serialize;
+use std::path::Path;
+use std::sync::Mutex;
+
+// Below this models may fail to download (the large model is ~3 GB)
+const LOW_DISK_WARNING_BYTES: u64 = 1024 * 1024 * 1024;
+// Below this recordings and the database may fail to write
+const LOW_DISK_ERROR_BYTES: u64 = 100 * 1024 * 1024;
+
+// The last model that failed to load this run, and why
+static MODEL_FAILURE: Mutex<Option<(WhisperModelSize, String)>> = Mutex::new(None);
+
+/// Remember a model that failed to load, for the next report
+pub fn record_model_failure(model: WhisperModelSize, error: String) {
+    *MODEL_FAILURE.lock().unwrap_or_else(|e| e.into_inner()) = Some((model, error));
+}
+
+/// Forget a recorded failure once the model loads
+pub fn clear_model_failure(model: WhisperModelSize) {
+    let mut failure = MODEL_FAILURE.lock().unwrap_or_else(|e| e.into_inner());
+    if failure.as_ref().is_some_and(|(failed, _)| *failed == model) {
+        *failure = None;
+    }
+}
+
+// Ordered from best to worst so the report status is the max
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Ok,
+    Warning,
+    Error,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DiagnosticCheck {
+    /// Stable id: "microphone", "models", "ollama", "disk" or "database"
+    /// ("temp_files" in the startup report)
+    pub id: &'static str,
+    pub label: String,
+    pub status: CheckStatus,
+    pub detail: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DiagnosticsReport {
+    pub version: &'static str,
+    pub os: String,
+    pub checks: Vec<DiagnosticCheck>,
+}
+
+impl DiagnosticsReport {
+    pub(crate) fn new(checks: Vec<DiagnosticCheck>) -> Self {
+        Self {
+            version: env!("CARGO_PKG_VERSION"),
+            os: format!("{} ({})", std::env::consts::OS, std::env::consts::ARCH),
+            checks,
+        }
+    }
+
+    /// Worst status across all checks
+    pub fn status(&self) -> CheckStatus {
+        self.checks.iter().map(|check| check.status).max().unwrap_or(CheckStatus::Ok)
+    }
+}
+
+impl DiagnosticCheck {
+    pub(crate) fn new(id: &'static str, status: CheckStatus, detail: String) -> Self {
+        Self {
+            id,
+            label: i18n::t(&format!("diagnostics.{}", id)),
+            status,
+            detail,
+        }
+    }
+}
+
+/// Run every check; `ollama_enabled` decides whether an unreachable server is a problem
+pub async fn run(db: &Database, selected_model: &str, ollama_enabled: bool, ollama: &OllamaSettings) -> DiagnosticsReport {
+    let store = ModelStore::open_or_temp();
+    let checks = vec![
+        check_microphone(),
+        check_models(&store, selected_model),
+        check_ollama(ollama_enabled, ollama).await,
+        check_disk_space(store.dir()),
+        check_database(db),
+    ];
+    for check in &checks {
+        if check.status != CheckStatus::Ok {
+            tracing::warn!("Diagnostics: {} - {}", check.id, check.detail);
+        }
+    }
+
+    DiagnosticsReport::new(checks)
+}
+
+fn check_microphone() -> DiagnosticCheck {
+    if microphone_blocked_by_privacy_settings() {
+        return DiagnosticCheck::new("microphone", CheckStatus::Error, i18n::t("diagnostics.microphone_blocked"));
+    }
+
+    let Some(device) = cpal::default_host().default_input_device() else {
+        return DiagnosticCheck::new("microphone", CheckStatus::Error, i18n::t("diagnostics.microphone_missing"));
+    };
+    let name = device.name().unwrap_or_else(|_| i18n::t("settings.device_unknown"));
+    match device.default_input_config() {
+        Ok(config) => DiagnosticCheck::new(
+            "microphone",
+            CheckStatus::Ok,
+            i18n::tf("diagnostics.microphone_ok", &[("device", &name), ("rate", &config.sample_rate().0)]),
+        ),
+        // Usually another app holds the device exclusively, or access was denied
+        Err(e) => DiagnosticCheck::new(
+            "microphone",
+            CheckStatus::Error,
+            i18n::tf("diagnostics.microphone_unavailable", &[("device", &name), ("error", &e)]),
+        ),
+    }
+}
+
+/// Windows keeps microphone consent per user; "Deny" blocks capture without an error from cpal
+#[cfg(target_os = "windows")]
+fn microphone_blocked_by_privacy_settings() -> bool {
+    use windows::core::w;
+    use windows::Win32::System::Registry::{RegGetValueW, HKEY_CURRENT_USER, RRF_RT_REG_SZ};
+
+    let mut buffer = [0u16; 16];
+    let mut size = std::mem::size_of_val(&buffer) as u32;
+    let result = unsafe {
+        RegGetValueW(
+            HKEY_CURRENT_USER,
+            w!(r"Software\Microsoft\Windows\CurrentVersion\CapabilityAccessManager\ConsentStore\microphone"),
+            w!("Value"),
+            RRF_RT_REG_SZ,
+            None,
+            Some(buffer.as_mut_ptr().cast()),
+            Some(&mut size as *mut u32),
+        )
+    };
+    if result.is_err() {
+        return false;
+    }
+    let len = buffer.iter().position(|&c| c == 0).unwrap_or(buffer.len());
+    String::from_utf16_lossy(&buffer[..len]) == "Deny"
+}
+
+#[cfg(not(target_os = "windows"))]
+fn microphone_blocked_by_privacy_settings() -> bool {
+    false
+}
+
+fn check_models(store: &ModelStore, selected_model: &str) -> DiagnosticCheck {
+    let installed: Vec<&str> = store
+        .list()
+        .into_iter()
+        .filter(|(_, installed)| *installed)
+        .map(|(model, _)| model.name())
+        .collect();
+
+    if installed.is_empty() {
+        return DiagnosticCheck::new("models", CheckStatus::Error, i18n::t("diagnostics.models_none"));
+    }
+    // Still there after failing to load (a deleted one is reported as missing)
+    let failure = MODEL_FAILURE.lock().unwrap_or_else(|e| e.into_inner()).clone();
+    if let Some((model, error)) = failure.filter(|(model, _)| store.is_installed(*model)) {
+        return DiagnosticCheck::new(
+            "models",
+            CheckStatus::Error,
+            i18n::tf("diagnostics.models_load_failed", &[("model", &model.name()), ("error", &error)]),
+        );
+    }
+    let selected_installed = WhisperModelSize::from_str(selected_model)
+        .is_some_and(|model| store.is_installed(model));
+    if !selected_installed {
+        return DiagnosticCheck::new(
+            "models",
+            CheckStatus::Warning,
+            i18n::tf("diagnostics.models_selected_missing", &[("model", &selected_model)]),
+        );
+    }
+    DiagnosticCheck::new(
+        "models",
+        CheckStatus::Ok,
+        i18n::tf("diagnostics.models_ok", &[("models", &installed.join(", "))]),
+    )
+}
+
+async fn check_ollama(ollama_enabled: bool, ollama: &OllamaSettings) -> DiagnosticCheck {
+    let url = ollama.base_url();
+    match crate::ollama::list_models(ollama).await.map(|models| models.len()) {
+        Ok(0) => DiagnosticCheck::new(
+            "ollama",
+            if ollama_enabled { CheckStatus::Warning } else { CheckStatus::Ok },
+            i18n::tf("diagnostics.ollama_no_models", &[("url", &url)]),
+        ),
+        Ok(count) => DiagnosticCheck::new(
+            "ollama",
+            CheckStatus::Ok,
+            i18n::tf("diagnostics.ollama_ok", &[("url", &url), ("count", &count)]),
+        ),
+        // Only a problem when parsing is set to use it
+        Err(_) if !ollama_enabled => DiagnosticCheck::new("ollama", CheckStatus::Ok, i18n::t("diagnostics.ollama_disabled")),
+        Err(e) => DiagnosticCheck::new(
+            "ollama",
+            CheckStatus::Warning,
+            i18n::tf("diagnostics.ollama_unreachable", &[("url", &url), ("error", &e)]),
+        ),
+    }
+}
+
+fn check_disk_space(dir: &Path) -> DiagnosticCheck {
+    let free = match free_disk_space(dir) {
+        Ok(free) => free,
+        Err(e) => {
+            return DiagnosticCheck::new(
+                "disk",
+                CheckStatus::Warning,
+                i18n::tf("diagnostics.disk_unknown", &[("error", &e)]),
+            )
+        }
+    };
+    let status = if free < LOW_DISK_ERROR_BYTES {
+        CheckStatus::Error
+    } else if free < LOW_DISK_WARNING_BYTES {
+        CheckStatus::Warning
+    } else {
+        CheckStatus::Ok
+    };
+    let free_mb = free / (1024 * 1024);
+    DiagnosticCheck::new(
+        "disk",
+        status,
+        i18n::tf("diagnostics.disk_free", &[("free_mb", &free_mb), ("dir", &dir.display())]),
+    )
+}
+
+#[cfg(target_os = "windows")]
+fn free_disk_space(dir: &Path) -> Result<u64, String> {
+    use std::os::windows::ffi::OsStrExt;
+    use windows::core::PCWSTR;
+    use windows::Win32::Storage::FileSystem::GetDiskFreeSpaceExW;
+
+    let wide: Vec<u16> = dir.as_os_str().encode_wide().chain(std::iter::once(0)).collect();
+    let mut free = 0u64;
+    unsafe { GetDiskFreeSpaceExW(PCWSTR(wide.as_ptr()), Some(&mut free as *mut u64), None, None) }
+        .map_err(|e| e.to_string())?;
+    Ok(free)
+}
+
+#[cfg(not(target_os = "windows"))]
+fn free_disk_space(dir: &Path) -> Result<u64, String> {
+    // POSIX output: header line, then "<fs> <blocks> <used> <available> ..." in KiB
+    let output = std::process::Command::new("df")
+        .arg("-Pk")
+        .arg(dir)
+        .output()
+        .map_err(|e| e.to_string())?;
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .nth(1)
+        .and_then(|line| line.split_whitespace().nth(3))
+        .and_then(|available| available.parse::<u64>().ok())
+        .map(|kib| kib * 1024)
+        .ok_or_else(|| "unexpected df output".to_string())
+}
+
+fn check_database(db: &Database) -> DiagnosticCheck {
+    match database::integrity_check(db) {
+        Ok(problems) if problems.is_empty() => {
+            DiagnosticCheck::new("database", CheckStatus::Ok, i18n::t("diagnostics.database_ok"))
+        }
+        Ok(problems) => DiagnosticCheck::new(
+            "database",
+            CheckStatus::Error,
+            i18n::tf("diagnostics.database_corrupt", &[("problems", &problems.join("; "))]),
+        ),
+        Err(e) => DiagnosticCheck::new(
+            "database",
+            CheckStatus::Error,
+            i18n::tf("diagnostics.database_error", &[("error", &e)]),
+        ),
+    }
+}
@@ -0,0 +1,135 @@
+// Disambiguation module
+// Completing a task by voice names it loosely ("done with the report"), and
+// several open tasks can fit. Instead of completing whichever matched first,
+// `DisambiguationState::complete` asks: the matching tasks come back as a
+// question, which waits until the user picks one (or none of them) with
+// `resolve`. A task whose text is exactly the phrase, or a phrase only one
+// task fits, is completed straight away as before, and one no task fits still
+// goes down as a new completed task.
+
+use crate::database::{self, Database, Task};
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+/// Most tasks offered in one question
+pub const MAX_CANDIDATES: usize = 5;
+
+/// Several open tasks fit a completion phrase; which one was meant?
+#[derive(Debug, Clone, Serialize)]
+pub struct NeedsDisambiguation {
+    pub id: u64,
+    /// What was said
+    pub phrase: String,
+    /// The open tasks that fit, best first
+    pub candidates: Vec<Task>,
+}
+
+/// What a spoken completion did
+#[derive(Debug, Clone)]
+pub enum Completion {
+    Completed(Task),
+    /// Nothing was completed; the question waits for `resolve`
+    NeedsDisambiguation(NeedsDisambiguation),
+}
+
+/// What answering a question did
+#[derive(Debug, Clone, Serialize)]
+pub struct Resolved {
+    /// The task picked, now completed; None for "none of these"
+    pub completed: Option<Task>,
+    /// The question asked after it, if one is waiting
+    pub next: Option<NeedsDisambiguation>,
+}
+
+/// Managed state holding the unanswered questions, oldest first
+#[derive(Default)]
+pub struct DisambiguationState {
+    questions: Mutex<VecDeque<NeedsDisambiguation>>,
+    next_id: AtomicU64,
+}
+
+impl DisambiguationState {
+    /// Complete the task `phrase` names, or ask which one when several fit
+    pub fn complete(&self, db: &Database, phrase: &str) -> Result<Completion, String> {
+        let candidates = database::find_open_tasks_matching(db, phrase, MAX_CANDIDATES)
+            .map_err(|e| e.to_string())?;
+        let exact = candidates
+            .first()
+            .is_some_and(|task| task.text.trim().to_lowercase() == phrase.trim().to_lowercase());
+        if candidates.len() > 1 && !exact {
+            let question = NeedsDisambiguation {
+                id: self.next_id.fetch_add(1, Ordering::Relaxed),
+                phrase: phrase.trim().to_string(),
+                candidates,
+            };
+            tracing::debug!(
+                "\"{}\" fits {} tasks; asking which",
+                question.phrase,
+                question.candidates.len()
+            );
+            self.questions.lock().unwrap().push_back(question.clone());
+            return Ok(Completion::NeedsDisambiguation(question));
+        }
+        let task = match candidates.into_iter().next() {
+            Some(task) => complete_task(db, task)?,
+            None => database::find_and_complete_task(db, phrase).map_err(|e| e.to_string())?,
+        };
+        Ok(Completion::Completed(task))
+    }
+
+    /// The oldest unanswered question
+    pub fn current(&self) -> Option<NeedsDisambiguation> {
+        self.questions.lock().unwrap().front().cloned()
+    }
+
+    /// The id the next question will get, to find the ones asked from here on
+    pub fn next_id(&self) -> u64 {
+        self.next_id.load(Ordering::Relaxed)
+    }
+
+    /// The oldest unanswered question asked since `next_id` returned `id`
+    pub fn asked_since(&self, id: u64) -> Option<NeedsDisambiguation> {
+        self.questions
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|question| question.id >= id)
+            .cloned()
+    }
+
+    /// Answer the oldest question with the id of the task meant, or None when
+    /// it was none of them
+    pub fn resolve(&self, db: &Database, choice: Option<i64>) -> Result<Resolved, String> {
+        let mut questions = self.questions.lock().unwrap();
+        let question = questions
+            .front()
+            .ok_or_else(|| crate::i18n::t("error.nothing_to_disambiguate"))?;
+        let completed = match choice {
+            Some(id) => {
+                let task = question
+                    .candidates
+                    .iter()
+                    .find(|task| task.id == id)
+                    .cloned()
+                    .ok_or_else(|| {
+                        crate::i18n::tf("error.disambiguation_choice", &[("id", &id)])
+                    })?;
+                Some(complete_task(db, task)?)
+            }
+            None => None,
+        };
+        questions.pop_front();
+        Ok(Resolved {
+            completed,
+            next: questions.front().cloned(),
+        })
+    }
+}
+
+// The task completed; one completed or deleted meanwhile comes back as it is
+fn complete_task(db: &Database, task: Task) -> Result<Task, String> {
+    let completed = database::complete_tasks(db, &[task.id]).map_err(|e| e.to_string())?;
+    Ok(completed.into_iter().next().unwrap_or(task))
+}
@@ -0,0 +1,181 @@
+// Do-not-disturb module
+// Detects when timer alerts should stay quiet: the OS is in do-not-disturb /
+// Focus Assist, or one of the configured apps (meetings, screen recording) is
+// in full screen on the active display
+
+use crate::settings::DndSettings;
+use serde::Serialize;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "reason", rename_all = "snake_case")]
+pub enum DndState {
+    Off,
+    /// OS do-not-disturb, Focus Assist or presentation mode
+    System,
+    /// A listed app is full screen
+    FullscreenApp { app: String },
+}
+
+impl DndState {
+    pub fn is_active(&self) -> bool {
+        *self != DndState::Off
+    }
+}
+
+/// Whether alerts should be suppressed right now under `settings`
+pub fn get_dnd_state(settings: &DndSettings) -> DndState {
+    if settings.respect_system_dnd && system_dnd_active() {
+        return DndState::System;
+    }
+    if settings.fullscreen_apps.is_empty() {
+        return DndState::Off;
+    }
+    match fullscreen_app() {
+        Some(app) if settings
+            .fullscreen_apps
+            .iter()
+            .any(|listed| normalize_app_name(listed) == app) =>
+        {
+            DndState::FullscreenApp { app }
+        }
+        _ => DndState::Off,
+    }
+}
+
+/// "Zoom.exe" and "zoom" compare equal
+fn normalize_app_name(name: &str) -> String {
+    let name = name.trim().to_lowercase();
+    name.strip_suffix(".exe").map(str::to_string).unwrap_or(name)
+}
+
+#[cfg(target_os = "windows")]
+fn system_dnd_active() -> bool {
+    use windows::Win32::UI::Shell::{SHQueryUserNotificationState, QUNS_PRESENTATION_MODE, QUNS_QUIET_TIME};
+
+    // The shell's own "should I show a notification" answer covers Focus Assist
+    // quiet hours and presentation mode
+    match unsafe { SHQueryUserNotificationState() } {
+        Ok(state) => state == QUNS_QUIET_TIME || state == QUNS_PRESENTATION_MODE,
+        Err(_) => false,
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn system_dnd_active() -> bool {
+    // Focus modes (Monterey and later) record active assertions here
+    let Some(path) = dirs::home_dir().map(|home| home.join("Library/DoNotDisturb/DB/Assertions.json")) else {
+        return false;
+    };
+    let Ok(contents) = std::fs::read_to_string(path) else {
+        return false;
+    };
+    serde_json::from_str::<serde_json::Value>(&contents)
+        .ok()
+        .and_then(|json| {
+            json.get("data")?
+                .as_array()?
+                .first()?
+                .get("storeAssertionRecords")?
+                .as_array()
+                .map(|records| !records.is_empty())
+        })
+        .unwrap_or(false)
+}
+
+#[cfg(target_os = "linux")]
+fn system_dnd_active() -> bool {
+    // GNOME's "Do Not Disturb" switch turns notification banners off
+    std::process::Command::new("gsettings")
+        .args(["get", "org.gnome.desktop.notifications", "show-banners"])
+        .output()
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim() == "false")
+        .unwrap_or(false)
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+fn system_dnd_active() -> bool {
+    false
+}
+
+/// Executable name of the foreground window when it covers its whole monitor
+#[cfg(target_os = "windows")]
+fn fullscreen_app() -> Option<String> {
+    use windows::Win32::Foundation::RECT;
+    use windows::Win32::Graphics::Gdi::{GetMonitorInfoW, MonitorFromWindow, MONITORINFO, MONITOR_DEFAULTTONEAREST};
+    use windows::Win32::UI::WindowsAndMessaging::{GetForegroundWindow, GetWindowRect};
+
+    unsafe {
+        let window = GetForegroundWindow();
+        if window.0 == 0 {
+            return None;
+        }
+
+        let mut rect = RECT::default();
+        GetWindowRect(window, &mut rect).ok()?;
+        let mut monitor = MONITORINFO {
+            cbSize: std::mem::size_of::<MONITORINFO>() as u32,
+            ..Default::default()
+        };
+        if !GetMonitorInfoW(MonitorFromWindow(window, MONITOR_DEFAULTTONEAREST), &mut monitor).as_bool() {
+            return None;
+        }
+        let screen = monitor.rcMonitor;
+        let covers_monitor = rect.left <= screen.left
+            && rect.top <= screen.top
+            && rect.right >= screen.right
+            && rect.bottom >= screen.bottom;
+        if !covers_monitor {
+            return None;
+        }
+        window_app(window)
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+fn fullscreen_app() -> Option<String> {
+    // No portable way to ask which app owns a full-screen window
+    None
+}
+
+/// Executable name ("code", "chrome") of the foreground window
+#[cfg(target_os = "windows")]
+pub fn foreground_app() -> Option<String> {
+    use windows::Win32::UI::WindowsAndMessaging::GetForegroundWindow;
+
+    let window = unsafe { GetForegroundWindow() };
+    if window.0 == 0 {
+        return None;
+    }
+    window_app(window)
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn foreground_app() -> Option<String> {
+    None
+}
+
+/// Normalized executable name of the process that owns `window`
+#[cfg(target_os = "windows")]
+fn window_app(window: windows::Win32::Foundation::HWND) -> Option<String> {
+    use windows::core::PWSTR;
+    use windows::Win32::Foundation::CloseHandle;
+    use windows::Win32::System::Threading::{
+        OpenProcess, QueryFullProcessImageNameW, PROCESS_NAME_WIN32, PROCESS_QUERY_LIMITED_INFORMATION,
+    };
+    use windows::Win32::UI::WindowsAndMessaging::GetWindowThreadProcessId;
+
+    unsafe {
+        let mut pid = 0u32;
+        GetWindowThreadProcessId(window, Some(&mut pid as *mut u32));
+        let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid).ok()?;
+        let mut buffer = [0u16; 260];
+        let mut len = buffer.len() as u32;
+        let result = QueryFullProcessImageNameW(process, PROCESS_NAME_WIN32, PWSTR(buffer.as_mut_ptr()), &mut len);
+        let _ = CloseHandle(process);
+        result.ok()?;
+
+        let path = String::from_utf16_lossy(&buffer[..len as usize]);
+        let file_name = std::path::Path::new(&path).file_name()?.to_string_lossy().to_string();
+        Some(normalize_app_name(&file_name))
+    }
+}
@@ -0,0 +1,175 @@
+// Downloads module
+// Queue for Whisper model downloads, shared by the Tauri and egui builds. Models
+// download in the background a few at a time with per-model progress, and can be
+// cancelled or retried. Each UI passes a listener that forwards status changes.
+
+use crate::model_store::{ModelStore, DOWNLOAD_CANCELLED};
+use crate::whisper::WhisperModelSize;
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+/// Downloads running at once; the rest wait in the queue
+pub const MAX_CONCURRENT_DOWNLOADS: usize = 2;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum DownloadStatus {
+    Queued,
+    Downloading { downloaded: u64, total: u64 },
+    Completed,
+    Failed { error: String },
+    Cancelled,
+}
+
+impl DownloadStatus {
+    /// Queued or downloading
+    pub fn is_pending(&self) -> bool {
+        matches!(self, DownloadStatus::Queued | DownloadStatus::Downloading { .. })
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DownloadEvent {
+    /// Lowercase model name ("tiny", "base", ...)
+    pub model: String,
+    #[serde(flatten)]
+    pub status: DownloadStatus,
+}
+
+type Listener = Arc<dyn Fn(&DownloadEvent) + Send + Sync>;
+
+#[derive(Default)]
+struct Queue {
+    statuses: HashMap<WhisperModelSize, DownloadStatus>,
+    waiting: VecDeque<WhisperModelSize>,
+    // Cancel flag of each running download
+    running: HashMap<WhisperModelSize, Arc<AtomicBool>>,
+}
+
+#[derive(Clone)]
+pub struct DownloadManager {
+    queue: Arc<Mutex<Queue>>,
+    listener: Listener,
+}
+
+impl DownloadManager {
+    /// `listener` is called from download threads on every status change
+    pub fn new(listener: impl Fn(&DownloadEvent) + Send + Sync + 'static) -> Self {
+        Self {
+            queue: Arc::new(Mutex::new(Queue::default())),
+            listener: Arc::new(listener),
+        }
+    }
+
+    /// Queue a model (also used to retry a failed or cancelled one).
+    /// Already installed, queued or downloading models are left alone.
+    pub fn enqueue(&self, model: WhisperModelSize) {
+        if ModelStore::open_or_temp().is_installed(model) {
+            self.set_status(model, DownloadStatus::Completed);
+            return;
+        }
+        {
+            let mut queue = self.queue.lock().unwrap();
+            if queue.statuses.get(&model).is_some_and(DownloadStatus::is_pending) {
+                return;
+            }
+            queue.waiting.push_back(model);
+        }
+        self.set_status(model, DownloadStatus::Queued);
+        self.start_waiting();
+    }
+
+    /// Drop a queued model or stop a running download
+    pub fn cancel(&self, model: WhisperModelSize) {
+        let was_waiting = {
+            let mut queue = self.queue.lock().unwrap();
+            if let Some(cancel) = queue.running.get(&model) {
+                // The download thread reports Cancelled once it stops
+                cancel.store(true, Ordering::Relaxed);
+                false
+            } else {
+                let before = queue.waiting.len();
+                queue.waiting.retain(|waiting| *waiting != model);
+                queue.waiting.len() != before
+            }
+        };
+        if was_waiting {
+            self.set_status(model, DownloadStatus::Cancelled);
+        }
+    }
+
+    /// Last known status of every model that has been queued this run (for a UI
+    /// that opens after downloads started; afterwards the listener keeps it current)
+    pub fn statuses(&self) -> Vec<DownloadEvent> {
+        let queue = self.queue.lock().unwrap();
+        WhisperModelSize::ALL
+            .into_iter()
+            .filter_map(|model| {
+                queue.statuses.get(&model).map(|status| DownloadEvent {
+                    model: model.name().to_lowercase(),
+                    status: status.clone(),
+                })
+            })
+            .collect()
+    }
+
+    fn set_status(&self, model: WhisperModelSize, status: DownloadStatus) {
+        self.queue.lock().unwrap().statuses.insert(model, status.clone());
+        (self.listener)(&DownloadEvent {
+            model: model.name().to_lowercase(),
+            status,
+        });
+    }
+
+    /// Start queued downloads while there are free slots
+    fn start_waiting(&self) {
+        loop {
+            let (model, cancel) = {
+                let mut queue = self.queue.lock().unwrap();
+                if queue.running.len() >= MAX_CONCURRENT_DOWNLOADS {
+                    return;
+                }
+                let Some(model) = queue.waiting.pop_front() else {
+                    return;
+                };
+                let cancel = Arc::new(AtomicBool::new(false));
+                queue.running.insert(model, cancel.clone());
+                (model, cancel)
+            };
+
+            let manager = self.clone();
+            thread::spawn(move || {
+                manager.set_status(model, DownloadStatus::Downloading { downloaded: 0, total: model.size_mb() * 1_000_000 });
+
+                // Report at most once per MB so listeners aren't flooded
+                let progress_manager = manager.clone();
+                let last_mb = AtomicU64::new(0);
+                let on_progress = Box::new(move |downloaded: u64, total: u64| {
+                    let mb = downloaded / 1_000_000;
+                    if last_mb.swap(mb, Ordering::Relaxed) != mb {
+                        progress_manager.set_status(model, DownloadStatus::Downloading { downloaded, total });
+                    }
+                });
+
+                let rt = tokio::runtime::Runtime::new().unwrap();
+                let result = ModelStore::open()
+                    .and_then(|store| rt.block_on(store.download(model, Some(on_progress), Some(&cancel))));
+
+                manager.queue.lock().unwrap().running.remove(&model);
+                let status = match result {
+                    Ok(_) => DownloadStatus::Completed,
+                    Err(e) if e == DOWNLOAD_CANCELLED => DownloadStatus::Cancelled,
+                    Err(error) => {
+                        tracing::warn!("Download of {} failed: {}", model.name(), error);
+                        DownloadStatus::Failed { error }
+                    }
+                };
+                manager.set_status(model, status);
+                manager.start_waiting();
+            });
+        }
+    }
+}
@@ -0,0 +1,189 @@
+// Audio feedback module
+// Plays short tones on recording start/stop and when tasks are captured,
+// so the global hotkey is usable with the window hidden. Also plays the
+// awareness timer alert sound.
+
+use rodio::source::{SineWave, Source};
+use rodio::{Decoder, OutputStream, Sink};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+static MUTED: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Earcon {
+    RecordStart,
+    RecordStop,
+    Success,
+}
+
+impl Earcon {
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "record_start" => Some(Earcon::RecordStart),
+            "record_stop" => Some(Earcon::RecordStop),
+            "success" => Some(Earcon::Success),
+            _ => None,
+        }
+    }
+
+    /// Tone sequence as (frequency Hz, duration ms)
+    fn tones(&self) -> &'static [(f32, u64)] {
+        match self {
+            // Rising pair - "listening"
+            Earcon::RecordStart => &[(660.0, 70), (880.0, 90)],
+            // Falling pair - "got it"
+            Earcon::RecordStop => &[(880.0, 70), (660.0, 90)],
+            // Major arpeggio - "tasks added"
+            Earcon::Success => &[(523.0, 80), (659.0, 80), (784.0, 140)],
+        }
+    }
+}
+
+pub fn set_muted(muted: bool) {
+    MUTED.store(muted, Ordering::Relaxed);
+}
+
+pub fn is_muted() -> bool {
+    MUTED.load(Ordering::Relaxed)
+}
+
+/// Play an earcon without blocking the caller (no-op when muted)
+pub fn play(earcon: Earcon) {
+    if is_muted() {
+        return;
+    }
+    play_tones(earcon.tones());
+}
+
+/// Play a tone sequence on the default output device in a background thread
+pub fn play_tones(tones: &'static [(f32, u64)]) {
+    play_tones_at(tones, 0.2);
+}
+
+fn play_tones_at(tones: &'static [(f32, u64)], volume: f32) {
+    with_output(move |sink| {
+        for &(frequency, millis) in tones {
+            sink.append(
+                SineWave::new(frequency)
+                    .take_duration(Duration::from_millis(millis))
+                    .fade_in(Duration::from_millis(5))
+                    .amplify(volume),
+            );
+        }
+        Ok(())
+    });
+}
+
+/// Shared output helper: opens the default device on a background thread,
+/// lets `fill` queue sources, then blocks that thread until playback ends
+fn with_output<F>(fill: F)
+where
+    F: FnOnce(&Sink) -> Result<(), String> + Send + 'static,
+{
+    std::thread::spawn(move || {
+        // OutputStream is not Send, so it has to live on this thread
+        let (_stream, handle) = match OutputStream::try_default() {
+            Ok(output) => output,
+            Err(e) => {
+                tracing::warn!("No audio output device: {}", e);
+                return;
+            }
+        };
+        let sink = match Sink::try_new(&handle) {
+            Ok(sink) => sink,
+            Err(e) => {
+                tracing::warn!("Failed to create audio sink: {}", e);
+                return;
+            }
+        };
+
+        if let Err(e) = fill(&sink) {
+            tracing::warn!("Failed to play sound: {}", e);
+            return;
+        }
+        sink.sleep_until_end();
+    });
+}
+
+/// Sound played when the awareness timer expires
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum AlertSound {
+    None,
+    #[default]
+    Chime,
+    Bell,
+    Digital,
+    /// User-provided WAV/MP3/OGG file
+    File(PathBuf),
+}
+
+impl AlertSound {
+    /// Settings representation: "none", "chime", "bell", "digital" or "file:<path>"
+    pub fn as_setting(&self) -> String {
+        match self {
+            AlertSound::None => "none".to_string(),
+            AlertSound::Chime => "chime".to_string(),
+            AlertSound::Bell => "bell".to_string(),
+            AlertSound::Digital => "digital".to_string(),
+            AlertSound::File(path) => format!("file:{}", path.to_string_lossy()),
+        }
+    }
+
+    pub fn from_setting(s: &str) -> Option<Self> {
+        if let Some(path) = s.strip_prefix("file:") {
+            return Some(AlertSound::File(PathBuf::from(path)));
+        }
+        match s.to_lowercase().as_str() {
+            "none" => Some(AlertSound::None),
+            "chime" => Some(AlertSound::Chime),
+            "bell" => Some(AlertSound::Bell),
+            "digital" => Some(AlertSound::Digital),
+            _ => None,
+        }
+    }
+}
+
+// Stored in settings using the same string form as `as_setting`
+impl serde::Serialize for AlertSound {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.as_setting())
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for AlertSound {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        AlertSound::from_setting(&value)
+            .ok_or_else(|| serde::de::Error::custom(format!("invalid alert sound: {}", value)))
+    }
+}
+
+/// Play the timer alert sound. Not affected by the earcon mute setting -
+/// use `AlertSound::None` to silence alerts.
+pub fn play_alert_sound(sound: &AlertSound) {
+    play_alert_sound_at(sound, 1.0);
+}
+
+/// Play the alert sound with its volume scaled by `gain` (escalated alerts play louder)
+pub fn play_alert_sound_at(sound: &AlertSound, gain: f32) {
+    match sound {
+        AlertSound::None => {}
+        AlertSound::Chime => play_tones_at(&[(784.0, 180), (1047.0, 420)], 0.3 * gain),
+        AlertSound::Bell => play_tones_at(&[(880.0, 600)], 0.3 * gain),
+        AlertSound::Digital => play_tones_at(&[(1200.0, 90), (0.0, 60), (1200.0, 90), (0.0, 60), (1200.0, 90)], 0.25 * gain),
+        AlertSound::File(path) => {
+            let path = path.clone();
+            with_output(move |sink| {
+                let file = std::fs::File::open(&path)
+                    .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+                let source = Decoder::new(std::io::BufReader::new(file))
+                    .map_err(|e| format!("Unsupported sound file {}: {}", path.display(), e))?;
+                sink.set_volume(gain);
+                sink.append(source);
+                Ok(())
+            });
+        }
+    }
+}
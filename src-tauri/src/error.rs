@@ -0,0 +1,98 @@
+// Error module
+// Errors that the frontends can tell apart. Each one is sent to the webview as
+// `{ code, message }` (plus the fields it carries), so the UI can offer a way
+// out, like downloading a missing model, instead of only showing text. Every
+// command returns one. Most internals still pass `String`s around and become
+// `Other` on the way out; the two convert both ways, so a module only moves
+// over once a caller needs to recognise one of its failures.
+
+use serde::ser::{Serialize, SerializeMap, Serializer};
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum FlowStateError {
+    /// The model is known but hasn't been downloaded
+    #[error("{}", model_message("error.model_missing", .model))]
+    ModelMissing { model: String },
+    #[error("{}", model_message("error.invalid_model", .model))]
+    InvalidModel { model: String },
+    /// The model file failed to load and doesn't match its checksum; delete and download it again
+    #[error("{}", model_message("error.model_corrupt", .model))]
+    ModelCorrupt { model: String },
+    /// No input device, or it couldn't be opened (often another app holds it)
+    #[error("{}", microphone_message(.0))]
+    MicrophoneUnavailable(String),
+    /// The same recording was sent again while the first copy is still processing
+    #[error("{}", crate::i18n::t("error.duplicate_recording"))]
+    DuplicateRecording,
+    /// Too many voice jobs are already waiting
+    #[error("{}", crate::i18n::t("error.processing_busy"))]
+    ProcessingBusy,
+    /// Another connection holds the database (SQLITE_BUSY / SQLITE_LOCKED)
+    #[error("{}", crate::i18n::t("error.database_locked"))]
+    DatabaseLocked,
+    #[error("{0}")]
+    Database(String),
+    #[error("{0}")]
+    Other(String),
+}
+
+fn model_message(key: &str, model: &str) -> String {
+    crate::i18n::tf(key, &[("model", &model)])
+}
+
+fn microphone_message(error: &str) -> String {
+    crate::i18n::tf("error.microphone_unavailable", &[("error", &error)])
+}
+
+impl FlowStateError {
+    /// Stable identifier the frontend matches on
+    pub fn code(&self) -> &'static str {
+        match self {
+            FlowStateError::ModelMissing { .. } => "model_missing",
+            FlowStateError::InvalidModel { .. } => "invalid_model",
+            FlowStateError::ModelCorrupt { .. } => "model_corrupt",
+            FlowStateError::MicrophoneUnavailable(_) => "microphone_unavailable",
+            FlowStateError::DuplicateRecording => "duplicate_recording",
+            FlowStateError::ProcessingBusy => "processing_busy",
+            FlowStateError::DatabaseLocked => "database_locked",
+            FlowStateError::Database(_) => "database",
+            FlowStateError::Other(_) => "other",
+        }
+    }
+}
+
+impl Serialize for FlowStateError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("code", self.code())?;
+        map.serialize_entry("message", &self.to_string())?;
+        if let FlowStateError::ModelMissing { model }
+        | FlowStateError::InvalidModel { model }
+        | FlowStateError::ModelCorrupt { model } = self
+        {
+            map.serialize_entry("model", model)?;
+        }
+        map.end()
+    }
+}
+
+impl From<String> for FlowStateError {
+    fn from(message: String) -> Self {
+        FlowStateError::Other(message)
+    }
+}
+
+impl From<rusqlite::Error> for FlowStateError {
+    fn from(error: rusqlite::Error) -> Self {
+        match error.sqlite_error_code() {
+            Some(rusqlite::ErrorCode::DatabaseBusy | rusqlite::ErrorCode::DatabaseLocked) => FlowStateError::DatabaseLocked,
+            _ => FlowStateError::Database(error.to_string()),
+        }
+    }
+}
+
+impl From<FlowStateError> for String {
+    fn from(error: FlowStateError) -> Self {
+        error.to_string()
+    }
+}
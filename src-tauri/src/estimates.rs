@@ -0,0 +1,178 @@
+// Estimates module
+// How long a task should take. An estimate is said or typed at the end of the
+// task ("write blog post, about an hour", "fix login (30 min)", "review PR
+// ~1h30m") and kept in its own column, like a context. The estimates of today's
+// tasks add up to a workload that both UIs show and warn about once it's over
+// the capacity in the settings; "what's my workload" asks for it by voice.
+
+use crate::database::{self, Database, TaskQuery, TaskStatus};
+use serde::{Deserialize, Serialize};
+
+// Words that mark the end of a task as an estimate ("about an hour")
+const QUALIFIERS: [&str; 12] = [
+    "about", "around", "roughly", "approximately", "approx", "maybe", "takes",
+    "примерно", "приблизительно", "около", "где-то", "займёт",
+];
+// Longest estimate looked for at the end of a task, in words
+const MAX_WORDS: usize = 6;
+const COMMANDS: [&str; 12] = [
+    "what's my workload",
+    "what is my workload",
+    "show my workload",
+    "workload summary",
+    "workload",
+    "how busy am i",
+    "how much is planned",
+    "какая нагрузка",
+    "какая у меня нагрузка",
+    "покажи нагрузку",
+    "нагрузка",
+    "сколько работы",
+];
+
+/// Today's estimated work against the capacity in the settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Workload {
+    /// Estimated minutes of open tasks and tasks completed today
+    pub planned_minutes: i64,
+    /// The part of `planned_minutes` already completed
+    pub done_minutes: i64,
+    pub capacity_minutes: i64,
+    /// How far `planned_minutes` is over capacity, 0 if it isn't
+    pub over_minutes: i64,
+    /// Open tasks without an estimate, not counted in the total
+    pub unestimated: usize,
+}
+
+/// The task text without a trailing estimate, and the estimate in minutes.
+/// It has to be set apart from the task by a comma, brackets, a "~" or a word
+/// like "about", so "wait 10 minutes" or "read for an hour" stay as they are.
+pub fn split_estimate(text: &str) -> (String, Option<i64>) {
+    let trimmed = text.trim().trim_end_matches(['.', '!', '?']);
+    let words: Vec<&str> = trimmed.split_whitespace().collect();
+    // Longest first, so "about an hour" wins over "hour"; one word always stays
+    for start in words.len().saturating_sub(MAX_WORDS).max(1)..words.len() {
+        let candidate = &words[start..];
+        let first = candidate[0].to_lowercase();
+        let marked = words[start - 1].ends_with([',', ';'])
+            || first.starts_with(['~', '('])
+            || QUALIFIERS.contains(&first.as_str());
+        if !marked {
+            continue;
+        }
+        if let Some(minutes) = parse_estimate(&candidate.join(" ")) {
+            let rest = words[..start].join(" ");
+            let rest = rest.trim_end_matches([',', ';']);
+            if !rest.is_empty() {
+                return (rest.to_string(), Some(minutes));
+            }
+        }
+    }
+    (text.trim().to_string(), None)
+}
+
+/// Minutes in a phrase that is only an estimate: "about an hour", "~45m",
+/// "(30 min)", "1.5h", "примерно полчаса"; None for anything else
+pub fn parse_estimate(phrase: &str) -> Option<i64> {
+    let phrase = phrase.trim().trim_end_matches(['.', '!', '?']).to_lowercase();
+    let words: Vec<&str> = phrase
+        .split_whitespace()
+        .map(|word| word.trim_matches(|c| matches!(c, ',' | ';' | '(' | ')' | '~')))
+        .filter(|word| !word.is_empty())
+        .collect();
+    let start = words.iter().position(|word| !QUALIFIERS.contains(word))?;
+    let minutes = match &words[start..] {
+        [token] => compact_minutes(token).or_else(|| Some(crate::reminders::parse_duration(&[*token])?.num_minutes()))?,
+        words => crate::reminders::parse_duration(words)?.num_minutes(),
+    };
+    (minutes > 0).then_some(minutes)
+}
+
+/// An estimate in the form `parse_estimate` reads back: "45m", "1h", "1h30m"
+pub fn format_estimate(minutes: i64) -> String {
+    match (minutes / 60, minutes % 60) {
+        (0, minutes) => format!("{}m", minutes),
+        (hours, 0) => format!("{}h", hours),
+        (hours, minutes) => format!("{}h{}m", hours, minutes),
+    }
+}
+
+// "30m", "1h", "1.5h", "1h30m", "45min", "2ч"
+fn compact_minutes(token: &str) -> Option<i64> {
+    let mut total = 0.0;
+    let mut rest = token;
+    while !rest.is_empty() {
+        let digits = rest.find(|c: char| !(c.is_ascii_digit() || c == '.' || c == ','))?;
+        if digits == 0 {
+            return None;
+        }
+        let number: f64 = rest[..digits].replace(',', ".").parse().ok()?;
+        rest = &rest[digits..];
+        let unit = rest.find(|c: char| c.is_ascii_digit()).unwrap_or(rest.len());
+        let factor = match &rest[..unit] {
+            "h" | "hr" | "hrs" | "ч" => 60.0,
+            "m" | "min" | "мин" | "м" => 1.0,
+            _ => return None,
+        };
+        total += number * factor;
+        rest = &rest[unit..];
+    }
+    Some(total.round() as i64)
+}
+
+/// Add up the estimates of open tasks and of tasks completed today
+pub fn workload(db: &Database, capacity_minutes: u32) -> Result<Workload, String> {
+    let tasks = |status| {
+        database::query_tasks(db, &TaskQuery { status, ..Default::default() }).map_err(|e| e.to_string())
+    };
+    let open = tasks(TaskStatus::Open)?;
+    let done = tasks(TaskStatus::CompletedToday)?;
+
+    let open_minutes: i64 = open.iter().filter_map(|task| task.estimate_minutes).sum();
+    let done_minutes: i64 = done.iter().filter_map(|task| task.estimate_minutes).sum();
+    let planned_minutes = open_minutes + done_minutes;
+    let capacity_minutes = i64::from(capacity_minutes);
+    Ok(Workload {
+        planned_minutes,
+        done_minutes,
+        capacity_minutes,
+        over_minutes: (planned_minutes - capacity_minutes).max(0),
+        unestimated: open.iter().filter(|task| task.estimate_minutes.is_none()).count(),
+    })
+}
+
+/// One-line summary for the notification, the native toast and speech
+pub fn summary(workload: &Workload) -> String {
+    let planned = format_estimate(workload.planned_minutes);
+    let capacity = format_estimate(workload.capacity_minutes);
+    let mut text = if workload.over_minutes > 0 {
+        crate::i18n::tf(
+            "workload.over",
+            &[("planned", &planned), ("capacity", &capacity), ("over", &format_estimate(workload.over_minutes))],
+        )
+    } else {
+        crate::i18n::tf(
+            "workload.summary",
+            &[("planned", &planned), ("capacity", &capacity), ("done", &format_estimate(workload.done_minutes))],
+        )
+    };
+    if workload.unestimated > 0 {
+        text.push(' ');
+        text.push_str(&crate::i18n::tf("workload.unestimated", &[("count", &workload.unestimated)]));
+    }
+    text
+}
+
+/// Whether a whole transcript asks for the workload summary
+pub fn workload_command(transcript: &str) -> bool {
+    let text = transcript.trim().trim_end_matches(['.', '!', '?']).to_lowercase().replace('’', "'");
+    let text = ["please", "пожалуйста"]
+        .iter()
+        .fold(text, |text, word| text.trim_end_matches(word).trim_end_matches([',', ' ']).to_string());
+    let text = ["for today", "today", "на сегодня", "сегодня"]
+        .iter()
+        .find_map(|suffix| text.strip_suffix(suffix))
+        .unwrap_or(&text)
+        .trim();
+    COMMANDS.contains(&text)
+}
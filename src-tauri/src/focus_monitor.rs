@@ -0,0 +1,110 @@
+// Focus monitor module
+// Optional "do not switch" mode: during a focus session the foreground app is
+// sampled every few seconds, and the session record gets how often it changed
+// and where the time went. Only executable names are kept, never window titles.
+// Sampling only works on Windows; elsewhere sessions are logged without it.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(2);
+// Apps listed in the summary, by time spent
+const TOP_APPS: usize = 3;
+
+/// Context switches during one focus session
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContextSwitches {
+    /// Times the foreground app changed
+    pub switches: u32,
+    /// Apps with the most foreground time, as (name, seconds)
+    pub top_apps: Vec<(String, u64)>,
+}
+
+#[derive(Default)]
+struct Samples {
+    current: Option<String>,
+    switches: u32,
+    seconds: HashMap<String, u64>,
+}
+
+/// Running sampler thread; stops when dropped
+pub struct FocusMonitor {
+    samples: Arc<Mutex<Samples>>,
+    stop: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl FocusMonitor {
+    pub fn start() -> Self {
+        let samples = Arc::new(Mutex::new(Samples::default()));
+        let stop = Arc::new(AtomicBool::new(false));
+
+        let thread_samples = samples.clone();
+        let stop_flag = stop.clone();
+        let handle = std::thread::spawn(move || {
+            while !stop_flag.load(Ordering::Relaxed) {
+                if let Some(app) = crate::dnd::foreground_app() {
+                    let mut samples = thread_samples.lock().unwrap();
+                    if samples.current.as_ref().is_some_and(|current| *current != app) {
+                        samples.switches += 1;
+                    }
+                    *samples.seconds.entry(app.clone()).or_default() += SAMPLE_INTERVAL.as_secs();
+                    samples.current = Some(app);
+                }
+                // Woken early when the monitor is stopped
+                std::thread::park_timeout(SAMPLE_INTERVAL);
+            }
+        });
+
+        Self {
+            samples,
+            stop,
+            handle: Some(handle),
+        }
+    }
+
+    /// Stop sampling and summarize; None if nothing could be sampled
+    pub fn finish(mut self) -> Option<ContextSwitches> {
+        self.stop_thread();
+        let samples = self.samples.lock().unwrap();
+        if samples.seconds.is_empty() {
+            return None;
+        }
+        let mut top_apps: Vec<(String, u64)> = samples.seconds.iter().map(|(app, secs)| (app.clone(), *secs)).collect();
+        top_apps.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        top_apps.truncate(TOP_APPS);
+        Some(ContextSwitches {
+            switches: samples.switches,
+            top_apps,
+        })
+    }
+
+    fn stop_thread(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            handle.thread().unpark();
+            let _ = handle.join();
+        }
+    }
+}
+
+impl Drop for FocusMonitor {
+    fn drop(&mut self) {
+        self.stop_thread();
+    }
+}
+
+/// One-line summary for the end-of-session notification
+pub fn summary(switches: &ContextSwitches) -> String {
+    let apps = switches
+        .top_apps
+        .iter()
+        .map(|(app, secs)| format!("{} {}m", app, secs.div_ceil(60)))
+        .collect::<Vec<_>>()
+        .join(", ");
+    crate::i18n::tf("focus_monitor.summary", &[("count", &switches.switches), ("apps", &apps)])
+}
@@ -0,0 +1,68 @@
+// GitHub module
+// Opt-in quick capture: a dictated "github: fix the login crash" files an issue
+// in the configured repository instead of going through the task parser. The
+// local task keeps the issue's URL (see `database::set_task_issue_url`).
+
+use crate::settings::GithubSettings;
+use serde::Deserialize;
+use std::time::Duration;
+
+const API_URL: &str = "https://api.github.com";
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(20);
+
+#[derive(Debug, Deserialize)]
+struct CreatedIssue {
+    html_url: String,
+}
+
+/// Issue title if the transcript is a GitHub command. Whisper may write the
+/// keyword as "GitHub:", "Github," or "github -", and may end with a period.
+pub fn issue_title(transcript: &str) -> Option<String> {
+    let transcript = transcript.trim();
+    let keyword = transcript.get(..6)?;
+    if !keyword.eq_ignore_ascii_case("github") {
+        return None;
+    }
+    let rest = &transcript[6..];
+    // "githubby" is not the keyword
+    if !rest.starts_with(|c: char| c.is_whitespace() || matches!(c, ':' | ',' | '-' | '.')) {
+        return None;
+    }
+    let title = rest
+        .trim_start_matches(|c: char| c.is_whitespace() || matches!(c, ':' | ',' | '-' | '.'))
+        .trim_end_matches(|c: char| c.is_whitespace() || c == '.');
+    (!title.is_empty()).then(|| title.to_string())
+}
+
+/// Create an issue in the configured repository and return its URL
+pub async fn create_issue(config: &GithubSettings, title: &str) -> Result<String, String> {
+    let client = reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .map_err(|e| e.to_string())?;
+    let response = client
+        .post(format!("{}/repos/{}/issues", API_URL, config.repo.trim()))
+        // GitHub rejects API requests without a User-Agent
+        .header("User-Agent", format!("FlowState/{}", crate::updates::current_version()))
+        .header("Accept", "application/vnd.github+json")
+        .bearer_auth(config.token.trim())
+        .json(&serde_json::json!({ "title": title }))
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    match response.status() {
+        reqwest::StatusCode::UNAUTHORIZED => return Err(crate::i18n::t("error.github_token")),
+        // GitHub answers 404 for private repos the token can't see
+        reqwest::StatusCode::NOT_FOUND | reqwest::StatusCode::FORBIDDEN => {
+            return Err(crate::i18n::tf("error.github_repo_access", &[("repo", &config.repo.trim())]));
+        }
+        status if !status.is_success() => return Err(format!("HTTP {}", status)),
+        _ => {}
+    }
+    let issue: CreatedIssue = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse GitHub issue: {}", e))?;
+    Ok(issue.html_url)
+}
@@ -0,0 +1,209 @@
+// Habits module
+// Recurring daily non-tasks ("meditate", "stretch") that don't fit the one-shot
+// task list. A habit has a schedule (every day or certain weekdays) and is
+// ticked off per local date; the streak is counted from that log rather than
+// stored, so un-ticking a day by mistake can't leave it out of step.
+// "log habit meditation" in a voice memo ticks it off for today.
+
+use crate::database::{self, Database, Habit};
+use chrono::{Datelike, Duration, Local, NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+pub const DAILY: &str = "daily";
+// Days shown as dots next to each habit, today last
+const RECENT_DAYS: i64 = 7;
+// How far back a streak is counted
+const MAX_STREAK_DAYS: i64 = 3660;
+
+const WEEKDAYS: [(&str, Weekday); 7] = [
+    ("mon", Weekday::Mon),
+    ("tue", Weekday::Tue),
+    ("wed", Weekday::Wed),
+    ("thu", Weekday::Thu),
+    ("fri", Weekday::Fri),
+    ("sat", Weekday::Sat),
+    ("sun", Weekday::Sun),
+];
+
+/// A habit with where it stands today
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HabitStatus {
+    pub id: i64,
+    pub name: String,
+    pub schedule: String,
+    /// Scheduled days done in a row (today counts once it's done)
+    pub streak: u32,
+    pub due_today: bool,
+    pub done_today: bool,
+    /// The last seven days, oldest first
+    pub recent: Vec<HabitDay>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HabitDay {
+    pub date: String,
+    pub due: bool,
+    pub done: bool,
+}
+
+/// Schedule in its stored form: "daily", or weekdays in week order ("mon,wed,fri")
+pub fn normalize_schedule(schedule: &str) -> Result<String, String> {
+    let schedule = schedule.trim().to_lowercase();
+    if schedule.is_empty() || schedule == DAILY {
+        return Ok(DAILY.to_string());
+    }
+    let mut days = HashSet::new();
+    for day in schedule.split(',').map(str::trim).filter(|day| !day.is_empty()) {
+        let known = WEEKDAYS
+            .iter()
+            .find(|(name, _)| day.starts_with(name))
+            .ok_or_else(|| crate::i18n::tf("error.habit_schedule", &[("schedule", &schedule)]))?;
+        days.insert(known.0);
+    }
+    if days.len() == WEEKDAYS.len() {
+        return Ok(DAILY.to_string());
+    }
+    Ok(WEEKDAYS
+        .iter()
+        .filter(|(name, _)| days.contains(name))
+        .map(|(name, _)| *name)
+        .collect::<Vec<_>>()
+        .join(","))
+}
+
+/// Whether the habit is scheduled on `date`
+pub fn is_due(schedule: &str, date: NaiveDate) -> bool {
+    if schedule == DAILY {
+        return true;
+    }
+    let weekday = date.weekday();
+    schedule
+        .split(',')
+        .any(|day| WEEKDAYS.iter().any(|(name, known)| *name == day && *known == weekday))
+}
+
+fn today() -> NaiveDate {
+    Local::now().date_naive()
+}
+
+fn date_key(date: NaiveDate) -> String {
+    date.format("%Y-%m-%d").to_string()
+}
+
+fn status(db: &Database, habit: Habit) -> Result<HabitStatus, String> {
+    let done: HashSet<String> = database::habit_log_dates(db, habit.id)
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .collect();
+    let today = today();
+    let is_done = |date: NaiveDate| done.contains(&date_key(date));
+
+    // Today doesn't break the streak until it's over
+    let mut streak = 0;
+    let mut date = if is_done(today) { today } else { today - Duration::days(1) };
+    for _ in 0..MAX_STREAK_DAYS {
+        if is_due(&habit.schedule, date) {
+            if !is_done(date) {
+                break;
+            }
+            streak += 1;
+        }
+        date -= Duration::days(1);
+    }
+
+    let recent = (0..RECENT_DAYS)
+        .rev()
+        .map(|ago| {
+            let date = today - Duration::days(ago);
+            HabitDay {
+                date: date_key(date),
+                due: is_due(&habit.schedule, date),
+                done: is_done(date),
+            }
+        })
+        .collect();
+
+    Ok(HabitStatus {
+        id: habit.id,
+        due_today: is_due(&habit.schedule, today),
+        done_today: is_done(today),
+        name: habit.name,
+        schedule: habit.schedule,
+        streak,
+        recent,
+    })
+}
+
+/// Every habit with its streak and recent days
+pub fn get_habits(db: &Database) -> Result<Vec<HabitStatus>, String> {
+    database::get_habits(db)
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(|habit| status(db, habit))
+        .collect()
+}
+
+pub fn add_habit(db: &Database, name: &str, schedule: &str) -> Result<HabitStatus, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(crate::i18n::t("error.habit_name_empty"));
+    }
+    let schedule = normalize_schedule(schedule)?;
+    let lower = name.to_lowercase();
+    let habits = database::get_habits(db).map_err(|e| e.to_string())?;
+    if habits.iter().any(|habit| habit.name.to_lowercase() == lower) {
+        return Err(crate::i18n::tf("error.habit_exists", &[("name", &name)]));
+    }
+    let habit = database::add_habit(db, name, &schedule).map_err(|e| e.to_string())?;
+    status(db, habit)
+}
+
+pub fn delete_habit(db: &Database, id: i64) -> Result<(), String> {
+    database::delete_habit(db, id).map_err(|e| e.to_string())
+}
+
+/// Tick today off, or un-tick it if it already was
+pub fn toggle_today(db: &Database, id: i64) -> Result<HabitStatus, String> {
+    let habit = database::get_habit(db, id).map_err(|e| e.to_string())?;
+    let today = date_key(today());
+    let done = database::habit_log_dates(db, id).map_err(|e| e.to_string())?.contains(&today);
+    database::set_habit_done(db, id, &today, !done).map_err(|e| e.to_string())?;
+    status(db, habit)
+}
+
+/// Tick off the habit named in a voice command for today
+pub fn log_by_name(db: &Database, spoken: &str) -> Result<HabitStatus, String> {
+    let habit = find_habit(db, spoken)?.ok_or_else(|| crate::i18n::tf("error.habit_not_found", &[("name", &spoken)]))?;
+    database::set_habit_done(db, habit.id, &date_key(today()), true).map_err(|e| e.to_string())?;
+    status(db, habit)
+}
+
+// Exact name first (ignoring case), then a name the words contain or that contains them
+fn find_habit(db: &Database, spoken: &str) -> Result<Option<Habit>, String> {
+    let spoken = spoken.trim().to_lowercase();
+    let habits = database::get_habits(db).map_err(|e| e.to_string())?;
+    let exact = habits.iter().position(|habit| habit.name.to_lowercase() == spoken);
+    let partial = || {
+        habits.iter().position(|habit| {
+            let name = habit.name.to_lowercase();
+            spoken.contains(&name) || name.contains(&spoken)
+        })
+    };
+    Ok(exact.or_else(partial).map(|index| habits[index].clone()))
+}
+
+/// The habit name in "log habit meditation" (also "track habit ...",
+/// "habit done ...", "отметить привычку ..."), or None for any other transcript
+pub fn habit_command(transcript: &str) -> Option<String> {
+    const PREFIXES: [&str; 5] = ["log habit", "track habit", "habit done", "отметить привычку", "отметь привычку"];
+    let text = transcript.trim().trim_end_matches(['.', '!', '?']);
+    let lower = text.to_lowercase();
+    PREFIXES.iter().find_map(|prefix| {
+        lower.starts_with(prefix).then_some(())?;
+        // Skip the prefix in the original text to keep the name's capitalization
+        let rest: String = text.chars().skip(prefix.chars().count()).collect();
+        let name = rest.trim_start_matches([':', ',']).trim();
+        (!name.is_empty()).then(|| name.to_string())
+    })
+}
@@ -0,0 +1,237 @@
+// History module
+// Re-parsing old voice notes after the parser gets better. Each transcribed
+// note is kept in `transcriptions` with the add/complete actions the parser
+// chain took from it. `reparse` runs those transcripts through the chain as it
+// is set up now and lists the notes it reads differently, without touching any
+// task (a dry run); `apply` then catches the picked notes up: new tasks are
+// added, new completions made, and open tasks only the old parse added are
+// deleted. Notes a command handled (timer, macros, habits...) kept no actions
+// and are left alone.
+
+use crate::database::{self, Database, Task, TranscriptionRow};
+use crate::parser::{ParsedMemo, ParserChain, ParserKind};
+use crate::settings::Settings;
+use chrono::{Duration, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ActionKind {
+    Add,
+    Complete,
+}
+
+/// One thing the parser took from a note
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HistoryAction {
+    pub kind: ActionKind,
+    /// The task text as parsed (for `Complete`, the words that picked the task)
+    pub text: String,
+    /// Due time the parser heard, in UTC
+    #[serde(default)]
+    pub due_at: Option<String>,
+    /// The task added or completed, once applied
+    #[serde(default)]
+    pub task_id: Option<i64>,
+}
+
+impl HistoryAction {
+    // The same action, whatever task it ended up on
+    fn same_as(&self, other: &HistoryAction) -> bool {
+        self.kind == other.kind && normalize(&self.text) == normalize(&other.text)
+    }
+}
+
+fn normalize(text: &str) -> String {
+    text.trim().trim_end_matches(['.', ',', '!', '?']).to_lowercase()
+}
+
+/// The actions in a parsed memo, before they're applied
+pub fn actions_from(parsed: &ParsedMemo) -> Vec<HistoryAction> {
+    parsed
+        .tasks
+        .iter()
+        .map(|task| HistoryAction {
+            kind: if task.completed { ActionKind::Complete } else { ActionKind::Add },
+            text: task.text.clone(),
+            due_at: task.due_at.clone(),
+            task_id: None,
+        })
+        .collect()
+}
+
+/// Actions as the JSON `database::log_transcription` keeps
+pub fn to_json(actions: &[HistoryAction]) -> String {
+    serde_json::to_string(actions).unwrap_or_else(|_| "[]".to_string())
+}
+
+/// Local dates to re-parse ("YYYY-MM-DD", both ends included); a missing end
+/// is left open
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HistoryRange {
+    pub from: Option<String>,
+    pub to: Option<String>,
+}
+
+impl HistoryRange {
+    // [start, end) as UTC timestamps
+    fn bounds(&self) -> Result<(Option<String>, Option<String>), String> {
+        let start = self.from.as_deref().map(parse_date).transpose()?;
+        let end = self.to.as_deref().map(parse_date).transpose()?;
+        Ok((
+            start.map(crate::report::utc_timestamp),
+            end.map(|end| crate::report::utc_timestamp(end + Duration::days(1))),
+        ))
+    }
+}
+
+fn parse_date(date: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d")
+        .map_err(|_| crate::i18n::tf("error.history_date", &[("date", &date.trim())]))
+}
+
+/// A note the current parser reads differently
+#[derive(Debug, Clone, Serialize)]
+pub struct ReparseDiff {
+    pub transcription_id: i64,
+    pub source: String,
+    /// When it was transcribed, in UTC
+    pub created_at: String,
+    pub transcript: String,
+    /// The parser the new actions came from
+    pub parser: ParserKind,
+    /// Actions only the current parser takes
+    pub added: Vec<HistoryAction>,
+    /// Actions only the old parse took
+    pub removed: Vec<HistoryAction>,
+    /// Actions both agree on
+    pub unchanged: usize,
+}
+
+/// What `apply` changed in the task list
+#[derive(Debug, Clone, Default)]
+pub struct ReparseApplied {
+    pub added: Vec<Task>,
+    pub completed: Vec<Task>,
+    pub deleted: Vec<Task>,
+}
+
+/// Re-parse the notes in `range` with the chain in the settings and list the
+/// ones that come out differently. Nothing is changed.
+pub async fn reparse(db: &Database, settings: &Settings, range: &HistoryRange) -> Result<Vec<ReparseDiff>, String> {
+    let (start, end) = range.bounds()?;
+    let notes = database::parsed_transcriptions_between(db, start.as_deref(), end.as_deref()).map_err(|e| e.to_string())?;
+    let chain = ParserChain::from_settings(settings);
+    let mut diffs = Vec::new();
+    for note in notes {
+        let diff = reparse_note(&chain, &note).await?.diff;
+        if !diff.added.is_empty() || !diff.removed.is_empty() {
+            diffs.push(diff);
+        }
+    }
+    Ok(diffs)
+}
+
+/// Re-parse the notes with these ids and apply what changed. Their kept
+/// actions become the new ones, so a second run finds nothing to do.
+pub async fn apply(db: &Database, settings: &Settings, ids: &[i64]) -> Result<ReparseApplied, String> {
+    let chain = ParserChain::from_settings(settings);
+    let mut applied = ReparseApplied::default();
+    for &id in ids {
+        let note = database::get_parsed_transcription(db, id).map_err(|e| e.to_string())?;
+        let Reparsed { diff, mut actions, new } = reparse_note(&chain, &note).await?;
+        if diff.added.is_empty() && diff.removed.is_empty() {
+            continue;
+        }
+
+        let mut new_tasks = Vec::new();
+        let mut new_actions = Vec::new();
+        for (action, _) in actions.iter_mut().zip(&new).filter(|&(_, &new)| new) {
+            match action.kind {
+                ActionKind::Complete => {
+                    if let Ok(task) = database::find_and_complete_task(db, &action.text) {
+                        action.task_id = Some(task.id);
+                        applied.completed.push(task);
+                    }
+                }
+                ActionKind::Add => {
+                    new_tasks.push((action.text.clone(), action.due_at.clone()));
+                    new_actions.push(action);
+                }
+            }
+        }
+        let added = database::add_tasks_with_due(db, &new_tasks, settings.voice_inbox).map_err(|e| e.to_string())?;
+        for (task, action) in added.into_iter().zip(new_actions) {
+            action.task_id = Some(task.id);
+            applied.added.push(task);
+        }
+
+        // Tasks the old parse added by mistake, unless they've been done since
+        let stale: Vec<i64> = diff
+            .removed
+            .iter()
+            .filter(|action| action.kind == ActionKind::Add)
+            .filter_map(|action| action.task_id)
+            .filter(|&id| database::get_task_by_id(db, id).is_ok_and(|task| !task.completed))
+            .collect();
+        applied.deleted.extend(database::delete_tasks(db, &stale).map_err(|e| e.to_string())?);
+
+        database::set_transcription_actions(db, note.id, &to_json(&actions)).map_err(|e| e.to_string())?;
+    }
+    Ok(applied)
+}
+
+// One note read again: the diff, and the new actions carrying over the task
+// ids of the ones the old parse also took (`new` marks the others)
+struct Reparsed {
+    diff: ReparseDiff,
+    actions: Vec<HistoryAction>,
+    new: Vec<bool>,
+}
+
+async fn reparse_note(chain: &ParserChain, note: &TranscriptionRow) -> Result<Reparsed, String> {
+    let old: Vec<HistoryAction> = serde_json::from_str(&note.actions)
+        .map_err(|e| format!("Invalid actions for transcription {}: {}", note.id, e))?;
+    let parsed = chain
+        .parse(&note.transcript)
+        .await
+        .map_err(|e| crate::i18n::tf("error.parse_transcript", &[("error", &e)]))?;
+    let mut actions = actions_from(&parsed);
+
+    let mut matched = vec![false; old.len()];
+    let mut new = Vec::with_capacity(actions.len());
+    for action in &mut actions {
+        match (0..old.len()).find(|&index| !matched[index] && old[index].same_as(action)) {
+            Some(index) => {
+                matched[index] = true;
+                action.task_id = old[index].task_id;
+                new.push(false);
+            }
+            None => new.push(true),
+        }
+    }
+    let added: Vec<HistoryAction> = actions
+        .iter()
+        .zip(&new)
+        .filter(|&(_, &new)| new)
+        .map(|(action, _)| action.clone())
+        .collect();
+    let removed: Vec<HistoryAction> = old
+        .iter()
+        .zip(&matched)
+        .filter(|&(_, &matched)| !matched)
+        .map(|(action, _)| action.clone())
+        .collect();
+
+    let diff = ReparseDiff {
+        transcription_id: note.id,
+        source: note.source.clone(),
+        created_at: note.created_at.clone(),
+        transcript: note.transcript.clone(),
+        parser: parsed.parser,
+        unchanged: actions.len() - added.len(),
+        added,
+        removed,
+    };
+    Ok(Reparsed { diff, actions, new })
+}
@@ -0,0 +1,128 @@
+// i18n module
+// Key-based message catalogs for user-facing text: errors returned by commands,
+// status messages and the egui UI. Catalogs live in `locales/<code>.txt` as
+// `key = value` lines; values may contain `{name}` placeholders filled by `tf`.
+// Missing keys fall back to English, then to the key itself.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::OnceLock;
+
+const EN_CATALOG: &str = include_str!("../locales/en.txt");
+const RU_CATALOG: &str = include_str!("../locales/ru.txt");
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Locale {
+    #[serde(rename = "en")]
+    English,
+    #[serde(rename = "ru")]
+    Russian,
+}
+
+impl Locale {
+    pub const ALL: [Locale; 2] = [Locale::English, Locale::Russian];
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            Locale::English => "en",
+            Locale::Russian => "ru",
+        }
+    }
+
+    /// Name of the language in itself, for language pickers
+    pub fn native_name(&self) -> &'static str {
+        match self {
+            Locale::English => "English",
+            Locale::Russian => "Русский",
+        }
+    }
+
+    /// Match a language tag such as "ru", "ru_RU.UTF-8" or "en-US"
+    pub fn from_tag(tag: &str) -> Option<Locale> {
+        let primary = tag.split(['_', '-', '.']).next()?.to_lowercase();
+        Locale::ALL.into_iter().find(|locale| locale.code() == primary)
+    }
+
+    fn from_index(index: u8) -> Locale {
+        Locale::ALL.get(index as usize).copied().unwrap_or(Locale::English)
+    }
+
+    fn index(&self) -> u8 {
+        Locale::ALL.iter().position(|locale| locale == self).unwrap_or(0) as u8
+    }
+
+    fn catalog(&self) -> &'static HashMap<&'static str, &'static str> {
+        static EN: OnceLock<HashMap<&str, &str>> = OnceLock::new();
+        static RU: OnceLock<HashMap<&str, &str>> = OnceLock::new();
+        match self {
+            Locale::English => EN.get_or_init(|| parse_catalog(EN_CATALOG)),
+            Locale::Russian => RU.get_or_init(|| parse_catalog(RU_CATALOG)),
+        }
+    }
+}
+
+// Index into Locale::ALL of the active locale
+static CURRENT: AtomicU8 = AtomicU8::new(0);
+
+fn parse_catalog(source: &'static str) -> HashMap<&'static str, &'static str> {
+    source
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| (key.trim(), value.trim()))
+        .collect()
+}
+
+/// Locale of the OS user interface, English if it isn't one we ship
+pub fn system_locale() -> Locale {
+    #[cfg(target_os = "windows")]
+    {
+        // Primary language id of the UI language; 0x19 is LANG_RUSSIAN
+        let lang_id = unsafe { windows::Win32::Globalization::GetUserDefaultUILanguage() };
+        if lang_id & 0x3ff == 0x19 {
+            return Locale::Russian;
+        }
+    }
+
+    ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|value| !value.is_empty())
+        .and_then(|value| Locale::from_tag(&value))
+        .unwrap_or(Locale::English)
+}
+
+/// Switch the active locale (None follows the system language)
+pub fn set_locale(locale: Option<Locale>) {
+    let locale = locale.unwrap_or_else(system_locale);
+    CURRENT.store(locale.index(), Ordering::Relaxed);
+    tracing::debug!("UI language: {}", locale.code());
+}
+
+pub fn locale() -> Locale {
+    Locale::from_index(CURRENT.load(Ordering::Relaxed))
+}
+
+/// Message for `key` in the active locale
+pub fn t(key: &str) -> String {
+    locale()
+        .catalog()
+        .get(key)
+        .or_else(|| Locale::English.catalog().get(key))
+        .map(|value| value.to_string())
+        .unwrap_or_else(|| {
+            tracing::warn!("Missing translation: {}", key);
+            key.to_string()
+        })
+}
+
+/// Message for `key` with its `{name}` placeholders filled in
+pub fn tf(key: &str, args: &[(&str, &dyn Display)]) -> String {
+    args.iter().fold(t(key), |message, (name, value)| {
+        message.replace(&format!("{{{}}}", name), &value.to_string())
+    })
+}
+
@@ -0,0 +1,37 @@
+// Inbox module
+// With `settings.voice_inbox` on, tasks captured by voice land in an inbox
+// instead of the main list, so rough dictation can be looked over first. Each
+// one is then triaged: committed to the list as is, scheduled (committed with
+// a due date, read by `dates`), or discarded. Typed tasks always go straight
+// to the list. The inbox is the `inbox` flag on a task, not a separate table,
+// so voice commands like "mark ... done" still find tasks waiting in it.
+
+use crate::database::{self, Database, Task};
+use chrono::Local;
+
+/// Open tasks waiting in the inbox, for the badge
+pub fn count(db: &Database) -> usize {
+    database::count_inbox_tasks(db).unwrap_or_else(|e| {
+        tracing::warn!("Failed to count inbox tasks: {}", e);
+        0
+    })
+}
+
+/// Move tasks from the inbox to the main list as they are
+pub fn commit(db: &Database, ids: &[i64]) -> Result<Vec<Task>, String> {
+    database::commit_inbox_tasks(db, ids).map_err(|e| e.to_string())
+}
+
+/// Move a task to the main list, due `when`: "tomorrow", "friday at 3pm",
+/// "next week", "2026-05-12" (see `dates::parse_due`)
+pub fn schedule(db: &Database, id: i64, when: &str) -> Result<Task, String> {
+    let at = crate::dates::parse_due(when, Local::now().naive_local())
+        .ok_or_else(|| crate::i18n::tf("error.inbox_when", &[("when", &when.trim())]))?;
+    let due_at = crate::dates::to_db(at).ok_or_else(|| crate::i18n::tf("error.inbox_when", &[("when", &when.trim())]))?;
+    database::schedule_inbox_task(db, id, &due_at).map_err(|e| e.to_string())
+}
+
+/// Delete tasks from the inbox, returning the ones that were there
+pub fn discard(db: &Database, ids: &[i64]) -> Result<Vec<Task>, String> {
+    database::delete_tasks(db, ids).map_err(|e| e.to_string())
+}
@@ -0,0 +1,63 @@
+//! FlowState core library
+//! Everything both frontends need: audio decoding and analysis, Whisper model
+//! management and transcription, transcript parsing, SQLite storage, and sync
+//! with outside apps (Markdown notes, Todoist), and the Telegram capture bridge.
+//! The Tauri binary (`main.rs`) and the egui binary (`native_main.rs`) only add UI,
+//! the timer loop and their platform glue on top.
+
+pub mod achievements;
+pub mod action_mode;
+pub mod api_tokens;
+pub mod ask;
+pub mod audio;
+pub mod benchmark;
+pub mod clipboard;
+pub mod config_file;
+pub mod content_filter;
+pub mod contexts;
+pub mod crash;
+pub mod database;
+pub mod databases;
+pub mod dates;
+pub mod diagnostics;
+pub mod disambiguation;
+pub mod dnd;
+pub mod downloads;
+pub mod earcon;
+pub mod error;
+pub mod estimates;
+pub mod focus_monitor;
+pub mod github;
+pub mod habits;
+pub mod history;
+pub mod i18n;
+pub mod inbox;
+pub mod logging;
+pub mod macros;
+pub mod metrics;
+pub mod model_store;
+pub mod mqtt;
+pub mod navigation;
+pub mod ollama;
+pub mod openai;
+pub mod parser;
+pub mod paths;
+pub mod power;
+pub mod presence;
+pub mod profiles;
+pub mod punctuation;
+pub mod reminders;
+pub mod report;
+pub mod rollover;
+pub mod schedule;
+pub mod settings;
+pub mod speaker;
+pub mod speech;
+pub mod startup;
+pub mod stt;
+pub mod sync;
+pub mod telegram;
+pub mod timer_control;
+pub mod updates;
+pub mod wakeword;
+pub mod whisper;
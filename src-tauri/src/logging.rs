@@ -0,0 +1,106 @@
+// Logging module
+// tracing setup shared by both binaries: human-readable output on stderr plus a
+// daily-rolling log file that users can attach to bug reports
+
+use std::fs;
+use std::path::PathBuf;
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::prelude::*;
+use tracing_subscriber::{fmt, EnvFilter};
+
+const LOG_FILE_PREFIX: &str = "flowstate";
+const LOG_FILE_SUFFIX: &str = "log";
+// Days of log files kept on disk
+const MAX_LOG_FILES: usize = 7;
+
+/// Directory holding the rolling log files (`<data dir>/flowstate/logs`)
+pub fn log_dir() -> PathBuf {
+    crate::paths::data_dir().join("logs")
+}
+
+/// Install the global subscriber. Keep the returned guard alive for the whole
+/// program, dropping it flushes and stops the file writer.
+pub fn init() -> Option<WorkerGuard> {
+    init_with_default_level("info")
+}
+
+/// Same as `init`, with a different level when RUST_LOG is unset (quieter for the CLI)
+pub fn init_with_default_level(level: &str) -> Option<WorkerGuard> {
+    // RUST_LOG overrides the default level, e.g. RUST_LOG=flowstate_core=debug
+    let filter = || EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(level));
+    let stderr_layer = fmt::layer().with_writer(std::io::stderr).with_filter(filter());
+
+    let file_appender = RollingFileAppender::builder()
+        .rotation(Rotation::DAILY)
+        .filename_prefix(LOG_FILE_PREFIX)
+        .filename_suffix(LOG_FILE_SUFFIX)
+        .max_log_files(MAX_LOG_FILES)
+        .build(log_dir());
+
+    match file_appender {
+        Ok(appender) => {
+            let (writer, guard) = tracing_appender::non_blocking(appender);
+            let file_layer = fmt::layer()
+                .with_ansi(false)
+                .with_writer(writer)
+                .with_filter(filter());
+            tracing_subscriber::registry()
+                .with(stderr_layer)
+                .with(file_layer)
+                .init();
+            Some(guard)
+        }
+        Err(e) => {
+            // Still log to stderr when the log directory can't be created
+            tracing_subscriber::registry().with(stderr_layer).init();
+            tracing::warn!("File logging disabled: {}", e);
+            None
+        }
+    }
+}
+
+/// Last `max_lines` log lines across the newest log files, oldest first
+pub fn recent_logs(max_lines: usize) -> Result<Vec<String>, String> {
+    let mut files: Vec<PathBuf> = fs::read_dir(log_dir())
+        .map_err(|e| format!("Failed to read log directory: {}", e))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with(LOG_FILE_PREFIX))
+        })
+        .collect();
+    // Date-stamped names sort chronologically
+    files.sort();
+
+    let mut lines = Vec::new();
+    for path in files.iter().rev() {
+        let contents = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read log file: {}", e))?;
+        let mut file_lines: Vec<String> = contents.lines().map(str::to_string).collect();
+        file_lines.append(&mut lines);
+        lines = file_lines;
+        if lines.len() >= max_lines {
+            break;
+        }
+    }
+
+    let skip = lines.len().saturating_sub(max_lines);
+    Ok(lines.split_off(skip))
+}
+
+/// Plain-text report for bug reports: version, platform and recent log lines
+pub fn diagnostics_report(max_lines: usize) -> String {
+    let logs = recent_logs(max_lines)
+        .map(|lines| lines.join("\n"))
+        .unwrap_or_else(|e| format!("(logs unavailable: {})", e));
+    format!(
+        "FlowState {}\nOS: {} ({})\nLog directory: {}\n\n{}",
+        env!("CARGO_PKG_VERSION"),
+        std::env::consts::OS,
+        std::env::consts::ARCH,
+        log_dir().display(),
+        logs,
+    )
+}
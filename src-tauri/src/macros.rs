@@ -0,0 +1,136 @@
+// Macros module
+// User-defined voice macros: a phrase like "standup" that runs a fixed list of
+// actions (add these three tasks, archive what's done, pause the timer) instead
+// of going through the parser. A transcript runs a macro when it is the phrase
+// and nothing else, give or take case and end punctuation, so a memo that
+// merely mentions the word still becomes tasks. Macros are checked before the
+// other voice commands, so one can take over a phrase they'd otherwise handle.
+
+use crate::database::{self, Database, MacroRow, Task};
+use crate::timer_control::TimerCommand;
+use serde::{Deserialize, Serialize};
+
+/// One step of a macro
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum MacroAction {
+    AddTask { text: String },
+    /// Archive every completed task still in the lists
+    ArchiveCompleted,
+    Timer { command: TimerCommand },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VoiceMacro {
+    pub id: i64,
+    pub phrase: String,
+    /// Run in order
+    pub actions: Vec<MacroAction>,
+}
+
+/// What running a macro did. Timer commands are left to the caller, since the
+/// timer lives in the UI process.
+#[derive(Debug, Clone, Default)]
+pub struct MacroRun {
+    pub added: Vec<Task>,
+    pub archived: usize,
+    pub timer: Vec<TimerCommand>,
+}
+
+/// A phrase as it's matched: lowercase, single-spaced, no end punctuation
+pub fn normalize_phrase(phrase: &str) -> String {
+    phrase
+        .trim()
+        .trim_end_matches(['.', '!', '?', ','])
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+fn from_row(row: MacroRow) -> Result<VoiceMacro, String> {
+    let actions = serde_json::from_str(&row.actions).map_err(|e| format!("Invalid macro \"{}\": {}", row.phrase, e))?;
+    Ok(VoiceMacro {
+        id: row.id,
+        phrase: row.phrase,
+        actions,
+    })
+}
+
+/// Every macro, in the order they were added. One that can't be read is
+/// skipped (and logged) rather than hiding the rest.
+pub fn get_macros(db: &Database) -> Result<Vec<VoiceMacro>, String> {
+    let rows = database::get_macros(db).map_err(|e| e.to_string())?;
+    Ok(rows
+        .into_iter()
+        .filter_map(|row| from_row(row).map_err(|e| tracing::warn!("{}", e)).ok())
+        .collect())
+}
+
+// The phrase to store, with the actions checked
+fn validate(db: &Database, id: Option<i64>, phrase: &str, actions: &[MacroAction]) -> Result<String, String> {
+    let phrase = normalize_phrase(phrase);
+    if phrase.is_empty() {
+        return Err(crate::i18n::t("error.macro_phrase_empty"));
+    }
+    if actions.is_empty() {
+        return Err(crate::i18n::tf("error.macro_no_actions", &[("phrase", &phrase)]));
+    }
+    if actions.iter().any(|action| matches!(action, MacroAction::AddTask { text } if text.trim().is_empty())) {
+        return Err(crate::i18n::t("error.macro_task_empty"));
+    }
+    let macros = database::get_macros(db).map_err(|e| e.to_string())?;
+    if macros.iter().any(|existing| Some(existing.id) != id && normalize_phrase(&existing.phrase) == phrase) {
+        return Err(crate::i18n::tf("error.macro_exists", &[("phrase", &phrase)]));
+    }
+    Ok(phrase)
+}
+
+fn actions_json(actions: &[MacroAction]) -> Result<String, String> {
+    serde_json::to_string(actions).map_err(|e| e.to_string())
+}
+
+pub fn add_macro(db: &Database, phrase: &str, actions: Vec<MacroAction>) -> Result<VoiceMacro, String> {
+    let phrase = validate(db, None, phrase, &actions)?;
+    let row = database::add_macro(db, &phrase, &actions_json(&actions)?).map_err(|e| e.to_string())?;
+    from_row(row)
+}
+
+pub fn update_macro(db: &Database, id: i64, phrase: &str, actions: Vec<MacroAction>) -> Result<VoiceMacro, String> {
+    let phrase = validate(db, Some(id), phrase, &actions)?;
+    let row = database::update_macro(db, id, &phrase, &actions_json(&actions)?).map_err(|e| e.to_string())?;
+    from_row(row)
+}
+
+pub fn delete_macro(db: &Database, id: i64) -> Result<(), String> {
+    database::delete_macro(db, id).map_err(|e| e.to_string())
+}
+
+/// The macro whose phrase is the whole transcript, if any
+pub fn find_macro(db: &Database, transcript: &str) -> Option<VoiceMacro> {
+    let spoken = normalize_phrase(transcript);
+    if spoken.is_empty() {
+        return None;
+    }
+    get_macros(db).ok()?.into_iter().find(|voice_macro| voice_macro.phrase == spoken)
+}
+
+/// Run the macro's actions against the database; the tasks it adds go in together
+pub fn run(db: &Database, voice_macro: &VoiceMacro) -> Result<MacroRun, String> {
+    tracing::info!("Macro: {}", voice_macro.phrase);
+    let mut run = MacroRun::default();
+    let mut texts = Vec::new();
+    for action in &voice_macro.actions {
+        match action {
+            MacroAction::AddTask { text } => texts.push(text.trim().to_string()),
+            MacroAction::ArchiveCompleted => {
+                run.archived += database::archive_completed(db).map_err(|e| e.to_string())?;
+            }
+            MacroAction::Timer { command } => run.timer.push(*command),
+        }
+    }
+    if !texts.is_empty() {
+        run.added = database::add_tasks(db, &texts).map_err(|e| e.to_string())?;
+    }
+    Ok(run)
+}
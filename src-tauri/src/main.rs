@@ -2,27 +2,103 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 mod commands;
-mod database;
+mod processing;
+mod scheduler;
+mod shutdown;
 mod timer;
-mod whisper;
-mod ollama;
+mod tray;
 
-use tauri::Manager;
+use tauri::{Emitter, Manager};
 
 fn main() {
+    // --data-dir moves everything, the log files included
+    flowstate_core::paths::init(std::env::args().skip(1));
+    // Keep the guard for the lifetime of the app so buffered log lines get flushed
+    let _log_guard = flowstate_core::logging::init();
+    flowstate_core::crash::install_panic_hook();
+
     tauri::Builder::default()
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_shell::init())
+        .plugin(tauri_plugin_notification::init())
+        .plugin(tauri_plugin_updater::Builder::new().build())
+        .plugin(
+            // Quick add and compact mode are the global shortcuts; tell them apart by the configured keys
+            tauri_plugin_global_shortcut::Builder::new()
+                .with_handler(|app, shortcut, event| {
+                    use tauri_plugin_global_shortcut::{Shortcut, ShortcutState};
+
+                    if event.state() != ShortcutState::Pressed {
+                        return;
+                    }
+                    let settings = flowstate_core::settings::load(&app.state::<flowstate_core::database::Database>());
+                    if settings.compact_mode_shortcut.parse::<Shortcut>().ok().as_ref() == Some(shortcut) {
+                        commands::toggle_compact_mode(app);
+                    } else {
+                        commands::toggle_quick_add(app);
+                    }
+                })
+                .build(),
+        )
         .setup(|app| {
-            // Initialize database
-            let app_handle_for_db = app.handle().clone();
-            let db = database::init_database(&app_handle_for_db)?;
+            // Open (and if need be repair) the database; problems go into the startup report
+            let (db, database_check) = flowstate_core::startup::open_database(&flowstate_core::paths::app_data_dir(app.handle())?);
+            // flowstate.toml next to the database sets defaults; keep applying it when it's edited
+            if let Err(e) = flowstate_core::config_file::apply(&db) {
+                tracing::warn!("{}", e);
+            }
+            if let Some(path) = flowstate_core::config_file::path(&db) {
+                let config_app = app.handle().clone();
+                let on_change = Box::new(move |file| commands::apply_config_file(&config_app, file));
+                match flowstate_core::config_file::ConfigWatcher::start(path, on_change) {
+                    Ok(watcher) => {
+                        app.manage(watcher);
+                    }
+                    Err(e) => tracing::warn!("Failed to watch the config file: {}", e),
+                }
+            }
+            let settings = flowstate_core::settings::load(&db);
+            flowstate_core::earcon::set_muted(settings.earcons_muted);
+            flowstate_core::i18n::set_locale(settings.ui_language);
+            commands::restore_window_state(app.handle(), &db);
             app.manage(db);
 
+            // Models used to live in the Tauri app-data dir; move them to the shared store
+            if let Ok(app_data_dir) = flowstate_core::paths::app_data_dir(app.handle()) {
+                flowstate_core::model_store::ModelStore::open_or_temp().migrate_from(&app_data_dir.join("whisper_models"));
+            }
+
+            // Offer the report from a crash in the previous run
+            if let Some(report) = flowstate_core::crash::take_pending_report() {
+                show_crash_report_dialog(app.handle(), report);
+            }
+
+            // Recordings and downloads from a previous run that crashed or was killed
+            let temp_dir = shutdown::temp_audio_dir(app.handle()).ok();
+            let temp_check = flowstate_core::startup::clean_temp_files(&flowstate_core::model_store::ModelStore::open_or_temp(), temp_dir.as_deref());
+
             // Initialize Whisper model cache (avoids reloading model on every recording)
-            let whisper_cache = whisper::WhisperCache::new();
+            let whisper_cache = flowstate_core::whisper::WhisperCache::new();
             app.manage(whisper_cache);
 
+            // Loading the model can take a while; the report follows as an event
+            app.manage(flowstate_core::startup::StartupReportState::default());
+            let startup_app = app.handle().clone();
+            tauri::async_runtime::spawn_blocking(move || {
+                commands::finish_startup_report(&startup_app, vec![database_check, temp_check]);
+            });
+
+            // Voice jobs take turns (see processing.rs)
+            app.manage(processing::ProcessingState::default());
+            // Completions waiting for the user to say which task they meant
+            app.manage(flowstate_core::disambiguation::DisambiguationState::default());
+
+            // Model downloads run in the background; the UI follows "model-download" events
+            let download_events = app.handle().clone();
+            app.manage(flowstate_core::downloads::DownloadManager::new(move |event| {
+                let _ = download_events.emit("model-download", event);
+            }));
+
             // Setup global shortcut (Win + Alt + R) - DISABLED
             // let app_handle = app.handle().clone();
             // tauri::async_runtime::spawn(async move {
@@ -31,48 +107,246 @@ fn main() {
             //     }
             // });
 
+            // Start hands-free wake-word listener if the user opted in
+            let wake_word_state = flowstate_core::wakeword::WakeWordState::default();
+            if settings.wake_word_enabled {
+                match commands::start_wake_word_listener(app.handle()) {
+                    Ok(listener) => *wake_word_state.listener.lock().unwrap() = Some(listener),
+                    Err(e) => tracing::warn!("Failed to start wake-word listener: {}", e),
+                }
+            }
+            app.manage(wake_word_state);
+
+            // Mirror the task list into a Markdown note if one is set up
+            let markdown_sync = flowstate_core::sync::markdown::MarkdownSyncState::default();
+            if settings.markdown_sync.enabled {
+                match commands::start_markdown_sync(app.handle(), &settings.markdown_sync) {
+                    Ok(sync) => *markdown_sync.sync.lock().unwrap() = Some(sync),
+                    Err(e) => tracing::warn!("Failed to start Markdown sync: {}", e),
+                }
+            }
+            app.manage(markdown_sync);
+
+            // Keep the .ics feed of dated tasks current
+            let calendar_export = flowstate_core::sync::calendar::CalendarExportState::default();
+            if settings.calendar_export.enabled {
+                let db = app.state::<flowstate_core::database::Database>();
+                match flowstate_core::sync::calendar::CalendarExport::start(&db, &settings.calendar_export) {
+                    Ok(export) => *calendar_export.export.lock().unwrap() = Some(export),
+                    Err(e) => tracing::warn!("Failed to start the calendar export: {}", e),
+                }
+            }
+            app.manage(calendar_export);
+
+            let google_calendar_sync = flowstate_core::sync::google_calendar::GoogleCalendarSyncState::default();
+            if settings.google_calendar.enabled {
+                match commands::start_google_calendar_sync(app.handle(), &settings.google_calendar) {
+                    Ok(sync) => *google_calendar_sync.sync.lock().unwrap() = Some(sync),
+                    Err(e) => tracing::warn!("Failed to start the Google Calendar push: {}", e),
+                }
+            }
+            app.manage(google_calendar_sync);
+
+            let todoist_sync = flowstate_core::sync::todoist::TodoistSyncState::default();
+            if settings.todoist.enabled {
+                match commands::start_todoist_sync(app.handle(), &settings.todoist) {
+                    Ok(sync) => *todoist_sync.sync.lock().unwrap() = Some(sync),
+                    Err(e) => tracing::warn!("Failed to start Todoist sync: {}", e),
+                }
+            }
+            app.manage(todoist_sync);
+
+            // Publish state for Home Assistant and similar dashboards
+            let mqtt_state = flowstate_core::mqtt::MqttState::default();
+            if settings.mqtt.enabled {
+                match flowstate_core::mqtt::MqttPublisher::start(&settings.mqtt) {
+                    Ok(publisher) => *mqtt_state.publisher.lock().unwrap() = Some(publisher),
+                    Err(e) => tracing::warn!("Failed to start MQTT publisher: {}", e),
+                }
+            }
+            app.manage(mqtt_state);
+            tauri::async_runtime::spawn(commands::run_mqtt_status(app.handle().clone()));
+
+            let clipboard_watcher = flowstate_core::clipboard::ClipboardWatcherState::default();
+            if settings.clipboard.enabled {
+                match commands::start_clipboard_watcher(app.handle(), &settings.clipboard) {
+                    Ok(watcher) => *clipboard_watcher.watcher.lock().unwrap() = Some(watcher),
+                    Err(e) => tracing::warn!("Failed to start clipboard watcher: {}", e),
+                }
+            }
+            app.manage(clipboard_watcher);
+
+            // Take tasks sent to the Telegram bot from the phone
+            let telegram_bridge = flowstate_core::telegram::TelegramBridgeState::default();
+            if settings.telegram.enabled {
+                match commands::start_telegram_bridge(app.handle(), &settings.telegram) {
+                    Ok(bridge) => *telegram_bridge.bridge.lock().unwrap() = Some(bridge),
+                    Err(e) => tracing::warn!("Failed to start Telegram bridge: {}", e),
+                }
+            }
+            app.manage(telegram_bridge);
+
+            for shortcut in [&settings.quick_add_shortcut, &settings.compact_mode_shortcut] {
+                if let Err(e) = commands::register_global_shortcut(app.handle(), None, shortcut) {
+                    tracing::warn!("{}", e);
+                }
+            }
+
+            if let Err(e) = tray::setup_tray(app.handle()) {
+                tracing::warn!("Failed to create tray icon: {}", e);
+            }
+
             // Setup awareness timer
             timer::setup_awareness_timer(app.handle().clone());
 
+            // Fire per-task reminders
+            scheduler::setup_reminder_scheduler(app.handle().clone());
+
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
             commands::get_tasks,
             commands::add_task,
             commands::update_task,
+            commands::set_task_due,
+            commands::reorder_tasks,
             commands::delete_task,
             commands::toggle_task,
+            commands::add_tasks,
+            commands::quick_add_task,
+            commands::close_quick_add,
+            commands::delete_tasks,
+            commands::complete_tasks,
+            commands::set_tasks_project,
+            commands::tag_tasks,
+            commands::set_recording_state,
+            commands::ask_tasks,
+            commands::generate_weekly_report,
+            commands::get_workload,
+            commands::get_usage_metrics,
+            commands::reparse_history,
+            commands::apply_reparse,
+            commands::speak_tasks,
+            commands::switch_profile,
+            commands::list_databases,
+            commands::switch_database,
+            commands::set_reminder,
+            commands::clear_reminder,
+            commands::get_reminders,
+            commands::get_habits,
+            commands::add_habit,
+            commands::delete_habit,
+            commands::toggle_habit,
+            commands::get_macros,
+            commands::add_macro,
+            commands::update_macro,
+            commands::delete_macro,
+            commands::get_api_tokens,
+            commands::issue_api_token,
+            commands::revoke_api_token,
+            commands::get_achievements,
+            commands::get_inbox_count,
+            commands::commit_inbox_tasks,
+            commands::schedule_inbox_task,
+            commands::discard_inbox_tasks,
             commands::process_voice_log,
+            commands::get_processing_status,
+            commands::enroll_speaker,
+            commands::get_disambiguation,
+            commands::resolve_disambiguation,
             commands::get_timer_status,
             commands::reset_timer,
+            commands::get_timer_paused,
+            commands::start_focus,
+            commands::stop_focus,
+            commands::get_focus_session,
+            commands::get_task_time_entries,
+            commands::get_focus_history,
+            commands::acknowledge_alert,
+            commands::get_break_remaining,
+            commands::end_break,
+            commands::process_journal_recording,
+            commands::get_journal_entries,
             commands::get_timer_duration,
             commands::set_timer_duration,
+            commands::get_settings,
+            commands::update_settings,
+            commands::connect_google_calendar,
             commands::set_always_on_top,
+            commands::set_compact_mode,
             commands::get_window_state,
-            commands::set_window_state,
             commands::save_window_state,
-            commands::load_window_state,
             commands::list_whisper_models,
             commands::download_whisper_model,
+            commands::cancel_model_download,
+            commands::repair_whisper_model,
+            commands::get_model_downloads,
             commands::check_whisper_model,
             commands::delete_whisper_model,
+            commands::unload_whisper_model,
+            commands::benchmark_whisper,
             commands::transcribe_audio,
             commands::save_audio_file,
             commands::process_voice_recording,
+            commands::import_audio_file,
             commands::get_autostart_enabled,
             commands::set_autostart_enabled,
-            commands::get_ollama_enabled,
-            commands::set_ollama_enabled,
+            commands::play_earcon,
+            commands::preview_alert_sound,
+            commands::get_recent_logs,
+            commands::run_diagnostics,
+            commands::get_startup_report,
+            commands::get_dnd_state,
+            commands::exit_app,
+            commands::check_for_updates,
+            commands::install_update,
         ])
         .on_window_event(|window, event| {
+            // Only the main window is kept alive; overlays and quick add really close
             if let tauri::WindowEvent::CloseRequested { api, .. } = event {
-                // Hide window instead of closing
-                window.hide().unwrap();
+                if window.label() != "main" {
+                    return;
+                }
                 api.prevent_close();
+                let db = window.state::<flowstate_core::database::Database>();
+                match flowstate_core::settings::load(&db).close_behavior {
+                    flowstate_core::settings::CloseBehavior::Hide => {
+                        let _ = window.hide();
+                    }
+                    flowstate_core::settings::CloseBehavior::Quit => {
+                        tauri::async_runtime::spawn(shutdown::quit(window.app_handle().clone()));
+                    }
+                }
             }
         })
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            // Covers exits that bypass quit_app (e.g. OS logout)
+            if let tauri::RunEvent::Exit = event {
+                shutdown::cleanup_temp_audio(app);
+            }
+        });
+}
+
+fn show_crash_report_dialog(app: &tauri::AppHandle, report: std::path::PathBuf) {
+    use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+
+    app.dialog()
+        .message(format!(
+            "FlowState closed unexpectedly last time. A crash report was saved to:\n{}",
+            report.display()
+        ))
+        .title("FlowState crashed")
+        .kind(MessageDialogKind::Error)
+        .buttons(MessageDialogButtons::OkCancelCustom("Open report".to_string(), "Dismiss".to_string()))
+        .show(move |open| {
+            if open {
+                if let Err(e) = flowstate_core::crash::open_report(&report) {
+                    tracing::warn!("{}", e);
+                }
+            }
+        });
 }
 
 // Global shortcut setup - DISABLED
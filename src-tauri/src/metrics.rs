@@ -0,0 +1,128 @@
+// Metrics module
+// Local usage metrics: how many voice notes were transcribed and how long that
+// took, and how long each parser took and how often it failed over to the next
+// one in the chain. Everything stays in the database; nothing is sent anywhere.
+// `get_usage_metrics` sums it up, e.g. to see whether parsing with Ollama is
+// worth the wait compared to the rules.
+
+use crate::database::{self, Database, MetricStats};
+use crate::parser::ParseAttempt;
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+const TRANSCRIPTION: &str = "transcription";
+const PARSE: &str = "parse";
+
+/// Transcriptions from one source ("recording", "import", "journal", "telegram")
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranscriptionMetrics {
+    pub source: String,
+    pub count: i64,
+    /// Average time spent transcribing one note
+    pub average_ms: i64,
+    /// Average length of the audio transcribed
+    pub average_audio_ms: i64,
+}
+
+/// Runs of one parser
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ParserMetrics {
+    pub parser: String,
+    /// Transcripts it parsed
+    pub parsed: i64,
+    pub average_ms: i64,
+    /// Times it failed and the next parser was tried (or, as the last one, the memo was lost)
+    pub fallbacks: i64,
+    /// Average time spent before failing, e.g. waiting for a server that's down
+    pub average_fallback_ms: i64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UsageMetrics {
+    /// The period covered in days, counting today (None for everything logged)
+    pub days: Option<u32>,
+    pub recordings: i64,
+    /// Average time spent transcribing one note, over all sources
+    pub average_transcription_ms: i64,
+    pub transcriptions: Vec<TranscriptionMetrics>,
+    pub parsers: Vec<ParserMetrics>,
+}
+
+/// Log how long transcribing `audio` took
+pub fn record_transcription(db: &Database, source: &str, audio: Duration, elapsed: Duration) {
+    if let Err(e) = database::log_metric(db, TRANSCRIPTION, source, millis(elapsed), Some(millis(audio)), true) {
+        tracing::warn!("Failed to log transcription time: {}", e);
+    }
+}
+
+/// Log the parsers a transcript went through (see `ParserChain::parse_timed`)
+pub fn record_parse(db: &Database, attempts: &[ParseAttempt]) {
+    for attempt in attempts {
+        let parser = attempt.parser.as_str();
+        if let Err(e) = database::log_metric(db, PARSE, parser, millis(attempt.elapsed), None, attempt.succeeded) {
+            tracing::warn!("Failed to log parse time: {}", e);
+        }
+    }
+}
+
+/// The metrics for the last `days` days (None for everything logged)
+pub fn usage_metrics(db: &Database, days: Option<u32>) -> Result<UsageMetrics, String> {
+    let start = days.map(|days| {
+        let first = Local::now().date_naive() - chrono::Duration::days(days.saturating_sub(1) as i64);
+        crate::report::utc_timestamp(first)
+    });
+    let stats = database::metric_stats_since(db, start.as_deref()).map_err(|e| e.to_string())?;
+    Ok(summarize(days, &stats))
+}
+
+fn summarize(days: Option<u32>, stats: &[MetricStats]) -> UsageMetrics {
+    let mut metrics = UsageMetrics { days, ..Default::default() };
+
+    let mut transcription_ms = 0;
+    for row in stats.iter().filter(|row| row.kind == TRANSCRIPTION) {
+        metrics.recordings += row.count;
+        transcription_ms += row.total_ms;
+        metrics.transcriptions.push(TranscriptionMetrics {
+            source: row.detail.clone(),
+            count: row.count,
+            average_ms: average(row.total_ms, row.count),
+            average_audio_ms: average(row.audio_ms, row.count),
+        });
+    }
+    metrics.average_transcription_ms = average(transcription_ms, metrics.recordings);
+    metrics.transcriptions.sort_by(|a, b| b.count.cmp(&a.count));
+
+    for row in stats.iter().filter(|row| row.kind == PARSE) {
+        let index = match metrics.parsers.iter().position(|parser| parser.parser == row.detail) {
+            Some(index) => index,
+            None => {
+                metrics.parsers.push(ParserMetrics {
+                    parser: row.detail.clone(),
+                    parsed: 0,
+                    average_ms: 0,
+                    fallbacks: 0,
+                    average_fallback_ms: 0,
+                });
+                metrics.parsers.len() - 1
+            }
+        };
+        let parser = &mut metrics.parsers[index];
+        if row.succeeded {
+            parser.parsed = row.count;
+            parser.average_ms = average(row.total_ms, row.count);
+        } else {
+            parser.fallbacks = row.count;
+            parser.average_fallback_ms = average(row.total_ms, row.count);
+        }
+    }
+    metrics
+}
+
+fn average(total: i64, count: i64) -> i64 {
+    if count == 0 { 0 } else { total / count }
+}
+
+fn millis(duration: Duration) -> i64 {
+    duration.as_millis() as i64
+}
@@ -0,0 +1,224 @@
+// Model store module
+// Single on-disk location for Whisper models, shared by the Tauri and egui builds
+// so a model downloaded in one UI is available in the other
+
+use crate::whisper::WhisperModelSize;
+use sha1::{Digest, Sha1};
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Error returned by `ModelStore::download` when it was cancelled
+pub const DOWNLOAD_CANCELLED: &str = "Download cancelled";
+
+pub struct ModelStore {
+    dir: PathBuf,
+}
+
+impl ModelStore {
+    /// Open the canonical store (`<data dir>/flowstate/whisper_models`), creating it if needed
+    pub fn open() -> Result<Self, String> {
+        let dir = crate::paths::data_dir().join("whisper_models");
+        fs::create_dir_all(&dir)
+            .map_err(|e| format!("Failed to create models directory: {}", e))?;
+        Ok(Self { dir })
+    }
+
+    /// Open the store, or fall back to a temp directory so callers always get a path
+    pub fn open_or_temp() -> Self {
+        Self::open().unwrap_or_else(|e| {
+            tracing::warn!("{} - using temp directory for models", e);
+            Self { dir: std::env::temp_dir().join("flowstate_models") }
+        })
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn model_path(&self, model: WhisperModelSize) -> PathBuf {
+        self.dir.join(model.filename())
+    }
+
+    pub fn is_installed(&self, model: WhisperModelSize) -> bool {
+        self.model_path(model).exists()
+    }
+
+    /// Every known model with whether it is installed, smallest first
+    pub fn list(&self) -> Vec<(WhisperModelSize, bool)> {
+        WhisperModelSize::ALL
+            .into_iter()
+            .map(|model| (model, self.is_installed(model)))
+            .collect()
+    }
+
+    pub fn delete(&self, model: WhisperModelSize) -> Result<(), String> {
+        let model_path = self.model_path(model);
+        if model_path.exists() {
+            fs::remove_file(&model_path)
+                .map_err(|e| format!("Failed to delete model: {}", e))?;
+        }
+        Ok(())
+    }
+
+    /// Delete downloads that never finished (the app was killed mid-download).
+    /// Only safe while nothing is downloading, i.e. at startup.
+    pub fn remove_partial_downloads(&self) -> usize {
+        let Ok(entries) = fs::read_dir(&self.dir) else {
+            return 0;
+        };
+        entries
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| path.to_string_lossy().ends_with(".bin.part"))
+            .filter(|path| fs::remove_file(path).is_ok())
+            .count()
+    }
+
+    /// Download a model into the store, reporting (downloaded, total) bytes as it goes.
+    /// Setting `cancel` stops between chunks with `DOWNLOAD_CANCELLED`.
+    pub async fn download(
+        &self,
+        model: WhisperModelSize,
+        on_progress: Option<Box<dyn Fn(u64, u64) + Send>>,
+        cancel: Option<&AtomicBool>,
+    ) -> Result<PathBuf, String> {
+        let model_path = self.model_path(model);
+
+        // If model already exists, return it
+        if model_path.exists() {
+            return Ok(model_path);
+        }
+
+        // Written under a temporary name so an interrupted download never looks installed
+        let part_path = model_path.with_extension("bin.part");
+        let result = self.download_to(model, &part_path, on_progress, cancel).await;
+        match result {
+            Ok(()) => {
+                fs::rename(&part_path, &model_path)
+                    .map_err(|e| format!("Failed to save model file: {}", e))?;
+                Ok(model_path)
+            }
+            Err(e) => {
+                let _ = fs::remove_file(&part_path);
+                Err(e)
+            }
+        }
+    }
+
+    async fn download_to(
+        &self,
+        model: WhisperModelSize,
+        path: &Path,
+        on_progress: Option<Box<dyn Fn(u64, u64) + Send>>,
+        cancel: Option<&AtomicBool>,
+    ) -> Result<(), String> {
+        let client = reqwest::Client::new();
+        let response = client
+            .get(model.url())
+            .send()
+            .await
+            .map_err(|e| format!("Failed to download model: {}", e))?;
+
+        if !response.status().is_success() {
+            return Err(format!("Failed to download model: HTTP {}", response.status()));
+        }
+
+        let total_size = response.content_length().unwrap_or(model.size_mb() * 1_000_000);
+        let mut file = fs::File::create(path)
+            .map_err(|e| format!("Failed to create model file: {}", e))?;
+
+        let mut stream = response.bytes_stream();
+        let mut downloaded: u64 = 0;
+
+        use futures_util::StreamExt;
+        use std::io::Write;
+
+        while let Some(item) = stream.next().await {
+            if cancel.is_some_and(|cancel| cancel.load(Ordering::Relaxed)) {
+                return Err(DOWNLOAD_CANCELLED.to_string());
+            }
+            let chunk = item.map_err(|e| format!("Download error: {}", e))?;
+            file.write_all(&chunk)
+                .map_err(|e| format!("Failed to write to file: {}", e))?;
+
+            downloaded += chunk.len() as u64;
+
+            if let Some(ref callback) = on_progress {
+                callback(downloaded, total_size);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Move models left in an older location into the store (existing files win)
+    pub fn migrate_from(&self, legacy_dir: &Path) {
+        if legacy_dir == self.dir || !legacy_dir.is_dir() {
+            return;
+        }
+
+        for model in WhisperModelSize::ALL {
+            let from = legacy_dir.join(model.filename());
+            if !from.exists() {
+                continue;
+            }
+            let to = self.model_path(model);
+            if to.exists() {
+                let _ = fs::remove_file(&from);
+                continue;
+            }
+            // Rename fails across drives - fall back to copy + delete
+            let moved = fs::rename(&from, &to).or_else(|_| {
+                fs::copy(&from, &to)
+                    .and_then(|_| fs::remove_file(&from))
+                    .inspect_err(|_| {
+                        // Never leave a partial copy that would look installed
+                        let _ = fs::remove_file(&to);
+                    })
+            });
+            match moved {
+                Ok(()) => tracing::info!("Moved {} to {}", model.filename(), self.dir.display()),
+                Err(e) => tracing::warn!("Failed to move {}: {}", from.display(), e),
+            }
+        }
+
+        // Drop the old directory once it's empty
+        let _ = fs::remove_dir(legacy_dir);
+    }
+}
+
+/// Whether the file at `path` is the published `model` (reads the whole file)
+pub fn checksum_matches(path: &Path, model: WhisperModelSize) -> Result<bool, String> {
+    let mut file = fs::File::open(path).map_err(|e| e.to_string())?;
+    let mut hasher = Sha1::new();
+    let mut buffer = vec![0u8; 1024 * 1024];
+    loop {
+        let read = file.read(&mut buffer).map_err(|e| e.to_string())?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    let digest: String = hasher.finalize().iter().map(|byte| format!("{:02x}", byte)).collect();
+    Ok(digest == model.sha1())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_damaged_model_is_told_apart_by_its_checksum() {
+        let path = std::env::temp_dir().join(format!("flowstate-model-{}.bin", std::process::id()));
+        std::fs::write(&path, "lmgg cut short").unwrap();
+        let matches = checksum_matches(&path, WhisperModelSize::Tiny);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(matches, Ok(false));
+
+        let error = crate::error::FlowStateError::ModelCorrupt { model: "Tiny".to_string() };
+        let json = serde_json::to_value(&error).unwrap();
+        assert_eq!(json["code"], "model_corrupt");
+        assert_eq!(json["model"], "Tiny");
+    }
+}
@@ -0,0 +1,159 @@
+// MQTT module
+// Publishes FlowState's state to an MQTT broker for Home Assistant and similar
+// dashboards. Every topic is under the configured prefix and retained, so a
+// client that subscribes later still gets the current value:
+// - <prefix>/status: "online", or "offline" (last will) when FlowState goes away
+// - <prefix>/recording: "on" / "off"
+// - <prefix>/timer: "running" / "paused" / "break"
+// - <prefix>/timer/remaining: seconds left in the interval or break
+// - <prefix>/tasks/open: number of open tasks
+// Only values that changed since the last publish are sent.
+
+use crate::settings::MqttSettings;
+use rumqttc::{Client, ConnectionError, Event, LastWill, MqttOptions, Outgoing, Packet, QoS};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+const KEEP_ALIVE: Duration = Duration::from_secs(30);
+// Wait before reconnecting after the broker drops or refuses the connection
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimerState {
+    Running,
+    Paused,
+    Break,
+}
+
+impl TimerState {
+    fn as_str(self) -> &'static str {
+        match self {
+            TimerState::Running => "running",
+            TimerState::Paused => "paused",
+            TimerState::Break => "break",
+        }
+    }
+}
+
+/// Everything that is published
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Status {
+    pub recording: bool,
+    pub timer: TimerState,
+    pub remaining_secs: u64,
+    pub open_tasks: usize,
+}
+
+/// Connected publisher; disconnects when dropped
+pub struct MqttPublisher {
+    client: Client,
+    prefix: String,
+    // Last published status; cleared on reconnect so everything is sent again
+    last: Arc<Mutex<Option<Status>>>,
+    // Ends the connection thread while it is retrying an unreachable broker
+    stop: Arc<AtomicBool>,
+}
+
+/// Managed state holding the active publisher (if it is enabled)
+#[derive(Default)]
+pub struct MqttState {
+    pub publisher: Mutex<Option<MqttPublisher>>,
+    /// Set by the frontend, which does the recording in the Tauri build
+    pub recording: AtomicBool,
+}
+
+impl MqttPublisher {
+    pub fn start(config: &MqttSettings) -> Result<Self, String> {
+        config.validate()?;
+        let prefix = config.topic_prefix.trim().trim_end_matches('/').to_string();
+
+        let client_id = format!("flowstate-{}", std::process::id());
+        let mut options = MqttOptions::new(client_id, config.host.trim(), config.port);
+        options.set_keep_alive(KEEP_ALIVE);
+        if !config.username.trim().is_empty() {
+            options.set_credentials(config.username.trim(), config.password.as_str());
+        }
+        options.set_last_will(LastWill::new(format!("{}/status", prefix), "offline", QoS::AtLeastOnce, true));
+
+        let (client, mut connection) = Client::new(options, 10);
+        let last = Arc::new(Mutex::new(None));
+
+        let status_client = client.clone();
+        let status_topic = format!("{}/status", prefix);
+        let reconnected = last.clone();
+        let stop = Arc::new(AtomicBool::new(false));
+        let stop_flag = stop.clone();
+        let host = format!("{}:{}", config.host.trim(), config.port);
+        // The connection is driven here until the publisher disconnects
+        std::thread::spawn(move || {
+            tracing::info!("MQTT publisher started ({})", host);
+            let mut failing = false;
+            for event in connection.iter() {
+                match event {
+                    Ok(Event::Incoming(Packet::ConnAck(_))) => {
+                        tracing::info!("Connected to MQTT broker {}", host);
+                        failing = false;
+                        let _ = status_client.try_publish(&status_topic, QoS::AtLeastOnce, true, "online");
+                        // The broker may have lost retained values while we were away
+                        *reconnected.lock().unwrap() = None;
+                    }
+                    Ok(Event::Outgoing(Outgoing::Disconnect)) | Err(ConnectionError::RequestsDone) => break,
+                    Ok(_) => {}
+                    Err(_) if stop_flag.load(Ordering::Relaxed) => break,
+                    Err(e) => {
+                        if !failing {
+                            tracing::warn!("MQTT connection to {} failed: {}", host, e);
+                        }
+                        failing = true;
+                        std::thread::sleep(RECONNECT_DELAY);
+                    }
+                }
+            }
+            tracing::info!("MQTT publisher stopped");
+        });
+
+        Ok(Self {
+            client,
+            prefix,
+            last,
+            stop,
+        })
+    }
+
+    /// Send whatever changed since the last call. Never blocks: if the broker
+    /// is unreachable the values are sent again after reconnecting.
+    pub fn publish(&self, status: &Status) {
+        let mut last = self.last.lock().unwrap();
+        let previous = last.as_ref();
+        let mut messages = Vec::new();
+        if previous.map(|p| p.recording) != Some(status.recording) {
+            messages.push(("recording", if status.recording { "on" } else { "off" }.to_string()));
+        }
+        if previous.map(|p| p.timer) != Some(status.timer) {
+            messages.push(("timer", status.timer.as_str().to_string()));
+        }
+        if previous.map(|p| p.remaining_secs) != Some(status.remaining_secs) {
+            messages.push(("timer/remaining", status.remaining_secs.to_string()));
+        }
+        if previous.map(|p| p.open_tasks) != Some(status.open_tasks) {
+            messages.push(("tasks/open", status.open_tasks.to_string()));
+        }
+        for (topic, payload) in messages {
+            if let Err(e) = self.client.try_publish(format!("{}/{}", self.prefix, topic), QoS::AtMostOnce, true, payload) {
+                tracing::debug!("MQTT publish skipped: {}", e);
+                return;
+            }
+        }
+        *last = Some(status.clone());
+    }
+}
+
+impl Drop for MqttPublisher {
+    fn drop(&mut self) {
+        // A clean disconnect doesn't trigger the last will, so say it ourselves
+        let _ = self.client.try_publish(format!("{}/status", self.prefix), QoS::AtLeastOnce, true, "offline");
+        let _ = self.client.try_disconnect();
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
@@ -4,50 +4,473 @@
 //! Native UI version using egui - 0% CPU when idle
 //! Run with: cargo run --bin flowstate-native --features native-ui --no-default-features
 
-mod database;
-mod ollama;
-mod whisper;
-
+use flowstate_core::{achievements, action_mode, api_tokens, ask, audio, benchmark, clipboard, config_file, content_filter, crash, database, dates, diagnostics, dnd, downloads, earcon, estimates, focus_monitor, github, habits, i18n, inbox, logging, macros, metrics, model_store, mqtt, navigation, ollama, parser, paths, power, presence, profiles, reminders, report, rollover, schedule, settings, speaker, speech, startup, sync, telegram, timer_control, updates, wakeword, whisper};
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
 use eframe::egui;
+use global_hotkey::{hotkey::HotKey, GlobalHotKeyEvent, GlobalHotKeyManager, HotKeyState};
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::sync::{Arc, Mutex, mpsc};
 use std::time::{Duration, Instant};
 use std::thread;
+use tray_icon::menu::{Menu, MenuEvent, MenuItem};
+use tray_icon::{MouseButton, MouseButtonState, TrayIcon, TrayIconBuilder, TrayIconEvent};
+
+// How long the timer-expiry prompt listens for an answer
+const JOURNAL_WINDOW: Duration = Duration::from_secs(15);
+// Escalation steps before an ignored alert is given up on
+const MAX_ESCALATIONS: u32 = 3;
+// Pomodoro: work interval, then a short break, repeated until stopped
+const POMODORO_MINUTES: u64 = 25;
+const POMODORO_BREAK_MINUTES: u64 = 5;
+// Added to the running countdown by the "+5" button
+const EXTEND_MINUTES: u64 = 5;
+// Task-list changes Ctrl+Z can step back through
+const MAX_UNDO: usize = 20;
+// Warnings fade after this; errors stay until clicked
+const TOAST_TIMEOUT: Duration = Duration::from_secs(5);
+// Messages kept for the history panel
+const MAX_TOAST_HISTORY: usize = 50;
+// How often the power source is looked at
+const POWER_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+// Toasts stacked on screen at once (older ones stay in the history)
+const MAX_VISIBLE_TOASTS: usize = 4;
+
+fn now_timestamp() -> String {
+    chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string()
+}
+
+/// "HH:MM" in local time for a stored UTC timestamp
+fn local_time(timestamp: &str) -> Option<String> {
+    let utc = chrono::NaiveDateTime::parse_from_str(timestamp, "%Y-%m-%d %H:%M:%S").ok()?.and_utc();
+    Some(utc.with_timezone(&chrono::Local).format("%H:%M").to_string())
+}
+
+/// "YYYY-MM-DD HH:MM" in local time for a stored UTC timestamp
+fn local_date_time(timestamp: &str) -> Option<String> {
+    let utc = chrono::NaiveDateTime::parse_from_str(timestamp, "%Y-%m-%d %H:%M:%S").ok()?.and_utc();
+    Some(utc.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M").to_string())
+}
+
+/// How long ago a UTC timestamp was, e.g. "2d ago"
+fn age_label(timestamp: &str) -> Option<String> {
+    let created = chrono::NaiveDateTime::parse_from_str(timestamp, "%Y-%m-%d %H:%M:%S").ok()?.and_utc();
+    let age = chrono::Utc::now().signed_duration_since(created);
+    let (key, count) = match age.num_minutes() {
+        ..=0 => return Some(i18n::t("age.now")),
+        minutes @ 1..=59 => ("age.minutes", minutes),
+        _ if age.num_hours() < 24 => ("age.hours", age.num_hours()),
+        _ if age.num_days() < 14 => ("age.days", age.num_days()),
+        _ => ("age.weeks", age.num_weeks()),
+    };
+    Some(i18n::tf(key, &[("count", &count)]))
+}
+
+/// Whisper models as (lowercase name, installed), smallest first
+fn installed_models() -> Vec<(String, bool)> {
+    model_store::ModelStore::open_or_temp()
+        .list()
+        .into_iter()
+        .map(|(model, installed)| (model.name().to_lowercase(), installed))
+        .collect()
+}
+
+/// Parse a Tauri-style accelerator ("CommandOrControl+Alt+N") into an egui shortcut
+fn parse_shortcut(accelerator: &str) -> Option<egui::KeyboardShortcut> {
+    let mut modifiers = egui::Modifiers::NONE;
+    let mut key = None;
+    for part in accelerator.split('+').map(str::trim) {
+        match part.to_lowercase().as_str() {
+            "commandorcontrol" | "cmdorctrl" | "cmdorcontrol" | "commandorctrl" => modifiers = modifiers | egui::Modifiers::COMMAND,
+            "control" | "ctrl" => modifiers = modifiers | egui::Modifiers::CTRL,
+            "alt" | "option" => modifiers = modifiers | egui::Modifiers::ALT,
+            "shift" => modifiers = modifiers | egui::Modifiers::SHIFT,
+            "super" | "command" | "cmd" | "meta" => modifiers = modifiers | egui::Modifiers::MAC_CMD,
+            _ => key = Some(egui::Key::from_name(part)?),
+        }
+    }
+    Some(egui::KeyboardShortcut::new(modifiers, key?))
+}
+
+/// Bring the window back from the tray or the taskbar
+fn show_window(ctx: &egui::Context) {
+    ctx.send_viewport_cmd(egui::ViewportCommand::Minimized(false));
+    ctx.send_viewport_cmd(egui::ViewportCommand::Visible(true));
+    ctx.send_viewport_cmd(egui::ViewportCommand::Focus);
+}
+
+// Tray menu entries (a left click on the icon is Show)
+enum TrayAction {
+    Show,
+    Record,
+    Quit,
+}
+
+/// Tray icon with Show / Record / Quit, drawn with the window's record icon.
+/// Clicks arrive while the window is hidden, so the handlers wake the UI.
+fn create_tray(ctx: &egui::Context) -> Result<(TrayIcon, mpsc::Receiver<TrayAction>), String> {
+    let show = MenuItem::new(i18n::t("tray.show"), true, None);
+    let record = MenuItem::new(i18n::t("tray.record"), true, None);
+    let quit = MenuItem::new(i18n::t("tray.quit"), true, None);
+    let menu = Menu::with_items(&[&show, &record, &quit]).map_err(|e| e.to_string())?;
+
+    let icon = create_record_icon();
+    let icon = tray_icon::Icon::from_rgba(icon.rgba, icon.width, icon.height).map_err(|e| e.to_string())?;
+    let tray = TrayIconBuilder::new()
+        .with_tooltip("FlowState")
+        .with_icon(icon)
+        .with_menu(Box::new(menu))
+        .with_menu_on_left_click(false)
+        .build()
+        .map_err(|e| e.to_string())?;
+
+    let (tx, rx) = mpsc::channel();
+    let (show_id, record_id, quit_id) = (show.id().clone(), record.id().clone(), quit.id().clone());
+    let menu_tx = tx.clone();
+    let menu_ctx = ctx.clone();
+    MenuEvent::set_event_handler(Some(move |event: MenuEvent| {
+        let action = if event.id == show_id {
+            TrayAction::Show
+        } else if event.id == record_id {
+            TrayAction::Record
+        } else if event.id == quit_id {
+            TrayAction::Quit
+        } else {
+            return;
+        };
+        if !matches!(action, TrayAction::Quit) {
+            show_window(&menu_ctx);
+        }
+        let _ = menu_tx.send(action);
+        menu_ctx.request_repaint();
+    }));
+    let icon_ctx = ctx.clone();
+    TrayIconEvent::set_event_handler(Some(move |event: TrayIconEvent| {
+        if let TrayIconEvent::Click { button: MouseButton::Left, button_state: MouseButtonState::Up, .. } = event {
+            show_window(&icon_ctx);
+            let _ = tx.send(TrayAction::Show);
+            icon_ctx.request_repaint();
+        }
+    }));
+    Ok((tray, rx))
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Severity {
+    /// Confirmation of something done in the background (clipboard capture)
+    Info,
+    /// Something didn't happen but nothing is broken (silent recording, no tasks found)
+    Warning,
+    Error,
+}
+
+impl Severity {
+    fn color(self) -> egui::Color32 {
+        match self {
+            Severity::Info => egui::Color32::from_rgb(74, 222, 128),
+            Severity::Warning => egui::Color32::from_rgb(251, 191, 36),
+            Severity::Error => egui::Color32::from_rgb(248, 113, 113),
+        }
+    }
+}
+
+/// Comma lists of a profile being typed in settings, applied on blur
+#[derive(Default)]
+struct ProfileDraft {
+    projects: String,
+    tags: String,
+    language: String,
+}
+
+impl ProfileDraft {
+    fn from(profile: &settings::Profile) -> Self {
+        Self {
+            projects: profile.projects.join(", "),
+            tags: profile.tags.join(", "),
+            language: profile.language.clone().unwrap_or_default(),
+        }
+    }
+}
+
+fn profile_drafts(profiles: &settings::ProfileSettings) -> Vec<ProfileDraft> {
+    profiles.profiles.iter().map(ProfileDraft::from).collect()
+}
+
+fn split_list(text: &str) -> Vec<String> {
+    text.split(',').map(str::trim).filter(|word| !word.is_empty()).map(str::to_string).collect()
+}
+
+#[derive(Clone)]
+struct Toast {
+    severity: Severity,
+    message: String,
+    shown_at: Instant,
+    /// Local time for the history panel
+    time: String,
+}
+
+/// Task-list change that Ctrl+Z reverts
+enum UndoAction {
+    Added(Vec<i64>),
+    Deleted(Vec<database::Task>),
+    Toggled(Vec<i64>),
+    /// Task ids and their text before the edit
+    Edited(Vec<(i64, String)>),
+}
 
 // Result from background processing
 enum ProcessingResult {
     Transcript(String),
+    /// Transcript to show for correction before it is parsed into tasks
+    Review(String),
     Tasks(Vec<database::Task>),
+    /// "github: ..." command: the task text and the created issue's URL
+    Issue(String, Result<String, String>),
+    /// Answer to the timer-expiry prompt: (text, interval start, interval end)
+    Journal(String, String, String),
+    /// A user-defined macro's phrase
+    Macro(macros::VoiceMacro),
+    /// "log habit ..." command: the spoken habit name
+    Habit(String),
+    /// "switch to ... mode" command: the spoken profile name
+    Profile(String),
+    /// "remind me to ..." command: the task text and the local time to remind at
+    Reminder(String, chrono::NaiveDateTime),
+    /// "pause the timer", "set the timer to 25 minutes", or a timer action from Ollama
+    Timer(timer_control::TimerCommand),
+    /// "open settings", "show completed", "switch to work project"
+    App(navigation::AppCommand),
+    /// "what's my workload"
+    Workload,
+    /// Action mode: an add, complete or delete after "command:", or dictation to add
+    TaskCommand(action_mode::TaskCommand),
     Error(String),
     Done,
 }
 
-// Download state shared between UI and download thread
-#[derive(Clone)]
-struct DownloadState {
-    is_downloading: Arc<Mutex<bool>>,
-    current_model: Arc<Mutex<Option<String>>>,
-    progress: Arc<Mutex<f32>>,        // 0.0 to 1.0
-    downloaded_mb: Arc<Mutex<f32>>,
-    total_mb: Arc<Mutex<f32>>,
-    error: Arc<Mutex<Option<String>>>,
-    completed: Arc<Mutex<bool>>,
+/// Scale every text style so the body text is `size` points
+fn apply_font_size(ctx: &egui::Context, size: f32) {
+    let defaults = egui::Style::default().text_styles;
+    let factor = size / settings::DEFAULT_FONT_SIZE;
+    ctx.all_styles_mut(|style| {
+        for (text_style, font) in style.text_styles.iter_mut() {
+            if let Some(default) = defaults.get(text_style) {
+                font.size = default.size * factor;
+            }
+        }
+    });
 }
 
-impl Default for DownloadState {
-    fn default() -> Self {
-        Self {
-            is_downloading: Arc::new(Mutex::new(false)),
-            current_model: Arc::new(Mutex::new(None)),
-            progress: Arc::new(Mutex::new(0.0)),
-            downloaded_mb: Arc::new(Mutex::new(0.0)),
-            total_mb: Arc::new(Mutex::new(0.0)),
-            error: Arc::new(Mutex::new(None)),
-            completed: Arc::new(Mutex::new(false)),
+/// Saved "#rrggbb" accent, or the default blue if it doesn't parse
+fn accent_color(value: &str) -> egui::Color32 {
+    let [r, g, b] = settings::parse_hex_color(value)
+        .or_else(|| settings::parse_hex_color(settings::DEFAULT_ACCENT_COLOR))
+        .unwrap_or([74, 158, 255]);
+    egui::Color32::from_rgb(r, g, b)
+}
+
+// Schedules offered when adding a habit
+const HABIT_SCHEDULES: [&str; 3] = [habits::DAILY, "mon,tue,wed,thu,fri", "sat,sun"];
+
+/// "3 tasks, archive completed" for the macro list in settings
+fn macro_summary(voice_macro: &macros::VoiceMacro) -> String {
+    let tasks = voice_macro
+        .actions
+        .iter()
+        .filter(|action| matches!(action, macros::MacroAction::AddTask { .. }))
+        .count();
+    let mut parts = Vec::new();
+    if tasks > 0 {
+        parts.push(i18n::tf("macros.tasks", &[("count", &tasks)]));
+    }
+    for action in &voice_macro.actions {
+        match action {
+            macros::MacroAction::AddTask { .. } => {}
+            macros::MacroAction::ArchiveCompleted => parts.push(i18n::t("macros.archive")),
+            macros::MacroAction::Timer { command } => parts.push(match command {
+                timer_control::TimerCommand::Reset => i18n::t("macros.timer_reset"),
+                timer_control::TimerCommand::Pause => i18n::t("macros.timer_pause"),
+                timer_control::TimerCommand::Resume => i18n::t("macros.timer_resume"),
+                timer_control::TimerCommand::SetMinutes(minutes) => i18n::tf("macros.timer_minutes", &[("minutes", minutes)]),
+            }),
+        }
+    }
+    parts.join(", ")
+}
+
+fn habit_schedule_label(schedule: &str) -> String {
+    match schedule {
+        habits::DAILY => i18n::t("habits.daily"),
+        "mon,tue,wed,thu,fri" => i18n::t("habits.weekdays"),
+        "sat,sun" => i18n::t("habits.weekends"),
+        other => other.to_string(),
+    }
+}
+
+/// Working-hours checkbox with the weekday and time fields under it
+fn schedule_editor(ui: &mut egui::Ui, schedule: &mut schedule::TimerSchedule) {
+    ui.checkbox(&mut schedule.enabled, i18n::t("settings.working_hours"));
+    if schedule.enabled {
+        ui.horizontal(|ui| {
+            for (idx, key) in ["day.mo", "day.tu", "day.we", "day.th", "day.fr", "day.sa", "day.su"].iter().enumerate() {
+                let day = idx as u8;
+                let mut active = schedule.days.contains(&day);
+                if ui.checkbox(&mut active, i18n::t(key)).changed() {
+                    if active {
+                        schedule.days.push(day);
+                        schedule.days.sort_unstable();
+                    } else {
+                        schedule.days.retain(|d| *d != day);
+                    }
+                }
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label(i18n::t("settings.hours_from"));
+            ui.add(egui::TextEdit::singleline(&mut schedule.start).desired_width(50.0));
+            ui.label(i18n::t("settings.hours_to"));
+            ui.add(egui::TextEdit::singleline(&mut schedule.end).desired_width(50.0));
+        });
+    }
+}
+
+fn theme_label(theme: settings::Theme) -> String {
+    match theme {
+        settings::Theme::Dark => i18n::t("theme.dark"),
+        settings::Theme::Light => i18n::t("theme.light"),
+        settings::Theme::System => i18n::t("theme.system"),
+    }
+}
+
+fn power_saving_label(mode: settings::PowerSaving) -> String {
+    match mode {
+        settings::PowerSaving::Auto => i18n::t("power.auto"),
+        settings::PowerSaving::Always => i18n::t("power.always"),
+        settings::PowerSaving::Never => i18n::t("power.never"),
+    }
+}
+
+/// Turn a transcript into task changes and report them on `tx`. A macro phrase
+/// saved in `db` runs the macro, a "log habit ..." transcript ticks off a habit, "switch to ... mode" switches
+/// profiles, "remind me to ... at ..." adds a task with a reminder, and with
+/// `github` set, a "github: ..." transcript files an issue. Anything else goes
+/// through `parsers`, whose timings go into `db`'s usage metrics. In
+/// `action_mode`, only what follows "command:" is checked for commands (and
+/// must be one); any other transcript is added as a single task.
+fn parse_transcript_into(
+    tx: &mpsc::Sender<ProcessingResult>,
+    transcript: &str,
+    parsers: &parser::ParserChain,
+    github: Option<&settings::GithubSettings>,
+    db: Option<&database::Database>,
+    action_mode: bool,
+) {
+    let transcript = match action_mode.then(|| action_mode::classify(transcript)) {
+        Some(action_mode::Utterance::Dictation(text)) => {
+            let _ = tx.send(ProcessingResult::TaskCommand(action_mode::TaskCommand::Add(text.to_string())));
+            return;
+        }
+        Some(action_mode::Utterance::Command(command)) => command,
+        None => transcript,
+    };
+    if let Some(voice_macro) = db.and_then(|db| macros::find_macro(db, transcript)) {
+        let _ = tx.send(ProcessingResult::Macro(voice_macro));
+        return;
+    }
+    if let Some(name) = habits::habit_command(transcript) {
+        let _ = tx.send(ProcessingResult::Habit(name));
+        return;
+    }
+    if let Some(name) = profiles::profile_command(transcript) {
+        let _ = tx.send(ProcessingResult::Profile(name));
+        return;
+    }
+    if let Some((text, at)) = reminders::reminder_command(transcript) {
+        let _ = tx.send(ProcessingResult::Reminder(text, at));
+        return;
+    }
+    if let Some(command) = timer_control::timer_command(transcript) {
+        let _ = tx.send(ProcessingResult::Timer(command));
+        return;
+    }
+    if let Some(command) = navigation::app_command(transcript) {
+        let _ = tx.send(ProcessingResult::App(command));
+        return;
+    }
+    if estimates::workload_command(transcript) {
+        let _ = tx.send(ProcessingResult::Workload);
+        return;
+    }
+    let rt = tokio::runtime::Runtime::new().unwrap();
+    if let Some((config, title)) = github.and_then(|config| Some((config, github::issue_title(transcript)?))) {
+        let issue = rt.block_on(github::create_issue(config, &title));
+        let _ = tx.send(ProcessingResult::Issue(title, issue));
+        return;
+    }
+    if action_mode {
+        let _ = tx.send(match action_mode::task_command(transcript) {
+            Some(command) => ProcessingResult::TaskCommand(command),
+            None => ProcessingResult::Error(i18n::tf("error.unknown_command", &[("command", &transcript)])),
+        });
+        return;
+    }
+    let (parsed, attempts) = rt.block_on(parsers.parse_timed(transcript));
+    if let Some(db) = db {
+        metrics::record_parse(db, &attempts);
+    }
+    match parsed {
+        Ok(parsed) => {
+            tracing::debug!("Parsed {} tasks with the {} parser", parsed.tasks.len(), parsed.parser.as_str());
+            if let Some(command) = parsed.timer {
+                let _ = tx.send(ProcessingResult::Timer(command));
+            }
+            let _ = tx.send(ProcessingResult::Tasks(parsed.tasks));
+        }
+        Err(e) => {
+            let _ = tx.send(ProcessingResult::Error(i18n::tf("error.parse", &[("error", &e)])));
         }
     }
 }
 
+/// Count a transcribed voice note for the weekly report
+fn record_transcription(db: &database::Database, source: &str, transcript: &str) {
+    if transcript.trim().is_empty() {
+        return;
+    }
+    if let Err(e) = database::log_transcription(db, source, transcript, None) {
+        tracing::warn!("Failed to log transcription: {}", e);
+    }
+}
+
+/// Transcribe 16kHz samples, along with how long it took
+fn timed_transcription(samples: &[f32], model: &str, language: Option<&str>) -> Result<(String, Duration), String> {
+    let started = Instant::now();
+    whisper::transcribe_audio(samples, model, language).map(|transcript| (transcript, started.elapsed()))
+}
+
+fn audio_length(samples: &[f32]) -> Duration {
+    Duration::from_secs_f64(samples.len() as f64 / 16000.0)
+}
+
+/// Move the visualizer level toward the RMS of the samples just captured
+fn update_level(audio_level: &Mutex<f32>, samples: &[f32]) {
+    if samples.is_empty() {
+        return;
+    }
+    let sum_squares: f32 = samples.iter().map(|s| s * s).sum();
+    let rms = (sum_squares / samples.len() as f32).sqrt();
+    let level = (rms * 4.0).min(1.0);
+    if let Ok(mut lvl) = audio_level.lock() {
+        *lvl = if level > *lvl { level } else { *lvl * 0.9 + level * 0.1 };
+    }
+}
+
+// Result from the update checker thread
+enum UpdateResult {
+    Checked(Result<Option<updates::UpdateInfo>, String>),
+    /// Installer downloaded and started (or failed to)
+    Installed(Result<(), String>),
+}
+
 // App state
 struct FlowStateApp {
     // Database
@@ -55,14 +478,141 @@ struct FlowStateApp {
 
     // Tasks
     tasks: Vec<database::Task>,
+    // Text of the "new task" field
+    new_task_text: String,
+    // Task being edited inline: (id, text being typed)
+    editing_task: Option<(i64, String)>,
+    // Open task being dragged to a new place
+    dragged_task: Option<i64>,
+    // "Done today" section under the open tasks (only without a filter chip)
+    done_today: Vec<database::Task>,
+    // GitHub issues filed for tasks, by task id
+    issue_urls: HashMap<i64, String>,
+    // Ids of the pinned tasks
+    pinned: HashSet<i64>,
+    // Pending reminders as short local labels ("18:30"), by task id
+    reminders: HashMap<i64, String>,
+    // Reminder time being typed for a task: (id, text)
+    reminder_edit: Option<(i64, String)>,
+    // Due date being typed for an inbox task: (id, text)
+    schedule_edit: Option<(i64, String)>,
+    // Voice tasks waiting in the inbox, for its chip
+    inbox_count: usize,
+    last_reminder_check: Instant,
+    // Targets of Delete and the bulk actions, picked by clicking tasks
+    selected_tasks: BTreeSet<i64>,
+    // Last plainly clicked or Ctrl-clicked task, where a Shift-click range starts
+    selection_anchor: Option<i64>,
+    // Project and tag typed into the bulk action bar
+    bulk_project: String,
+    bulk_tag: String,
+    undo_stack: Vec<UndoAction>,
+    show_shortcuts: bool,
+    // Search box and Open/Done chip above the list (Recent shows the normal list)
+    task_search: String,
+    task_filter: database::TaskStatus,
+    // Context chip (@phone, @errand) narrowing the list, and the open tasks per context
+    task_context: Option<String>,
+    // +project picked by voice ("switch to work project"), shown as a chip until cleared
+    task_project: Option<String>,
+    contexts: Vec<(String, i64)>,
+    // Today's estimated work, refreshed with the list
+    today_workload: Option<estimates::Workload>,
+    // Question box under the search: the question, the answer in flight, the last answer
+    ask_text: String,
+    ask_rx: Option<mpsc::Receiver<Result<ask::TaskAnswer, String>>>,
+    ask_answer: Option<ask::TaskAnswer>,
+    // Work/Personal profiles, with the lists being typed in settings
+    profiles: settings::ProfileSettings,
+    profile_drafts: Vec<ProfileDraft>,
+    new_profile_name: String,
+    // Habit dots above the list, and the add form in settings
+    habits: Vec<habits::HabitStatus>,
+    new_habit_name: String,
+    new_habit_schedule: String,
+    // Voice macros, and the add form in settings (tasks one per line)
+    macros: Vec<macros::VoiceMacro>,
+    new_macro_phrase: String,
+    new_macro_tasks: String,
+    new_macro_archive: bool,
+    // API tokens for network clients, the issue form in settings, and the
+    // secret of the token just issued (shown until Settings closes)
+    api_tokens: Vec<api_tokens::ApiToken>,
+    new_token_name: String,
+    new_token_scopes: Vec<api_tokens::Scope>,
+    issued_secret: Option<String>,
 
     // Timer
     timer_start: Instant,
     timer_duration: Duration,
+    // Set while the timer is paused because the user is away (locked/idle) or paused it
+    timer_paused_at: Option<Instant>,
+    // Paused with the pause button; only the button resumes it
+    paused_by_user: bool,
+    // Pomodoro cycle running instead of the awareness interval
+    pomodoro: bool,
+    last_presence_check: Instant,
+    // Power saving override, whether saving is in effect, and when that was last checked
+    power_saving_mode: settings::PowerSaving,
+    power_saving: bool,
+    last_power_check: Instant,
+    // The Whisper model was loaded ahead of the first recording (deferred while saving power)
+    model_preloaded: bool,
+    // Unload the model after this many minutes without a recording (0 keeps it)
+    model_unload_minutes: u64,
+    // Outcome of the last model benchmark, and the run in progress
+    benchmark: Option<Result<Vec<benchmark::BenchmarkResult>, String>>,
+    benchmark_rx: Option<mpsc::Receiver<Result<Vec<benchmark::BenchmarkResult>, String>>>,
+    // Task the countdown is attached to: (task id, text, minutes, started_at)
+    focus_task: Option<(i64, String, u64, String)>,
+    // Foreground-app sampler for the focus session, when the focus monitor is on
+    focus_monitor: Option<focus_monitor::FocusMonitor>,
+    focus_monitor_enabled: bool,
+    // Wall-clock start of the current interval, for linking journal entries
+    interval_started_at: String,
+    journal_prompt_enabled: bool,
+    speak_tasks_on_alert: bool,
+    voice_inbox: bool,
+    action_mode: bool,
+    content_filter: settings::ContentFilterSettings,
+    // The filtered words being edited, comma-separated
+    content_filter_text: String,
+    // Set while recording the answer to "what did you just do?": (start, end)
+    journal_interval: Option<(String, String)>,
+    // Session row of the last expired interval until the user responds to it
+    pending_session: Option<i64>,
+    // Unacknowledged alert: when the next escalation step is due and how many have fired
+    pending_alert: Option<(Instant, u32)>,
+    escalation: settings::EscalationSettings,
+    // Hard-break mode: full-screen viewport until this instant
+    break_settings: settings::BreakSettings,
+    dnd: settings::DndSettings,
+    // Comma-separated editor for dnd.fullscreen_apps
+    dnd_apps_text: String,
+    break_ends_at: Option<Instant>,
+    focus_history: Vec<database::FocusDay>,
+    auto_pause_enabled: bool,
+    idle_threshold_minutes: u64,
+    // Working hours; outside them the timer pauses and restarts fresh afterwards
+    schedule: schedule::TimerSchedule,
+    restart_on_resume: bool,
 
     // Recording state
     is_recording: bool,
     is_processing: bool,
+    // Quit was chosen (or close with close_behavior = Quit); exit once processing finishes
+    quit_requested: bool,
+    close_behavior: settings::CloseBehavior,
+    // Kept alive for the icon to stay; None if the tray couldn't be created (close then minimizes)
+    tray: Option<TrayIcon>,
+    tray_rx: Option<mpsc::Receiver<TrayAction>>,
+    theme: settings::Theme,
+    accent: egui::Color32,
+    // Zoom (also changed by egui's Ctrl+plus/minus) and body text size
+    ui_scale: f32,
+    font_size: f32,
+    // None follows the system language
+    ui_language: Option<i18n::Locale>,
     recording_start: Option<Instant>,
     audio_buffer: Arc<Mutex<Vec<f32>>>,
     audio_stream: Option<cpal::Stream>,
@@ -72,18 +622,39 @@ struct FlowStateApp {
     // Settings
     show_settings: bool,
     always_on_top: bool,
+    // Last seen window placement, saved on close
+    window_geometry: Option<settings::WindowGeometry>,
+    // Compact strip (timer, record button, top task) and where it was placed
+    compact: bool,
+    compact_geometry: Option<settings::WindowGeometry>,
+    compact_shortcut: Option<egui::KeyboardShortcut>,
     timer_duration_mins: u32,
     selected_model: String,
+    // Per-recording language override (None = the profile's or the setting)
+    recording_language: Option<String>,
+    // Language to transcribe in when nothing overrides it ("auto" detects)
+    transcription_language: String,
     available_models: Vec<(String, bool)>, // (name, installed)
     ollama_enabled: bool,
+    ollama: settings::OllamaSettings,
+    // Models the server reported on the last test, for the model picker
+    ollama_models: Vec<String>,
+    // Outcome of the last test: (reachable with the model, message)
+    ollama_status: Option<(bool, String)>,
+    punctuation: settings::PunctuationSettings,
+    ollama_rx: Option<mpsc::Receiver<Result<Vec<String>, String>>>,
+    earcons_muted: bool,
+    alert_mode: settings::AlertMode,
+    alert_sound: earcon::AlertSound,
 
     // Audio devices
     audio_devices: Vec<String>,
     selected_device_idx: usize,
 
-    // Error state
-    error_message: Option<String>,
-    error_time: Option<Instant>,
+    // Messages stacked in the corner, and everything shown this run
+    toasts: Vec<Toast>,
+    toast_history: Vec<Toast>,
+    show_toast_history: bool,
 
     // Processing message
     status_message: Option<String>,
@@ -91,16 +662,116 @@ struct FlowStateApp {
     // Background processing channel
     processing_rx: Option<mpsc::Receiver<ProcessingResult>>,
 
-    // Model download state
-    download_state: DownloadState,
+    // Hands-free wake-word mode
+    wake_word_enabled: bool,
+    wake_word: Option<wakeword::WakeWordListener>,
+    wake_word_rx: Option<mpsc::Receiver<wakeword::WakeWordEvent>>,
+    // Markdown note sync: its settings, the path being typed, and the running
+    // sync with the settings it was started with
+    markdown_sync: settings::MarkdownSyncSettings,
+    markdown_path_text: String,
+    markdown_running: Option<(settings::MarkdownSyncSettings, sync::markdown::MarkdownSync)>,
+    markdown_rx: Option<mpsc::Receiver<sync::markdown::Imported>>,
+    // Calendar feed: its settings, the path being typed, and the running
+    // export with the settings it was started with
+    calendar_export: settings::CalendarExportSettings,
+    calendar_path_text: String,
+    calendar_running: Option<(settings::CalendarExportSettings, sync::calendar::CalendarExport)>,
+    // Google Calendar push: settings as edited, the running push with the
+    // settings it was started with, its failures, and a sign-in in progress
+    google_calendar: settings::GoogleCalendarSettings,
+    google_running: Option<(settings::GoogleCalendarSettings, sync::google_calendar::GoogleCalendarSync)>,
+    google_rx: Option<mpsc::Receiver<String>>,
+    google_connect_rx: Option<mpsc::Receiver<Result<String, String>>>,
+    // Todoist sync: settings as edited, the token being typed, and the running
+    // sync with the settings it was started with
+    todoist: settings::TodoistSettings,
+    todoist_token_text: String,
+    todoist_running: Option<(settings::TodoistSettings, sync::todoist::TodoistSync)>,
+    todoist_rx: Option<mpsc::Receiver<sync::todoist::SyncEvent>>,
+    github: settings::GithubSettings,
+    github_token_text: String,
+    mqtt: settings::MqttSettings,
+    mqtt_password_text: String,
+    mqtt_running: Option<(settings::MqttSettings, mqtt::MqttPublisher)>,
+    mqtt_published_at: Instant,
+    clipboard: settings::ClipboardSettings,
+    clipboard_trigger_text: String,
+    report: settings::ReportSettings,
+    report_folder_text: String,
+    rollover: settings::RolloverSettings,
+    workload: settings::WorkloadSettings,
+    clipboard_running: Option<(settings::ClipboardSettings, clipboard::ClipboardWatcher)>,
+    clipboard_rx: Option<mpsc::Receiver<database::Task>>,
+    telegram: settings::TelegramSettings,
+    telegram_token_text: String,
+    telegram_running: Option<(settings::TelegramSettings, telegram::TelegramBridge)>,
+    telegram_rx: Option<mpsc::Receiver<telegram::Incoming>>,
+    // Results for Telegram messages; handled like a recording's, without blocking the record button
+    remote_tx: mpsc::Sender<ProcessingResult>,
+    remote_rx: mpsc::Receiver<ProcessingResult>,
+    hands_free_recording: bool,
+    // Transcript waiting for "Apply" / "Discard", editable
+    pending_transcript: Option<String>,
+
+    // Model download queue (shared with the Tauri build's download path); status
+    // changes arrive on the channel and are kept here for drawing
+    download_manager: downloads::DownloadManager,
+    download_rx: mpsc::Receiver<downloads::DownloadEvent>,
+    download_statuses: Vec<downloads::DownloadEvent>,
+
+    // Quick-add box: Some(text being typed) while open
+    quick_add: Option<String>,
+    quick_add_shortcut: Option<egui::KeyboardShortcut>,
+
+    // System-wide record hotkey; works while minimized, press again to stop
+    hotkey_manager: Option<GlobalHotKeyManager>,
+    record_hotkey: Option<HotKey>,
+    record_shortcut: String,
+    hotkey_rx: mpsc::Receiver<GlobalHotKeyEvent>,
+    hotkey_recording: bool,
+
+    // Crash report from the previous run, until dismissed
+    crash_report: Option<std::path::PathBuf>,
+
+    // Update checker
+    auto_check_updates: bool,
+    available_update: Option<updates::UpdateInfo>,
+    update_status: Option<String>,
+    update_rx: Option<mpsc::Receiver<UpdateResult>>,
+
+    // Health checks from Settings
+    diagnostics: Option<diagnostics::DiagnosticsReport>,
+    diagnostics_rx: Option<mpsc::Receiver<diagnostics::DiagnosticsReport>>,
+    // flowstate.toml: the watcher and the edits it passes on
+    _config_watcher: Option<config_file::ConfigWatcher>,
+    config_rx: mpsc::Receiver<config_file::ConfigFile>,
 }
 
-impl Default for FlowStateApp {
-    fn default() -> Self {
-        let db = database::Database::new().expect("Failed to open database");
+impl FlowStateApp {
+    fn new(db: database::Database, database_check: diagnostics::DiagnosticCheck, ctx: egui::Context) -> Self {
         let tasks = database::get_all_tasks(&db).unwrap_or_default();
-        let timer_duration_mins = 15;
-        let ollama_enabled = database::get_ollama_enabled(&db).unwrap_or(false);
+        // flowstate.toml next to the database sets defaults; keep applying it when it's edited
+        if let Err(e) = config_file::apply(&db) {
+            tracing::warn!("{}", e);
+        }
+        let (config_tx, config_rx) = mpsc::channel();
+        let repaint_ctx = ctx.clone();
+        let config_watcher = config_file::path(&db).and_then(|path| {
+            let on_change = Box::new(move |file| {
+                let _ = config_tx.send(file);
+                repaint_ctx.request_repaint();
+            });
+            config_file::ConfigWatcher::start(path, on_change)
+                .map_err(|e| tracing::warn!("Failed to watch the config file: {}", e))
+                .ok()
+        });
+        let settings = settings::load(&db);
+        i18n::set_locale(settings.ui_language);
+        ctx.set_zoom_factor(settings.ui_scale);
+        apply_font_size(&ctx, settings.font_size);
+        let timer_duration_mins = settings.timer_duration_minutes as u32;
+        earcon::set_muted(settings.earcons_muted);
 
         // Get audio devices
         let host = cpal::default_host();
@@ -112,236 +783,1714 @@ impl Default for FlowStateApp {
                     .collect()
             })
             .unwrap_or_default();
+        // 0 is the system default; a saved device that is no longer plugged in falls back to it
+        let selected_device_idx = settings
+            .input_device
+            .as_ref()
+            .and_then(|name| audio_devices.iter().position(|d| d == name))
+            .map_or(0, |idx| idx + 1);
+
+        // Check whisper models
+        let available_models = installed_models();
+
+        // Keep the saved model if it's still installed, otherwise pick the first available (prefer smaller ones)
+        let selected_model = if available_models.iter().any(|(name, installed)| *installed && *name == settings.selected_model) {
+            settings.selected_model.clone()
+        } else {
+            available_models
+                .iter()
+                .find(|(_, installed)| *installed)
+                .map(|(name, _)| name.clone())
+                .unwrap_or_else(|| "tiny".to_string())
+        };
 
-        // Check whisper models (use same path as whisper module)
-        let models_dir = dirs::data_dir()
-            .unwrap_or_default()
-            .join("flowstate")
-            .join("whisper_models");
-        let available_models: Vec<(String, bool)> = vec![
-            ("tiny", 75),
-            ("base", 142),
-            ("small", 466),
-            ("medium", 1500),
-        ]
-        .into_iter()
-        .map(|(name, _size)| {
-            let model_path = models_dir.join(format!("ggml-{}.bin", name));
-            (name.to_string(), model_path.exists())
-        })
-        .collect();
+        tracing::debug!("Selected Whisper model: {} (available: {:?})", selected_model, available_models);
 
-        // Auto-select first available model (prefer smaller ones)
-        let selected_model = available_models
-            .iter()
-            .find(|(_, installed)| *installed)
-            .map(|(name, _)| name.clone())
-            .unwrap_or_else(|| "tiny".to_string());
+        // Download threads report each status change and wake the UI to draw it
+        let (download_tx, download_rx) = mpsc::channel();
+        let download_ctx = ctx.clone();
+        let download_manager = downloads::DownloadManager::new(move |event| {
+            let _ = download_tx.send(event.clone());
+            download_ctx.request_repaint();
+        });
+
+        let (tray, tray_rx) = match create_tray(&ctx) {
+            Ok((tray, rx)) => (Some(tray), Some(rx)),
+            Err(e) => {
+                tracing::warn!("Failed to create tray icon: {}", e);
+                (None, None)
+            }
+        };
 
-        eprintln!("🔧 Selected Whisper model: {} (available: {:?})", selected_model, available_models);
+        // Hotkey presses arrive even while the window is minimized and no frames run,
+        // so bring the window back from the handler and leave the recording to update()
+        let hotkey_manager = GlobalHotKeyManager::new()
+            .map_err(|e| tracing::warn!("Global hotkeys unavailable: {}", e))
+            .ok();
+        let (hotkey_tx, hotkey_rx) = mpsc::channel();
+        GlobalHotKeyEvent::set_event_handler(Some(move |event: GlobalHotKeyEvent| {
+            if event.state == HotKeyState::Pressed {
+                show_window(&ctx);
+            }
+            let _ = hotkey_tx.send(event);
+            ctx.request_repaint();
+        }));
+        let (remote_tx, remote_rx) = mpsc::channel();
 
-        Self {
+        let mut app = Self {
             db,
             tasks,
+            new_task_text: String::new(),
+            editing_task: None,
+            dragged_task: None,
+            done_today: Vec::new(),
+            issue_urls: HashMap::new(),
+            pinned: HashSet::new(),
+            reminders: HashMap::new(),
+            reminder_edit: None,
+            schedule_edit: None,
+            inbox_count: 0,
+            last_reminder_check: Instant::now(),
+            selected_tasks: BTreeSet::new(),
+            selection_anchor: None,
+            bulk_project: String::new(),
+            bulk_tag: String::new(),
+            undo_stack: Vec::new(),
+            show_shortcuts: false,
+            task_search: String::new(),
+            ask_text: String::new(),
+            ask_rx: None,
+            ask_answer: None,
+            profile_drafts: profile_drafts(&settings.profiles),
+            profiles: settings.profiles.clone(),
+            new_profile_name: String::new(),
+            habits: Vec::new(),
+            new_habit_name: String::new(),
+            new_habit_schedule: habits::DAILY.to_string(),
+            macros: Vec::new(),
+            new_macro_phrase: String::new(),
+            new_macro_tasks: String::new(),
+            new_macro_archive: false,
+            api_tokens: Vec::new(),
+            new_token_name: String::new(),
+            new_token_scopes: vec![api_tokens::Scope::Write],
+            issued_secret: None,
+            task_filter: database::TaskStatus::Recent,
+            task_context: None,
+            task_project: None,
+            contexts: Vec::new(),
+            today_workload: None,
             timer_start: Instant::now(),
             timer_duration: Duration::from_secs(timer_duration_mins as u64 * 60),
+            timer_paused_at: None,
+            paused_by_user: false,
+            pomodoro: false,
+            focus_task: None,
+            focus_monitor: None,
+            focus_monitor_enabled: settings.focus_monitor_enabled,
+            interval_started_at: now_timestamp(),
+            journal_prompt_enabled: settings.journal_prompt_enabled,
+            speak_tasks_on_alert: settings.speak_tasks_on_alert,
+            voice_inbox: settings.voice_inbox,
+            action_mode: settings.action_mode,
+            content_filter_text: settings.content_filter.words.join(", "),
+            content_filter: settings.content_filter.clone(),
+            journal_interval: None,
+            pending_session: None,
+            pending_alert: None,
+            escalation: settings.escalation,
+            break_settings: settings.break_mode,
+            dnd_apps_text: settings.dnd.fullscreen_apps.join(", "),
+            dnd: settings.dnd,
+            break_ends_at: None,
+            focus_history: Vec::new(),
+            last_presence_check: Instant::now(),
+            power_saving_mode: settings.power_saving,
+            power_saving: power::should_save(settings.power_saving),
+            last_power_check: Instant::now(),
+            model_preloaded: false,
+            model_unload_minutes: settings.model_unload_minutes,
+            benchmark: None,
+            benchmark_rx: None,
+            auto_pause_enabled: settings.auto_pause_enabled,
+            idle_threshold_minutes: settings.idle_threshold_minutes,
+            schedule: settings.schedule,
+            restart_on_resume: false,
             is_recording: false,
             is_processing: false,
+            quit_requested: false,
+            close_behavior: settings.close_behavior,
+            tray,
+            tray_rx,
+            theme: settings.theme,
+            accent: accent_color(&settings.accent_color),
+            ui_scale: settings.ui_scale,
+            font_size: settings.font_size,
+            ui_language: settings.ui_language,
             recording_start: None,
             audio_buffer: Arc::new(Mutex::new(Vec::new())),
             audio_stream: None,
             audio_level: Arc::new(Mutex::new(0.0)),
             input_sample_rate: 48000, // Default, will be updated when recording starts
             show_settings: false,
-            always_on_top: false,
+            always_on_top: settings.window.always_on_top,
+            window_geometry: settings.window.geometry,
+            compact: settings.window.compact,
+            compact_geometry: settings.window.compact_geometry,
+            compact_shortcut: parse_shortcut(&settings.compact_mode_shortcut),
             timer_duration_mins,
             selected_model,
+            recording_language: None,
+            transcription_language: settings.transcription_language.clone(),
             available_models,
-            ollama_enabled,
+            ollama_enabled: settings.ollama_enabled,
+            punctuation: settings.punctuation.clone(),
+            ollama: settings.ollama,
+            ollama_models: Vec::new(),
+            ollama_status: None,
+            ollama_rx: None,
+            earcons_muted: settings.earcons_muted,
+            alert_mode: settings.alert_mode,
+            alert_sound: settings.alert_sound,
             audio_devices,
-            selected_device_idx: 0,
-            error_message: None,
-            error_time: None,
+            selected_device_idx,
+            toasts: Vec::new(),
+            toast_history: Vec::new(),
+            show_toast_history: false,
             status_message: None,
             processing_rx: None,
-            download_state: DownloadState::default(),
+            wake_word_enabled: settings.wake_word_enabled,
+            wake_word: None,
+            wake_word_rx: None,
+            markdown_path_text: settings.markdown_sync.path.clone(),
+            markdown_sync: settings.markdown_sync.clone(),
+            markdown_running: None,
+            markdown_rx: None,
+            calendar_path_text: settings.calendar_export.path.clone(),
+            calendar_export: settings.calendar_export.clone(),
+            calendar_running: None,
+            google_calendar: settings.google_calendar.clone(),
+            google_running: None,
+            google_rx: None,
+            google_connect_rx: None,
+            todoist_token_text: settings.todoist.token.clone(),
+            todoist: settings.todoist.clone(),
+            todoist_running: None,
+            todoist_rx: None,
+            github_token_text: settings.github.token.clone(),
+            github: settings.github.clone(),
+            mqtt_password_text: settings.mqtt.password.clone(),
+            mqtt: settings.mqtt.clone(),
+            mqtt_running: None,
+            mqtt_published_at: Instant::now(),
+            clipboard_trigger_text: settings.clipboard.trigger.clone(),
+            clipboard: settings.clipboard.clone(),
+            report_folder_text: settings.report.folder.clone(),
+            report: settings.report.clone(),
+            rollover: settings.rollover.clone(),
+            workload: settings.workload.clone(),
+            clipboard_running: None,
+            clipboard_rx: None,
+            telegram_token_text: settings.telegram.token.clone(),
+            telegram: settings.telegram.clone(),
+            telegram_running: None,
+            telegram_rx: None,
+            remote_tx,
+            remote_rx,
+            hands_free_recording: false,
+            pending_transcript: None,
+            download_manager,
+            download_rx,
+            download_statuses: Vec::new(),
+            quick_add: None,
+            quick_add_shortcut: parse_shortcut(&settings.quick_add_shortcut),
+            hotkey_manager,
+            record_hotkey: None,
+            record_shortcut: settings.record_shortcut.clone(),
+            hotkey_rx,
+            hotkey_recording: false,
+            crash_report: crash::take_pending_report(),
+            auto_check_updates: settings.auto_check_updates,
+            available_update: None,
+            update_status: None,
+            update_rx: None,
+            diagnostics: None,
+            diagnostics_rx: None,
+            _config_watcher: config_watcher,
+            config_rx,
+        };
+        app.reload_tasks();
+        app.reload_habits();
+        app.reload_macros();
+        app.reload_api_tokens();
+        app.show_startup_report(database_check);
+        app.preload_model();
+        if let Err(e) = app.register_record_hotkey() {
+            tracing::warn!("{}", e);
         }
+        if app.auto_check_updates {
+            app.check_for_updates();
+        }
+        app
     }
-}
 
-impl FlowStateApp {
     fn reload_tasks(&mut self) {
-        self.tasks = database::get_all_tasks(&self.db).unwrap_or_default();
+        // Without a chip the list is the open tasks, with today's completions in their own section
+        let sectioned = self.task_filter == database::TaskStatus::Recent;
+        let query = database::TaskQuery {
+            status: if sectioned { database::TaskStatus::Open } else { self.task_filter },
+            search: Some(self.task_search.clone()),
+            project: self.task_project.clone(),
+            context: self.task_context.clone(),
+            scope: self.profiles.task_scope(),
+            ..database::TaskQuery::default()
+        };
+        self.tasks = database::query_tasks(&self.db, &query).unwrap_or_default();
+        self.done_today = if sectioned {
+            let query = database::TaskQuery {
+                status: database::TaskStatus::CompletedToday,
+                sort: database::TaskSort::RecentlyCompleted,
+                ..query
+            };
+            database::query_tasks(&self.db, &query).unwrap_or_default()
+        } else {
+            Vec::new()
+        };
+        self.issue_urls = database::task_issue_urls(&self.db).unwrap_or_default();
+        self.pinned = database::pinned_task_ids(&self.db).unwrap_or_default();
+        self.contexts = database::task_contexts(&self.db).unwrap_or_default();
+        self.today_workload = estimates::workload(&self.db, self.workload.capacity_minutes).ok();
+        self.reload_reminders();
+        self.inbox_count = inbox::count(&self.db);
+        self.check_achievements();
+        // Bulk actions only reach tasks that are still listed
+        let tasks = &self.tasks;
+        self.selected_tasks.retain(|id| tasks.iter().any(|t| t.id == *id));
     }
 
-    fn refresh_models(&mut self) {
-        let models_dir = dirs::data_dir()
-            .unwrap_or_default()
-            .join("flowstate")
-            .join("whisper_models");
-        self.available_models = vec![
-            ("tiny", 75),
-            ("base", 142),
-            ("small", 466),
-            ("medium", 1500),
-        ]
-        .into_iter()
-        .map(|(name, _size)| {
-            let model_path = models_dir.join(format!("ggml-{}.bin", name));
-            (name.to_string(), model_path.exists())
-        })
-        .collect();
-    }
-
-    fn start_download(&mut self, model_name: &str) {
-        let state = self.download_state.clone();
-        let model = model_name.to_string();
-
-        // Set downloading state
-        *state.is_downloading.lock().unwrap() = true;
-        *state.current_model.lock().unwrap() = Some(model.clone());
-        *state.progress.lock().unwrap() = 0.0;
-        *state.downloaded_mb.lock().unwrap() = 0.0;
-        *state.error.lock().unwrap() = None;
-        *state.completed.lock().unwrap() = false;
-
-        // Get model URL and size
-        let (url, total_size) = match model.as_str() {
-            "tiny" => ("https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-tiny.bin", 75_000_000u64),
-            "base" => ("https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-base.bin", 142_000_000u64),
-            "small" => ("https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-small.bin", 466_000_000u64),
-            "medium" => ("https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-medium.bin", 1_500_000_000u64),
-            _ => {
-                *state.error.lock().unwrap() = Some("Unknown model".to_string());
-                *state.is_downloading.lock().unwrap() = false;
-                return;
+    /// Make `name` the active profile (None for no profile); false if there is no such profile
+    fn switch_profile(&mut self, name: Option<String>) -> bool {
+        match profiles::switch_profile(&self.db, name.as_deref()) {
+            Ok(saved) => {
+                self.profiles.active = saved.profiles.active;
+                let label = self.profiles.active.clone().unwrap_or_else(|| i18n::t("profiles.none"));
+                self.show_info(i18n::tf("profiles.switched", &[("name", &label)]));
+                self.reload_tasks();
+                true
             }
-        };
+            Err(e) => {
+                self.show_error(e);
+                false
+            }
+        }
+    }
 
-        *state.total_mb.lock().unwrap() = total_size as f32 / 1_000_000.0;
+    // Every reload follows a change, so this is where new achievements show up
+    fn check_achievements(&mut self) {
+        for achievement in achievements::check(&self.db) {
+            self.show_info(format!("🏆 {}", achievements::unlocked_message(&achievement)));
+        }
+    }
 
-        let url = url.to_string();
+    fn reload_reminders(&mut self) {
+        match reminders::get_reminders(&self.db) {
+            Ok(pending) => {
+                self.reminders = pending
+                    .into_iter()
+                    .filter_map(|reminder| {
+                        let at = chrono::NaiveDateTime::parse_from_str(&reminder.remind_at, reminders::LOCAL_FORMAT).ok()?;
+                        Some((reminder.task_id, reminders::when_label(at)))
+                    })
+                    .collect();
+            }
+            Err(e) => tracing::error!("Failed to load reminders: {}", e),
+        }
+    }
 
-        // Download in background thread
-        thread::spawn(move || {
-            let rt = tokio::runtime::Runtime::new().unwrap();
-            rt.block_on(async {
-                let models_dir = dirs::data_dir()
-                    .unwrap_or_default()
-                    .join("flowstate")
-                    .join("whisper_models");
-
-                if let Err(e) = std::fs::create_dir_all(&models_dir) {
-                    *state.error.lock().unwrap() = Some(format!("Failed to create directory: {}", e));
-                    *state.is_downloading.lock().unwrap() = false;
-                    return;
-                }
+    /// Set the task's reminder from what was typed next to it; an empty time clears it
+    fn set_reminder(&mut self, task_id: i64, when: &str) {
+        let result = if when.trim().is_empty() {
+            reminders::clear_reminder(&self.db, task_id).map(|_| None)
+        } else {
+            reminders::set_reminder(&self.db, task_id, when).map(Some)
+        };
+        match result {
+            Ok(Some(at)) => self.show_info(i18n::tf("reminders.set_for", &[("time", &reminders::when_label(at))])),
+            Ok(None) => {}
+            Err(e) => self.show_error(e),
+        }
+        self.reload_reminders();
+    }
 
-                let model_path = models_dir.join(format!("ggml-{}.bin", model));
+    /// Move inbox tasks to the main list as they are
+    fn commit_inbox(&mut self, ids: &[i64]) {
+        if let Err(e) = inbox::commit(&self.db, ids) {
+            self.show_error(e);
+        }
+        self.reload_tasks();
+    }
 
-                // Download with reqwest
-                let client = reqwest::Client::new();
-                let response = match client.get(&url).send().await {
-                    Ok(r) => r,
-                    Err(e) => {
-                        *state.error.lock().unwrap() = Some(format!("Download failed: {}", e));
-                        *state.is_downloading.lock().unwrap() = false;
-                        return;
-                    }
-                };
+    /// Move an inbox task to the main list with a due date
+    fn schedule_inbox(&mut self, id: i64, when: &str) {
+        match inbox::schedule(&self.db, id, when) {
+            Ok(task) => {
+                let label = task.due_at.as_deref().and_then(dates::label).unwrap_or_default();
+                self.show_info(i18n::tf("inbox.scheduled", &[("task", &task.text), ("when", &label)]));
+            }
+            Err(e) => self.show_error(e),
+        }
+        self.reload_tasks();
+    }
 
-                if !response.status().is_success() {
-                    *state.error.lock().unwrap() = Some(format!("HTTP error: {}", response.status()));
-                    *state.is_downloading.lock().unwrap() = false;
-                    return;
-                }
+    /// Fire reminders that have come due (an OS notification and a toast each),
+    /// then run the end-of-day rollover if its time has come
+    fn check_reminders(&mut self) {
+        if self.last_reminder_check.elapsed() < power::throttled(Duration::from_secs(10), self.power_saving) {
+            return;
+        }
+        self.last_reminder_check = Instant::now();
+
+        if let Some(review) = rollover::run_if_due(&self.db, &self.rollover) {
+            let summary = rollover::summary(&review);
+            if let Err(e) = notify_rust::Notification::new()
+                .summary("FlowState")
+                .body(&summary)
+                .show()
+            {
+                tracing::warn!("Failed to show notification: {}", e);
+            }
+            self.show_info(summary);
+            self.reload_tasks();
+        }
 
-                let total = response.content_length().unwrap_or(total_size);
-                *state.total_mb.lock().unwrap() = total as f32 / 1_000_000.0;
+        let due = reminders::take_due(&self.db);
+        if due.is_empty() {
+            return;
+        }
+        // The notification always shows (the user asked for it); do-not-disturb only silences it
+        if !dnd::get_dnd_state(&self.dnd).is_active() {
+            earcon::play_alert_sound(&self.alert_sound);
+        }
+        for reminder in &due {
+            tracing::info!("Reminder fired for task {}", reminder.task_id);
+            let body = reminders::notification_body(reminder);
+            if let Err(e) = notify_rust::Notification::new()
+                .summary("FlowState")
+                .body(&body)
+                .show()
+            {
+                tracing::warn!("Failed to show notification: {}", e);
+            }
+            self.show_info(body);
+        }
+        self.reload_reminders();
+    }
 
-                let mut file = match std::fs::File::create(&model_path) {
-                    Ok(f) => f,
-                    Err(e) => {
-                        *state.error.lock().unwrap() = Some(format!("Failed to create file: {}", e));
-                        *state.is_downloading.lock().unwrap() = false;
-                        return;
-                    }
-                };
+    fn reload_habits(&mut self) {
+        match habits::get_habits(&self.db) {
+            Ok(habits) => self.habits = habits,
+            Err(e) => tracing::error!("Failed to load habits: {}", e),
+        }
+    }
 
-                use futures_util::StreamExt;
-                use std::io::Write;
+    fn toggle_habit(&mut self, id: i64) {
+        match habits::toggle_today(&self.db, id) {
+            Ok(_) => self.reload_habits(),
+            Err(e) => self.show_error(e),
+        }
+    }
 
-                let mut stream = response.bytes_stream();
-                let mut downloaded: u64 = 0;
+    fn add_habit(&mut self) {
+        match habits::add_habit(&self.db, &self.new_habit_name, &self.new_habit_schedule) {
+            Ok(_) => {
+                self.new_habit_name.clear();
+                self.reload_habits();
+            }
+            Err(e) => self.show_error(e),
+        }
+    }
 
-                while let Some(chunk) = stream.next().await {
-                    match chunk {
-                        Ok(bytes) => {
-                            if let Err(e) = file.write_all(&bytes) {
-                                *state.error.lock().unwrap() = Some(format!("Write error: {}", e));
-                                *state.is_downloading.lock().unwrap() = false;
-                                return;
-                            }
-                            downloaded += bytes.len() as u64;
-                            *state.downloaded_mb.lock().unwrap() = downloaded as f32 / 1_000_000.0;
-                            *state.progress.lock().unwrap() = downloaded as f32 / total as f32;
-                        }
-                        Err(e) => {
-                            *state.error.lock().unwrap() = Some(format!("Download error: {}", e));
-                            *state.is_downloading.lock().unwrap() = false;
-                            return;
-                        }
-                    }
-                }
+    fn delete_habit(&mut self, id: i64) {
+        match habits::delete_habit(&self.db, id) {
+            Ok(()) => self.reload_habits(),
+            Err(e) => self.show_error(e),
+        }
+    }
 
-                *state.completed.lock().unwrap() = true;
-                *state.is_downloading.lock().unwrap() = false;
-            });
-        });
+    fn reload_macros(&mut self) {
+        match macros::get_macros(&self.db) {
+            Ok(macros) => self.macros = macros,
+            Err(e) => tracing::error!("Failed to load macros: {}", e),
+        }
     }
 
-    fn start_recording(&mut self) {
-        let host = cpal::default_host();
+    fn add_macro(&mut self) {
+        let mut actions: Vec<macros::MacroAction> = self
+            .new_macro_tasks
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(|text| macros::MacroAction::AddTask { text: text.to_string() })
+            .collect();
+        if self.new_macro_archive {
+            actions.insert(0, macros::MacroAction::ArchiveCompleted);
+        }
+        match macros::add_macro(&self.db, &self.new_macro_phrase, actions) {
+            Ok(_) => {
+                self.new_macro_phrase.clear();
+                self.new_macro_tasks.clear();
+                self.new_macro_archive = false;
+                self.reload_macros();
+            }
+            Err(e) => self.show_error(e),
+        }
+    }
 
-        eprintln!("🎙️ Starting recording with device index: {}", self.selected_device_idx);
+    fn delete_macro(&mut self, id: i64) {
+        match macros::delete_macro(&self.db, id) {
+            Ok(()) => self.reload_macros(),
+            Err(e) => self.show_error(e),
+        }
+    }
 
-        let device = if self.selected_device_idx == 0 {
-            eprintln!("  → Using default input device");
-            host.default_input_device()
-        } else {
-            eprintln!("  → Using device at index {}", self.selected_device_idx - 1);
-            host.input_devices()
-                .ok()
-                .and_then(|mut devices| devices.nth(self.selected_device_idx - 1))
-        };
+    fn reload_api_tokens(&mut self) {
+        match api_tokens::list(&self.db) {
+            Ok(tokens) => self.api_tokens = tokens,
+            Err(e) => tracing::error!("Failed to load API tokens: {}", e),
+        }
+    }
 
-        let Some(device) = device else {
-            eprintln!("❌ No audio device found!");
-            self.error_message = Some("No audio device found".to_string());
-            self.error_time = Some(Instant::now());
-            return;
-        };
+    fn issue_api_token(&mut self) {
+        match api_tokens::issue(&self.db, &self.new_token_name, &self.new_token_scopes) {
+            Ok(issued) => {
+                self.new_token_name.clear();
+                self.issued_secret = Some(issued.secret);
+                self.reload_api_tokens();
+            }
+            Err(e) => self.show_error(e),
+        }
+    }
 
-        eprintln!("  → Device: {:?}", device.name());
+    fn revoke_api_token(&mut self, id: i64) {
+        match api_tokens::revoke(&self.db, id) {
+            Ok(()) => self.reload_api_tokens(),
+            Err(e) => self.show_error(e),
+        }
+    }
 
-        let supported_config = match device.default_input_config() {
-            Ok(c) => c,
-            Err(e) => {
-                self.error_message = Some(format!("Failed to get audio config: {}", e));
-                self.error_time = Some(Instant::now());
-                return;
+    fn run_macro(&mut self, voice_macro: macros::VoiceMacro) {
+        match macros::run(&self.db, &voice_macro) {
+            Ok(run) => {
+                for command in run.timer {
+                    self.apply_timer_command(command);
+                }
+                earcon::play(earcon::Earcon::Success);
+                self.show_info(i18n::tf(
+                    "macros.ran",
+                    &[("phrase", &voice_macro.phrase), ("added", &run.added.len()), ("archived", &run.archived)],
+                ));
+                self.reload_tasks();
             }
-        };
+            Err(e) => self.show_error(e),
+        }
+    }
 
-        let buffer = self.audio_buffer.clone();
-        let audio_level = self.audio_level.clone();
-        let sample_rate = supported_config.sample_rate().0;
-        let channels = supported_config.channels() as usize;
-        let sample_format = supported_config.sample_format();
+    /// One line per habit: the last seven days as dots (today's can be
+    /// clicked) and the current streak
+    fn habit_dots(&mut self, ui: &mut egui::Ui) {
+        let accent = self.accent;
+        let mut toggle = None;
+        for habit in &self.habits {
+            ui.horizontal(|ui| {
+                ui.label(&habit.name);
+                let today = habit.recent.len().saturating_sub(1);
+                for (index, day) in habit.recent.iter().enumerate() {
+                    let color = if day.done {
+                        accent
+                    } else if day.due {
+                        egui::Color32::GRAY
+                    } else {
+                        egui::Color32::from_gray(70)
+                    };
+                    let dot = egui::RichText::new(if day.done { "●" } else { "○" }).color(color);
+                    if index == today {
+                        let response = ui
+                            .add(egui::Label::new(dot).sense(egui::Sense::click()))
+                            .on_hover_text(i18n::t("habits.toggle_hint"));
+                        if response.clicked() {
+                            toggle = Some(habit.id);
+                        }
+                    } else {
+                        ui.label(dot).on_hover_text(&day.date);
+                    }
+                }
+                if habit.streak > 0 {
+                    ui.weak(format!("🔥{}", habit.streak));
+                }
+            });
+        }
+        if let Some(id) = toggle {
+            self.toggle_habit(id);
+        }
+    }
 
-        // Store sample rate for resampling later
-        self.input_sample_rate = sample_rate;
-        eprintln!("🎤 Recording at {} Hz, {} channels, format: {:?}", sample_rate, channels, sample_format);
+    fn notify(&mut self, severity: Severity, message: String) {
+        let toast = Toast {
+            severity,
+            message,
+            shown_at: Instant::now(),
+            time: chrono::Local::now().format("%H:%M:%S").to_string(),
+        };
+        if self.toast_history.len() == MAX_TOAST_HISTORY {
+            self.toast_history.remove(0);
+        }
+        self.toast_history.push(toast.clone());
+        self.toasts.push(toast);
+    }
+
+    fn show_error(&mut self, message: impl Into<String>) {
+        self.notify(Severity::Error, message.into());
+    }
+
+    fn show_warning(&mut self, message: impl Into<String>) {
+        self.notify(Severity::Warning, message.into());
+    }
+
+    fn show_info(&mut self, message: impl Into<String>) {
+        self.notify(Severity::Info, message.into());
+    }
+
+    /// Toast stack in the bottom-right corner; click one to dismiss it
+    fn show_toasts(&mut self, ctx: &egui::Context) {
+        self.toasts.retain(|toast| toast.severity == Severity::Error || toast.shown_at.elapsed() < TOAST_TIMEOUT);
+        if self.toasts.is_empty() {
+            return;
+        }
+        let mut dismissed = None;
+        let first_visible = self.toasts.len().saturating_sub(MAX_VISIBLE_TOASTS);
+        egui::Area::new(egui::Id::new("toasts"))
+            .anchor(egui::Align2::RIGHT_BOTTOM, [-8.0, -8.0])
+            .order(egui::Order::Foreground)
+            .show(ctx, |ui| {
+                ui.set_max_width(260.0);
+                for (i, toast) in self.toasts.iter().enumerate().skip(first_visible) {
+                    let frame = egui::Frame::popup(ui.style())
+                        .stroke(egui::Stroke::new(1.0, toast.severity.color()))
+                        .show(ui, |ui| {
+                            ui.colored_label(toast.severity.color(), &toast.message);
+                        });
+                    if frame.response.interact(egui::Sense::click()).on_hover_text(i18n::t("toasts.dismiss_hint")).clicked() {
+                        dismissed = Some(i);
+                    }
+                }
+            });
+        if let Some(i) = dismissed {
+            self.toasts.remove(i);
+        }
+        // Come back to fade out warnings and confirmations
+        if self.toasts.iter().any(|toast| toast.severity != Severity::Error) {
+            ctx.request_repaint_after(Duration::from_secs(1));
+        }
+    }
+
+    fn push_undo(&mut self, action: UndoAction) {
+        if self.undo_stack.len() == MAX_UNDO {
+            self.undo_stack.remove(0);
+        }
+        self.undo_stack.push(action);
+    }
+
+    /// Revert the latest task-list change
+    fn undo(&mut self) {
+        let Some(action) = self.undo_stack.pop() else {
+            return;
+        };
+        let result = match action {
+            UndoAction::Added(ids) => database::delete_tasks(&self.db, &ids).map(|_| ()),
+            UndoAction::Deleted(tasks) => tasks.iter().try_for_each(|task| database::restore_task(&self.db, task)),
+            UndoAction::Toggled(ids) => ids.iter().try_for_each(|id| database::toggle_task(&self.db, *id).map(|_| ())),
+            UndoAction::Edited(edits) => edits.iter().try_for_each(|(id, text)| database::update_task(&self.db, *id, text)),
+        };
+        if let Err(e) = result {
+            self.show_error(i18n::tf("error.undo", &[("error", &e)]));
+        }
+        self.reload_tasks();
+    }
+
+    /// Delete tasks, keeping them for undo (the caller reloads)
+    fn delete_tasks(&mut self, ids: &[i64]) {
+        match database::delete_tasks(&self.db, ids) {
+            Ok(deleted) if !deleted.is_empty() => self.push_undo(UndoAction::Deleted(deleted)),
+            Ok(_) => {}
+            Err(e) => self.show_error(e.to_string()),
+        }
+        self.selected_tasks.retain(|id| !ids.contains(id));
+    }
+
+    /// Carry out an add, complete or delete from action mode, with undo
+    fn apply_task_command(&mut self, command: action_mode::TaskCommand) {
+        use action_mode::TaskCommand;
+        let not_found = |text: &str| i18n::tf("error.no_matching_task", &[("text", &text)]);
+        let result = match command {
+            TaskCommand::Add(text) => database::add_tasks_with_due(&self.db, &[(text, None)], self.voice_inbox)
+                .map_err(|e| e.to_string())
+                .map(|added| {
+                    self.push_undo(UndoAction::Added(added.iter().map(|t| t.id).collect()));
+                    let key = if self.voice_inbox { "status.tasks_to_inbox" } else { "status.tasks_added" };
+                    i18n::tf(key, &[("count", &added.len())])
+                }),
+            TaskCommand::Complete(text) => database::find_and_complete_task(&self.db, &text)
+                .map_err(|_| not_found(&text))
+                .map(|task| {
+                    self.push_undo(UndoAction::Toggled(vec![task.id]));
+                    i18n::tf("status.task_completed", &[("task", &task.text)])
+                }),
+            TaskCommand::Delete(text) => match database::find_and_delete_task(&self.db, &text) {
+                Ok(Some(task)) => {
+                    let message = i18n::tf("status.task_deleted", &[("task", &task.text)]);
+                    self.push_undo(UndoAction::Deleted(vec![task]));
+                    Ok(message)
+                }
+                Ok(None) => Err(not_found(&text)),
+                Err(e) => Err(e.to_string()),
+            },
+        };
+        match result {
+            Ok(message) => {
+                self.reload_tasks();
+                earcon::play(earcon::Earcon::Success);
+                self.status_message = Some(message);
+            }
+            Err(e) => self.show_error(e),
+        }
+    }
+
+    /// Click selects one task, Ctrl+click adds or removes one, Shift+click adds
+    /// the range from the last click
+    fn select_task(&mut self, id: i64, modifiers: egui::Modifiers) {
+        let position = |id: i64| self.tasks.iter().position(|t| t.id == id);
+        if modifiers.shift {
+            if let (Some(from), Some(to)) = (self.selection_anchor.and_then(position), position(id)) {
+                let range = from.min(to)..=from.max(to);
+                self.selected_tasks.extend(self.tasks[range].iter().map(|t| t.id));
+                return;
+            }
+        }
+        if modifiers.command {
+            if !self.selected_tasks.remove(&id) {
+                self.selected_tasks.insert(id);
+            }
+        } else {
+            self.selected_tasks.clear();
+            self.selected_tasks.insert(id);
+        }
+        self.selection_anchor = Some(id);
+    }
+
+    /// Rewrite the selected tasks' text with a batch update, keeping the old text for undo
+    fn edit_selected(&mut self, edit: impl FnOnce(&database::Database, &[i64]) -> rusqlite::Result<Vec<database::Task>>) {
+        let ids: Vec<i64> = self.selected_tasks.iter().copied().collect();
+        let previous: Vec<(i64, String)> = self
+            .tasks
+            .iter()
+            .filter(|t| self.selected_tasks.contains(&t.id))
+            .map(|t| (t.id, t.text.clone()))
+            .collect();
+        match edit(&self.db, &ids) {
+            Ok(changed) if !changed.is_empty() => {
+                let edits = previous.into_iter().filter(|(id, _)| changed.iter().any(|t| t.id == *id)).collect();
+                self.push_undo(UndoAction::Edited(edits));
+            }
+            Ok(_) => {}
+            Err(e) => self.show_error(e.to_string()),
+        }
+        self.reload_tasks();
+    }
+
+    /// Complete, delete, move or tag every selected task
+    fn bulk_actions(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal_wrapped(|ui| {
+            ui.label(i18n::tf("tasks.selected", &[("count", &self.selected_tasks.len())]));
+            let ids: Vec<i64> = self.selected_tasks.iter().copied().collect();
+            if ui.button(i18n::t("tasks.bulk_complete")).clicked() {
+                match database::complete_tasks(&self.db, &ids) {
+                    Ok(completed) if !completed.is_empty() => {
+                        self.push_undo(UndoAction::Toggled(completed.iter().map(|t| t.id).collect()));
+                    }
+                    Ok(_) => {}
+                    Err(e) => self.show_error(e.to_string()),
+                }
+                self.selected_tasks.clear();
+                self.reload_tasks();
+            }
+            if ui.button(i18n::t("tasks.bulk_delete")).clicked() {
+                self.delete_tasks(&ids);
+                self.reload_tasks();
+            }
+
+            ui.add(
+                egui::TextEdit::singleline(&mut self.bulk_project)
+                    .hint_text(i18n::t("tasks.bulk_project_hint"))
+                    .desired_width(90.0),
+            );
+            if ui.button(i18n::t("tasks.bulk_move")).on_hover_text(i18n::t("tasks.bulk_move_hint")).clicked() {
+                let project = self.bulk_project.trim().to_string();
+                self.edit_selected(|db, ids| {
+                    database::set_tasks_project(db, ids, Some(project.as_str()).filter(|p| !p.is_empty()))
+                });
+                self.bulk_project.clear();
+            }
+
+            ui.add(
+                egui::TextEdit::singleline(&mut self.bulk_tag)
+                    .hint_text(i18n::t("tasks.bulk_tag_hint"))
+                    .desired_width(90.0),
+            );
+            let tag = self.bulk_tag.trim().to_string();
+            if ui.add_enabled(!tag.is_empty(), egui::Button::new(i18n::t("tasks.bulk_tag"))).clicked() {
+                self.edit_selected(|db, ids| database::tag_tasks(db, ids, &tag));
+                self.bulk_tag.clear();
+            }
+
+            if ui.small_button("✕").on_hover_text(i18n::t("tasks.clear_selection")).clicked() {
+                self.selected_tasks.clear();
+            }
+        });
+    }
+
+    /// Space, Ctrl+N, Ctrl+F, Delete, Escape, Ctrl+Z and F1. Keys that also edit text are
+    /// left to a focused text field.
+    fn handle_shortcuts(&mut self, ctx: &egui::Context) {
+        let typing = ctx.wants_keyboard_input();
+        let (record, delete, clear_selection, undo, quick_add, search, help) = ctx.input_mut(|i| {
+            (
+                !typing && i.consume_key(egui::Modifiers::NONE, egui::Key::Space),
+                !typing && i.consume_key(egui::Modifiers::NONE, egui::Key::Delete),
+                !typing && i.consume_key(egui::Modifiers::NONE, egui::Key::Escape),
+                !typing && i.consume_key(egui::Modifiers::COMMAND, egui::Key::Z),
+                i.consume_key(egui::Modifiers::COMMAND, egui::Key::N),
+                i.consume_key(egui::Modifiers::COMMAND, egui::Key::F),
+                i.consume_key(egui::Modifiers::NONE, egui::Key::F1),
+            )
+        });
+        if record {
+            self.toggle_hotkey_recording();
+        }
+        if delete && !self.selected_tasks.is_empty() {
+            let ids: Vec<i64> = self.selected_tasks.iter().copied().collect();
+            self.delete_tasks(&ids);
+            self.reload_tasks();
+        }
+        if clear_selection {
+            self.selected_tasks.clear();
+        }
+        if undo {
+            self.undo();
+        }
+        if quick_add {
+            self.quick_add = Some(self.quick_add.take().unwrap_or_default());
+        }
+        // The search box is only on the full-size window
+        if search && !self.compact {
+            ctx.memory_mut(|m| m.request_focus(egui::Id::new("task_search")));
+        }
+        if help {
+            self.show_shortcuts = !self.show_shortcuts;
+        }
+    }
+
+    /// The list shows a subset, so its order can't be rearranged
+    fn task_list_filtered(&self) -> bool {
+        self.task_filter != database::TaskStatus::Recent
+            || self.task_context.is_some()
+            || self.task_project.is_some()
+            || !self.task_search.trim().is_empty()
+    }
+
+    /// Persist the settings panel to the shared store (same document the Tauri UI uses)
+    fn save_settings(&mut self) {
+        let saved = settings::load(&self.db);
+        let updated = settings::Settings {
+            timer_duration_minutes: self.timer_duration_mins as u64,
+            selected_model: self.selected_model.clone(),
+            transcription_language: self.transcription_language.trim().to_string(),
+            input_device: self
+                .selected_device_idx
+                .checked_sub(1)
+                .and_then(|idx| self.audio_devices.get(idx).cloned()),
+            ollama_enabled: self.ollama_enabled,
+            ollama: self.ollama.clone(),
+            punctuation: self.punctuation.clone(),
+            markdown_sync: self.markdown_sync.clone(),
+            calendar_export: self.calendar_export.clone(),
+            google_calendar: self.google_calendar.clone(),
+            todoist: self.todoist.clone(),
+            github: self.github.clone(),
+            telegram: self.telegram.clone(),
+            clipboard: self.clipboard.clone(),
+            mqtt: self.mqtt.clone(),
+            report: self.report.clone(),
+            rollover: self.rollover.clone(),
+            workload: self.workload.clone(),
+            profiles: self.profiles.clone(),
+            earcons_muted: self.earcons_muted,
+            wake_word_enabled: self.wake_word_enabled,
+            alert_mode: self.alert_mode,
+            alert_sound: self.alert_sound.clone(),
+            auto_pause_enabled: self.auto_pause_enabled,
+            idle_threshold_minutes: self.idle_threshold_minutes,
+            journal_prompt_enabled: self.journal_prompt_enabled,
+            speak_tasks_on_alert: self.speak_tasks_on_alert,
+            voice_inbox: self.voice_inbox,
+            action_mode: self.action_mode,
+            content_filter: self.content_filter.clone(),
+            focus_monitor_enabled: self.focus_monitor_enabled,
+            schedule: self.schedule.clone(),
+            escalation: self.escalation.clone(),
+            break_mode: self.break_settings.clone(),
+            dnd: self.dnd.clone(),
+            close_behavior: self.close_behavior,
+            theme: self.theme,
+            accent_color: format!("#{:02x}{:02x}{:02x}", self.accent.r(), self.accent.g(), self.accent.b()),
+            ui_scale: self.ui_scale,
+            font_size: self.font_size,
+            ui_language: self.ui_language,
+            auto_check_updates: self.auto_check_updates,
+            power_saving: self.power_saving_mode,
+            model_unload_minutes: self.model_unload_minutes,
+            record_shortcut: self.record_shortcut.trim().to_string(),
+            window: settings::WindowSettings {
+                geometry: self.window_geometry,
+                always_on_top: self.always_on_top,
+                compact: self.compact,
+                compact_geometry: self.compact_geometry,
+            },
+            ..saved.clone()
+        };
+        let result = updated.validate().and_then(|()| settings::save(&self.db, &updated));
+        if let Err(e) = result {
+            // Free-text fields (schedule, Ollama server, note and calendar paths, Google Calendar, Todoist, GitHub,
+            // Telegram and MQTT settings, clipboard trigger, report folder, rollover time, profiles) fall back to the stored ones
+            self.schedule = saved.schedule;
+            self.transcription_language = saved.transcription_language.clone();
+            self.ollama = saved.ollama;
+            self.markdown_path_text = saved.markdown_sync.path.clone();
+            self.markdown_sync = saved.markdown_sync;
+            self.calendar_path_text = saved.calendar_export.path.clone();
+            self.calendar_export = saved.calendar_export;
+            self.google_calendar = saved.google_calendar;
+            self.todoist_token_text = saved.todoist.token.clone();
+            self.todoist = saved.todoist;
+            self.github_token_text = saved.github.token.clone();
+            self.github = saved.github;
+            self.telegram_token_text = saved.telegram.token.clone();
+            self.telegram = saved.telegram;
+            self.clipboard_trigger_text = saved.clipboard.trigger.clone();
+            self.clipboard = saved.clipboard;
+            self.mqtt_password_text = saved.mqtt.password.clone();
+            self.mqtt = saved.mqtt;
+            self.report_folder_text = saved.report.folder.clone();
+            self.report = saved.report;
+            self.rollover = saved.rollover;
+            self.profile_drafts = profile_drafts(&saved.profiles);
+            self.profiles = saved.profiles;
+            self.show_error(e);
+        }
+    }
+
+    /// Register `record_shortcut` system-wide, replacing the previous hotkey.
+    /// On failure the previous hotkey stays active.
+    fn register_record_hotkey(&mut self) -> Result<(), String> {
+        let shortcut = self.record_shortcut.trim();
+        let register_error = |error: String| i18n::tf("error.register_shortcut", &[("shortcut", shortcut), ("error", &error)]);
+        let Some(manager) = &self.hotkey_manager else {
+            return Err(register_error(i18n::t("error.global_hotkeys_unavailable")));
+        };
+        let hotkey = shortcut.parse::<HotKey>().map_err(|e| register_error(e.to_string()))?;
+        if self.record_hotkey == Some(hotkey) {
+            return Ok(());
+        }
+        manager.register(hotkey).map_err(|e| register_error(e.to_string()))?;
+        if let Some(previous) = self.record_hotkey.replace(hotkey) {
+            let _ = manager.unregister(previous);
+        }
+        Ok(())
+    }
+
+    /// Record hotkey, tray entry and Space: start a recording, or stop the one it started
+    fn toggle_hotkey_recording(&mut self) {
+        if self.is_recording {
+            if self.hotkey_recording {
+                self.stop_recording();
+            }
+        } else if !self.is_processing {
+            self.start_recording();
+            self.hotkey_recording = self.is_recording;
+        }
+    }
+
+    fn refresh_models(&mut self) {
+        self.available_models = installed_models();
+    }
+
+    /// Look for a newer release in the background
+    fn check_for_updates(&mut self) {
+        let (tx, rx) = mpsc::channel();
+        self.update_rx = Some(rx);
+        self.update_status = Some(i18n::t("updates.checking"));
+        thread::spawn(move || {
+            let rt = tokio::runtime::Runtime::new().unwrap();
+            let _ = tx.send(UpdateResult::Checked(rt.block_on(updates::check_for_updates())));
+        });
+    }
+
+    /// Download the latest installer, check its signature and start it; quits
+    /// once it is running
+    fn install_update(&mut self) {
+        let (tx, rx) = mpsc::channel();
+        self.update_rx = Some(rx);
+        self.update_status = Some(i18n::t("updates.downloading"));
+        thread::spawn(move || {
+            let rt = tokio::runtime::Runtime::new().unwrap();
+            let result = rt
+                .block_on(updates::download_verified_installer())
+                .and_then(|path| updates::launch_installer(&path));
+            let _ = tx.send(UpdateResult::Installed(result));
+        });
+    }
+
+    /// Run the health checks in the background (the Ollama check can take a few seconds)
+    fn run_diagnostics(&mut self) {
+        let (tx, rx) = mpsc::channel();
+        self.diagnostics_rx = Some(rx);
+        let selected_model = self.selected_model.clone();
+        let ollama_enabled = self.ollama_enabled;
+        let ollama = self.ollama.clone();
+        let db = self.db.reopen();
+        thread::spawn(move || {
+            let db = match db {
+                Ok(db) => db,
+                Err(e) => {
+                    tracing::error!("Failed to open database for diagnostics: {}", e);
+                    return;
+                }
+            };
+            let rt = tokio::runtime::Runtime::new().unwrap();
+            let report = rt.block_on(diagnostics::run(&db, &selected_model, ollama_enabled, &ollama));
+            let _ = tx.send(report);
+        });
+    }
+
+    /// Ask the Ollama server for its models in the background ("Test" in Settings)
+    fn test_ollama(&mut self) {
+        if let Err(e) = self.ollama.validate() {
+            self.ollama_status = Some((false, e));
+            return;
+        }
+        let (tx, rx) = mpsc::channel();
+        self.ollama_rx = Some(rx);
+        self.ollama_status = Some((false, i18n::t("settings.ollama_testing")));
+        let config = self.ollama.clone();
+        thread::spawn(move || {
+            let rt = tokio::runtime::Runtime::new().unwrap();
+            let _ = tx.send(rt.block_on(ollama::list_models(&config)));
+        });
+    }
+
+    /// Time the installed models on the test clip in the background
+    fn run_benchmark(&mut self) {
+        let (tx, rx) = mpsc::channel();
+        self.benchmark_rx = Some(rx);
+        self.benchmark = None;
+        thread::spawn(move || {
+            let _ = tx.send(crash::catch_panic(benchmark::run_installed));
+        });
+    }
+
+    fn poll_benchmark(&mut self) {
+        if let Some(result) = self.benchmark_rx.as_ref().and_then(|rx| rx.try_recv().ok()) {
+            self.benchmark_rx = None;
+            self.benchmark = Some(result);
+        }
+    }
+
+    /// Apply an edited flowstate.toml to the settings and the panel
+    fn poll_config_file(&mut self) {
+        let Some(file) = self.config_rx.try_iter().last() else {
+            return;
+        };
+        let previous = settings::load(&self.db);
+        let saved = match settings::update(&self.db, file.patch()) {
+            Ok(saved) => saved,
+            Err(e) => {
+                self.show_error(i18n::tf("error.config_file", &[("path", &config_file::FILE_NAME), ("error", &e)]));
+                return;
+            }
+        };
+        // Our own write-back after a change in the panel
+        if saved == previous {
+            return;
+        }
+        self.selected_model = saved.selected_model;
+        self.transcription_language = saved.transcription_language;
+        self.ui_language = saved.ui_language;
+        i18n::set_locale(saved.ui_language);
+        self.ollama = saved.ollama;
+        if self.timer_duration_mins != saved.timer_duration_minutes as u32 {
+            self.timer_duration_mins = saved.timer_duration_minutes as u32;
+            self.reset_timer();
+        }
+        self.show_info(i18n::t("status.config_file_applied"));
+    }
+
+    fn poll_ollama(&mut self) {
+        let Some(result) = self.ollama_rx.as_ref().and_then(|rx| rx.try_recv().ok()) else {
+            return;
+        };
+        self.ollama_rx = None;
+        self.ollama_status = Some(match result {
+            Ok(models) => {
+                let model = self.ollama.model.trim().to_string();
+                self.ollama_models = models;
+                match ollama::find_model(&self.ollama_models, &model) {
+                    Some(found) => (true, i18n::tf("settings.ollama_test_ok", &[("model", &found)])),
+                    None => (false, i18n::tf("settings.ollama_test_missing", &[("model", &model)])),
+                }
+            }
+            Err(e) => (false, i18n::tf("settings.ollama_test_failed", &[("url", &self.ollama.base_url()), ("error", &e)])),
+        });
+    }
+
+    /// Write the weekly review for this week or `weeks_ago` weeks back
+    fn generate_report(&mut self, weeks_ago: u32) {
+        match report::generate_weekly_report(&self.db, &self.report, weeks_ago) {
+            Ok(written) => self.show_info(i18n::tf("report.written", &[("path", &written.pdf_path.unwrap_or(written.markdown_path))])),
+            Err(e) => self.show_error(e),
+        }
+    }
+
+    /// Answer the question in the ask box in the background
+    fn ask_tasks(&mut self) {
+        let db = match self.db.reopen() {
+            Ok(db) => db,
+            Err(e) => {
+                self.show_error(e.to_string());
+                return;
+            }
+        };
+        let (tx, rx) = mpsc::channel();
+        self.ask_rx = Some(rx);
+        self.ask_answer = None;
+        let question = self.ask_text.clone();
+        let ollama = self.ollama_enabled.then(|| self.ollama.clone());
+        thread::spawn(move || {
+            let rt = tokio::runtime::Runtime::new().unwrap();
+            let _ = tx.send(rt.block_on(ask::ask_tasks(&db, &question, ollama.as_ref())));
+        });
+    }
+
+    fn poll_ask(&mut self) {
+        let Some(result) = self.ask_rx.as_ref().and_then(|rx| rx.try_recv().ok()) else {
+            return;
+        };
+        self.ask_rx = None;
+        match result {
+            Ok(answer) => self.ask_answer = Some(answer),
+            Err(e) => self.show_error(e),
+        }
+    }
+
+    fn poll_updates(&mut self) {
+        let Some(result) = self.update_rx.as_ref().and_then(|rx| rx.try_recv().ok()) else {
+            return;
+        };
+        self.update_rx = None;
+        match result {
+            UpdateResult::Checked(Ok(update)) => {
+                self.update_status = update.is_none().then(|| i18n::t("updates.up_to_date"));
+                self.available_update = update;
+            }
+            UpdateResult::Installed(Ok(())) => {
+                self.update_status = None;
+                self.quit_requested = true;
+            }
+            UpdateResult::Checked(Err(e)) | UpdateResult::Installed(Err(e)) => {
+                tracing::warn!("{}", e);
+                self.update_status = Some(e);
+            }
+        }
+    }
+
+    /// Start, restart or stop the Markdown sync to match `markdown_sync`
+    /// (it waits for a path before starting)
+    fn sync_markdown(&mut self, ctx: &egui::Context) {
+        let wanted = Some(&self.markdown_sync).filter(|config| config.enabled && !config.path.trim().is_empty());
+        if self.markdown_running.as_ref().map(|(config, _)| config) == wanted {
+            return;
+        }
+        let wanted = wanted.cloned();
+        // Dropping the sync stops its thread
+        self.markdown_running = None;
+        self.markdown_rx = None;
+        let Some(config) = wanted else {
+            return;
+        };
+
+        let (tx, rx) = mpsc::channel();
+        let repaint_ctx = ctx.clone();
+        let on_import = Box::new(move |imported: sync::markdown::Imported| {
+            let _ = tx.send(imported);
+            repaint_ctx.request_repaint();
+        });
+        let started = config
+            .validate()
+            .and_then(|()| sync::markdown::MarkdownSync::start(&self.db, &config, on_import));
+        match started {
+            Ok(running) => {
+                self.markdown_running = Some((config, running));
+                self.markdown_rx = Some(rx);
+            }
+            Err(e) => {
+                // Keep the path but stop retrying until the settings change
+                self.markdown_sync.enabled = false;
+                self.show_error(e);
+            }
+        }
+    }
+
+    /// Act on one result from background processing; true once the work is done
+    fn apply_processing_result(&mut self, ctx: &egui::Context, result: ProcessingResult) -> bool {
+        match result {
+            ProcessingResult::Transcript(transcript) => {
+                self.acknowledge_alert(ctx);
+                record_transcription(&self.db, "recording", &transcript);
+                self.status_message = Some(i18n::tf("status.transcribed", &[("transcript", &transcript)]));
+            }
+            ProcessingResult::Review(transcript) => {
+                self.acknowledge_alert(ctx);
+                record_transcription(&self.db, "recording", &transcript);
+                self.pending_transcript = Some(transcript);
+            }
+            ProcessingResult::Tasks(parsed_tasks) => {
+                if parsed_tasks.is_empty() {
+                    self.show_warning(i18n::t("error.no_tasks_found"));
+                } else {
+                    let mut new_tasks = Vec::new();
+                    for task in &parsed_tasks {
+                        tracing::debug!("Adding task: '{}' (completed: {})", task.text, task.completed);
+                        if task.completed {
+                            let _ = database::find_and_complete_task(&self.db, &task.text);
+                        } else {
+                            new_tasks.push((task.text.clone(), task.due_at.clone()));
+                        }
+                    }
+                    match database::add_tasks_with_due(&self.db, &new_tasks, self.voice_inbox) {
+                        Ok(added) if !added.is_empty() => {
+                            self.push_undo(UndoAction::Added(added.iter().map(|t| t.id).collect()));
+                        }
+                        Ok(_) => {}
+                        Err(e) => tracing::error!("Failed to add tasks: {}", e),
+                    }
+                    self.reload_tasks();
+                    earcon::play(earcon::Earcon::Success);
+                    let key = if self.voice_inbox { "status.tasks_to_inbox" } else { "status.tasks_added" };
+                    self.status_message = Some(i18n::tf(key, &[("count", &parsed_tasks.len())]));
+                }
+            }
+            ProcessingResult::Issue(text, issue) => {
+                // The task is kept even if the issue failed, so the capture isn't lost
+                match database::add_task(&self.db, &text) {
+                    Ok(task) => {
+                        self.push_undo(UndoAction::Added(vec![task.id]));
+                        match issue {
+                            Ok(url) => {
+                                if let Err(e) = database::set_task_issue_url(&self.db, task.id, &url) {
+                                    tracing::warn!("Failed to store issue URL: {}", e);
+                                }
+                                earcon::play(earcon::Earcon::Success);
+                                self.status_message = Some(i18n::tf("status.tasks_added", &[("count", &1)]));
+                            }
+                            Err(e) => self.show_error(i18n::tf("error.github_issue", &[("error", &e)])),
+                        }
+                    }
+                    Err(e) => tracing::error!("Failed to add task: {}", e),
+                }
+                self.reload_tasks();
+            }
+            ProcessingResult::Journal(text, interval_start, interval_end) => {
+                self.acknowledge_alert(ctx);
+                record_transcription(&self.db, "journal", &text);
+                match database::add_journal_entry(&self.db, &text, &interval_start, &interval_end) {
+                    Ok(_) => {
+                        earcon::play(earcon::Earcon::Success);
+                        tracing::debug!("Journal entry: \"{}\"", text);
+                    }
+                    Err(e) => {
+                        self.show_error(i18n::tf("error.save_journal", &[("error", &e)]));
+                    }
+                }
+            }
+            ProcessingResult::Macro(voice_macro) => self.run_macro(voice_macro),
+            ProcessingResult::Habit(name) => match habits::log_by_name(&self.db, &name) {
+                Ok(habit) => {
+                    earcon::play(earcon::Earcon::Success);
+                    self.show_info(i18n::tf("habits.logged", &[("name", &habit.name), ("streak", &habit.streak)]));
+                    self.reload_habits();
+                }
+                Err(e) => self.show_error(e),
+            },
+            ProcessingResult::Profile(name) => {
+                if self.switch_profile(Some(name)) {
+                    earcon::play(earcon::Earcon::Success);
+                }
+            }
+            ProcessingResult::Reminder(text, at) => {
+                match database::add_task(&self.db, &text) {
+                    Ok(task) => {
+                        self.push_undo(UndoAction::Added(vec![task.id]));
+                        match reminders::set_reminder_at(&self.db, task.id, at) {
+                            Ok(()) => {
+                                earcon::play(earcon::Earcon::Success);
+                                self.show_info(i18n::tf("reminders.set", &[("task", &task.text), ("time", &reminders::when_label(at))]));
+                            }
+                            Err(e) => self.show_error(e),
+                        }
+                    }
+                    Err(e) => tracing::error!("Failed to add task: {}", e),
+                }
+                self.reload_tasks();
+            }
+            ProcessingResult::Timer(command) => self.apply_timer_command(command),
+            ProcessingResult::App(command) => {
+                show_window(ctx);
+                self.apply_app_command(command);
+                earcon::play(earcon::Earcon::Success);
+            }
+            ProcessingResult::Workload => match estimates::workload(&self.db, self.workload.capacity_minutes) {
+                Ok(workload) => {
+                    earcon::play(earcon::Earcon::Success);
+                    if workload.over_minutes > 0 {
+                        self.show_warning(estimates::summary(&workload));
+                    } else {
+                        self.show_info(estimates::summary(&workload));
+                    }
+                }
+                Err(e) => self.show_error(e),
+            },
+            ProcessingResult::TaskCommand(command) => self.apply_task_command(command),
+            ProcessingResult::Error(e) => {
+                self.show_error(e);
+            }
+            ProcessingResult::Done => return true,
+        }
+        false
+    }
+
+    /// Start, restart or stop the calendar feed to match `calendar_export`
+    /// (it waits for a path before starting)
+    fn sync_calendar(&mut self) {
+        let wanted = Some(&self.calendar_export).filter(|config| config.enabled && !config.path.trim().is_empty());
+        if self.calendar_running.as_ref().map(|(config, _)| config) == wanted {
+            return;
+        }
+        let wanted = wanted.cloned();
+        self.calendar_running = None;
+        let Some(config) = wanted else {
+            return;
+        };
+
+        let started = config
+            .validate()
+            .and_then(|()| sync::calendar::CalendarExport::start(&self.db, &config));
+        match started {
+            Ok(running) => self.calendar_running = Some((config, running)),
+            Err(e) => {
+                self.calendar_export.enabled = false;
+                self.show_error(e);
+            }
+        }
+    }
+
+    /// Start, restart or stop the Google Calendar push to match `google_calendar`
+    /// (it waits for Connect). Changes apply once Settings closes.
+    fn sync_google_calendar(&mut self, ctx: &egui::Context) {
+        if self.show_settings {
+            return;
+        }
+        let wanted = Some(&self.google_calendar).filter(|config| config.enabled && !config.refresh_token.is_empty());
+        if self.google_running.as_ref().map(|(config, _)| config) == wanted {
+            return;
+        }
+        let wanted = wanted.cloned();
+        // Dropping the push stops its thread
+        self.google_running = None;
+        self.google_rx = None;
+        let Some(config) = wanted else {
+            return;
+        };
+
+        let (tx, rx) = mpsc::channel();
+        let repaint_ctx = ctx.clone();
+        let on_error = Box::new(move |message: String| {
+            let _ = tx.send(message);
+            repaint_ctx.request_repaint();
+        });
+        match sync::google_calendar::GoogleCalendarSync::start(&self.db, &config, on_error) {
+            Ok(running) => {
+                self.google_running = Some((config, running));
+                self.google_rx = Some(rx);
+            }
+            Err(e) => {
+                self.google_calendar.enabled = false;
+                self.show_error(e);
+            }
+        }
+    }
+
+    fn poll_google_calendar(&mut self) {
+        let errors: Vec<_> = self.google_rx.as_ref().map(|rx| rx.try_iter().collect()).unwrap_or_default();
+        for error in errors {
+            self.show_error(error);
+        }
+        let Some(result) = self.google_connect_rx.as_ref().and_then(|rx| rx.try_recv().ok()) else {
+            return;
+        };
+        self.google_connect_rx = None;
+        match result {
+            Ok(refresh_token) => {
+                self.google_calendar.refresh_token = refresh_token;
+                self.save_settings();
+            }
+            Err(e) => self.show_error(e),
+        }
+    }
+
+    /// Sign in to Google in the browser on a thread of its own; the refresh
+    /// token arrives in `poll_google_calendar`
+    fn connect_google_calendar(&mut self, ctx: &egui::Context) {
+        let (tx, rx) = mpsc::channel();
+        self.google_connect_rx = Some(rx);
+        let (client_id, client_secret) = (self.google_calendar.client_id.clone(), self.google_calendar.client_secret.clone());
+        let ctx = ctx.clone();
+        thread::spawn(move || {
+            let rt = tokio::runtime::Runtime::new().unwrap();
+            let result = rt.block_on(sync::google_calendar::connect(&client_id, &client_secret, |url| {
+                ctx.open_url(egui::OpenUrl::new_tab(url));
+                ctx.request_repaint();
+                Ok(())
+            }));
+            let _ = tx.send(result);
+            ctx.request_repaint();
+        });
+    }
+
+    /// Start, restart or stop the Todoist sync to match `todoist`. Changes apply
+    /// once Settings closes, so each edit doesn't trigger a sync run.
+    fn sync_todoist(&mut self, ctx: &egui::Context) {
+        if self.show_settings {
+            return;
+        }
+        let wanted = Some(&self.todoist).filter(|config| config.enabled);
+        if self.todoist_running.as_ref().map(|(config, _)| config) == wanted {
+            return;
+        }
+        let wanted = wanted.cloned();
+        // Dropping the sync stops its thread
+        self.todoist_running = None;
+        self.todoist_rx = None;
+        let Some(config) = wanted else {
+            return;
+        };
+
+        let (tx, rx) = mpsc::channel();
+        let repaint_ctx = ctx.clone();
+        let on_event = Box::new(move |event: sync::todoist::SyncEvent| {
+            let _ = tx.send(event);
+            repaint_ctx.request_repaint();
+        });
+        match sync::todoist::TodoistSync::start(&self.db, &config, on_event) {
+            Ok(running) => {
+                self.todoist_running = Some((config, running));
+                self.todoist_rx = Some(rx);
+            }
+            Err(e) => {
+                self.todoist.enabled = false;
+                self.show_error(e);
+            }
+        }
+    }
+
+    fn poll_todoist(&mut self) {
+        let events: Vec<_> = self.todoist_rx.as_ref().map(|rx| rx.try_iter().collect()).unwrap_or_default();
+        let mut reload = false;
+        for event in events {
+            match event {
+                sync::todoist::SyncEvent::TaskChanged(_) => reload = true,
+                sync::todoist::SyncEvent::Failed(e) => self.show_error(e),
+            }
+        }
+        if reload {
+            self.reload_tasks();
+        }
+    }
+
+    /// Connect, reconnect or disconnect MQTT to match `mqtt`, once Settings closes
+    fn sync_mqtt(&mut self) {
+        if self.show_settings {
+            return;
+        }
+        let wanted = Some(&self.mqtt).filter(|config| config.enabled);
+        if self.mqtt_running.as_ref().map(|(config, _)| config) == wanted {
+            return;
+        }
+        let wanted = wanted.cloned();
+        // Dropping the publisher disconnects it
+        self.mqtt_running = None;
+        let Some(config) = wanted else {
+            return;
+        };
+        match mqtt::MqttPublisher::start(&config) {
+            Ok(publisher) => self.mqtt_running = Some((config, publisher)),
+            Err(e) => {
+                self.mqtt.enabled = false;
+                self.show_error(e);
+            }
+        }
+    }
+
+    /// Publish timer, recording and task state, at most once a second
+    fn publish_mqtt(&mut self) {
+        let Some((_, publisher)) = &self.mqtt_running else {
+            return;
+        };
+        if self.mqtt_published_at.elapsed() < power::throttled(Duration::from_secs(1), self.power_saving) {
+            return;
+        }
+        self.mqtt_published_at = Instant::now();
+        let (timer, remaining) = match self.break_ends_at {
+            Some(ends) => (mqtt::TimerState::Break, ends.saturating_duration_since(Instant::now())),
+            None if self.timer_paused_at.is_some() => (mqtt::TimerState::Paused, self.timer_remaining()),
+            None => (mqtt::TimerState::Running, self.timer_remaining()),
+        };
+        publisher.publish(&mqtt::Status {
+            recording: self.is_recording,
+            timer,
+            remaining_secs: remaining.as_secs(),
+            open_tasks: database::count_open_tasks(&self.db).unwrap_or(0),
+        });
+    }
+
+    /// Start, restart or stop the clipboard watcher to match `clipboard`
+    fn sync_clipboard(&mut self, ctx: &egui::Context) {
+        let wanted = Some(&self.clipboard).filter(|config| config.enabled);
+        if self.clipboard_running.as_ref().map(|(config, _)| config) == wanted {
+            return;
+        }
+        let wanted = wanted.cloned();
+        // Dropping the watcher stops its thread
+        self.clipboard_running = None;
+        self.clipboard_rx = None;
+        let Some(config) = wanted else {
+            return;
+        };
+
+        let (tx, rx) = mpsc::channel();
+        let repaint_ctx = ctx.clone();
+        let on_capture = Box::new(move |task: database::Task| {
+            let _ = tx.send(task);
+            repaint_ctx.request_repaint();
+        });
+        match clipboard::ClipboardWatcher::start(&self.db, &config, on_capture) {
+            Ok(running) => {
+                self.clipboard_running = Some((config, running));
+                self.clipboard_rx = Some(rx);
+            }
+            Err(e) => {
+                self.clipboard.enabled = false;
+                self.show_error(e);
+            }
+        }
+    }
+
+    fn poll_clipboard(&mut self) {
+        let captured: Vec<_> = self.clipboard_rx.as_ref().map(|rx| rx.try_iter().collect()).unwrap_or_default();
+        if captured.is_empty() {
+            return;
+        }
+        self.push_undo(UndoAction::Added(captured.iter().map(|task| task.id).collect()));
+        for task in captured {
+            self.show_info(i18n::tf("clipboard.added", &[("task", &task.text)]));
+        }
+        earcon::play(earcon::Earcon::Success);
+        self.reload_tasks();
+    }
+
+    /// Start, restart or stop the Telegram bridge to match `telegram`. Like the
+    /// Todoist sync, changes apply once Settings closes.
+    fn sync_telegram(&mut self, ctx: &egui::Context) {
+        if self.show_settings {
+            return;
+        }
+        let wanted = Some(&self.telegram).filter(|config| config.enabled);
+        if self.telegram_running.as_ref().map(|(config, _)| config) == wanted {
+            return;
+        }
+        let wanted = wanted.cloned();
+        // Dropping the bridge stops its thread
+        self.telegram_running = None;
+        self.telegram_rx = None;
+        let Some(config) = wanted else {
+            return;
+        };
+
+        let (tx, rx) = mpsc::channel();
+        let repaint_ctx = ctx.clone();
+        let on_message = Box::new(move |message: telegram::Incoming| {
+            let _ = tx.send(message);
+            repaint_ctx.request_repaint();
+        });
+        match telegram::TelegramBridge::start(&config, on_message) {
+            Ok(running) => {
+                self.telegram_running = Some((config, running));
+                self.telegram_rx = Some(rx);
+            }
+            Err(e) => {
+                self.telegram.enabled = false;
+                self.show_error(e);
+            }
+        }
+    }
+
+    /// Transcribe and parse Telegram messages in the background, then apply
+    /// their results like a recording's
+    fn poll_telegram(&mut self, ctx: &egui::Context) {
+        let messages: Vec<_> = self.telegram_rx.as_ref().map(|rx| rx.try_iter().collect()).unwrap_or_default();
+        for message in messages {
+            if let telegram::Incoming::Failed(e) = message {
+                self.show_error(e);
+                continue;
+            }
+            let tx = self.remote_tx.clone();
+            let repaint_ctx = ctx.clone();
+            let model = self.transcription_model();
+            let parsers = self.parser_chain();
+            let action_mode = self.action_mode;
+            let github = self.github.enabled.then(|| self.github.clone());
+            let content_filter = self.content_filter.clone();
+            let db = self.db.reopen();
+            thread::spawn(move || {
+                let transcript = match message {
+                    telegram::Incoming::Voice(samples) => match timed_transcription(&samples, &model, None) {
+                        Ok((transcript, elapsed)) => {
+                            let transcript = content_filter::filter(&transcript, &content_filter);
+                            if let Ok(db) = &db {
+                                record_transcription(db, "telegram", &transcript);
+                                metrics::record_transcription(db, "telegram", audio_length(&samples), elapsed);
+                            }
+                            transcript
+                        }
+                        Err(e) => {
+                            let _ = tx.send(ProcessingResult::Error(i18n::tf("error.transcription", &[("error", &e)])));
+                            repaint_ctx.request_repaint();
+                            return;
+                        }
+                    },
+                    telegram::Incoming::Text(text) => text,
+                    telegram::Incoming::Failed(_) => return,
+                };
+                if !transcript.trim().is_empty() {
+                    parse_transcript_into(&tx, &transcript, &parsers, github.as_ref(), db.as_ref().ok(), action_mode);
+                }
+                repaint_ctx.request_repaint();
+            });
+        }
+
+        let results: Vec<_> = self.remote_rx.try_iter().collect();
+        for result in results {
+            self.apply_processing_result(ctx, result);
+        }
+    }
+
+    /// Start or stop the wake-word listener to match `wake_word_enabled`
+    fn sync_wake_word(&mut self, ctx: &egui::Context) {
+        if !self.wake_word_enabled {
+            // Dropping the listener stops the capture thread
+            self.wake_word = None;
+            self.wake_word_rx = None;
+            return;
+        }
+        if self.wake_word.is_some() {
+            return;
+        }
+
+        let phrase = settings::load(&self.db).wake_word_phrase;
+
+        // Dedicated cache so the tiny wake-word model doesn't evict the dictation model
+        let wake_cache = whisper::WhisperCache::new();
+        let transcribe = Box::new(move |samples: &[f32]| {
+            let ctx = wake_cache.get_or_create(whisper::WhisperModelSize::Tiny)?;
+            whisper::transcribe_samples_with_context(&ctx, samples, None)
+        });
+
+        let (tx, rx) = mpsc::channel();
+        let repaint_ctx = ctx.clone();
+        let on_event = Box::new(move |event: wakeword::WakeWordEvent| {
+            let _ = tx.send(event);
+            repaint_ctx.request_repaint();
+        });
+
+        match wakeword::WakeWordListener::start(&phrase, transcribe, on_event) {
+            Ok(listener) => {
+                self.wake_word = Some(listener);
+                self.wake_word_rx = Some(rx);
+            }
+            Err(e) => {
+                self.wake_word_enabled = false;
+                self.show_error(i18n::tf("error.hands_free_unavailable", &[("error", &e)]));
+            }
+        }
+    }
+
+    fn start_recording(&mut self) {
+        let host = cpal::default_host();
+
+        tracing::debug!("Starting recording with device index: {}", self.selected_device_idx);
+
+        let device = if self.selected_device_idx == 0 {
+            tracing::debug!("Using default input device");
+            host.default_input_device()
+        } else {
+            tracing::debug!("Using device at index {}", self.selected_device_idx - 1);
+            host.input_devices()
+                .ok()
+                .and_then(|mut devices| devices.nth(self.selected_device_idx - 1))
+        };
+
+        let Some(device) = device else {
+            tracing::error!("No audio device found!");
+            self.show_error(i18n::t("error.no_audio_device"));
+            return;
+        };
+
+        tracing::debug!("Device: {:?}", device.name());
+
+        let supported_config = match device.default_input_config() {
+            Ok(c) => c,
+            Err(e) => {
+                self.show_error(i18n::tf("error.audio_config", &[("error", &e)]));
+                return;
+            }
+        };
+
+        let buffer = self.audio_buffer.clone();
+        let audio_level = self.audio_level.clone();
+        let sample_rate = supported_config.sample_rate().0;
+        let channels = supported_config.channels() as usize;
+        let sample_format = supported_config.sample_format();
+
+        // Store sample rate for resampling later
+        self.input_sample_rate = sample_rate;
+        tracing::debug!("Recording at {} Hz, {} channels, format: {:?}", sample_rate, channels, sample_format);
 
         // Clear buffer and reset level
         buffer.lock().unwrap().clear();
@@ -357,246 +2506,917 @@ impl FlowStateApp {
                 device.build_input_stream(
                     &config,
                     move |data: &[i16], _: &cpal::InputCallbackInfo| {
-                        // Convert i16 to f32 and then to mono
-                        let mono: Vec<f32> = data
-                            .chunks(channels)
-                            .map(|chunk| {
-                                let sum: f32 = chunk.iter().map(|&s| s as f32 / 32768.0).sum();
-                                sum / channels as f32
-                            })
-                            .collect();
-
-                        // Calculate RMS level for visualization
-                        if !mono.is_empty() {
-                            let sum_squares: f32 = mono.iter().map(|s| s * s).sum();
-                            let rms = (sum_squares / mono.len() as f32).sqrt();
-                            let level = (rms * 4.0).min(1.0);
-                            if let Ok(mut lvl) = audio_level.lock() {
-                                *lvl = if level > *lvl { level } else { *lvl * 0.9 + level * 0.1 };
+                        // Convert i16 to f32 and then to mono, straight into the buffer
+                        let mut buf = buffer.lock().unwrap();
+                        let start = buf.len();
+                        buf.extend(data.chunks(channels).map(|chunk| {
+                            let sum: f32 = chunk.iter().map(|&s| s as f32 / 32768.0).sum();
+                            sum / channels as f32
+                        }));
+                        update_level(&audio_level, &buf[start..]);
+                    },
+                    |err| tracing::error!("Audio error: {}", err),
+                    None,
+                )
+            }
+            cpal::SampleFormat::F32 => {
+                let buffer = buffer.clone();
+                let audio_level = audio_level.clone();
+                device.build_input_stream(
+                    &config,
+                    move |data: &[f32], _: &cpal::InputCallbackInfo| {
+                        // Convert to mono, straight into the buffer
+                        let mut buf = buffer.lock().unwrap();
+                        let start = buf.len();
+                        buf.extend(data.chunks(channels).map(|chunk| chunk.iter().sum::<f32>() / channels as f32));
+                        update_level(&audio_level, &buf[start..]);
+                    },
+                    |err| tracing::error!("Audio error: {}", err),
+                    None,
+                )
+            }
+            _ => {
+                self.show_error(i18n::tf("error.sample_format", &[("format", &format!("{:?}", sample_format))]));
+                return;
+            }
+        };
+
+        let stream = match stream {
+            Ok(s) => s,
+            Err(e) => {
+                self.show_error(i18n::tf("error.start_recording", &[("error", &e)]));
+                return;
+            }
+        };
+
+        if let Err(e) = stream.play() {
+            self.show_error(i18n::tf("error.play_stream", &[("error", &e)]));
+            return;
+        }
+
+        self.audio_stream = Some(stream);
+        self.is_recording = true;
+        self.recording_start = Some(Instant::now());
+        earcon::play(earcon::Earcon::RecordStart);
+    }
+
+    fn stop_recording(&mut self) {
+        let journal = self.journal_interval.take();
+        self.hotkey_recording = false;
+        self.is_recording = false;
+        self.audio_stream = None;
+        self.recording_start = None;
+        earcon::play(earcon::Earcon::RecordStop);
+
+        // Reset audio level
+        *self.audio_level.lock().unwrap() = 0.0;
+
+        // Take the captured audio, leaving an empty buffer for the next recording
+        let audio_data = std::mem::take(&mut *self.audio_buffer.lock().unwrap());
+
+        // Analyze the captured audio
+        let stats = audio::analyze_samples(&audio_data, self.input_sample_rate);
+
+        tracing::info!(
+            samples = audio_data.len(),
+            duration_secs = stats.duration_secs,
+            min = stats.min,
+            max = stats.max,
+            rms = stats.rms,
+            "Stopped recording"
+        );
+
+        // An unanswered journal prompt is not an error - just nothing to log
+        if journal.is_some() && stats.is_silent() {
+            return;
+        }
+
+        if audio_data.is_empty() {
+            self.show_warning(i18n::t("error.no_audio"));
+            return;
+        }
+
+        // Check minimum duration
+        if stats.duration_secs < audio::MIN_RECORDING_SECS {
+            self.show_warning(i18n::tf("error.recording_too_short", &[("seconds", &format!("{:.1}", stats.duration_secs))]));
+            return;
+        }
+
+        // Check if audio has enough volume
+        if stats.peak() < audio::MIN_PEAK {
+            self.show_warning(i18n::tf("error.audio_too_quiet", &[("peak", &format!("{:.4}", stats.peak()))]));
+            return;
+        }
+
+        self.is_processing = true;
+        self.status_message = Some(i18n::t("status.loading_model"));
+
+        let model = self.transcription_model();
+        // The one-click override wins over the profile's language, which wins
+        // over the setting
+        let language = self
+            .recording_language
+            .clone()
+            .or_else(|| Some(self.profiles.language().unwrap_or(&self.transcription_language).to_string()))
+            .filter(|language| !language.eq_ignore_ascii_case("auto"));
+        let parsers = self.parser_chain();
+        let action_mode = self.action_mode;
+        let github = self.github.enabled.then(|| self.github.clone());
+        let input_rate = self.input_sample_rate;
+        let speaker_gate = settings::load(&self.db).speaker_gate;
+        let content_filter = self.content_filter.clone();
+        let db = self.db.reopen();
+        // Hands-free and compact mode have no room for a correction step
+        let review = !self.hands_free_recording && !self.compact;
+
+        // Create channel for results
+        let (tx, rx) = mpsc::channel();
+        self.processing_rx = Some(rx);
+
+        // Process in background thread; a panic is reported like any other error
+        let panic_tx = tx.clone();
+        thread::spawn(move || {
+            let work = move || {
+                // Downsample to 16kHz (same resampler as the Tauri build)
+                tracing::debug!("Resampling from {} Hz to 16000 Hz ({} samples)", input_rate, audio_data.len());
+                let resampled = whisper::into_16k(audio_data, input_rate);
+                // Other voices are cut before Whisper hears them
+                let resampled = speaker::gate(resampled, &speaker_gate);
+
+                tracing::debug!("Resampled to {} samples", resampled.len());
+
+                // Transcribe
+                match timed_transcription(&resampled, &model, language.as_deref()) {
+                    Ok((transcript, elapsed)) => {
+                        // Filtered words never reach the parser or the journal
+                        let transcript = content_filter::filter(&transcript, &content_filter);
+                        tracing::debug!("Transcript: '{}'", transcript);
+                        if let Ok(db) = &db {
+                            let source = if journal.is_some() { "journal" } else { "recording" };
+                            metrics::record_transcription(db, source, audio_length(&resampled), elapsed);
+                        }
+
+                        if let Some((interval_start, interval_end)) = journal {
+                            // Journal answers are stored verbatim, not parsed into tasks
+                            if !transcript.trim().is_empty() {
+                                let _ = tx.send(ProcessingResult::Journal(transcript.trim().to_string(), interval_start, interval_end));
                             }
+                            let _ = tx.send(ProcessingResult::Done);
+                            return;
+                        }
+
+                        if transcript.trim().is_empty() {
+                            let _ = tx.send(ProcessingResult::Error(
+                                i18n::t("error.no_speech")
+                            ));
+                            let _ = tx.send(ProcessingResult::Done);
+                            return;
                         }
 
-                        let mut buf = buffer.lock().unwrap();
-                        buf.extend(mono);
-                    },
-                    |err| eprintln!("Audio error: {}", err),
-                    None,
-                )
-            }
-            cpal::SampleFormat::F32 => {
-                let buffer = buffer.clone();
-                let audio_level = audio_level.clone();
-                device.build_input_stream(
-                    &config,
-                    move |data: &[f32], _: &cpal::InputCallbackInfo| {
-                        // Convert to mono
-                        let mono: Vec<f32> = data
-                            .chunks(channels)
-                            .map(|chunk| chunk.iter().sum::<f32>() / channels as f32)
-                            .collect();
-
-                        // Calculate RMS level for visualization
-                        if !mono.is_empty() {
-                            let sum_squares: f32 = mono.iter().map(|s| s * s).sum();
-                            let rms = (sum_squares / mono.len() as f32).sqrt();
-                            let level = (rms * 4.0).min(1.0);
-                            if let Ok(mut lvl) = audio_level.lock() {
-                                *lvl = if level > *lvl { level } else { *lvl * 0.9 + level * 0.1 };
-                            }
-                        }
+                        if review {
+                            let _ = tx.send(ProcessingResult::Review(transcript));
+                        } else {
+                            let _ = tx.send(ProcessingResult::Transcript(transcript.clone()));
+                            parse_transcript_into(&tx, &transcript, &parsers, github.as_ref(), db.as_ref().ok(), action_mode);
+                        }
+                    }
+                    Err(e) => {
+                        let _ = tx.send(ProcessingResult::Error(i18n::tf("error.transcription", &[("error", &e)])));
+                    }
+                }
+                let _ = tx.send(ProcessingResult::Done);
+            };
+            if let Err(e) = crash::catch_panic(|| {
+                work();
+                Ok(())
+            }) {
+                let _ = panic_tx.send(ProcessingResult::Error(e));
+                let _ = panic_tx.send(ProcessingResult::Done);
+            }
+        });
+    }
+
+    /// Parse a reviewed transcript into tasks in the background
+    fn apply_transcript(&mut self, transcript: String) {
+        self.pending_transcript = None;
+        if transcript.trim().is_empty() {
+            return;
+        }
+        self.is_processing = true;
+        self.status_message = Some(i18n::tf("status.transcribed", &[("transcript", &transcript)]));
+
+        let parsers = self.parser_chain();
+        let action_mode = self.action_mode;
+        let github = self.github.enabled.then(|| self.github.clone());
+        let db = self.db.reopen();
+        let (tx, rx) = mpsc::channel();
+        self.processing_rx = Some(rx);
+        thread::spawn(move || {
+            parse_transcript_into(&tx, &transcript, &parsers, github.as_ref(), db.as_ref().ok(), action_mode);
+            let _ = tx.send(ProcessingResult::Done);
+        });
+    }
+
+    fn timer_remaining(&self) -> Duration {
+        // While paused, time stops at the moment of pausing
+        let elapsed = self.timer_paused_at
+            .unwrap_or_else(Instant::now)
+            .saturating_duration_since(self.timer_start);
+        if elapsed >= self.timer_duration {
+            Duration::ZERO
+        } else {
+            self.timer_duration - elapsed
+        }
+    }
+
+    /// Alert the user that the interval is over; false when do-not-disturb suppressed it
+    fn trigger_alert(&mut self, ctx: &egui::Context) -> bool {
+        let dnd = dnd::get_dnd_state(&self.dnd);
+        if dnd.is_active() {
+            tracing::info!("Timer alert suppressed ({:?})", dnd);
+            return false;
+        }
 
-                        let mut buf = buffer.lock().unwrap();
-                        buf.extend(mono);
-                    },
-                    |err| eprintln!("Audio error: {}", err),
-                    None,
-                )
+        earcon::play_alert_sound(&self.alert_sound);
+        if self.speak_tasks_on_alert && !self.journal_prompt_enabled {
+            if let Err(e) = speech::speak_tasks(&self.db) {
+                tracing::warn!("Failed to read tasks aloud: {}", e);
             }
-            _ => {
-                self.error_message = Some(format!("Unsupported sample format: {:?}", sample_format));
-                self.error_time = Some(Instant::now());
-                return;
+        }
+
+        if self.alert_mode.focuses() {
+            show_window(ctx);
+        }
+
+        if self.alert_mode.notifies() {
+            let body = match (&self.focus_task, self.tasks.iter().find(|t| !t.completed)) {
+                (Some((_, text, minutes, _)), _) => i18n::tf("alert.focus_complete", &[("task", text), ("minutes", minutes)]),
+                (None, Some(task)) => i18n::tf("alert.current_task", &[("task", &task.text)]),
+                (None, None) => i18n::t("alert.awareness_check"),
+            };
+            if let Err(e) = notify_rust::Notification::new()
+                .summary("FlowState")
+                .body(&body)
+                .show()
+            {
+                tracing::warn!("Failed to show notification: {}", e);
             }
-        };
+        }
+    }
 
-        let stream = match stream {
-            Ok(s) => s,
-            Err(e) => {
-                self.error_message = Some(format!("Failed to start recording: {}", e));
-                self.error_time = Some(Instant::now());
-                return;
+    /// Follow the power source: throttle background work on battery and load
+    /// the Whisper model once back on mains if that was put off. The same
+    /// once-a-minute check unloads the model after it has sat idle.
+    fn check_power(&mut self) {
+        if self.last_power_check.elapsed() < POWER_CHECK_INTERVAL {
+            return;
+        }
+        self.last_power_check = Instant::now();
+        self.set_power_saving(power::should_save(self.power_saving_mode));
+        if self.model_unload_minutes > 0 {
+            whisper::unload_native_if_idle(Duration::from_secs(self.model_unload_minutes * 60));
+        }
+    }
+
+    fn set_power_saving(&mut self, saving: bool) {
+        if saving != self.power_saving {
+            tracing::info!("Power saving {}", if saving { "on" } else { "off" });
+            self.power_saving = saving;
+        }
+        self.preload_model();
+    }
+
+    /// Clean up after a previous run and check the model file (`preload_model`
+    /// loads it), then show whatever went wrong at startup
+    fn show_startup_report(&mut self, database_check: diagnostics::DiagnosticCheck) {
+        let store = model_store::ModelStore::open_or_temp();
+        let checks = vec![
+            database_check,
+            startup::clean_temp_files(&store, None),
+            startup::check_model_file(&store, &self.selected_model),
+        ];
+        let report = startup::report(checks);
+        for check in report.checks {
+            match check.status {
+                diagnostics::CheckStatus::Ok => {}
+                diagnostics::CheckStatus::Warning => self.show_warning(format!("{}: {}", check.label, check.detail)),
+                diagnostics::CheckStatus::Error => self.show_error(format!("{}: {}", check.label, check.detail)),
             }
-        };
+        }
+        self.refresh_models();
+    }
 
-        if let Err(e) = stream.play() {
-            self.error_message = Some(format!("Failed to play stream: {}", e));
-            self.error_time = Some(Instant::now());
+    /// Load the selected model in the background so the first recording doesn't
+    /// wait for it; put off while saving power
+    fn preload_model(&mut self) {
+        if self.model_preloaded || self.power_saving {
+            return;
+        }
+        self.model_preloaded = true;
+        let model = self.selected_model.clone();
+        if !self.available_models.iter().any(|(name, installed)| *installed && *name == model) {
             return;
         }
+        thread::spawn(move || {
+            if let Err(e) = whisper::preload_native(&model) {
+                tracing::warn!("Failed to preload Whisper model: {}", e);
+            }
+        });
+    }
 
-        self.audio_stream = Some(stream);
-        self.is_recording = true;
-        self.recording_start = Some(Instant::now());
+    /// The saved parser chain, with the Ollama server and profile from the panel
+    fn parser_chain(&self) -> parser::ParserChain {
+        parser::ParserChain::from_settings(&settings::Settings {
+            ollama_enabled: self.ollama_enabled,
+            ollama: self.ollama.clone(),
+            punctuation: self.punctuation.clone(),
+            profiles: self.profiles.clone(),
+            ..settings::load(&self.db)
+        })
     }
 
-    fn stop_recording(&mut self) {
-        self.is_recording = false;
-        self.audio_stream = None;
-        self.recording_start = None;
+    /// The model to transcribe with: the tiny one while saving power
+    fn transcription_model(&self) -> String {
+        let tiny_installed = self.available_models.iter().any(|(name, installed)| *installed && name == power::SAVING_MODEL);
+        power::transcription_model(&self.selected_model, self.power_saving, tiny_installed).to_string()
+    }
 
-        // Reset audio level
-        *self.audio_level.lock().unwrap() = 0.0;
+    /// Pause the timer while the user is away or outside working hours, resume afterwards
+    fn check_presence(&mut self) {
+        if self.last_presence_check.elapsed() < power::throttled(Duration::from_secs(10), self.power_saving) {
+            return;
+        }
+        self.last_presence_check = Instant::now();
+
+        let threshold = Duration::from_secs(self.idle_threshold_minutes * 60);
+        let away = self.auto_pause_enabled && presence::is_user_away(threshold);
+        // An explicit focus session still runs outside working hours
+        let schedule = self.profiles.timer_schedule().unwrap_or(&self.schedule);
+        let off_schedule = self.focus_task.is_none() && !schedule.is_active_now();
+        self.restart_on_resume |= off_schedule;
+
+        match (away || off_schedule, self.timer_paused_at) {
+            (true, None) => {
+                if off_schedule {
+                    tracing::info!("Outside working hours - pausing awareness timer");
+                } else {
+                    tracing::info!("User away - pausing awareness timer");
+                }
+                self.timer_paused_at = Some(Instant::now());
+            }
+            (false, Some(paused_at)) if !self.paused_by_user => {
+                tracing::info!("Resuming awareness timer");
+                self.timer_paused_at = None;
+                if self.restart_on_resume {
+                    self.restart_on_resume = false;
+                    self.reset_timer();
+                } else {
+                    self.timer_start += paused_at.elapsed();
+                }
+            }
+            _ => {}
+        }
+    }
 
-        // Get audio data
-        let audio_data: Vec<f32> = {
-            let buf = self.audio_buffer.lock().unwrap();
-            buf.clone()
+    fn reset_timer(&mut self) {
+        if self.timer_paused_at.is_some() {
+            self.timer_paused_at = Some(Instant::now());
+        }
+        self.timer_start = Instant::now();
+        self.interval_started_at = now_timestamp();
+        self.timer_duration = match self.focus_task {
+            Some((_, _, minutes, _)) => Duration::from_secs(minutes * 60),
+            None if self.pomodoro => Duration::from_secs(POMODORO_MINUTES * 60),
+            None => Duration::from_secs(self.timer_duration_mins as u64 * 60),
         };
+    }
 
-        // Analyze the captured audio
-        let duration_secs = audio_data.len() as f32 / self.input_sample_rate as f32;
-        let (min_val, max_val, rms) = if audio_data.is_empty() {
-            (0.0, 0.0, 0.0)
-        } else {
-            let min = audio_data.iter().cloned().fold(f32::INFINITY, f32::min);
-            let max = audio_data.iter().cloned().fold(f32::NEG_INFINITY, f32::max);
-            let sum_sq: f32 = audio_data.iter().map(|s| s * s).sum();
-            let rms = (sum_sq / audio_data.len() as f32).sqrt();
-            (min, max, rms)
-        };
+    /// Whether the countdown fires at all (an awareness interval of 0 turns it off)
+    fn timer_running(&self) -> bool {
+        self.timer_duration_mins > 0 || self.focus_task.is_some() || self.pomodoro
+    }
 
-        eprintln!("🛑 Stopped recording:");
-        eprintln!("   Samples: {}", audio_data.len());
-        eprintln!("   Duration: {:.2}s", duration_secs);
-        eprintln!("   Min: {:.4}, Max: {:.4}, RMS: {:.4}", min_val, max_val, rms);
+    /// Pause button: hold the countdown until pressed again (presence checks leave it alone)
+    fn toggle_pause(&mut self) {
+        if self.paused_by_user {
+            self.paused_by_user = false;
+            if let Some(paused_at) = self.timer_paused_at.take() {
+                self.timer_start += paused_at.elapsed();
+            }
+        } else {
+            self.paused_by_user = true;
+            self.timer_paused_at.get_or_insert_with(Instant::now);
+        }
+    }
 
-        if audio_data.is_empty() {
-            self.error_message = Some("No audio recorded".to_string());
-            self.error_time = Some(Instant::now());
-            return;
+    fn open_settings(&mut self) {
+        self.show_settings = true;
+        // Fill the Ollama model picker
+        if self.ollama_enabled && self.ollama_models.is_empty() {
+            self.test_ollama();
         }
+        self.focus_history = database::get_focus_history(&self.db, 7).unwrap_or_default();
+    }
 
-        // Check minimum duration (at least 0.3 seconds)
-        if duration_secs < 0.3 {
-            self.error_message = Some(format!("Recording too short ({:.1}s). Hold longer.", duration_secs));
-            self.error_time = Some(Instant::now());
-            return;
+    fn close_settings(&mut self) {
+        self.save_settings();
+        self.show_settings = false;
+        // The secret of a token just issued isn't shown again
+        self.issued_secret = None;
+    }
+
+    /// Carry out a spoken navigation command, as the matching click would
+    fn apply_app_command(&mut self, command: navigation::AppCommand) {
+        use navigation::AppCommand;
+        tracing::info!("App command: {:?}", command);
+        match command {
+            AppCommand::OpenSettings => self.open_settings(),
+            AppCommand::CloseSettings if self.show_settings => self.close_settings(),
+            AppCommand::CloseSettings => {}
+            AppCommand::ShowTasks { status } => self.task_filter = status,
+            AppCommand::ShowProject { project } => self.task_project = project,
+            AppCommand::ShowContext { context } => self.task_context = context,
+            AppCommand::ClearFilters => {
+                self.task_filter = database::TaskStatus::Recent;
+                self.task_project = None;
+                self.task_context = None;
+                self.task_search.clear();
+            }
         }
+        self.reload_tasks();
+    }
 
-        // Check if audio has enough volume (use max amplitude, more reliable than RMS)
-        let max_amplitude = max_val.abs().max(min_val.abs());
-        if max_amplitude < 0.01 {
-            self.error_message = Some(format!("Audio too quiet (peak: {:.4}). Check mic volume.", max_amplitude));
-            self.error_time = Some(Instant::now());
-            return;
+    /// Carry out a spoken timer command, as the timer bar buttons and the duration slider would
+    fn apply_timer_command(&mut self, command: timer_control::TimerCommand) {
+        use timer_control::TimerCommand;
+        tracing::info!("Timer command: {:?}", command);
+        match command {
+            TimerCommand::Reset => self.reset_timer(),
+            TimerCommand::Pause if !self.paused_by_user => self.toggle_pause(),
+            TimerCommand::Resume if self.paused_by_user => self.toggle_pause(),
+            TimerCommand::Pause | TimerCommand::Resume => {}
+            TimerCommand::SetMinutes(minutes) => {
+                if minutes > 60 {
+                    self.show_error(i18n::t("error.timer_duration"));
+                    return;
+                }
+                self.timer_duration_mins = minutes as u32;
+                self.reset_timer();
+                self.save_settings();
+            }
         }
+        earcon::play(earcon::Earcon::Success);
+        self.show_info(timer_control::confirmation(command));
+    }
 
-        self.is_processing = true;
-        self.status_message = Some("Loading model...".to_string());
+    /// Give the running countdown a few more minutes (the next interval is back to normal)
+    fn extend_timer(&mut self, minutes: u64) {
+        self.timer_duration += Duration::from_secs(minutes * 60);
+    }
 
-        let model = self.selected_model.clone();
-        let ollama_enabled = self.ollama_enabled;
-        let input_rate = self.input_sample_rate;
+    fn toggle_pomodoro(&mut self) {
+        self.pomodoro = !self.pomodoro;
+        tracing::info!("Pomodoro {}", if self.pomodoro { "started" } else { "stopped" });
+        self.reset_timer();
+    }
 
-        // Create channel for results
-        let (tx, rx) = mpsc::channel();
-        self.processing_rx = Some(rx);
+    /// Hold the timer and show the full-screen break viewport
+    fn start_break(&mut self, minutes: u64) {
+        tracing::info!("Break started ({} min)", minutes);
+        self.break_ends_at = Some(Instant::now() + Duration::from_secs(minutes * 60));
+        self.timer_paused_at = Some(Instant::now());
+    }
 
-        // Process in background thread
-        thread::spawn(move || {
-            // Downsample to 16kHz
-            let input_rate = input_rate as f32;
-            let output_rate = 16000.0;
-            eprintln!("🔄 Resampling from {} Hz to {} Hz ({} samples)", input_rate, output_rate, audio_data.len());
+    /// Break over or skipped - start a fresh interval
+    fn end_break(&mut self) {
+        if self.break_ends_at.take().is_some() {
+            tracing::info!("Break finished");
+            self.timer_paused_at = None;
+            self.paused_by_user = false;
+            self.reset_timer();
+        }
+    }
 
-            let resampled = if (input_rate - output_rate).abs() < 1.0 {
-                audio_data
-            } else {
-                let ratio = input_rate / output_rate;
-                let new_len = (audio_data.len() as f32 / ratio) as usize;
-                let mut resampled = Vec::with_capacity(new_len);
-                for i in 0..new_len {
-                    let src_idx = i as f32 * ratio;
-                    let idx = src_idx as usize;
-                    let frac = src_idx - idx as f32;
-                    let sample = if idx + 1 < audio_data.len() {
-                        audio_data[idx] * (1.0 - frac) + audio_data[idx + 1] * frac
-                    } else if idx < audio_data.len() {
-                        audio_data[idx]
-                    } else {
-                        0.0
-                    };
-                    resampled.push(sample);
+    /// Render the quick-add box; returns true once it should close
+    /// Shrink to the always-on-top strip, or go back to the saved full window
+    fn set_compact(&mut self, ctx: &egui::Context, compact: bool) {
+        self.compact = compact;
+        if compact {
+            let (width, height) = settings::WindowSettings::COMPACT_SIZE;
+            ctx.send_viewport_cmd(egui::ViewportCommand::MinInnerSize(egui::Vec2::ZERO));
+            ctx.send_viewport_cmd(egui::ViewportCommand::InnerSize(egui::vec2(width as f32, height as f32)));
+            ctx.send_viewport_cmd(egui::ViewportCommand::Resizable(false));
+            ctx.send_viewport_cmd(egui::ViewportCommand::WindowLevel(egui::WindowLevel::AlwaysOnTop));
+            if let Some(geometry) = self.compact_geometry.filter(|g| g.is_plausible()) {
+                ctx.send_viewport_cmd(egui::ViewportCommand::OuterPosition(egui::pos2(geometry.x as f32, geometry.y as f32)));
+            }
+        } else {
+            ctx.send_viewport_cmd(egui::ViewportCommand::Resizable(true));
+            ctx.send_viewport_cmd(egui::ViewportCommand::MinInnerSize(egui::vec2(280.0, 400.0)));
+            match self.window_geometry.filter(|g| g.is_plausible()) {
+                Some(geometry) => {
+                    ctx.send_viewport_cmd(egui::ViewportCommand::OuterPosition(egui::pos2(geometry.x as f32, geometry.y as f32)));
+                    ctx.send_viewport_cmd(egui::ViewportCommand::InnerSize(egui::vec2(geometry.width as f32, geometry.height as f32)));
                 }
-                resampled
+                None => ctx.send_viewport_cmd(egui::ViewportCommand::InnerSize(egui::vec2(320.0, 480.0))),
+            }
+            let level = if self.always_on_top {
+                egui::WindowLevel::AlwaysOnTop
+            } else {
+                egui::WindowLevel::Normal
             };
+            ctx.send_viewport_cmd(egui::ViewportCommand::WindowLevel(level));
+        }
+        self.save_settings();
+    }
 
-            eprintln!("📊 Resampled to {} samples", resampled.len());
+    /// Compact strip under the timer bar: record button, top task, expand button
+    fn show_compact(&mut self, ctx: &egui::Context) {
+        let mut expand = false;
+        egui::CentralPanel::default().show(ctx, |ui| {
+            ui.horizontal_centered(|ui| {
+                let icon = if self.is_processing {
+                    "…"
+                } else if self.is_recording {
+                    "⏹"
+                } else {
+                    "⏺"
+                };
+                let button = egui::Button::new(egui::RichText::new(icon).size(20.0))
+                    .min_size(egui::vec2(36.0, 36.0))
+                    .rounding(18.0);
+                let button = if self.is_recording {
+                    button.fill(egui::Color32::from_rgb(239, 68, 68))
+                } else {
+                    button
+                };
+                let response = ui.add_enabled(!self.is_processing, button);
 
-            // Transcribe
-            match whisper::transcribe_audio(&resampled, &model) {
-                Ok(transcript) => {
-                    eprintln!("📝 Transcript: '{}'", transcript);
+                // Same hold-to-record behaviour as the full-size button
+                let mouse_down = ctx.input(|i| i.pointer.primary_down());
+                if response.is_pointer_button_down_on() && !self.is_recording && !self.is_processing {
+                    self.start_recording();
+                }
+                if self.is_recording && !mouse_down && !self.hands_free_recording && !self.hotkey_recording && self.journal_interval.is_none() {
+                    self.stop_recording();
+                }
 
-                    if transcript.trim().is_empty() {
-                        let _ = tx.send(ProcessingResult::Error(
-                            "No speech detected. Try speaking louder or closer to the mic.".to_string()
-                        ));
-                        let _ = tx.send(ProcessingResult::Done);
-                        return;
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    if ui.button("🗖").on_hover_text(i18n::t("compact.expand")).clicked() {
+                        expand = true;
                     }
+                    // Focused task first, otherwise the newest open one
+                    let top_task = self
+                        .focus_task
+                        .as_ref()
+                        .map(|(_, text, _, _)| text.clone())
+                        .or_else(|| self.tasks.iter().find(|t| !t.completed).map(|t| t.text.clone()));
+                    let text = if self.is_processing {
+                        i18n::t("recording.processing")
+                    } else {
+                        top_task.unwrap_or_else(|| i18n::t("compact.no_tasks"))
+                    };
+                    ui.add(egui::Label::new(text).truncate());
+                });
+            });
+        });
+        if expand {
+            self.set_compact(ctx, false);
+        }
+    }
 
-                    let _ = tx.send(ProcessingResult::Transcript(transcript.clone()));
+    fn show_quick_add(&mut self, ctx: &egui::Context) -> bool {
+        let Some(mut text) = self.quick_add.take() else {
+            return false;
+        };
+        let mut close = false;
+        let mut submit = false;
+        ctx.show_viewport_immediate(
+            egui::ViewportId::from_hash_of("quick_add"),
+            egui::ViewportBuilder::default()
+                .with_title(i18n::t("quick_add.title"))
+                .with_inner_size([380.0, 56.0])
+                .with_resizable(false)
+                .with_always_on_top()
+                .with_decorations(false),
+            |ctx, _class| {
+                close = ctx.input(|i| i.viewport().close_requested() || i.key_pressed(egui::Key::Escape));
+                egui::CentralPanel::default().show(ctx, |ui| {
+                    let response = ui.add(
+                        egui::TextEdit::singleline(&mut text)
+                            .hint_text(i18n::t("quick_add.hint"))
+                            .desired_width(f32::INFINITY),
+                    );
+                    response.request_focus();
+                    submit = response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                });
+            },
+        );
 
-                    // Parse tasks
-                    let rt = tokio::runtime::Runtime::new().unwrap();
-                    match rt.block_on(ollama::parse_transcript(&transcript, ollama_enabled)) {
-                        Ok(parsed_tasks) => {
-                            eprintln!("✅ Parsed {} tasks", parsed_tasks.len());
-                            let _ = tx.send(ProcessingResult::Tasks(parsed_tasks));
-                        }
-                        Err(e) => {
-                            let _ = tx.send(ProcessingResult::Error(format!("Parse error: {}", e)));
-                        }
-                    }
+        if submit && !text.trim().is_empty() {
+            match database::add_task(&self.db, text.trim()) {
+                Ok(task) => {
+                    self.push_undo(UndoAction::Added(vec![task.id]));
+                    self.reload_tasks();
                 }
                 Err(e) => {
-                    let _ = tx.send(ProcessingResult::Error(format!("Transcription error: {}", e)));
+                    self.show_error(i18n::tf("error.add_task", &[("error", &e)]));
                 }
             }
-            let _ = tx.send(ProcessingResult::Done);
+            return true;
+        }
+        self.quick_add = Some(text);
+        close
+    }
+
+    /// Render the break overlay; returns true when the user skips or closes it
+    fn show_break_overlay(&self, ctx: &egui::Context, ends: Instant) -> bool {
+        ctx.show_viewport_immediate(
+            egui::ViewportId::from_hash_of("break_overlay"),
+            egui::ViewportBuilder::default()
+                .with_title(i18n::t("break.title"))
+                .with_fullscreen(true)
+                .with_always_on_top()
+                .with_decorations(false),
+            |ctx, _class| {
+                let mut skip = ctx.input(|i| i.viewport().close_requested());
+                egui::CentralPanel::default().show(ctx, |ui| {
+                    let remaining = ends.saturating_duration_since(Instant::now()).as_secs();
+                    ui.vertical_centered(|ui| {
+                        ui.add_space(ui.available_height() / 3.0);
+                        ui.heading(i18n::t("break.heading"));
+                        ui.label(egui::RichText::new(i18n::t("break.hint")).color(egui::Color32::GRAY));
+                        ui.add_space(16.0);
+                        ui.label(
+                            egui::RichText::new(format!("{}:{:02}", remaining / 60, remaining % 60))
+                                .size(72.0)
+                                .color(self.accent),
+                        );
+                        ui.add_space(24.0);
+                        if ui.button(i18n::t("break.skip")).clicked() {
+                            skip = true;
+                        }
+                    });
+                });
+                skip
+            },
+        )
+    }
+
+    fn arm_escalation(&mut self) {
+        self.pending_alert = self.escalation.enabled.then(|| {
+            (Instant::now() + Duration::from_secs(self.escalation.after_minutes.max(1) * 60), 0)
         });
     }
 
-    fn timer_remaining(&self) -> Duration {
-        let elapsed = self.timer_start.elapsed();
-        if elapsed >= self.timer_duration {
-            Duration::ZERO
-        } else {
-            self.timer_duration - elapsed
+    /// Repeat an ignored alert more insistently: notification, louder chime, taskbar flash
+    fn escalate_if_due(&mut self, ctx: &egui::Context) {
+        // Escalation is held back while the user is away
+        if self.timer_paused_at.is_some() {
+            return;
+        }
+        let Some((due, level)) = self.pending_alert else {
+            return;
+        };
+        if due > Instant::now() {
+            return;
+        }
+
+        // Held until do-not-disturb ends
+        if dnd::get_dnd_state(&self.dnd).is_active() {
+            return;
+        }
+
+        let level = level + 1;
+        self.pending_alert = (level < MAX_ESCALATIONS).then(|| {
+            (Instant::now() + Duration::from_secs(self.escalation.after_minutes.max(1) * 60), level)
+        });
+        tracing::info!("Alert ignored - escalating (step {})", level);
+
+        earcon::play_alert_sound_at(&self.alert_sound, 1.0 + 0.5 * level as f32);
+        if self.escalation.flash_taskbar {
+            ctx.send_viewport_cmd(egui::ViewportCommand::RequestUserAttention(egui::UserAttentionType::Critical));
+        }
+
+        let body = match self.tasks.iter().find(|t| !t.completed) {
+            Some(task) => i18n::tf("alert.escalation_task", &[("task", &task.text)]),
+            None => i18n::t("alert.escalation"),
+        };
+        if let Err(e) = notify_rust::Notification::new()
+            .summary("FlowState")
+            .body(&body)
+            .show()
+        {
+            tracing::warn!("Failed to show notification: {}", e);
         }
     }
 
-    fn reset_timer(&mut self) {
-        self.timer_start = Instant::now();
-        self.timer_duration = Duration::from_secs(self.timer_duration_mins as u64 * 60);
+    /// Log the interval that just expired, with the focused (or top open) task
+    fn log_session(&mut self) {
+        let task = match &self.focus_task {
+            Some((id, text, _, _)) => Some((*id, text.clone())),
+            None => self.tasks.iter().find(|t| !t.completed).map(|t| (t.id, t.text.clone())),
+        };
+        match database::log_session(
+            &self.db,
+            &self.interval_started_at,
+            &now_timestamp(),
+            task.as_ref().map(|(id, _)| *id),
+            task.as_ref().map(|(_, text)| text.as_str()),
+        ) {
+            Ok(id) => self.pending_session = Some(id),
+            Err(e) => tracing::warn!("Failed to log session: {}", e),
+        }
+    }
+
+    /// The user responded to the last alert: stop escalating and mark the checkpoint answered
+    fn acknowledge_alert(&mut self, ctx: &egui::Context) {
+        if self.pending_alert.take().is_some() {
+            ctx.send_viewport_cmd(egui::ViewportCommand::RequestUserAttention(egui::UserAttentionType::Reset));
+        }
+        if let Some(id) = self.pending_session.take() {
+            if let Err(e) = database::acknowledge_session(&self.db, id) {
+                tracing::warn!("Failed to acknowledge session: {}", e);
+            }
+        }
+    }
+
+    /// Record a short "what did you just do?" answer for the interval that just ended
+    fn start_journal_prompt(&mut self) {
+        if !self.journal_prompt_enabled || self.is_recording || self.is_processing {
+            return;
+        }
+        let interval = (self.interval_started_at.clone(), now_timestamp());
+        self.start_recording();
+        if self.is_recording {
+            self.journal_interval = Some(interval);
+            self.status_message = Some(format!("📓 {}", i18n::t("journal.prompt")));
+        }
+    }
+
+    /// Attach the countdown to a task for `minutes`
+    fn start_focus(&mut self, ctx: &egui::Context, task_id: i64, text: String, minutes: u64) {
+        self.focus_task = Some((task_id, text, minutes, now_timestamp()));
+        self.focus_monitor = self.focus_monitor_enabled.then(focus_monitor::FocusMonitor::start);
+        self.acknowledge_alert(ctx);
+        self.reset_timer();
+    }
+
+    /// Cancel the focus session without logging it
+    fn stop_focus(&mut self) {
+        self.focus_task = None;
+        self.focus_monitor = None;
+        self.reset_timer();
+    }
+
+    /// Log a finished focus session to the time-tracking table
+    fn finish_focus(&mut self) {
+        if let Some((task_id, text, minutes, started_at)) = self.focus_task.take() {
+            let ended_at = now_timestamp();
+            let switches = self.focus_monitor.take().and_then(focus_monitor::FocusMonitor::finish);
+            let seconds = (minutes * 60) as i64;
+            if let Err(e) = database::log_time_entry(&self.db, task_id, &text, &started_at, &ended_at, seconds, switches.as_ref()) {
+                tracing::warn!("Failed to log focus session: {}", e);
+            }
+            if let Some(switches) = switches {
+                self.show_info(focus_monitor::summary(&switches));
+            }
+        }
     }
 }
 
 impl eframe::App for FlowStateApp {
     fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
+        // The timer stays paused for the whole break
+        if let Some(ends) = self.break_ends_at {
+            if Instant::now() >= ends || self.show_break_overlay(ctx, ends) {
+                self.end_break();
+            }
+        } else {
+            self.check_presence();
+        }
+        self.escalate_if_due(ctx);
+        self.check_power();
+        self.check_reminders();
+
+        // Quick add (the shortcut works while FlowState has focus; it toggles the box)
+        if let Some(shortcut) = self.quick_add_shortcut {
+            if ctx.input_mut(|i| i.consume_shortcut(&shortcut)) {
+                self.quick_add = if self.quick_add.is_some() { None } else { Some(String::new()) };
+            }
+        }
+        if self.show_quick_add(ctx) {
+            self.quick_add = None;
+        }
+        self.handle_shortcuts(ctx);
+        if let Some(shortcut) = self.compact_shortcut {
+            if ctx.input_mut(|i| i.consume_shortcut(&shortcut)) {
+                self.set_compact(ctx, !self.compact);
+            }
+        }
+
+        let hotkey_presses: Vec<_> = self.hotkey_rx
+            .try_iter()
+            .filter(|event| event.state == HotKeyState::Pressed && self.record_hotkey.is_some_and(|hotkey| hotkey.id() == event.id))
+            .collect();
+        for _ in hotkey_presses {
+            self.toggle_hotkey_recording();
+        }
+        let tray_actions: Vec<_> = self.tray_rx
+            .as_ref()
+            .map(|rx| rx.try_iter().collect())
+            .unwrap_or_default();
+        for action in tray_actions {
+            match action {
+                // The handler already brought the window back
+                TrayAction::Show => {}
+                TrayAction::Record => self.toggle_hotkey_recording(),
+                TrayAction::Quit => {
+                    self.save_settings();
+                    self.quit_requested = true;
+                }
+            }
+        }
+
+        self.poll_updates();
+        self.poll_ollama();
+        self.poll_benchmark();
+        self.poll_ask();
+        for event in self.download_rx.try_iter() {
+            self.download_statuses.retain(|known| known.model != event.model);
+            self.download_statuses.push(event);
+        }
+        if let Some(report) = self.diagnostics_rx.as_ref().and_then(|rx| rx.try_recv().ok()) {
+            self.diagnostics = Some(report);
+            self.diagnostics_rx = None;
+        }
+
         // Check timer expiry
-        if self.timer_remaining() == Duration::ZERO && self.timer_duration_mins > 0 {
-            // Play sound (beep)
-            print!("\x07"); // ASCII bell
+        if self.timer_remaining() == Duration::ZERO && self.timer_running() {
+            // Hard-break mode (and every pomodoro) takes over the screen instead of
+            // escalating; none of it happens when do-not-disturb kept the alert quiet
+            let alerted = self.trigger_alert(ctx);
+            if alerted {
+                if self.pomodoro && self.focus_task.is_none() {
+                    self.start_break(POMODORO_BREAK_MINUTES);
+                } else if self.break_settings.enabled {
+                    self.start_break(self.break_settings.minutes);
+                } else {
+                    self.arm_escalation();
+                }
+            }
+            self.log_session();
+            self.finish_focus();
+            if alerted {
+                self.start_journal_prompt();
+            }
             self.reset_timer();
         }
 
-        // Clear old errors
-        if let Some(error_time) = self.error_time {
-            if error_time.elapsed() > Duration::from_secs(5) {
-                self.error_message = None;
-                self.error_time = None;
+        // Journal prompt records for a fixed window
+        if self.journal_interval.is_some()
+            && self.recording_start.is_some_and(|start| start.elapsed() >= JOURNAL_WINDOW)
+        {
+            self.stop_recording();
+        }
+
+        // Hands-free mode: wake phrase starts recording, trailing silence stops it
+        self.sync_wake_word(ctx);
+        self.sync_markdown(ctx);
+        self.sync_calendar();
+        // Ticks and new lines from the note are already in the database
+        if self.markdown_rx.as_ref().is_some_and(|rx| rx.try_iter().count() > 0) {
+            self.reload_tasks();
+        }
+        self.sync_google_calendar(ctx);
+        self.poll_google_calendar();
+        self.sync_todoist(ctx);
+        self.poll_todoist();
+        self.sync_telegram(ctx);
+        self.poll_telegram(ctx);
+        self.sync_clipboard(ctx);
+        self.poll_clipboard();
+        self.poll_config_file();
+        self.sync_mqtt();
+        self.publish_mqtt();
+        let wake_events: Vec<_> = self.wake_word_rx
+            .as_ref()
+            .map(|rx| rx.try_iter().collect())
+            .unwrap_or_default();
+        for event in wake_events {
+            match event {
+                wakeword::WakeWordEvent::Detected => {
+                    if !self.is_recording && !self.is_processing {
+                        self.start_recording();
+                        self.hands_free_recording = self.is_recording;
+                    }
+                }
+                wakeword::WakeWordEvent::SpeechEnded => {
+                    if self.is_recording && self.hands_free_recording {
+                        self.stop_recording();
+                        self.hands_free_recording = false;
+                    }
+                }
             }
         }
 
@@ -609,51 +3429,76 @@ impl eframe::App for FlowStateApp {
 
             let mut done = false;
             for result in results {
-                match result {
-                    ProcessingResult::Transcript(transcript) => {
-                        self.status_message = Some(format!("Transcribed: {}", transcript));
-                    }
-                    ProcessingResult::Tasks(parsed_tasks) => {
-                        if parsed_tasks.is_empty() {
-                            self.error_message = Some("No tasks found in transcript".to_string());
-                            self.error_time = Some(Instant::now());
-                        } else {
-                            for task in &parsed_tasks {
-                                eprintln!("  → Adding task: '{}' (completed: {})", task.text, task.completed);
-                                if task.completed {
-                                    let _ = database::find_and_complete_task(&self.db, &task.text);
-                                } else {
-                                    let _ = database::add_task(&self.db, &task.text);
-                                }
-                            }
-                            self.reload_tasks();
-                            self.status_message = Some(format!("Added {} task(s)", parsed_tasks.len()));
-                        }
-                    }
-                    ProcessingResult::Error(e) => {
-                        self.error_message = Some(e);
-                        self.error_time = Some(Instant::now());
-                    }
-                    ProcessingResult::Done => {
-                        done = true;
-                    }
-                }
+                done |= self.apply_processing_result(ctx, result);
             }
 
             if done {
                 self.is_processing = false;
                 self.status_message = None;
             } else {
-                // Put the receiver back if not done
-                self.processing_rx = Some(rx);
+                // Put the receiver back if not done
+                self.processing_rx = Some(rx);
+            }
+        }
+
+        // Track placement so the next launch opens in the same spot
+        let (outer_rect, inner_rect, closing) = ctx.input(|i| {
+            let viewport = i.viewport();
+            (viewport.outer_rect, viewport.inner_rect, viewport.close_requested())
+        });
+        if let (Some(outer), Some(inner)) = (outer_rect, inner_rect) {
+            let geometry = settings::WindowGeometry {
+                x: outer.min.x as f64,
+                y: outer.min.y as f64,
+                width: inner.width() as f64,
+                height: inner.height() as f64,
+            };
+            // Skip frames where the window hasn't caught up with a compact switch yet
+            let compact_sized = inner.height() as f64 <= settings::WindowSettings::COMPACT_SIZE.1 + 1.0;
+            if self.compact && compact_sized {
+                self.compact_geometry = Some(geometry);
+            } else if !self.compact && !compact_sized {
+                self.window_geometry = Some(geometry);
+            }
+        }
+        if closing {
+            self.save_settings();
+            if self.quit_requested || self.close_behavior == settings::CloseBehavior::Quit {
+                if self.is_processing {
+                    // Don't drop a recording that is still being transcribed
+                    ctx.send_viewport_cmd(egui::ViewportCommand::CancelClose);
+                    self.quit_requested = true;
+                    self.status_message = Some(i18n::t("status.finishing_before_quit"));
+                }
+            } else {
+                // Hide to the tray; without one, keep running minimized
+                ctx.send_viewport_cmd(egui::ViewportCommand::CancelClose);
+                if self.tray.is_some() {
+                    ctx.send_viewport_cmd(egui::ViewportCommand::Visible(false));
+                } else {
+                    ctx.send_viewport_cmd(egui::ViewportCommand::Minimized(true));
+                }
             }
         }
+        if self.quit_requested && !self.is_processing {
+            ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+        }
 
-        // Set always on top
-        // Note: eframe 0.29 doesn't have direct always_on_top, would need platform-specific code
-
-        // Dark theme
-        ctx.set_visuals(egui::Visuals::dark());
+        // Theme, with the accent on selections as well as the timer bar and record button
+        ctx.set_theme(match self.theme {
+            settings::Theme::Dark => egui::ThemePreference::Dark,
+            settings::Theme::Light => egui::ThemePreference::Light,
+            settings::Theme::System => egui::ThemePreference::System,
+        });
+        let accent = self.accent;
+        ctx.all_styles_mut(|style| {
+            style.visuals.selection.bg_fill = accent;
+            style.visuals.hyperlink_color = accent;
+        });
+        // Keep zooming with the keyboard for the next start (not while the scale slider is dragged)
+        if ctx.dragged_id().is_none() {
+            self.ui_scale = ctx.zoom_factor();
+        }
 
         // Timer bar at top
         egui::TopBottomPanel::top("timer_bar").show(ctx, |ui| {
@@ -665,222 +3510,1032 @@ impl eframe::App for FlowStateApp {
                 0.0
             };
 
+            let mut pause = false;
+            let mut reset = false;
+            let mut extend = false;
+            let mut pomodoro = false;
             ui.horizontal(|ui| {
-                let bar_width = ui.available_width() - 60.0;
+                // Room for the time and the control buttons
+                let bar_width = (ui.available_width() - 170.0).max(40.0);
                 let (rect, _) = ui.allocate_exact_size(
                     egui::vec2(bar_width, 4.0),
                     egui::Sense::hover(),
                 );
 
                 // Background
-                ui.painter().rect_filled(rect, 0.0, egui::Color32::from_gray(40));
+                ui.painter().rect_filled(rect, 0.0, ui.visuals().extreme_bg_color);
 
                 // Progress
                 let progress_rect = egui::Rect::from_min_size(
                     rect.min,
                     egui::vec2(rect.width() * progress, rect.height()),
                 );
-                ui.painter().rect_filled(
-                    progress_rect,
-                    0.0,
-                    egui::Color32::from_rgb(74, 158, 255),
-                );
+                ui.painter().rect_filled(progress_rect, 0.0, self.accent);
 
                 // Time text
                 let mins = remaining.as_secs() / 60;
                 let secs = remaining.as_secs() % 60;
-                ui.label(format!("{}:{:02}", mins, secs));
+                if self.timer_paused_at.is_some() && !self.paused_by_user {
+                    ui.label(format!("⏸ {}:{:02}", mins, secs)).on_hover_text(i18n::t("timer.paused_hint"));
+                } else {
+                    ui.label(format!("{}:{:02}", mins, secs));
+                }
+
+                let running = self.timer_running();
+                let (pause_icon, pause_hint) = if self.paused_by_user { ("▶", "timer.resume") } else { ("⏸", "timer.pause") };
+                pause = ui.add_enabled(running, egui::Button::new(pause_icon).small()).on_hover_text(i18n::t(pause_hint)).clicked();
+                reset = ui.add_enabled(running, egui::Button::new("↺").small()).on_hover_text(i18n::t("timer.reset")).clicked();
+                extend = ui
+                    .add_enabled(running, egui::Button::new(format!("+{}", EXTEND_MINUTES)).small())
+                    .on_hover_text(i18n::tf("timer.extend", &[("minutes", &EXTEND_MINUTES)]))
+                    .clicked();
+                let pomodoro_hint = if self.pomodoro {
+                    i18n::t("timer.pomodoro_stop")
+                } else {
+                    i18n::tf("timer.pomodoro_start", &[("minutes", &POMODORO_MINUTES), ("break", &POMODORO_BREAK_MINUTES)])
+                };
+                pomodoro = ui.add(egui::SelectableLabel::new(self.pomodoro, "🍅")).on_hover_text(pomodoro_hint).clicked();
             });
-        });
+            if pause {
+                self.toggle_pause();
+            }
+            if reset {
+                self.reset_timer();
+            }
+            if extend {
+                self.extend_timer(EXTEND_MINUTES);
+            }
+            if pomodoro {
+                self.toggle_pomodoro();
+            }
 
-        // Main content
-        egui::CentralPanel::default().show(ctx, |ui| {
-            // Header
-            ui.horizontal(|ui| {
-                ui.heading("FlowState");
-                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                    if ui.button("⚙").clicked() {
-                        self.show_settings = true;
+            // Focus session indicator
+            let mut cancel_focus = false;
+            if let Some((_, ref text, minutes, _)) = self.focus_task {
+                ui.horizontal(|ui| {
+                    ui.label(egui::RichText::new(format!("⏱ {}", i18n::tf("focus.active", &[("minutes", &minutes), ("task", text)]))).small());
+                    if ui.small_button("✕").on_hover_text(i18n::t("focus.cancel")).clicked() {
+                        cancel_focus = true;
                     }
-                    let pin_text = if self.always_on_top { "📌" } else { "📍" };
-                    if ui.button(pin_text).clicked() {
-                        self.always_on_top = !self.always_on_top;
+                });
+            }
+            if cancel_focus {
+                self.stop_focus();
+            }
+
+            // Unacknowledged alert - escalates if ignored
+            let mut acknowledge = false;
+            if self.pending_alert.is_some() {
+                ui.horizontal(|ui| {
+                    ui.label(egui::RichText::new(format!("⏰ {}", i18n::t("alert.checkpoint"))).small());
+                    if ui.small_button(i18n::t("alert.acknowledge")).clicked() {
+                        acknowledge = true;
                     }
                 });
-            });
+            }
+            if acknowledge {
+                self.acknowledge_alert(ctx);
+            }
+        });
 
-            ui.add_space(8.0);
+        // Main content (the compact strip replaces it entirely)
+        let mut enter_compact = false;
+        if self.compact {
+            self.show_compact(ctx);
+        } else {
+            egui::CentralPanel::default().show(ctx, |ui| {
+                // Header
+                ui.horizontal(|ui| {
+                    ui.heading("FlowState");
+                    if !self.profiles.profiles.is_empty() {
+                        let mut selected = self.profiles.active.clone();
+                        egui::ComboBox::from_id_salt("active_profile")
+                            .selected_text(selected.clone().unwrap_or_else(|| i18n::t("profiles.none")))
+                            .show_ui(ui, |ui| {
+                                ui.selectable_value(&mut selected, None, i18n::t("profiles.none"));
+                                for profile in &self.profiles.profiles {
+                                    ui.selectable_value(&mut selected, Some(profile.name.clone()), &profile.name);
+                                }
+                            })
+                            .response
+                            .on_hover_text(i18n::t("profiles.hint"));
+                        if selected != self.profiles.active {
+                            self.switch_profile(selected);
+                        }
+                    }
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui.button("⚙").clicked() {
+                            self.open_settings();
+                        }
+                        if ui.button("✚").on_hover_text(i18n::t("quick_add.title")).clicked() {
+                            self.quick_add = Some(String::new());
+                        }
+                        if !self.toast_history.is_empty()
+                            && ui.button("🔔").on_hover_text(i18n::t("toasts.history")).clicked()
+                        {
+                            self.show_toast_history = !self.show_toast_history;
+                        }
+                        if ui.button("⌨").on_hover_text(i18n::t("shortcuts.title")).clicked() {
+                            self.show_shortcuts = !self.show_shortcuts;
+                        }
+                        if ui.button("🗕").on_hover_text(i18n::t("compact.enter")).clicked() {
+                            enter_compact = true;
+                        }
+                        let pin_text = if self.always_on_top { "📌" } else { "📍" };
+                        if ui.button(pin_text).clicked() {
+                            self.always_on_top = !self.always_on_top;
+                            let level = if self.always_on_top {
+                                egui::WindowLevel::AlwaysOnTop
+                            } else {
+                                egui::WindowLevel::Normal
+                            };
+                            ui.ctx().send_viewport_cmd(egui::ViewportCommand::WindowLevel(level));
+                            self.save_settings();
+                        }
+                    });
+                });
 
-            // Record button
-            ui.vertical_centered(|ui| {
-                let button_size = egui::vec2(100.0, 100.0);
-                let (rect, response) = ui.allocate_exact_size(button_size, egui::Sense::click());
+                ui.add_space(8.0);
 
-                let is_hovered = response.hovered();
+                // Record button
+                ui.vertical_centered(|ui| {
+                    let button_size = egui::vec2(100.0, 100.0);
+                    let (rect, response) = ui.allocate_exact_size(button_size, egui::Sense::click());
 
-                // Get current audio level
-                let current_level = *self.audio_level.lock().unwrap();
+                    let is_hovered = response.hovered();
 
-                // Draw audio level ring when recording
-                if self.is_recording && current_level > 0.01 {
-                    let level_radius = 42.0 + current_level * 8.0; // 42-50 range
-                    let level_alpha = (current_level * 200.0) as u8;
-                    ui.painter().circle_stroke(
-                        rect.center(),
-                        level_radius,
-                        egui::Stroke::new(3.0, egui::Color32::from_rgba_unmultiplied(239, 68, 68, level_alpha)),
-                    );
+                    // Get current audio level
+                    let current_level = *self.audio_level.lock().unwrap();
+
+                    // Draw audio level ring when recording
+                    if self.is_recording && current_level > 0.01 {
+                        let level_radius = 42.0 + current_level * 8.0; // 42-50 range
+                        let level_alpha = (current_level * 200.0) as u8;
+                        ui.painter().circle_stroke(
+                            rect.center(),
+                            level_radius,
+                            egui::Stroke::new(3.0, egui::Color32::from_rgba_unmultiplied(239, 68, 68, level_alpha)),
+                        );
+                    }
+
+                    let bg_color = if self.is_recording {
+                        egui::Color32::from_rgb(239, 68, 68) // Red when recording
+                    } else if self.is_processing {
+                        ui.visuals().widgets.active.weak_bg_fill
+                    } else if is_hovered {
+                        ui.visuals().widgets.hovered.weak_bg_fill
+                    } else {
+                        ui.visuals().widgets.inactive.weak_bg_fill
+                    };
+
+                    ui.painter().circle_filled(rect.center(), 40.0, bg_color);
+
+                    // Icon
+                    if self.is_processing {
+                        // Spinner would go here - just show text for now
+                        ui.painter().text(
+                            rect.center(),
+                            egui::Align2::CENTER_CENTER,
+                            "...",
+                            egui::FontId::proportional(24.0),
+                            ui.visuals().strong_text_color(),
+                        );
+                    } else if self.is_recording {
+                        // Stop icon (square)
+                        let square_size = 20.0;
+                        let square_rect = egui::Rect::from_center_size(
+                            rect.center(),
+                            egui::vec2(square_size, square_size),
+                        );
+                        ui.painter().rect_filled(square_rect, 2.0, egui::Color32::WHITE);
+                    } else {
+                        // Record icon (circle)
+                        ui.painter().circle_filled(rect.center(), 20.0, self.accent);
+                    }
+
+                    // Handle hold-to-record: start on press, stop on release
+                    // Use global mouse state so releasing anywhere stops recording
+                    let mouse_down = ctx.input(|i| i.pointer.primary_down());
+
+                    if response.is_pointer_button_down_on() && !self.is_recording && !self.is_processing {
+                        // Mouse pressed on button - start recording
+                        self.start_recording();
+                    }
+
+                    if self.is_recording && !mouse_down && !self.hands_free_recording && !self.hotkey_recording && self.journal_interval.is_none() {
+                        // Mouse released anywhere - stop recording
+                        self.stop_recording();
+                    }
+
+                    // Recording time / hint
+                    if self.is_recording {
+                        if let Some(start) = self.recording_start {
+                            let secs = start.elapsed().as_secs();
+                            ui.label(format!("● {}", i18n::tf("recording.elapsed", &[("time", &format!("{}:{:02}", secs / 60, secs % 60))])));
+                        }
+                    } else if self.is_processing {
+                        ui.label(i18n::t("recording.processing"));
+                    } else {
+                        ui.label(egui::RichText::new(i18n::t("recording.hint")).color(egui::Color32::GRAY));
+                    }
+
+                    // One-click language override for the next memo
+                    // Without one, the profile's language or the setting is shown
+                    let language = self
+                        .recording_language
+                        .as_deref()
+                        .unwrap_or_else(|| self.profiles.language().unwrap_or(&self.transcription_language));
+                    let language_label = if language.eq_ignore_ascii_case("auto") {
+                        i18n::t("recording.language_auto")
+                    } else {
+                        language.to_uppercase()
+                    };
+                    if ui.small_button(language_label)
+                        .on_hover_text(i18n::t("recording.language_hint"))
+                        .clicked()
+                    {
+                        self.recording_language = match self.recording_language.as_deref() {
+                            None => Some("ru".to_string()),
+                            Some("ru") => Some("en".to_string()),
+                            _ => None,
+                        };
+                    }
+                });
+
+                ui.add_space(8.0);
+
+                // Status message
+                if let Some(ref status) = self.status_message {
+                    ui.label(status);
                 }
 
-                let bg_color = if self.is_recording {
-                    egui::Color32::from_rgb(239, 68, 68) // Red when recording
-                } else if self.is_processing {
-                    egui::Color32::from_gray(60)
-                } else if is_hovered {
-                    egui::Color32::from_gray(50)
-                } else {
-                    egui::Color32::from_gray(42)
-                };
+                // Transcript review: fix Whisper's mistakes before tasks are created
+                let mut review_action: Option<bool> = None;
+                if let Some(transcript) = self.pending_transcript.as_mut() {
+                    egui::Frame::group(ui.style()).show(ui, |ui| {
+                        ui.label(egui::RichText::new(i18n::t("transcript.review")).small().color(egui::Color32::GRAY));
+                        ui.add(
+                            egui::TextEdit::multiline(transcript)
+                                .desired_rows(3)
+                                .desired_width(f32::INFINITY),
+                        );
+                        ui.horizontal(|ui| {
+                            if ui.button(i18n::t("transcript.apply")).clicked() {
+                                review_action = Some(true);
+                            }
+                            if ui.button(i18n::t("transcript.discard")).clicked() {
+                                review_action = Some(false);
+                            }
+                        });
+                    });
+                }
+                match review_action {
+                    Some(true) => {
+                        let transcript = self.pending_transcript.take().unwrap_or_default();
+                        self.apply_transcript(transcript);
+                    }
+                    Some(false) => self.pending_transcript = None,
+                    None => {}
+                }
 
-                ui.painter().circle_filled(rect.center(), 40.0, bg_color);
+                ui.add_space(8.0);
 
-                // Icon
-                if self.is_processing {
-                    // Spinner would go here - just show text for now
-                    ui.painter().text(
-                        rect.center(),
-                        egui::Align2::CENTER_CENTER,
-                        "...",
-                        egui::FontId::proportional(24.0),
-                        egui::Color32::WHITE,
+                // Manual add
+                ui.horizontal(|ui| {
+                    let add_button = ui.button("✚").on_hover_text(i18n::t("tasks.add"));
+                    let field = ui.add(
+                        egui::TextEdit::singleline(&mut self.new_task_text)
+                            .hint_text(i18n::t("tasks.add_hint"))
+                            .desired_width(f32::INFINITY),
                     );
-                } else if self.is_recording {
-                    // Stop icon (square)
-                    let square_size = 20.0;
-                    let square_rect = egui::Rect::from_center_size(
-                        rect.center(),
-                        egui::vec2(square_size, square_size),
+                    let submitted = field.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                    if (submitted || add_button.clicked()) && !self.new_task_text.trim().is_empty() {
+                        match database::add_task(&self.db, self.new_task_text.trim()) {
+                            Ok(task) => {
+                                self.push_undo(UndoAction::Added(vec![task.id]));
+                                self.new_task_text.clear();
+                                self.reload_tasks();
+                            }
+                            Err(e) => {
+                                self.show_error(e.to_string());
+                            }
+                        }
+                        if submitted {
+                            field.request_focus();
+                        }
+                    }
+                });
+
+                // Today's estimated work, red once it's over capacity
+                if let Some(workload) = self.today_workload.as_ref().filter(|w| w.planned_minutes > 0) {
+                    let text = i18n::tf(
+                        "workload.line",
+                        &[
+                            ("planned", &estimates::format_estimate(workload.planned_minutes)),
+                            ("capacity", &estimates::format_estimate(workload.capacity_minutes)),
+                        ],
                     );
-                    ui.painter().rect_filled(square_rect, 2.0, egui::Color32::WHITE);
-                } else {
-                    // Record icon (circle)
-                    ui.painter().circle_filled(rect.center(), 20.0, egui::Color32::WHITE);
+                    let color = if workload.over_minutes > 0 {
+                        egui::Color32::from_rgb(248, 113, 113)
+                    } else {
+                        egui::Color32::GRAY
+                    };
+                    ui.label(egui::RichText::new(text).small().color(color))
+                        .on_hover_text(estimates::summary(workload));
                 }
 
-                // Handle hold-to-record: start on press, stop on release
-                // Use global mouse state so releasing anywhere stops recording
-                let mouse_down = ctx.input(|i| i.pointer.primary_down());
+                // Search and filter chips (clicking the active chip clears it)
+                ui.horizontal(|ui| {
+                    let mut changed = false;
+                    for (status, key) in [
+                        (database::TaskStatus::Open, "tasks.filter_open"),
+                        (database::TaskStatus::Completed, "tasks.filter_done"),
+                        (database::TaskStatus::Pinned, "tasks.filter_pinned"),
+                    ] {
+                        if ui.selectable_label(self.task_filter == status, i18n::t(key)).clicked() {
+                            self.task_filter = if self.task_filter == status {
+                                database::TaskStatus::Recent
+                            } else {
+                                status
+                            };
+                            changed = true;
+                        }
+                    }
+                    // Only there while something waits in the inbox (or it's being looked at)
+                    let in_inbox = self.task_filter == database::TaskStatus::Inbox;
+                    if (self.inbox_count > 0 || in_inbox)
+                        && ui
+                            .selectable_label(in_inbox, i18n::tf("tasks.filter_inbox", &[("count", &self.inbox_count)]))
+                            .on_hover_text(i18n::t("tasks.inbox_hint"))
+                            .clicked()
+                    {
+                        self.task_filter = if in_inbox { database::TaskStatus::Recent } else { database::TaskStatus::Inbox };
+                        changed = true;
+                    }
+                    // A project only comes from voice; its chip is how it's cleared
+                    if let Some(project) = &self.task_project {
+                        if ui.selectable_label(true, format!("+{} ✕", project)).clicked() {
+                            self.task_project = None;
+                            changed = true;
+                        }
+                    }
+                    // Contexts with open tasks; the selected one stays until it's cleared
+                    let mut contexts = self.contexts.clone();
+                    if let Some(selected) = self.task_context.as_ref().filter(|c| !contexts.iter().any(|(name, _)| name == *c)) {
+                        contexts.push((selected.clone(), 0));
+                    }
+                    for (name, count) in contexts {
+                        let selected = self.task_context.as_deref() == Some(name.as_str());
+                        if ui
+                            .selectable_label(selected, format!("@{} {}", name, count))
+                            .on_hover_text(i18n::tf("tasks.context_hint", &[("context", &name)]))
+                            .clicked()
+                        {
+                            self.task_context = if selected { None } else { Some(name) };
+                            changed = true;
+                        }
+                    }
+                    let search = ui.add(
+                        egui::TextEdit::singleline(&mut self.task_search)
+                            .id(egui::Id::new("task_search"))
+                            .hint_text(format!("🔍 {}", i18n::t("tasks.search_hint")))
+                            .desired_width(f32::INFINITY),
+                    );
+                    if search.changed() {
+                        changed = true;
+                    }
+                    if changed {
+                        self.reload_tasks();
+                    }
+                });
 
-                if response.is_pointer_button_down_on() && !self.is_recording && !self.is_processing {
-                    // Mouse pressed on button - start recording
-                    self.start_recording();
+                // Questions about the task history (needs Ollama)
+                if self.ollama_enabled {
+                    ui.horizontal(|ui| {
+                        let field = ui.add_enabled(
+                            self.ask_rx.is_none(),
+                            egui::TextEdit::singleline(&mut self.ask_text)
+                                .hint_text(format!("💬 {}", i18n::t("tasks.ask_hint")))
+                                .desired_width(f32::INFINITY),
+                        );
+                        if field.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) && !self.ask_text.trim().is_empty() {
+                            self.ask_tasks();
+                        }
+                    });
+                    if self.ask_rx.is_some() {
+                        ui.horizontal(|ui| {
+                            ui.spinner();
+                            ui.label(i18n::t("tasks.asking"));
+                        });
+                    }
+                    let mut dismiss = false;
+                    if let Some(answer) = &self.ask_answer {
+                        ui.horizontal_wrapped(|ui| {
+                            ui.label(&answer.answer);
+                            if !answer.task_ids.is_empty() {
+                                let ids = answer.task_ids.iter().map(|id| format!("#{}", id)).collect::<Vec<_>>().join(", ");
+                                ui.weak(i18n::tf("tasks.ask_sources", &[("ids", &ids)]));
+                            }
+                            dismiss = ui.small_button("✕").clicked();
+                        });
+                    }
+                    if dismiss {
+                        self.ask_answer = None;
+                    }
                 }
 
-                if self.is_recording && !mouse_down {
-                    // Mouse released anywhere - stop recording
-                    self.stop_recording();
+                if !self.habits.is_empty() {
+                    self.habit_dots(ui);
                 }
 
-                // Recording time / hint
-                if self.is_recording {
-                    if let Some(start) = self.recording_start {
-                        let secs = start.elapsed().as_secs();
-                        ui.label(format!("● Recording {}:{:02}", secs / 60, secs % 60));
-                    }
-                } else if self.is_processing {
-                    ui.label("Processing...");
-                } else {
-                    ui.label(egui::RichText::new("Hold to record").color(egui::Color32::GRAY));
+                if !self.selected_tasks.is_empty() {
+                    self.bulk_actions(ui);
                 }
-            });
 
-            ui.add_space(8.0);
+                ui.add_space(4.0);
+
+                // Task list
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    let mut tasks_to_toggle = Vec::new();
+                    let mut tasks_to_delete = Vec::new();
+                    let mut task_to_focus: Option<(i64, String)> = None;
+                    let mut start_reminder: Option<i64> = None;
+                    let mut pin_toggle: Option<i64> = None;
+                    let mut inbox_to_commit: Option<i64> = None;
+                    let mut start_schedule: Option<i64> = None;
+                    // Some(Some(when)) schedules the task, Some(None) cancels the edit
+                    let mut finish_schedule: Option<Option<String>> = None;
+                    // Some(Some(when)) sets the reminder, Some(None) cancels the edit
+                    let mut finish_reminder: Option<Option<String>> = None;
+                    let mut start_edit: Option<(i64, String)> = None;
+                    let mut select: Option<(i64, egui::Modifiers)> = None;
+                    // Some(Some(text)) saves the edit, Some(None) cancels it
+                    let mut finish_edit: Option<Option<String>> = None;
+                    let mut drop_target: Option<i64> = None;
+                    let pointer_released = ctx.input(|i| i.pointer.any_released());
+                    let list_filtered = self.task_list_filtered();
+
+                    for task in &self.tasks {
+                        let row = ui.horizontal(|ui| {
+                            // Only open tasks have a place in the manual order
+                            if !task.completed && !list_filtered {
+                                let handle = ui
+                                    .add(egui::Label::new(egui::RichText::new("☰").color(egui::Color32::GRAY)).sense(egui::Sense::drag()))
+                                    .on_hover_text(i18n::t("tasks.drag_hint"));
+                                if handle.drag_started() {
+                                    self.dragged_task = Some(task.id);
+                                }
+                                if handle.hovered() || handle.dragged() {
+                                    ui.ctx().set_cursor_icon(egui::CursorIcon::Grab);
+                                }
+                            }
+
+                            let mut completed = task.completed;
+                            if ui.checkbox(&mut completed, "").changed() {
+                                tasks_to_toggle.push(task.id);
+                            }
 
-            // Error message
-            if let Some(ref error) = self.error_message {
-                ui.colored_label(egui::Color32::from_rgb(248, 113, 113), error);
-            }
+                            match self.editing_task.as_mut().filter(|(id, _)| *id == task.id) {
+                                Some((_, text)) => {
+                                    let field = ui.text_edit_singleline(text);
+                                    if !field.has_focus() && !field.lost_focus() {
+                                        field.request_focus();
+                                    }
+                                    if ui.input(|i| i.key_pressed(egui::Key::Escape)) {
+                                        finish_edit = Some(None);
+                                    } else if field.lost_focus() {
+                                        finish_edit = Some(Some(text.clone()));
+                                    }
+                                }
+                                None => {
+                                    let text = if task.completed {
+                                        egui::RichText::new(&task.text)
+                                            .strikethrough()
+                                            .color(egui::Color32::GRAY)
+                                    } else {
+                                        egui::RichText::new(&task.text)
+                                    };
+                                    let label = ui
+                                        .add(egui::Label::new(text).sense(egui::Sense::click()))
+                                        .on_hover_text(i18n::t("tasks.edit_hint"));
+                                    if label.double_clicked() {
+                                        start_edit = Some((task.id, task.text.clone()));
+                                    } else if label.clicked() {
+                                        select = Some((task.id, ui.input(|i| i.modifiers)));
+                                    }
+                                }
+                            }
 
-            // Status message
-            if let Some(ref status) = self.status_message {
-                ui.label(status);
-            }
+                            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                                if ui.small_button("×").clicked() {
+                                    tasks_to_delete.push(task.id);
+                                }
+                                // Triage: keep it as is, or keep it with a due date (× discards it)
+                                if task.inbox && !task.completed {
+                                    match self.schedule_edit.as_mut().filter(|(id, _)| *id == task.id) {
+                                        Some((_, when)) => {
+                                            let field = ui.add(
+                                                egui::TextEdit::singleline(when)
+                                                    .desired_width(90.0)
+                                                    .hint_text(i18n::t("inbox.when_hint")),
+                                            );
+                                            if !field.has_focus() && !field.lost_focus() {
+                                                field.request_focus();
+                                            }
+                                            if ui.input(|i| i.key_pressed(egui::Key::Escape)) {
+                                                finish_schedule = Some(None);
+                                            } else if field.lost_focus() {
+                                                finish_schedule = Some(Some(when.clone()));
+                                            }
+                                        }
+                                        None => {
+                                            if ui.small_button("📅").on_hover_text(i18n::t("inbox.schedule_hint")).clicked() {
+                                                start_schedule = Some(task.id);
+                                            }
+                                        }
+                                    }
+                                    if ui.small_button("✔").on_hover_text(i18n::t("inbox.commit_hint")).clicked() {
+                                        inbox_to_commit = Some(task.id);
+                                    }
+                                }
+                                if !task.completed
+                                    && ui.small_button("⏱").on_hover_text(i18n::t("focus.start_hint")).clicked()
+                                {
+                                    task_to_focus = Some((task.id, task.text.clone()));
+                                }
+                                if !task.completed {
+                                    match self.reminder_edit.as_mut().filter(|(id, _)| *id == task.id) {
+                                        Some((_, when)) => {
+                                            let field = ui.add(
+                                                egui::TextEdit::singleline(when)
+                                                    .desired_width(90.0)
+                                                    .hint_text(i18n::t("reminders.when_hint")),
+                                            );
+                                            if !field.has_focus() && !field.lost_focus() {
+                                                field.request_focus();
+                                            }
+                                            if ui.input(|i| i.key_pressed(egui::Key::Escape)) {
+                                                finish_reminder = Some(None);
+                                            } else if field.lost_focus() {
+                                                finish_reminder = Some(Some(when.clone()));
+                                            }
+                                        }
+                                        None => {
+                                            let label = match self.reminders.get(&task.id) {
+                                                Some(time) => format!("⏰ {}", time),
+                                                None => "⏰".to_string(),
+                                            };
+                                            if ui.small_button(label).on_hover_text(i18n::t("reminders.hint")).clicked() {
+                                                start_reminder = Some(task.id);
+                                            }
+                                        }
+                                    }
+                                }
+                                if !task.completed {
+                                    let pinned = self.pinned.contains(&task.id);
+                                    let pin = egui::RichText::new("📌").color(if pinned {
+                                        self.accent
+                                    } else {
+                                        egui::Color32::GRAY
+                                    });
+                                    let hint = i18n::t(if pinned { "tasks.unpin" } else { "tasks.pin" });
+                                    if ui.small_button(pin).on_hover_text(hint).clicked() {
+                                        pin_toggle = Some(task.id);
+                                    }
+                                }
+                                if let Some(url) = self.issue_urls.get(&task.id) {
+                                    ui.hyperlink_to("🐙", url).on_hover_text(url);
+                                }
+                                if let Some(context) = &task.context {
+                                    ui.label(egui::RichText::new(format!("@{}", context)).small().color(self.accent));
+                                }
+                                if let Some(minutes) = task.estimate_minutes {
+                                    ui.label(
+                                        egui::RichText::new(format!("~{}", estimates::format_estimate(minutes)))
+                                            .small()
+                                            .color(egui::Color32::GRAY),
+                                    );
+                                }
+                                if let Some(due) = task.due_at.as_deref().filter(|_| !task.completed) {
+                                    // Red once overdue, amber for the rest of the day it's due
+                                    let now = chrono::Local::now().naive_local();
+                                    let color = match dates::from_db(due) {
+                                        Some(at) if at < now => egui::Color32::from_rgb(248, 113, 113),
+                                        Some(at) if at.date() == now.date() => egui::Color32::from_rgb(251, 191, 36),
+                                        _ => self.accent,
+                                    };
+                                    if let Some(label) = dates::label(due) {
+                                        ui.label(egui::RichText::new(format!("📅 {}", label)).small().color(color))
+                                            .on_hover_text(i18n::tf("dates.due", &[("when", &label)]));
+                                    }
+                                }
+                                if let Some(age) = age_label(&task.created_at) {
+                                    ui.label(egui::RichText::new(age).small().color(egui::Color32::GRAY))
+                                        .on_hover_text(i18n::tf("age.created", &[("date", &local_date_time(&task.created_at).unwrap_or_default())]));
+                                }
+                            });
+                        })
+                        .response;
 
-            ui.add_space(8.0);
+                        if self.selected_tasks.contains(&task.id) {
+                            ui.painter().rect_stroke(row.rect.expand(1.0), 3.0, egui::Stroke::new(1.0, self.accent));
+                        }
 
-            // Task list
-            egui::ScrollArea::vertical().show(ui, |ui| {
-                let mut tasks_to_toggle = Vec::new();
-                let mut tasks_to_delete = Vec::new();
+                        // Show where a dragged task would land
+                        if self.dragged_task.is_some_and(|id| id != task.id)
+                            && !task.completed
+                            && ui.rect_contains_pointer(row.rect)
+                        {
+                            ui.painter().hline(
+                                row.rect.x_range(),
+                                row.rect.top(),
+                                egui::Stroke::new(2.0, self.accent),
+                            );
+                            if pointer_released {
+                                drop_target = Some(task.id);
+                            }
+                        }
+                    }
 
-                for task in &self.tasks {
-                    ui.horizontal(|ui| {
-                        let mut completed = task.completed;
-                        if ui.checkbox(&mut completed, "").changed() {
-                            tasks_to_toggle.push(task.id);
+                    // Completed since midnight, under the open tasks
+                    if !self.done_today.is_empty() {
+                        ui.add_space(4.0);
+                        egui::CollapsingHeader::new(i18n::tf("tasks.done_today", &[("count", &self.done_today.len())]))
+                            .id_salt("done_today")
+                            .default_open(true)
+                            .show(ui, |ui| {
+                                for task in &self.done_today {
+                                    ui.horizontal(|ui| {
+                                        let mut completed = true;
+                                        if ui.checkbox(&mut completed, "").on_hover_text(i18n::t("tasks.reopen")).changed() {
+                                            tasks_to_toggle.push(task.id);
+                                        }
+                                        ui.label(egui::RichText::new(&task.text).strikethrough().color(egui::Color32::GRAY));
+                                        if let Some(time) = task.completed_at.as_deref().and_then(local_time) {
+                                            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                                                ui.label(egui::RichText::new(time).small().color(egui::Color32::GRAY));
+                                            });
+                                        }
+                                    });
+                                }
+                            });
+                    }
+
+                    // Apply changes
+                    if let Some((id, modifiers)) = select {
+                        self.select_task(id, modifiers);
+                    }
+                    if let Some(edit) = start_edit {
+                        self.editing_task = Some(edit);
+                    }
+                    if let Some(id) = start_reminder {
+                        self.reminder_edit = Some((id, String::new()));
+                    }
+                    if let Some(id) = start_schedule {
+                        self.schedule_edit = Some((id, String::new()));
+                    }
+                    if let Some(result) = finish_schedule {
+                        if let (Some((id, _)), Some(when)) = (self.schedule_edit.take(), result) {
+                            if !when.trim().is_empty() {
+                                self.schedule_inbox(id, &when);
+                            }
+                        }
+                    }
+                    if let Some(id) = inbox_to_commit {
+                        self.commit_inbox(&[id]);
+                    }
+                    if let Some(result) = finish_reminder {
+                        if let (Some((id, _)), Some(when)) = (self.reminder_edit.take(), result) {
+                            self.set_reminder(id, &when);
+                        }
+                    }
+                    let mut should_reload = !tasks_to_toggle.is_empty() || !tasks_to_delete.is_empty();
+                    if let Some(id) = pin_toggle {
+                        should_reload |= database::set_task_pinned(&self.db, id, !self.pinned.contains(&id)).is_ok();
+                    }
+                    if let Some(result) = finish_edit {
+                        if let (Some((id, _)), Some(text)) = (self.editing_task.take(), result) {
+                            // An emptied task keeps its old text; delete it with ×
+                            let previous = self
+                                .tasks
+                                .iter()
+                                .find(|t| t.id == id && !text.trim().is_empty() && t.text != text.trim())
+                                .map(|t| t.text.clone());
+                            if let Some(previous) = previous {
+                                if database::update_task(&self.db, id, text.trim()).is_ok() {
+                                    self.push_undo(UndoAction::Edited(vec![(id, previous)]));
+                                    should_reload = true;
+                                }
+                            }
+                        }
+                    }
+                    if pointer_released {
+                        let dragged = self.dragged_task.take();
+                        if let (Some(dragged), Some(target)) = (dragged, drop_target) {
+                            let mut order: Vec<i64> = self.tasks.iter().filter(|t| !t.completed).map(|t| t.id).collect();
+                            if let (Some(from), Some(to)) = (
+                                order.iter().position(|id| *id == dragged),
+                                order.iter().position(|id| *id == target),
+                            ) {
+                                order.remove(from);
+                                order.insert(to, dragged);
+                                let _ = database::reorder_tasks(&self.db, &order);
+                                should_reload = true;
+                            }
+                        }
+                    }
+                    for id in tasks_to_toggle {
+                        if database::toggle_task(&self.db, id).is_ok() {
+                            self.push_undo(UndoAction::Toggled(vec![id]));
+                        }
+                    }
+                    if !tasks_to_delete.is_empty() {
+                        self.delete_tasks(&tasks_to_delete);
+                    }
+                    if should_reload {
+                        self.reload_tasks();
+                    }
+                    if let Some((id, text)) = task_to_focus {
+                        self.start_focus(ctx, id, text, 25);
+                    }
+                });
+            });
+        }
+        if enter_compact {
+            self.set_compact(ctx, true);
+        }
+
+        // Messages shown this run, newest first
+        if self.show_toast_history {
+            let mut open = true;
+            let mut clear = false;
+            egui::Window::new(i18n::t("toasts.history"))
+                .open(&mut open)
+                .collapsible(false)
+                .default_width(300.0)
+                .show(ctx, |ui| {
+                    egui::ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+                        for toast in self.toast_history.iter().rev() {
+                            ui.horizontal_wrapped(|ui| {
+                                ui.label(egui::RichText::new(&toast.time).small().color(egui::Color32::GRAY));
+                                ui.colored_label(toast.severity.color(), &toast.message);
+                            });
                         }
+                    });
+                    if ui.button(i18n::t("toasts.clear")).clicked() {
+                        clear = true;
+                    }
+                });
+            if clear {
+                self.toast_history.clear();
+                self.toasts.clear();
+            }
+            self.show_toast_history = open && !clear;
+        }
+        self.show_toasts(ctx);
 
-                        let text = if task.completed {
-                            egui::RichText::new(&task.text)
-                                .strikethrough()
-                                .color(egui::Color32::GRAY)
-                        } else {
-                            egui::RichText::new(&task.text)
-                        };
-                        ui.label(text);
+        // Keyboard cheat-sheet (F1)
+        if self.show_shortcuts {
+            let mut open = true;
+            egui::Window::new(i18n::t("shortcuts.title"))
+                .open(&mut open)
+                .collapsible(false)
+                .resizable(false)
+                .show(ctx, |ui| {
+                    egui::Grid::new("shortcuts").num_columns(2).spacing([16.0, 4.0]).show(ui, |ui| {
+                        for (keys, action) in [
+                            ("Space", i18n::t("shortcuts.record")),
+                            (self.record_shortcut.as_str(), i18n::t("shortcuts.record_anywhere")),
+                            ("Ctrl+N", i18n::t("shortcuts.quick_add")),
+                            ("Ctrl+F", i18n::t("shortcuts.search")),
+                            ("Delete", i18n::t("shortcuts.delete")),
+                            ("Esc", i18n::t("shortcuts.clear_selection")),
+                            ("Ctrl+Z", i18n::t("shortcuts.undo")),
+                            ("F1", i18n::t("shortcuts.help")),
+                        ] {
+                            ui.label(egui::RichText::new(keys).monospace());
+                            ui.label(action);
+                            ui.end_row();
+                        }
+                    });
+                });
+            self.show_shortcuts = open;
+        }
 
-                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                            if ui.small_button("×").clicked() {
-                                tasks_to_delete.push(task.id);
+        // Crash report from the previous run
+        if let Some(report) = self.crash_report.clone() {
+            egui::Window::new(i18n::t("crash.title"))
+                .collapsible(false)
+                .resizable(false)
+                .show(ctx, |ui| {
+                    ui.label(i18n::t("crash.message"));
+                    ui.label(egui::RichText::new(report.display().to_string()).small().color(egui::Color32::GRAY));
+                    ui.horizontal(|ui| {
+                        if ui.button(i18n::t("crash.open_report")).clicked() {
+                            if let Err(e) = crash::open_report(&report) {
+                                self.show_error(e);
                             }
-                        });
+                            self.crash_report = None;
+                        }
+                        if ui.button(i18n::t("common.dismiss")).clicked() {
+                            self.crash_report = None;
+                        }
                     });
-                }
+                });
+        }
 
-                // Apply changes
-                let should_reload = !tasks_to_toggle.is_empty() || !tasks_to_delete.is_empty();
-                for id in tasks_to_toggle {
-                    let _ = database::toggle_task(&self.db, id);
-                }
-                for id in tasks_to_delete {
-                    let _ = database::delete_task(&self.db, id);
-                }
-                if should_reload {
-                    self.reload_tasks();
-                }
-            });
-        });
+        // Update available
+        if let Some(update) = self.available_update.clone() {
+            egui::Window::new(i18n::tf("updates.available", &[("version", &update.version)]))
+                .collapsible(false)
+                .resizable(false)
+                .show(ctx, |ui| {
+                    ui.label(i18n::tf("updates.current_version", &[("version", &update.current_version)]));
+                    if !update.notes.is_empty() {
+                        egui::CollapsingHeader::new(i18n::t("updates.whats_new")).show(ui, |ui| {
+                            egui::ScrollArea::vertical().max_height(160.0).show(ui, |ui| {
+                                ui.label(egui::RichText::new(&update.notes).small());
+                            });
+                        });
+                    }
+                    if let Some(status) = &self.update_status {
+                        ui.label(egui::RichText::new(status).small().color(egui::Color32::GRAY));
+                    }
+                    ui.horizontal(|ui| {
+                        let busy = self.update_rx.is_some();
+                        if update.installable {
+                            if ui.add_enabled(!busy, egui::Button::new(i18n::t("updates.install"))).clicked() {
+                                self.install_update();
+                            }
+                        } else if ui.button(i18n::t("updates.open_release")).clicked() {
+                            ui.ctx().open_url(egui::OpenUrl::new_tab(&update.release_url));
+                            self.available_update = None;
+                        }
+                        if ui.add_enabled(!busy, egui::Button::new(i18n::t("updates.later"))).clicked() {
+                            self.available_update = None;
+                            self.update_status = None;
+                        }
+                    });
+                });
+        }
 
         // Settings window
         if self.show_settings {
-            egui::Window::new("Settings")
+            egui::Window::new(i18n::t("settings.title"))
                 .collapsible(false)
                 .resizable(false)
                 .show(ctx, |ui| {
                     // Timer duration
                     ui.horizontal(|ui| {
-                        ui.label("Timer (minutes):");
+                        ui.label(i18n::t("settings.timer_minutes"));
                         if ui.add(egui::Slider::new(&mut self.timer_duration_mins, 0..=60)).changed() {
                             self.reset_timer();
                         }
                     });
 
+                    ui.checkbox(&mut self.journal_prompt_enabled, i18n::t("settings.journal_prompt"));
+                    ui.checkbox(&mut self.speak_tasks_on_alert, i18n::t("settings.speak_tasks"));
+                    ui.checkbox(&mut self.voice_inbox, i18n::t("settings.voice_inbox"))
+                        .on_hover_text(i18n::t("settings.voice_inbox_hint"));
+                    ui.checkbox(&mut self.action_mode, i18n::t("settings.action_mode"))
+                        .on_hover_text(i18n::t("settings.action_mode_hint"));
+
+                    // Content filter for screen sharing
+                    ui.checkbox(&mut self.content_filter.enabled, i18n::t("settings.content_filter"))
+                        .on_hover_text(i18n::t("settings.content_filter_hint"));
+                    if self.content_filter.enabled {
+                        ui.horizontal(|ui| {
+                            ui.selectable_value(&mut self.content_filter.mode, content_filter::FilterMode::Mask, i18n::t("content_filter.mask"));
+                            ui.selectable_value(&mut self.content_filter.mode, content_filter::FilterMode::Drop, i18n::t("content_filter.drop"));
+                        });
+                        ui.checkbox(&mut self.content_filter.profanity, i18n::t("settings.content_filter_profanity"));
+                        ui.horizontal(|ui| {
+                            ui.label(i18n::t("settings.content_filter_words"));
+                            let response = ui.add(
+                                egui::TextEdit::singleline(&mut self.content_filter_text)
+                                    .hint_text("Acme, project falcon")
+                                    .desired_width(140.0),
+                            );
+                            if response.lost_focus() {
+                                self.content_filter.words = split_list(&self.content_filter_text);
+                            }
+                        });
+                    }
+                    ui.checkbox(&mut self.focus_monitor_enabled, i18n::t("settings.focus_monitor"))
+                        .on_hover_text(i18n::t("settings.focus_monitor_hint"));
+                    ui.checkbox(&mut self.auto_pause_enabled, i18n::t("settings.auto_pause"));
+
+                    // Hard break
+                    ui.checkbox(&mut self.break_settings.enabled, i18n::t("settings.hard_break"));
+                    if self.break_settings.enabled {
+                        ui.horizontal(|ui| {
+                            ui.label(i18n::t("settings.break_minutes"));
+                            ui.add(egui::Slider::new(&mut self.break_settings.minutes, 1..=30));
+                        });
+                    }
+
+                    // Escalation for ignored alerts
+                    ui.checkbox(&mut self.escalation.enabled, i18n::t("settings.escalate"));
+                    if self.escalation.enabled {
+                        ui.horizontal(|ui| {
+                            ui.label(i18n::t("settings.repeat_after"));
+                            ui.add(egui::Slider::new(&mut self.escalation.after_minutes, 1..=15));
+                        });
+                        ui.checkbox(&mut self.escalation.flash_taskbar, i18n::t("settings.flash_taskbar"));
+                    }
+
+                    // Working hours
+                    schedule_editor(ui, &mut self.schedule);
+                    if self.auto_pause_enabled {
+                        ui.horizontal(|ui| {
+                            ui.label(i18n::t("settings.idle_after"));
+                            ui.add(egui::Slider::new(&mut self.idle_threshold_minutes, 1..=30));
+                        });
+                    }
+
+                    // Do not disturb
+                    ui.checkbox(&mut self.dnd.respect_system_dnd, i18n::t("settings.dnd_system"));
+                    ui.horizontal(|ui| {
+                        ui.label(i18n::t("settings.dnd_apps"));
+                        let response = ui.add(
+                            egui::TextEdit::singleline(&mut self.dnd_apps_text)
+                                .hint_text("zoom, obs64")
+                                .desired_width(140.0),
+                        );
+                        if response.lost_focus() {
+                            self.dnd.fullscreen_apps = self.dnd_apps_text
+                                .split(',')
+                                .map(|app| app.trim().to_string())
+                                .filter(|app| !app.is_empty())
+                                .collect();
+                        }
+                    });
+
+                    // Timer alert style
+                    ui.horizontal(|ui| {
+                        ui.label(i18n::t("settings.timer_alert"));
+                        egui::ComboBox::from_id_salt("alert_mode")
+                            .selected_text(match self.alert_mode {
+                                settings::AlertMode::Notification => i18n::t("alert_mode.notification"),
+                                settings::AlertMode::Focus => i18n::t("alert_mode.focus"),
+                                settings::AlertMode::Both => i18n::t("alert_mode.both"),
+                            })
+                            .show_ui(ui, |ui| {
+                                ui.selectable_value(&mut self.alert_mode, settings::AlertMode::Notification, i18n::t("alert_mode.notification"));
+                                ui.selectable_value(&mut self.alert_mode, settings::AlertMode::Focus, i18n::t("alert_mode.focus"));
+                                ui.selectable_value(&mut self.alert_mode, settings::AlertMode::Both, i18n::t("alert_mode.both"));
+                            });
+                    });
+
+                    // Timer alert sound
+                    ui.horizontal(|ui| {
+                        ui.label(i18n::t("settings.alert_sound"));
+                        let selected_text = match &self.alert_sound {
+                            earcon::AlertSound::None => i18n::t("sound.none"),
+                            earcon::AlertSound::Chime => i18n::t("sound.chime"),
+                            earcon::AlertSound::Bell => i18n::t("sound.bell"),
+                            earcon::AlertSound::Digital => i18n::t("sound.digital"),
+                            earcon::AlertSound::File(path) => path
+                                .file_name()
+                                .map(|n| n.to_string_lossy().to_string())
+                                .unwrap_or_else(|| i18n::t("sound.custom_file")),
+                        };
+                        egui::ComboBox::from_id_salt("alert_sound")
+                            .selected_text(selected_text)
+                            .show_ui(ui, |ui| {
+                                ui.selectable_value(&mut self.alert_sound, earcon::AlertSound::None, i18n::t("sound.none"));
+                                ui.selectable_value(&mut self.alert_sound, earcon::AlertSound::Chime, i18n::t("sound.chime"));
+                                ui.selectable_value(&mut self.alert_sound, earcon::AlertSound::Bell, i18n::t("sound.bell"));
+                                ui.selectable_value(&mut self.alert_sound, earcon::AlertSound::Digital, i18n::t("sound.digital"));
+                            });
+                        if ui.small_button("▶").on_hover_text(i18n::t("sound.preview")).clicked() {
+                            earcon::play_alert_sound(&self.alert_sound);
+                        }
+                    });
+
+                    // Custom sound file: drop a WAV/MP3/OGG onto the window
+                    let dropped = ctx.input(|i| i.raw.dropped_files.first().and_then(|f| f.path.clone()));
+                    if let Some(path) = dropped {
+                        self.alert_sound = earcon::AlertSound::File(path);
+                    }
+                    ui.label(egui::RichText::new(i18n::t("sound.drop_hint")).small().color(egui::Color32::GRAY));
+
                     ui.add_space(8.0);
 
                     // Audio device
-                    ui.label("Microphone:");
+                    ui.label(i18n::t("settings.microphone"));
                     let selected_device_name = if self.selected_device_idx == 0 {
-                        "Default".to_string()
+                        i18n::t("settings.device_default")
                     } else {
                         self.audio_devices
                             .get(self.selected_device_idx - 1)
                             .cloned()
-                            .unwrap_or_else(|| "Unknown".to_string())
+                            .unwrap_or_else(|| i18n::t("settings.device_unknown"))
                     };
                     egui::ComboBox::from_id_salt("audio_device")
                         .selected_text(&selected_device_name)
                         .show_ui(ui, |ui| {
-                            ui.selectable_value(&mut self.selected_device_idx, 0, "Default");
+                            ui.selectable_value(&mut self.selected_device_idx, 0, i18n::t("settings.device_default"));
                             for (idx, name) in self.audio_devices.iter().enumerate() {
                                 ui.selectable_value(&mut self.selected_device_idx, idx + 1, name);
                             }
@@ -889,87 +4544,841 @@ impl eframe::App for FlowStateApp {
                     ui.add_space(8.0);
 
                     // Whisper model
-                    ui.label("Whisper Model:");
+                    ui.label(i18n::t("settings.whisper_model"));
 
-                    // Check if download completed and refresh
-                    if *self.download_state.completed.lock().unwrap() {
+                    // Pick up models that finished downloading
+                    let download_statuses = self.download_statuses.clone();
+                    let newly_installed = download_statuses.iter().any(|event| {
+                        event.status == downloads::DownloadStatus::Completed
+                            && self.available_models.iter().any(|(name, installed)| *name == event.model && !installed)
+                    });
+                    if newly_installed {
                         self.refresh_models();
-                        *self.download_state.completed.lock().unwrap() = false;
                     }
 
-                    let is_downloading = *self.download_state.is_downloading.lock().unwrap();
-                    let current_downloading = self.download_state.current_model.lock().unwrap().clone();
-
                     // Clone available_models to avoid borrow issues
                     let models_snapshot: Vec<_> = self.available_models.clone();
                     let mut model_to_download: Option<String> = None;
+                    let mut model_to_cancel: Option<String> = None;
 
                     for (name, installed) in &models_snapshot {
+                        let status = download_statuses
+                            .iter()
+                            .find(|event| event.model == *name)
+                            .map(|event| event.status.clone());
                         ui.horizontal(|ui| {
                             let selected = self.selected_model == *name;
                             if ui.radio(selected && *installed, name).clicked() && *installed {
                                 self.selected_model = name.clone();
+                                self.save_settings();
                             }
 
-                            let is_this_downloading = current_downloading.as_ref() == Some(name);
-
                             if *installed {
-                                ui.colored_label(egui::Color32::from_rgb(74, 222, 128), "✓ Installed");
-                            } else if is_this_downloading {
-                                // Show progress
-                                let progress = *self.download_state.progress.lock().unwrap();
-                                let downloaded = *self.download_state.downloaded_mb.lock().unwrap();
-                                let total = *self.download_state.total_mb.lock().unwrap();
-                                ui.add(egui::ProgressBar::new(progress).text(format!("{:.0}/{:.0} MB", downloaded, total)));
-                            } else if !is_downloading {
-                                // Show download button
-                                if ui.small_button("Download").clicked() {
-                                    model_to_download = Some(name.clone());
+                                ui.colored_label(egui::Color32::from_rgb(74, 222, 128), format!("✓ {}", i18n::t("models.installed")));
+                                return;
+                            }
+                            match &status {
+                                Some(downloads::DownloadStatus::Queued) => {
+                                    ui.colored_label(egui::Color32::GRAY, i18n::t("models.queued"));
                                 }
-                            } else {
-                                ui.colored_label(egui::Color32::GRAY, "—");
+                                Some(downloads::DownloadStatus::Downloading { downloaded, total }) => {
+                                    let progress = *downloaded as f32 / (*total).max(1) as f32;
+                                    ui.add(
+                                        egui::ProgressBar::new(progress)
+                                            .desired_width(140.0)
+                                            .text(format!("{:.0}/{:.0} MB", *downloaded as f32 / 1_000_000.0, *total as f32 / 1_000_000.0)),
+                                    );
+                                }
+                                Some(downloads::DownloadStatus::Failed { error }) => {
+                                    if ui.small_button(i18n::t("models.retry")).on_hover_text(error).clicked() {
+                                        model_to_download = Some(name.clone());
+                                    }
+                                }
+                                _ => {
+                                    if ui.small_button(i18n::t("models.download")).clicked() {
+                                        model_to_download = Some(name.clone());
+                                    }
+                                }
+                            }
+                            if status.as_ref().is_some_and(downloads::DownloadStatus::is_pending)
+                                && ui.small_button("✕").on_hover_text(i18n::t("models.cancel")).clicked()
+                            {
+                                model_to_cancel = Some(name.clone());
                             }
                         });
+                        if let Some(downloads::DownloadStatus::Failed { error }) = &status {
+                            ui.colored_label(egui::Color32::from_rgb(248, 113, 113), error);
+                        }
                     }
 
-                    // Start download if requested (after the loop to avoid borrow issues)
-                    if let Some(model) = model_to_download {
-                        self.start_download(&model);
+                    // Start or cancel after the loop to avoid borrow issues
+                    let model_size = |name: &str| whisper::WhisperModelSize::from_str(name);
+                    if let Some(model) = model_to_download.as_deref().and_then(model_size) {
+                        self.download_manager.enqueue(model);
+                    }
+                    if let Some(model) = model_to_cancel.as_deref().and_then(model_size) {
+                        self.download_manager.cancel(model);
                     }
+                    ui.horizontal(|ui| {
+                        ui.label(i18n::t("settings.model_unload"));
+                        ui.add(egui::Slider::new(&mut self.model_unload_minutes, 0..=120));
+                        if ui.small_button(i18n::t("settings.model_unload_now")).clicked() {
+                            whisper::unload_native();
+                        }
+                    });
+                    ui.label(egui::RichText::new(i18n::t("settings.model_unload_hint")).small().color(egui::Color32::GRAY));
+                    ui.horizontal(|ui| {
+                        ui.label(i18n::t("settings.transcription_language"));
+                        ui.add(
+                            egui::TextEdit::singleline(&mut self.transcription_language)
+                                .hint_text("ru")
+                                .desired_width(60.0),
+                        );
+                    });
+                    ui.label(
+                        egui::RichText::new(i18n::t("settings.transcription_language_hint"))
+                            .small()
+                            .color(egui::Color32::GRAY),
+                    );
 
-                    // Show download error if any
-                    if let Some(ref error) = *self.download_state.error.lock().unwrap() {
-                        ui.colored_label(egui::Color32::from_rgb(248, 113, 113), error);
+                    // Benchmark
+                    ui.horizontal(|ui| {
+                        let running = self.benchmark_rx.is_some();
+                        if ui.add_enabled(!running, egui::Button::new(i18n::t("settings.benchmark"))).clicked() {
+                            self.run_benchmark();
+                        }
+                        if running {
+                            ui.spinner();
+                            ui.label(egui::RichText::new(i18n::t("settings.benchmark_running")).small().color(egui::Color32::GRAY));
+                        }
+                    });
+                    match &self.benchmark {
+                        Some(Ok(results)) => {
+                            for result in results {
+                                let memory = result.memory_mb.map_or("?".to_string(), |mb| format!("{:.0} MB", mb));
+                                ui.label(egui::RichText::new(i18n::tf(
+                                    "settings.benchmark_result",
+                                    &[
+                                        ("model", &result.model),
+                                        ("rtf", &format!("{:.2}", result.real_time_factor)),
+                                        ("load", &format!("{:.1}", result.load_secs)),
+                                        ("memory", &memory),
+                                    ],
+                                )).small());
+                            }
+                        }
+                        Some(Err(e)) => {
+                            ui.colored_label(egui::Color32::from_rgb(248, 113, 113), e);
+                        }
+                        None => {
+                            ui.label(egui::RichText::new(i18n::t("settings.benchmark_hint")).small().color(egui::Color32::GRAY));
+                        }
                     }
 
                     ui.add_space(8.0);
 
-                    // Ollama toggle
-                    ui.checkbox(&mut self.ollama_enabled, "Use Ollama for better parsing");
+                    // Ollama toggle and server
+                    if ui.checkbox(&mut self.ollama_enabled, i18n::t("settings.ollama")).changed() && self.ollama_enabled {
+                        self.test_ollama();
+                    }
                     if self.ollama_enabled {
-                        ui.label(egui::RichText::new("Slower but more accurate").small().color(egui::Color32::GRAY));
+                        ui.label(egui::RichText::new(i18n::t("settings.ollama_hint")).small().color(egui::Color32::GRAY));
+                        ui.horizontal(|ui| {
+                            ui.label(i18n::t("settings.ollama_url"));
+                            ui.add(egui::TextEdit::singleline(&mut self.ollama.url).desired_width(f32::INFINITY));
+                        });
+                        ui.horizontal(|ui| {
+                            ui.label(i18n::t("settings.ollama_model"));
+                            // Free text until the server has listed its models
+                            if self.ollama_models.is_empty() {
+                                ui.add(egui::TextEdit::singleline(&mut self.ollama.model).desired_width(120.0));
+                            } else {
+                                egui::ComboBox::from_id_salt("ollama_model")
+                                    .selected_text(self.ollama.model.as_str())
+                                    .show_ui(ui, |ui| {
+                                        for model in &self.ollama_models {
+                                            ui.selectable_value(&mut self.ollama.model, model.clone(), model);
+                                        }
+                                    });
+                            }
+                        });
+                        ui.horizontal(|ui| {
+                            ui.label(i18n::t("settings.ollama_timeout"));
+                            ui.add(egui::Slider::new(&mut self.ollama.timeout_secs, 1..=120).suffix(" s"));
+                        });
+                        ui.horizontal(|ui| {
+                            if ui.add_enabled(self.ollama_rx.is_none(), egui::Button::new(i18n::t("settings.ollama_test"))).clicked() {
+                                self.test_ollama();
+                            }
+                            if let Some((ok, status)) = &self.ollama_status {
+                                let color = if *ok { egui::Color32::from_rgb(74, 222, 128) } else { egui::Color32::GRAY };
+                                ui.label(egui::RichText::new(status).small().color(color));
+                            }
+                        });
                     }
 
-                    ui.add_space(16.0);
+                    ui.add_space(8.0);
+
+                    // Sentence breaks for run-on transcripts
+                    ui.checkbox(&mut self.punctuation.enabled, i18n::t("settings.punctuation"))
+                        .on_hover_text(i18n::t("settings.punctuation_hint"));
+                    if self.punctuation.enabled && self.ollama_enabled {
+                        ui.checkbox(&mut self.punctuation.ollama, i18n::t("settings.punctuation_ollama"));
+                    }
+
+                    ui.add_space(8.0);
+
+                    // Markdown note, e.g. in an Obsidian vault
+                    ui.checkbox(&mut self.markdown_sync.enabled, i18n::t("settings.markdown_sync"));
+                    if self.markdown_sync.enabled {
+                        ui.horizontal(|ui| {
+                            ui.label(i18n::t("settings.markdown_file"));
+                            let response = ui.add(
+                                egui::TextEdit::singleline(&mut self.markdown_path_text)
+                                    .hint_text("C:\\Vault\\FlowState.md")
+                                    .desired_width(180.0),
+                            );
+                            if response.lost_focus() {
+                                self.markdown_sync.path = self.markdown_path_text.trim().to_string();
+                            }
+                        });
+                        ui.checkbox(&mut self.markdown_sync.two_way, i18n::t("settings.markdown_two_way"));
+                        ui.label(egui::RichText::new(i18n::t("settings.markdown_hint")).small().color(egui::Color32::GRAY));
+                    }
+
+                    // Calendar feed of dated tasks
+                    ui.checkbox(&mut self.calendar_export.enabled, i18n::t("settings.calendar_export"));
+                    if self.calendar_export.enabled {
+                        ui.horizontal(|ui| {
+                            ui.label(i18n::t("settings.calendar_file"));
+                            let response = ui.add(
+                                egui::TextEdit::singleline(&mut self.calendar_path_text)
+                                    .hint_text("C:\\Calendars\\FlowState.ics")
+                                    .desired_width(180.0),
+                            );
+                            if response.lost_focus() {
+                                self.calendar_export.path = self.calendar_path_text.trim().to_string();
+                            }
+                        });
+                        ui.label(egui::RichText::new(i18n::t("settings.calendar_hint")).small().color(egui::Color32::GRAY));
+                    }
+
+                    // Google Calendar push
+                    ui.checkbox(&mut self.google_calendar.enabled, i18n::t("settings.google_calendar"));
+                    if self.google_calendar.enabled {
+                        ui.horizontal(|ui| {
+                            ui.label(i18n::t("settings.google_client_id"));
+                            ui.add(egui::TextEdit::singleline(&mut self.google_calendar.client_id).desired_width(180.0));
+                        });
+                        ui.horizontal(|ui| {
+                            ui.label(i18n::t("settings.google_client_secret"));
+                            ui.add(egui::TextEdit::singleline(&mut self.google_calendar.client_secret).password(true).desired_width(180.0));
+                        });
+                        ui.horizontal(|ui| {
+                            ui.label(i18n::t("settings.google_calendar_id"));
+                            ui.add(egui::TextEdit::singleline(&mut self.google_calendar.calendar_id).hint_text("primary").desired_width(180.0));
+                        });
+                        ui.horizontal(|ui| {
+                            let connecting = self.google_connect_rx.is_some();
+                            if ui.add_enabled(!connecting, egui::Button::new(i18n::t("settings.google_connect"))).clicked() {
+                                self.connect_google_calendar(ui.ctx());
+                            }
+                            if connecting {
+                                ui.spinner();
+                            } else if !self.google_calendar.refresh_token.is_empty() {
+                                ui.label(i18n::t("settings.google_connected"));
+                            }
+                        });
+                        ui.label(egui::RichText::new(i18n::t("settings.google_calendar_hint")).small().color(egui::Color32::GRAY));
+                    }
+
+                    // Todoist account
+                    ui.checkbox(&mut self.todoist.enabled, i18n::t("settings.todoist"));
+                    if self.todoist.enabled {
+                        ui.horizontal(|ui| {
+                            ui.label(i18n::t("settings.todoist_token"));
+                            let response = ui.add(
+                                egui::TextEdit::singleline(&mut self.todoist_token_text)
+                                    .password(true)
+                                    .desired_width(180.0),
+                            );
+                            if response.lost_focus() {
+                                self.todoist.token = self.todoist_token_text.trim().to_string();
+                            }
+                        });
+                        ui.horizontal(|ui| {
+                            ui.label(i18n::t("settings.todoist_interval"));
+                            ui.add(egui::Slider::new(&mut self.todoist.interval_minutes, 1..=60).suffix(" min"));
+                        });
+                        ui.label(egui::RichText::new(i18n::t("settings.todoist_hint")).small().color(egui::Color32::GRAY));
+                    }
+
+                    // GitHub issue capture
+                    ui.checkbox(&mut self.github.enabled, i18n::t("settings.github"));
+                    if self.github.enabled {
+                        ui.horizontal(|ui| {
+                            ui.label(i18n::t("settings.github_token"));
+                            let response = ui.add(
+                                egui::TextEdit::singleline(&mut self.github_token_text)
+                                    .password(true)
+                                    .desired_width(180.0),
+                            );
+                            if response.lost_focus() {
+                                self.github.token = self.github_token_text.trim().to_string();
+                            }
+                        });
+                        ui.horizontal(|ui| {
+                            ui.label(i18n::t("settings.github_repo"));
+                            ui.add(
+                                egui::TextEdit::singleline(&mut self.github.repo)
+                                    .hint_text("owner/name")
+                                    .desired_width(180.0),
+                            );
+                        });
+                        ui.label(egui::RichText::new(i18n::t("settings.github_hint")).small().color(egui::Color32::GRAY));
+                    }
+
+                    // Clipboard quick capture
+                    ui.checkbox(&mut self.clipboard.enabled, i18n::t("settings.clipboard"));
+                    if self.clipboard.enabled {
+                        ui.horizontal(|ui| {
+                            ui.label(i18n::t("settings.clipboard_trigger"));
+                            let response = ui.add(egui::TextEdit::singleline(&mut self.clipboard_trigger_text).desired_width(100.0));
+                            if response.lost_focus() {
+                                self.clipboard.trigger = self.clipboard_trigger_text.trim().to_string();
+                            }
+                        });
+                        ui.label(egui::RichText::new(i18n::t("settings.clipboard_hint")).small().color(egui::Color32::GRAY));
+                    }
+
+                    // MQTT broker for Home Assistant and dashboards
+                    ui.checkbox(&mut self.mqtt.enabled, i18n::t("settings.mqtt"));
+                    if self.mqtt.enabled {
+                        ui.horizontal(|ui| {
+                            ui.label(i18n::t("settings.mqtt_host"));
+                            ui.add(egui::TextEdit::singleline(&mut self.mqtt.host).desired_width(140.0));
+                            ui.add(egui::DragValue::new(&mut self.mqtt.port).range(1..=65535));
+                        });
+                        ui.horizontal(|ui| {
+                            ui.label(i18n::t("settings.mqtt_username"));
+                            ui.add(egui::TextEdit::singleline(&mut self.mqtt.username).desired_width(120.0));
+                        });
+                        ui.horizontal(|ui| {
+                            ui.label(i18n::t("settings.mqtt_password"));
+                            let response = ui.add(
+                                egui::TextEdit::singleline(&mut self.mqtt_password_text)
+                                    .password(true)
+                                    .desired_width(120.0),
+                            );
+                            if response.lost_focus() {
+                                self.mqtt.password = self.mqtt_password_text.clone();
+                            }
+                        });
+                        ui.horizontal(|ui| {
+                            ui.label(i18n::t("settings.mqtt_prefix"));
+                            ui.add(egui::TextEdit::singleline(&mut self.mqtt.topic_prefix).desired_width(120.0));
+                        });
+                        let prefix = self.mqtt.topic_prefix.trim().trim_end_matches('/');
+                        ui.label(egui::RichText::new(i18n::tf("settings.mqtt_hint", &[("prefix", &prefix)])).small().color(egui::Color32::GRAY));
+                    }
+
+                    // Weekly review report
+                    ui.label(i18n::t("settings.report"));
+                    ui.horizontal(|ui| {
+                        ui.label(i18n::t("settings.report_folder"));
+                        let response = ui.add(
+                            egui::TextEdit::singleline(&mut self.report_folder_text)
+                                .hint_text(i18n::t("settings.report_folder_hint"))
+                                .desired_width(180.0),
+                        );
+                        if response.lost_focus() {
+                            self.report.folder = self.report_folder_text.trim().to_string();
+                        }
+                    });
+                    ui.horizontal(|ui| {
+                        ui.checkbox(&mut self.report.pdf, i18n::t("settings.report_pdf"));
+                        if ui.button(i18n::t("settings.report_this_week")).clicked() {
+                            self.generate_report(0);
+                        }
+                        if ui.button(i18n::t("settings.report_last_week")).clicked() {
+                            self.generate_report(1);
+                        }
+                    });
+
+                    // End-of-day rollover
+                    ui.label(i18n::t("settings.rollover"));
+                    ui.checkbox(&mut self.rollover.enabled, i18n::t("settings.rollover_enabled"))
+                        .on_hover_text(i18n::t("settings.rollover_hint"));
+                    if self.rollover.enabled {
+                        ui.horizontal(|ui| {
+                            ui.label(i18n::t("settings.rollover_time"));
+                            ui.add(egui::TextEdit::singleline(&mut self.rollover.time).desired_width(50.0));
+                        });
+                        ui.horizontal(|ui| {
+                            ui.label(i18n::t("settings.rollover_archive_days"));
+                            ui.add(egui::Slider::new(&mut self.rollover.archive_after_days, 1..=30));
+                        });
+                    }
+
+                    // Workload: how much estimated work fits in a day
+                    ui.label(i18n::t("settings.workload"));
+                    ui.horizontal(|ui| {
+                        ui.label(i18n::t("settings.workload_capacity"));
+                        ui.add(egui::Slider::new(&mut self.workload.capacity_minutes, 60..=720).step_by(30.0));
+                    })
+                    .response
+                    .on_hover_text(i18n::t("settings.workload_hint"));
+
+                    // Profiles: what each one narrows and swaps in while active
+                    ui.label(i18n::t("settings.profiles"));
+                    let global_schedule = self.schedule.clone();
+                    let mut delete_profile = None;
+                    for (index, (profile, draft)) in self.profiles.profiles.iter_mut().zip(self.profile_drafts.iter_mut()).enumerate() {
+                        ui.collapsing(profile.name.clone(), |ui| {
+                            ui.horizontal(|ui| {
+                                ui.label(i18n::t("settings.profile_projects"));
+                                let response = ui.add(
+                                    egui::TextEdit::singleline(&mut draft.projects)
+                                        .hint_text("work, website")
+                                        .desired_width(160.0),
+                                );
+                                if response.lost_focus() {
+                                    profile.projects = split_list(&draft.projects);
+                                }
+                            });
+                            ui.horizontal(|ui| {
+                                ui.label(i18n::t("settings.profile_tags"));
+                                let response = ui.add(
+                                    egui::TextEdit::singleline(&mut draft.tags)
+                                        .hint_text("urgent, calls")
+                                        .desired_width(160.0),
+                                );
+                                if response.lost_focus() {
+                                    profile.tags = split_list(&draft.tags);
+                                }
+                            });
+                            ui.horizontal(|ui| {
+                                ui.label(i18n::t("settings.profile_language"));
+                                let response = ui.add(
+                                    egui::TextEdit::singleline(&mut draft.language)
+                                        .hint_text("en / ru / auto")
+                                        .desired_width(60.0),
+                                );
+                                if response.lost_focus() {
+                                    let language = draft.language.trim().to_lowercase();
+                                    profile.language = (!language.is_empty()).then_some(language);
+                                }
+                            });
+                            ui.label(i18n::t("settings.profile_prompt"));
+                            ui.add(
+                                egui::TextEdit::multiline(&mut profile.prompt)
+                                    .hint_text(i18n::t("settings.profile_prompt_hint"))
+                                    .desired_rows(2)
+                                    .desired_width(f32::INFINITY),
+                            );
+                            let mut own_hours = profile.schedule.is_some();
+                            if ui.checkbox(&mut own_hours, i18n::t("settings.profile_hours")).changed() {
+                                profile.schedule = own_hours.then(|| global_schedule.clone());
+                            }
+                            if let Some(schedule) = &mut profile.schedule {
+                                schedule_editor(ui, schedule);
+                            }
+                            if ui.button(i18n::t("settings.profile_delete")).clicked() {
+                                delete_profile = Some(index);
+                            }
+                        });
+                    }
+                    if let Some(index) = delete_profile {
+                        let removed = self.profiles.profiles.remove(index);
+                        self.profile_drafts.remove(index);
+                        if self.profiles.active.as_deref().is_some_and(|active| active.eq_ignore_ascii_case(&removed.name)) {
+                            self.profiles.active = None;
+                            self.reload_tasks();
+                        }
+                        self.save_settings();
+                    }
+                    ui.horizontal(|ui| {
+                        let field = ui.add(
+                            egui::TextEdit::singleline(&mut self.new_profile_name)
+                                .hint_text(i18n::t("settings.profile_name_hint"))
+                                .desired_width(120.0),
+                        );
+                        let submitted = field.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                        if (ui.button(i18n::t("settings.profile_add")).clicked() || submitted) && !self.new_profile_name.trim().is_empty() {
+                            let profile = settings::Profile {
+                                name: self.new_profile_name.trim().to_string(),
+                                ..settings::Profile::default()
+                            };
+                            self.profile_drafts.push(ProfileDraft::from(&profile));
+                            self.profiles.profiles.push(profile);
+                            self.new_profile_name.clear();
+                            self.save_settings();
+                        }
+                    });
+                    ui.label(egui::RichText::new(i18n::t("settings.profiles_hint")).small().color(egui::Color32::GRAY));
+
+                    // Habits: the list with delete buttons, then the add form
+                    ui.label(i18n::t("settings.habits"));
+                    let mut delete_habit = None;
+                    for habit in &self.habits {
+                        ui.horizontal(|ui| {
+                            ui.label(&habit.name);
+                            ui.weak(habit_schedule_label(&habit.schedule));
+                            if ui.small_button("🗑").on_hover_text(i18n::t("settings.habit_delete")).clicked() {
+                                delete_habit = Some(habit.id);
+                            }
+                        });
+                    }
+                    if let Some(id) = delete_habit {
+                        self.delete_habit(id);
+                    }
+                    ui.horizontal(|ui| {
+                        let field = ui.add(
+                            egui::TextEdit::singleline(&mut self.new_habit_name)
+                                .hint_text(i18n::t("settings.habit_name_hint"))
+                                .desired_width(120.0),
+                        );
+                        egui::ComboBox::from_id_salt("habit_schedule")
+                            .selected_text(habit_schedule_label(&self.new_habit_schedule))
+                            .show_ui(ui, |ui| {
+                                for schedule in HABIT_SCHEDULES {
+                                    ui.selectable_value(&mut self.new_habit_schedule, schedule.to_string(), habit_schedule_label(schedule));
+                                }
+                            });
+                        let submitted = field.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                        if (ui.button(i18n::t("settings.habit_add")).clicked() || submitted) && !self.new_habit_name.trim().is_empty() {
+                            self.add_habit();
+                        }
+                    });
+
+                    // Voice macros: the list with delete buttons, then the add form
+                    ui.label(i18n::t("settings.macros"));
+                    let mut delete_macro = None;
+                    for voice_macro in &self.macros {
+                        ui.horizontal(|ui| {
+                            ui.label(format!("\"{}\"", voice_macro.phrase));
+                            ui.weak(macro_summary(voice_macro));
+                            if ui.small_button("🗑").on_hover_text(i18n::t("settings.macro_delete")).clicked() {
+                                delete_macro = Some(voice_macro.id);
+                            }
+                        });
+                    }
+                    if let Some(id) = delete_macro {
+                        self.delete_macro(id);
+                    }
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.new_macro_phrase)
+                            .hint_text(i18n::t("settings.macro_phrase_hint"))
+                            .desired_width(180.0),
+                    );
+                    ui.add(
+                        egui::TextEdit::multiline(&mut self.new_macro_tasks)
+                            .hint_text(i18n::t("settings.macro_tasks_hint"))
+                            .desired_rows(3)
+                            .desired_width(240.0),
+                    );
+                    ui.horizontal(|ui| {
+                        ui.checkbox(&mut self.new_macro_archive, i18n::t("settings.macro_archive"));
+                        if ui.button(i18n::t("settings.macro_add")).clicked() {
+                            self.add_macro();
+                        }
+                    });
+                    ui.label(egui::RichText::new(i18n::t("settings.macros_hint")).small().color(egui::Color32::GRAY));
+
+                    // API tokens: the list with revoke buttons, then the issue form
+                    ui.label(i18n::t("settings.api_tokens"));
+                    let mut revoke_token = None;
+                    for token in &self.api_tokens {
+                        ui.horizontal(|ui| {
+                            ui.label(&token.name);
+                            let scopes: Vec<&str> = token.scopes.iter().map(|scope| scope.as_str()).collect();
+                            ui.weak(format!("{}… {}", token.prefix, scopes.join(", ")));
+                            if ui.small_button("🗑").on_hover_text(i18n::t("settings.token_revoke")).clicked() {
+                                revoke_token = Some(token.id);
+                            }
+                        });
+                    }
+                    if let Some(id) = revoke_token {
+                        self.revoke_api_token(id);
+                    }
+                    ui.horizontal(|ui| {
+                        ui.add(
+                            egui::TextEdit::singleline(&mut self.new_token_name)
+                                .hint_text(i18n::t("settings.token_name_hint"))
+                                .desired_width(140.0),
+                        );
+                        for scope in [api_tokens::Scope::Read, api_tokens::Scope::Write, api_tokens::Scope::Voice] {
+                            let mut granted = self.new_token_scopes.contains(&scope);
+                            if ui.checkbox(&mut granted, scope.as_str()).changed() {
+                                self.new_token_scopes.retain(|other| *other != scope);
+                                if granted {
+                                    self.new_token_scopes.push(scope);
+                                }
+                            }
+                        }
+                        if ui.button(i18n::t("settings.token_issue")).clicked() {
+                            self.issue_api_token();
+                        }
+                    });
+                    if let Some(secret) = &self.issued_secret {
+                        ui.horizontal(|ui| {
+                            ui.code(secret);
+                            if ui.small_button("📋").on_hover_text(i18n::t("settings.token_copy")).clicked() {
+                                ui.ctx().copy_text(secret.clone());
+                            }
+                        });
+                    }
+                    let hint = if self.issued_secret.is_some() { "settings.token_issued_hint" } else { "settings.api_tokens_hint" };
+                    ui.label(egui::RichText::new(i18n::t(hint)).small().color(egui::Color32::GRAY));
+
+                    // Telegram bot for capture from the phone
+                    ui.checkbox(&mut self.telegram.enabled, i18n::t("settings.telegram"));
+                    if self.telegram.enabled {
+                        ui.horizontal(|ui| {
+                            ui.label(i18n::t("settings.telegram_token"));
+                            let response = ui.add(
+                                egui::TextEdit::singleline(&mut self.telegram_token_text)
+                                    .password(true)
+                                    .desired_width(180.0),
+                            );
+                            if response.lost_focus() {
+                                self.telegram.token = self.telegram_token_text.trim().to_string();
+                            }
+                        });
+                        ui.horizontal(|ui| {
+                            ui.label(i18n::t("settings.telegram_username"));
+                            ui.add(
+                                egui::TextEdit::singleline(&mut self.telegram.username)
+                                    .hint_text("@username")
+                                    .desired_width(180.0),
+                            );
+                        });
+                        ui.label(egui::RichText::new(i18n::t("settings.telegram_hint")).small().color(egui::Color32::GRAY));
+                    }
+
+                    ui.add_space(8.0);
+
+                    // Hands-free toggle
+                    ui.checkbox(&mut self.wake_word_enabled, i18n::t("settings.hands_free"));
+                    if self.wake_word_enabled {
+                        ui.label(egui::RichText::new(i18n::t("settings.hands_free_hint")).small().color(egui::Color32::GRAY));
+                    }
+
+                    ui.add_space(8.0);
+
+                    // Earcon mute toggle
+                    if ui.checkbox(&mut self.earcons_muted, i18n::t("settings.mute_earcons")).changed() {
+                        earcon::set_muted(self.earcons_muted);
+                    }
+
+                    ui.add_space(8.0);
+
+                    // Checkpoints responded to over the last week
+                    ui.collapsing(i18n::t("history.title"), |ui| {
+                        if self.focus_history.is_empty() {
+                            ui.label(egui::RichText::new(i18n::t("history.empty")).small().color(egui::Color32::GRAY));
+                        }
+                        for day in &self.focus_history {
+                            ui.label(i18n::tf("history.day", &[
+                                ("date", &day.date),
+                                ("answered", &day.acknowledged),
+                                ("checkpoints", &day.checkpoints),
+                                ("minutes", &(day.focus_seconds / 60)),
+                            ]));
+                        }
+                    });
+
+                    // Health checks and a report to attach to bug reports
+                    ui.horizontal(|ui| {
+                        let running = self.diagnostics_rx.is_some();
+                        let label = if running { i18n::t("diagnostics.running") } else { i18n::t("diagnostics.run") };
+                        if ui.add_enabled(!running, egui::Button::new(format!("🩺 {}", label))).clicked() {
+                            self.run_diagnostics();
+                        }
+                        if ui.button(format!("📋 {}", i18n::t("settings.copy_diagnostics"))).clicked() {
+                            ui.ctx().copy_text(logging::diagnostics_report(500));
+                        }
+                    });
+                    if let Some(report) = &self.diagnostics {
+                        for check in &report.checks {
+                            let (icon, color) = match check.status {
+                                diagnostics::CheckStatus::Ok => ("✓", egui::Color32::from_rgb(74, 222, 128)),
+                                diagnostics::CheckStatus::Warning => ("⚠", egui::Color32::from_rgb(251, 191, 36)),
+                                diagnostics::CheckStatus::Error => ("✕", egui::Color32::from_rgb(248, 113, 113)),
+                            };
+                            ui.horizontal_wrapped(|ui| {
+                                ui.colored_label(color, format!("{} {}", icon, check.label));
+                                ui.label(egui::RichText::new(&check.detail).small().color(egui::Color32::GRAY));
+                            });
+                        }
+                    }
+
+                    ui.add_space(8.0);
+
+                    ui.horizontal(|ui| {
+                        ui.label(i18n::t("settings.language"));
+                        let previous = self.ui_language;
+                        egui::ComboBox::from_id_salt("ui_language")
+                            .selected_text(match self.ui_language {
+                                Some(locale) => locale.native_name().to_string(),
+                                None => i18n::t("settings.language_system"),
+                            })
+                            .show_ui(ui, |ui| {
+                                ui.selectable_value(&mut self.ui_language, None, i18n::t("settings.language_system"));
+                                for locale in i18n::Locale::ALL {
+                                    ui.selectable_value(&mut self.ui_language, Some(locale), locale.native_name());
+                                }
+                            });
+                        if self.ui_language != previous {
+                            i18n::set_locale(self.ui_language);
+                        }
+                    });
+
+                    ui.horizontal(|ui| {
+                        ui.label(i18n::t("settings.theme"));
+                        egui::ComboBox::from_id_salt("theme")
+                            .selected_text(theme_label(self.theme))
+                            .show_ui(ui, |ui| {
+                                for theme in [settings::Theme::System, settings::Theme::Dark, settings::Theme::Light] {
+                                    ui.selectable_value(&mut self.theme, theme, theme_label(theme));
+                                }
+                            });
+                        ui.label(i18n::t("settings.accent"));
+                        egui::color_picker::color_edit_button_srgba(ui, &mut self.accent, egui::color_picker::Alpha::Opaque);
+                        if ui.small_button("↺").on_hover_text(i18n::t("settings.accent_reset")).clicked() {
+                            self.accent = accent_color(settings::DEFAULT_ACCENT_COLOR);
+                        }
+                    });
+
+                    ui.horizontal(|ui| {
+                        ui.label(i18n::t("settings.ui_scale"));
+                        let scale = ui.add(
+                            egui::Slider::new(&mut self.ui_scale, settings::UI_SCALE_RANGE)
+                                .step_by(0.05)
+                                .custom_formatter(|value, _| format!("{:.0}%", value * 100.0)),
+                        );
+                        // Apply once the drag ends, so the slider doesn't move under the pointer
+                        if scale.drag_stopped() || (scale.changed() && !scale.dragged()) {
+                            ctx.set_zoom_factor(self.ui_scale);
+                        }
+                    });
+                    ui.horizontal(|ui| {
+                        ui.label(i18n::t("settings.font_size"));
+                        let size = ui.add(egui::Slider::new(&mut self.font_size, settings::FONT_SIZE_RANGE).step_by(0.5).suffix(" pt"));
+                        if ui.small_button("↺").on_hover_text(i18n::t("settings.scale_reset")).clicked() {
+                            self.ui_scale = 1.0;
+                            self.font_size = settings::DEFAULT_FONT_SIZE;
+                            ctx.set_zoom_factor(self.ui_scale);
+                            apply_font_size(ctx, self.font_size);
+                        } else if size.changed() {
+                            apply_font_size(ctx, self.font_size);
+                        }
+                    });
+
+                    ui.horizontal(|ui| {
+                        ui.label(i18n::t("settings.close_button"));
+                        egui::ComboBox::from_id_salt("close_behavior")
+                            .selected_text(match self.close_behavior {
+                                settings::CloseBehavior::Hide => i18n::t("close_behavior.hide"),
+                                settings::CloseBehavior::Quit => i18n::t("close_behavior.quit"),
+                            })
+                            .show_ui(ui, |ui| {
+                                ui.selectable_value(&mut self.close_behavior, settings::CloseBehavior::Hide, i18n::t("close_behavior.hide"));
+                                ui.selectable_value(&mut self.close_behavior, settings::CloseBehavior::Quit, i18n::t("close_behavior.quit"));
+                            });
+                    });
+
+                    ui.horizontal(|ui| {
+                        ui.label(i18n::t("settings.record_hotkey"));
+                        let response = ui.add(
+                            egui::TextEdit::singleline(&mut self.record_shortcut)
+                                .hint_text(settings::DEFAULT_RECORD_SHORTCUT)
+                                .desired_width(160.0),
+                        );
+                        // Re-register once editing is done; a combo that can't be used is reverted
+                        if response.lost_focus() {
+                            let candidate = settings::Settings {
+                                record_shortcut: self.record_shortcut.trim().to_string(),
+                                ..settings::load(&self.db)
+                            };
+                            if let Err(e) = candidate.validate().and_then(|()| self.register_record_hotkey()) {
+                                self.record_shortcut = settings::load(&self.db).record_shortcut;
+                                self.show_error(e);
+                            }
+                        }
+                    });
+                    ui.label(egui::RichText::new(i18n::t("settings.record_hotkey_hint")).small().color(egui::Color32::GRAY));
+
+                    ui.add_space(8.0);
+
+                    // Power saving: automatic on battery, or forced on or off
+                    ui.horizontal(|ui| {
+                        ui.label(i18n::t("settings.power_saving"));
+                        let previous = self.power_saving_mode;
+                        egui::ComboBox::from_id_salt("power_saving")
+                            .selected_text(power_saving_label(self.power_saving_mode))
+                            .show_ui(ui, |ui| {
+                                for mode in [settings::PowerSaving::Auto, settings::PowerSaving::Always, settings::PowerSaving::Never] {
+                                    ui.selectable_value(&mut self.power_saving_mode, mode, power_saving_label(mode));
+                                }
+                            });
+                        if self.power_saving_mode != previous {
+                            self.set_power_saving(power::should_save(self.power_saving_mode));
+                        }
+                        if self.power_saving {
+                            ui.label(egui::RichText::new(i18n::t("settings.power_saving_active")).small().color(self.accent));
+                        }
+                    });
+                    ui.label(egui::RichText::new(i18n::t("settings.power_saving_hint")).small().color(egui::Color32::GRAY));
+
+                    ui.add_space(8.0);
 
-                    if ui.button("Close").clicked() {
-                        // Save settings
-                        let _ = database::set_ollama_enabled(&self.db, self.ollama_enabled);
-                        self.show_settings = false;
+                    ui.horizontal(|ui| {
+                        ui.checkbox(&mut self.auto_check_updates, i18n::t("updates.auto_check"));
+                        if ui.add_enabled(self.update_rx.is_none(), egui::Button::new(i18n::t("updates.check_now"))).clicked() {
+                            self.check_for_updates();
+                        }
+                    });
+                    if self.available_update.is_none() {
+                        if let Some(status) = &self.update_status {
+                            ui.label(egui::RichText::new(status).small().color(egui::Color32::GRAY));
+                        }
                     }
+
+                    ui.add_space(16.0);
+
+                    ui.horizontal(|ui| {
+                        if ui.button(i18n::t("common.close")).clicked() {
+                            self.close_settings();
+                        }
+                        if ui.button(i18n::t("settings.quit")).clicked() {
+                            self.save_settings();
+                            self.quit_requested = true;
+                        }
+                    });
                 });
         }
 
         // Only repaint when needed (not continuously!)
         // This is the key to 0% CPU - we only repaint on events
-        let is_downloading = *self.download_state.is_downloading.lock().unwrap();
-
-        if self.is_recording || is_downloading || self.is_processing {
-            // Repaint every 100ms while recording, downloading, or processing
+        if self.break_ends_at.is_some() {
+            // Break countdown ticks every second
+            ctx.request_repaint_after(Duration::from_secs(1));
+        } else if self.is_recording || self.is_processing || self.update_rx.is_some() || self.diagnostics_rx.is_some() || self.ollama_rx.is_some() || self.ask_rx.is_some() {
+            // Repaint every 100ms while recording, processing or waiting on a background check
+            // (download progress wakes the UI itself)
             ctx.request_repaint_after(Duration::from_millis(100));
         } else {
-            // When idle, only repaint every 10 seconds for timer
-            ctx.request_repaint_after(Duration::from_secs(10));
+            // When idle, only repaint every 10 seconds for timer (or right at expiry if sooner);
+            // less often on battery
+            let remaining = self.timer_remaining();
+            let idle_wait = power::throttled(Duration::from_secs(10), self.power_saving);
+            let wait = if remaining.is_zero() {
+                idle_wait
+            } else {
+                remaining.min(idle_wait)
+            };
+            ctx.request_repaint_after(wait);
         }
     }
 }
@@ -1092,20 +5501,52 @@ fn create_record_icon() -> egui::IconData {
 }
 
 fn main() -> eframe::Result<()> {
+    // --data-dir moves everything, the log files included
+    paths::init(std::env::args().skip(1));
+    // Keep the guard for the lifetime of the app so buffered log lines get flushed
+    let _log_guard = logging::init();
+    crash::install_panic_hook();
+
     let icon = create_record_icon();
 
+    // Read the saved placement before the window is created; a damaged database is repaired first
+    let (db, database_check) = startup::open_database(&paths::data_dir());
+    let window = settings::load(&db).window;
+
+    let mut viewport = egui::ViewportBuilder::default()
+        .with_inner_size([320.0, 480.0])
+        .with_min_inner_size([280.0, 400.0])
+        .with_title("FlowState")
+        .with_icon(std::sync::Arc::new(icon));
+    if window.compact {
+        let (width, height) = settings::WindowSettings::COMPACT_SIZE;
+        viewport = viewport
+            .with_min_inner_size([0.0, 0.0])
+            .with_inner_size([width as f32, height as f32])
+            .with_resizable(false)
+            .with_always_on_top();
+        if let Some(geometry) = window.compact_geometry.filter(|g| g.is_plausible()) {
+            viewport = viewport.with_position([geometry.x as f32, geometry.y as f32]);
+        }
+    } else {
+        if let Some(geometry) = window.geometry.filter(|g| g.is_plausible()) {
+            viewport = viewport
+                .with_position([geometry.x as f32, geometry.y as f32])
+                .with_inner_size([geometry.width as f32, geometry.height as f32]);
+        }
+        if window.always_on_top {
+            viewport = viewport.with_always_on_top();
+        }
+    }
+
     let options = eframe::NativeOptions {
-        viewport: egui::ViewportBuilder::default()
-            .with_inner_size([320.0, 480.0])
-            .with_min_inner_size([280.0, 400.0])
-            .with_title("FlowState")
-            .with_icon(std::sync::Arc::new(icon)),
+        viewport,
         ..Default::default()
     };
 
     eframe::run_native(
         "FlowState",
         options,
-        Box::new(|_cc| Ok(Box::new(FlowStateApp::default()))),
+        Box::new(|cc| Ok(Box::new(FlowStateApp::new(db, database_check, cc.egui_ctx.clone())))),
     )
 }
@@ -0,0 +1,100 @@
+// Navigation module
+// Voice commands that drive the app rather than the task list: "open settings",
+// "show completed", "switch to work project", "show phone context", "clear
+// filters" (and their Russian forms). The Tauri pipeline sends them to the
+// windows as `app-command` events; the native UI applies them directly.
+
+use crate::database::TaskStatus;
+use serde::{Deserialize, Serialize};
+
+/// What the app should show, as sent with the `app-command` event
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum AppCommand {
+    OpenSettings,
+    CloseSettings,
+    /// The Open or Done chip
+    ShowTasks { status: TaskStatus },
+    /// Only tasks in this +project, or every project with None
+    ShowProject { project: Option<String> },
+    /// Only tasks in this @context, or every context with None
+    ShowContext { context: Option<String> },
+    /// Back to the normal list: no chips, project or search
+    ClearFilters,
+}
+
+#[derive(Clone, Copy)]
+enum Verb {
+    Show,
+    /// Only for settings: "open project proposal" is more likely a task
+    Open,
+    Close,
+    Clear,
+}
+
+// Longer phrases first, so "show me" wins over "show"
+const VERBS: [(&str, Verb); 17] = [
+    ("switch to", Verb::Show),
+    ("go to", Verb::Show),
+    ("show me", Verb::Show),
+    ("show", Verb::Show),
+    ("open", Verb::Open),
+    ("переключись на", Verb::Show),
+    ("перейди в", Verb::Show),
+    ("перейди к", Verb::Show),
+    ("покажи", Verb::Show),
+    ("открой", Verb::Open),
+    ("close", Verb::Close),
+    ("hide", Verb::Close),
+    ("закрой", Verb::Close),
+    ("clear", Verb::Clear),
+    ("reset", Verb::Clear),
+    ("сбрось", Verb::Clear),
+    ("убери", Verb::Clear),
+];
+const FILLER: [&str; 7] = ["the", "my", "me", "please", "пожалуйста", "мне", "мои"];
+
+/// The navigation command a whole transcript makes up, or None for any other
+/// transcript ("show the report to the team" stays a task)
+pub fn app_command(transcript: &str) -> Option<AppCommand> {
+    let text = transcript.trim().trim_end_matches(['.', '!', '?']).to_lowercase();
+    let (verb, rest) = VERBS.iter().find_map(|(prefix, verb)| {
+        let rest = text.strip_prefix(prefix)?;
+        (rest.is_empty() || rest.starts_with(' ')).then_some((*verb, rest))
+    })?;
+    let words: Vec<&str> = rest
+        .split_whitespace()
+        .map(|word| word.trim_matches(','))
+        .filter(|word| !word.is_empty() && !FILLER.contains(word))
+        .collect();
+
+    match (verb, words.as_slice()) {
+        (Verb::Show | Verb::Open, ["settings" | "настройки"]) => Some(AppCommand::OpenSettings),
+        (Verb::Close, ["settings" | "настройки"]) => Some(AppCommand::CloseSettings),
+        (Verb::Clear, ["filters" | "filter" | "фильтры" | "фильтр"])
+        | (Verb::Show, ["all", "tasks"] | ["everything"] | ["все", "задачи"] | ["всё"]) => Some(AppCommand::ClearFilters),
+        (Verb::Show, ["all", "projects"] | ["все", "проекты"]) => Some(AppCommand::ShowProject { project: None }),
+        (Verb::Show, ["all", "contexts"] | ["все", "контексты"]) => Some(AppCommand::ShowContext { context: None }),
+        (Verb::Show, [name @ .., "project"] | ["project" | "проект", name @ ..]) if !name.is_empty() => {
+            Some(AppCommand::ShowProject { project: Some(name.join("-")) })
+        }
+        (Verb::Show, [name @ .., "context"] | ["context" | "контекст", name @ ..]) if !name.is_empty() => {
+            crate::contexts::normalize(&name.join("-")).map(|context| AppCommand::ShowContext { context: Some(context) })
+        }
+        (Verb::Show, [status] | [status, "tasks" | "задачи"]) => {
+            status_word(status).map(|status| AppCommand::ShowTasks { status })
+        }
+        _ => None,
+    }
+}
+
+fn status_word(word: &str) -> Option<TaskStatus> {
+    match word {
+        "completed" | "done" | "finished" | "выполненные" | "сделанные" | "завершённые" | "завершенные" => {
+            Some(TaskStatus::Completed)
+        }
+        "open" | "unfinished" | "pending" | "открытые" | "невыполненные" | "текущие" => Some(TaskStatus::Open),
+        "inbox" | "входящие" => Some(TaskStatus::Inbox),
+        _ => None,
+    }
+}
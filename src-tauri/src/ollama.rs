@@ -1,8 +1,14 @@
 // Ollama/Local LLM integration module
 // This will handle parsing transcripts to extract tasks
 
+use aho_corasick::AhoCorasick;
 use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::ops::Range;
+use std::sync::OnceLock;
 use crate::database::Task;
+use crate::parser::{ParsedMemo, ParserKind};
+use crate::settings::OllamaSettings;
 
 #[derive(Debug, Serialize, Deserialize)]
 struct OllamaRequest {
@@ -41,6 +47,91 @@ pub enum TaskAction {
     Remove(String),        // Delete/remove a task (by matching text)
 }
 
+// Keywords that indicate COMPLETING tasks (mark as done, not delete)
+// Includes both "done with X" and "X done" patterns
+const COMPLETE_KEYWORDS: [&str; 17] = [
+    "done with", "finished with", "completed", "finished", "done",
+    "mark as done", "mark done", "check off", "crossed off",
+    "i did", "i've done", "just did", "already did", "took care of",
+    "handled", "sorted", "wrapped up"
+];
+
+// Keywords that indicate REMOVING/DELETING tasks
+const REMOVE_KEYWORDS: [&str; 9] = [
+    "delete", "remove", "cancel", "get rid of", "drop", "forget about",
+    "never mind", "scratch", "erase"
+];
+
+// Keywords that indicate ADDING new tasks
+const ADD_KEYWORDS: [&str; 15] = [
+    "add task", "new task", "create task", "add", "need to", "should",
+    "must", "have to", "gotta", "got to", "want to", "going to",
+    "reminder to", "remind me to", "don't forget to"
+];
+
+/// Keywords found in one pass with an Aho-Corasick automaton, as whole words
+/// only, so "add" doesn't match inside "address" or "done" inside "abandoned"
+struct KeywordSet {
+    automaton: AhoCorasick,
+}
+
+impl KeywordSet {
+    fn new(keywords: &[&str]) -> Self {
+        let automaton = AhoCorasick::builder()
+            .ascii_case_insensitive(true)
+            .build(keywords)
+            .expect("parser keywords build an automaton");
+        Self { automaton }
+    }
+
+    /// Where the keyword listed first occurs in `text` (its first occurrence)
+    fn find(&self, text: &str) -> Option<Range<usize>> {
+        self.automaton
+            .find_overlapping_iter(text)
+            .filter(|found| is_word_boundary(text, found.start()) && is_word_boundary(text, found.end()))
+            .min_by_key(|found| (found.pattern(), found.start(), Reverse(found.end())))
+            .map(|found| found.range())
+    }
+
+    fn matches(&self, text: &str) -> bool {
+        self.find(text).is_some()
+    }
+}
+
+// Built once, on the first transcript
+struct Keywords {
+    complete: KeywordSet,
+    remove: KeywordSet,
+    add: KeywordSet,
+    // All of them, add keywords first, for cutting the keyword off a task
+    any: KeywordSet,
+}
+
+fn keywords() -> &'static Keywords {
+    static KEYWORDS: OnceLock<Keywords> = OnceLock::new();
+    KEYWORDS.get_or_init(|| {
+        let any: Vec<&str> = ADD_KEYWORDS.iter()
+            .chain(COMPLETE_KEYWORDS.iter())
+            .chain(REMOVE_KEYWORDS.iter())
+            .copied()
+            .collect();
+        Keywords {
+            complete: KeywordSet::new(&COMPLETE_KEYWORDS),
+            remove: KeywordSet::new(&REMOVE_KEYWORDS),
+            add: KeywordSet::new(&ADD_KEYWORDS),
+            any: KeywordSet::new(&any),
+        }
+    })
+}
+
+// Whether `pos` is not inside a word: the text starts or ends there, or a letter
+// or digit isn't on both sides
+fn is_word_boundary(text: &str, pos: usize) -> bool {
+    let before = text[..pos].chars().next_back();
+    let after = text[pos..].chars().next();
+    !(before.is_some_and(char::is_alphanumeric) && after.is_some_and(char::is_alphanumeric))
+}
+
 // Extract task name from "[task] done" or "[task] is done" patterns
 fn extract_task_from_trailing_pattern(text: &str) -> String {
     let trailing_patterns = [
@@ -76,32 +167,11 @@ pub fn parse_transcript_to_actions(transcript: &str) -> Vec<TaskAction> {
     let mut actions = Vec::new();
     let transcript_lower = transcript.to_lowercase();
 
-    // Keywords that indicate COMPLETING tasks (mark as done, not delete)
-    // Includes both "done with X" and "X done" patterns
-    let complete_keywords = [
-        "done with", "finished with", "completed", "finished", "done",
-        "mark as done", "mark done", "check off", "crossed off",
-        "i did", "i've done", "just did", "already did", "took care of",
-        "handled", "sorted", "wrapped up"
-    ];
-
-    // Keywords that indicate REMOVING/DELETING tasks
-    let remove_keywords = [
-        "delete", "remove", "cancel", "get rid of", "drop", "forget about",
-        "never mind", "scratch", "erase"
-    ];
-
-    // Keywords that indicate ADDING new tasks
-    let add_keywords = [
-        "add task", "new task", "create task", "add", "need to", "should",
-        "must", "have to", "gotta", "got to", "want to", "going to",
-        "reminder to", "remind me to", "don't forget to"
-    ];
-
     // Check what type of action this is
-    let has_complete = complete_keywords.iter().any(|kw| transcript_lower.contains(kw));
-    let has_remove = remove_keywords.iter().any(|kw| transcript_lower.contains(kw));
-    let has_add = add_keywords.iter().any(|kw| transcript_lower.contains(kw));
+    let keywords = keywords();
+    let has_complete = keywords.complete.matches(transcript);
+    let has_remove = keywords.remove.matches(transcript);
+    let has_add = keywords.add.matches(transcript);
 
     // Check for "[task] done" or "[task] is done" pattern (keyword at end)
     let trailing_done_pattern = transcript_lower.ends_with(" done")
@@ -117,7 +187,7 @@ pub fn parse_transcript_to_actions(transcript: &str) -> Vec<TaskAction> {
     if trailing_done_pattern && !has_complete && !has_remove && !has_add {
         let task_text = extract_task_from_trailing_pattern(&transcript_lower);
         if !task_text.is_empty() {
-            eprintln!("✅ Completing task (trailing pattern): {}", task_text);
+            tracing::debug!("Completing task (trailing pattern): {}", task_text);
             actions.push(TaskAction::Complete(task_text));
             return actions;
         }
@@ -133,10 +203,18 @@ pub fn parse_transcript_to_actions(transcript: &str) -> Vec<TaskAction> {
             .collect();
 
         for part in parts {
+            // "Write the blog post, about an hour" is one task with its estimate
+            if let (Some(_), Some(TaskAction::Add(previous))) =
+                (crate::estimates::parse_estimate(part), actions.last_mut())
+            {
+                previous.push_str(", ");
+                previous.push_str(part.trim());
+                continue;
+            }
             let task_text = clean_task_text(part);
             // Only skip if it's clearly not a task (too short or just noise)
             if !task_text.is_empty() && task_text.len() >= 3 && !is_noise_transcript(&task_text) {
-                eprintln!("📝 Creating task: {}", task_text);
+                tracing::debug!("Creating task: {}", task_text);
                 actions.push(TaskAction::Add(task_text));
             }
         }
@@ -144,7 +222,7 @@ pub fn parse_transcript_to_actions(transcript: &str) -> Vec<TaskAction> {
     }
 
     // Extract the task description from the transcript
-    let task_text = extract_task_description(transcript, &add_keywords, &complete_keywords, &remove_keywords);
+    let task_text = extract_task_description(transcript);
 
     if task_text.is_empty() {
         return actions;
@@ -237,27 +315,13 @@ fn looks_like_task_command(text: &str) -> bool {
 }
 
 // Extract the actual task description from the transcript
-fn extract_task_description(transcript: &str, add_kw: &[&str], complete_kw: &[&str], remove_kw: &[&str]) -> String {
-    let mut text = transcript.to_string();
-    let text_lower = text.to_lowercase();
-
-    // Remove action keywords to get the task description
-    let all_keywords: Vec<&str> = add_kw.iter()
-        .chain(complete_kw.iter())
-        .chain(remove_kw.iter())
-        .copied()
-        .collect();
-
-    // Find and remove keywords (case insensitive)
-    for kw in all_keywords {
-        if let Some(pos) = text_lower.find(kw) {
-            // Remove the keyword and anything before it
-            text = text[pos + kw.len()..].to_string();
-            break;
-        }
-    }
-
-    clean_task_text(&text)
+fn extract_task_description(transcript: &str) -> String {
+    // Remove the action keyword and anything before it
+    let text = match keywords().any.find(transcript) {
+        Some(keyword) => &transcript[keyword.end..],
+        None => transcript,
+    };
+    clean_task_text(text)
 }
 
 // Clean up task text
@@ -284,32 +348,50 @@ fn clean_task_text(text: &str) -> String {
 }
 
 // Simple fallback parser that works without Ollama
-fn parse_transcript_simple(transcript: &str) -> Vec<Task> {
+pub fn parse_transcript_simple(transcript: &str) -> Vec<Task> {
     let actions = parse_transcript_to_actions(transcript);
 
     // Convert actions to tasks (for backward compatibility)
     // Note: Remove actions are handled separately in process_voice_recording
     actions.into_iter().filter_map(|action| {
         match action {
-            TaskAction::Add(text) => Some(Task {
-                id: 0,
-                text,
-                completed: false,
-                created_at: chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string(),
-                completed_at: None,
-            }),
+            TaskAction::Add(text) => {
+                // "Call the bank tomorrow" → "Call the bank", due tomorrow
+                let (text, due) = crate::dates::split_due(&text, chrono::Local::now().naive_local());
+                Some(Task {
+                    id: 0,
+                    text,
+                    completed: false,
+                    created_at: chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+                    completed_at: None,
+                    context: None,
+                    estimate_minutes: None,
+                    due_at: due.and_then(crate::dates::to_db),
+                    inbox: false,
+                })
+            }
             TaskAction::Complete(text) => Some(Task {
                 id: 0,
                 text,
                 completed: true,
                 created_at: chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string(),
                 completed_at: Some(chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string()),
+                context: None,
+                estimate_minutes: None,
+                due_at: None,
+                inbox: false,
             }),
             TaskAction::Remove(_) => None, // Handled separately
         }
     }).collect()
 }
 
+/// Whether the transcript says to remove something ("delete", "never mind", ...),
+/// as a whole word, so "dropbox" or "cancellation" don't count
+pub fn has_removal_keywords(transcript: &str) -> bool {
+    keywords().remove.matches(transcript)
+}
+
 // Get removal actions from transcript (simple parser - for local fallback)
 pub fn get_removal_actions(transcript: &str) -> Vec<String> {
     parse_transcript_to_actions(transcript)
@@ -324,20 +406,77 @@ pub fn get_removal_actions(transcript: &str) -> Vec<String> {
         .collect()
 }
 
+/// Names of the models the Ollama server has pulled (`/api/tags`), or why it couldn't be reached
+pub async fn list_models(config: &OllamaSettings) -> Result<Vec<String>, String> {
+    let response = reqwest::Client::new()
+        .get(format!("{}/api/tags", config.base_url()))
+        .timeout(std::time::Duration::from_secs(3))
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    if !response.status().is_success() {
+        return Err(format!("HTTP {}", response.status()));
+    }
+    let models: serde_json::Value = response.json().await.map_err(|e| e.to_string())?;
+    Ok(models
+        .get("models")
+        .and_then(|m| m.as_array())
+        .map(|models| {
+            models
+                .iter()
+                .filter_map(|m| m.get("name").and_then(|n| n.as_str()).map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default())
+}
+
+/// The pulled model matching `wanted`, exactly or with a version tag
+/// (e.g. "llama3.2" matches "llama3.2:latest")
+pub fn find_model(models: &[String], wanted: &str) -> Option<String> {
+    let wanted = wanted.trim();
+    models
+        .iter()
+        .find(|name| *name == wanted || name.starts_with(&format!("{}:", wanted)))
+        .cloned()
+}
+
+/// Send one prompt to the configured model and return its reply text
+pub async fn generate(prompt: &str, config: &OllamaSettings) -> Result<String, String> {
+    let request = OllamaRequest {
+        model: config.model.trim().to_string(),
+        prompt: prompt.to_string(),
+        stream: false,
+    };
+    let response = reqwest::Client::new()
+        .post(format!("{}/api/generate", config.base_url()))
+        .json(&request)
+        .timeout(std::time::Duration::from_secs(config.timeout_secs))
+        .send()
+        .await
+        .map_err(|e| crate::i18n::tf("error.ollama_unreachable", &[("url", &config.base_url()), ("error", &e)]))?;
+    if !response.status().is_success() {
+        let status = response.status();
+        let error_body = response.text().await.unwrap_or_else(|_| "No error details".to_string());
+        return Err(format!("Ollama API error {}: {}", status, error_body));
+    }
+    let ollama_response: OllamaResponse = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse Ollama response: {}", e))?;
+    Ok(ollama_response.response)
+}
+
 // Get removal actions using Ollama
-pub async fn get_removal_actions_ollama(transcript: &str) -> Vec<String> {
-    match try_ollama_removal_parse(transcript).await {
+pub async fn get_removal_actions_ollama(transcript: &str, config: &OllamaSettings) -> Vec<String> {
+    match try_ollama_removal_parse(transcript, config).await {
         Ok(removals) => removals,
         Err(_) => get_removal_actions(transcript), // Fall back to simple parser
     }
 }
 
-async fn try_ollama_removal_parse(transcript: &str) -> Result<Vec<String>, String> {
-    let ollama_url = std::env::var("OLLAMA_URL")
-        .unwrap_or_else(|_| "http://localhost:11434".to_string());
-
-    let model = std::env::var("OLLAMA_MODEL")
-        .unwrap_or_else(|_| "llama3.2".to_string());
+async fn try_ollama_removal_parse(transcript: &str, config: &OllamaSettings) -> Result<Vec<String>, String> {
+    let ollama_url = config.base_url();
+    let model = config.model.trim().to_string();
 
     let client = reqwest::Client::new();
 
@@ -377,7 +516,7 @@ Return ONLY valid JSON array of strings:"#,
     let response = client
         .post(&format!("{}/api/generate", ollama_url))
         .json(&request)
-        .timeout(std::time::Duration::from_secs(30))
+        .timeout(std::time::Duration::from_secs(config.timeout_secs))
         .send()
         .await
         .map_err(|e| e.to_string())?;
@@ -401,97 +540,30 @@ Return ONLY valid JSON array of strings:"#,
     serde_json::from_str(json_str).map_err(|e| e.to_string())
 }
 
-pub async fn parse_transcript(transcript: &str, ollama_enabled: bool) -> Result<Vec<Task>, String> {
-    // Check if Ollama is enabled (from database setting)
-    if !ollama_enabled {
-        eprintln!("⚡ Using simple parser (fast mode)");
-        return Ok(parse_transcript_simple(transcript));
-    }
-
-    // Try Ollama if explicitly enabled
-    eprintln!("🔄 Trying Ollama for parsing...");
-    let ollama_result = try_ollama_parse(transcript).await;
+/// Parse a memo with the configured Ollama model (see `parser::OllamaParser`).
+/// `instructions` (from the active profile) are added to the prompt.
+pub async fn parse_memo(transcript: &str, config: &OllamaSettings, instructions: Option<&str>) -> Result<ParsedMemo, String> {
+    // First, check if Ollama is running and has the model
+    let models = list_models(config)
+        .await
+        .map_err(|e| format!("Ollama not available: {}", e))?;
+    let model = config.model.trim();
+    let model = find_model(&models, model)
+        .ok_or_else(|| format!("Model '{}' not found. Available models: {}", model, models.join(", ")))?;
+    let config = OllamaSettings { model, ..config.clone() };
 
-    match ollama_result {
-        Ok(tasks) => {
-            eprintln!("✨ Ollama parsing succeeded");
-            Ok(tasks)
-        },
-        Err(e) => {
-            // If Ollama fails, use simple parser
-            eprintln!("⚠️ Ollama unavailable: {}. Using simple parser.", e);
-            Ok(parse_transcript_simple(transcript))
-        }
-    }
+    let reply = generate(&memo_prompt(transcript, instructions), &config).await?;
+    memo_from_reply(&reply, ParserKind::Ollama)
 }
 
-async fn try_ollama_parse(transcript: &str) -> Result<Vec<Task>, String> {
-    // Default to localhost:11434 (Ollama default)
-    let ollama_url = std::env::var("OLLAMA_URL")
-        .unwrap_or_else(|_| "http://localhost:11434".to_string());
-
-    let model = std::env::var("OLLAMA_MODEL")
-        .unwrap_or_else(|_| "llama3.2".to_string());
-
-    // First, check if Ollama is running (quick check with short timeout)
-    let client = reqwest::Client::new();
-    let models_response = client
-        .get(&format!("{}/api/tags", ollama_url))
-        .timeout(std::time::Duration::from_secs(3))
-        .send()
-        .await
-        .map_err(|e| format!("Ollama not available: {}", e))?;
-    
-    if !models_response.status().is_success() {
-        return Err(format!("Failed to check Ollama models: {}", models_response.status()));
-    }
-    
-    let models_json: serde_json::Value = models_response
-        .json()
-        .await
-        .map_err(|e| format!("Failed to parse models list: {}", e))?;
-    
-    let models = models_json.get("models")
-        .and_then(|m| m.as_array())
-        .ok_or_else(|| "No models found in Ollama response".to_string())?;
-    
-    // Find matching model - check for exact match or version tag match
-    let model_to_use = models.iter()
-        .find_map(|m| {
-            m.get("name")
-                .and_then(|n| n.as_str())
-                .and_then(|n| {
-                    // Exact match
-                    if n == model {
-                        Some(n.to_string())
-                    }
-                    // Match with version tag (e.g., "llama3.2" matches "llama3.2:latest")
-                    else if n.starts_with(&format!("{}:", model)) {
-                        Some(n.to_string())
-                    }
-                    // Match base name (e.g., "llama3.2" matches "llama3.2:latest")
-                    else if n.starts_with(&model) && (n.len() == model.len() || n.chars().nth(model.len()) == Some(':')) {
-                        Some(n.to_string())
-                    } else {
-                        None
-                    }
-                })
-        })
-        .ok_or_else(|| {
-            format!("Model '{}' not found. Available models: {}", 
-                model,
-                models.iter()
-                    .filter_map(|m| m.get("name").and_then(|n| n.as_str()))
-                    .collect::<Vec<_>>()
-                    .join(", ")
-            )
-        })?;
-    
-    let prompt = format!(
+/// The prompt asking a language model for a memo's actions as JSON (shared by
+/// the Ollama and OpenAI parsers)
+pub fn memo_prompt(transcript: &str, instructions: Option<&str>) -> String {
+    format!(
         r#"Extract ALL tasks from this voice memo. Return EVERY task mentioned as a separate item.
 
 Output: JSON array with objects having "action" and "text" fields.
-Actions: "add" (new task), "complete" (done), "remove" (delete)
+Actions: "add" (new task), "complete" (done), "remove" (delete), "timer" (control the awareness timer: text is "reset", "pause", "resume" or a number of minutes)
 
 Examples:
 Input: "Buy milk, call mom, finish report"
@@ -503,47 +575,39 @@ Output: [{{"action":"add","text":"Buy bread"}},{{"action":"add","text":"Buy wate
 Input: "Выпить воды, поесть, помыть посуду"
 Output: [{{"action":"add","text":"Выпить воды"}},{{"action":"add","text":"Поесть"}},{{"action":"add","text":"Помыть посуду"}}]
 
+Input: "At the store buy batteries, and call the plumber"
+Output: [{{"action":"add","text":"At the store buy batteries"}},{{"action":"add","text":"Call the plumber"}}]
+
+Input: "Write the blog post, about an hour, and book flights"
+Output: [{{"action":"add","text":"Write the blog post, about an hour"}},{{"action":"add","text":"Book flights"}}]
+
 Input: "Done with email"
 Output: [{{"action":"complete","text":"Email"}}]
 
+Input: "Hold the countdown for now"
+Output: [{{"action":"timer","text":"pause"}}]
+
+Input: "Set a 25 minute timer, then write the intro"
+Output: [{{"action":"timer","text":"25"}},{{"action":"add","text":"Write the intro"}}]
+
 Input: "Hello"
 Output: []
 
 IMPORTANT: Extract EVERY task as a separate item. If there are 4 tasks, return 4 objects.
-
+Keep where a task is done ("at the store", "on the phone", "@computer") in its text.
+Keep how long a task takes ("about an hour", "~30m") at the end of its text.
+{}
 Voice memo: "{}"
 
 JSON:"#,
+        instructions.map(|text| format!("\nAlso: {}\n", text)).unwrap_or_default(),
         transcript
-    );
-    
-    let request = OllamaRequest {
-        model: model_to_use.clone(),
-        prompt,
-        stream: false,
-    };
-    
-    let response = client
-        .post(&format!("{}/api/generate", ollama_url))
-        .json(&request)
-        .timeout(std::time::Duration::from_secs(15))
-        .send()
-        .await
-        .map_err(|e| format!("Ollama timeout or connection error: {}", e))?;
-    
-    if !response.status().is_success() {
-        let status = response.status();
-        let error_body = response.text().await.unwrap_or_else(|_| "No error details".to_string());
-        return Err(format!("Ollama API error {}: {}", status, error_body));
-    }
-    
-    let ollama_response: OllamaResponse = response
-        .json()
-        .await
-        .map_err(|e| format!("Failed to parse Ollama response: {}", e))?;
-    
-    // Parse the JSON from the response - try new action format first
-    let response_text = ollama_response.response.trim();
+    )
+}
+
+/// Read the actions out of a language model's answer to `memo_prompt`
+pub fn memo_from_reply(reply: &str, parser: ParserKind) -> Result<ParsedMemo, String> {
+    let response_text = reply.trim();
 
     // Try to extract JSON from the response (handle markdown code blocks)
     let json_str = if response_text.contains("```") {
@@ -559,7 +623,11 @@ JSON:"#,
 
     // Try new action-based format first
     if let Ok(actions) = serde_json::from_str::<Vec<ParsedTaskAction>>(json_str) {
-        return Ok(actions.into_iter().filter_map(|a| {
+        let timer = actions
+            .iter()
+            .filter(|a| a.action.eq_ignore_ascii_case("timer"))
+            .find_map(|a| crate::timer_control::from_action(&a.text));
+        let tasks = actions.into_iter().filter_map(|a| {
             let action_lower = a.action.to_lowercase();
             match action_lower.as_str() {
                 "add" => Some(Task {
@@ -568,6 +636,10 @@ JSON:"#,
                     completed: false,
                     created_at: chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string(),
                     completed_at: None,
+                    context: None,
+                    estimate_minutes: None,
+                    due_at: None,
+                    inbox: false,
                 }),
                 "complete" => Some(Task {
                     id: 0,
@@ -575,18 +647,23 @@ JSON:"#,
                     completed: true,
                     created_at: chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string(),
                     completed_at: Some(chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string()),
+                    context: None,
+                    estimate_minutes: None,
+                    due_at: None,
+                    inbox: false,
                 }),
                 "remove" => None, // Remove actions handled separately via get_removal_actions
-                _ => None,
+                _ => None, // "timer" is picked out above
             }
-        }).collect());
+        }).collect();
+        return Ok(ParsedMemo { tasks, timer, parser });
     }
 
     // Fall back to legacy format
     let tasks: Vec<ParsedTask> = serde_json::from_str(json_str)
-        .map_err(|e| format!("Failed to parse task JSON: {}. Response: {}", e, ollama_response.response))?;
+        .map_err(|e| format!("Failed to parse task JSON: {}. Response: {}", e, reply))?;
 
-    Ok(tasks.into_iter().map(|t| Task {
+    let tasks = tasks.into_iter().map(|t| Task {
         id: 0, // Will be set by database
         text: t.text,
         completed: t.completed,
@@ -596,5 +673,10 @@ JSON:"#,
         } else {
             None
         },
-    }).collect())
+        context: None,
+        estimate_minutes: None,
+        due_at: None,
+        inbox: false,
+    }).collect();
+    Ok(ParsedMemo { tasks, timer: None, parser })
 }
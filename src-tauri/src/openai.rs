@@ -0,0 +1,63 @@
+// OpenAI module
+// Chat completions from OpenAI, or from any server that speaks the same API
+// (set `base_url`), for parsing voice memos when no local Ollama is around
+// (see `parser::OpenAiParser`). Off until an API key is entered.
+
+use crate::settings::OpenAiSettings;
+use serde::Deserialize;
+use std::time::Duration;
+
+#[derive(Debug, Deserialize)]
+struct Completion {
+    choices: Vec<Choice>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Choice {
+    message: Message,
+}
+
+#[derive(Debug, Deserialize)]
+struct Message {
+    #[serde(default)]
+    content: Option<String>,
+}
+
+/// Send one prompt to the configured model and return its reply text
+pub async fn complete(prompt: &str, config: &OpenAiSettings) -> Result<String, String> {
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(config.timeout_secs))
+        .build()
+        .map_err(|e| e.to_string())?;
+    let response = client
+        .post(format!("{}/chat/completions", config.base_url()))
+        .bearer_auth(config.api_key.trim())
+        .json(&serde_json::json!({
+            "model": config.model.trim(),
+            "messages": [{ "role": "user", "content": prompt }],
+            // The same memo should give the same tasks
+            "temperature": 0,
+        }))
+        .send()
+        .await
+        .map_err(|e| crate::i18n::tf("error.openai_unreachable", &[("url", &config.base_url()), ("error", &e)]))?;
+
+    match response.status() {
+        reqwest::StatusCode::UNAUTHORIZED => return Err(crate::i18n::t("error.openai_key")),
+        status if !status.is_success() => {
+            let error_body = response.text().await.unwrap_or_else(|_| "No error details".to_string());
+            return Err(format!("OpenAI API error {}: {}", status, error_body));
+        }
+        _ => {}
+    }
+    let completion: Completion = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse OpenAI response: {}", e))?;
+    completion
+        .choices
+        .into_iter()
+        .next()
+        .and_then(|choice| choice.message.content)
+        .ok_or_else(|| "OpenAI returned no answer".to_string())
+}
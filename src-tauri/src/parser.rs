@@ -0,0 +1,195 @@
+// Parser module
+// Turning a transcript into task actions. Each way of doing it (the built-in
+// rules, an Ollama model, an OpenAI model) is a `TranscriptParser`, and the
+// settings put them in a chain (`Settings::parser_chain`): the first one that
+// answers wins, so an LLM that's down or slow falls through to the next one,
+// usually the rules. The result says which parser it came from.
+
+use crate::database::Task;
+use crate::punctuation::Punctuator;
+use crate::settings::{OllamaSettings, OpenAiSettings, Settings};
+use crate::timer_control::TimerCommand;
+use futures_util::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+/// The parsers a chain can be built from
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ParserKind {
+    /// Keyword rules (`ollama::parse_transcript_simple`); instant and offline
+    #[default]
+    Rules,
+    Ollama,
+    OpenAi,
+}
+
+impl ParserKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ParserKind::Rules => "rules",
+            ParserKind::Ollama => "ollama",
+            ParserKind::OpenAi => "openai",
+        }
+    }
+}
+
+/// What a voice memo asks for: tasks to add or complete, and maybe a change to the timer
+#[derive(Debug, Clone, Default)]
+pub struct ParsedMemo {
+    pub tasks: Vec<Task>,
+    pub timer: Option<TimerCommand>,
+    /// The parser that produced these actions
+    pub parser: ParserKind,
+}
+
+/// One way of turning a transcript into a `ParsedMemo`
+pub trait TranscriptParser: Send + Sync {
+    fn kind(&self) -> ParserKind;
+
+    /// Parse a transcript. `instructions` are the active profile's additions
+    /// to an LLM prompt; parsers without a prompt ignore them.
+    fn parse<'a>(&'a self, transcript: &'a str, instructions: Option<&'a str>) -> BoxFuture<'a, Result<ParsedMemo, String>>;
+}
+
+pub struct RuleBased;
+
+impl TranscriptParser for RuleBased {
+    fn kind(&self) -> ParserKind {
+        ParserKind::Rules
+    }
+
+    fn parse<'a>(&'a self, transcript: &'a str, _instructions: Option<&'a str>) -> BoxFuture<'a, Result<ParsedMemo, String>> {
+        Box::pin(async move {
+            Ok(ParsedMemo {
+                tasks: crate::ollama::parse_transcript_simple(transcript),
+                // Timer commands the rules would know were caught by `timer_control` already
+                timer: None,
+                parser: ParserKind::Rules,
+            })
+        })
+    }
+}
+
+pub struct OllamaParser(pub OllamaSettings);
+
+impl TranscriptParser for OllamaParser {
+    fn kind(&self) -> ParserKind {
+        ParserKind::Ollama
+    }
+
+    fn parse<'a>(&'a self, transcript: &'a str, instructions: Option<&'a str>) -> BoxFuture<'a, Result<ParsedMemo, String>> {
+        Box::pin(crate::ollama::parse_memo(transcript, &self.0, instructions))
+    }
+}
+
+pub struct OpenAiParser(pub OpenAiSettings);
+
+impl TranscriptParser for OpenAiParser {
+    fn kind(&self) -> ParserKind {
+        ParserKind::OpenAi
+    }
+
+    fn parse<'a>(&'a self, transcript: &'a str, instructions: Option<&'a str>) -> BoxFuture<'a, Result<ParsedMemo, String>> {
+        Box::pin(async move {
+            let prompt = crate::ollama::memo_prompt(transcript, instructions);
+            let reply = crate::openai::complete(&prompt, &self.0).await?;
+            crate::ollama::memo_from_reply(&reply, ParserKind::OpenAi)
+        })
+    }
+}
+
+/// How one parser in a chain did, for the usage metrics
+#[derive(Debug, Clone, Copy)]
+pub struct ParseAttempt {
+    pub parser: ParserKind,
+    pub elapsed: Duration,
+    pub succeeded: bool,
+}
+
+/// Parsers to try in order, with the profile instructions they get and how
+/// a run-on transcript is punctuated before the rules split it
+pub struct ParserChain {
+    parsers: Vec<Box<dyn TranscriptParser>>,
+    instructions: Option<String>,
+    punctuator: Punctuator,
+}
+
+impl ParserChain {
+    pub fn new(parsers: Vec<Box<dyn TranscriptParser>>, instructions: Option<String>) -> Self {
+        Self {
+            parsers,
+            instructions,
+            punctuator: Punctuator::default(),
+        }
+    }
+
+    /// The chain in the settings. Ollama and OpenAI are left out while they're
+    /// turned off; with nothing left, the rules parse on their own.
+    pub fn from_settings(settings: &Settings) -> Self {
+        let mut parsers: Vec<Box<dyn TranscriptParser>> = settings
+            .parser_chain
+            .iter()
+            .filter_map(|kind| -> Option<Box<dyn TranscriptParser>> {
+                match kind {
+                    ParserKind::Rules => Some(Box::new(RuleBased)),
+                    ParserKind::Ollama => settings
+                        .ollama_enabled
+                        .then(|| Box::new(OllamaParser(settings.ollama.clone())) as Box<dyn TranscriptParser>),
+                    ParserKind::OpenAi => settings
+                        .openai
+                        .enabled
+                        .then(|| Box::new(OpenAiParser(settings.openai.clone())) as Box<dyn TranscriptParser>),
+                }
+            })
+            .collect();
+        if parsers.is_empty() {
+            parsers.push(Box::new(RuleBased));
+        }
+        Self {
+            punctuator: Punctuator::from_settings(settings),
+            ..Self::new(parsers, settings.profile_prompt().map(str::to_string))
+        }
+    }
+
+    /// Parse with the first parser that succeeds; if none does, the last error
+    pub async fn parse(&self, transcript: &str) -> Result<ParsedMemo, String> {
+        self.parse_timed(transcript).await.0
+    }
+
+    /// `parse`, along with how long each parser tried took
+    pub async fn parse_timed(&self, transcript: &str) -> (Result<ParsedMemo, String>, Vec<ParseAttempt>) {
+        let mut attempts = Vec::new();
+        let mut last_error = None;
+        for parser in &self.parsers {
+            let kind = parser.kind();
+            // The LLMs cope with run-on text; only the rules need it punctuated
+            let punctuated;
+            let transcript = if kind == ParserKind::Rules {
+                punctuated = self.punctuator.restore(transcript).await;
+                punctuated.as_str()
+            } else {
+                transcript
+            };
+            let started = Instant::now();
+            let result = parser.parse(transcript, self.instructions.as_deref()).await;
+            attempts.push(ParseAttempt {
+                parser: kind,
+                elapsed: started.elapsed(),
+                succeeded: result.is_ok(),
+            });
+            match result {
+                Ok(parsed) => {
+                    tracing::info!("Parsed transcript with {}", kind.as_str());
+                    return (Ok(parsed), attempts);
+                }
+                Err(e) => {
+                    tracing::warn!("{} parser failed: {}", kind.as_str(), e);
+                    last_error = Some(e);
+                }
+            }
+        }
+        let error = last_error.unwrap_or_else(|| "No transcript parser configured".to_string());
+        (Err(error), attempts)
+    }
+}
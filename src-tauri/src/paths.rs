@@ -0,0 +1,79 @@
+// Paths module
+// Where FlowState keeps its data: the database, the config file, Whisper
+// models, logs and crash reports. Normally that's `<data dir>/flowstate` (and
+// the Tauri app-data folder for the Tauri build's database). `--data-dir
+// <path>` or FLOWSTATE_DATA_DIR puts all of it in one folder instead, for a
+// portable install on a USB stick or a separate test profile. A relative path
+// is taken from the folder the executable is in, so it moves with the install.
+
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+pub const FLAG: &str = "--data-dir";
+pub const ENV_VAR: &str = "FLOWSTATE_DATA_DIR";
+
+static DATA_DIR: OnceLock<Option<PathBuf>> = OnceLock::new();
+
+/// Take `--data-dir <path>` (or `--data-dir=<path>`) out of the command line,
+/// falling back to FLOWSTATE_DATA_DIR, and return the other arguments. Call it
+/// first thing in `main`, before anything looks up a path.
+pub fn init(args: impl IntoIterator<Item = String>) -> Vec<String> {
+    let mut rest = Vec::new();
+    let mut flag = None;
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        if arg == FLAG {
+            flag = args.next();
+        } else if let Some(value) = arg.strip_prefix(FLAG).and_then(|value| value.strip_prefix('=')) {
+            flag = Some(value.to_string());
+        } else {
+            rest.push(arg);
+        }
+    }
+    let dir = flag.map(OsString::from).or_else(env_dir).map(resolve);
+    if DATA_DIR.set(dir).is_err() {
+        tracing::warn!("Data directory was looked up before {} was read", FLAG);
+    }
+    rest
+}
+
+/// The folder given with `--data-dir` or FLOWSTATE_DATA_DIR, if any
+pub fn data_dir_override() -> Option<&'static Path> {
+    DATA_DIR.get_or_init(|| env_dir().map(resolve)).as_deref()
+}
+
+/// `<data dir>/flowstate`, or the folder given with `--data-dir`
+pub fn data_dir() -> PathBuf {
+    match data_dir_override() {
+        Some(dir) => dir.to_path_buf(),
+        None => dirs::data_dir().unwrap_or_else(|| PathBuf::from(".")).join("flowstate"),
+    }
+}
+
+/// The Tauri build's data folder: its app-data folder unless overridden
+#[cfg(feature = "tauri-ui")]
+pub fn app_data_dir<R: tauri::Runtime>(app: &tauri::AppHandle<R>) -> tauri::Result<PathBuf> {
+    use tauri::Manager;
+
+    match data_dir_override() {
+        Some(dir) => Ok(dir.to_path_buf()),
+        None => app.path().app_data_dir(),
+    }
+}
+
+fn env_dir() -> Option<OsString> {
+    std::env::var_os(ENV_VAR).filter(|value| !value.is_empty())
+}
+
+fn resolve(dir: OsString) -> PathBuf {
+    let dir = PathBuf::from(dir);
+    if dir.is_absolute() {
+        return dir;
+    }
+    let exe_dir = std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(Path::to_path_buf))
+        .unwrap_or_default();
+    exe_dir.join(dir)
+}
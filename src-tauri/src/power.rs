@@ -0,0 +1,108 @@
+// Power awareness module
+// Tells whether the machine is running on battery or in a low-power mode, so
+// the egui build can go easy on a laptop: background checks run less often, the
+// Whisper model isn't loaded ahead of the first recording, and transcription
+// uses the tiny model. `settings.power_saving` can force this on or off.
+
+use crate::settings::PowerSaving;
+use std::time::Duration;
+
+/// How much longer background checks wait while saving power
+pub const THROTTLE_FACTOR: u32 = 4;
+/// The model transcription falls back to while saving power
+pub const SAVING_MODEL: &str = "tiny";
+
+/// Where the machine's power comes from right now
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PowerState {
+    pub on_battery: bool,
+    /// Battery saver, Low Power Mode or the "low-power" platform profile
+    pub low_power: bool,
+}
+
+/// Whether to save power: on battery or in a low-power mode unless overridden
+pub fn should_save(mode: PowerSaving) -> bool {
+    match mode {
+        PowerSaving::Auto => {
+            let state = power_state();
+            state.on_battery || state.low_power
+        }
+        PowerSaving::Always => true,
+        PowerSaving::Never => false,
+    }
+}
+
+/// `interval` stretched by `THROTTLE_FACTOR` while saving power
+pub fn throttled(interval: Duration, saving: bool) -> Duration {
+    if saving {
+        interval * THROTTLE_FACTOR
+    } else {
+        interval
+    }
+}
+
+/// The model to transcribe with: the tiny one while saving power, if it's installed
+pub fn transcription_model<'a>(selected: &'a str, saving: bool, tiny_installed: bool) -> &'a str {
+    if saving && tiny_installed {
+        SAVING_MODEL
+    } else {
+        selected
+    }
+}
+
+#[cfg(target_os = "windows")]
+pub fn power_state() -> PowerState {
+    use windows::Win32::System::Power::{GetSystemPowerStatus, SYSTEM_POWER_STATUS};
+
+    let mut status = SYSTEM_POWER_STATUS::default();
+    if unsafe { GetSystemPowerStatus(&mut status) }.is_err() {
+        return PowerState::default();
+    }
+    PowerState {
+        // 0 is offline; 255 (unknown) counts as plugged in
+        on_battery: status.ACLineStatus == 0,
+        // Bit 0 is set while battery saver is on
+        low_power: status.SystemStatusFlag & 1 != 0,
+    }
+}
+
+#[cfg(target_os = "linux")]
+pub fn power_state() -> PowerState {
+    let read = |path: std::path::PathBuf| std::fs::read_to_string(path).map(|text| text.trim().to_string()).ok();
+    let supplies: Vec<_> = std::fs::read_dir("/sys/class/power_supply")
+        .map(|entries| entries.filter_map(|entry| entry.ok()).map(|entry| entry.path()).collect())
+        .unwrap_or_default();
+    // Desktops have no battery; a laptop is on battery while no mains supply is online
+    let has_battery = supplies.iter().any(|supply| read(supply.join("type")).as_deref() == Some("Battery"));
+    let on_mains = supplies.iter().any(|supply| {
+        read(supply.join("type")).as_deref() == Some("Mains") && read(supply.join("online")).as_deref() == Some("1")
+    });
+    PowerState {
+        on_battery: has_battery && !on_mains,
+        low_power: read("/sys/firmware/acpi/platform_profile".into()).as_deref() == Some("low-power"),
+    }
+}
+
+#[cfg(target_os = "macos")]
+pub fn power_state() -> PowerState {
+    let pmset = |args: &[&str]| {
+        std::process::Command::new("pmset")
+            .args(args)
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+            .map(|output| String::from_utf8_lossy(&output.stdout).to_string())
+            .unwrap_or_default()
+    };
+    PowerState {
+        on_battery: pmset(&["-g", "batt"]).contains("'Battery Power'"),
+        low_power: pmset(&["-g"])
+            .lines()
+            .any(|line| line.split_whitespace().collect::<Vec<_>>() == ["lowpowermode", "1"]),
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux", target_os = "macos")))]
+pub fn power_state() -> PowerState {
+    PowerState::default()
+}
@@ -0,0 +1,129 @@
+// User presence detection module
+// Reports whether the workstation is locked or the user has been idle, so the
+// awareness timer can pause while nobody is at the keyboard
+
+use std::time::Duration;
+
+/// Default idle time before the user is considered away
+pub const DEFAULT_IDLE_THRESHOLD_MINUTES: u64 = 5;
+
+/// True when the session is locked or there has been no input for `idle_threshold`
+pub fn is_user_away(idle_threshold: Duration) -> bool {
+    away_for(idle_threshold).is_some()
+}
+
+/// How long the user has been away (see `is_user_away`), or None while they're here
+pub fn away_for(idle_threshold: Duration) -> Option<Duration> {
+    let idle = idle_time();
+    if is_session_locked() {
+        // Locking stops input, so the idle time dates the lock
+        return Some(idle.unwrap_or_default());
+    }
+    idle.filter(|idle| *idle >= idle_threshold)
+        .map(|idle| idle - idle_threshold)
+}
+
+#[cfg(target_os = "windows")]
+fn is_session_locked() -> bool {
+    use windows::Win32::Foundation::BOOL;
+    use windows::Win32::System::StationsAndDesktops::{CloseDesktop, OpenInputDesktop, DESKTOP_CONTROL_FLAGS};
+
+    const DESKTOP_SWITCHDESKTOP: u32 = 0x0100;
+
+    // The input desktop can't be opened while the secure (lock) desktop is active
+    unsafe {
+        match OpenInputDesktop(DESKTOP_CONTROL_FLAGS(0), BOOL(0), DESKTOP_SWITCHDESKTOP) {
+            Ok(desktop) => {
+                let _ = CloseDesktop(desktop);
+                false
+            }
+            Err(_) => true,
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn idle_time() -> Option<Duration> {
+    use windows::Win32::System::SystemInformation::GetTickCount;
+    use windows::Win32::UI::Input::KeyboardAndMouse::{GetLastInputInfo, LASTINPUTINFO};
+
+    unsafe {
+        let mut info = LASTINPUTINFO {
+            cbSize: std::mem::size_of::<LASTINPUTINFO>() as u32,
+            dwTime: 0,
+        };
+        if !GetLastInputInfo(&mut info).as_bool() {
+            return None;
+        }
+        let idle_ms = GetTickCount().wrapping_sub(info.dwTime);
+        Some(Duration::from_millis(idle_ms as u64))
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn logind_session_property(property: &str) -> Option<String> {
+    let session = std::env::var("XDG_SESSION_ID").unwrap_or_else(|_| "self".to_string());
+    let output = std::process::Command::new("loginctl")
+        .args(["show-session", &session, "-p", property, "--value"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+#[cfg(target_os = "linux")]
+fn is_session_locked() -> bool {
+    logind_session_property("LockedHint").as_deref() == Some("yes")
+}
+
+#[cfg(target_os = "linux")]
+fn idle_time() -> Option<Duration> {
+    // logind only exposes a boolean idle hint plus the timestamp it went idle
+    if logind_session_property("IdleHint").as_deref() != Some("yes") {
+        return Some(Duration::ZERO);
+    }
+    let since_usec: u64 = logind_session_property("IdleSinceHint")?.parse().ok()?;
+    let now_usec = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .ok()?
+        .as_micros() as u64;
+    Some(Duration::from_micros(now_usec.saturating_sub(since_usec)))
+}
+
+#[cfg(target_os = "macos")]
+#[link(name = "CoreGraphics", kind = "framework")]
+extern "C" {
+    fn CGEventSourceSecondsSinceLastEventType(source_state_id: i32, event_type: u32) -> f64;
+}
+
+#[cfg(target_os = "macos")]
+fn is_session_locked() -> bool {
+    // Screen lock also stops input events, so idle time covers it
+    false
+}
+
+#[cfg(target_os = "macos")]
+fn idle_time() -> Option<Duration> {
+    const COMBINED_SESSION_STATE: i32 = 0;
+    const ANY_INPUT_EVENT_TYPE: u32 = u32::MAX;
+    let seconds = unsafe {
+        CGEventSourceSecondsSinceLastEventType(COMBINED_SESSION_STATE, ANY_INPUT_EVENT_TYPE)
+    };
+    if seconds.is_finite() && seconds >= 0.0 {
+        Some(Duration::from_secs_f64(seconds))
+    } else {
+        None
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux", target_os = "macos")))]
+fn is_session_locked() -> bool {
+    false
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux", target_os = "macos")))]
+fn idle_time() -> Option<Duration> {
+    None
+}
@@ -0,0 +1,123 @@
+// Processing module
+// One voice job at a time. A recording, import or journal answer that comes in
+// while another is being transcribed waits its turn (up to `MAX_QUEUED` of
+// them), and the same audio sent again while the first copy is still pending (a
+// double-tapped hotkey) is refused, so overlapping requests can't race each
+// other into duplicate tasks.
+
+use flowstate_core::error::FlowStateError;
+use serde::Serialize;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::sync::{Mutex, MutexGuard};
+use std::time::Instant;
+
+/// Jobs allowed to wait behind the one being processed
+pub const MAX_QUEUED: usize = 2;
+
+/// Managed state every voice job goes through (see `begin`)
+#[derive(Default)]
+pub struct ProcessingState {
+    // Held by the job being processed; tokio's mutex hands it out in arrival order
+    turn: tokio::sync::Mutex<()>,
+    jobs: Mutex<Jobs>,
+}
+
+#[derive(Default)]
+struct Jobs {
+    next_id: u64,
+    active: Option<Job>,
+    waiting: Vec<Job>,
+}
+
+struct Job {
+    id: u64,
+    source: &'static str,
+    fingerprint: Option<u64>,
+    started: Instant,
+}
+
+/// What `get_processing_status` reports
+#[derive(Debug, Clone, Serialize)]
+pub struct ProcessingStatus {
+    pub busy: bool,
+    /// What's being processed: "recording", "import", "journal", "telegram" or "text"
+    pub source: Option<String>,
+    pub elapsed_secs: Option<u64>,
+    /// Jobs waiting their turn
+    pub queued: usize,
+}
+
+/// A job's place in line; it has the turn once `begin` returns and gives it
+/// up when dropped
+pub struct ProcessingTicket<'a> {
+    state: &'a ProcessingState,
+    id: u64,
+    _turn: Option<tokio::sync::MutexGuard<'a, ()>>,
+}
+
+impl ProcessingState {
+    /// Wait for this job's turn. `fingerprint` (see `fingerprint`) identifies
+    /// the audio, so a second copy of it is refused instead of queued.
+    pub async fn begin(&self, source: &'static str, fingerprint: Option<u64>) -> Result<ProcessingTicket<'_>, FlowStateError> {
+        let mut ticket = {
+            let mut jobs = self.lock();
+            let mut pending = jobs.active.iter().chain(&jobs.waiting);
+            if fingerprint.is_some() && pending.any(|job| job.fingerprint == fingerprint) {
+                tracing::info!("Ignoring a {} that is already being processed", source);
+                return Err(FlowStateError::DuplicateRecording);
+            }
+            if jobs.waiting.len() >= MAX_QUEUED {
+                return Err(FlowStateError::ProcessingBusy);
+            }
+            jobs.next_id += 1;
+            let id = jobs.next_id;
+            jobs.waiting.push(Job { id, source, fingerprint, started: Instant::now() });
+            ProcessingTicket { state: self, id, _turn: None }
+        };
+
+        let turn = self.turn.lock().await;
+        let mut jobs = self.lock();
+        if let Some(index) = jobs.waiting.iter().position(|job| job.id == ticket.id) {
+            let mut job = jobs.waiting.remove(index);
+            job.started = Instant::now();
+            jobs.active = Some(job);
+        }
+        drop(jobs);
+        ticket._turn = Some(turn);
+        Ok(ticket)
+    }
+
+    pub fn status(&self) -> ProcessingStatus {
+        let jobs = self.lock();
+        ProcessingStatus {
+            busy: jobs.active.is_some(),
+            source: jobs.active.as_ref().map(|job| job.source.to_string()),
+            elapsed_secs: jobs.active.as_ref().map(|job| job.started.elapsed().as_secs()),
+            queued: jobs.waiting.len(),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Jobs> {
+        self.jobs.lock().unwrap()
+    }
+}
+
+impl Drop for ProcessingTicket<'_> {
+    fn drop(&mut self) {
+        let mut jobs = self.state.lock();
+        if jobs.active.as_ref().is_some_and(|job| job.id == self.id) {
+            jobs.active = None;
+        } else {
+            // Dropped while still waiting
+            jobs.waiting.retain(|job| job.id != self.id);
+        }
+    }
+}
+
+/// Identifies a job's input (the recording's bytes, an imported file's path)
+pub fn fingerprint(input: impl Hash) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    input.hash(&mut hasher);
+    hasher.finish()
+}
@@ -0,0 +1,50 @@
+// Profiles module
+// Switching between modes like "Work" and "Personal". The profiles themselves
+// live in settings (`ProfileSettings`); this is the switch, shared by both
+// UIs, and the "switch to work mode" voice command.
+
+use crate::database::Database;
+use crate::settings::{self, Settings};
+
+// Words that mark a transcript as a profile switch rather than a task
+const MODE_WORDS: [&str; 4] = ["mode", "profile", "режим", "профиль"];
+
+/// Make `name` the active profile, or go back to the plain settings with None
+pub fn switch_profile(db: &Database, name: Option<&str>) -> Result<Settings, String> {
+    let mut settings = settings::load(db);
+    settings.profiles.active = match name.map(str::trim).filter(|name| !name.is_empty()) {
+        Some(name) => {
+            let profile = settings
+                .profiles
+                .find(name)
+                .ok_or_else(|| crate::i18n::tf("error.profile_unknown", &[("name", &name)]))?;
+            Some(profile.name.clone())
+        }
+        None => None,
+    };
+    settings::save(db, &settings)?;
+    tracing::info!("Profile: {}", settings.profiles.active.as_deref().unwrap_or("none"));
+    Ok(settings)
+}
+
+/// The profile named in "switch to work mode" (also "switch to work profile",
+/// "switch profile to work", "переключись на режим работа"), or None for any
+/// other transcript
+pub fn profile_command(transcript: &str) -> Option<String> {
+    const PREFIXES: [&str; 5] = ["switch profile to", "switch to", "переключись на", "переключи на", "включи"];
+    let text = transcript.trim().trim_end_matches(['.', '!', '?']).to_lowercase();
+    let rest = PREFIXES.iter().find_map(|prefix| text.strip_prefix(prefix))?;
+    let mut words: Vec<&str> = rest.split_whitespace().collect();
+    // "switch profile to" names the profile outright; otherwise a mode word is required
+    let named_outright = text.starts_with("switch profile to");
+    let mode_word = |word: &&str| MODE_WORDS.contains(&word.trim_matches(',').trim());
+    if words.last().is_some_and(mode_word) {
+        words.pop();
+    } else if words.first().is_some_and(mode_word) {
+        words.remove(0);
+    } else if !named_outright {
+        return None;
+    }
+    let name = words.join(" ");
+    (!name.is_empty()).then_some(name)
+}
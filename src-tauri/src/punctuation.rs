@@ -0,0 +1,166 @@
+// Punctuation module
+// Whisper's smaller models often hand back one lowercase run-on sentence ("buy
+// milk then call mom also pick up the kids"), and the rules parser, which
+// splits a memo on commas and full stops, reads that as a single task. Before
+// the rules parse such a transcript, `Punctuator` puts the sentence breaks
+// back: at connectives that start a new thought ("then", "also", "after
+// that", "потом", "а ещё"), which are dropped, with each sentence capitalized.
+// With Ollama on, it can ask the model to punctuate instead, and keeps the
+// answer only if the model changed nothing but punctuation and case.
+// Transcripts that already have punctuation are left as they are.
+
+use crate::settings::{OllamaSettings, Settings};
+
+// Phrases that start a new sentence in a run-on memo, longer ones first so
+// "and then" wins over "then"
+const BREAKS: [&str; 14] = [
+    "and after that",
+    "after that",
+    "and then",
+    "and also",
+    "oh and",
+    "then",
+    "also",
+    "после этого",
+    "а потом",
+    "и потом",
+    "а ещё",
+    "и ещё",
+    "потом",
+    "затем",
+];
+
+/// How a parser chain restores punctuation before the rules parse
+#[derive(Debug, Clone, Default)]
+pub struct Punctuator {
+    enabled: bool,
+    /// Ask this Ollama model first
+    ollama: Option<OllamaSettings>,
+}
+
+impl Punctuator {
+    pub fn from_settings(settings: &Settings) -> Self {
+        let enabled = settings.punctuation.enabled;
+        Self {
+            enabled,
+            ollama: (enabled && settings.punctuation.ollama && settings.ollama_enabled)
+                .then(|| settings.ollama.clone()),
+        }
+    }
+
+    /// The transcript with its sentence breaks restored, or unchanged when it's
+    /// punctuated already (or restoring is off)
+    pub async fn restore(&self, transcript: &str) -> String {
+        if !self.enabled || !is_unpunctuated(transcript) {
+            return transcript.to_string();
+        }
+        if let Some(config) = &self.ollama {
+            match restore_with_ollama(transcript, config).await {
+                Ok(restored) => return restored,
+                Err(e) => tracing::warn!("Falling back to rule-based punctuation: {}", e),
+            }
+        }
+        restore(transcript)
+    }
+}
+
+/// Rule-based restoration: break the transcript into sentences at the
+/// connectives and capitalize them. Without a connective it comes back as is.
+pub fn restore(transcript: &str) -> String {
+    let text = transcript.trim();
+    if !is_unpunctuated(text) {
+        return text.to_string();
+    }
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let mut sentences: Vec<Vec<&str>> = vec![Vec::new()];
+    let mut index = 0;
+    while index < words.len() {
+        // Only between two pieces of a memo: "then" at either end stays
+        let length = BREAKS
+            .iter()
+            .map(|phrase| phrase.split(' ').collect::<Vec<_>>())
+            .find(|phrase| starts_with(&words[index..], phrase))
+            .map(|phrase| phrase.len())
+            .filter(|&length| index > 0 && index + length < words.len());
+        match length {
+            Some(length) => {
+                sentences.push(Vec::new());
+                index += length;
+            }
+            None => {
+                sentences.last_mut().unwrap().push(words[index]);
+                index += 1;
+            }
+        }
+    }
+    if sentences.len() < 2 {
+        return text.to_string();
+    }
+    let sentences: Vec<String> = sentences
+        .iter()
+        .filter(|sentence| !sentence.is_empty())
+        .map(|sentence| {
+            let words: Vec<String> = sentence.iter().map(|word| fix_case(word)).collect();
+            capitalize(&words.join(" "))
+        })
+        .collect();
+    let mut restored = sentences.join(". ");
+    if !restored.ends_with(['.', '!', '?']) {
+        restored.push('.');
+    }
+    restored
+}
+
+// No sentence punctuation before the final mark
+fn is_unpunctuated(text: &str) -> bool {
+    let text = text.trim().trim_end_matches(['.', '!', '?']);
+    !text.is_empty() && !text.contains(['.', ',', ';', '!', '?'])
+}
+
+fn starts_with(words: &[&str], phrase: &[&str]) -> bool {
+    words.len() >= phrase.len()
+        && words
+            .iter()
+            .zip(phrase)
+            .all(|(word, part)| word.to_lowercase() == *part)
+}
+
+// "i" and "i'm" are always capitals
+fn fix_case(word: &str) -> String {
+    if word == "i" || word.starts_with("i'") {
+        capitalize(word)
+    } else {
+        word.to_string()
+    }
+}
+
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+async fn restore_with_ollama(transcript: &str, config: &OllamaSettings) -> Result<String, String> {
+    let prompt = format!(
+        "Add punctuation and capitalization to this voice note transcript, ending each separate thought \
+         with a full stop. Do not add, remove, reorder or change any words. Reply with the punctuated text \
+         only.\n\nTranscript: {}",
+        transcript.trim()
+    );
+    let reply = crate::ollama::generate(&prompt, config).await?;
+    let restored = reply.trim().trim_matches('"').trim();
+    // The model may only have touched punctuation and case
+    if words(restored) != words(transcript) {
+        return Err("the model changed the words".to_string());
+    }
+    Ok(restored.to_string())
+}
+
+fn words(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric() && c != '\'')
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
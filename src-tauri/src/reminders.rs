@@ -0,0 +1,328 @@
+// Reminders module
+// One reminder per task, fired as an OS notification at a set local time.
+// Times come from the `set_reminder` command or from a voice memo ("remind me
+// to call mom at 6pm", "напомни завтра в 9 купить хлеб") and are stored in UTC
+// like every other timestamp. Each UI runs the scheduling itself (the Tauri
+// scheduler task, the egui update loop); this module only decides what's due.
+
+use crate::database::{self, Database, Reminder};
+use chrono::{Datelike, Duration, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+
+const DB_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+// Local form shown in the UIs and accepted back by `set_reminder`
+pub const LOCAL_FORMAT: &str = "%Y-%m-%d %H:%M";
+// "remind me tomorrow" with no clock time
+const MORNING_HOUR: u32 = 9;
+// "remind me tonight" with no clock time
+const EVENING_HOUR: u32 = 20;
+// Longest time phrase tried at either end of a voice reminder, in words
+const MAX_TIME_WORDS: usize = 6;
+
+const PREFIXES: [&str; 4] = ["remind me to", "remind me", "напомни мне", "напомни"];
+// Words left between the time and the task ("remind me at 6 to call mom")
+const CONNECTORS: [&str; 4] = ["to", "that", "что", "чтобы"];
+
+#[derive(Clone, Copy, PartialEq)]
+enum Day {
+    Today,
+    Tonight,
+    Tomorrow,
+}
+
+/// Set the task's reminder from a local time: "2024-05-01 18:30" or a phrase
+/// like "6pm", "tomorrow at 9", "in 20 minutes". Returns the local time it's set for.
+pub fn set_reminder(db: &Database, task_id: i64, when: &str) -> Result<NaiveDateTime, String> {
+    let at = parse_when(when, Local::now().naive_local())
+        .ok_or_else(|| crate::i18n::tf("error.reminder_time", &[("when", &when.trim())]))?;
+    set_reminder_at(db, task_id, at)?;
+    Ok(at)
+}
+
+/// Set the task's reminder for a local time that's already been parsed
+pub fn set_reminder_at(db: &Database, task_id: i64, at: NaiveDateTime) -> Result<(), String> {
+    if at <= Local::now().naive_local() {
+        return Err(crate::i18n::t("error.reminder_past"));
+    }
+    database::get_task_by_id(db, task_id).map_err(|e| e.to_string())?;
+    let utc = to_utc(at).ok_or_else(|| crate::i18n::tf("error.reminder_time", &[("when", &at)]))?;
+    database::set_reminder(db, task_id, &utc.format(DB_FORMAT).to_string()).map_err(|e| e.to_string())?;
+    tracing::info!("Reminder for task {} at {}", task_id, at.format(LOCAL_FORMAT));
+    Ok(())
+}
+
+pub fn clear_reminder(db: &Database, task_id: i64) -> Result<(), String> {
+    database::clear_reminder(db, task_id).map_err(|e| e.to_string())
+}
+
+/// Pending reminders, soonest first, with `remind_at` in local time (`LOCAL_FORMAT`)
+pub fn get_reminders(db: &Database) -> Result<Vec<Reminder>, String> {
+    let reminders = database::pending_reminders(db).map_err(|e| e.to_string())?;
+    Ok(reminders
+        .into_iter()
+        .map(|reminder| Reminder {
+            remind_at: local_label(&reminder.remind_at).unwrap_or(reminder.remind_at),
+            ..reminder
+        })
+        .collect())
+}
+
+/// Reminders due now; each is returned once and then counts as fired
+pub fn take_due(db: &Database) -> Vec<Reminder> {
+    let now = Utc::now().naive_utc().format(DB_FORMAT).to_string();
+    database::take_due_reminders(db, &now).unwrap_or_else(|e| {
+        tracing::warn!("Failed to check reminders: {}", e);
+        Vec::new()
+    })
+}
+
+/// Move reminders on open tasks that have gone by (fired or not) to the same
+/// local time on the next day still ahead, so unfinished tasks come up again.
+/// Returns how many moved.
+pub fn roll_over(db: &Database) -> Result<usize, String> {
+    let now = Local::now().naive_local();
+    let utc_now = Utc::now().naive_utc().format(DB_FORMAT).to_string();
+    let overdue = database::overdue_reminders(db, &utc_now).map_err(|e| e.to_string())?;
+    let mut moved = 0;
+    for reminder in overdue {
+        let Some(mut at) = local_label(&reminder.remind_at)
+            .and_then(|local| NaiveDateTime::parse_from_str(&local, LOCAL_FORMAT).ok())
+        else {
+            continue;
+        };
+        while at <= now {
+            at += Duration::days(1);
+        }
+        let Some(utc) = to_utc(at) else {
+            continue;
+        };
+        database::set_reminder(db, reminder.task_id, &utc.format(DB_FORMAT).to_string()).map_err(|e| e.to_string())?;
+        moved += 1;
+    }
+    Ok(moved)
+}
+
+/// Time until the next pending reminder, None if there isn't one
+pub fn next_due_in(db: &Database) -> Option<std::time::Duration> {
+    let next = database::pending_reminders(db).ok()?.into_iter().next()?;
+    let at = NaiveDateTime::parse_from_str(&next.remind_at, DB_FORMAT).ok()?;
+    Some((at - Utc::now().naive_utc()).to_std().unwrap_or_default())
+}
+
+pub fn notification_body(reminder: &Reminder) -> String {
+    crate::i18n::tf("reminders.notification", &[("task", &reminder.task_text)])
+}
+
+/// Short local label for a reminder time: "18:30" today, "Tu 09:00" otherwise
+pub fn when_label(at: NaiveDateTime) -> String {
+    if at.date() == Local::now().date_naive() {
+        at.format("%H:%M").to_string()
+    } else {
+        format!("{} {}", crate::dates::weekday_name(at.weekday()), at.format("%H:%M"))
+    }
+}
+
+pub(crate) fn to_utc(at: NaiveDateTime) -> Option<NaiveDateTime> {
+    Local.from_local_datetime(&at).earliest().map(|local| local.with_timezone(&Utc).naive_utc())
+}
+
+fn local_label(utc: &str) -> Option<String> {
+    let at = NaiveDateTime::parse_from_str(utc, DB_FORMAT).ok()?;
+    Some(Utc.from_utc_datetime(&at).with_timezone(&Local).format(LOCAL_FORMAT).to_string())
+}
+
+/// The task and local time in "remind me to call mom at 6pm" (the time may
+/// also come first: "remind me tomorrow at 9 to call mom"), or None for any
+/// other transcript, including a reminder with no time we can read
+pub fn reminder_command(transcript: &str) -> Option<(String, NaiveDateTime)> {
+    let text = transcript.trim().trim_end_matches(['.', '!', '?']);
+    let rest = PREFIXES.iter().find_map(|prefix| strip_prefix_ci(text, prefix))?;
+    let words: Vec<&str> = rest.split_whitespace().collect();
+    let now = Local::now().naive_local();
+    let max = MAX_TIME_WORDS.min(words.len().saturating_sub(1));
+
+    // Longest time phrase first, so "tomorrow at 6pm" isn't read as just "6pm"
+    let (task, at) = (1..=max)
+        .rev()
+        .find_map(|n| parse_when(&words[words.len() - n..].join(" "), now).map(|at| (&words[..words.len() - n], at)))
+        .or_else(|| (1..=max).rev().find_map(|n| parse_when(&words[..n].join(" "), now).map(|at| (&words[n..], at))))?;
+
+    let mut task = task.to_vec();
+    if task.first().is_some_and(|word| CONNECTORS.contains(&word.to_lowercase().as_str())) {
+        task.remove(0);
+    }
+    let task = task.join(" ").trim_matches(',').trim().to_string();
+    let mut chars = task.chars();
+    let first = chars.next()?;
+    Some((first.to_uppercase().chain(chars).collect(), at))
+}
+
+// `text` without `prefix` (matched case-insensitively, as whole words)
+fn strip_prefix_ci<'a>(text: &'a str, prefix: &str) -> Option<&'a str> {
+    let head = text.get(..prefix.len())?;
+    let rest = &text[prefix.len()..];
+    (head.to_lowercase() == prefix && (rest.is_empty() || rest.starts_with([' ', ',']))).then(|| rest.trim_start_matches([' ', ',']))
+}
+
+/// Local time for a phrase like "6pm", "at 18:30", "tomorrow at 9",
+/// "in 20 minutes", "через полчаса", "завтра в 7 вечера", or an explicit
+/// "YYYY-MM-DD HH:MM". A clock time that has passed today means tomorrow.
+pub fn parse_when(text: &str, now: NaiveDateTime) -> Option<NaiveDateTime> {
+    let text = text.trim().trim_end_matches(['.', '!', '?', ',']).to_lowercase();
+    for format in [LOCAL_FORMAT, "%Y-%m-%dT%H:%M", "%Y-%m-%dT%H:%M:%S", DB_FORMAT] {
+        if let Ok(at) = NaiveDateTime::parse_from_str(&text, format) {
+            return Some(at);
+        }
+    }
+
+    let words: Vec<&str> = text.split_whitespace().map(|word| word.trim_matches(',')).collect();
+    match words.first() {
+        Some(&"in") | Some(&"через") => return parse_duration(&words[1..]).map(|duration| now + duration),
+        None => return None,
+        _ => {}
+    }
+
+    let mut day = None;
+    let mut at_word = false;
+    let mut clock = Vec::new();
+    for word in words {
+        let word_day = match word {
+            "today" | "сегодня" => Some(Day::Today),
+            "tonight" | "вечером" => Some(Day::Tonight),
+            "tomorrow" | "завтра" => Some(Day::Tomorrow),
+            _ => None,
+        };
+        match (word_day, day) {
+            // "сегодня вечером", "today tonight"
+            (Some(Day::Today), Some(Day::Tonight)) | (Some(Day::Tonight), Some(Day::Today)) => day = Some(Day::Tonight),
+            (Some(_), Some(_)) => return None,
+            (Some(_), None) => day = word_day,
+            (None, _) if (word == "at" || word == "в") && !at_word && clock.is_empty() => at_word = true,
+            (None, _) => clock.push(word),
+        }
+    }
+
+    let today = now.date();
+    let tomorrow = today.succ_opt()?;
+    if clock.is_empty() {
+        return match day? {
+            Day::Tomorrow => Some(tomorrow.and_time(NaiveTime::from_hms_opt(MORNING_HOUR, 0, 0)?)),
+            Day::Tonight => Some(today.and_time(NaiveTime::from_hms_opt(EVENING_HOUR, 0, 0)?)).filter(|at| *at > now),
+            Day::Today => None,
+        };
+    }
+
+    let (hour, minute, meridiem) = parse_clock(&clock)?;
+    // A bare "6" is only a time after "at"/"в" or a day word
+    if meridiem.is_none() && minute.is_none() && !at_word && day.is_none() {
+        return None;
+    }
+    let minute = minute.unwrap_or(0);
+    let hour = match (meridiem, day) {
+        (Some(pm), _) => to_24h(hour, pm)?,
+        (None, Some(Day::Tonight)) => to_24h(hour, true)?,
+        _ => hour,
+    };
+    let on = |date: NaiveDate, hour| NaiveTime::from_hms_opt(hour, minute, 0).map(|time| date.and_time(time));
+    // "at 6" said in the afternoon means 18:00 rather than tomorrow morning
+    let ambiguous = meridiem.is_none() && (1..12).contains(&hour);
+    match day {
+        Some(Day::Tomorrow) => on(tomorrow, hour),
+        Some(_) => on(today, hour)
+            .filter(|at| *at > now)
+            .or_else(|| on(today, hour + 12).filter(|at| ambiguous && *at > now)),
+        None => on(today, hour)
+            .filter(|at| *at > now)
+            .or_else(|| on(today, hour + 12).filter(|at| ambiguous && *at > now))
+            .or_else(|| on(tomorrow, hour)),
+    }
+}
+
+// Hour, minutes if given, and Some(true) for pm / Some(false) for am if said
+fn parse_clock(words: &[&str]) -> Option<(u32, Option<u32>, Option<bool>)> {
+    let (number, meridiem) = match words {
+        [single] => match single {
+            &"noon" | &"полдень" => return Some((12, None, None)),
+            &"midnight" | &"полночь" => return Some((0, None, None)),
+            _ => split_meridiem(single),
+        },
+        [number, suffix] => (*number, Some(meridiem_word(suffix)?)),
+        _ => return None,
+    };
+    let (hour, minute) = match number.split_once([':', '.']) {
+        Some((hour, minute)) if minute.len() == 2 => (hour.parse().ok()?, Some(minute.parse().ok()?)),
+        Some(_) => return None,
+        None => (number.parse().ok().or_else(|| word_number(number))?, None),
+    };
+    (hour < 24 && minute.unwrap_or(0) < 60).then_some((hour, minute, meridiem))
+}
+
+// "6pm" → ("6", Some(true)); "18:30" → ("18:30", None)
+fn split_meridiem(word: &str) -> (&str, Option<bool>) {
+    for suffix in ["a.m", "p.m", "am", "pm"] {
+        if let Some(number) = word.trim_end_matches('.').strip_suffix(suffix).filter(|number| !number.is_empty()) {
+            return (number, meridiem_word(suffix));
+        }
+    }
+    (word, None)
+}
+
+fn meridiem_word(word: &str) -> Option<bool> {
+    match word.trim_end_matches('.') {
+        "pm" | "p.m" | "вечера" | "дня" => Some(true),
+        "am" | "a.m" | "утра" | "ночи" => Some(false),
+        _ => None,
+    }
+}
+
+fn to_24h(hour: u32, pm: bool) -> Option<u32> {
+    match (hour, pm) {
+        (1..=11, true) => Some(hour + 12),
+        (12, false) => Some(0),
+        (0..=12, _) => Some(hour),
+        _ => None,
+    }
+}
+
+// "20 minutes", "an hour", "half an hour", "2 hours", "3 days", "a week",
+// "через 5 минут", "полчаса", "через неделю"
+pub(crate) fn parse_duration(words: &[&str]) -> Option<Duration> {
+    match words {
+        ["half", "an", "hour"] | ["полчаса"] => return Some(Duration::minutes(30)),
+        ["an", "hour"] | ["a", "hour"] | ["час"] => return Some(Duration::hours(1)),
+        ["a", "minute"] | ["минуту"] => return Some(Duration::minutes(1)),
+        ["a", "day"] | ["день"] => return Some(Duration::days(1)),
+        ["a", "week"] | ["неделю"] => return Some(Duration::weeks(1)),
+        _ => {}
+    }
+    let [count, unit] = words else {
+        return None;
+    };
+    let count: i64 = count.parse().ok().or_else(|| word_number(count).map(i64::from))?;
+    if count <= 0 {
+        return None;
+    }
+    let unit = unit.trim_end_matches('.');
+    if unit.starts_with("min") || unit.starts_with("мин") {
+        Some(Duration::minutes(count))
+    } else if unit.starts_with("hour") || unit.starts_with("hr") || unit.starts_with("час") {
+        Some(Duration::hours(count))
+    } else if unit.starts_with("day") || unit.starts_with("дн") || unit == "день" {
+        Some(Duration::days(count))
+    } else if unit.starts_with("week") || unit.starts_with("недел") {
+        Some(Duration::weeks(count))
+    } else {
+        None
+    }
+}
+
+// Numbers Whisper sometimes spells out
+fn word_number(word: &str) -> Option<u32> {
+    const NUMBERS: [(&str, u32); 28] = [
+        ("one", 1), ("two", 2), ("three", 3), ("four", 4), ("five", 5), ("six", 6),
+        ("seven", 7), ("eight", 8), ("nine", 9), ("ten", 10), ("eleven", 11), ("twelve", 12),
+        ("fifteen", 15), ("twenty", 20), ("thirty", 30), ("forty", 40),
+        ("один", 1), ("одну", 1), ("два", 2), ("две", 2), ("три", 3), ("четыре", 4),
+        ("пять", 5), ("шесть", 6), ("десять", 10), ("пятнадцать", 15), ("двадцать", 20), ("тридцать", 30),
+    ];
+    NUMBERS.iter().find(|(name, _)| *name == word).map(|(_, number)| *number)
+}
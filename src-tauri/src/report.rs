@@ -0,0 +1,276 @@
+// Report module
+// Weekly review: completed tasks, focus sessions, checkpoint responses and
+// transcription counts for one Monday-to-Sunday week, written as a Markdown
+// file named after the ISO week (and optionally rendered to PDF next to it).
+// Generating the same week again overwrites the earlier file.
+
+use crate::database::{self, Database, Task, TimeEntry};
+use crate::i18n::{t, tf};
+use crate::settings::ReportSettings;
+use chrono::{Datelike, Duration, Local, NaiveDate, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+// Tasks listed under "Focus time by task"
+const MAX_FOCUS_TASKS: usize = 10;
+
+/// Files written for one report
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WeeklyReport {
+    pub markdown_path: String,
+    pub pdf_path: Option<String>,
+}
+
+/// Write the report for the week `weeks_ago` weeks back (0 is the current
+/// week, up to now) into the configured folder
+pub fn generate_weekly_report(db: &Database, config: &ReportSettings, weeks_ago: u32) -> Result<WeeklyReport, String> {
+    config.validate()?;
+    let today = Local::now().date_naive();
+    let monday = today - Duration::days(today.weekday().num_days_from_monday() as i64) - Duration::weeks(weeks_ago as i64);
+    let next_monday = monday + Duration::weeks(1);
+    let (start, end) = (utc_timestamp(monday), utc_timestamp(next_monday));
+
+    let completed = database::completed_tasks_between(db, &start, &end).map_err(|e| e.to_string())?;
+    let focus = database::time_entries_between(db, &start, &end).map_err(|e| e.to_string())?;
+    let checkpoints = database::checkpoint_counts_between(db, &start, &end).map_err(|e| e.to_string())?;
+    let transcriptions = database::transcription_stats_between(db, &start, &end).map_err(|e| e.to_string())?;
+
+    let markdown = render_markdown(monday, &completed, &focus, checkpoints, &transcriptions);
+
+    let dir = config.output_dir();
+    std::fs::create_dir_all(&dir).map_err(|e| tf("error.report_write", &[("path", &dir.display()), ("error", &e)]))?;
+    let week = monday.iso_week();
+    let base = dir.join(format!("flowstate-week-{}-W{:02}", week.year(), week.week()));
+    let markdown_path = base.with_extension("md");
+    std::fs::write(&markdown_path, &markdown)
+        .map_err(|e| tf("error.report_write", &[("path", &markdown_path.display()), ("error", &e)]))?;
+
+    let pdf_path = if config.pdf {
+        let pdf_path = base.with_extension("pdf");
+        write_pdf(&markdown, &pdf_path).map_err(|e| tf("error.report_write", &[("path", &pdf_path.display()), ("error", &e)]))?;
+        Some(pdf_path.to_string_lossy().to_string())
+    } else {
+        None
+    };
+
+    tracing::info!("Weekly report written to {}", markdown_path.display());
+    Ok(WeeklyReport {
+        markdown_path: markdown_path.to_string_lossy().to_string(),
+        pdf_path,
+    })
+}
+
+// Local midnight of `date` as a UTC timestamp, the format the database stores
+pub(crate) fn utc_timestamp(date: NaiveDate) -> String {
+    let midnight = date.and_hms_opt(0, 0, 0).unwrap_or_default();
+    let local = Local
+        .from_local_datetime(&midnight)
+        .earliest()
+        .map(|local| local.with_timezone(&Utc).naive_utc())
+        .unwrap_or(midnight);
+    local.format("%Y-%m-%d %H:%M:%S").to_string()
+}
+
+// Local date of a UTC timestamp from the database
+fn local_date(timestamp: &str) -> Option<NaiveDate> {
+    let utc = chrono::NaiveDateTime::parse_from_str(timestamp, "%Y-%m-%d %H:%M:%S").ok()?;
+    Some(Utc.from_utc_datetime(&utc).with_timezone(&Local).date_naive())
+}
+
+fn format_duration(seconds: i64) -> String {
+    let minutes = (seconds + 30) / 60;
+    match (minutes / 60, minutes % 60) {
+        (0, minutes) => format!("{}m", minutes),
+        (hours, 0) => format!("{}h", hours),
+        (hours, minutes) => format!("{}h {}m", hours, minutes),
+    }
+}
+
+fn render_markdown(
+    monday: NaiveDate,
+    completed: &[Task],
+    focus: &[TimeEntry],
+    (checkpoints, acknowledged): (i64, i64),
+    transcriptions: &[database::TranscriptionStats],
+) -> String {
+    let sunday = monday + Duration::days(6);
+    let focus_seconds: i64 = focus.iter().map(|entry| entry.duration_seconds).sum();
+    let notes: i64 = transcriptions.iter().map(|stats| stats.count).sum();
+    let words: i64 = transcriptions.iter().map(|stats| stats.words).sum();
+
+    let mut md = String::new();
+    let _ = writeln!(md, "# {}\n", tf("report.title", &[("start", &monday), ("end", &sunday)]));
+
+    let _ = writeln!(md, "## {}\n", t("report.summary"));
+    let _ = writeln!(md, "- {}", tf("report.tasks_completed", &[("count", &completed.len())]));
+    let _ = writeln!(md, "- {}", tf("report.focus_sessions", &[("count", &focus.len()), ("time", &format_duration(focus_seconds))]));
+    let _ = writeln!(md, "- {}", tf("report.checkpoints", &[("answered", &acknowledged), ("count", &checkpoints)]));
+    let _ = writeln!(md, "- {}", tf("report.transcriptions", &[("count", &notes), ("words", &words)]));
+    md.push('\n');
+
+    let _ = writeln!(md, "## {}\n", t("report.completed"));
+    if completed.is_empty() {
+        let _ = writeln!(md, "{}\n", t("report.none"));
+    } else {
+        let mut day = None;
+        for task in completed {
+            let date = task.completed_at.as_deref().and_then(local_date);
+            if date != day {
+                if day.is_some() {
+                    md.push('\n');
+                }
+                if let Some(date) = date {
+                    let _ = writeln!(md, "### {}\n", date.format("%Y-%m-%d (%a)"));
+                }
+                day = date;
+            }
+            let _ = writeln!(md, "- [x] {}", task.text);
+        }
+        md.push('\n');
+    }
+
+    let _ = writeln!(md, "## {}\n", t("report.focus"));
+    if focus.is_empty() {
+        let _ = writeln!(md, "{}\n", t("report.none"));
+    } else {
+        let mut by_task: HashMap<&str, (usize, i64)> = HashMap::new();
+        for entry in focus {
+            let totals = by_task.entry(entry.task_text.as_str()).or_default();
+            totals.0 += 1;
+            totals.1 += entry.duration_seconds;
+        }
+        let mut by_task: Vec<_> = by_task.into_iter().collect();
+        by_task.sort_by(|a, b| b.1 .1.cmp(&a.1 .1).then_with(|| a.0.cmp(b.0)));
+        let _ = writeln!(md, "| {} | {} | {} |", t("report.task"), t("report.sessions"), t("report.time"));
+        md.push_str("|---|---:|---:|\n");
+        for (task, (sessions, seconds)) in by_task.into_iter().take(MAX_FOCUS_TASKS) {
+            let _ = writeln!(md, "| {} | {} | {} |", task.replace('|', "\\|"), sessions, format_duration(seconds));
+        }
+        md.push('\n');
+
+        // Only sessions run with the focus monitor on have switch counts
+        let monitored: Vec<_> = focus.iter().filter_map(|entry| entry.context_switches.as_ref()).collect();
+        if !monitored.is_empty() {
+            let switches: u32 = monitored.iter().map(|switches| switches.switches).sum();
+            let mut apps: HashMap<&str, u64> = HashMap::new();
+            for session in &monitored {
+                for (app, seconds) in &session.top_apps {
+                    *apps.entry(app.as_str()).or_default() += seconds;
+                }
+            }
+            let mut apps: Vec<_> = apps.into_iter().collect();
+            apps.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+            let apps = apps
+                .iter()
+                .take(3)
+                .map(|(app, seconds)| format!("{} ({})", app, format_duration(*seconds as i64)))
+                .collect::<Vec<_>>()
+                .join(", ");
+            let average = switches as f64 / monitored.len() as f64;
+            let _ = writeln!(md, "{}\n", tf("report.context_switches", &[("average", &format!("{:.1}", average)), ("apps", &apps)]));
+        }
+    }
+
+    if !transcriptions.is_empty() {
+        let _ = writeln!(md, "## {}\n", t("report.transcription_stats"));
+        let _ = writeln!(md, "| {} | {} | {} |", t("report.source"), t("report.notes"), t("report.words"));
+        md.push_str("|---|---:|---:|\n");
+        for stats in transcriptions {
+            let source = t(&format!("report.source_{}", stats.source));
+            let _ = writeln!(md, "| {} | {} | {} |", source, stats.count, stats.words);
+        }
+        md.push('\n');
+    }
+
+    md
+}
+
+// A4 page with 20 mm margins
+const PAGE_WIDTH_MM: f32 = 210.0;
+const PAGE_HEIGHT_MM: f32 = 297.0;
+const MARGIN_MM: f32 = 20.0;
+const PT_TO_MM: f32 = 0.3528;
+
+/// Lay the Markdown out as plain text: headings get bigger type, tables become
+/// rows separated by dots, everything else wraps at the margin
+fn write_pdf(markdown: &str, path: &Path) -> Result<(), String> {
+    use printpdf::{BuiltinFont, Mm, PdfDocument};
+
+    let title = markdown.lines().next().unwrap_or_default().trim_start_matches("# ");
+    let (doc, page, layer) = PdfDocument::new(title, Mm(PAGE_WIDTH_MM), Mm(PAGE_HEIGHT_MM), "Report");
+    // The built-in Helvetica only covers Latin text, so prefer a system font
+    let font = match system_font().and_then(|font| std::fs::File::open(font).ok()) {
+        Some(file) => doc.add_external_font(file),
+        None => doc.add_builtin_font(BuiltinFont::Helvetica),
+    }
+    .map_err(|e| e.to_string())?;
+
+    let mut layer = doc.get_page(page).get_layer(layer);
+    let mut y = PAGE_HEIGHT_MM - MARGIN_MM;
+    for line in markdown.lines() {
+        let (text, size) = if let Some(heading) = line.strip_prefix("# ") {
+            (heading.to_string(), 18.0)
+        } else if let Some(heading) = line.strip_prefix("## ") {
+            (heading.to_string(), 14.0)
+        } else if let Some(heading) = line.strip_prefix("### ") {
+            (heading.to_string(), 12.0)
+        } else if line.starts_with("|---") {
+            continue;
+        } else if line.starts_with('|') {
+            let cells: Vec<&str> = line.trim_matches('|').split(" | ").map(str::trim).collect();
+            (cells.join("  ·  ").replace("\\|", "|"), 10.0)
+        } else if let Some(item) = line.strip_prefix("- [x] ").or_else(|| line.strip_prefix("- ")) {
+            (format!("• {}", item), 10.0)
+        } else {
+            (line.to_string(), 10.0)
+        };
+
+        let line_height = size * PT_TO_MM * 1.5;
+        // Rough average glyph width of half the type size
+        let max_chars = ((PAGE_WIDTH_MM - 2.0 * MARGIN_MM) / (size * PT_TO_MM * 0.5)) as usize;
+        for row in wrap(&text, max_chars) {
+            if y - line_height < MARGIN_MM {
+                let (page, new_layer) = doc.add_page(Mm(PAGE_WIDTH_MM), Mm(PAGE_HEIGHT_MM), "Report");
+                layer = doc.get_page(page).get_layer(new_layer);
+                y = PAGE_HEIGHT_MM - MARGIN_MM;
+            }
+            y -= line_height;
+            layer.use_text(row, size, Mm(MARGIN_MM), Mm(y), &font);
+        }
+    }
+
+    let file = std::fs::File::create(path).map_err(|e| e.to_string())?;
+    doc.save(&mut std::io::BufWriter::new(file)).map_err(|e| e.to_string())
+}
+
+// A TrueType font with Cyrillic glyphs, if the system has one of the usual ones
+fn system_font() -> Option<PathBuf> {
+    let windows = std::env::var_os("WINDIR").map(|dir| PathBuf::from(dir).join("Fonts").join("arial.ttf"));
+    windows
+        .into_iter()
+        .chain([
+            PathBuf::from("/System/Library/Fonts/Supplemental/Arial.ttf"),
+            PathBuf::from("/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf"),
+        ])
+        .find(|path| path.is_file())
+}
+
+// Break `text` into rows of at most `width` characters at spaces
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut rows = Vec::new();
+    let mut row = String::new();
+    for word in text.split_whitespace() {
+        if !row.is_empty() && row.chars().count() + 1 + word.chars().count() > width {
+            rows.push(std::mem::take(&mut row));
+        }
+        if !row.is_empty() {
+            row.push(' ');
+        }
+        row.push_str(word);
+    }
+    // Blank lines keep their spacing
+    rows.push(row);
+    rows
+}
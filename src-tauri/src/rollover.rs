@@ -0,0 +1,98 @@
+// Rollover module
+// The end-of-day job. Once the configured time has passed each day, reminders
+// on tasks that are still open move to the next day, completed tasks older than
+// `archive_after_days` are archived out of the lists, and the day's counts come
+// back as a `DailyReview` for the UI to offer. Both UIs call `run_if_due` from
+// their background checks; the date of the last run is kept in the settings
+// table, so a night the app wasn't running for is caught up on the next start.
+
+use crate::database::{self, Database};
+use crate::settings::RolloverSettings;
+use chrono::{Duration, Local, NaiveDate, Timelike, Utc};
+use serde::{Deserialize, Serialize};
+
+const LAST_RUN_KEY: &str = "rollover_last_run";
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// What the day came to, for the "daily review ready" prompt
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DailyReview {
+    /// Local date the review is for, "YYYY-MM-DD"
+    pub date: String,
+    /// Tasks completed that day
+    pub completed: usize,
+    /// Tasks still open
+    pub open: usize,
+    /// Reminders moved to the next day
+    pub rolled_over: usize,
+    /// Completed tasks archived
+    pub archived: usize,
+}
+
+/// Run the rollover if today's (or a missed day's) is due; None otherwise
+pub fn run_if_due(db: &Database, config: &RolloverSettings) -> Option<DailyReview> {
+    if !config.enabled {
+        return None;
+    }
+    let now = Local::now().naive_local();
+    let today = now.date();
+    let yesterday = today.pred_opt()?;
+    let day_over = now.hour() * 60 + now.minute() >= crate::schedule::parse_time(&config.time)?;
+    let last_run = database::get_setting(db, LAST_RUN_KEY)
+        .ok()
+        .flatten()
+        .and_then(|date| NaiveDate::parse_from_str(&date, DATE_FORMAT).ok());
+
+    // Yesterday's run, caught up in the morning, still leaves tonight's to come
+    let date = match last_run {
+        Some(last) if last >= today => return None,
+        _ if day_over => today,
+        Some(last) if last < yesterday => yesterday,
+        _ => return None,
+    };
+    // Recorded first, so a run that fails isn't retried every minute
+    if let Err(e) = database::set_setting(db, LAST_RUN_KEY, &date.format(DATE_FORMAT).to_string()) {
+        tracing::warn!("Failed to record rollover: {}", e);
+        return None;
+    }
+    match run(db, config.archive_after_days, date) {
+        Ok(review) => Some(review),
+        Err(e) => {
+            tracing::warn!("Rollover failed: {}", e);
+            None
+        }
+    }
+}
+
+/// Roll reminders over, archive old completed tasks and count the day
+pub fn run(db: &Database, archive_after_days: u32, date: NaiveDate) -> Result<DailyReview, String> {
+    let rolled_over = crate::reminders::roll_over(db)?;
+    let cutoff = Utc::now().naive_utc() - Duration::days(archive_after_days.into());
+    let archived = database::archive_completed_before(db, &cutoff.format("%Y-%m-%d %H:%M:%S").to_string())
+        .map_err(|e| e.to_string())?;
+    let (start, end) = (crate::report::utc_timestamp(date), crate::report::utc_timestamp(date + Duration::days(1)));
+    let completed = database::completed_tasks_between(db, &start, &end).map_err(|e| e.to_string())?.len();
+    let open = database::count_open_tasks(db).map_err(|e| e.to_string())?;
+
+    tracing::info!("Rollover for {}: {} reminders moved, {} tasks archived", date, rolled_over, archived);
+    Ok(DailyReview {
+        date: date.format(DATE_FORMAT).to_string(),
+        completed,
+        open,
+        rolled_over,
+        archived,
+    })
+}
+
+/// One-line summary for the notification and the native toast
+pub fn summary(review: &DailyReview) -> String {
+    crate::i18n::tf(
+        "rollover.review_ready",
+        &[
+            ("completed", &review.completed),
+            ("open", &review.open),
+            ("rolled", &review.rolled_over),
+            ("archived", &review.archived),
+        ],
+    )
+}
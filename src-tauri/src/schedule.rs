@@ -0,0 +1,85 @@
+// Timer schedule module
+// Working hours and days during which the awareness timer is allowed to run,
+// so it stays quiet in the evening and on weekends
+
+use chrono::{Datelike, Duration, Local, NaiveDateTime, Timelike};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TimerSchedule {
+    pub enabled: bool,
+    /// Active weekdays, 0 = Monday ... 6 = Sunday
+    pub days: Vec<u8>,
+    /// Local start time, "HH:MM"
+    pub start: String,
+    /// Local end time, "HH:MM" (earlier than start means the window runs past midnight)
+    pub end: String,
+}
+
+impl Default for TimerSchedule {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            days: vec![0, 1, 2, 3, 4],
+            start: "09:00".to_string(),
+            end: "18:00".to_string(),
+        }
+    }
+}
+
+impl TimerSchedule {
+    pub fn validate(&self) -> Result<(), String> {
+        let start = parse_time(&self.start)
+            .ok_or_else(|| crate::i18n::tf("error.schedule_start", &[("time", &self.start)]))?;
+        let end = parse_time(&self.end)
+            .ok_or_else(|| crate::i18n::tf("error.schedule_end", &[("time", &self.end)]))?;
+        if start == end {
+            return Err(crate::i18n::t("error.schedule_same_time"));
+        }
+        if let Some(day) = self.days.iter().find(|d| **d > 6) {
+            return Err(crate::i18n::tf("error.schedule_weekday", &[("day", &day)]));
+        }
+        Ok(())
+    }
+
+    /// True when the timer should run right now (always true when the schedule is off)
+    pub fn is_active_now(&self) -> bool {
+        self.is_active_at(Local::now().naive_local())
+    }
+
+    pub fn is_active_at(&self, now: NaiveDateTime) -> bool {
+        if !self.enabled {
+            return true;
+        }
+        let (Some(start), Some(end)) = (parse_time(&self.start), parse_time(&self.end)) else {
+            return true;
+        };
+
+        let minute = now.hour() * 60 + now.minute();
+        let day_active = |at: NaiveDateTime| self.days.contains(&(at.weekday().num_days_from_monday() as u8));
+
+        if start < end {
+            day_active(now) && minute >= start && minute < end
+        } else if minute >= start {
+            // Overnight window, evening part - belongs to today
+            day_active(now)
+        } else if minute < end {
+            // Overnight window, after midnight - belongs to the previous day
+            day_active(now - Duration::days(1))
+        } else {
+            false
+        }
+    }
+}
+
+/// Parse "HH:MM" into minutes since midnight
+/// Minutes since midnight of a local "HH:MM"
+pub(crate) fn parse_time(value: &str) -> Option<u32> {
+    let (hours, minutes) = value.trim().split_once(':')?;
+    let hours: u32 = hours.parse().ok()?;
+    let minutes: u32 = minutes.parse().ok()?;
+    if hours > 23 || minutes > 59 {
+        return None;
+    }
+    Some(hours * 60 + minutes)
+}
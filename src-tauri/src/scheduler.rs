@@ -0,0 +1,97 @@
+// Scheduler module
+// Background loop that fires per-task reminders as OS notifications, runs the
+// end-of-day rollover and unloads the Whisper model once it has sat idle. Like
+// the awareness timer it sleeps until the next reminder is due (checking at
+// least once a minute) and is woken early whenever a reminder is set, moved or
+// cleared.
+
+use std::sync::OnceLock;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_notification::NotificationExt;
+use tokio::sync::Notify;
+use flowstate_core::database::Database;
+
+// Longest sleep between checks, so clock changes and sleep/resume are noticed
+const MAX_WAIT: Duration = Duration::from_secs(60);
+
+pub fn setup_reminder_scheduler(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            fire_due_reminders(&app);
+            run_rollover(&app);
+            unload_idle_model(&app);
+
+            let wait = flowstate_core::reminders::next_due_in(&app.state::<Database>())
+                .map_or(MAX_WAIT, |due| due.min(MAX_WAIT));
+            tokio::select! {
+                _ = tokio::time::sleep(wait) => {}
+                _ = reminders_changed().notified() => {}
+            }
+        }
+    });
+}
+
+fn reminders_changed() -> &'static Notify {
+    static REMINDERS_CHANGED: OnceLock<Notify> = OnceLock::new();
+    REMINDERS_CHANGED.get_or_init(Notify::new)
+}
+
+/// Call after setting or clearing a reminder so the loop re-plans its sleep
+pub fn notify_reminders_changed() {
+    reminders_changed().notify_one();
+}
+
+fn fire_due_reminders(app: &AppHandle) {
+    let db = app.state::<Database>();
+    let due = flowstate_core::reminders::take_due(&db);
+    if due.is_empty() {
+        return;
+    }
+
+    // The notification always shows (the user asked for it); do-not-disturb only silences it
+    let settings = flowstate_core::settings::load(&db);
+    if !flowstate_core::dnd::get_dnd_state(&settings.dnd).is_active() {
+        flowstate_core::earcon::play_alert_sound(&settings.alert_sound);
+    }
+    for reminder in &due {
+        tracing::info!("Reminder fired for task {}", reminder.task_id);
+        if let Err(e) = app.notification()
+            .builder()
+            .title("FlowState")
+            .body(flowstate_core::reminders::notification_body(reminder))
+            .show()
+        {
+            tracing::warn!("Failed to show notification: {}", e);
+        }
+        let _ = app.emit("reminder-fired", reminder);
+    }
+    let _ = app.emit("reminders-changed", ());
+}
+
+// The nightly rollover; its review goes to the windows and the notification area
+fn run_rollover(app: &AppHandle) {
+    let db = app.state::<Database>();
+    let config = flowstate_core::settings::load(&db).rollover;
+    let Some(review) = flowstate_core::rollover::run_if_due(&db, &config) else {
+        return;
+    };
+    if let Err(e) = app.notification()
+        .builder()
+        .title("FlowState")
+        .body(flowstate_core::rollover::summary(&review))
+        .show()
+    {
+        tracing::warn!("Failed to show notification: {}", e);
+    }
+    let _ = app.emit("daily-review", &review);
+    let _ = app.emit("reminders-changed", ());
+}
+
+// Frees the model's memory after `model_unload_minutes` without a recording
+fn unload_idle_model(app: &AppHandle) {
+    let minutes = flowstate_core::settings::load(&app.state::<Database>()).model_unload_minutes;
+    if minutes > 0 {
+        app.state::<flowstate_core::whisper::WhisperCache>().unload_if_idle(Duration::from_secs(minutes * 60));
+    }
+}
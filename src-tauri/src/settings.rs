@@ -0,0 +1,1292 @@
+// Settings module
+// Single typed store for user preferences, persisted as one JSON document in the
+// SQLite settings table. Both UIs read and write through here.
+
+use crate::database::{self, Database};
+use crate::content_filter::FilterMode;
+use crate::earcon::AlertSound;
+use crate::i18n::Locale;
+use crate::parser::ParserKind;
+use crate::schedule::TimerSchedule;
+use crate::speaker::VoiceProfile;
+use serde::{Deserialize, Serialize};
+
+const SETTINGS_KEY: &str = "app_settings";
+pub const DEFAULT_QUICK_ADD_SHORTCUT: &str = "CommandOrControl+Alt+N";
+pub const DEFAULT_COMPACT_MODE_SHORTCUT: &str = "CommandOrControl+Alt+M";
+pub const DEFAULT_RECORD_SHORTCUT: &str = "CommandOrControl+Alt+R";
+pub const DEFAULT_ACCENT_COLOR: &str = "#4a9eff";
+/// egui's own body text size, so the default keeps its stock look
+pub const DEFAULT_FONT_SIZE: f32 = 12.5;
+pub const UI_SCALE_RANGE: std::ops::RangeInclusive<f32> = 0.5..=3.0;
+pub const FONT_SIZE_RANGE: std::ops::RangeInclusive<f32> = 9.0..=24.0;
+
+// Per-key settings written before the unified store existed
+const LEGACY_KEYS: &[&str] = &[
+    "ollama_enabled",
+    "earcons_muted",
+    "wake_word_enabled",
+    "wake_word_phrase",
+    "alert_mode",
+    "alert_sound",
+    "auto_pause_enabled",
+    "idle_threshold_minutes",
+    "journal_prompt_enabled",
+    "timer_schedule",
+    "alert_escalation",
+    "break_mode",
+];
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// Awareness interval (0 disables the alert)
+    pub timer_duration_minutes: u64,
+    /// Whisper model used for dictation
+    pub selected_model: String,
+    /// Language Whisper transcribes in when neither the recording nor the
+    /// active profile sets one ("auto" detects it)
+    pub transcription_language: String,
+    /// Unload the Whisper model after this many minutes without a recording (0 keeps it)
+    pub model_unload_minutes: u64,
+    /// Input device name for the native UI (None uses the system default;
+    /// the web UI keeps its own browser device id)
+    pub input_device: Option<String>,
+    pub ollama_enabled: bool,
+    pub ollama: OllamaSettings,
+    pub openai: OpenAiSettings,
+    /// Transcript parsers in the order they're tried; turned-off ones are
+    /// skipped (see `parser::ParserChain`)
+    pub parser_chain: Vec<ParserKind>,
+    pub punctuation: PunctuationSettings,
+    pub earcons_muted: bool,
+    pub wake_word_enabled: bool,
+    pub wake_word_phrase: String,
+    pub alert_mode: AlertMode,
+    pub alert_sound: AlertSound,
+    pub auto_pause_enabled: bool,
+    pub idle_threshold_minutes: u64,
+    pub journal_prompt_enabled: bool,
+    /// Read open tasks aloud when the timer expires (skipped while the journal
+    /// prompt records, so the voice isn't in the answer)
+    pub speak_tasks_on_alert: bool,
+    /// Put tasks captured by voice in the inbox to triage, instead of the main list
+    pub voice_inbox: bool,
+    /// Only recordings starting with "command:" run commands; anything else is
+    /// added as one task, word for word (see `action_mode`)
+    pub action_mode: bool,
+    pub speaker_gate: SpeakerGateSettings,
+    pub content_filter: ContentFilterSettings,
+    /// Sample the foreground app during focus sessions and log context switches
+    pub focus_monitor_enabled: bool,
+    pub schedule: TimerSchedule,
+    pub escalation: EscalationSettings,
+    pub break_mode: BreakSettings,
+    pub dnd: DndSettings,
+    pub markdown_sync: MarkdownSyncSettings,
+    pub calendar_export: CalendarExportSettings,
+    pub google_calendar: GoogleCalendarSettings,
+    pub todoist: TodoistSettings,
+    pub github: GithubSettings,
+    pub telegram: TelegramSettings,
+    pub clipboard: ClipboardSettings,
+    pub mqtt: MqttSettings,
+    pub report: ReportSettings,
+    pub rollover: RolloverSettings,
+    pub workload: WorkloadSettings,
+    pub profiles: ProfileSettings,
+    pub window: WindowSettings,
+    /// Global shortcut for the quick-add box, in Tauri accelerator form
+    pub quick_add_shortcut: String,
+    /// Global shortcut that toggles the compact widget window
+    pub compact_mode_shortcut: String,
+    /// Global shortcut that shows the native window and starts or stops a recording
+    pub record_shortcut: String,
+    pub close_behavior: CloseBehavior,
+    pub theme: Theme,
+    /// Accent for the timer bar and record button, as "#rrggbb"
+    pub accent_color: String,
+    /// Zoom of the egui build on top of the display's own scaling
+    pub ui_scale: f32,
+    /// Body text size of the egui build in points (other text scales with it)
+    pub font_size: f32,
+    /// Look for a newer release on startup
+    pub auto_check_updates: bool,
+    /// When the egui build throttles background work (see `power`)
+    pub power_saving: PowerSaving,
+    /// Language of the UI and of messages returned by commands (None follows the system)
+    pub ui_language: Option<Locale>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            timer_duration_minutes: 15,
+            selected_model: "tiny".to_string(),
+            transcription_language: crate::whisper::DEFAULT_LANGUAGE.to_string(),
+            model_unload_minutes: crate::whisper::DEFAULT_UNLOAD_MINUTES,
+            input_device: None,
+            ollama_enabled: false,
+            ollama: OllamaSettings::default(),
+            openai: OpenAiSettings::default(),
+            parser_chain: vec![ParserKind::Ollama, ParserKind::OpenAi, ParserKind::Rules],
+            punctuation: PunctuationSettings::default(),
+            earcons_muted: false,
+            wake_word_enabled: false,
+            wake_word_phrase: crate::wakeword::DEFAULT_WAKE_PHRASE.to_string(),
+            alert_mode: AlertMode::Notification,
+            alert_sound: AlertSound::default(),
+            auto_pause_enabled: true,
+            idle_threshold_minutes: crate::presence::DEFAULT_IDLE_THRESHOLD_MINUTES,
+            journal_prompt_enabled: true,
+            speak_tasks_on_alert: false,
+            voice_inbox: false,
+            action_mode: false,
+            speaker_gate: SpeakerGateSettings::default(),
+            content_filter: ContentFilterSettings::default(),
+            focus_monitor_enabled: false,
+            schedule: TimerSchedule::default(),
+            escalation: EscalationSettings::default(),
+            break_mode: BreakSettings::default(),
+            dnd: DndSettings::default(),
+            markdown_sync: MarkdownSyncSettings::default(),
+            calendar_export: CalendarExportSettings::default(),
+            google_calendar: GoogleCalendarSettings::default(),
+            todoist: TodoistSettings::default(),
+            github: GithubSettings::default(),
+            telegram: TelegramSettings::default(),
+            clipboard: ClipboardSettings::default(),
+            mqtt: MqttSettings::default(),
+            report: ReportSettings::default(),
+            rollover: RolloverSettings::default(),
+            workload: WorkloadSettings::default(),
+            profiles: ProfileSettings::default(),
+            window: WindowSettings::default(),
+            quick_add_shortcut: DEFAULT_QUICK_ADD_SHORTCUT.to_string(),
+            compact_mode_shortcut: DEFAULT_COMPACT_MODE_SHORTCUT.to_string(),
+            record_shortcut: DEFAULT_RECORD_SHORTCUT.to_string(),
+            close_behavior: CloseBehavior::default(),
+            theme: Theme::default(),
+            accent_color: DEFAULT_ACCENT_COLOR.to_string(),
+            ui_scale: 1.0,
+            font_size: DEFAULT_FONT_SIZE,
+            auto_check_updates: true,
+            power_saving: PowerSaving::default(),
+            ui_language: None,
+        }
+    }
+}
+
+impl Settings {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.timer_duration_minutes > 60 {
+            return Err(crate::i18n::t("error.timer_duration"));
+        }
+        if crate::whisper::WhisperModelSize::from_str(&self.selected_model).is_none() {
+            return Err(crate::i18n::tf("error.invalid_model", &[("model", &self.selected_model)]));
+        }
+        let language = self.transcription_language.trim();
+        if !(language == "auto" || ((2..=3).contains(&language.len()) && language.chars().all(|c| c.is_ascii_lowercase()))) {
+            return Err(crate::i18n::tf("error.transcription_language", &[("language", &language)]));
+        }
+        if self.quick_add_shortcut.trim().is_empty() {
+            return Err(crate::i18n::t("error.quick_add_shortcut_empty"));
+        }
+        if self.compact_mode_shortcut.trim().is_empty() {
+            return Err(crate::i18n::t("error.compact_mode_shortcut_empty"));
+        }
+        if self.record_shortcut.trim().is_empty() {
+            return Err(crate::i18n::t("error.record_shortcut_empty"));
+        }
+        let shortcuts = [&self.quick_add_shortcut, &self.compact_mode_shortcut, &self.record_shortcut];
+        for (i, shortcut) in shortcuts.iter().enumerate() {
+            if shortcuts[i + 1..].iter().any(|other| other.trim().eq_ignore_ascii_case(shortcut.trim())) {
+                return Err(crate::i18n::t("error.shortcuts_identical"));
+            }
+        }
+        self.ollama.validate()?;
+        self.openai.validate()?;
+        self.speaker_gate.validate()?;
+        if self.parser_chain.is_empty() {
+            return Err(crate::i18n::t("error.parser_chain_empty"));
+        }
+        for (i, kind) in self.parser_chain.iter().enumerate() {
+            if self.parser_chain[i + 1..].contains(kind) {
+                return Err(crate::i18n::tf("error.parser_chain_duplicate", &[("parser", &kind.as_str())]));
+            }
+        }
+        self.markdown_sync.validate()?;
+        self.calendar_export.validate()?;
+        self.google_calendar.validate()?;
+        self.todoist.validate()?;
+        self.github.validate()?;
+        self.telegram.validate()?;
+        self.clipboard.validate()?;
+        self.mqtt.validate()?;
+        self.report.validate()?;
+        self.rollover.validate()?;
+        self.workload.validate()?;
+        self.profiles.validate()?;
+        if parse_hex_color(&self.accent_color).is_none() {
+            return Err(crate::i18n::tf("error.invalid_accent_color", &[("color", &self.accent_color)]));
+        }
+        if !UI_SCALE_RANGE.contains(&self.ui_scale) {
+            return Err(crate::i18n::t("error.ui_scale"));
+        }
+        if !FONT_SIZE_RANGE.contains(&self.font_size) {
+            return Err(crate::i18n::t("error.font_size"));
+        }
+        if self.wake_word_phrase.trim().is_empty() {
+            return Err(crate::i18n::t("error.wake_phrase_empty"));
+        }
+        if !(1..=60).contains(&self.idle_threshold_minutes) {
+            return Err(crate::i18n::t("error.idle_threshold"));
+        }
+        if self.model_unload_minutes > 240 {
+            return Err(crate::i18n::t("error.model_unload"));
+        }
+        if !(1..=60).contains(&self.escalation.after_minutes) {
+            return Err(crate::i18n::t("error.escalation_delay"));
+        }
+        if !(1..=60).contains(&self.break_mode.minutes) {
+            return Err(crate::i18n::t("error.break_duration"));
+        }
+        self.schedule.validate()
+    }
+
+    /// Working hours in effect: the active profile's own, or the global ones
+    pub fn timer_schedule(&self) -> &TimerSchedule {
+        self.profiles.timer_schedule().unwrap_or(&self.schedule)
+    }
+
+    pub fn profile_language(&self) -> Option<&str> {
+        self.profiles.language()
+    }
+
+    /// Language to transcribe in: the active profile's, else `transcription_language`
+    pub fn recording_language(&self) -> &str {
+        self.profile_language().unwrap_or(&self.transcription_language)
+    }
+
+    pub fn profile_prompt(&self) -> Option<&str> {
+        self.profiles.prompt()
+    }
+
+    pub fn task_scope(&self) -> Option<database::TaskScope> {
+        self.profiles.task_scope()
+    }
+}
+
+/// How the awareness timer gets the user's attention on expiry
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AlertMode {
+    /// OS notification only (default - least disruptive)
+    Notification,
+    /// Bring the window to the front
+    Focus,
+    Both,
+}
+
+impl AlertMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AlertMode::Notification => "notification",
+            AlertMode::Focus => "focus",
+            AlertMode::Both => "both",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "notification" => Some(AlertMode::Notification),
+            "focus" => Some(AlertMode::Focus),
+            "both" => Some(AlertMode::Both),
+            _ => None,
+        }
+    }
+
+    pub fn notifies(&self) -> bool {
+        matches!(self, AlertMode::Notification | AlertMode::Both)
+    }
+
+    pub fn focuses(&self) -> bool {
+        matches!(self, AlertMode::Focus | AlertMode::Both)
+    }
+}
+
+/// What the window's close button does
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CloseBehavior {
+    /// Keep running in the background, hidden to the tray
+    #[default]
+    Hide,
+    Quit,
+}
+
+/// Light or dark visuals (the egui build; the web UI is always dark)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Theme {
+    #[default]
+    Dark,
+    Light,
+    /// Follow the OS light/dark preference
+    System,
+}
+
+/// Whether to save power (the egui build)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PowerSaving {
+    /// While on battery or in the OS's low-power mode
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+/// Parse "#rrggbb" (the leading # is optional)
+pub fn parse_hex_color(value: &str) -> Option<[u8; 3]> {
+    let hex = value.trim().trim_start_matches('#');
+    if hex.len() != 6 {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+    Some([channel(0)?, channel(2)?, channel(4)?])
+}
+
+/// Ollama server used to parse transcripts when `ollama_enabled` is on
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OllamaSettings {
+    pub url: String,
+    /// Model name; "llama3.2" also matches a pulled "llama3.2:latest"
+    pub model: String,
+    /// Seconds to wait for an answer before falling back to the simple parser
+    pub timeout_secs: u64,
+}
+
+impl Default for OllamaSettings {
+    fn default() -> Self {
+        Self {
+            url: "http://localhost:11434".to_string(),
+            model: "llama3.2".to_string(),
+            timeout_secs: 15,
+        }
+    }
+}
+
+impl OllamaSettings {
+    pub fn validate(&self) -> Result<(), String> {
+        let url = self.url.trim();
+        if !(url.starts_with("http://") || url.starts_with("https://")) {
+            return Err(crate::i18n::tf("error.ollama_url", &[("url", &url)]));
+        }
+        if self.model.trim().is_empty() {
+            return Err(crate::i18n::t("error.ollama_model_empty"));
+        }
+        if !(1..=120).contains(&self.timeout_secs) {
+            return Err(crate::i18n::t("error.ollama_timeout"));
+        }
+        Ok(())
+    }
+
+    /// URL without a trailing slash, ready for "/api/..." paths
+    pub fn base_url(&self) -> &str {
+        self.url.trim().trim_end_matches('/')
+    }
+}
+
+/// OpenAI (or a compatible server) used to parse transcripts (see `openai`)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OpenAiSettings {
+    pub enabled: bool,
+    pub api_key: String,
+    pub model: String,
+    /// API root; another OpenAI-compatible server can be put here
+    pub url: String,
+    /// Seconds to wait for an answer before the next parser in the chain
+    pub timeout_secs: u64,
+}
+
+impl Default for OpenAiSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            api_key: String::new(),
+            model: "gpt-4o-mini".to_string(),
+            url: "https://api.openai.com/v1".to_string(),
+            timeout_secs: 15,
+        }
+    }
+}
+
+impl OpenAiSettings {
+    pub fn validate(&self) -> Result<(), String> {
+        let url = self.url.trim();
+        if !(url.starts_with("http://") || url.starts_with("https://")) {
+            return Err(crate::i18n::tf("error.openai_url", &[("url", &url)]));
+        }
+        if self.model.trim().is_empty() {
+            return Err(crate::i18n::t("error.openai_model_empty"));
+        }
+        if !(1..=120).contains(&self.timeout_secs) {
+            return Err(crate::i18n::t("error.openai_timeout"));
+        }
+        if self.enabled && self.api_key.trim().is_empty() {
+            return Err(crate::i18n::t("error.openai_key_empty"));
+        }
+        Ok(())
+    }
+
+    /// URL without a trailing slash, ready for "/chat/completions"
+    pub fn base_url(&self) -> &str {
+        self.url.trim().trim_end_matches('/')
+    }
+}
+
+/// Task list mirrored into a Markdown note (see `sync::markdown`)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MarkdownSyncSettings {
+    pub enabled: bool,
+    /// The .md file FlowState writes, e.g. a note in an Obsidian vault
+    pub path: String,
+    /// Also complete tasks ticked in the note and add its new unticked lines
+    pub two_way: bool,
+}
+
+impl MarkdownSyncSettings {
+    pub fn validate(&self) -> Result<(), String> {
+        if !self.enabled {
+            return Ok(());
+        }
+        let path = self.path.trim();
+        if path.is_empty() {
+            return Err(crate::i18n::t("error.markdown_path_empty"));
+        }
+        if !path.to_lowercase().ends_with(".md") {
+            return Err(crate::i18n::tf("error.markdown_extension", &[("path", &path)]));
+        }
+        Ok(())
+    }
+}
+
+/// Dated tasks written to an .ics file (see `sync::calendar`)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CalendarExportSettings {
+    pub enabled: bool,
+    /// The .ics file FlowState writes, for a calendar app to subscribe to
+    pub path: String,
+}
+
+impl CalendarExportSettings {
+    pub fn validate(&self) -> Result<(), String> {
+        if !self.enabled {
+            return Ok(());
+        }
+        let path = self.path.trim();
+        if path.is_empty() {
+            return Err(crate::i18n::t("error.calendar_path_empty"));
+        }
+        if !path.to_lowercase().ends_with(".ics") {
+            return Err(crate::i18n::tf("error.calendar_extension", &[("path", &path)]));
+        }
+        Ok(())
+    }
+}
+
+/// Dated tasks pushed to a Google calendar (see `sync::google_calendar`)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GoogleCalendarSettings {
+    pub enabled: bool,
+    /// OAuth client of type "Desktop app" from the Google Cloud console
+    pub client_id: String,
+    pub client_secret: String,
+    /// Kept from signing in with Connect; empty until then
+    pub refresh_token: String,
+    /// Calendar the events go to ("primary" is the account's own)
+    pub calendar_id: String,
+}
+
+impl Default for GoogleCalendarSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            client_id: String::new(),
+            client_secret: String::new(),
+            refresh_token: String::new(),
+            calendar_id: "primary".to_string(),
+        }
+    }
+}
+
+impl GoogleCalendarSettings {
+    pub fn validate(&self) -> Result<(), String> {
+        if !self.enabled {
+            return Ok(());
+        }
+        if self.client_id.trim().is_empty() || self.client_secret.trim().is_empty() {
+            return Err(crate::i18n::t("error.google_client_empty"));
+        }
+        if self.refresh_token.trim().is_empty() {
+            return Err(crate::i18n::t("error.google_not_connected"));
+        }
+        if self.calendar_id.trim().is_empty() {
+            return Err(crate::i18n::t("error.google_calendar_id_empty"));
+        }
+        Ok(())
+    }
+}
+
+/// Two-way sync with a Todoist account (see `sync::todoist`)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TodoistSettings {
+    pub enabled: bool,
+    /// API token from Todoist's Settings → Integrations → Developer
+    pub token: String,
+    /// Minutes between sync runs
+    pub interval_minutes: u64,
+}
+
+impl Default for TodoistSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            token: String::new(),
+            interval_minutes: 5,
+        }
+    }
+}
+
+impl TodoistSettings {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.enabled && self.token.trim().is_empty() {
+            return Err(crate::i18n::t("error.todoist_token_empty"));
+        }
+        if !(1..=60).contains(&self.interval_minutes) {
+            return Err(crate::i18n::t("error.todoist_interval"));
+        }
+        Ok(())
+    }
+}
+
+/// Repository that "github: ..." voice commands file issues in (see `github`)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GithubSettings {
+    pub enabled: bool,
+    /// Personal access token allowed to create issues in `repo`
+    pub token: String,
+    /// "owner/name"
+    pub repo: String,
+}
+
+impl GithubSettings {
+    pub fn validate(&self) -> Result<(), String> {
+        if !self.enabled {
+            return Ok(());
+        }
+        if self.token.trim().is_empty() {
+            return Err(crate::i18n::t("error.github_token_empty"));
+        }
+        let repo = self.repo.trim();
+        let valid = repo
+            .split_once('/')
+            .is_some_and(|(owner, name)| !owner.is_empty() && !name.is_empty() && !name.contains('/') && !repo.contains(char::is_whitespace));
+        if !valid {
+            return Err(crate::i18n::tf("error.github_repo", &[("repo", &repo)]));
+        }
+        Ok(())
+    }
+}
+
+/// Telegram bot that forwards messages from the phone as tasks (see `telegram`)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TelegramSettings {
+    pub enabled: bool,
+    /// Bot token from @BotFather
+    pub token: String,
+    /// Telegram username (without "@") whose messages are accepted
+    pub username: String,
+}
+
+impl TelegramSettings {
+    pub fn validate(&self) -> Result<(), String> {
+        if !self.enabled {
+            return Ok(());
+        }
+        if self.token.trim().is_empty() {
+            return Err(crate::i18n::t("error.telegram_token_empty"));
+        }
+        if self.username.trim().trim_start_matches('@').is_empty() {
+            return Err(crate::i18n::t("error.telegram_username_empty"));
+        }
+        Ok(())
+    }
+}
+
+/// Copying text that starts with `trigger` adds it as a task (see `clipboard`)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ClipboardSettings {
+    pub enabled: bool,
+    pub trigger: String,
+}
+
+impl Default for ClipboardSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            trigger: "todo:".to_string(),
+        }
+    }
+}
+
+impl ClipboardSettings {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.enabled && self.trigger.trim().is_empty() {
+            return Err(crate::i18n::t("error.clipboard_trigger_empty"));
+        }
+        Ok(())
+    }
+}
+
+/// Broker that timer and task state is published to (see `mqtt`)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MqttSettings {
+    pub enabled: bool,
+    pub host: String,
+    pub port: u16,
+    /// Empty for brokers that allow anonymous clients
+    pub username: String,
+    pub password: String,
+    /// Topics are "<prefix>/timer", "<prefix>/recording", ...
+    pub topic_prefix: String,
+}
+
+impl Default for MqttSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            host: "localhost".to_string(),
+            port: 1883,
+            username: String::new(),
+            password: String::new(),
+            topic_prefix: "flowstate".to_string(),
+        }
+    }
+}
+
+impl MqttSettings {
+    pub fn validate(&self) -> Result<(), String> {
+        if !self.enabled {
+            return Ok(());
+        }
+        if self.host.trim().is_empty() {
+            return Err(crate::i18n::t("error.mqtt_host_empty"));
+        }
+        if self.port == 0 {
+            return Err(crate::i18n::t("error.mqtt_port"));
+        }
+        let prefix = self.topic_prefix.trim().trim_end_matches('/');
+        // Wildcards can't be published to
+        if prefix.is_empty() || prefix.contains(['#', '+']) {
+            return Err(crate::i18n::tf("error.mqtt_prefix", &[("prefix", &prefix)]));
+        }
+        Ok(())
+    }
+}
+
+/// Where weekly review reports are written (see `report`)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReportSettings {
+    /// Output folder; empty means "FlowState" in the Documents folder
+    pub folder: String,
+    /// Also render each report to PDF next to the Markdown file
+    pub pdf: bool,
+}
+
+impl ReportSettings {
+    pub fn validate(&self) -> Result<(), String> {
+        let folder = self.folder.trim();
+        if !folder.is_empty() && !std::path::Path::new(folder).is_dir() {
+            return Err(crate::i18n::tf("error.report_folder", &[("path", &folder)]));
+        }
+        Ok(())
+    }
+
+    /// The folder reports go to
+    pub fn output_dir(&self) -> std::path::PathBuf {
+        match self.folder.trim() {
+            "" => dirs::document_dir()
+                .or_else(dirs::home_dir)
+                .unwrap_or_default()
+                .join("FlowState"),
+            folder => std::path::PathBuf::from(folder),
+        }
+    }
+}
+
+/// Sentence breaks put back into run-on transcripts before the rules parse
+/// them (see `punctuation`)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PunctuationSettings {
+    pub enabled: bool,
+    /// Let Ollama punctuate, when it's on; the rules are the fallback
+    pub ollama: bool,
+}
+
+impl Default for PunctuationSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            ollama: false,
+        }
+    }
+}
+
+/// Keeps recordings to the enrolled voice (see `speaker`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SpeakerGateSettings {
+    pub enabled: bool,
+    pub profile: Option<VoiceProfile>,
+    /// How far a stretch of speech may be from the enrolled pitch and still count
+    pub tolerance_semitones: f32,
+}
+
+impl Default for SpeakerGateSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            profile: None,
+            tolerance_semitones: 4.0,
+        }
+    }
+}
+
+impl SpeakerGateSettings {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.enabled && self.profile.is_none() {
+            return Err(crate::i18n::t("error.speaker_not_enrolled"));
+        }
+        if !(1.0..=12.0).contains(&self.tolerance_semitones) {
+            return Err(crate::i18n::t("error.speaker_tolerance"));
+        }
+        Ok(())
+    }
+}
+
+/// Masks or drops swear words and listed words in transcripts before they're
+/// stored (see `content_filter`)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ContentFilterSettings {
+    pub enabled: bool,
+    pub mode: FilterMode,
+    /// Catch common swear words (English and Russian)
+    pub profanity: bool,
+    /// Words or phrases to filter too, like client or project names
+    pub words: Vec<String>,
+}
+
+impl Default for ContentFilterSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            mode: FilterMode::default(),
+            profanity: true,
+            words: Vec::new(),
+        }
+    }
+}
+
+/// End-of-day job (see `rollover`): moves unfinished tasks' reminders to the
+/// next day, archives old completed tasks and offers a daily review
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RolloverSettings {
+    pub enabled: bool,
+    /// Local time the day ends, "HH:MM"
+    pub time: String,
+    /// Completed tasks older than this many days leave the lists
+    pub archive_after_days: u32,
+}
+
+impl Default for RolloverSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            time: "23:30".to_string(),
+            archive_after_days: 7,
+        }
+    }
+}
+
+impl RolloverSettings {
+    pub fn validate(&self) -> Result<(), String> {
+        if crate::schedule::parse_time(&self.time).is_none() {
+            return Err(crate::i18n::tf("error.rollover_time", &[("time", &self.time)]));
+        }
+        if !(1..=365).contains(&self.archive_after_days) {
+            return Err(crate::i18n::t("error.rollover_archive_days"));
+        }
+        Ok(())
+    }
+}
+
+/// How much estimated work fits in a day (see `estimates`)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WorkloadSettings {
+    /// Minutes of estimated work a day before the workload summary warns
+    pub capacity_minutes: u32,
+}
+
+impl Default for WorkloadSettings {
+    fn default() -> Self {
+        Self { capacity_minutes: 360 }
+    }
+}
+
+impl WorkloadSettings {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(30..=960).contains(&self.capacity_minutes) {
+            return Err(crate::i18n::t("error.workload_capacity"));
+        }
+        Ok(())
+    }
+}
+
+/// A mode like "Work" or "Personal". While active it narrows the task list to
+/// its projects and tags and swaps in its own recording language, task-parsing
+/// instructions and working hours (see `profiles`)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Profile {
+    pub name: String,
+    /// Projects shown, without the "+"; tasks with no project or tag always show
+    pub projects: Vec<String>,
+    /// Tags shown, without the "#"
+    pub tags: Vec<String>,
+    /// Whisper language code ("auto" detects); None keeps the default
+    pub language: Option<String>,
+    /// Added to the Ollama prompt when parsing voice memos
+    pub prompt: String,
+    /// Working hours for the timer; None keeps the global schedule
+    pub schedule: Option<TimerSchedule>,
+}
+
+impl Profile {
+    /// The task list filter for this profile (None when it lists no projects or tags)
+    pub fn scope(&self) -> Option<database::TaskScope> {
+        let clean = |words: &[String], prefix: char| -> Vec<String> {
+            words
+                .iter()
+                .map(|word| word.trim().trim_start_matches(prefix).to_string())
+                .filter(|word| !word.is_empty())
+                .collect()
+        };
+        let scope = database::TaskScope {
+            projects: clean(&self.projects, '+'),
+            tags: clean(&self.tags, '#'),
+        };
+        (!scope.projects.is_empty() || !scope.tags.is_empty()).then_some(scope)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProfileSettings {
+    /// Name of the active profile (None uses the plain settings)
+    pub active: Option<String>,
+    pub profiles: Vec<Profile>,
+}
+
+impl Default for ProfileSettings {
+    fn default() -> Self {
+        Self {
+            active: None,
+            profiles: vec![
+                Profile {
+                    name: "Work".to_string(),
+                    projects: vec!["work".to_string()],
+                    schedule: Some(TimerSchedule {
+                        enabled: true,
+                        ..TimerSchedule::default()
+                    }),
+                    ..Profile::default()
+                },
+                Profile {
+                    name: "Personal".to_string(),
+                    projects: vec!["personal".to_string()],
+                    tags: vec!["home".to_string()],
+                    ..Profile::default()
+                },
+            ],
+        }
+    }
+}
+
+impl ProfileSettings {
+    pub fn validate(&self) -> Result<(), String> {
+        for (i, profile) in self.profiles.iter().enumerate() {
+            let name = profile.name.trim();
+            if name.is_empty() {
+                return Err(crate::i18n::t("error.profile_name_empty"));
+            }
+            if self.profiles[i + 1..].iter().any(|other| other.name.trim().to_lowercase() == name.to_lowercase()) {
+                return Err(crate::i18n::tf("error.profile_duplicate", &[("name", &name)]));
+            }
+            if let Some(language) = profile.language.as_deref() {
+                if language.is_empty() || language.len() > 5 || !language.chars().all(|c| c.is_ascii_alphabetic()) {
+                    return Err(crate::i18n::tf("error.profile_language", &[("language", &language)]));
+                }
+            }
+            if let Some(schedule) = &profile.schedule {
+                schedule.validate()?;
+            }
+        }
+        if let Some(active) = self.active.as_deref() {
+            if self.find(active).is_none() {
+                return Err(crate::i18n::tf("error.profile_unknown", &[("name", &active)]));
+            }
+        }
+        Ok(())
+    }
+
+    /// The profile called `name`, ignoring case
+    pub fn find(&self, name: &str) -> Option<&Profile> {
+        let name = name.trim().to_lowercase();
+        self.profiles.iter().find(|profile| profile.name.trim().to_lowercase() == name)
+    }
+
+    pub fn active_profile(&self) -> Option<&Profile> {
+        self.active.as_deref().and_then(|name| self.find(name))
+    }
+
+    /// The active profile's own working hours, if it has them
+    pub fn timer_schedule(&self) -> Option<&TimerSchedule> {
+        self.active_profile().and_then(|profile| profile.schedule.as_ref())
+    }
+
+    /// Recording language set by the active profile, if any
+    pub fn language(&self) -> Option<&str> {
+        self.active_profile().and_then(|profile| profile.language.as_deref())
+    }
+
+    /// Extra task-parsing instructions from the active profile, if any
+    pub fn prompt(&self) -> Option<&str> {
+        self.active_profile()
+            .map(|profile| profile.prompt.trim())
+            .filter(|prompt| !prompt.is_empty())
+    }
+
+    /// Which tasks the list shows under the active profile (None shows all)
+    pub fn task_scope(&self) -> Option<database::TaskScope> {
+        self.active_profile().and_then(Profile::scope)
+    }
+}
+
+/// What happens when a timer alert goes unacknowledged
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EscalationSettings {
+    pub enabled: bool,
+    /// Minutes between escalation steps
+    pub after_minutes: u64,
+    /// Flash the taskbar icon in addition to the repeat notification and louder chime
+    pub flash_taskbar: bool,
+}
+
+impl Default for EscalationSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            after_minutes: 2,
+            flash_taskbar: true,
+        }
+    }
+}
+
+/// Hard-break mode: timer expiry covers the screen for the break duration
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BreakSettings {
+    pub enabled: bool,
+    pub minutes: u64,
+}
+
+impl Default for BreakSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            minutes: 5,
+        }
+    }
+}
+
+/// When timer alerts stay quiet (see `dnd::get_dnd_state`)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DndSettings {
+    /// Follow the OS do-not-disturb / Focus Assist state
+    pub respect_system_dnd: bool,
+    /// Executable names (case-insensitive, without ".exe") that silence alerts while full screen
+    pub fullscreen_apps: Vec<String>,
+}
+
+impl Default for DndSettings {
+    fn default() -> Self {
+        Self {
+            respect_system_dnd: true,
+            fullscreen_apps: ["zoom", "obs64", "obs", "teams", "ms-teams"]
+                .iter()
+                .map(|app| app.to_string())
+                .collect(),
+        }
+    }
+}
+
+/// Main window placement, restored on the next launch
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WindowSettings {
+    /// Last position and size (None until the window has been moved or resized)
+    pub geometry: Option<WindowGeometry>,
+    pub always_on_top: bool,
+    /// Shrunk to the widget strip (timer, record button, top task)
+    pub compact: bool,
+    /// Where the widget strip was last placed (its size is fixed)
+    pub compact_geometry: Option<WindowGeometry>,
+}
+
+impl WindowSettings {
+    /// Inner size of the compact widget strip in logical pixels
+    pub const COMPACT_SIZE: (f64, f64) = (300.0, 100.0);
+}
+
+/// Outer position and inner size in logical pixels, so both UIs agree on any display scale
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WindowGeometry {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl WindowGeometry {
+    /// Reject sizes and positions that would leave the window unusable or far off-screen
+    pub fn is_plausible(&self) -> bool {
+        self.width >= 100.0 && self.height >= 100.0 && self.x >= -1000.0 && self.y >= -1000.0
+    }
+}
+
+/// Load settings, migrating the old per-key values on first run
+pub fn load(db: &Database) -> Settings {
+    match database::get_setting(db, SETTINGS_KEY) {
+        Ok(Some(json)) => parse_lenient(&json),
+        Ok(None) => migrate_legacy(db),
+        Err(e) => {
+            tracing::warn!("Failed to read settings, using defaults: {}", e);
+            Settings::default()
+        }
+    }
+}
+
+/// Settings from the stored document. A field that no longer fits (renamed
+/// variant, wrong type, hand edit) falls back to its default on its own, so
+/// the rest survive and the next save doesn't wipe them.
+fn parse_lenient(json: &str) -> Settings {
+    let stored: serde_json::Value = match serde_json::from_str(json) {
+        Ok(stored) => stored,
+        Err(e) => {
+            tracing::warn!("Failed to parse settings, using defaults: {}", e);
+            return Settings::default();
+        }
+    };
+    if let Ok(settings) = serde_json::from_value(stored.clone()) {
+        return settings;
+    }
+    let mut value = serde_json::to_value(Settings::default()).expect("default settings serialize");
+    keep_valid(&mut value, &mut Vec::new(), stored);
+    serde_json::from_value(value).unwrap_or_default()
+}
+
+// Merge the stored fields under `path` into `value` one at a time, keeping
+// each only if the whole still deserializes; a bad object is tried field by field
+fn keep_valid(value: &mut serde_json::Value, path: &mut Vec<String>, stored: serde_json::Value) {
+    let serde_json::Value::Object(fields) = stored else {
+        return;
+    };
+    for (key, field) in fields {
+        let parent_pointer: String = path
+            .iter()
+            .map(|key| format!("/{}", key.replace('~', "~0").replace('/', "~1")))
+            .collect();
+        let mut candidate = value.clone();
+        if let Some(serde_json::Value::Object(parent)) = candidate.pointer_mut(&parent_pointer) {
+            match parent.get_mut(&key) {
+                Some(existing) => merge(existing, field.clone()),
+                None => {
+                    parent.insert(key.clone(), field.clone());
+                }
+            }
+        }
+        path.push(key);
+        if serde_json::from_value::<Settings>(candidate.clone()).is_ok() {
+            *value = candidate;
+        } else if field.is_object() {
+            keep_valid(value, path, field);
+        } else {
+            tracing::warn!("Ignoring invalid setting {}, using its default", path.join("."));
+        }
+        path.pop();
+    }
+}
+
+pub fn save(db: &Database, settings: &Settings) -> Result<(), String> {
+    let json = serde_json::to_string(settings)
+        .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+    database::set_setting(db, SETTINGS_KEY, &json)
+        .map_err(|e| format!("Failed to save settings: {}", e))?;
+    // Keep flowstate.toml in step, so it doesn't undo the change when it's next read
+    if let Some(path) = crate::config_file::path(db) {
+        if let Err(e) = crate::config_file::write_back(&path, settings) {
+            tracing::warn!("{}", e);
+        }
+    }
+    Ok(())
+}
+
+/// Apply a partial update (any subset of fields, nested objects merged) and persist it
+pub fn update(db: &Database, patch: serde_json::Value) -> Result<Settings, String> {
+    let previous = load(db);
+    let mut value = serde_json::to_value(&previous)
+        .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+    merge(&mut value, patch);
+
+    let settings: Settings = serde_json::from_value(value)
+        .map_err(|e| format!("Invalid settings: {}", e))?;
+    settings.validate()?;
+    // Only check a custom sound when it is being chosen (the file may later move)
+    if settings.alert_sound != previous.alert_sound {
+        if let AlertSound::File(ref path) = settings.alert_sound {
+            if !path.exists() {
+                return Err(format!("Sound file not found: {}", path.display()));
+            }
+        }
+    }
+    save(db, &settings)?;
+    Ok(settings)
+}
+
+fn merge(target: &mut serde_json::Value, patch: serde_json::Value) {
+    match (target, patch) {
+        (serde_json::Value::Object(target), serde_json::Value::Object(patch)) => {
+            for (key, value) in patch {
+                match target.get_mut(&key) {
+                    Some(existing) => merge(existing, value),
+                    None => {
+                        target.insert(key, value);
+                    }
+                }
+            }
+        }
+        (target, patch) => *target = patch,
+    }
+}
+
+/// Build settings from the pre-unification keys, then replace them with the single document
+fn migrate_legacy(db: &Database) -> Settings {
+    let legacy = |key: &str| database::get_setting(db, key).ok().flatten();
+    let flag = |key: &str, default: bool| match legacy(key).as_deref() {
+        Some("true") => true,
+        Some("false") => false,
+        _ => default,
+    };
+    let defaults = Settings::default();
+
+    let settings = Settings {
+        ollama_enabled: flag("ollama_enabled", defaults.ollama_enabled),
+        earcons_muted: flag("earcons_muted", defaults.earcons_muted),
+        wake_word_enabled: flag("wake_word_enabled", defaults.wake_word_enabled),
+        wake_word_phrase: legacy("wake_word_phrase").unwrap_or(defaults.wake_word_phrase),
+        alert_mode: legacy("alert_mode")
+            .and_then(|value| AlertMode::from_str(&value))
+            .unwrap_or(defaults.alert_mode),
+        alert_sound: legacy("alert_sound")
+            .and_then(|value| AlertSound::from_setting(&value))
+            .unwrap_or(defaults.alert_sound),
+        auto_pause_enabled: flag("auto_pause_enabled", defaults.auto_pause_enabled),
+        idle_threshold_minutes: legacy("idle_threshold_minutes")
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(defaults.idle_threshold_minutes),
+        journal_prompt_enabled: flag("journal_prompt_enabled", defaults.journal_prompt_enabled),
+        schedule: legacy("timer_schedule")
+            .and_then(|value| serde_json::from_str(&value).ok())
+            .unwrap_or(defaults.schedule),
+        escalation: legacy("alert_escalation")
+            .and_then(|value| serde_json::from_str(&value).ok())
+            .unwrap_or(defaults.escalation),
+        break_mode: legacy("break_mode")
+            .and_then(|value| serde_json::from_str(&value).ok())
+            .unwrap_or(defaults.break_mode),
+        ..Settings::default()
+    };
+
+    // Only drop the old keys once the new document is safely stored
+    match save(db, &settings) {
+        Ok(()) => {
+            for key in LEGACY_KEYS {
+                let _ = database::delete_setting(db, key);
+            }
+        }
+        Err(e) => tracing::warn!("Failed to migrate settings: {}", e),
+    }
+    settings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn one_bad_setting_falls_back_alone() {
+        let db = Database::new_in_memory().unwrap();
+        update(
+            &db,
+            serde_json::json!({
+                "timer_duration_minutes": 40,
+                "accent_color": "#336699",
+                "markdown_sync": { "enabled": true, "path": "/vault/FlowState.md", "two_way": true }
+            }),
+        )
+        .unwrap();
+
+        // A hand edit or an older build left a value that no longer parses
+        let mut stored = serde_json::to_value(load(&db)).unwrap();
+        stored["theme"] = serde_json::json!("sepia");
+        stored["markdown_sync"]["two_way"] = serde_json::json!("yes");
+        database::set_setting(&db, "app_settings", &stored.to_string()).unwrap();
+
+        let loaded = load(&db);
+        assert_eq!(loaded.theme, Theme::default());
+        assert!(!loaded.markdown_sync.two_way);
+        assert_eq!(loaded.timer_duration_minutes, 40);
+        assert_eq!(loaded.accent_color, "#336699");
+        assert!(loaded.markdown_sync.enabled);
+        assert_eq!(loaded.markdown_sync.path, "/vault/FlowState.md");
+    }
+}
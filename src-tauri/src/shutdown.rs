@@ -0,0 +1,114 @@
+// Shutdown module
+// Tracks in-flight voice processing so Quit can let it finish, and owns the
+// temporary recordings so none are left behind after errors or a crash
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+use tauri::AppHandle;
+
+// Recordings/imports currently being transcribed or applied
+static IN_FLIGHT: AtomicUsize = AtomicUsize::new(0);
+// Set once Quit is requested; new processing is refused from then on
+static QUITTING: AtomicBool = AtomicBool::new(false);
+
+// Longest Quit waits for in-flight processing before exiting anyway
+const DRAIN_TIMEOUT: Duration = Duration::from_secs(30);
+const DRAIN_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Held for the duration of one processing job
+pub struct ProcessingGuard;
+
+impl Drop for ProcessingGuard {
+    fn drop(&mut self) {
+        IN_FLIGHT.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+pub fn begin_processing() -> Result<ProcessingGuard, String> {
+    if QUITTING.load(Ordering::SeqCst) {
+        return Err(flowstate_core::i18n::t("error.shutting_down"));
+    }
+    IN_FLIGHT.fetch_add(1, Ordering::SeqCst);
+    Ok(ProcessingGuard)
+}
+
+/// A recording in the temp directory, deleted when dropped
+pub struct TempAudioFile {
+    path: PathBuf,
+}
+
+impl TempAudioFile {
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Keep the file on disk; the caller becomes responsible for it
+    pub fn keep(self) -> PathBuf {
+        let path = self.path.clone();
+        std::mem::forget(self);
+        path
+    }
+}
+
+impl Drop for TempAudioFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// Where recordings wait to be transcribed
+pub fn temp_audio_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    let app_data_dir = flowstate_core::paths::app_data_dir(app)
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    Ok(app_data_dir.join("audio_temp"))
+}
+
+/// Write recorded audio to a uniquely named file in the temp directory
+pub fn write_temp_audio(app: &AppHandle, audio_data: &[u8]) -> Result<TempAudioFile, String> {
+    let audio_temp_dir = temp_audio_dir(app)?;
+    std::fs::create_dir_all(&audio_temp_dir)
+        .map_err(|e| format!("Failed to create audio temp directory: {}", e))?;
+
+    // Generate unique filename with timestamp
+    let timestamp = chrono::Utc::now().format("%Y%m%d_%H%M%S_%f");
+    let file = TempAudioFile {
+        path: audio_temp_dir.join(format!("recording_{}.wav", timestamp)),
+    };
+
+    std::fs::write(file.path(), audio_data)
+        .map_err(|e| format!("Failed to write audio data: {}", e))?;
+    Ok(file)
+}
+
+/// Delete recordings left behind by a crash or a killed process
+pub fn cleanup_temp_audio(app: &AppHandle) {
+    let Ok(audio_temp_dir) = temp_audio_dir(app) else {
+        return;
+    };
+    let removed = flowstate_core::startup::remove_orphaned_recordings(&audio_temp_dir);
+    if removed > 0 {
+        tracing::info!("Removed {} orphaned recording(s)", removed);
+    }
+}
+
+/// Stop accepting work, let in-flight processing finish, clean up and exit
+pub async fn quit(app: AppHandle) {
+    QUITTING.store(true, Ordering::SeqCst);
+
+    let deadline = Instant::now() + DRAIN_TIMEOUT;
+    while IN_FLIGHT.load(Ordering::SeqCst) > 0 {
+        if Instant::now() >= deadline {
+            tracing::warn!(
+                "Quitting with {} recording(s) still processing",
+                IN_FLIGHT.load(Ordering::SeqCst)
+            );
+            break;
+        }
+        tokio::time::sleep(DRAIN_POLL_INTERVAL).await;
+    }
+
+    cleanup_temp_audio(&app);
+    tracing::info!("Shutting down");
+    app.exit(0);
+}
@@ -0,0 +1,215 @@
+// Speaker module
+// An optional gate that keeps other people's speech out of voice notes, for
+// open offices. The user enrolls by recording a few seconds of their own
+// voice, and `enroll` keeps its typical pitch. Before a recording is
+// transcribed, `gate` cuts it into stretches of speech at the pauses and
+// drops the stretches whose pitch is too far from the enrolled voice. It's a
+// heuristic rather than speaker recognition: it tells a deep voice from a high
+// one well, and two similar voices apart hardly at all.
+
+use crate::settings::SpeakerGateSettings;
+use serde::{Deserialize, Serialize};
+
+const SAMPLE_RATE: usize = 16000;
+// 40 ms analysis frames
+const FRAME: usize = 640;
+// Pitch searched between 60 and 400 Hz
+const MIN_LAG: usize = SAMPLE_RATE / 400;
+const MAX_LAG: usize = SAMPLE_RATE / 60;
+// How periodic a frame has to be to count as voiced
+const MIN_PERIODICITY: f32 = 0.5;
+// Frame loudness, relative to the loudest frame, below which it's a pause
+const QUIET_RATIO: f32 = 0.1;
+// Quiet frames in a row that end a stretch of speech (0.4 s)
+const PAUSE_FRAMES: usize = 10;
+// Silence left between the stretches kept (0.2 s)
+const GAP: usize = SAMPLE_RATE / 5;
+
+/// Voiced audio an enrollment needs
+pub const MIN_ENROLL_SECS: f32 = 3.0;
+
+/// The enrolled voice
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct VoiceProfile {
+    /// Median pitch of the sample
+    pub pitch_hz: f32,
+    /// Seconds of voiced audio it was taken from
+    pub voiced_secs: f32,
+}
+
+/// Take the voice profile from a recording of the user speaking (16 kHz mono)
+pub fn enroll(samples: &[f32]) -> Result<VoiceProfile, String> {
+    let threshold = quiet_threshold(samples);
+    let mut pitches: Vec<f32> = samples
+        .chunks_exact(FRAME)
+        .filter_map(|frame| frame_pitch(frame, threshold))
+        .collect();
+    let voiced_secs = (pitches.len() * FRAME) as f32 / SAMPLE_RATE as f32;
+    if voiced_secs < MIN_ENROLL_SECS {
+        return Err(crate::i18n::tf(
+            "error.speaker_sample_short",
+            &[("secs", &MIN_ENROLL_SECS)],
+        ));
+    }
+    let pitch_hz = median(&mut pitches);
+    tracing::info!(
+        "Enrolled a voice at {:.0} Hz from {:.1}s of speech",
+        pitch_hz,
+        voiced_secs
+    );
+    Ok(VoiceProfile {
+        pitch_hz,
+        voiced_secs,
+    })
+}
+
+/// Drop the stretches of a 16 kHz recording that don't sound like the enrolled
+/// voice. Stretches without a pitch to go on (whispers, clicks) are kept. With
+/// the gate off, the samples come back untouched.
+pub fn gate(samples: Vec<f32>, settings: &SpeakerGateSettings) -> Vec<f32> {
+    let Some(profile) = settings.profile.filter(|_| settings.enabled) else {
+        return samples;
+    };
+    let threshold = quiet_threshold(&samples);
+    let stretches = speech_stretches(&samples, threshold);
+    let mut kept = Vec::with_capacity(samples.len());
+    let mut dropped = 0;
+    for &(start, end) in &stretches {
+        let stretch = &samples[start..end];
+        let mut pitches: Vec<f32> = stretch
+            .chunks_exact(FRAME)
+            .filter_map(|frame| frame_pitch(frame, threshold))
+            .collect();
+        if !pitches.is_empty()
+            && semitones(median(&mut pitches), profile.pitch_hz).abs()
+                > settings.tolerance_semitones
+        {
+            dropped += 1;
+            continue;
+        }
+        if !kept.is_empty() {
+            kept.resize(kept.len() + GAP, 0.0);
+        }
+        kept.extend_from_slice(stretch);
+    }
+    if dropped > 0 {
+        tracing::info!(
+            "Speaker gate dropped {} of {} stretches of speech",
+            dropped,
+            stretches.len()
+        );
+    }
+    kept
+}
+
+// Loudness (RMS) below which a frame counts as a pause
+fn quiet_threshold(samples: &[f32]) -> f32 {
+    let loudest = samples.chunks(FRAME).map(rms).fold(0.0, f32::max);
+    (loudest * QUIET_RATIO).max(crate::audio::MIN_PEAK)
+}
+
+// [start, end) sample ranges of speech separated by pauses
+fn speech_stretches(samples: &[f32], threshold: f32) -> Vec<(usize, usize)> {
+    let mut stretches = Vec::new();
+    let mut current: Option<(usize, usize)> = None;
+    let mut quiet = 0;
+    for (index, frame) in samples.chunks(FRAME).enumerate() {
+        let start = index * FRAME;
+        let end = start + frame.len();
+        if rms(frame) >= threshold {
+            current = Some(current.map_or((start, end), |(first, _)| (first, end)));
+            quiet = 0;
+        } else if current.is_some() {
+            quiet += 1;
+            if quiet >= PAUSE_FRAMES {
+                stretches.extend(current.take());
+            }
+        }
+    }
+    stretches.extend(current);
+    stretches
+}
+
+// Pitch of a voiced frame by autocorrelation, or None for a quiet or
+// unvoiced one
+fn frame_pitch(frame: &[f32], threshold: f32) -> Option<f32> {
+    if rms(frame) < threshold {
+        return None;
+    }
+    let correlation = |lag: usize| {
+        let (head, tail) = (&frame[..frame.len() - lag], &frame[lag..]);
+        let product: f32 = head.iter().zip(tail).map(|(a, b)| a * b).sum();
+        let energy = (head.iter().map(|s| s * s).sum::<f32>()
+            * tail.iter().map(|s| s * s).sum::<f32>())
+        .sqrt();
+        if energy > 0.0 {
+            product / energy
+        } else {
+            0.0
+        }
+    };
+    let scores: Vec<(usize, f32)> = (MIN_LAG..=MAX_LAG)
+        .map(|lag| (lag, correlation(lag)))
+        .collect();
+    let best = scores
+        .iter()
+        .map(|&(_, score)| score)
+        .fold(f32::MIN, f32::max);
+    if best < MIN_PERIODICITY {
+        return None;
+    }
+    // Multiples of the period correlate almost as well; the peak around the
+    // first lag that comes close to the best is the pitch
+    let mut index = scores.iter().position(|&(_, score)| score >= best * 0.9)?;
+    while index + 1 < scores.len() && scores[index + 1].1 > scores[index].1 {
+        index += 1;
+    }
+    let lag = scores[index].0;
+    Some(SAMPLE_RATE as f32 / lag as f32)
+}
+
+fn rms(samples: &[f32]) -> f32 {
+    if samples.is_empty() {
+        return 0.0;
+    }
+    (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt()
+}
+
+fn median(values: &mut [f32]) -> f32 {
+    values.sort_by(f32::total_cmp);
+    values[values.len() / 2]
+}
+
+fn semitones(pitch: f32, reference: f32) -> f32 {
+    12.0 * (pitch / reference).log2()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A steady voice-like tone at 16kHz
+    fn tone(hz: f32, secs: usize) -> Vec<f32> {
+        (0..secs * 16000)
+            .map(|i| 0.3 * (2.0 * std::f32::consts::PI * hz * i as f32 / 16000.0).sin())
+            .collect()
+    }
+
+    #[test]
+    fn the_speaker_gate_keeps_only_the_enrolled_voice() {
+        assert!(enroll(&tone(120.0, 1)).is_err());
+        let profile = enroll(&tone(120.0, 4)).unwrap();
+        assert!((profile.pitch_hz - 120.0).abs() < 2.0);
+
+        // Me, a pause, then a colleague an octave up
+        let mut samples = tone(120.0, 1);
+        samples.extend(vec![0.0; 16000]);
+        samples.extend(tone(240.0, 1));
+
+        let off = SpeakerGateSettings { profile: Some(profile), ..Default::default() };
+        assert_eq!(gate(samples.clone(), &off).len(), samples.len());
+
+        let on = SpeakerGateSettings { enabled: true, ..off };
+        assert_eq!(gate(samples, &on), tone(120.0, 1));
+    }
+}
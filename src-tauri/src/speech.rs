@@ -0,0 +1,65 @@
+// Speech module
+// Reads open tasks aloud with the system's text-to-speech voice (SAPI/WinRT on
+// Windows), so a timer alert is useful away from the screen. Speech runs on one
+// worker thread that owns the engine; new text interrupts what is playing.
+
+use crate::database::{self, Database, Task};
+use std::sync::{mpsc, Mutex, OnceLock};
+
+// Longer lists are summarized as "and N more"
+const MAX_SPOKEN_TASKS: usize = 5;
+
+/// Speak `text` without blocking, cutting off anything still being spoken
+pub fn speak(text: String) {
+    static WORKER: OnceLock<Mutex<mpsc::Sender<String>>> = OnceLock::new();
+    let worker = WORKER.get_or_init(|| {
+        let (tx, rx) = mpsc::channel::<String>();
+        std::thread::spawn(move || {
+            let mut tts = match tts::Tts::default() {
+                Ok(tts) => tts,
+                Err(e) => {
+                    tracing::warn!("Text-to-speech unavailable: {}", e);
+                    return;
+                }
+            };
+            for text in rx {
+                if let Err(e) = tts.speak(text, true) {
+                    tracing::warn!("Failed to speak: {}", e);
+                }
+            }
+        });
+        Mutex::new(tx)
+    });
+    if let Ok(tx) = worker.lock() {
+        let _ = tx.send(text);
+    }
+}
+
+/// Read the open tasks aloud, top of the list first
+pub fn speak_tasks(db: &Database) -> Result<(), String> {
+    let open: Vec<Task> = database::get_all_tasks(db)
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .filter(|task| !task.completed)
+        .collect();
+    speak(tasks_summary(&open));
+    Ok(())
+}
+
+/// What is said for a list of open tasks
+pub fn tasks_summary(tasks: &[Task]) -> String {
+    if tasks.is_empty() {
+        return crate::i18n::t("speech.no_tasks");
+    }
+    let mut summary = crate::i18n::tf("speech.tasks", &[("count", &tasks.len())]);
+    for task in tasks.iter().take(MAX_SPOKEN_TASKS) {
+        summary.push(' ');
+        summary.push_str(task.text.trim().trim_end_matches('.'));
+        summary.push('.');
+    }
+    if tasks.len() > MAX_SPOKEN_TASKS {
+        summary.push(' ');
+        summary.push_str(&crate::i18n::tf("speech.more", &[("count", &(tasks.len() - MAX_SPOKEN_TASKS))]));
+    }
+    summary
+}
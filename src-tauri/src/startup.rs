@@ -0,0 +1,189 @@
+// Startup module
+// Checks run once at launch, before anything else touches the database or the
+// model: a damaged database is set aside and rebuilt from what can still be
+// read, recordings and downloads left by a crash are deleted, and the selected
+// Whisper model is checked (a broken file is removed so it can be downloaded
+// again). Each step ends up as one check in a startup report (the same shape
+// as the diagnostics report), so a problem is shown instead of the app
+// crashing at launch.
+
+use crate::database::{self, Database};
+use crate::databases;
+use crate::diagnostics::{CheckStatus, DiagnosticCheck, DiagnosticsReport};
+use crate::i18n;
+use crate::model_store::ModelStore;
+use crate::whisper::WhisperModelSize;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Event the Tauri build emits with the report once the model check is done
+pub const EVENT: &str = "startup-report";
+// First four bytes of a whisper.cpp (ggml) model file
+const GGML_MAGIC: u32 = 0x6767_6d6c;
+
+/// The report from this launch, for a UI that asks after the event went out
+#[derive(Default)]
+pub struct StartupReportState {
+    pub report: Mutex<Option<DiagnosticsReport>>,
+}
+
+/// Open the database in `dir` (the one last switched to, see `databases`),
+/// repairing it if it's damaged. When it can't be opened at all, an in-memory
+/// database stands in (nothing is saved) and the check says why.
+pub fn open_database(dir: &Path) -> (Database, DiagnosticCheck) {
+    if let Err(e) = std::fs::create_dir_all(dir) {
+        return in_memory(e);
+    }
+    let path = dir.join(databases::file_name(&databases::last_used(dir)));
+
+    let problem = match Database::open(&path) {
+        Ok(db) => match database::integrity_check(&db) {
+            Ok(problems) if problems.is_empty() => {
+                return (db, DiagnosticCheck::new("database", CheckStatus::Ok, i18n::t("diagnostics.database_ok")));
+            }
+            Ok(problems) => problems.join("; "),
+            Err(e) if database::is_corruption(&e) => e.to_string(),
+            // Not checked, but usable
+            Err(e) => {
+                let detail = i18n::tf("diagnostics.database_error", &[("error", &e)]);
+                return (db, DiagnosticCheck::new("database", CheckStatus::Warning, detail));
+            }
+        },
+        Err(e) if database::is_corruption(&e) => e.to_string(),
+        // Locked by another copy, no permission, ...: leave the file alone
+        Err(e) => return in_memory(e),
+    };
+
+    tracing::error!("Database is damaged: {}", problem);
+    match recover(&path) {
+        Ok((db, backup, lost)) => {
+            let backup = backup.display();
+            let detail = if lost.is_empty() {
+                i18n::tf("startup.database_recovered", &[("backup", &backup)])
+            } else {
+                i18n::tf("startup.database_recovered_partly", &[("backup", &backup), ("tables", &lost.join(", "))])
+            };
+            (db, DiagnosticCheck::new("database", CheckStatus::Warning, detail))
+        }
+        Err(e) => in_memory(e),
+    }
+}
+
+/// Move the damaged file (and its WAL) aside as a backup, start a new one and
+/// copy over what can be read. Returns the backup and the tables lost.
+fn recover(path: &Path) -> Result<(Database, PathBuf, Vec<String>), String> {
+    let stamp = chrono::Local::now().format("%Y%m%d-%H%M%S");
+    let file_name = path.file_name().map(|name| name.to_string_lossy()).unwrap_or_default();
+    let backup = path.with_file_name(format!("{}.corrupt-{}", file_name, stamp));
+    std::fs::rename(path, &backup).map_err(|e| e.to_string())?;
+    // SQLite finds the WAL next to the backup by name
+    for suffix in ["-wal", "-shm"] {
+        let side_file = PathBuf::from(format!("{}{}", path.display(), suffix));
+        if side_file.exists() {
+            let _ = std::fs::rename(&side_file, format!("{}{}", backup.display(), suffix));
+        }
+    }
+    tracing::info!("Damaged database moved to {}", backup.display());
+
+    let db = Database::open(path).map_err(|e| e.to_string())?;
+    let lost = database::salvage_from(&db, &backup).unwrap_or_else(|e| {
+        tracing::warn!("Nothing could be read from the damaged database: {}", e);
+        vec![i18n::t("startup.database_everything")]
+    });
+    Ok((db, backup, lost))
+}
+
+fn in_memory(error: impl std::fmt::Display) -> (Database, DiagnosticCheck) {
+    tracing::error!("Failed to open database: {}", error);
+    let db = Database::new_in_memory().expect("in-memory database");
+    let detail = i18n::tf("startup.database_unavailable", &[("error", &error)]);
+    (db, DiagnosticCheck::new("database", CheckStatus::Error, detail))
+}
+
+/// Delete recordings left in `temp_audio_dir` (the Tauri build's) and model
+/// downloads that never finished
+pub fn clean_temp_files(store: &ModelStore, temp_audio_dir: Option<&Path>) -> DiagnosticCheck {
+    let removed = temp_audio_dir.map_or(0, remove_orphaned_recordings) + store.remove_partial_downloads();
+    if removed > 0 {
+        tracing::info!("Removed {} leftover temp file(s)", removed);
+    }
+    DiagnosticCheck::new("temp_files", CheckStatus::Ok, i18n::tf("startup.temp_files_removed", &[("count", &removed)]))
+}
+
+/// Delete recordings left behind by a crash or a killed process
+pub fn remove_orphaned_recordings(dir: &Path) -> usize {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return 0;
+    };
+    entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with("recording_") && name.ends_with(".wav"))
+        })
+        .filter(|path| std::fs::remove_file(path).is_ok())
+        .count()
+}
+
+/// Check that the selected model's file is a Whisper model, deleting it if it
+/// isn't (a download cut short, a file saved over). `load` then loads it, for
+/// a UI that keeps the model loaded anyway.
+pub fn check_model(
+    store: &ModelStore,
+    model_name: &str,
+    load: impl FnOnce(WhisperModelSize) -> Result<(), String>,
+) -> DiagnosticCheck {
+    let check = check_model_file(store, model_name);
+    let Some(model) = WhisperModelSize::from_str(model_name).filter(|_| check.status == CheckStatus::Ok) else {
+        return check;
+    };
+    match load(model) {
+        Ok(()) => check,
+        Err(e) => DiagnosticCheck::new(
+            "models",
+            CheckStatus::Error,
+            i18n::tf("startup.model_failed", &[("model", &model.name()), ("error", &e)]),
+        ),
+    }
+}
+
+/// `check_model` without loading the model
+pub fn check_model_file(store: &ModelStore, model_name: &str) -> DiagnosticCheck {
+    let Some(model) = WhisperModelSize::from_str(model_name).filter(|&model| store.is_installed(model)) else {
+        // Nothing to check until it's downloaded
+        return DiagnosticCheck::new(
+            "models",
+            CheckStatus::Warning,
+            i18n::tf("diagnostics.models_selected_missing", &[("model", &model_name)]),
+        );
+    };
+    let path = store.model_path(model);
+    if is_ggml_file(&path) {
+        return DiagnosticCheck::new("models", CheckStatus::Ok, i18n::tf("startup.model_ok", &[("model", &model.name())]));
+    }
+
+    tracing::error!("{} is not a Whisper model file", path.display());
+    let detail = match store.delete(model) {
+        Ok(()) => i18n::tf("startup.model_removed", &[("model", &model.name())]),
+        Err(e) => i18n::tf("startup.model_failed", &[("model", &model.name()), ("error", &e)]),
+    };
+    DiagnosticCheck::new("models", CheckStatus::Error, detail)
+}
+
+fn is_ggml_file(path: &Path) -> bool {
+    let mut magic = [0u8; 4];
+    std::fs::File::open(path)
+        .and_then(|mut file| file.read_exact(&mut magic))
+        .is_ok_and(|()| u32::from_le_bytes(magic) == GGML_MAGIC)
+}
+
+/// The startup report from its checks
+pub fn report(checks: Vec<DiagnosticCheck>) -> DiagnosticsReport {
+    let report = DiagnosticsReport::new(checks);
+    for check in report.checks.iter().filter(|check| check.status != CheckStatus::Ok) {
+        tracing::warn!("Startup: {} - {}", check.id, check.detail);
+    }
+    report
+}
@@ -0,0 +1,31 @@
+// Speech-to-text module
+// The interface transcription goes through, so the commands don't depend on
+// one engine. Whisper (whisper-rs, with models from the shared store) is the
+// only engine so far; another one (a remote Whisper server, Vosk) implements
+// `SpeechToText` and gets picked in `engine`, and the callers stay as they are.
+
+use crate::error::FlowStateError;
+use crate::whisper::{WhisperCache, WhisperModelSize};
+use std::sync::Arc;
+use whisper_rs::WhisperContext;
+
+/// A speech-to-text engine, loaded and ready to transcribe
+pub trait SpeechToText: Send + Sync {
+    /// Transcribe 16kHz mono samples. `language` is a language code
+    /// ("en", "ru"); `None` auto-detects.
+    fn transcribe(&self, samples: &[f32], language: Option<&str>) -> Result<String, String>;
+}
+
+/// A Whisper model loaded through whisper-rs
+impl SpeechToText for WhisperContext {
+    fn transcribe(&self, samples: &[f32], language: Option<&str>) -> Result<String, String> {
+        crate::whisper::transcribe_samples_with_context(self, samples, language)
+    }
+}
+
+/// The engine for a model named in the settings or by the UI; Whisper models
+/// come from (and stay in) `whisper_cache`
+pub fn engine(whisper_cache: &WhisperCache, model_name: &str) -> Result<Arc<dyn SpeechToText>, FlowStateError> {
+    let model = WhisperModelSize::parse(model_name)?;
+    Ok(whisper_cache.get_or_create(model)?)
+}
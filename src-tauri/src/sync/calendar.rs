@@ -0,0 +1,247 @@
+// Calendar feed
+// Keeps an .ics file with an event for every open task that has a due date,
+// so calendar apps that subscribe to the file (or import it) show the tasks
+// next to the rest of the day. A due time becomes an event lasting the task's
+// estimate (half an hour without one); a due day without a time becomes an
+// all-day event. Export only: nothing made in the calendar comes back.
+
+use crate::database::{self, Database, Task, TaskQuery, TaskStatus};
+use crate::settings::CalendarExportSettings;
+use chrono::{Duration, NaiveDate, NaiveDateTime};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+
+// How often the task list is compared with the last written feed
+const POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(2);
+// Length of an event for a task without an estimate
+const DEFAULT_MINUTES: i64 = 30;
+// Lines longer than this (in bytes) are folded, as RFC 5545 asks
+const MAX_LINE: usize = 75;
+const UTC_FORMAT: &str = "%Y%m%dT%H%M%SZ";
+
+/// Running export thread; stops when dropped
+pub struct CalendarExport {
+    stop: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+/// Managed state holding the active export (if it is enabled)
+#[derive(Default)]
+pub struct CalendarExportState {
+    pub export: Mutex<Option<CalendarExport>>,
+}
+
+impl CalendarExport {
+    pub fn start(db: &Database, config: &CalendarExportSettings) -> Result<Self, String> {
+        let path = PathBuf::from(config.path.trim());
+        if !path.parent().is_some_and(Path::is_dir) {
+            return Err(crate::i18n::tf("error.calendar_folder", &[("path", &path.display())]));
+        }
+        let db = db.reopen().map_err(|e| e.to_string())?;
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let stop_flag = stop.clone();
+        let handle = std::thread::spawn(move || {
+            tracing::info!("Calendar export started ({})", path.display());
+            let mut written = None;
+            while !stop_flag.load(Ordering::Relaxed) {
+                export(&db, &path, &mut written);
+                std::thread::sleep(POLL_INTERVAL);
+            }
+            tracing::info!("Calendar export stopped");
+        });
+
+        Ok(Self {
+            stop,
+            handle: Some(handle),
+        })
+    }
+}
+
+impl Drop for CalendarExport {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+/// When a task's event happens
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EventTime {
+    /// A due time: the task's estimate long (half an hour without one), in UTC
+    At { start: NaiveDateTime, end: NaiveDateTime },
+    /// A due day without a time
+    AllDay(NaiveDate),
+}
+
+/// The event for an open task with a due date, or None
+pub fn event_time(task: &Task) -> Option<EventTime> {
+    if task.completed {
+        return None;
+    }
+    let due = task.due_at.as_deref()?;
+    let start = NaiveDateTime::parse_from_str(due, "%Y-%m-%d %H:%M:%S").ok()?;
+    let local = crate::dates::from_db(due)?;
+    if !crate::dates::has_time(local) {
+        return Some(EventTime::AllDay(local.date()));
+    }
+    let minutes = task.estimate_minutes.filter(|&minutes| minutes > 0).unwrap_or(DEFAULT_MINUTES);
+    Some(EventTime::At {
+        start,
+        end: start + Duration::minutes(minutes),
+    })
+}
+
+/// The feed for a task list: one event per open task with a due date
+pub fn render(tasks: &[Task]) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//FlowState//Tasks//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+        "X-WR-CALNAME:FlowState".to_string(),
+    ];
+    for task in tasks {
+        let Some(time) = event_time(task) else {
+            continue;
+        };
+        lines.push("BEGIN:VEVENT".to_string());
+        lines.push(format!("UID:task-{}@flowstate", task.id));
+        // Stable, so an unchanged task renders the same every time
+        let stamp = NaiveDateTime::parse_from_str(&task.created_at, "%Y-%m-%d %H:%M:%S").unwrap_or_default();
+        lines.push(format!("DTSTAMP:{}", stamp.format(UTC_FORMAT)));
+        match time {
+            EventTime::At { start, end } => {
+                lines.push(format!("DTSTART:{}", start.format(UTC_FORMAT)));
+                lines.push(format!("DTEND:{}", end.format(UTC_FORMAT)));
+            }
+            EventTime::AllDay(day) => {
+                lines.push(format!("DTSTART;VALUE=DATE:{}", day.format("%Y%m%d")));
+                lines.push(format!("DTEND;VALUE=DATE:{}", (day + Duration::days(1)).format("%Y%m%d")));
+            }
+        }
+        lines.push(format!("SUMMARY:{}", escape(task.text.trim())));
+        if let Some(context) = &task.context {
+            lines.push(format!("CATEGORIES:{}", escape(context)));
+        }
+        lines.push("END:VEVENT".to_string());
+    }
+    lines.push("END:VCALENDAR".to_string());
+
+    let mut feed = String::new();
+    for line in lines {
+        feed.push_str(&fold(&line));
+        feed.push_str("\r\n");
+    }
+    feed
+}
+
+// Text values escape backslashes, commas, semicolons and line breaks
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' | ',' | ';' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '\n' => escaped.push_str("\\n"),
+            '\r' => {}
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+// Long lines go on as continuation lines starting with a space, never
+// splitting a character
+fn fold(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len());
+    let mut length = 0;
+    for c in line.chars() {
+        if length + c.len_utf8() > MAX_LINE {
+            folded.push_str("\r\n ");
+            length = 1;
+        }
+        folded.push(c);
+        length += c.len_utf8();
+    }
+    folded
+}
+
+// Write the feed if it changed since the last write
+fn export(db: &Database, path: &Path, written: &mut Option<String>) {
+    let query = TaskQuery {
+        status: TaskStatus::Open,
+        ..Default::default()
+    };
+    let tasks = match database::query_tasks(db, &query) {
+        Ok(tasks) => tasks,
+        Err(e) => {
+            tracing::warn!("Calendar export couldn't read tasks: {}", e);
+            return;
+        }
+    };
+    let feed = render(&tasks);
+    if written.as_deref() == Some(feed.as_str()) {
+        return;
+    }
+    // Replace in one step so a calendar app never reads a half-written feed
+    let temp = path.with_extension("ics.tmp");
+    match std::fs::write(&temp, &feed).and_then(|()| std::fs::rename(&temp, path)) {
+        Ok(()) => *written = Some(feed),
+        Err(e) => tracing::warn!("Calendar export couldn't write {}: {}", path.display(), e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn calendar_feed_has_an_event_per_open_dated_task() {
+        let task = |id: i64, text: &str, due: Option<NaiveDateTime>| Task {
+            id,
+            text: text.to_string(),
+            completed: false,
+            created_at: "2026-05-01 08:00:00".to_string(),
+            completed_at: None,
+            context: None,
+            estimate_minutes: None,
+            due_at: due.and_then(crate::dates::to_db),
+            inbox: false,
+        };
+        let day = NaiveDate::from_ymd_opt(2026, 5, 12).unwrap();
+        let meeting = day.and_hms_opt(15, 0, 0).unwrap();
+        let mut timed = task(1, "Call Anna; bring notes, slides", Some(meeting));
+        timed.estimate_minutes = Some(45);
+        let all_day = task(2, "Pay rent", Some(day.and_hms_opt(23, 59, 0).unwrap()));
+        let undated = task(3, "Someday", None);
+        let mut done = task(4, "Already done", Some(meeting));
+        done.completed = true;
+        let tasks = [timed.clone(), all_day, undated, done];
+
+        let feed = render(&tasks);
+        assert!(feed.starts_with("BEGIN:VCALENDAR\r\n") && feed.ends_with("END:VCALENDAR\r\n"));
+        assert_eq!(feed.matches("BEGIN:VEVENT").count(), 2);
+        assert!(!feed.contains("Someday") && !feed.contains("Already done"));
+        assert!(feed.contains(concat!(r"SUMMARY:Call Anna\; bring notes\, slides", "\r\n")));
+
+        // A due time is an event as long as the estimate, in UTC
+        let start = NaiveDateTime::parse_from_str(timed.due_at.as_deref().unwrap(), "%Y-%m-%d %H:%M:%S").unwrap();
+        let end = start + Duration::minutes(45);
+        assert!(feed.contains(&format!("DTSTART:{}\r\n", start.format("%Y%m%dT%H%M%SZ"))));
+        assert!(feed.contains(&format!("DTEND:{}\r\n", end.format("%Y%m%dT%H%M%SZ"))));
+
+        // A due day alone is an all-day event on that day
+        assert!(feed.contains("DTSTART;VALUE=DATE:20260512\r\n"));
+        assert!(feed.contains("DTEND;VALUE=DATE:20260513\r\n"));
+
+        // The same tasks give the same feed, so the file isn't rewritten for nothing
+        assert_eq!(feed, render(&tasks));
+    }
+}
@@ -0,0 +1,428 @@
+// Google Calendar push
+// Opt-in, one-way push of dated tasks to a Google calendar, so they show up
+// next to the rest of the day without subscribing to the .ics feed. Each open
+// task with a due date gets one event, timed like in the feed (see
+// `calendar::event_time`); the event changes when the task does and is
+// deleted once the task is completed, undated or deleted. Nothing made in
+// Google Calendar comes back.
+// Signing in uses the OAuth loopback flow for desktop apps: `connect` opens
+// Google's consent page in the browser and waits on a local port for the
+// redirect, then keeps only the refresh token. The google_calendar_events
+// table remembers each task's event and the body last sent.
+
+use crate::database::{self, Database, GoogleCalendarEvent, TaskQuery, TaskStatus};
+use crate::settings::GoogleCalendarSettings;
+use crate::sync::calendar::{self, EventTime};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::{mpsc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+const API_URL: &str = "https://www.googleapis.com/calendar/v3";
+const AUTH_URL: &str = "https://accounts.google.com/o/oauth2/v2/auth";
+const TOKEN_URL: &str = "https://oauth2.googleapis.com/token";
+// Events only: FlowState never reads the rest of the calendar
+const SCOPE: &str = "https://www.googleapis.com/auth/calendar.events";
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(20);
+// How often the task list is compared with what was pushed
+const POLL_INTERVAL: Duration = Duration::from_secs(60);
+// How long `connect` waits for the browser to come back
+const SIGN_IN_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Called with a message when a run fails (once until a run succeeds again)
+pub type ErrorHandler = Box<dyn Fn(String) + Send>;
+
+/// Running push thread; stops when dropped
+pub struct GoogleCalendarSync {
+    // Dropping the sender wakes the thread and ends it
+    _stop: mpsc::Sender<()>,
+}
+
+/// Managed state holding the active push (if it is enabled)
+#[derive(Default)]
+pub struct GoogleCalendarSyncState {
+    pub sync: Mutex<Option<GoogleCalendarSync>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    access_token: Option<String>,
+    expires_in: Option<u64>,
+    refresh_token: Option<String>,
+}
+
+impl GoogleCalendarSync {
+    pub fn start(db: &Database, config: &GoogleCalendarSettings, on_error: ErrorHandler) -> Result<Self, String> {
+        config.validate()?;
+        let db = db.reopen().map_err(|e| e.to_string())?;
+        let mut api = Api::new(config)?;
+
+        let (stop_tx, stop_rx) = mpsc::channel::<()>();
+        // Not joined on drop: a request in flight may take a while, and the
+        // thread ends by itself once it sees the sender is gone
+        std::thread::spawn(move || {
+            let runtime = match tokio::runtime::Runtime::new() {
+                Ok(runtime) => runtime,
+                Err(e) => {
+                    on_error(e.to_string());
+                    return;
+                }
+            };
+            tracing::info!("Google Calendar push started");
+            let mut failing = false;
+            loop {
+                match runtime.block_on(push_once(&mut api, &db)) {
+                    Ok(()) => failing = false,
+                    Err(e) => {
+                        tracing::warn!("Google Calendar push failed: {}", e);
+                        if !failing {
+                            on_error(crate::i18n::tf("error.google_calendar_sync", &[("error", &e)]));
+                        }
+                        failing = true;
+                    }
+                }
+                if !matches!(stop_rx.recv_timeout(POLL_INTERVAL), Err(mpsc::RecvTimeoutError::Timeout)) {
+                    break;
+                }
+            }
+            tracing::info!("Google Calendar push stopped");
+        });
+
+        Ok(Self { _stop: stop_tx })
+    }
+}
+
+/// Sign in to Google in the browser (`open` shows the consent page) and
+/// return the refresh token to keep in the settings
+pub async fn connect(
+    client_id: &str,
+    client_secret: &str,
+    open: impl FnOnce(&str) -> Result<(), String>,
+) -> Result<String, String> {
+    let (client_id, client_secret) = (client_id.trim(), client_secret.trim());
+    if client_id.is_empty() || client_secret.is_empty() {
+        return Err(crate::i18n::t("error.google_client_empty"));
+    }
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+        .await
+        .map_err(|e| e.to_string())?;
+    let redirect = format!("http://127.0.0.1:{}", listener.local_addr().map_err(|e| e.to_string())?.port());
+    // Ties the redirect to this sign-in, so another page can't hand us a code
+    let state = random_hex(16)?;
+    let url = reqwest::Url::parse_with_params(AUTH_URL, &[
+        ("client_id", client_id),
+        ("redirect_uri", redirect.as_str()),
+        ("response_type", "code"),
+        ("scope", SCOPE),
+        ("access_type", "offline"),
+        ("prompt", "consent"),
+        ("state", state.as_str()),
+    ])
+    .map_err(|e| e.to_string())?;
+    open(url.as_str())?;
+
+    let code = tokio::time::timeout(SIGN_IN_TIMEOUT, wait_for_code(&listener, &state))
+        .await
+        .map_err(|_| crate::i18n::t("error.google_sign_in_timeout"))??;
+
+    let client = client()?;
+    let response = client
+        .post(TOKEN_URL)
+        .form(&[
+            ("client_id", client_id),
+            ("client_secret", client_secret),
+            ("code", code.as_str()),
+            ("redirect_uri", redirect.as_str()),
+            ("grant_type", "authorization_code"),
+        ])
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    let token: TokenResponse = check_status(response)?
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse Google's token: {}", e))?;
+    token
+        .refresh_token
+        .ok_or_else(|| crate::i18n::tf("error.google_sign_in", &[("error", &"no refresh token")]))
+}
+
+// Take redirects until one carries this sign-in's state, and answer the
+// browser with a page saying it can be closed
+async fn wait_for_code(listener: &tokio::net::TcpListener, state: &str) -> Result<String, String> {
+    loop {
+        let (mut stream, _) = listener.accept().await.map_err(|e| e.to_string())?;
+        let mut buffer = vec![0u8; 8192];
+        let read = stream.read(&mut buffer).await.map_err(|e| e.to_string())?;
+        let request = String::from_utf8_lossy(&buffer[..read]);
+        // "GET /?state=...&code=... HTTP/1.1"
+        let Some(target) = request.lines().next().and_then(|line| line.split_whitespace().nth(1)) else {
+            continue;
+        };
+        let Ok(url) = reqwest::Url::parse(&format!("http://127.0.0.1{}", target)) else {
+            continue;
+        };
+        let query: HashMap<_, _> = url.query_pairs().into_owned().collect();
+        if query.get("state").map(String::as_str) != Some(state) {
+            continue;
+        }
+        let page = crate::i18n::t("settings.google_signed_in");
+        let reply = format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            page.len(),
+            page
+        );
+        let _ = stream.write_all(reply.as_bytes()).await;
+        return match (query.get("code"), query.get("error")) {
+            (Some(code), _) => Ok(code.clone()),
+            (None, error) => Err(crate::i18n::tf("error.google_sign_in", &[(
+                "error",
+                &error.map(String::as_str).unwrap_or("no code"),
+            )])),
+        };
+    }
+}
+
+// One run: create, update and delete events until the calendar matches the
+// dated open tasks
+async fn push_once(api: &mut Api, db: &Database) -> Result<(), String> {
+    let query = TaskQuery {
+        status: TaskStatus::Open,
+        ..Default::default()
+    };
+    let mut wanted: HashMap<i64, String> = HashMap::new();
+    for task in database::query_tasks(db, &query).map_err(|e| e.to_string())? {
+        if let Some(time) = calendar::event_time(&task) {
+            wanted.insert(task.id, event_body(task.text.trim(), &time).to_string());
+        }
+    }
+    let mut pushed: HashMap<i64, GoogleCalendarEvent> = database::google_calendar_events(db)
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(|event| (event.task_id, event))
+        .collect();
+
+    for (task_id, body) in wanted {
+        let event = match pushed.remove(&task_id) {
+            Some(event) if event.synced == body => continue,
+            Some(event) => {
+                if !api.update(&event.event_id, &body).await? {
+                    // Deleted in Google Calendar: put it back
+                    api.insert(&event.event_id, &body).await?;
+                }
+                event
+            }
+            None => {
+                let event_id = format!("fs{}", random_hex(16)?);
+                api.insert(&event_id, &body).await?;
+                GoogleCalendarEvent {
+                    task_id,
+                    event_id,
+                    synced: String::new(),
+                }
+            }
+        };
+        database::save_google_calendar_event(db, &GoogleCalendarEvent { synced: body, ..event })
+            .map_err(|e| e.to_string())?;
+    }
+
+    // Whatever is left belongs to tasks that were completed, undated or deleted
+    for event in pushed.into_values() {
+        api.delete(&event.event_id).await?;
+        database::delete_google_calendar_event(db, event.task_id).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// The event Google Calendar gets for a task
+fn event_body(summary: &str, time: &EventTime) -> serde_json::Value {
+    let (start, end) = match time {
+        EventTime::At { start, end } => (
+            serde_json::json!({ "dateTime": start.format("%Y-%m-%dT%H:%M:%SZ").to_string() }),
+            serde_json::json!({ "dateTime": end.format("%Y-%m-%dT%H:%M:%SZ").to_string() }),
+        ),
+        EventTime::AllDay(day) => (
+            serde_json::json!({ "date": day.format("%Y-%m-%d").to_string() }),
+            serde_json::json!({ "date": (*day + chrono::Duration::days(1)).format("%Y-%m-%d").to_string() }),
+        ),
+    };
+    serde_json::json!({
+        "summary": summary,
+        "start": start,
+        "end": end,
+        // An event deleted in Google Calendar stays behind as cancelled;
+        // updating it with this brings it back
+        "status": "confirmed",
+    })
+}
+
+struct Api {
+    client: reqwest::Client,
+    client_id: String,
+    client_secret: String,
+    refresh_token: String,
+    calendar_id: String,
+    // Access token and when it stops working
+    access: Option<(String, Instant)>,
+}
+
+impl Api {
+    fn new(config: &GoogleCalendarSettings) -> Result<Self, String> {
+        Ok(Self {
+            client: client()?,
+            client_id: config.client_id.trim().to_string(),
+            client_secret: config.client_secret.trim().to_string(),
+            refresh_token: config.refresh_token.trim().to_string(),
+            calendar_id: config.calendar_id.trim().to_string(),
+            access: None,
+        })
+    }
+
+    fn events_url(&self) -> Result<reqwest::Url, String> {
+        let mut url = reqwest::Url::parse(API_URL).map_err(|e| e.to_string())?;
+        url.path_segments_mut()
+            .map_err(|()| "Invalid Google Calendar URL".to_string())?
+            .extend(["calendars", self.calendar_id.as_str(), "events"]);
+        Ok(url)
+    }
+
+    fn event_url(&self, event_id: &str) -> Result<reqwest::Url, String> {
+        let mut url = self.events_url()?;
+        url.path_segments_mut()
+            .map_err(|()| "Invalid Google Calendar URL".to_string())?
+            .push(event_id);
+        Ok(url)
+    }
+
+    // A fresh access token from the refresh token, reused until it expires
+    async fn access_token(&mut self) -> Result<String, String> {
+        if let Some((token, expires)) = &self.access {
+            if Instant::now() < *expires {
+                return Ok(token.clone());
+            }
+        }
+        let response = self
+            .client
+            .post(TOKEN_URL)
+            .form(&[
+                ("client_id", self.client_id.as_str()),
+                ("client_secret", self.client_secret.as_str()),
+                ("refresh_token", self.refresh_token.as_str()),
+                ("grant_type", "refresh_token"),
+            ])
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+        if response.status() == reqwest::StatusCode::BAD_REQUEST || response.status() == reqwest::StatusCode::UNAUTHORIZED {
+            // Revoked, expired or for another client
+            return Err(crate::i18n::t("error.google_calendar_token"));
+        }
+        let token: TokenResponse = check_status(response)?
+            .json()
+            .await
+            .map_err(|e| format!("Failed to parse Google's token: {}", e))?;
+        let access = token.access_token.ok_or("Google sent no access token")?;
+        // A minute early, so a token never runs out mid-run
+        let lifetime = Duration::from_secs(token.expires_in.unwrap_or(3600).saturating_sub(60));
+        self.access = Some((access.clone(), Instant::now() + lifetime));
+        Ok(access)
+    }
+
+    // Insert with our own id; if the id is taken (an earlier run inserted it
+    // and then failed to save the link) update that event instead
+    async fn insert(&mut self, event_id: &str, body: &str) -> Result<(), String> {
+        let mut event: serde_json::Value = serde_json::from_str(body).map_err(|e| e.to_string())?;
+        event["id"] = serde_json::Value::from(event_id);
+        let request = self.client.post(self.events_url()?).json(&event);
+        let response = self.send_unchecked(request).await?;
+        if response.status() == reqwest::StatusCode::CONFLICT {
+            self.update(event_id, body).await?;
+            return Ok(());
+        }
+        check_status(response).map(drop)
+    }
+
+    /// Replace an event; false if Google Calendar no longer has it
+    async fn update(&mut self, event_id: &str, body: &str) -> Result<bool, String> {
+        let request = self
+            .client
+            .put(self.event_url(event_id)?)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(body.to_string());
+        let response = self.send_unchecked(request).await?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(false);
+        }
+        check_status(response).map(|_| true)
+    }
+
+    // Already gone is as good as deleted
+    async fn delete(&mut self, event_id: &str) -> Result<(), String> {
+        let request = self.client.delete(self.event_url(event_id)?);
+        let response = self.send_unchecked(request).await?;
+        if matches!(response.status(), reqwest::StatusCode::NOT_FOUND | reqwest::StatusCode::GONE) {
+            return Ok(());
+        }
+        check_status(response).map(drop)
+    }
+
+    async fn send_unchecked(&mut self, request: reqwest::RequestBuilder) -> Result<reqwest::Response, String> {
+        let token = self.access_token().await?;
+        request.bearer_auth(token).send().await.map_err(|e| e.to_string())
+    }
+}
+
+fn client() -> Result<reqwest::Client, String> {
+    reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .map_err(|e| e.to_string())
+}
+
+fn check_status(response: reqwest::Response) -> Result<reqwest::Response, String> {
+    if response.status() == reqwest::StatusCode::UNAUTHORIZED || response.status() == reqwest::StatusCode::FORBIDDEN {
+        return Err(crate::i18n::t("error.google_calendar_token"));
+    }
+    if !response.status().is_success() {
+        return Err(format!("HTTP {}", response.status()));
+    }
+    Ok(response)
+}
+
+// Event ids may only use the base32hex alphabet (0-9, a-v), which hex is in
+fn random_hex(bytes: usize) -> Result<String, String> {
+    let mut random = vec![0u8; bytes];
+    getrandom::getrandom(&mut random).map_err(|e| e.to_string())?;
+    Ok(random.iter().map(|byte| format!("{:02x}", byte)).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn timed_events_are_sent_in_utc_and_all_day_events_end_the_next_day() {
+        let start = NaiveDate::from_ymd_opt(2026, 5, 4).unwrap().and_hms_opt(7, 30, 0).unwrap();
+        let timed = event_body("Call Anna", &EventTime::At {
+            start,
+            end: start + chrono::Duration::minutes(45),
+        });
+        assert_eq!(timed["summary"], "Call Anna");
+        assert_eq!(timed["start"]["dateTime"], "2026-05-04T07:30:00Z");
+        assert_eq!(timed["end"]["dateTime"], "2026-05-04T08:15:00Z");
+
+        let all_day = event_body("Pay rent", &EventTime::AllDay(NaiveDate::from_ymd_opt(2026, 5, 31).unwrap()));
+        assert_eq!(all_day["start"]["date"], "2026-05-31");
+        assert_eq!(all_day["end"]["date"], "2026-06-01");
+        assert_eq!(all_day["status"], "confirmed");
+    }
+
+    #[test]
+    fn event_ids_use_only_characters_google_accepts() {
+        let id = format!("fs{}", random_hex(16).unwrap());
+        assert_eq!(id.len(), 34);
+        assert!(id.chars().all(|c| c.is_ascii_digit() || ('a'..='v').contains(&c)));
+    }
+}
@@ -0,0 +1,197 @@
+// Markdown vault sync
+// Mirrors the main task list into a Markdown note (e.g. in an Obsidian vault)
+// as `- [ ]` checkboxes. Each line ends with a hidden `<!-- flowstate:ID -->`
+// comment so ticks made in the note can be matched back to tasks when two-way
+// sync is on. FlowState owns the file: anything else edited there is
+// overwritten on the next write.
+
+use crate::database::{self, Database, Task, TaskQuery};
+use crate::settings::MarkdownSyncSettings;
+use notify::{RecursiveMode, Watcher};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+// How often the task list is compared with the last written note
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+const ID_MARKER: &str = "<!-- flowstate:";
+
+/// Task changed by an edit in the note
+#[derive(Debug, Clone)]
+pub enum Imported {
+    /// New unticked line, now a task
+    Added(Task),
+    /// Ticked or unticked line
+    Toggled(Task),
+}
+
+pub type ImportHandler = Box<dyn Fn(Imported) + Send>;
+
+/// Running sync thread; stops when dropped
+pub struct MarkdownSync {
+    stop: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+/// Managed state holding the active sync (if it is enabled)
+#[derive(Default)]
+pub struct MarkdownSyncState {
+    pub sync: Mutex<Option<MarkdownSync>>,
+}
+
+impl MarkdownSync {
+    pub fn start(db: &Database, config: &MarkdownSyncSettings, on_import: ImportHandler) -> Result<Self, String> {
+        let path = PathBuf::from(config.path.trim());
+        let folder = path
+            .parent()
+            .filter(|folder| folder.is_dir())
+            .ok_or_else(|| crate::i18n::tf("error.markdown_folder", &[("path", &path.display())]))?
+            .to_path_buf();
+        let db = db.reopen().map_err(|e| e.to_string())?;
+
+        // Editors often save by replacing the file, so watch its folder
+        let (event_tx, event_rx) = mpsc::channel();
+        let mut watcher = notify::recommended_watcher(event_tx).map_err(|e| e.to_string())?;
+        if config.two_way {
+            watcher
+                .watch(&folder, RecursiveMode::NonRecursive)
+                .map_err(|e| e.to_string())?;
+        }
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let stop_flag = stop.clone();
+        let two_way = config.two_way;
+        let handle = std::thread::spawn(move || {
+            // Keep the watcher alive for the life of the thread
+            let _watcher = watcher;
+            tracing::info!("Markdown sync started ({})", path.display());
+            // Last note written or read, so our own writes aren't imported back
+            let mut written = None;
+            // Pick up ticks made while FlowState wasn't running
+            if two_way {
+                import(&db, &path, &mut written, &on_import);
+            }
+            while !stop_flag.load(Ordering::Relaxed) {
+                match event_rx.recv_timeout(POLL_INTERVAL) {
+                    Ok(Ok(event)) if event.paths.iter().any(|p| p.file_name() == path.file_name()) => {
+                        import(&db, &path, &mut written, &on_import);
+                    }
+                    Ok(Err(e)) => tracing::warn!("Markdown sync watcher error: {}", e),
+                    Ok(Ok(_)) | Err(mpsc::RecvTimeoutError::Timeout) => {}
+                    Err(mpsc::RecvTimeoutError::Disconnected) => break,
+                }
+                export(&db, &path, &mut written);
+            }
+            tracing::info!("Markdown sync stopped");
+        });
+
+        Ok(Self {
+            stop,
+            handle: Some(handle),
+        })
+    }
+}
+
+impl Drop for MarkdownSync {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+/// The note for a task list: a heading and one checkbox line per task
+pub fn render(tasks: &[Task]) -> String {
+    let mut note = String::from("# FlowState\n\n");
+    for task in tasks {
+        let mark = if task.completed { 'x' } else { ' ' };
+        // The context and estimate live in their own columns; show them the way they can be typed
+        let context = task.context.as_deref().map(|context| format!(" @{}", context)).unwrap_or_default();
+        let estimate = task
+            .estimate_minutes
+            .map(|minutes| format!(" ~{}", crate::estimates::format_estimate(minutes)))
+            .unwrap_or_default();
+        note.push_str(&format!(
+            "- [{}] {}{}{} {}{} -->\n",
+            mark,
+            task.text.trim(),
+            context,
+            estimate,
+            ID_MARKER,
+            task.id
+        ));
+    }
+    note
+}
+
+/// A checkbox line as (ticked, text, task id if it came from FlowState)
+pub fn parse_line(line: &str) -> Option<(bool, String, Option<i64>)> {
+    let rest = line.trim_start().strip_prefix("- [")?;
+    let mut chars = rest.chars();
+    let ticked = match chars.next()? {
+        ' ' => false,
+        'x' | 'X' => true,
+        _ => return None,
+    };
+    let rest = chars.as_str().strip_prefix(']')?;
+    let (text, id) = match rest.rfind(ID_MARKER) {
+        Some(start) => {
+            let id = rest[start + ID_MARKER.len()..].trim_end().strip_suffix("-->")?.trim().parse().ok();
+            (&rest[..start], id)
+        }
+        None => (rest, None),
+    };
+    Some((ticked, text.trim().to_string(), id))
+}
+
+// Write the note if the task list changed since the last write
+fn export(db: &Database, path: &Path, written: &mut Option<String>) {
+    let tasks = match database::query_tasks(db, &TaskQuery::default()) {
+        Ok(tasks) => tasks,
+        Err(e) => {
+            tracing::warn!("Markdown sync couldn't read tasks: {}", e);
+            return;
+        }
+    };
+    let note = render(&tasks);
+    if written.as_deref() == Some(note.as_str()) {
+        return;
+    }
+    // Replace in one step so the vault never sees a half-written note
+    let temp = path.with_extension("md.tmp");
+    match std::fs::write(&temp, &note).and_then(|()| std::fs::rename(&temp, path)) {
+        Ok(()) => *written = Some(note),
+        Err(e) => tracing::warn!("Markdown sync couldn't write {}: {}", path.display(), e),
+    }
+}
+
+// Apply ticks and new lines from the note. Removed lines are not deleted;
+// the next write puts them back.
+fn import(db: &Database, path: &Path, written: &mut Option<String>, on_import: &ImportHandler) {
+    let Ok(note) = std::fs::read_to_string(path) else {
+        return;
+    };
+    // Our own write coming back through the watcher
+    if written.as_deref() == Some(note.as_str()) {
+        return;
+    }
+    for (ticked, text, id) in note.lines().filter_map(parse_line) {
+        let result = match id {
+            Some(id) => match database::get_task_by_id(db, id) {
+                Ok(task) if task.completed != ticked => database::toggle_task(db, id).map(Imported::Toggled),
+                _ => continue,
+            },
+            None if !ticked && !text.is_empty() => database::add_task(db, &text).map(Imported::Added),
+            None => continue,
+        };
+        match result {
+            Ok(imported) => on_import(imported),
+            Err(e) => tracing::warn!("Markdown sync couldn't apply \"{}\": {}", text, e),
+        }
+    }
+    // Rewrite the note so new lines get their ids
+    *written = Some(note);
+}
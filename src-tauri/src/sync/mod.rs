@@ -0,0 +1,9 @@
+// Sync module
+// Keeps the task list in step with places outside FlowState. Each target runs
+// on its own thread with its own database connection and reports the tasks it
+// changed so the UI can refresh.
+
+pub mod calendar;
+pub mod google_calendar;
+pub mod markdown;
+pub mod todoist;
@@ -0,0 +1,239 @@
+// Todoist sync
+// Two-way sync with Todoist over its REST API, on a schedule. Open FlowState
+// tasks are pushed to Todoist once; after that, completions and text edits flow
+// both ways. The todoist_links table keeps what both sides looked like after
+// the last run, so each run can tell which side changed:
+// - a change on one side is copied to the other
+// - when both sides edited the text, FlowState's text wins
+// - both sides can't disagree on completion, since each can only flip the
+//   shared state
+// Tasks deleted on either side just stop syncing; nothing is deleted remotely.
+
+use crate::database::{self, Database, Task, TodoistLink};
+use crate::settings::TodoistSettings;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::{mpsc, Mutex};
+use std::time::Duration;
+
+const API_URL: &str = "https://api.todoist.com/rest/v2";
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(20);
+
+#[derive(Debug, Clone)]
+pub enum SyncEvent {
+    /// FlowState task changed to match Todoist
+    TaskChanged(Task),
+    /// A run failed (reported once until a run succeeds again)
+    Failed(String),
+}
+
+pub type EventHandler = Box<dyn Fn(SyncEvent) + Send>;
+
+/// Running sync thread; stops when dropped
+pub struct TodoistSync {
+    // Dropping the sender wakes the thread and ends it
+    _stop: mpsc::Sender<()>,
+}
+
+/// Managed state holding the active sync (if it is enabled)
+#[derive(Default)]
+pub struct TodoistSyncState {
+    pub sync: Mutex<Option<TodoistSync>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RemoteTask {
+    id: String,
+    content: String,
+}
+
+impl TodoistSync {
+    pub fn start(db: &Database, config: &TodoistSettings, on_event: EventHandler) -> Result<Self, String> {
+        config.validate()?;
+        let db = db.reopen().map_err(|e| e.to_string())?;
+        let api = Api::new(config.token.trim())?;
+        let interval = Duration::from_secs(config.interval_minutes * 60);
+
+        let (stop_tx, stop_rx) = mpsc::channel::<()>();
+        // Not joined on drop: a request in flight may take a while, and the
+        // thread ends by itself once it sees the sender is gone
+        std::thread::spawn(move || {
+            let runtime = match tokio::runtime::Runtime::new() {
+                Ok(runtime) => runtime,
+                Err(e) => {
+                    on_event(SyncEvent::Failed(e.to_string()));
+                    return;
+                }
+            };
+            tracing::info!("Todoist sync started (every {} min)", interval.as_secs() / 60);
+            let mut failing = false;
+            loop {
+                match runtime.block_on(sync_once(&api, &db, &on_event)) {
+                    Ok(()) => failing = false,
+                    Err(e) => {
+                        tracing::warn!("Todoist sync failed: {}", e);
+                        if !failing {
+                            on_event(SyncEvent::Failed(crate::i18n::tf("error.todoist_sync", &[("error", &e)])));
+                        }
+                        failing = true;
+                    }
+                }
+                if !matches!(stop_rx.recv_timeout(interval), Err(mpsc::RecvTimeoutError::Timeout)) {
+                    break;
+                }
+            }
+            tracing::info!("Todoist sync stopped");
+        });
+
+        Ok(Self { _stop: stop_tx })
+    }
+}
+
+// One run: reconcile linked tasks, then push new open ones
+async fn sync_once(api: &Api, db: &Database, on_event: &EventHandler) -> Result<(), String> {
+    // Completed tasks aren't listed, so a linked task missing here was completed
+    // (or deleted) in Todoist
+    let active: HashMap<String, String> = api
+        .active_tasks()
+        .await?
+        .into_iter()
+        .map(|task| (task.id, task.content))
+        .collect();
+
+    for link in database::todoist_links(db).map_err(|e| e.to_string())? {
+        let Ok(local) = database::get_task_by_id(db, link.task_id) else {
+            // Deleted in FlowState: leave the Todoist task alone
+            database::delete_todoist_link(db, link.task_id).map_err(|e| e.to_string())?;
+            continue;
+        };
+        let remote_text = active.get(&link.todoist_id);
+        let remote_completed = remote_text.is_none();
+
+        let completed = if local.completed != link.synced_completed {
+            local.completed
+        } else {
+            remote_completed
+        };
+        let text = match remote_text {
+            Some(remote) if local.text == link.synced_text => remote.clone(),
+            _ => local.text.clone(),
+        };
+
+        if remote_completed != completed && !api.set_completed(&link.todoist_id, completed).await? {
+            // Deleted in Todoist: keep the FlowState task, stop syncing it
+            database::delete_todoist_link(db, link.task_id).map_err(|e| e.to_string())?;
+            continue;
+        }
+        // Todoist only edits open tasks; a completed one keeps its last text there
+        if !completed && remote_text != Some(&text) {
+            api.update(&link.todoist_id, &text).await?;
+        }
+
+        let mut changed = false;
+        if local.text != text {
+            database::update_task(db, local.id, &text).map_err(|e| e.to_string())?;
+            changed = true;
+        }
+        if local.completed != completed {
+            database::toggle_task(db, local.id).map_err(|e| e.to_string())?;
+            changed = true;
+        }
+        if changed {
+            if let Ok(task) = database::get_task_by_id(db, local.id) {
+                on_event(SyncEvent::TaskChanged(task));
+            }
+        }
+
+        database::save_todoist_link(db, &TodoistLink {
+            synced_text: text,
+            synced_completed: completed,
+            ..link
+        })
+        .map_err(|e| e.to_string())?;
+    }
+
+    for task in database::unlinked_open_tasks(db).map_err(|e| e.to_string())? {
+        let remote = api.create(&task.text).await?;
+        database::save_todoist_link(db, &TodoistLink {
+            task_id: task.id,
+            todoist_id: remote.id,
+            synced_text: task.text,
+            synced_completed: false,
+        })
+        .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+struct Api {
+    client: reqwest::Client,
+    token: String,
+}
+
+impl Api {
+    fn new(token: &str) -> Result<Self, String> {
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .map_err(|e| e.to_string())?;
+        Ok(Self {
+            client,
+            token: token.to_string(),
+        })
+    }
+
+    async fn active_tasks(&self) -> Result<Vec<RemoteTask>, String> {
+        let response = self.send(self.client.get(format!("{}/tasks", API_URL))).await?;
+        response.json().await.map_err(|e| format!("Failed to parse Todoist tasks: {}", e))
+    }
+
+    async fn create(&self, content: &str) -> Result<RemoteTask, String> {
+        let request = self
+            .client
+            .post(format!("{}/tasks", API_URL))
+            .json(&serde_json::json!({ "content": content }));
+        let response = self.send(request).await?;
+        response.json().await.map_err(|e| format!("Failed to parse Todoist task: {}", e))
+    }
+
+    async fn update(&self, id: &str, content: &str) -> Result<(), String> {
+        let request = self
+            .client
+            .post(format!("{}/tasks/{}", API_URL, id))
+            .json(&serde_json::json!({ "content": content }));
+        self.send(request).await.map(|_| ())
+    }
+
+    /// Close or reopen a task; false if Todoist no longer has it
+    async fn set_completed(&self, id: &str, completed: bool) -> Result<bool, String> {
+        let action = if completed { "close" } else { "reopen" };
+        let request = self.client.post(format!("{}/tasks/{}/{}", API_URL, id, action));
+        let response = self.send_unchecked(request).await?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(false);
+        }
+        check_status(response).map(|_| true)
+    }
+
+    async fn send(&self, request: reqwest::RequestBuilder) -> Result<reqwest::Response, String> {
+        check_status(self.send_unchecked(request).await?)
+    }
+
+    async fn send_unchecked(&self, request: reqwest::RequestBuilder) -> Result<reqwest::Response, String> {
+        request
+            .bearer_auth(&self.token)
+            .send()
+            .await
+            .map_err(|e| e.to_string())
+    }
+}
+
+fn check_status(response: reqwest::Response) -> Result<reqwest::Response, String> {
+    if response.status() == reqwest::StatusCode::UNAUTHORIZED || response.status() == reqwest::StatusCode::FORBIDDEN {
+        return Err(crate::i18n::t("error.todoist_token"));
+    }
+    if !response.status().is_success() {
+        return Err(format!("HTTP {}", response.status()));
+    }
+    Ok(response)
+}
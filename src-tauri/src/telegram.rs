@@ -0,0 +1,215 @@
+// Telegram module
+// Remote capture from the phone: a bot is long-polled for messages, and text
+// and voice notes from the configured user are handed to the app, which runs
+// them through the usual transcription and task parsing. The bot never
+// replies; messages from anyone else are ignored.
+
+use crate::settings::TelegramSettings;
+use serde::Deserialize;
+use std::sync::{mpsc, Mutex};
+use std::time::Duration;
+
+const API_URL: &str = "https://api.telegram.org";
+// Long-poll wait; Telegram answers as soon as a message arrives
+const POLL_TIMEOUT_SECS: u64 = 25;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(POLL_TIMEOUT_SECS + 15);
+// Pause after a failed poll before trying again
+const RETRY_DELAY: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone)]
+pub enum Incoming {
+    /// Text message, to be parsed like a transcript
+    Text(String),
+    /// Voice note as 16 kHz mono samples, ready for Whisper
+    Voice(Vec<f32>),
+    /// Polling failed (reported once until a poll succeeds again)
+    Failed(String),
+}
+
+pub type MessageHandler = Box<dyn Fn(Incoming) + Send>;
+
+/// Running bridge thread; stops when dropped
+pub struct TelegramBridge {
+    // Dropping the sender ends the thread after the poll in flight
+    _stop: mpsc::Sender<()>,
+}
+
+/// Managed state holding the active bridge (if it is enabled)
+#[derive(Default)]
+pub struct TelegramBridgeState {
+    pub bridge: Mutex<Option<TelegramBridge>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Response<T> {
+    ok: bool,
+    result: Option<T>,
+    description: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Update {
+    update_id: i64,
+    message: Option<Message>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Message {
+    from: Option<User>,
+    text: Option<String>,
+    voice: Option<Voice>,
+}
+
+#[derive(Debug, Deserialize)]
+struct User {
+    username: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Voice {
+    file_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct File {
+    file_path: Option<String>,
+}
+
+impl TelegramBridge {
+    pub fn start(config: &TelegramSettings, on_message: MessageHandler) -> Result<Self, String> {
+        config.validate()?;
+        let api = Api::new(config.token.trim())?;
+        let username = config.username.trim().trim_start_matches('@').to_string();
+
+        let (stop_tx, stop_rx) = mpsc::channel::<()>();
+        // Not joined on drop: a long poll may take a while to return
+        std::thread::spawn(move || {
+            let runtime = match tokio::runtime::Runtime::new() {
+                Ok(runtime) => runtime,
+                Err(e) => {
+                    on_message(Incoming::Failed(e.to_string()));
+                    return;
+                }
+            };
+            tracing::info!("Telegram bridge started (accepting @{})", username);
+            // Messages sent while FlowState was closed are still waiting on
+            // Telegram's side, so starting from 0 picks them up
+            let mut offset = 0;
+            let mut failing = false;
+            while matches!(stop_rx.try_recv(), Err(mpsc::TryRecvError::Empty)) {
+                match runtime.block_on(api.updates(offset)) {
+                    Ok(updates) => {
+                        failing = false;
+                        for update in updates {
+                            offset = update.update_id + 1;
+                            if let Some(message) = update.message {
+                                runtime.block_on(handle_message(&api, &username, message, &on_message));
+                            }
+                        }
+                    }
+                    Err(e) => {
+                        tracing::warn!("Telegram poll failed: {}", e);
+                        if !failing {
+                            on_message(Incoming::Failed(crate::i18n::tf("error.telegram", &[("error", &e)])));
+                        }
+                        failing = true;
+                        if !matches!(stop_rx.recv_timeout(RETRY_DELAY), Err(mpsc::RecvTimeoutError::Timeout)) {
+                            break;
+                        }
+                    }
+                }
+            }
+            tracing::info!("Telegram bridge stopped");
+        });
+
+        Ok(Self { _stop: stop_tx })
+    }
+}
+
+// Pass one message on, if it comes from the configured user
+async fn handle_message(api: &Api, username: &str, message: Message, on_message: &MessageHandler) {
+    let sender = message.from.and_then(|user| user.username).unwrap_or_default();
+    if !sender.eq_ignore_ascii_case(username) {
+        tracing::debug!("Ignoring Telegram message from @{}", sender);
+        return;
+    }
+    if let Some(text) = message.text.filter(|text| !text.trim().is_empty()) {
+        on_message(Incoming::Text(text));
+    } else if let Some(voice) = message.voice {
+        match api.voice_samples(&voice.file_id).await {
+            Ok(samples) => on_message(Incoming::Voice(samples)),
+            Err(e) => {
+                tracing::warn!("Failed to fetch Telegram voice note: {}", e);
+                on_message(Incoming::Failed(crate::i18n::tf("error.telegram", &[("error", &e)])));
+            }
+        }
+    }
+}
+
+struct Api {
+    client: reqwest::Client,
+    token: String,
+}
+
+impl Api {
+    fn new(token: &str) -> Result<Self, String> {
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .map_err(|e| e.to_string())?;
+        Ok(Self {
+            client,
+            token: token.to_string(),
+        })
+    }
+
+    async fn updates(&self, offset: i64) -> Result<Vec<Update>, String> {
+        let request = self.client.get(format!("{}/bot{}/getUpdates", API_URL, self.token)).query(&[
+            ("offset", offset.to_string()),
+            ("timeout", POLL_TIMEOUT_SECS.to_string()),
+            ("allowed_updates", "[\"message\"]".to_string()),
+        ]);
+        self.call(request).await
+    }
+
+    /// Download a voice note and decode it for Whisper
+    async fn voice_samples(&self, file_id: &str) -> Result<Vec<f32>, String> {
+        let request = self
+            .client
+            .get(format!("{}/bot{}/getFile", API_URL, self.token))
+            .query(&[("file_id", file_id)]);
+        let file: File = self.call(request).await?;
+        let path = file.file_path.ok_or_else(|| "Voice note is too large to download".to_string())?;
+        let data = self
+            .client
+            .get(format!("{}/file/bot{}/{}", API_URL, self.token, path))
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| e.without_url().to_string())?
+            .bytes()
+            .await
+            .map_err(|e| e.without_url().to_string())?;
+
+        let samples = tokio::task::spawn_blocking(move || crate::audio::decode_ogg_opus(&data))
+            .await
+            .map_err(|e| format!("Audio decoding task failed: {}", e))??;
+        Ok(crate::whisper::resample(&samples, crate::audio::OPUS_SAMPLE_RATE as usize, 16000))
+    }
+
+    async fn call<T: serde::de::DeserializeOwned>(&self, request: reqwest::RequestBuilder) -> Result<T, String> {
+        // Errors from reqwest include the URL, which contains the token
+        let response = request.send().await.map_err(|e| e.without_url().to_string())?;
+        if response.status() == reqwest::StatusCode::UNAUTHORIZED || response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Err(crate::i18n::t("error.telegram_token"));
+        }
+        let body: Response<T> = response
+            .json()
+            .await
+            .map_err(|e| format!("Failed to parse Telegram response: {}", e.without_url()))?;
+        match body.result {
+            Some(result) if body.ok => Ok(result),
+            _ => Err(body.description.unwrap_or_else(|| "Unknown Telegram error".to_string())),
+        }
+    }
+}
@@ -1,9 +1,52 @@
-use std::sync::Mutex;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
 use std::time::{Duration, Instant};
-use tauri::{AppHandle, Emitter, Manager};
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+use tokio::sync::Notify;
+use tauri_plugin_notification::NotificationExt;
+use flowstate_core::database::Database;
+use flowstate_core::focus_monitor::{ContextSwitches, FocusMonitor};
 
 static TIMER_START: Mutex<Option<Instant>> = Mutex::new(None);
 static TIMER_DURATION: Mutex<Option<Duration>> = Mutex::new(None);
+static TIMER_PAUSED_AT: Mutex<Option<Instant>> = Mutex::new(None);
+// Set by "pause the timer"; presence and working hours don't resume it
+static PAUSED_BY_USER: AtomicBool = AtomicBool::new(false);
+static FOCUS_SESSION: Mutex<Option<FocusSession>> = Mutex::new(None);
+// Foreground-app sampler for the focus session, when the focus monitor is on
+static FOCUS_MONITOR: Mutex<Option<FocusMonitor>> = Mutex::new(None);
+
+// How often presence and working hours are re-checked while they hold the timer paused
+const PRESENCE_POLL_INTERVAL: Duration = Duration::from_secs(30);
+// Longest sleep while nothing is counting down (paused or disabled)
+const IDLE_WAIT: Duration = Duration::from_secs(60 * 60);
+// Escalation steps before an ignored alert is given up on
+const MAX_ESCALATIONS: u32 = 3;
+// Wall-clock start of the current interval, so journal answers can be linked to it
+static INTERVAL_STARTED_AT: Mutex<Option<String>> = Mutex::new(None);
+// Session row of the last expired interval until the user responds to it
+static PENDING_SESSION: Mutex<Option<i64>> = Mutex::new(None);
+// Unacknowledged alert: when the next escalation step is due and how many have fired
+static PENDING_ALERT: Mutex<Option<(Instant, u32)>> = Mutex::new(None);
+// End of the current hard break (overlay window is open until then)
+static BREAK_ENDS_AT: Mutex<Option<Instant>> = Mutex::new(None);
+
+/// Wall-clock bounds of a finished timer interval
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimerInterval {
+    pub started_at: String,
+    pub ended_at: String,
+}
+
+/// A countdown attached to a specific task ("focus on this for 25 minutes")
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FocusSession {
+    pub task_id: i64,
+    pub task_text: String,
+    pub minutes: u64,
+    pub started_at: String,
+}
 
 pub fn setup_awareness_timer(app: AppHandle) {
     // Load timer duration from config
@@ -12,43 +55,198 @@ pub fn setup_awareness_timer(app: AppHandle) {
     // Initialize timer
     reset_timer().unwrap();
     
-    // Listen for Windows session unlock events
-    // This is a simplified version - in production, you'd use WTSSESSION_UNLOCK
     tauri::async_runtime::spawn(async move {
+        let mut paused = false;
+        // Set when the pause covered off-schedule time, so the next working period starts fresh
+        let mut restart_on_resume = false;
+        // Whether the user's return or the start of working hours has to be watched for
+        // (looked at once soon after startup too, in case it starts off-hours)
+        let mut polling = true;
+
         loop {
-            // Check every 10 seconds to minimize CPU usage
-            tokio::time::sleep(Duration::from_secs(10)).await;
-
-            if let Ok(remaining) = get_remaining_time() {
-                if remaining == 0 {
-                    // Timer expired - trigger alert
-                    trigger_alert(&app);
-                    reset_timer().unwrap();
+            // Sleep until the next expiry; resets and setting changes wake us early
+            let mut wait = if paused || get_timer_duration().is_zero() {
+                IDLE_WAIT
+            } else {
+                time_until_expiry()
+            };
+            if polling {
+                wait = wait.min(PRESENCE_POLL_INTERVAL);
+            }
+            if let Some(ends) = *BREAK_ENDS_AT.lock().unwrap() {
+                wait = wait.min(ends.saturating_duration_since(Instant::now()));
+            }
+            // Escalation is held back while the user is away
+            if let Some((due, _)) = (*PENDING_ALERT.lock().unwrap()).filter(|_| !paused) {
+                wait = wait.min(due.saturating_duration_since(Instant::now()));
+            }
+            tokio::select! {
+                _ = tokio::time::sleep(wait) => {}
+                _ = timer_changed().notified() => {}
+            }
+
+            // The timer stays paused for the whole break
+            let break_ends = *BREAK_ENDS_AT.lock().unwrap();
+            if let Some(ends) = break_ends {
+                if ends > Instant::now() {
+                    continue;
+                }
+                end_break(&app);
+            }
+
+            // Pause while the workstation is locked or idle, resume on return. A running
+            // timer only looks when it wakes (expiry, escalation, a change); the time
+            // already spent away is taken back then, so nothing is lost by not polling.
+            let (away, off_schedule) = {
+                let settings = flowstate_core::settings::load(&app.state::<Database>());
+                let away = settings.auto_pause_enabled
+                    .then(|| flowstate_core::presence::away_for(Duration::from_secs(settings.idle_threshold_minutes * 60)))
+                    .flatten();
+
+                // Outside working hours the timer stays quiet (an explicit focus session still runs)
+                let schedule = settings.timer_schedule();
+                let focusing = get_focus_session().ok().flatten().is_some();
+                (away, !focusing && !schedule.is_active_now())
+            };
+            restart_on_resume |= off_schedule;
+
+            let paused_by_user = PAUSED_BY_USER.load(Ordering::SeqCst);
+            let should_pause = away.is_some() || off_schedule || paused_by_user;
+            if should_pause && !paused {
+                if paused_by_user {
+                    tracing::info!("Awareness timer paused on request");
+                } else if off_schedule {
+                    tracing::info!("Outside working hours - pausing awareness timer");
+                } else {
+                    tracing::info!("User away - pausing awareness timer");
+                }
+                let since = away.and_then(|away| Instant::now().checked_sub(away));
+                let _ = pause_timer_at(since.unwrap_or_else(Instant::now));
+                paused = true;
+                emit_to_main(&app, "timer-paused");
+            } else if !should_pause && paused {
+                tracing::info!("Resuming awareness timer");
+                if restart_on_resume {
+                    let _ = reset_timer();
+                    restart_on_resume = false;
                 }
+                let _ = resume_timer();
+                paused = false;
+                emit_to_main(&app, "timer-resumed");
+            }
+            // A user pause is lifted through `notify_timer_changed`; nothing to watch for
+            polling = paused && (away.is_some() || off_schedule);
+
+            if !paused {
+                escalate_if_due(&app);
+            }
+
+            // A zero-minute interval disables the awareness alert (focus sessions still run)
+            if get_timer_duration().is_zero() {
+                continue;
+            }
+
+            if !paused && time_until_expiry().is_zero() {
+                // Timer expired - finish any focus session, then trigger alert
+                let focus = take_focus_session();
+                let switches = focus.as_ref().and_then(|session| log_focus_session(&app, session));
+                let interval = finish_interval();
+                log_session(&app, focus.as_ref(), &interval);
+
+                // Hard-break mode takes over the screen instead of escalating;
+                // neither happens when do-not-disturb kept the alert quiet
+                if trigger_alert(&app, focus.as_ref(), switches.as_ref(), &interval) {
+                    let break_settings = flowstate_core::settings::load(&app.state::<Database>()).break_mode;
+                    if break_settings.enabled {
+                        if let Err(e) = start_break(&app, break_settings.minutes) {
+                            tracing::warn!("Failed to start break: {}", e);
+                            arm_escalation(&app);
+                        }
+                    } else {
+                        arm_escalation(&app);
+                    }
+                }
+                reset_timer().unwrap();
             }
         }
     });
 }
 
+/// Cover the screen with the break overlay and hold the timer until it ends
+pub fn start_break(app: &AppHandle, minutes: u64) -> Result<(), String> {
+    {
+        let mut ends = BREAK_ENDS_AT.lock().map_err(|e| e.to_string())?;
+        *ends = Some(Instant::now() + Duration::from_secs(minutes * 60));
+    }
+    pause_timer()?;
+
+    if app.get_webview_window("break").is_none() {
+        tauri::WebviewWindowBuilder::new(app, "break", tauri::WebviewUrl::App("index.html?view=break".into()))
+            .title("Break")
+            .fullscreen(true)
+            .always_on_top(true)
+            .decorations(false)
+            .skip_taskbar(true)
+            .build()
+            .map_err(|e| format!("Failed to open break overlay: {}", e))?;
+    }
+
+    tracing::info!("Break started ({} min)", minutes);
+    notify_timer_changed();
+    Ok(())
+}
+
+/// Close the overlay (break over or skipped) and start a fresh interval
+pub fn end_break(app: &AppHandle) {
+    let was_on_break = BREAK_ENDS_AT.lock().ok().and_then(|mut ends| ends.take()).is_some();
+    if let Some(window) = app.get_webview_window("break") {
+        let _ = window.close();
+    }
+    if !was_on_break {
+        return;
+    }
+
+    tracing::info!("Break finished");
+    let _ = reset_timer();
+    let _ = resume_timer();
+    emit_to_main(app, "timer-reset");
+}
+
+/// Seconds left in the current break, if one is running
+pub fn get_break_remaining() -> Option<u64> {
+    let ends = (*BREAK_ENDS_AT.lock().ok()?)?;
+    Some(ends.saturating_duration_since(Instant::now()).as_secs())
+}
+
+/// Wakes the awareness loop so it recomputes when the next expiry is due
+fn timer_changed() -> &'static Notify {
+    static TIMER_CHANGED: OnceLock<Notify> = OnceLock::new();
+    TIMER_CHANGED.get_or_init(Notify::new)
+}
+
+/// Call after changing anything the awareness loop depends on (settings, schedule)
+pub fn notify_timer_changed() {
+    timer_changed().notify_one();
+}
+
 fn get_timer_duration() -> Duration {
+    // A focus session overrides the awareness interval until it expires
+    if let Some(ref session) = *FOCUS_SESSION.lock().unwrap() {
+        return Duration::from_secs(session.minutes * 60);
+    }
     let duration = TIMER_DURATION.lock().unwrap();
     duration.unwrap_or_else(|| Duration::from_secs(15 * 60)) // Default 15 minutes
 }
 
-pub fn set_timer_duration(app: &AppHandle, minutes: u64) -> Result<(), String> {
-    let duration = Duration::from_secs(minutes * 60);
+/// Switch the running timer to an already-saved duration and restart the interval
+pub fn apply_timer_duration(minutes: u64) -> Result<(), String> {
     {
         let mut timer_duration = TIMER_DURATION.lock().map_err(|e| e.to_string())?;
-        *timer_duration = Some(duration);
+        *timer_duration = Some(Duration::from_secs(minutes * 60));
     }
-    
-    // Save to config file
-    save_timer_duration(app, minutes)?;
-    
+
     // Reset timer with new duration
-    reset_timer()?;
-    
-    Ok(())
+    reset_timer()
 }
 
 pub fn get_timer_duration_minutes() -> Result<u64, String> {
@@ -57,76 +255,350 @@ pub fn get_timer_duration_minutes() -> Result<u64, String> {
 }
 
 fn load_timer_duration(app: &AppHandle) {
-    if let Ok(app_data_dir) = app.path().app_data_dir() {
+    let db = app.state::<Database>();
+    let mut settings = flowstate_core::settings::load(&db);
+
+    // Older versions kept the duration in timer_config.json - fold it into settings once
+    if let Ok(app_data_dir) = flowstate_core::paths::app_data_dir(app) {
         let config_path = app_data_dir.join("timer_config.json");
-        
+
         if let Ok(json) = std::fs::read_to_string(&config_path) {
-            if let Ok(config) = serde_json::from_str::<serde_json::Value>(&json) {
-                if let Some(minutes) = config.get("duration_minutes").and_then(|v| v.as_u64()) {
-                    let duration = Duration::from_secs(minutes * 60);
-                    if let Ok(mut timer_duration) = TIMER_DURATION.lock() {
-                        *timer_duration = Some(duration);
-                    }
+            if let Some(minutes) = serde_json::from_str::<serde_json::Value>(&json)
+                .ok()
+                .and_then(|config| config.get("duration_minutes").and_then(|v| v.as_u64()))
+            {
+                settings.timer_duration_minutes = minutes.min(60);
+            }
+            match flowstate_core::settings::save(&db, &settings) {
+                Ok(()) => {
+                    let _ = std::fs::remove_file(&config_path);
                 }
+                Err(e) => tracing::warn!("Failed to migrate timer config: {}", e),
             }
         }
     }
-}
 
-fn save_timer_duration(app: &AppHandle, minutes: u64) -> Result<(), String> {
-    let app_data_dir = app.path()
-        .app_data_dir()
-        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
-    
-    std::fs::create_dir_all(&app_data_dir)
-        .map_err(|e| format!("Failed to create app data directory: {}", e))?;
-    
-    let config_path = app_data_dir.join("timer_config.json");
-    let config = serde_json::json!({
-        "duration_minutes": minutes
-    });
-    
-    let json = serde_json::to_string_pretty(&config)
-        .map_err(|e| format!("Failed to serialize timer config: {}", e))?;
-    
-    std::fs::write(&config_path, json)
-        .map_err(|e| format!("Failed to write timer config: {}", e))?;
-    
-    Ok(())
+    if let Ok(mut timer_duration) = TIMER_DURATION.lock() {
+        *timer_duration = Some(Duration::from_secs(settings.timer_duration_minutes * 60));
+    }
 }
 
 pub fn get_remaining_time() -> Result<u64, String> {
-    let start = TIMER_START.lock().map_err(|e| e.to_string())?;
+    Ok(time_until_expiry().as_secs())
+}
+
+/// Exact time left in the current interval
+fn time_until_expiry() -> Duration {
+    let start = *TIMER_START.lock().unwrap();
+    let paused_at = *TIMER_PAUSED_AT.lock().unwrap();
     let duration = get_timer_duration();
-    
-    if let Some(start_time) = *start {
-        let elapsed = start_time.elapsed();
-        if elapsed >= duration {
-            Ok(0)
-        } else {
-            Ok((duration - elapsed).as_secs())
-        }
-    } else {
-        Ok(duration.as_secs())
+
+    match start {
+        // While paused, time stops at the moment of pausing
+        Some(start_time) => duration.saturating_sub(
+            paused_at.unwrap_or_else(Instant::now).saturating_duration_since(start_time),
+        ),
+        None => duration,
     }
 }
 
 pub fn reset_timer() -> Result<(), String> {
     let mut start = TIMER_START.lock().map_err(|e| e.to_string())?;
-    *start = Some(Instant::now());
+    let mut paused_at = TIMER_PAUSED_AT.lock().map_err(|e| e.to_string())?;
+    let now = Instant::now();
+    *start = Some(now);
+    if let Ok(mut interval_start) = INTERVAL_STARTED_AT.lock() {
+        *interval_start = Some(now_timestamp());
+    }
+    // A reset while paused stays paused with the full duration remaining
+    if paused_at.is_some() {
+        *paused_at = Some(now);
+    }
+    notify_timer_changed();
     Ok(())
 }
 
-fn trigger_alert(app: &AppHandle) {
+pub fn pause_timer() -> Result<(), String> {
+    pause_timer_at(Instant::now())
+}
+
+/// Pause as of `at` (when the user went away), but no earlier than the interval's start
+fn pause_timer_at(at: Instant) -> Result<(), String> {
+    let start = TIMER_START.lock().map_err(|e| e.to_string())?;
+    let mut paused_at = TIMER_PAUSED_AT.lock().map_err(|e| e.to_string())?;
+    if paused_at.is_none() {
+        *paused_at = Some(start.map_or(at, |start| at.max(start)));
+    }
+    Ok(())
+}
+
+pub fn resume_timer() -> Result<(), String> {
+    let mut start = TIMER_START.lock().map_err(|e| e.to_string())?;
+    let mut paused_at = TIMER_PAUSED_AT.lock().map_err(|e| e.to_string())?;
+    if let Some(paused) = paused_at.take() {
+        // Shift the start forward by the paused interval
+        if let Some(start_time) = *start {
+            *start = Some(start_time + paused.elapsed());
+        }
+    }
+    Ok(())
+}
+
+/// Hold the countdown until asked to resume ("pause the timer"), or release it.
+/// The timer loop carries it out and tells the windows.
+pub fn set_paused_by_user(paused: bool) {
+    PAUSED_BY_USER.store(paused, Ordering::SeqCst);
+    notify_timer_changed();
+}
+
+pub fn is_timer_paused() -> Result<bool, String> {
+    let paused_at = TIMER_PAUSED_AT.lock().map_err(|e| e.to_string())?;
+    Ok(paused_at.is_some())
+}
+
+/// Attach the countdown to a task and restart it with the focus duration.
+/// With `monitor`, context switches are sampled until the session ends.
+pub fn start_focus(session: FocusSession, monitor: bool) -> Result<(), String> {
+    {
+        let mut focus = FOCUS_SESSION.lock().map_err(|e| e.to_string())?;
+        *focus = Some(session);
+    }
+    {
+        let mut focus_monitor = FOCUS_MONITOR.lock().map_err(|e| e.to_string())?;
+        *focus_monitor = monitor.then(FocusMonitor::start);
+    }
+    reset_timer()
+}
+
+/// Cancel the current focus session (not logged) and return to the awareness interval
+pub fn stop_focus() -> Result<Option<FocusSession>, String> {
+    let session = take_focus_session();
+    drop(take_focus_monitor());
+    reset_timer()?;
+    Ok(session)
+}
+
+pub fn get_focus_session() -> Result<Option<FocusSession>, String> {
+    let focus = FOCUS_SESSION.lock().map_err(|e| e.to_string())?;
+    Ok(focus.clone())
+}
+
+fn now_timestamp() -> String {
+    chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string()
+}
+
+fn finish_interval() -> TimerInterval {
+    let ended_at = now_timestamp();
+    let started_at = INTERVAL_STARTED_AT.lock()
+        .ok()
+        .and_then(|start| start.clone())
+        .unwrap_or_else(|| ended_at.clone());
+    TimerInterval { started_at, ended_at }
+}
+
+fn take_focus_session() -> Option<FocusSession> {
+    FOCUS_SESSION.lock().ok().and_then(|mut focus| focus.take())
+}
+
+fn take_focus_monitor() -> Option<FocusMonitor> {
+    FOCUS_MONITOR.lock().ok().and_then(|mut monitor| monitor.take())
+}
+
+/// Log the finished session; returns its context switches if they were monitored
+fn log_focus_session(app: &AppHandle, session: &FocusSession) -> Option<ContextSwitches> {
+    let db = app.state::<Database>();
+    let ended_at = now_timestamp();
+    let switches = take_focus_monitor().and_then(FocusMonitor::finish);
+    if let Err(e) = flowstate_core::database::log_time_entry(
+        &db,
+        session.task_id,
+        &session.task_text,
+        &session.started_at,
+        &ended_at,
+        (session.minutes * 60) as i64,
+        switches.as_ref(),
+    ) {
+        tracing::warn!("Failed to log focus session: {}", e);
+    }
+    switches
+}
+
+fn log_session(app: &AppHandle, focus: Option<&FocusSession>, interval: &TimerInterval) {
+    let db = app.state::<Database>();
+    // The focused task, or whatever is at the top of the list
+    let task = match focus {
+        Some(session) => Some((session.task_id, session.task_text.clone())),
+        None => flowstate_core::database::get_top_open_task(&db)
+            .ok()
+            .flatten()
+            .map(|task| (task.id, task.text)),
+    };
+
+    match flowstate_core::database::log_session(
+        &db,
+        &interval.started_at,
+        &interval.ended_at,
+        task.as_ref().map(|(id, _)| *id),
+        task.as_ref().map(|(_, text)| text.as_str()),
+    ) {
+        Ok(id) => {
+            if let Ok(mut pending) = PENDING_SESSION.lock() {
+                *pending = Some(id);
+            }
+        }
+        Err(e) => tracing::warn!("Failed to log session: {}", e),
+    }
+}
+
+/// The user responded to the last alert (recording, journal entry, focus start or
+/// explicit acknowledgement): stop escalating and mark the checkpoint answered
+pub fn acknowledge_alert<R: Runtime>(app: &AppHandle<R>) {
+    let escalating = PENDING_ALERT.lock().ok().and_then(|mut pending| pending.take()).is_some();
+    if escalating {
+        if let Some(window) = app.get_webview_window("main") {
+            let _ = window.request_user_attention(None);
+        }
+        notify_timer_changed();
+    }
+
+    let pending = PENDING_SESSION.lock().ok().and_then(|mut pending| pending.take());
+    if let Some(id) = pending {
+        let db = app.state::<Database>();
+        if let Err(e) = flowstate_core::database::acknowledge_session(&db, id) {
+            tracing::warn!("Failed to acknowledge session: {}", e);
+        }
+    }
+}
+
+/// Arm escalation for a freshly fired alert (replaces any previous one)
+fn arm_escalation(app: &AppHandle) {
+    let settings = flowstate_core::settings::load(&app.state::<Database>()).escalation;
+    let next = settings.enabled
+        .then(|| (Instant::now() + Duration::from_secs(settings.after_minutes.max(1) * 60), 0));
+    if let Ok(mut pending) = PENDING_ALERT.lock() {
+        *pending = next;
+    }
+}
+
+/// Repeat an ignored alert more insistently: notification, louder chime, taskbar flash
+fn escalate_if_due(app: &AppHandle) {
+    let level = {
+        let mut pending = PENDING_ALERT.lock().unwrap();
+        match *pending {
+            Some((due, level)) if due <= Instant::now() => {
+                // Held until do-not-disturb ends
+                if flowstate_core::dnd::get_dnd_state(&flowstate_core::settings::load(&app.state::<Database>()).dnd).is_active() {
+                    return;
+                }
+                let level = level + 1;
+                *pending = if level < MAX_ESCALATIONS {
+                    let settings = flowstate_core::settings::load(&app.state::<Database>()).escalation;
+                    Some((Instant::now() + Duration::from_secs(settings.after_minutes.max(1) * 60), level))
+                } else {
+                    None
+                };
+                level
+            }
+            _ => return,
+        }
+    };
+
+    tracing::info!("Alert ignored - escalating (step {})", level);
+    let db = app.state::<Database>();
+    let settings = flowstate_core::settings::load(&db);
+
+    flowstate_core::earcon::play_alert_sound_at(&settings.alert_sound, 1.0 + 0.5 * level as f32);
+
+    if settings.escalation.flash_taskbar {
+        if let Some(window) = app.get_webview_window("main") {
+            let _ = window.request_user_attention(Some(tauri::UserAttentionType::Critical));
+        }
+    }
+
+    let body = match flowstate_core::database::get_top_open_task(&db) {
+        Ok(Some(task)) => flowstate_core::i18n::tf("alert.escalation_task", &[("task", &task.text)]),
+        _ => flowstate_core::i18n::t("alert.escalation"),
+    };
+    if let Err(e) = app.notification()
+        .builder()
+        .title("FlowState")
+        .body(body)
+        .show()
+    {
+        tracing::warn!("Failed to show notification: {}", e);
+    }
+}
+
+fn emit_to_main(app: &AppHandle, event: &str) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.emit(event, ());
+    }
+}
+
+/// Alert the user that the interval is over; false when do-not-disturb suppressed it
+fn trigger_alert(
+    app: &AppHandle,
+    focus: Option<&FocusSession>,
+    switches: Option<&ContextSwitches>,
+    interval: &TimerInterval,
+) -> bool {
+    let db = app.state::<Database>();
+    let settings = flowstate_core::settings::load(&db);
+    let dnd = flowstate_core::dnd::get_dnd_state(&settings.dnd);
+    if dnd.is_active() {
+        tracing::info!("Timer alert suppressed ({:?})", dnd);
+        return false;
+    }
+    let alert_mode = settings.alert_mode;
+    // Ask the frontend to record a short "what did you just do?" answer
+    let journal_prompt = settings.journal_prompt_enabled;
+
+    // Play chime sound
+    flowstate_core::earcon::play_alert_sound(&settings.alert_sound);
+    if settings.speak_tasks_on_alert && !journal_prompt {
+        if let Err(e) = flowstate_core::speech::speak_tasks(&db) {
+            tracing::warn!("Failed to read tasks aloud: {}", e);
+        }
+    }
+
     // Emit event to frontend
     if let Some(window) = app.get_webview_window("main") {
-        let _ = window.emit("timer-alert", ());
+        let _ = window.emit("timer-alert", serde_json::json!({
+            "focus": focus,
+            "context_switches": switches,
+            "interval": interval,
+            "journal_prompt": journal_prompt,
+        }));
 
-        // Visual pulse - flash window
-        let _ = window.show();
-        let _ = window.set_focus();
+        if alert_mode.focuses() {
+            // Visual pulse - flash window
+            let _ = window.show();
+            let _ = window.set_focus();
+        }
     }
 
-    // TODO: Play chime sound
-    // For now, we'll rely on frontend to handle audio
+    if alert_mode.notifies() {
+        let body = match focus {
+            Some(session) => {
+                let body = flowstate_core::i18n::tf("alert.focus_complete", &[("task", &session.task_text), ("minutes", &session.minutes)]);
+                match switches {
+                    Some(switches) => format!("{}\n{}", body, flowstate_core::focus_monitor::summary(switches)),
+                    None => body,
+                }
+            }
+            None => match flowstate_core::database::get_top_open_task(&db) {
+                Ok(Some(task)) => flowstate_core::i18n::tf("alert.current_task", &[("task", &task.text)]),
+                _ => flowstate_core::i18n::t("alert.awareness_check"),
+            },
+        };
+        if let Err(e) = app.notification()
+            .builder()
+            .title("FlowState")
+            .body(body)
+            .show()
+        {
+            tracing::warn!("Failed to show notification: {}", e);
+        }
+    }
+    true
 }
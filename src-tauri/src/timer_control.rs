@@ -0,0 +1,110 @@
+// Timer control module
+// Voice commands for the awareness timer: "reset the timer", "pause the timer",
+// "resume the timer" and "set the timer to 25 minutes" (and their Russian
+// forms). Both UIs check a transcript here before it's parsed into tasks, and
+// the Ollama prompt has a "timer" action for freer phrasings, read by
+// `from_action`. Carrying the command out is up to each UI's timer.
+
+use serde::{Deserialize, Serialize};
+
+/// What a timer command asks for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TimerCommand {
+    /// Start the countdown over
+    Reset,
+    /// Hold the countdown until resumed
+    Pause,
+    Resume,
+    /// New interval length, in minutes
+    SetMinutes(u64),
+}
+
+const TIMER_WORDS: [&str; 2] = ["timer", "таймер"];
+// Dropped before the timer word ("pause the timer", "set a timer")
+const FILLER: [&str; 6] = ["the", "my", "a", "please", "пожалуйста", "мой"];
+const SET_VERBS: [&str; 6] = ["set", "change", "поставь", "установи", "заведи", "поменяй"];
+const VERBS: [(&str, TimerCommand); 19] = [
+    ("reset", TimerCommand::Reset),
+    ("restart", TimerCommand::Reset),
+    ("сбрось", TimerCommand::Reset),
+    ("сбросить", TimerCommand::Reset),
+    ("перезапусти", TimerCommand::Reset),
+    ("заново", TimerCommand::Reset),
+    ("pause", TimerCommand::Pause),
+    ("stop", TimerCommand::Pause),
+    ("hold", TimerCommand::Pause),
+    ("останови", TimerCommand::Pause),
+    ("приостанови", TimerCommand::Pause),
+    ("пауза", TimerCommand::Pause),
+    ("resume", TimerCommand::Resume),
+    ("unpause", TimerCommand::Resume),
+    ("continue", TimerCommand::Resume),
+    ("start", TimerCommand::Resume),
+    ("продолжи", TimerCommand::Resume),
+    ("возобнови", TimerCommand::Resume),
+    ("запусти", TimerCommand::Resume),
+];
+
+/// The timer command a whole transcript makes up, or None for any other
+/// transcript (a task that merely mentions a timer stays a task)
+pub fn timer_command(transcript: &str) -> Option<TimerCommand> {
+    let text = transcript.trim().trim_end_matches(['.', '!', '?']).to_lowercase();
+    let words: Vec<&str> = text
+        .split_whitespace()
+        .map(|word| word.trim_matches(','))
+        .filter(|word| !word.is_empty())
+        .collect();
+    let index = words.iter().position(|word| TIMER_WORDS.contains(word))?;
+    let head: Vec<&str> = words[..index].iter().copied().filter(|word| !FILLER.contains(word)).collect();
+    let tail: Vec<&str> = words[index + 1..]
+        .iter()
+        .copied()
+        .filter(|word| *word != "please" && *word != "пожалуйста")
+        .collect();
+    let verb = |word: &str| VERBS.iter().find(|(name, _)| *name == word).map(|(_, command)| *command);
+
+    match (head.as_slice(), tail.as_slice()) {
+        ([word], []) | ([], [word]) => verb(word),
+        (["start"], ["over"]) => Some(TimerCommand::Reset),
+        ([] | ["поставь"], ["на", "паузу"]) => Some(TimerCommand::Pause),
+        (["сними"], ["с", "паузы"]) => Some(TimerCommand::Resume),
+        ([], [preposition, duration @ ..]) | ([_], [preposition, duration @ ..])
+            if ["to", "for", "на"].contains(preposition) && head.iter().all(|word| SET_VERBS.contains(word)) =>
+        {
+            minutes(duration).map(TimerCommand::SetMinutes)
+        }
+        _ => None,
+    }
+}
+
+/// The command in an Ollama "timer" action, whose text is "reset", "pause",
+/// "resume" or a number of minutes
+pub fn from_action(text: &str) -> Option<TimerCommand> {
+    let text = text.trim().to_lowercase();
+    match text.as_str() {
+        "reset" | "restart" => Some(TimerCommand::Reset),
+        "pause" | "stop" => Some(TimerCommand::Pause),
+        "resume" | "continue" | "start" => Some(TimerCommand::Resume),
+        _ => minutes(&text.split_whitespace().collect::<Vec<_>>()).map(TimerCommand::SetMinutes),
+    }
+}
+
+/// Confirmation for a carried-out command, for the native toast
+pub fn confirmation(command: TimerCommand) -> String {
+    match command {
+        TimerCommand::Reset => crate::i18n::t("timer.voice_reset"),
+        TimerCommand::Pause => crate::i18n::t("timer.voice_paused"),
+        TimerCommand::Resume => crate::i18n::t("timer.voice_resumed"),
+        TimerCommand::SetMinutes(minutes) => crate::i18n::tf("timer.voice_set", &[("minutes", &minutes)]),
+    }
+}
+
+// "25", "25 minutes" or "half an hour" as whole minutes
+fn minutes(words: &[&str]) -> Option<u64> {
+    let minutes = match words {
+        [count] if count.parse::<u64>().is_ok() => count.parse().ok()?,
+        _ => u64::try_from(crate::reminders::parse_duration(words)?.num_minutes()).ok()?,
+    };
+    (minutes > 0).then_some(minutes)
+}
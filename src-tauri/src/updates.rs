@@ -0,0 +1,205 @@
+// Updates module
+// Checks GitHub releases for a newer version and installs it. An installer is
+// only run once its minisign signature checks out against the release key:
+// the Tauri build goes through tauri-plugin-updater, the native build
+// verifies the download itself (see `download_verified_installer`). The
+// public key is compiled in from FLOWSTATE_UPDATER_PUBKEY, the one `tauri
+// signer generate` prints; a build without it only links to the release page.
+
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+const LATEST_RELEASE_URL: &str =
+    "https://api.github.com/repos/kerbeus-a/MicrotaskFlowState/releases/latest";
+// The release key's public half, base64 like in tauri.conf.json
+const UPDATER_PUBKEY: Option<&str> = option_env!("FLOWSTATE_UPDATER_PUBKEY");
+// The bundler publishes each installer's signature next to it under this suffix
+const SIGNATURE_SUFFIX: &str = ".sig";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateInfo {
+    pub version: String,
+    pub current_version: String,
+    /// Release notes (Markdown) shown as the changelog
+    pub notes: String,
+    /// Release page, for when the update can't be installed from the app
+    pub release_url: String,
+    /// The release has a signed Windows installer and this build can check
+    /// the signature
+    pub installable: bool,
+}
+
+#[derive(Deserialize)]
+struct GithubRelease {
+    tag_name: String,
+    #[serde(default)]
+    body: Option<String>,
+    html_url: String,
+    #[serde(default)]
+    assets: Vec<GithubAsset>,
+}
+
+#[derive(Deserialize)]
+struct GithubAsset {
+    name: String,
+    browser_download_url: String,
+}
+
+pub fn current_version() -> &'static str {
+    env!("CARGO_PKG_VERSION")
+}
+
+/// "v1.2.3" -> [1, 2, 3]; anything after a '-' (pre-release) is ignored
+fn parse_version(version: &str) -> Vec<u64> {
+    version
+        .trim()
+        .trim_start_matches('v')
+        .split('-')
+        .next()
+        .unwrap_or("")
+        .split('.')
+        .map(|part| part.parse().unwrap_or(0))
+        .collect()
+}
+
+fn is_newer(candidate: &str, current: &str) -> bool {
+    parse_version(candidate) > parse_version(current)
+}
+
+/// The key installers are checked against, if this build has one
+pub fn pubkey() -> Option<&'static str> {
+    UPDATER_PUBKEY.map(str::trim).filter(|key| !key.is_empty())
+}
+
+// The latest release, or None when none is published yet
+async fn latest_release(client: &reqwest::Client) -> Result<Option<GithubRelease>, String> {
+    let response = client
+        .get(LATEST_RELEASE_URL)
+        // GitHub rejects API requests without a User-Agent
+        .header("User-Agent", format!("FlowState/{}", current_version()))
+        .header("Accept", "application/vnd.github+json")
+        .send()
+        .await
+        .map_err(|e| crate::i18n::tf("error.check_updates", &[("error", &e)]))?;
+
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
+    if !response.status().is_success() {
+        return Err(crate::i18n::tf("error.check_updates", &[("error", &format!("HTTP {}", response.status()))]));
+    }
+    response
+        .json()
+        .await
+        .map(Some)
+        .map_err(|e| format!("Failed to parse release info: {}", e))
+}
+
+// The Windows installer attached to a release and the signature beside it
+fn installer_assets(release: &GithubRelease) -> Option<(&GithubAsset, Option<&GithubAsset>)> {
+    let installer = release.assets.iter().find(|asset| {
+        let name = asset.name.to_lowercase();
+        name.ends_with(".msi") || name.ends_with("-setup.exe")
+    })?;
+    let signature_name = format!("{}{}", installer.name, SIGNATURE_SUFFIX);
+    let signature = release.assets.iter().find(|asset| asset.name == signature_name);
+    Some((installer, signature))
+}
+
+/// Latest release if it is newer than this build
+pub async fn check_for_updates() -> Result<Option<UpdateInfo>, String> {
+    let client = reqwest::Client::new();
+    let Some(release) = latest_release(&client).await? else {
+        return Ok(None);
+    };
+    if !is_newer(&release.tag_name, current_version()) {
+        return Ok(None);
+    }
+
+    let signed = matches!(installer_assets(&release), Some((_, Some(_))));
+    Ok(Some(UpdateInfo {
+        version: release.tag_name.trim_start_matches('v').to_string(),
+        current_version: current_version().to_string(),
+        notes: release.body.unwrap_or_default(),
+        release_url: release.html_url,
+        installable: signed && pubkey().is_some(),
+    }))
+}
+
+/// Look the latest release up again, download its installer and signature,
+/// and save the installer to the temp directory only if the signature
+/// matches the release key
+pub async fn download_verified_installer() -> Result<PathBuf, String> {
+    let key = pubkey().ok_or_else(|| crate::i18n::t("error.update_unsigned"))?;
+    let client = reqwest::Client::new();
+    let release = latest_release(&client)
+        .await?
+        .filter(|release| is_newer(&release.tag_name, current_version()))
+        .ok_or_else(|| crate::i18n::t("updates.up_to_date"))?;
+    let (installer, signature) = installer_assets(&release).ok_or_else(|| crate::i18n::t("error.no_installer"))?;
+    let signature = signature.ok_or_else(|| crate::i18n::t("error.update_unsigned"))?;
+
+    let signature = download(&client, &signature.browser_download_url).await?;
+    let bytes = download(&client, &installer.browser_download_url).await?;
+    verify(key, &bytes, &String::from_utf8_lossy(&signature))?;
+
+    let path = std::env::temp_dir().join(&installer.name);
+    std::fs::write(&path, &bytes)
+        .map_err(|e| format!("Failed to save update: {}", e))?;
+    tracing::info!("Downloaded and verified the FlowState {} installer at {}", release.tag_name, path.display());
+    Ok(path)
+}
+
+async fn download(client: &reqwest::Client, url: &str) -> Result<Vec<u8>, String> {
+    let response = client
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to download update: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("Failed to download update: HTTP {}", response.status()));
+    }
+    let bytes = response
+        .bytes()
+        .await
+        .map_err(|e| format!("Failed to download update: {}", e))?;
+    Ok(bytes.to_vec())
+}
+
+// Check a signature made by `tauri signer sign`. Its keys and signatures are
+// minisign's text files, base64-encoded once more.
+fn verify(pubkey: &str, data: &[u8], signature: &str) -> Result<(), String> {
+    let decode = |encoded: &str| {
+        base64::engine::general_purpose::STANDARD
+            .decode(encoded.trim())
+            .ok()
+            .and_then(|text| String::from_utf8(text).ok())
+    };
+    let key = decode(pubkey)
+        .and_then(|text| minisign_verify::PublicKey::decode(&text).ok())
+        .ok_or("Invalid update signing key")?;
+    let signature = decode(signature)
+        .and_then(|text| minisign_verify::Signature::decode(&text).ok())
+        .ok_or_else(|| crate::i18n::t("error.update_signature"))?;
+    key.verify(data, &signature, true)
+        .map_err(|_| crate::i18n::t("error.update_signature"))
+}
+
+/// Start the installer; the caller should quit right after so files can be replaced
+pub fn launch_installer(path: &std::path::Path) -> Result<(), String> {
+    let is_msi = path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("msi"));
+    let mut command = if is_msi {
+        let mut command = std::process::Command::new("msiexec");
+        command.arg("/i").arg(path);
+        command
+    } else {
+        std::process::Command::new(path)
+    };
+    command
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| format!("Failed to start installer: {}", e))
+}
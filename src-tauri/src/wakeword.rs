@@ -0,0 +1,298 @@
+// Wake-word module
+// Opt-in hands-free mode: listens continuously and triggers a recording when
+// the wake phrase (e.g. "hey flow") is spoken. Frames are gated by a cheap
+// energy detector so Whisper only runs on short voiced segments.
+
+use crate::error::FlowStateError;
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+pub const DEFAULT_WAKE_PHRASE: &str = "hey flow";
+
+// Analysis timing
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+const FRAME_MS: usize = 30;
+// Wake phrase segmentation
+const MIN_PHRASE_SPEECH_MS: usize = 250;
+const MAX_PHRASE_MS: usize = 2500;
+const PHRASE_END_SILENCE_MS: usize = 400;
+// Dictation after the wake phrase
+const DICTATION_END_SILENCE_MS: usize = 1500;
+const DICTATION_START_TIMEOUT_MS: usize = 5000;
+const MAX_DICTATION_MS: usize = 30_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WakeWordEvent {
+    /// Wake phrase heard - start recording
+    Detected,
+    /// Speaker went quiet after the wake phrase - stop recording
+    SpeechEnded,
+}
+
+/// Transcribes 16kHz mono samples (kept generic so each UI can supply its own model lookup)
+pub type Transcriber = Box<dyn Fn(&[f32]) -> Result<String, String> + Send>;
+pub type EventHandler = Box<dyn Fn(WakeWordEvent) + Send>;
+
+/// Running background listener; stops when dropped
+pub struct WakeWordListener {
+    stop: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+/// Managed state holding the active listener (if hands-free mode is on)
+#[derive(Default)]
+pub struct WakeWordState {
+    pub listener: Mutex<Option<WakeWordListener>>,
+}
+
+impl WakeWordListener {
+    pub fn start(phrase: &str, transcribe: Transcriber, on_event: EventHandler) -> Result<Self, FlowStateError> {
+        let phrase = normalize(phrase);
+        if phrase.is_empty() {
+            return Err("Wake phrase cannot be empty".to_string().into());
+        }
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let stop_flag = stop.clone();
+
+        // cpal streams are not Send, so the device is opened on the listener thread
+        // and the result reported back so start() can fail synchronously
+        let (ready_tx, ready_rx) = mpsc::channel();
+        let handle = std::thread::spawn(move || {
+            let capture = match Capture::open() {
+                Ok(capture) => {
+                    let _ = ready_tx.send(Ok(()));
+                    capture
+                }
+                Err(e) => {
+                    let _ = ready_tx.send(Err(e));
+                    return;
+                }
+            };
+            tracing::info!("Wake-word listener started (phrase: \"{}\")", phrase);
+            run_loop(&capture, &phrase, &transcribe, &on_event, &stop_flag);
+            tracing::info!("Wake-word listener stopped");
+        });
+
+        ready_rx.recv()
+            .map_err(|_| FlowStateError::from("Wake-word listener exited unexpectedly".to_string()))??;
+
+        Ok(Self {
+            stop,
+            handle: Some(handle),
+        })
+    }
+}
+
+impl Drop for WakeWordListener {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+struct Capture {
+    _stream: cpal::Stream,
+    buffer: Arc<Mutex<Vec<f32>>>,
+    sample_rate: u32,
+}
+
+impl Capture {
+    fn open() -> Result<Self, FlowStateError> {
+        let host = cpal::default_host();
+        let device = host.default_input_device()
+            .ok_or_else(|| FlowStateError::MicrophoneUnavailable("No audio input device found".to_string()))?;
+        let supported_config = device.default_input_config()
+            .map_err(|e| format!("Failed to get audio config: {}", e))?;
+
+        let sample_rate = supported_config.sample_rate().0;
+        let channels = supported_config.channels() as usize;
+        let sample_format = supported_config.sample_format();
+        let config: cpal::StreamConfig = supported_config.into();
+        let buffer = Arc::new(Mutex::new(Vec::new()));
+
+        let stream = match sample_format {
+            cpal::SampleFormat::F32 => {
+                let buffer = buffer.clone();
+                device.build_input_stream(
+                    &config,
+                    move |data: &[f32], _: &cpal::InputCallbackInfo| {
+                        let mut buf = buffer.lock().unwrap();
+                        buf.extend(data.chunks(channels).map(|c| c.iter().sum::<f32>() / channels as f32));
+                    },
+                    |err| tracing::error!("Wake-word audio error: {}", err),
+                    None,
+                )
+            }
+            cpal::SampleFormat::I16 => {
+                let buffer = buffer.clone();
+                device.build_input_stream(
+                    &config,
+                    move |data: &[i16], _: &cpal::InputCallbackInfo| {
+                        let mut buf = buffer.lock().unwrap();
+                        buf.extend(data.chunks(channels).map(|c| {
+                            c.iter().map(|&s| s as f32 / 32768.0).sum::<f32>() / channels as f32
+                        }));
+                    },
+                    |err| tracing::error!("Wake-word audio error: {}", err),
+                    None,
+                )
+            }
+            other => return Err(format!("Unsupported sample format: {:?}", other).into()),
+        }
+        .map_err(|e| FlowStateError::MicrophoneUnavailable(e.to_string()))?;
+
+        stream.play()
+            .map_err(|e| FlowStateError::MicrophoneUnavailable(e.to_string()))?;
+
+        Ok(Self {
+            _stream: stream,
+            buffer,
+            sample_rate,
+        })
+    }
+}
+
+enum Mode {
+    Listening {
+        segment: Vec<f32>,
+        in_speech: bool,
+        speech_frames: usize,
+        silence_frames: usize,
+    },
+    Dictating {
+        frames: usize,
+        silence_frames: usize,
+        heard_speech: bool,
+    },
+}
+
+impl Mode {
+    fn listening() -> Self {
+        Mode::Listening {
+            segment: Vec::new(),
+            in_speech: false,
+            speech_frames: 0,
+            silence_frames: 0,
+        }
+    }
+}
+
+fn run_loop(
+    capture: &Capture,
+    phrase: &str,
+    transcribe: &Transcriber,
+    on_event: &EventHandler,
+    stop: &AtomicBool,
+) {
+    let frame_len = (capture.sample_rate as usize * FRAME_MS / 1000).max(1);
+    let mut pending: Vec<f32> = Vec::new();
+    let mut noise_floor = 0.01f32;
+    let mut mode = Mode::listening();
+
+    while !stop.load(Ordering::Relaxed) {
+        std::thread::sleep(POLL_INTERVAL);
+
+        {
+            let mut buf = capture.buffer.lock().unwrap();
+            pending.append(&mut buf);
+        }
+
+        let full_frames = pending.len() / frame_len;
+        for frame in pending[..full_frames * frame_len].chunks_exact(frame_len) {
+            let rms = (frame.iter().map(|s| s * s).sum::<f32>() / frame.len() as f32).sqrt();
+            let voiced = rms > (noise_floor * 3.0).max(0.015);
+            if !voiced {
+                // Track ambient noise so the gate adapts to the room
+                noise_floor = noise_floor * 0.95 + rms * 0.05;
+            }
+
+            match &mut mode {
+                Mode::Listening { segment, in_speech, speech_frames, silence_frames } => {
+                    if !*in_speech {
+                        if !voiced {
+                            continue;
+                        }
+                        *in_speech = true;
+                        segment.clear();
+                        *speech_frames = 0;
+                        *silence_frames = 0;
+                    }
+
+                    segment.extend_from_slice(frame);
+                    if voiced {
+                        *speech_frames += 1;
+                        *silence_frames = 0;
+                    } else {
+                        *silence_frames += 1;
+                    }
+
+                    let segment_ms = segment.len() * 1000 / capture.sample_rate as usize;
+                    if *silence_frames * FRAME_MS < PHRASE_END_SILENCE_MS && segment_ms < MAX_PHRASE_MS {
+                        continue;
+                    }
+
+                    *in_speech = false;
+                    if *speech_frames * FRAME_MS < MIN_PHRASE_SPEECH_MS {
+                        continue;
+                    }
+
+                    let samples = crate::whisper::resample(segment, capture.sample_rate as usize, 16000);
+                    let heard = match transcribe(&samples) {
+                        Ok(text) => normalize(&text),
+                        Err(e) => {
+                            tracing::warn!("Wake-word transcription failed: {}", e);
+                            continue;
+                        }
+                    };
+
+                    if heard.contains(phrase) {
+                        tracing::debug!("Wake phrase detected: \"{}\"", heard);
+                        on_event(WakeWordEvent::Detected);
+                        mode = Mode::Dictating {
+                            frames: 0,
+                            silence_frames: 0,
+                            heard_speech: false,
+                        };
+                    }
+                }
+                Mode::Dictating { frames, silence_frames, heard_speech } => {
+                    *frames += 1;
+                    if voiced {
+                        *heard_speech = true;
+                        *silence_frames = 0;
+                    } else {
+                        *silence_frames += 1;
+                    }
+
+                    let elapsed_ms = *frames * FRAME_MS;
+                    let finished = (*heard_speech && *silence_frames * FRAME_MS >= DICTATION_END_SILENCE_MS)
+                        || (!*heard_speech && elapsed_ms >= DICTATION_START_TIMEOUT_MS)
+                        || elapsed_ms >= MAX_DICTATION_MS;
+
+                    if finished {
+                        on_event(WakeWordEvent::SpeechEnded);
+                        mode = Mode::listening();
+                    }
+                }
+            }
+        }
+        pending.drain(..full_frames * frame_len);
+    }
+}
+
+/// Lowercase and strip punctuation so "Hey, Flow!" matches "hey flow"
+fn normalize(text: &str) -> String {
+    text.to_lowercase()
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
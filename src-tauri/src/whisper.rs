@@ -1,20 +1,28 @@
 // Whisper.cpp integration module
 // This handles local speech-to-text conversion and model management
 
-use std::path::PathBuf;
-use std::fs;
-use std::sync::{Arc, Mutex, OnceLock};
-#[cfg(feature = "tauri-ui")]
-use tauri::{AppHandle, Manager};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard, OnceLock};
+use std::time::{Duration, Instant};
 use serde::{Deserialize, Serialize};
 use whisper_rs::{WhisperContext, WhisperContextParameters, FullParams, SamplingStrategy};
+use crate::error::FlowStateError;
+use crate::model_store::ModelStore;
 
-/// Helper functions for Whisper model path management
-pub struct WhisperPaths;
+/// Minutes without a recording before the cached model is unloaded
+pub const DEFAULT_UNLOAD_MINUTES: u64 = 10;
 
-/// Thread-safe cache for Whisper model to avoid reloading on every recording
+/// Thread-safe cache for Whisper model to avoid reloading on every recording.
+/// A model left unused for a while can be unloaded with `unload_if_idle`; the
+/// next recording loads it again.
 pub struct WhisperCache {
-    engine: Mutex<Option<(WhisperModelSize, Arc<WhisperContext>)>>,
+    engine: Mutex<Option<CachedModel>>,
+}
+
+struct CachedModel {
+    size: WhisperModelSize,
+    ctx: Arc<WhisperContext>,
+    last_used: Instant,
 }
 
 impl WhisperCache {
@@ -24,100 +32,109 @@ impl WhisperCache {
         }
     }
 
-    /// Get or create a WhisperContext for the given model size (standalone/native version)
-    pub fn get_or_create_standalone(&self, model_size: WhisperModelSize) -> Result<Arc<WhisperContext>, String> {
-        // Recover from poisoned lock (previous panic) by clearing it
-        let mut guard = self.engine.lock().unwrap_or_else(|poisoned| {
-            eprintln!("⚠️ Recovering from poisoned lock, clearing cache...");
-            let mut guard = poisoned.into_inner();
-            *guard = None;
-            guard
-        });
+    /// Get or create a WhisperContext for the given model size from the shared model store
+    pub fn get_or_create(&self, model_size: WhisperModelSize) -> Result<Arc<WhisperContext>, FlowStateError> {
+        self.get_or_load(model_size, ModelStore::open_or_temp().model_path(model_size))
+    }
+
+    fn get_or_load(&self, model_size: WhisperModelSize, model_path: PathBuf) -> Result<Arc<WhisperContext>, FlowStateError> {
+        let mut guard = self.lock();
 
         // Check if we already have the right model loaded
-        if let Some((cached_size, ref ctx)) = *guard {
-            if cached_size == model_size {
-                eprintln!("✅ Using cached Whisper model");
-                return Ok(Arc::clone(ctx));
-            }
+        if let Some(cached) = guard.as_mut().filter(|cached| cached.size == model_size) {
+            tracing::debug!("Using cached Whisper model");
+            cached.last_used = Instant::now();
+            return Ok(Arc::clone(&cached.ctx));
         }
 
         // Need to load a new model
-        let model_path = WhisperPaths::get_model_path_standalone(model_size);
-
-        if !model_path.exists() {
-            return Err(format!(
-                "Model {} not found. Please download it first from Settings.",
-                model_size.filename()
-            ));
-        }
-
-        eprintln!("🔄 Loading Whisper model: {} (this may take a moment...)", model_size.filename());
+        let ctx = Arc::new(load_context(model_size, &model_path)?);
+        *guard = Some(CachedModel {
+            size: model_size,
+            ctx: Arc::clone(&ctx),
+            last_used: Instant::now(),
+        });
 
-        let ctx = WhisperContext::new_with_params(
-            model_path.to_str().ok_or("Invalid model path")?,
-            WhisperContextParameters::default(),
-        ).map_err(|e| format!("Failed to load Whisper model: {}", e))?;
+        tracing::info!("Whisper model loaded and cached!");
+        Ok(ctx)
+    }
 
-        let ctx = Arc::new(ctx);
-        *guard = Some((model_size, Arc::clone(&ctx)));
+    /// Clear the cached model (useful when user deletes a model)
+    pub fn clear(&self) {
+        *self.lock() = None;
+        tracing::info!("Whisper cache cleared");
+    }
 
-        eprintln!("✅ Whisper model loaded and cached!");
-        Ok(ctx)
+    /// Unload the model if it hasn't been used for `idle` and no transcription
+    /// holds it. Returns the model that was unloaded.
+    pub fn unload_if_idle(&self, idle: Duration) -> Option<WhisperModelSize> {
+        // Busy loading, or poisoned (cleared on the next use); either way not idle
+        let Ok(mut guard) = self.engine.try_lock() else {
+            return None;
+        };
+        let cached = guard.as_ref()?;
+        if cached.last_used.elapsed() < idle || Arc::strong_count(&cached.ctx) > 1 {
+            return None;
+        }
+        let size = cached.size;
+        *guard = None;
+        tracing::info!("Unloaded Whisper model {} after {} idle minutes", size.name(), idle.as_secs() / 60);
+        Some(size)
     }
 
-    #[cfg(feature = "tauri-ui")]
-    /// Get or create a WhisperContext for the given model size (Tauri version)
-    pub fn get_or_create(&self, app: &AppHandle, model_size: WhisperModelSize) -> Result<Arc<WhisperContext>, String> {
-        // Recover from poisoned lock (previous panic) by clearing it
-        let mut guard = self.engine.lock().unwrap_or_else(|poisoned| {
-            eprintln!("⚠️ Recovering from poisoned lock, clearing cache...");
+    // Recover from poisoned lock (previous panic) by clearing it
+    fn lock(&self) -> MutexGuard<'_, Option<CachedModel>> {
+        self.engine.lock().unwrap_or_else(|poisoned| {
+            tracing::warn!("Recovering from poisoned lock, clearing cache...");
             let mut guard = poisoned.into_inner();
             *guard = None;
             guard
-        });
+        })
+    }
+}
 
-        // Check if we already have the right model loaded
-        if let Some((cached_size, ref ctx)) = *guard {
-            if cached_size == model_size {
-                eprintln!("✅ Using cached Whisper model");
-                return Ok(Arc::clone(ctx));
-            }
-        }
+fn load_context(model_size: WhisperModelSize, model_path: &Path) -> Result<WhisperContext, FlowStateError> {
+    if !model_path.exists() {
+        return Err(FlowStateError::ModelMissing { model: model_size.name().to_string() });
+    }
 
-        // Need to load a new model
-        let model_path = WhisperPaths::get_model_path(app, model_size);
+    tracing::info!("Loading Whisper model: {} (this may take a moment...)", model_size.filename());
 
-        if !model_path.exists() {
-            return Err(format!(
-                "Model {} not found. Please download it first from Settings.",
-                model_size.filename()
-            ));
+    let path = model_path.to_str().ok_or_else(|| "Invalid model path".to_string())?;
+    match WhisperContext::new_with_params(path, WhisperContextParameters::default()) {
+        Ok(ctx) => {
+            crate::diagnostics::clear_model_failure(model_size);
+            Ok(ctx)
         }
-
-        eprintln!("🔄 Loading Whisper model: {} (this may take a moment...)", model_size.filename());
-
-        let ctx = WhisperContext::new_with_params(
-            model_path.to_str().ok_or("Invalid model path")?,
-            WhisperContextParameters::default(),
-        ).map_err(|e| format!("Failed to load Whisper model: {}", e))?;
-
-        let ctx = Arc::new(ctx);
-        *guard = Some((model_size, Arc::clone(&ctx)));
-
-        eprintln!("✅ Whisper model loaded and cached!");
-        Ok(ctx)
+        Err(e) => Err(load_failed(model_size, model_path, e)),
     }
+}
 
-    /// Clear the cached model (useful when user deletes a model)
-    pub fn clear(&self) {
-        // Recover from poisoned lock if needed
-        match self.engine.lock() {
-            Ok(mut guard) => *guard = None,
-            Err(poisoned) => *poisoned.into_inner() = None,
+/// A model that doesn't load is checked against its checksum: a damaged file
+/// gets `ModelCorrupt`, so the UI can offer to download it again. Either way
+/// the failure shows up in the diagnostics.
+fn load_failed(model_size: WhisperModelSize, model_path: &Path, error: impl std::fmt::Display) -> FlowStateError {
+    tracing::error!("Failed to load Whisper model {}: {}", model_path.display(), error);
+    let corrupt = match crate::model_store::checksum_matches(model_path, model_size) {
+        Ok(matches) => !matches,
+        Err(e) => {
+            tracing::warn!("Couldn't verify {}: {}", model_path.display(), e);
+            false
         }
-        eprintln!("🗑️ Whisper cache cleared");
-    }
+    };
+    let error = if corrupt {
+        FlowStateError::ModelCorrupt { model: model_size.name().to_string() }
+    } else {
+        FlowStateError::Other(format!("Failed to load Whisper model: {}", error))
+    };
+    crate::diagnostics::record_model_failure(model_size, error.to_string());
+    error
+}
+
+/// Load a model from the shared model store outside any cache, so its load
+/// time and memory can be measured (see `benchmark`)
+pub fn load_uncached(model_size: WhisperModelSize) -> Result<WhisperContext, FlowStateError> {
+    load_context(model_size, &ModelStore::open_or_temp().model_path(model_size))
 }
 
 /// Global cache for native UI (avoids reloading model on every recording)
@@ -127,7 +144,17 @@ fn get_native_cache() -> &'static WhisperCache {
     NATIVE_CACHE.get_or_init(WhisperCache::new)
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+/// `WhisperCache::unload_if_idle` for the native UI's cache
+pub fn unload_native_if_idle(idle: Duration) -> Option<WhisperModelSize> {
+    get_native_cache().unload_if_idle(idle)
+}
+
+/// Unload the native UI's model now; the next recording loads it again
+pub fn unload_native() {
+    get_native_cache().clear();
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub enum WhisperModelSize {
     Tiny,   // ~75 MB
     Base,   // ~142 MB
@@ -137,6 +164,26 @@ pub enum WhisperModelSize {
 }
 
 impl WhisperModelSize {
+    /// Every model offered in Settings, smallest first
+    pub const ALL: [WhisperModelSize; 5] = [
+        WhisperModelSize::Tiny,
+        WhisperModelSize::Base,
+        WhisperModelSize::Small,
+        WhisperModelSize::Medium,
+        WhisperModelSize::Large,
+    ];
+
+    /// Display name; lowercased it round-trips through `from_str`
+    pub fn name(&self) -> &'static str {
+        match self {
+            WhisperModelSize::Tiny => "Tiny",
+            WhisperModelSize::Base => "Base",
+            WhisperModelSize::Small => "Small",
+            WhisperModelSize::Medium => "Medium",
+            WhisperModelSize::Large => "Large",
+        }
+    }
+
     pub fn filename(&self) -> &'static str {
         match self {
             WhisperModelSize::Tiny => "ggml-tiny.bin",
@@ -158,6 +205,17 @@ impl WhisperModelSize {
         }
     }
 
+    /// SHA-1 of the file at `url`, as published with the whisper.cpp models
+    pub fn sha1(&self) -> &'static str {
+        match self {
+            WhisperModelSize::Tiny => "bd577a113a864445d4c299885e0cb97d4ba92b5f",
+            WhisperModelSize::Base => "465707469ff3a37a2b9b8d8f89f2f99de7299dac",
+            WhisperModelSize::Small => "55356645c2b361a969dfd0ef2c5a50d530afd8d5",
+            WhisperModelSize::Medium => "fd9727b6e1217c2f614f9b698455c4ffd82463b4",
+            WhisperModelSize::Large => "ad82bf6a9043ceed055076d0fd39f5f186ff8062",
+        }
+    }
+
     pub fn size_mb(&self) -> u64 {
         match self {
             WhisperModelSize::Tiny => 75,
@@ -178,58 +236,46 @@ impl WhisperModelSize {
             _ => None,
         }
     }
-}
-
-impl WhisperPaths {
-    /// Get models directory without Tauri
-    pub fn get_models_dir_standalone() -> Result<PathBuf, String> {
-        let app_data_dir = dirs::data_dir()
-            .unwrap_or_else(|| std::path::PathBuf::from("."))
-            .join("flowstate");
-
-        let models_dir = app_data_dir.join("whisper_models");
-        std::fs::create_dir_all(&models_dir)
-            .map_err(|e| format!("Failed to create models directory: {}", e))?;
 
-        Ok(models_dir)
+    /// `from_str` for a name that came from a caller, as a typed error
+    pub fn parse(name: &str) -> Result<Self, FlowStateError> {
+        Self::from_str(name).ok_or_else(|| FlowStateError::InvalidModel { model: name.to_string() })
     }
+}
 
-    /// Get model path without Tauri
-    pub fn get_model_path_standalone(model_size: WhisperModelSize) -> PathBuf {
-        let models_dir = Self::get_models_dir_standalone().unwrap_or_else(|_| {
-            std::env::temp_dir().join("flowstate_models")
-        });
-        models_dir.join(model_size.filename())
-    }
-
-    #[cfg(feature = "tauri-ui")]
-    pub fn get_models_dir(app: &AppHandle) -> Result<PathBuf, String> {
-        let app_data_dir = app.path()
-            .app_data_dir()
-            .map_err(|e| format!("Failed to get app data directory: {}", e))?;
-
-        let models_dir = app_data_dir.join("whisper_models");
-        std::fs::create_dir_all(&models_dir)
-            .map_err(|e| format!("Failed to create models directory: {}", e))?;
-
-        Ok(models_dir)
-    }
+/// Transcription language until one is chosen in the settings
+pub const DEFAULT_LANGUAGE: &str = "ru";
 
-    #[cfg(feature = "tauri-ui")]
-    pub fn get_model_path(app: &AppHandle, model_size: WhisperModelSize) -> PathBuf {
-        let models_dir = Self::get_models_dir(app).unwrap_or_else(|_| {
-            std::env::temp_dir().join("flowstate_models")
-        });
-        models_dir.join(model_size.filename())
+/// Resolve a recording's language. `None` falls back to the default language,
+/// "auto" enables Whisper's language detection.
+pub fn resolve_language(language: Option<&str>) -> Option<&str> {
+    match language {
+        None => Some(DEFAULT_LANGUAGE),
+        Some(lang) if lang.eq_ignore_ascii_case("auto") => None,
+        Some(lang) => Some(lang),
     }
 }
 
 /// Transcribe audio using a cached WhisperContext (avoids reloading model)
-pub fn transcribe_with_context(ctx: &WhisperContext, audio_path: &str) -> Result<String, String> {
-    // Read WAV file
+pub fn transcribe_with_context(ctx: &WhisperContext, audio_path: &str, language: Option<&str>) -> Result<String, String> {
+    transcribe_samples_with_context(ctx, &read_wav(audio_path)?, language)
+}
+
+/// Read a WAV recording as 16kHz samples, ready for Whisper
+pub fn read_wav(audio_path: &str) -> Result<Vec<f32>, String> {
     let reader = hound::WavReader::open(audio_path)
         .map_err(|e| format!("Failed to open audio file: {}", e))?;
+    Ok(wav_samples(reader))
+}
+
+/// `read_wav` for a recording already in memory, without writing it to disk
+pub fn decode_wav(bytes: &[u8]) -> Result<Vec<f32>, String> {
+    let reader = hound::WavReader::new(std::io::Cursor::new(bytes))
+        .map_err(|e| format!("Failed to read audio: {}", e))?;
+    Ok(wav_samples(reader))
+}
 
+fn wav_samples<R: std::io::Read>(reader: hound::WavReader<R>) -> Vec<f32> {
     let spec = reader.spec();
     let sample_rate = spec.sample_rate;
 
@@ -249,20 +295,29 @@ pub fn transcribe_with_context(ctx: &WhisperContext, audio_path: &str) -> Result
         }
     };
 
-    // Resample to 16kHz if needed (Whisper expects 16kHz)
-    let samples = if sample_rate != 16000 {
-        resample(&samples, sample_rate as usize, 16000)
-    } else {
+    into_16k(samples, sample_rate)
+}
+
+/// Resample to 16kHz if needed (Whisper expects 16kHz); 16kHz audio is
+/// passed through as it is, without a copy
+pub fn into_16k(samples: Vec<f32>, sample_rate: u32) -> Vec<f32> {
+    if sample_rate == 16000 {
         samples
-    };
+    } else {
+        resample(&samples, sample_rate as usize, 16000)
+    }
+}
 
+/// Transcribe 16kHz mono samples using a cached WhisperContext.
+/// `language` is a Whisper language code; `None` auto-detects.
+pub fn transcribe_samples_with_context(ctx: &WhisperContext, samples: &[f32], language: Option<&str>) -> Result<String, String> {
     // Create whisper state
     let mut state = ctx.create_state()
         .map_err(|e| format!("Failed to create Whisper state: {}", e))?;
 
     // Set up parameters
     let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
-    params.set_language(Some("ru")); // Russian language
+    params.set_language(language);
     params.set_print_special(false);
     params.set_print_progress(false);
     params.set_print_realtime(false);
@@ -271,7 +326,7 @@ pub fn transcribe_with_context(ctx: &WhisperContext, audio_path: &str) -> Result
     params.set_single_segment(false);
 
     // Run transcription
-    state.full(params, &samples)
+    state.full(params, samples)
         .map_err(|e| format!("Transcription failed: {}", e))?;
 
     // Collect results
@@ -290,7 +345,7 @@ pub fn transcribe_with_context(ctx: &WhisperContext, audio_path: &str) -> Result
 }
 
 /// Simple linear resampling
-fn resample(samples: &[f32], from_rate: usize, to_rate: usize) -> Vec<f32> {
+pub fn resample(samples: &[f32], from_rate: usize, to_rate: usize) -> Vec<f32> {
     if from_rate == to_rate {
         return samples.to_vec();
     }
@@ -319,135 +374,25 @@ fn resample(samples: &[f32], from_rate: usize, to_rate: usize) -> Vec<f32> {
 }
 
 /// Transcribe raw audio samples (for native UI without Tauri)
-/// Uses a global cache to avoid reloading the model on every recording
-pub fn transcribe_audio(samples: &[f32], model_name: &str) -> Result<String, String> {
+/// Uses a global cache to avoid reloading the model on every recording.
+/// `language` is a Whisper language code; `None` auto-detects.
+pub fn transcribe_audio(samples: &[f32], model_name: &str, language: Option<&str>) -> Result<String, String> {
     let model_size = WhisperModelSize::from_str(model_name)
         .ok_or_else(|| format!("Unknown model: {}", model_name))?;
 
     // Use cached model (only loads once per model size)
-    let ctx = get_native_cache().get_or_create_standalone(model_size)?;
-
-    // Create whisper state
-    let mut state = ctx.create_state()
-        .map_err(|e| format!("Failed to create Whisper state: {}", e))?;
-
-    // Set up parameters
-    let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
-    params.set_language(None); // Auto-detect language (supports Russian, English, etc.)
-    params.set_print_special(false);
-    params.set_print_progress(false);
-    params.set_print_realtime(false);
-    params.set_print_timestamps(false);
-    params.set_suppress_blank(true);
-    params.set_single_segment(false);
-
-    // Run transcription
-    state.full(params, samples)
-        .map_err(|e| format!("Transcription failed: {}", e))?;
-
-    // Collect results
-    let num_segments = state.full_n_segments()
-        .map_err(|e| format!("Failed to get segments: {}", e))?;
+    let ctx = get_native_cache().get_or_create(model_size)?;
+    let transcript = transcribe_samples_with_context(&ctx, samples, language)?;
 
-    let mut transcript = String::new();
-    for i in 0..num_segments {
-        if let Ok(segment) = state.full_get_segment_text(i) {
-            transcript.push_str(&segment);
-            transcript.push(' ');
-        }
-    }
-
-    eprintln!("✅ Transcribed: {}", transcript.trim());
-    Ok(transcript.trim().to_string())
-}
-
-// Model download functions
-#[cfg(feature = "tauri-ui")]
-pub async fn download_model(
-    app: &AppHandle,
-    model_size: WhisperModelSize,
-    on_progress: Option<Box<dyn Fn(u64, u64) + Send>>,
-) -> Result<PathBuf, String> {
-    let models_dir = WhisperPaths::get_models_dir(app)?;
-    let model_path = models_dir.join(model_size.filename());
-    
-    // If model already exists, return it
-    if model_path.exists() {
-        return Ok(model_path);
-    }
-
-    let url = model_size.url();
-    let client = reqwest::Client::new();
-    
-    // Download the model
-    let response = client
-        .get(url)
-        .send()
-        .await
-        .map_err(|e| format!("Failed to download model: {}", e))?;
-
-    if !response.status().is_success() {
-        return Err(format!("Failed to download model: HTTP {}", response.status()));
-    }
-
-    let total_size = response.content_length().unwrap_or(0);
-    let mut file = fs::File::create(&model_path)
-        .map_err(|e| format!("Failed to create model file: {}", e))?;
-    
-    let mut stream = response.bytes_stream();
-    let mut downloaded: u64 = 0;
-
-    use futures_util::StreamExt;
-    use std::io::Write;
-
-    while let Some(item) = stream.next().await {
-        let chunk = item.map_err(|e| format!("Download error: {}", e))?;
-        file.write_all(&chunk)
-            .map_err(|e| format!("Failed to write to file: {}", e))?;
-        
-        downloaded += chunk.len() as u64;
-        
-        if let Some(ref callback) = on_progress {
-            callback(downloaded, total_size);
-        }
-    }
-
-    Ok(model_path)
+    tracing::debug!("Transcribed: {}", transcript);
+    Ok(transcript)
 }
 
-#[cfg(feature = "tauri-ui")]
-pub fn check_model_exists(app: &AppHandle, model_size: WhisperModelSize) -> bool {
-    let model_path = WhisperPaths::get_model_path(app, model_size);
-    model_path.exists()
-}
-
-#[cfg(feature = "tauri-ui")]
-#[allow(dead_code)]
-pub fn list_available_models(app: &AppHandle) -> Vec<(String, bool, u64)> {
-    let models = vec![
-        WhisperModelSize::Tiny,
-        WhisperModelSize::Base,
-        WhisperModelSize::Small,
-        WhisperModelSize::Medium,
-        WhisperModelSize::Large,
-    ];
-
-    models.into_iter().map(|model| {
-        let exists = check_model_exists(app, model);
-        let size = model.size_mb();
-        (model.filename().to_string(), exists, size)
-    }).collect()
-}
-
-#[cfg(feature = "tauri-ui")]
-pub fn delete_model(app: &AppHandle, model_size: WhisperModelSize) -> Result<(), String> {
-    let model_path = WhisperPaths::get_model_path(app, model_size);
-
-    if model_path.exists() {
-        fs::remove_file(&model_path)
-            .map_err(|e| format!("Failed to delete model: {}", e))?;
-    }
-
+/// Load a model into the native UI's cache ahead of the first recording
+pub fn preload_native(model_name: &str) -> Result<(), String> {
+    let model_size = WhisperModelSize::from_str(model_name)
+        .ok_or_else(|| format!("Unknown model: {}", model_name))?;
+    get_native_cache().get_or_create(model_size)?;
     Ok(())
 }
 
@@ -2,40 +2,177 @@ import { useState, useEffect, useRef } from "react";
 import { invoke } from "@tauri-apps/api/core";
 import { listen } from "@tauri-apps/api/event";
 import { getCurrentWindow } from "@tauri-apps/api/window";
+import { open } from "@tauri-apps/plugin-dialog";
 import TaskList from "./components/TaskList";
+import ContextChips from "./components/ContextChips";
+import AskBox from "./components/AskBox";
+import HabitDots from "./components/HabitDots";
 import RecordButton from "./components/RecordButton";
 import TimerBar from "./components/TimerBar";
 import SettingsModal from "./components/SettingsModal";
+import UpdateBanner from "./components/UpdateBanner";
+import DailyReviewBanner from "./components/DailyReviewBanner";
+import DisambiguationBanner from "./components/DisambiguationBanner";
+import AchievementBanner from "./components/AchievementBanner";
+import StartupBanner from "./components/StartupBanner";
+import TaskFilterBar, { StatusFilter } from "./components/TaskFilterBar";
+import WorkloadBar from "./components/WorkloadBar";
 import AudioVisualizer from "./components/AudioVisualizer";
 import PinIcon from "./components/PinIcon";
 import { useAudioRecorder } from "./hooks/useAudioRecorder";
+import { errorCode, errorMessage, isFlowStateError } from "./errors";
 import "./App.css";
 
+// How long the timer-expiry prompt listens for an answer
+const JOURNAL_WINDOW_MS = 15000;
+
 // Check if running in Tauri (v2 uses __TAURI_INTERNALS__)
 const isTauri = typeof window !== "undefined" && ("__TAURI_INTERNALS__" in window || "__TAURI_IPC__" in window);
 
+interface TimerInterval {
+  started_at: string;
+  ended_at: string;
+}
+
+interface TimerAlertPayload {
+  interval: TimerInterval;
+  journal_prompt: boolean;
+}
+
 interface Task {
   id: number;
   text: string;
   completed: boolean;
   created_at: string;
   completed_at: string | null;
+  // GitHub issue filed for the task
+  issue_url: string | null;
+  // GTD context without the "@" ("phone", "errand")
+  context: string | null;
+  // Estimated minutes, from "about an hour" or "~30m" at the end of the text
+  estimate_minutes: number | null;
+  // When it's due, UTC "YYYY-MM-DD HH:MM:SS" ("tomorrow", "by Friday" in the memo)
+  due_at: string | null;
+  // Captured by voice and waiting to be triaged (settings.voice_inbox)
+  inbox: boolean;
+  // Parser that turned a voice memo into this task (voice results only)
+  parsed_by?: "rules" | "ollama" | "openai" | null;
+}
+
+// Mirrors settings::ProfileSettings (only what the header picker needs)
+interface ProfileSettings {
+  active: string | null;
+  profiles: { name: string }[];
+}
+
+// Mirrors databases::DatabaseList on the Rust side
+interface DatabaseList {
+  current: string;
+  databases: string[];
+}
+
+type ProcessingStage = "decoding" | "transcribing" | "parsing" | "applying";
+
+interface ProcessingProgress {
+  stage: ProcessingStage;
+  step: number;
+  total_steps: number;
+  transcript: string | null;
+  // Set while a long memo goes through piece by piece
+  chunk: { index: number; total: number } | null;
 }
 
+interface VoiceProcessingResult {
+  transcript: string;
+  tasks: Task[];
+  // Set when a completion fit several tasks; DisambiguationBanner asks which
+  needs_disambiguation: { id: number; phrase: string; candidates: Task[] } | null;
+}
+
+// Mirrors navigation::AppCommand on the Rust side ("open settings", "show completed")
+type AppCommand =
+  | { command: "open_settings" }
+  | { command: "close_settings" }
+  | { command: "show_tasks"; status: StatusFilter }
+  | { command: "show_project"; project: string | null }
+  | { command: "show_context"; context: string | null }
+  | { command: "clear_filters" };
+
+const STAGE_LABELS: Record<ProcessingStage, string> = {
+  decoding: "Reading audio",
+  transcribing: "Transcribing",
+  parsing: "Finding tasks",
+  applying: "Updating tasks",
+};
+
+// "Transcribing (part 2 of 4)..." for a long memo
+function stageLabel(stage: ProcessingStage, chunk: ProcessingProgress["chunk"]): string {
+  const part = chunk ? ` (part ${chunk.index} of ${chunk.total})` : "";
+  return `${STAGE_LABELS[stage]}${part}...`;
+}
+
+// Same order as get_tasks: open first, newest first
+const sortTasks = (tasks: Task[]) =>
+  tasks.sort((a, b) =>
+    Number(a.completed) - Number(b.completed) || b.created_at.localeCompare(a.created_at)
+  );
+
 function App() {
   const [tasks, setTasks] = useState<Task[]>([]);
+  // Context chip narrowing the list (null shows every task)
+  const [contextFilter, setContextFilter] = useState<string | null>(null);
+  // Open/Done chip and +project the list is loaded with
+  const [statusFilter, setStatusFilter] = useState<StatusFilter>("recent");
+  const [projectFilter, setProjectFilter] = useState<string | null>(null);
+  // Pending reminders by task id, local "YYYY-MM-DD HH:MM"
+  const [reminders, setReminders] = useState<Record<number, string>>({});
+  // Voice tasks waiting in the inbox, for the filter chip
+  const [inboxCount, setInboxCount] = useState(0);
   const [isProcessing, setIsProcessing] = useState(false);
   const [timerRemaining, setTimerRemaining] = useState(900); // 15 minutes in seconds
   const [timerDuration, setTimerDuration] = useState(15); // minutes
   const [alwaysOnTop, setAlwaysOnTop] = useState(false);
+  const [compactMode, setCompactMode] = useState(false);
   const [showSettings, setShowSettings] = useState(false);
   const [hasWhisperModel, setHasWhisperModel] = useState<boolean | null>(null);
   const [selectedModel, setSelectedModel] = useState<string>("tiny");
   const [processingError, setProcessingError] = useState<string | null>(null);
+  // Current voice-processing step and the last thing Whisper heard
+  const [processingStage, setProcessingStage] = useState<ProcessingStage | null>(null);
+  const [processingChunk, setProcessingChunk] = useState<ProcessingProgress["chunk"]>(null);
+  const [lastTranscript, setLastTranscript] = useState<string | null>(null);
+  const [focusTask, setFocusTask] = useState<string | null>(null);
+  // Per-recording Whisper language override (one-click toggle next to the record button)
+  // starts at the transcription language from the settings ("auto" detects)
+  const [recordingLanguage, setRecordingLanguage] = useState<string>("ru");
+  const recordingLanguageRef = useRef<string>("ru");
+  const transcriptionLanguageRef = useRef<string | null>(null);
+  // Work/Personal profiles for the picker in the header
+  const [profiles, setProfiles] = useState<ProfileSettings>({ active: null, profiles: [] });
+  const [databases, setDatabases] = useState<DatabaseList>({ current: "default", databases: ["default"] });
 
   // Audio recorder hook
   const audioRecorder = useAudioRecorder();
 
+  // Timer paused while the user is away (locked/idle)
+  const timerPausedRef = useRef(false);
+  // The list filters for loadTasks, which the event listeners hold from the first render
+  const listQueryRef = useRef<{ status: StatusFilter; project: string | null }>({ status: "recent", project: null });
+
+  // Timer-expiry journal prompt: the interval the current recording answers for
+  const [journalPrompt, setJournalPrompt] = useState(false);
+  // Timer alert waiting for acknowledgement (backend escalates if ignored)
+  const [alertPending, setAlertPending] = useState(false);
+  const journalIntervalRef = useRef<TimerInterval | null>(null);
+  const journalTimeoutRef = useRef<ReturnType<typeof setTimeout> | null>(null);
+  const isRecordingRef = useRef(false);
+
+  useEffect(() => {
+    isRecordingRef.current = audioRecorder.state.isRecording;
+    // The backend publishes it over MQTT
+    invoke("set_recording_state", { recording: audioRecorder.state.isRecording }).catch(() => {});
+  }, [audioRecorder.state.isRecording]);
+
   // Window state persistence
   const windowStateSaveTimeoutRef = useRef<ReturnType<typeof setTimeout> | null>(null);
 
@@ -45,31 +182,31 @@ function App() {
       return;
     }
 
-    // Restore window state on startup (after a short delay to ensure window is ready)
-    const restoreWindowState = async () => {
-      try {
-        // Wait a bit for window to be fully initialized
-        await new Promise(resolve => setTimeout(resolve, 100));
-        const savedState = await invoke<{ x: number; y: number; width: number; height: number } | null>("load_window_state");
-        if (savedState) {
-          // Validate state (ensure window fits on screen)
-          if (savedState.width > 0 && savedState.height > 0 && savedState.x >= -1000 && savedState.y >= -1000) {
-            await invoke("set_window_state", { state: savedState });
-          }
-        }
-      } catch (error) {
-        console.error("Failed to restore window state:", error);
-      }
-    };
-
-    restoreWindowState();
+    // Window position and size are restored by the backend before the page loads;
+    // only the pin button and the layout need the saved window flags
+    invoke<{ window: { always_on_top: boolean; compact: boolean }; profiles: ProfileSettings; transcription_language: string }>("get_settings")
+      .then(settings => {
+        followTranscriptionLanguage(settings.transcription_language);
+        setAlwaysOnTop(settings.window.always_on_top);
+        setCompactMode(settings.window.compact);
+        setProfiles(settings.profiles);
+      })
+      .catch(error => console.error("Failed to load settings:", error));
+    invoke<DatabaseList>("list_databases")
+      .then(setDatabases)
+      .catch(error => console.error("Failed to list databases:", error));
 
     loadTasks();
+    loadReminders();
+    invoke<number>("get_inbox_count")
+      .then(setInboxCount)
+      .catch(error => console.error("Failed to count inbox tasks:", error));
     syncTimer(); // Initial sync only
     checkWhisperModels();
 
     // Local countdown - update every 10 seconds to minimize CPU usage
     const interval = setInterval(() => {
+      if (timerPausedRef.current) return;
       setTimerRemaining(prev => (prev > 0 ? prev - 10 : prev));
     }, 10000);
 
@@ -101,8 +238,20 @@ function App() {
     });
 
     // Listen for timer alerts
-    const unlisten = listen("timer-alert", async () => {
-      playChime();
+    const unlisten = listen<TimerAlertPayload>("timer-alert", async (event) => {
+      // Any focus session ends when the timer fires
+      setFocusTask(null);
+      setAlertPending(true);
+      // Arm a short "what did you just do?" recording (skipped if already recording)
+      if (event.payload.journal_prompt && !isRecordingRef.current) {
+        journalIntervalRef.current = event.payload.interval;
+        setJournalPrompt(true);
+        await handleStartRecording();
+        journalTimeoutRef.current = setTimeout(() => {
+          handleStopRecording();
+        }, JOURNAL_WINDOW_MS);
+      }
+      // Chime is played by the backend (configurable in Settings)
       // Visual pulse effect
       document.body.style.animation = "pulse 0.5s";
       setTimeout(() => {
@@ -121,18 +270,164 @@ function App() {
       }
     });
 
+    // Backend pauses the timer while the workstation is locked or idle, or when asked by voice
+    const unlistenPaused = listen("timer-paused", () => {
+      timerPausedRef.current = true;
+    });
+    const unlistenResumed = listen("timer-resumed", () => {
+      timerPausedRef.current = false;
+      syncTimer();
+    });
+
+    // Timer duration changed in Settings
+    const unlistenReset = listen("timer-reset", () => {
+      syncTimer();
+    });
+
+    // Model picked in Settings, window flags from the pin button or the compact shortcut
+    const unlistenSettings = listen<{ selected_model: string; window: { always_on_top: boolean; compact: boolean }; profiles: ProfileSettings; transcription_language: string }>("settings-changed", (event) => {
+      setSelectedModel(event.payload.selected_model);
+      followTranscriptionLanguage(event.payload.transcription_language);
+      setAlwaysOnTop(event.payload.window.always_on_top);
+      setCompactMode(event.payload.window.compact);
+      setProfiles(event.payload.profiles);
+    });
+
+    // Switched from the picker or by voice ("switch to work mode"): the list is
+    // scoped differently, and the profile may bring its own recording language
+    const unlistenProfile = listen<{ name: string | null; language: string | null }>("profile-changed", (event) => {
+      loadTasks();
+      const language = event.payload.language;
+      if (language) {
+        recordingLanguageRef.current = language;
+        setRecordingLanguage(language);
+      }
+    });
+
+    // Tasks, habits, reminders and settings all come from the other database now
+    const unlistenDatabase = listen("database-switched", () => {
+      window.location.reload();
+    });
+
+    // Task changes from any command (voice, import, clicks) patch the list in place
+    // Events only carry the issue link when the task is filed, so keep the known one
+    const withIssue = (prev: Task[], task: Task) => ({
+      ...task,
+      issue_url: task.issue_url ?? prev.find(t => t.id === task.id)?.issue_url ?? null,
+    });
+    const upsertTask = (event: { payload: Task }) => {
+      // Inbox tasks only show in the inbox, and the inbox only shows them
+      const inView = event.payload.inbox === (listQueryRef.current.status === "inbox");
+      setTasks(prev => {
+        const others = prev.filter(t => t.id !== event.payload.id);
+        return inView ? sortTasks([...others, withIssue(prev, event.payload)]) : others;
+      });
+    };
+    const unlistenTaskAdded = listen<Task>("task-added", upsertTask);
+    const unlistenTaskCompleted = listen<Task>("task-completed", upsertTask);
+    // Edits keep their place so a manual order survives
+    const unlistenTaskUpdated = listen<Task>("task-updated", (event) => {
+      setTasks(prev => prev.map(t => (t.id === event.payload.id ? withIssue(prev, event.payload) : t)));
+    });
+    const unlistenTaskDeleted = listen<{ id: number }>("task-deleted", (event) => {
+      setTasks(prev => prev.filter(t => t.id !== event.payload.id));
+    });
+    // Voice tasks landed in the inbox, or were kept, scheduled or discarded
+    const unlistenInbox = listen<number>("inbox-changed", (event) => {
+      setInboxCount(event.payload);
+      loadTasks();
+    });
+    // A voice macro archived the completed tasks
+    const unlistenTasksArchived = listen<number>("tasks-archived", () => {
+      loadTasks();
+    });
+    // Manual order lives in the database; reload rather than re-sort here
+    const unlistenTasksReordered = listen<number[]>("tasks-reordered", () => {
+      loadTasks();
+    });
+
+    // Set, cleared or fired (by the scheduler, a command or "remind me to ...")
+    const unlistenReminders = listen("reminders-changed", () => {
+      loadReminders();
+    });
+    // "Open settings", "show completed", "switch to work project" said in a voice note
+    const unlistenAppCommand = listen<AppCommand>("app-command", (event) => {
+      const command = event.payload;
+      switch (command.command) {
+        case "open_settings":
+          setShowSettings(true);
+          break;
+        case "close_settings":
+          setShowSettings(false);
+          break;
+        case "show_tasks":
+          changeListQuery(command.status, listQueryRef.current.project);
+          break;
+        case "show_project":
+          changeListQuery(listQueryRef.current.status, command.project);
+          break;
+        case "show_context":
+          setContextFilter(command.context);
+          break;
+        case "clear_filters":
+          setContextFilter(null);
+          changeListQuery("recent", null);
+          break;
+      }
+    });
+
+    // The end-of-day rollover archived tasks and moved reminders
+    const unlistenDailyReview = listen("daily-review", () => {
+      loadTasks();
+    });
+
+    // Voice processing steps (transcript arrives before tasks are applied)
+    const unlistenProcessing = listen<ProcessingProgress>("voice-processing", (event) => {
+      setProcessingStage(event.payload.stage);
+      setProcessingChunk(event.payload.chunk);
+      if (event.payload.transcript !== null) {
+        setLastTranscript(event.payload.transcript);
+      }
+    });
+
     // Listen for start-recording event (from global shortcut)
     const unlistenRecording = listen("start-recording", () => {
       handleRecordClick();
     });
 
+    // Listen for stop-recording event (hands-free mode detected end of speech)
+    const unlistenStopRecording = listen("stop-recording", () => {
+      handleStopRecording();
+    });
+
     return () => {
       clearInterval(interval);
       if (windowStateSaveTimeoutRef.current) {
         clearTimeout(windowStateSaveTimeoutRef.current);
       }
+      if (journalTimeoutRef.current) {
+        clearTimeout(journalTimeoutRef.current);
+      }
       unlisten.then(fn => fn());
       unlistenRecording.then(fn => fn());
+      unlistenStopRecording.then(fn => fn());
+      unlistenPaused.then(fn => fn());
+      unlistenResumed.then(fn => fn());
+      unlistenReset.then(fn => fn());
+      unlistenSettings.then(fn => fn());
+      unlistenProfile.then(fn => fn());
+      unlistenDatabase.then(fn => fn());
+      unlistenTasksArchived.then(fn => fn());
+      unlistenTaskAdded.then(fn => fn());
+      unlistenTaskCompleted.then(fn => fn());
+      unlistenTaskUpdated.then(fn => fn());
+      unlistenTaskDeleted.then(fn => fn());
+      unlistenTasksReordered.then(fn => fn());
+      unlistenReminders.then(fn => fn());
+      unlistenDailyReview.then(fn => fn());
+      unlistenInbox.then(fn => fn());
+      unlistenAppCommand.then(fn => fn());
+      unlistenProcessing.then(fn => fn());
       unlistenMove.then(fn => fn());
       unlistenResize.then(fn => fn());
     };
@@ -146,10 +441,11 @@ function App() {
       const hasModel = installedModels.length > 0;
       setHasWhisperModel(hasModel);
 
-      // Auto-select the first installed model
+      // Use the saved model if it's installed, otherwise the first installed one
       if (hasModel) {
-        const firstInstalled = installedModels[0];
-        setSelectedModel(firstInstalled.name.toLowerCase());
+        const { selected_model } = await invoke<{ selected_model: string }>("get_settings");
+        const saved = installedModels.find(m => m.name.toLowerCase() === selected_model);
+        setSelectedModel((saved ?? installedModels[0]).name.toLowerCase());
       } else {
         // Show settings if no model is installed
         setTimeout(() => setShowSettings(true), 1000);
@@ -162,13 +458,30 @@ function App() {
   const loadTasks = async () => {
     if (!isTauri) return;
     try {
-      const loadedTasks = await invoke<Task[]>("get_tasks");
+      const loadedTasks = await invoke<Task[]>("get_tasks", { query: listQueryRef.current });
       setTasks(loadedTasks);
     } catch (error) {
       console.error("Failed to load tasks:", error);
     }
   };
 
+  const changeListQuery = (status: StatusFilter, project: string | null) => {
+    listQueryRef.current = { status, project };
+    setStatusFilter(status);
+    setProjectFilter(project);
+    loadTasks();
+  };
+
+  const loadReminders = async () => {
+    if (!isTauri) return;
+    try {
+      const pending = await invoke<Array<{ task_id: number; remind_at: string }>>("get_reminders");
+      setReminders(Object.fromEntries(pending.map(r => [r.task_id, r.remind_at])));
+    } catch (error) {
+      console.error("Failed to load reminders:", error);
+    }
+  };
+
   // Sync timer with backend (only called on mount and after reset)
   const syncTimer = async () => {
     if (!isTauri) return;
@@ -184,19 +497,39 @@ function App() {
     }
   };
 
+  const handleAcknowledgeAlert = async () => {
+    setAlertPending(false);
+    try {
+      await invoke("acknowledge_alert");
+    } catch (error) {
+      console.error("Failed to acknowledge alert:", error);
+    }
+  };
+
   const handleStartRecording = async () => {
+    // Recording is a response to the checkpoint (backend acknowledges once processed)
+    setAlertPending(false);
     try {
       setProcessingError(null);
       await audioRecorder.startRecording();
+      invoke("play_earcon", { kind: "record_start" }).catch(() => {});
     } catch (error) {
       console.error("Failed to start recording:", error);
-      const errorMessage = error instanceof Error ? error.message : String(error);
-      setProcessingError(errorMessage);
+      setProcessingError(errorMessage(error));
       setTimeout(() => setProcessingError(null), 5000);
     }
   };
 
   const handleStopRecording = async () => {
+    // A journal answer may be stopped early by the user or by the timeout
+    if (journalTimeoutRef.current) {
+      clearTimeout(journalTimeoutRef.current);
+      journalTimeoutRef.current = null;
+    }
+    const journalInterval = journalIntervalRef.current;
+    journalIntervalRef.current = null;
+    setJournalPrompt(false);
+
     // Stop recording and process
     try {
       setIsProcessing(true);
@@ -204,37 +537,140 @@ function App() {
 
       // Stop recording and get WAV blob
       const audioBlob = await audioRecorder.stopRecording();
+      invoke("play_earcon", { kind: "record_stop" }).catch(() => {});
 
       // Convert blob to array buffer
       const arrayBuffer = await audioBlob.arrayBuffer();
       const audioData = Array.from(new Uint8Array(arrayBuffer));
 
-      // Process with backend
-      await invoke("process_voice_recording", {
+      if (journalInterval) {
+        // Answer to the timer prompt goes to the journal, not the task list
+        await invoke("process_journal_recording", {
+          audioData,
+          modelName: selectedModel,
+          language: recordingLanguageRef.current,
+          intervalStart: journalInterval.started_at,
+          intervalEnd: journalInterval.ended_at,
+        });
+        return;
+      }
+
+      // Process with backend (task events update the list)
+      const result = await invoke<VoiceProcessingResult>("process_voice_recording", {
         audioData,
         modelName: selectedModel,
+        language: recordingLanguageRef.current,
       });
-
-      // Reload tasks to get the latest
-      await loadTasks();
+      setLastTranscript(result.transcript);
     } catch (error) {
       console.error("Failed to process voice recording:", error);
-      let errorMessage = error instanceof Error ? error.message : String(error);
+      // A double-tapped stop sends the same recording twice; the first copy is still on its way
+      if (errorCode(error) === "duplicate_recording") return;
+      if (offerModelDownload(error)) return;
+      let message = errorMessage(error);
       
       // Improve error messages
-      if (errorMessage.includes('Ollama') || errorMessage.includes('404')) {
-        errorMessage = 'Ollama is not running. Please start Ollama (it should run on port 11434). The audio was recorded but cannot be transcribed without Ollama.';
-      } else if (errorMessage.includes('Unable to decode audio data')) {
-        errorMessage = 'Unable to decode audio data. The recording may be empty or corrupted. Check if the microphone is working and try again.';
+      if (message.includes('Ollama') || message.includes('404')) {
+        message = 'Ollama is not running. Please start Ollama (it should run on port 11434). The audio was recorded but cannot be transcribed without Ollama.';
+      } else if (message.includes('Unable to decode audio data')) {
+        message = 'Unable to decode audio data. The recording may be empty or corrupted. Check if the microphone is working and try again.';
       }
       
-      setProcessingError(errorMessage);
+      setProcessingError(message);
 
       // Show error notification
       setTimeout(() => setProcessingError(null), 5000);
     } finally {
       setIsProcessing(false);
+      setProcessingStage(null);
+    }
+  };
+
+  // The selected model was deleted, never downloaded or is damaged: offer to
+  // fetch it instead of only reporting it. Returns whether the error was handled.
+  const offerModelDownload = (error: unknown) => {
+    if (!isFlowStateError(error) || !error.model) return false;
+    if (error.code !== "model_missing" && error.code !== "model_corrupt") return false;
+    const corrupt = error.code === "model_corrupt";
+    const question = corrupt
+      ? `Delete it and download the ${error.model} model again?`
+      : `Download the ${error.model} model now?`;
+    if (confirm(`${error.message}\n\n${question}`)) {
+      const command = corrupt ? "repair_whisper_model" : "download_whisper_model";
+      invoke(command, { modelName: error.model.toLowerCase() }).catch((downloadError) => {
+        console.error("Failed to download model:", downloadError);
+        setProcessingError(errorMessage(downloadError));
+      });
+      setShowSettings(true);
     }
+    return true;
+  };
+
+  const handleImportAudio = async () => {
+    try {
+      const filePath = await open({
+        multiple: false,
+        filters: [{ name: "Audio", extensions: ["wav", "mp3", "m4a", "ogg"] }],
+      });
+      if (!filePath || Array.isArray(filePath)) return;
+
+      setIsProcessing(true);
+      setProcessingError(null);
+      const result = await invoke<VoiceProcessingResult>("import_audio_file", {
+        filePath,
+        modelName: selectedModel,
+        language: recordingLanguageRef.current,
+      });
+      setLastTranscript(result.transcript);
+    } catch (error) {
+      console.error("Failed to import audio file:", error);
+      if (offerModelDownload(error)) return;
+      setProcessingError(errorMessage(error));
+      setTimeout(() => setProcessingError(null), 5000);
+    } finally {
+      setIsProcessing(false);
+      setProcessingStage(null);
+    }
+  };
+
+  const switchProfile = async (name: string | null) => {
+    try {
+      await invoke("switch_profile", { name });
+    } catch (error) {
+      console.error("Failed to switch profile:", error);
+      setProcessingError(errorMessage(error));
+    }
+  };
+
+  const switchDatabase = async (name: string) => {
+    if (name === "+") {
+      const entered = window.prompt("Name of the new database (letters, digits, - and _):")?.trim();
+      if (!entered) return;
+      name = entered;
+    }
+    try {
+      await invoke<DatabaseList>("switch_database", { name });
+    } catch (error) {
+      console.error("Failed to switch database:", error);
+      setProcessingError(errorMessage(error));
+    }
+  };
+
+  // Only a changed setting moves the toggle, so other settings changes keep
+  // the language picked for the next memo
+  const followTranscriptionLanguage = (language: string) => {
+    if (transcriptionLanguageRef.current === language) {
+      return;
+    }
+    transcriptionLanguageRef.current = language;
+    recordingLanguageRef.current = language;
+    setRecordingLanguage(language);
+  };
+
+  const toggleRecordingLanguage = () => {
+    const next = recordingLanguage === "ru" ? "en" : "ru";
+    recordingLanguageRef.current = next;
+    setRecordingLanguage(next);
   };
 
   // Legacy click handler for global shortcut compatibility
@@ -249,7 +685,6 @@ function App() {
   const handleToggleTask = async (id: number) => {
     try {
       await invoke("toggle_task", { id });
-      await loadTasks();
     } catch (error) {
       console.error("Failed to toggle task:", error);
     }
@@ -257,39 +692,74 @@ function App() {
 
   const handleDeleteTask = async (id: number) => {
     try {
-      await invoke("delete_task", { id });
-      await loadTasks();
+      // Discarding from the inbox also updates its count
+      if (tasks.find(t => t.id === id)?.inbox) {
+        await invoke("discard_inbox_tasks", { ids: [id] });
+      } else {
+        await invoke("delete_task", { id });
+      }
     } catch (error) {
       console.error("Failed to delete task:", error);
     }
   };
 
+  const handleKeepTask = async (id: number) => {
+    try {
+      await invoke("commit_inbox_tasks", { ids: [id] });
+    } catch (error) {
+      console.error("Failed to keep task:", error);
+    }
+  };
+
+  const handleScheduleTask = async (id: number, when: string) => {
+    try {
+      await invoke("schedule_inbox_task", { id, when });
+    } catch (error) {
+      console.error("Failed to schedule task:", error);
+      setProcessingError(errorMessage(error));
+    }
+  };
+
+  const handleFocusTask = async (id: number) => {
+    try {
+      const session = await invoke<{ task_text: string; minutes: number }>("start_focus", { taskId: id, minutes: 25 });
+      setAlertPending(false);
+      setFocusTask(session.task_text);
+      setTimerDuration(session.minutes);
+      setTimerRemaining(session.minutes * 60);
+    } catch (error) {
+      console.error("Failed to start focus session:", error);
+    }
+  };
+
+  // An empty time clears the reminder; "reminders-changed" refreshes the list
+  const handleRemindTask = async (id: number, when: string) => {
+    try {
+      if (when.trim()) {
+        await invoke("set_reminder", { taskId: id, when });
+      } else {
+        await invoke("clear_reminder", { taskId: id });
+      }
+    } catch (error) {
+      console.error("Failed to set reminder:", error);
+      setProcessingError(errorMessage(error));
+    }
+  };
+
   const handleUpdateTask = async (id: number, text: string) => {
     try {
       await invoke("update_task", { id, text });
-      await loadTasks();
     } catch (error) {
       console.error("Failed to update task:", error);
     }
   };
 
-  const playChime = () => {
-    // Create a simple chime sound
-    const audioContext = new AudioContext();
-    const oscillator = audioContext.createOscillator();
-    const gainNode = audioContext.createGain();
-    
-    oscillator.connect(gainNode);
-    gainNode.connect(audioContext.destination);
-    
-    oscillator.frequency.value = 800;
-    oscillator.type = "sine";
-    
-    gainNode.gain.setValueAtTime(0.3, audioContext.currentTime);
-    gainNode.gain.exponentialRampToValueAtTime(0.01, audioContext.currentTime + 0.5);
-    
-    oscillator.start(audioContext.currentTime);
-    oscillator.stop(audioContext.currentTime + 0.5);
+  const handleSetDue = async (id: number, dueAt: string | null) => {
+    try {
+      await invoke("set_task_due", { id, dueAt });
+    } catch (error) {
+      console.error("Failed to set the due date:", error);
+    }
   };
 
   const toggleAlwaysOnTop = async () => {
@@ -303,7 +773,15 @@ function App() {
       setAlwaysOnTop(newValue);
     } catch (error) {
       console.error("Failed to toggle always on top:", error);
-      alert(`Failed to toggle always on top: ${error}`);
+      alert(`Failed to toggle always on top: ${errorMessage(error)}`);
+    }
+  };
+
+  const toggleCompactMode = async () => {
+    try {
+      await invoke("set_compact_mode", { compact: !compactMode });
+    } catch (error) {
+      console.error("Failed to toggle compact mode:", error);
     }
   };
 
@@ -334,13 +812,87 @@ function App() {
     );
   }
 
+  if (compactMode) {
+    // Focused task first, otherwise the newest open one (tasks are sorted open first)
+    const topTask = focusTask ?? tasks.find(t => !t.completed)?.text;
+    return (
+      <div className="app compact">
+        <TimerBar remaining={timerRemaining} duration={timerDuration} label={focusTask} />
+        <div className="compact-content" data-tauri-drag-region>
+          <RecordButton
+            compact
+            isRecording={audioRecorder.state.isRecording}
+            isProcessing={isProcessing}
+            recordingTime={audioRecorder.state.recordingTime}
+            onStartRecording={handleStartRecording}
+            onStopRecording={handleStopRecording}
+          />
+          <span className="compact-task" title={topTask} data-tauri-drag-region>
+            {isProcessing && processingStage
+              ? stageLabel(processingStage, processingChunk)
+              : topTask ?? "No open tasks"}
+          </span>
+          <button className="settings-button" onClick={toggleCompactMode} title="Expand window">
+            ⤢
+          </button>
+        </div>
+      </div>
+    );
+  }
+
+  // Open tasks per context, for the chips above the list
+  const contextCounts: Record<string, number> = {};
+  for (const task of tasks) {
+    if (task.context && !task.completed) {
+      contextCounts[task.context] = (contextCounts[task.context] ?? 0) + 1;
+    }
+  }
+  // Tasks arrive from events whatever the filters, so they're applied here as well
+  const visibleTasks = tasks.filter(t =>
+    (!contextFilter || t.context === contextFilter) &&
+    (statusFilter === "recent" || t.completed === (statusFilter === "completed")) &&
+    (!projectFilter || t.text.toLowerCase().includes(`+${projectFilter.toLowerCase()}`))
+  );
+
   return (
     <div className="app">
-      <TimerBar remaining={timerRemaining} duration={timerDuration} />
+      <TimerBar remaining={timerRemaining} duration={timerDuration} label={focusTask} />
       <div className="app-content">
         <div className="header">
           <h1>FlowState</h1>
           <div className="header-actions">
+            <select
+              className="profile-select"
+              value={databases.current}
+              onChange={(e) => switchDatabase(e.target.value)}
+              title="Database: a separate list of tasks with its own settings, e.g. for work and home"
+            >
+              {databases.databases.map((name) => (
+                <option key={name} value={name}>{name === "default" ? "Default" : name}</option>
+              ))}
+              <option value="+">New database…</option>
+            </select>
+            {profiles.profiles.length > 0 && (
+              <select
+                className="profile-select"
+                value={profiles.active ?? ""}
+                onChange={(e) => switchProfile(e.target.value || null)}
+                title='Profile: narrows the list and sets the language, prompt and working hours. Say "switch to work mode" to change it'
+              >
+                <option value="">No profile</option>
+                {profiles.profiles.map((profile) => (
+                  <option key={profile.name} value={profile.name}>{profile.name}</option>
+                ))}
+              </select>
+            )}
+            <button
+              className="settings-button"
+              onClick={handleImportAudio}
+              disabled={isProcessing || audioRecorder.state.isRecording}
+              title="Import audio file"
+            >
+              📂
+            </button>
             <button
               className="settings-button"
               onClick={() => setShowSettings(true)}
@@ -348,6 +900,13 @@ function App() {
             >
               ⚙️
             </button>
+            <button
+              className="settings-button"
+              onClick={toggleCompactMode}
+              title="Compact mode"
+            >
+              ▭
+            </button>
             <button
               className="always-on-top-toggle"
               onClick={toggleAlwaysOnTop}
@@ -364,6 +923,49 @@ function App() {
           onStartRecording={handleStartRecording}
           onStopRecording={handleStopRecording}
         />
+        <div className="language-toggle-container">
+          <button
+            className="language-toggle"
+            onClick={toggleRecordingLanguage}
+            disabled={audioRecorder.state.isRecording || isProcessing}
+            title="Transcription language for this memo (click to switch)"
+          >
+            {recordingLanguage.toUpperCase()}
+          </button>
+        </div>
+
+        <StartupBanner />
+        <UpdateBanner />
+        <DailyReviewBanner />
+        <DisambiguationBanner />
+        <AchievementBanner />
+
+        {isProcessing && processingStage && (
+          <div className="processing-status">
+            <p>{stageLabel(processingStage, processingChunk)}</p>
+          </div>
+        )}
+
+        {!isProcessing && lastTranscript && (
+          <div className="last-transcript" title="Last transcript">
+            <p>“{lastTranscript}”</p>
+          </div>
+        )}
+
+        {alertPending && !audioRecorder.state.isRecording && (
+          <div className="journal-prompt">
+            <p>⏰ Checkpoint - what are you working on?</p>
+            <button className="language-toggle" onClick={handleAcknowledgeAlert}>
+              Got it
+            </button>
+          </div>
+        )}
+
+        {journalPrompt && audioRecorder.state.isRecording && (
+          <div className="journal-prompt">
+            <p>📓 What did you just do?</p>
+          </div>
+        )}
 
         {/* Audio Visualizer - only show when recording */}
         {audioRecorder.state.isRecording && (
@@ -398,11 +1000,31 @@ function App() {
           </div>
         )}
 
+        <HabitDots />
+        <AskBox />
+        <WorkloadBar tasks={tasks} />
+        <TaskFilterBar
+          status={statusFilter}
+          project={projectFilter}
+          inboxCount={inboxCount}
+          onChange={changeListQuery}
+        />
+        <ContextChips
+          counts={contextCounts}
+          selected={contextFilter}
+          onSelect={setContextFilter}
+        />
         <TaskList
-          tasks={tasks}
+          tasks={visibleTasks}
           onToggle={handleToggleTask}
           onDelete={handleDeleteTask}
           onUpdate={handleUpdateTask}
+          onFocus={handleFocusTask}
+          reminders={reminders}
+          onRemind={handleRemindTask}
+          onSetDue={handleSetDue}
+          onKeep={handleKeepTask}
+          onSchedule={handleScheduleTask}
         />
         {hasWhisperModel === false && (
           <div className="model-warning">
@@ -0,0 +1,41 @@
+import { useState, useEffect } from "react";
+import { listen } from "@tauri-apps/api/event";
+import "./AchievementBanner.css";
+
+// Mirrors achievements::AchievementStatus on the Rust side
+interface Achievement {
+  id: "ten_tasks" | "week_streak" | "hundred_captures";
+  title: string;
+  description: string;
+  progress: number;
+  goal: number;
+  unlocked_at: string | null;
+}
+
+// Celebrates achievements as they're unlocked; several in a row queue up
+export default function AchievementBanner() {
+  const [unlocked, setUnlocked] = useState<Achievement[]>([]);
+
+  useEffect(() => {
+    const unlisten = listen<Achievement>("achievement-unlocked", (event) => {
+      setUnlocked((queue) => [...queue, event.payload]);
+    });
+    return () => {
+      unlisten.then(fn => fn());
+    };
+  }, []);
+
+  const achievement = unlocked[0];
+  if (!achievement) return null;
+
+  return (
+    <div className="achievement-banner">
+      <span className="achievement-trophy">🏆</span>
+      <div className="achievement-text">
+        <p className="achievement-title">Achievement unlocked: {achievement.title}</p>
+        <p className="achievement-description">{achievement.description}</p>
+      </div>
+      <button onClick={() => setUnlocked((queue) => queue.slice(1))}>Nice!</button>
+    </div>
+  );
+}
@@ -0,0 +1,66 @@
+import { useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { errorMessage } from "../errors";
+import "./AskBox.css";
+
+interface TaskAnswer {
+  answer: string;
+  // Tasks the answer is based on
+  task_ids: number[];
+}
+
+// Questions about the task history, answered by Ollama
+export default function AskBox() {
+  const [question, setQuestion] = useState("");
+  const [answer, setAnswer] = useState<TaskAnswer | null>(null);
+  const [asking, setAsking] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  const handleKeyDown = async (e: React.KeyboardEvent<HTMLInputElement>) => {
+    if (e.key === "Escape") {
+      setAnswer(null);
+      setError(null);
+      return;
+    }
+    if (e.key !== "Enter" || !question.trim() || asking) return;
+
+    setAsking(true);
+    setAnswer(null);
+    setError(null);
+    try {
+      setAnswer(await invoke<TaskAnswer>("ask_tasks", { question }));
+    } catch (err) {
+      setError(errorMessage(err));
+    } finally {
+      setAsking(false);
+    }
+  };
+
+  return (
+    <div className="ask-box">
+      <input
+        className="ask-input"
+        value={question}
+        placeholder='💬 Ask about your tasks, e.g. "what did I finish last week?"'
+        disabled={asking}
+        onChange={(e) => setQuestion(e.target.value)}
+        onKeyDown={handleKeyDown}
+      />
+      {asking && <p className="ask-status">Thinking...</p>}
+      {answer && (
+        <div className="ask-answer">
+          <p>{answer.answer}</p>
+          {answer.task_ids.length > 0 && (
+            <p className="ask-sources">
+              Based on tasks: {answer.task_ids.map((id) => `#${id}`).join(", ")}
+            </p>
+          )}
+          <button className="ask-dismiss" onClick={() => setAnswer(null)} title="Dismiss">
+            ✕
+          </button>
+        </div>
+      )}
+      {error && <p className="ask-error">{error}</p>}
+    </div>
+  );
+}
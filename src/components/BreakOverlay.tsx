@@ -0,0 +1,50 @@
+import { useState, useEffect } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import "./BreakOverlay.css";
+
+// Full-screen break window shown by hard-break mode (opened by the backend)
+export default function BreakOverlay() {
+  const [remaining, setRemaining] = useState<number | null>(null);
+
+  useEffect(() => {
+    invoke<number | null>("get_break_remaining")
+      .then(seconds => setRemaining(seconds ?? 0))
+      .catch(error => console.error("Failed to get break status:", error));
+
+    const interval = setInterval(() => {
+      setRemaining(prev => (prev !== null && prev > 0 ? prev - 1 : prev));
+    }, 1000);
+    return () => clearInterval(interval);
+  }, []);
+
+  // The backend also ends the break on time; this just closes it without waiting for the next tick
+  useEffect(() => {
+    if (remaining === 0) {
+      invoke("end_break").catch(() => {});
+    }
+  }, [remaining]);
+
+  const handleSkip = async () => {
+    try {
+      await invoke("end_break");
+    } catch (error) {
+      console.error("Failed to skip break:", error);
+    }
+  };
+
+  const minutes = Math.floor((remaining ?? 0) / 60);
+  const seconds = (remaining ?? 0) % 60;
+
+  return (
+    <div className="break-overlay">
+      <h1>Time for a break</h1>
+      <p className="break-hint">Step away from the screen, stretch, look at something far away.</p>
+      <div className="break-countdown">
+        {minutes}:{seconds.toString().padStart(2, "0")}
+      </div>
+      <button className="break-skip" onClick={handleSkip}>
+        Skip break
+      </button>
+    </div>
+  );
+}
@@ -0,0 +1,35 @@
+import "./ContextChips.css";
+
+interface ContextChipsProps {
+  // Contexts of the open tasks with how many each has
+  counts: Record<string, number>;
+  selected: string | null;
+  onSelect: (context: string | null) => void;
+}
+
+// One chip per context (@phone, @errand) narrowing the list; clicking the selected one clears it
+export default function ContextChips({ counts, selected, onSelect }: ContextChipsProps) {
+  const names = Object.keys(counts).sort();
+  // Keep the selected chip even once its last task is done, so it can be cleared
+  if (selected && !names.includes(selected)) {
+    names.push(selected);
+  }
+  if (names.length === 0) {
+    return null;
+  }
+
+  return (
+    <div className="context-chips">
+      {names.map((name) => (
+        <button
+          key={name}
+          className={`context-chip ${selected === name ? "selected" : ""}`}
+          onClick={() => onSelect(selected === name ? null : name)}
+          title={`Only tasks in @${name} (click again to show all)`}
+        >
+          @{name} {counts[name] ?? 0}
+        </button>
+      ))}
+    </div>
+  );
+}
@@ -0,0 +1,43 @@
+import { useState, useEffect } from "react";
+import { listen } from "@tauri-apps/api/event";
+import "./DailyReviewBanner.css";
+
+// Mirrors rollover::DailyReview on the Rust side
+interface DailyReview {
+  date: string;
+  completed: number;
+  open: number;
+  rolled_over: number;
+  archived: number;
+}
+
+// "Daily review ready" prompt, shown after the end-of-day rollover has run
+export default function DailyReviewBanner() {
+  const [review, setReview] = useState<DailyReview | null>(null);
+
+  useEffect(() => {
+    const unlisten = listen<DailyReview>("daily-review", (event) => {
+      setReview(event.payload);
+    });
+    return () => {
+      unlisten.then(fn => fn());
+    };
+  }, []);
+
+  if (!review) return null;
+
+  return (
+    <div className="daily-review-banner">
+      <p className="daily-review-title">Daily review ready ({review.date})</p>
+      <ul className="daily-review-counts">
+        <li>✓ {review.completed} done</li>
+        <li>○ {review.open} still open</li>
+        <li>⏰ {review.rolled_over} reminders moved to tomorrow</li>
+        <li>🗄 {review.archived} completed tasks archived</li>
+      </ul>
+      <div className="daily-review-actions">
+        <button onClick={() => setReview(null)}>Got it</button>
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,67 @@
+import { useState, useEffect } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import "./DisambiguationBanner.css";
+
+interface Candidate {
+  id: number;
+  text: string;
+}
+
+// Mirrors disambiguation::NeedsDisambiguation on the Rust side
+interface Question {
+  id: number;
+  phrase: string;
+  candidates: Candidate[];
+}
+
+// Mirrors disambiguation::Resolved on the Rust side
+interface Resolved {
+  completed: Candidate | null;
+  next: Question | null;
+}
+
+// Asks which task a spoken completion meant when several fit. The questions
+// queue up on the Rust side; this shows the oldest one until it's answered.
+export default function DisambiguationBanner() {
+  const [question, setQuestion] = useState<Question | null>(null);
+
+  useEffect(() => {
+    invoke<Question | null>("get_disambiguation").then(setQuestion).catch(console.error);
+    const unlisten = listen<Question>("disambiguation-needed", (event) => {
+      setQuestion((shown) => shown ?? event.payload);
+    });
+    return () => {
+      unlisten.then(fn => fn());
+    };
+  }, []);
+
+  const resolve = async (choice: number | null) => {
+    try {
+      const resolved = await invoke<Resolved>("resolve_disambiguation", { choice });
+      setQuestion(resolved.next);
+    } catch (error) {
+      console.error("Failed to resolve disambiguation:", error);
+      // The question may have been answered elsewhere; show whatever is left
+      setQuestion(await invoke<Question | null>("get_disambiguation"));
+    }
+  };
+
+  if (!question) return null;
+
+  return (
+    <div className="disambiguation-banner">
+      <p className="disambiguation-title">Which task is done? "{question.phrase}" fits several</p>
+      <div className="disambiguation-choices">
+        {question.candidates.map((task) => (
+          <button key={task.id} onClick={() => resolve(task.id)}>
+            {task.text}
+          </button>
+        ))}
+        <button className="disambiguation-none" onClick={() => resolve(null)}>
+          None of these
+        </button>
+      </div>
+    </div>
+  );
+}
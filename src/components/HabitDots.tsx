@@ -0,0 +1,84 @@
+import { useState, useEffect } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import { errorMessage } from "../errors";
+import "./HabitDots.css";
+
+interface HabitDay {
+  date: string;
+  due: boolean;
+  done: boolean;
+}
+
+export interface HabitStatus {
+  id: number;
+  name: string;
+  schedule: string;
+  streak: number;
+  due_today: boolean;
+  done_today: boolean;
+  // The last seven days, oldest first
+  recent: HabitDay[];
+}
+
+// One row per habit: the last week as dots, today's clickable, and the streak
+export default function HabitDots() {
+  const [habits, setHabits] = useState<HabitStatus[]>([]);
+  const [error, setError] = useState<string | null>(null);
+
+  const loadHabits = async () => {
+    try {
+      setHabits(await invoke<HabitStatus[]>("get_habits"));
+    } catch (err) {
+      console.error("Failed to load habits:", err);
+    }
+  };
+
+  useEffect(() => {
+    loadHabits();
+    // Changed in Settings or by a "log habit ..." voice note
+    const unlisten = listen("habits-changed", () => {
+      loadHabits();
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  const toggleToday = async (id: number) => {
+    setError(null);
+    try {
+      await invoke("toggle_habit", { id });
+    } catch (err) {
+      setError(errorMessage(err));
+    }
+  };
+
+  if (habits.length === 0) return null;
+
+  return (
+    <div className="habit-dots">
+      {habits.map((habit) => (
+        <div key={habit.id} className="habit-row">
+          <span className="habit-name">{habit.name}</span>
+          {habit.recent.map((day, index) => {
+            const isToday = index === habit.recent.length - 1;
+            const className = `habit-dot${day.done ? " done" : ""}${day.due ? "" : " off"}${isToday ? " today" : ""}`;
+            return isToday ? (
+              <button
+                key={day.date}
+                className={className}
+                onClick={() => toggleToday(habit.id)}
+                title="Mark today done or not done"
+              />
+            ) : (
+              <span key={day.date} className={className} title={day.date} />
+            );
+          })}
+          {habit.streak > 0 && <span className="habit-streak">🔥{habit.streak}</span>}
+        </div>
+      ))}
+      {error && <p className="habit-error">{error}</p>}
+    </div>
+  );
+}
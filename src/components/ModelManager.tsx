@@ -1,6 +1,7 @@
 import { useState, useEffect } from "react";
 import { invoke } from "@tauri-apps/api/core";
 import { listen } from "@tauri-apps/api/event";
+import { errorMessage } from "../errors";
 import "./ModelManager.css";
 
 // Check if running in Tauri (v2 uses __TAURI_INTERNALS__)
@@ -13,27 +14,44 @@ interface ModelInfo {
   installed: boolean;
 }
 
-interface DownloadProgress {
+// One model's place in the download queue ("model-download" event payload)
+type DownloadEvent = { model: string } & (
+  | { state: "queued" | "completed" | "cancelled" }
+  | { state: "downloading"; downloaded: number; total: number }
+  | { state: "failed"; error: string }
+);
+
+// Mirrors benchmark::BenchmarkResult on the Rust side
+interface BenchmarkResult {
   model: string;
-  downloaded: number;
-  total: number;
-  progress: number;
+  load_secs: number;
+  transcribe_secs: number;
+  real_time_factor: number;
+  memory_mb: number | null;
 }
 
 export default function ModelManager() {
   const [models, setModels] = useState<ModelInfo[]>([]);
-  const [downloading, setDownloading] = useState<string | null>(null);
-  const [downloadProgress, setDownloadProgress] = useState<DownloadProgress | null>(null);
+  const [downloads, setDownloads] = useState<Record<string, DownloadEvent>>({});
   const [selectedModel, setSelectedModel] = useState<string>("tiny");
+  const [benchmarks, setBenchmarks] = useState<Record<string, BenchmarkResult>>({});
+  const [benchmarking, setBenchmarking] = useState<boolean>(false);
+  const [benchmarkError, setBenchmarkError] = useState<string | null>(null);
 
   useEffect(() => {
     if (!isTauri) return;
 
     loadModels();
+    // Downloads keep running while Settings is closed; pick up where they are
+    invoke<DownloadEvent[]>("get_model_downloads")
+      .then(events => setDownloads(Object.fromEntries(events.map(e => [e.model, e]))))
+      .catch(error => console.error("Failed to load downloads:", error));
 
-    // Listen for download progress
-    const unlisten = listen("model-download-progress", (event) => {
-      setDownloadProgress(event.payload as DownloadProgress);
+    const unlisten = listen<DownloadEvent>("model-download", (event) => {
+      setDownloads(prev => ({ ...prev, [event.payload.model]: event.payload }));
+      if (event.payload.state === "completed") {
+        loadModels();
+      }
     });
 
     return () => {
@@ -47,15 +65,26 @@ export default function ModelManager() {
       const loadedModels = await invoke<ModelInfo[]>("list_whisper_models");
       setModels(loadedModels);
       
-      // Set default selected model to first installed one, or tiny
-      const installed = loadedModels.find(m => m.installed);
+      // Show the saved model if it's installed, otherwise the first installed one
+      const { selected_model } = await invoke<{ selected_model: string }>("get_settings");
+      const installed = loadedModels.find(m => m.installed && m.name.toLowerCase() === selected_model)
+        ?? loadedModels.find(m => m.installed);
       if (installed) {
         setSelectedModel(installed.name.toLowerCase());
       }
     } catch (error) {
       console.error("Failed to load models:", error);
       // Show error to user
-      alert(`Failed to load models: ${error}. Please check the console for details.`);
+      alert(`Failed to load models: ${errorMessage(error)}. Please check the console for details.`);
+    }
+  };
+
+  const handleSelect = async (modelName: string) => {
+    try {
+      await invoke("update_settings", { patch: { selected_model: modelName.toLowerCase() } });
+      setSelectedModel(modelName.toLowerCase());
+    } catch (error) {
+      console.error("Failed to select model:", error);
     }
   };
 
@@ -65,17 +94,18 @@ export default function ModelManager() {
       return;
     }
     try {
-      setDownloading(modelName);
-      setDownloadProgress(null);
       await invoke("download_whisper_model", { modelName: modelName.toLowerCase() });
-      await loadModels();
-      setDownloading(null);
-      setDownloadProgress(null);
     } catch (error) {
       console.error("Failed to download model:", error);
-      alert(`Failed to download model: ${error}`);
-      setDownloading(null);
-      setDownloadProgress(null);
+      alert(`Failed to download model: ${errorMessage(error)}`);
+    }
+  };
+
+  const handleCancel = async (modelName: string) => {
+    try {
+      await invoke("cancel_model_download", { modelName: modelName.toLowerCase() });
+    } catch (error) {
+      console.error("Failed to cancel download:", error);
     }
   };
 
@@ -89,7 +119,22 @@ export default function ModelManager() {
       await loadModels();
     } catch (error) {
       console.error("Failed to delete model:", error);
-      alert(`Failed to delete model: ${error}`);
+      alert(`Failed to delete model: ${errorMessage(error)}`);
+    }
+  };
+
+  // Time every installed model on the built-in test clip
+  const handleBenchmark = async () => {
+    setBenchmarking(true);
+    setBenchmarkError(null);
+    try {
+      const results = await invoke<BenchmarkResult[]>("benchmark_whisper", { model: null });
+      setBenchmarks(Object.fromEntries(results.map(r => [r.model, r])));
+    } catch (error) {
+      console.error("Failed to benchmark models:", error);
+      setBenchmarkError(errorMessage(error));
+    } finally {
+      setBenchmarking(false);
     }
   };
 
@@ -123,64 +168,93 @@ export default function ModelManager() {
       )}
 
       <div className="models-list">
-        {models.map((model) => (
-          <div key={model.name} className="model-item">
-            <div className="model-info">
-              <div className="model-header">
-                <span className="model-name">{model.name}</span>
-                <span className="model-size">{formatSize(model.size_mb)}</span>
+        {models.map((model) => {
+          const download = downloads[model.name.toLowerCase()];
+          const benchmark = benchmarks[model.name.toLowerCase()];
+          return (
+            <div key={model.name} className="model-item">
+              <div className="model-info">
+                <div className="model-header">
+                  <span className="model-name">{model.name}</span>
+                  <span className="model-size">{formatSize(model.size_mb)}</span>
+                </div>
+                <div className="model-status">
+                  {model.installed ? (
+                    <span className="status-installed">✓ Installed</span>
+                  ) : (
+                    <span className="status-missing">✗ Not installed</span>
+                  )}
+                </div>
               </div>
-              <div className="model-status">
+              <div className="model-actions">
                 {model.installed ? (
-                  <span className="status-installed">✓ Installed</span>
+                  <>
+                    <button
+                      className="btn-select"
+                      onClick={() => handleSelect(model.name)}
+                      disabled={selectedModel === model.name.toLowerCase()}
+                    >
+                      {selectedModel === model.name.toLowerCase() ? "Selected" : "Select"}
+                    </button>
+                    <button
+                      className="btn-delete"
+                      onClick={() => handleDelete(model.name)}
+                    >
+                      Delete
+                    </button>
+                  </>
+                ) : download?.state === "queued" || download?.state === "downloading" ? (
+                  <button className="btn-delete" onClick={() => handleCancel(model.name)}>
+                    Cancel
+                  </button>
                 ) : (
-                  <span className="status-missing">✗ Not installed</span>
+                  <button className="btn-download" onClick={() => handleDownload(model.name)}>
+                    {download?.state === "failed" ? "Retry" : "Download"}
+                  </button>
                 )}
               </div>
-            </div>
-            <div className="model-actions">
-              {model.installed ? (
-                <>
-                  <button
-                    className="btn-select"
-                    onClick={() => setSelectedModel(model.name.toLowerCase())}
-                    disabled={selectedModel === model.name.toLowerCase()}
-                  >
-                    {selectedModel === model.name.toLowerCase() ? "Selected" : "Select"}
-                  </button>
-                  <button
-                    className="btn-delete"
-                    onClick={() => handleDelete(model.name)}
-                  >
-                    Delete
-                  </button>
-                </>
-              ) : (
-                <button
-                  className="btn-download"
-                  onClick={() => handleDownload(model.name)}
-                  disabled={downloading !== null}
-                >
-                  {downloading === model.name ? "Downloading..." : "Download"}
-                </button>
+              {download?.state === "queued" && !model.installed && (
+                <div className="progress-text">Waiting for another download to finish...</div>
               )}
-            </div>
-            {downloading === model.name && downloadProgress && (
-              <div className="download-progress">
-                <div className="progress-bar">
-                  <div
-                    className="progress-fill"
-                    style={{ width: `${downloadProgress.progress}%` }}
-                  />
+              {download?.state === "downloading" && (
+                <div className="download-progress">
+                  <div className="progress-bar">
+                    <div
+                      className="progress-fill"
+                      style={{ width: `${Math.round((download.downloaded / Math.max(download.total, 1)) * 100)}%` }}
+                    />
+                  </div>
+                  <div className="progress-text">
+                    {formatSize(Math.round(download.downloaded / 1_000_000))} / {formatSize(Math.round(download.total / 1_000_000))}
+                    {" "}({Math.round((download.downloaded / Math.max(download.total, 1)) * 100)}%)
+                  </div>
                 </div>
-                <div className="progress-text">
-                  {formatSize(downloadProgress.downloaded)} / {formatSize(downloadProgress.total)} 
-                  ({downloadProgress.progress}%)
+              )}
+              {download?.state === "failed" && (
+                <div className="progress-text status-missing">{download.error}</div>
+              )}
+              {model.installed && benchmark && (
+                <div className="benchmark-result">
+                  {benchmark.real_time_factor.toFixed(2)}× real time · loads in {benchmark.load_secs.toFixed(1)}s
+                  {benchmark.memory_mb !== null && ` · uses ${formatSize(Math.round(benchmark.memory_mb))}`}
                 </div>
-              </div>
-            )}
-          </div>
-        ))}
+              )}
+            </div>
+          );
+        })}
+      </div>
+
+      <div className="benchmark">
+        <button
+          className="btn-select"
+          onClick={handleBenchmark}
+          disabled={benchmarking || !models.some(m => m.installed)}
+        >
+          {benchmarking ? "Benchmarking..." : "Benchmark installed models"}
+        </button>
+        <span className="benchmark-hint">
+          {benchmarkError ?? "Times each model on a built-in test clip; below 1× is faster than real time"}
+        </span>
       </div>
 
       <div className="model-note">
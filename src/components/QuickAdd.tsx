@@ -0,0 +1,48 @@
+import { useState, useEffect, useRef } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { errorMessage } from "../errors";
+import "./QuickAdd.css";
+
+// Tiny always-on-top task box opened by the global quick-add shortcut
+export default function QuickAdd() {
+  const [text, setText] = useState("");
+  const [error, setError] = useState<string | null>(null);
+  const inputRef = useRef<HTMLInputElement>(null);
+
+  useEffect(() => {
+    inputRef.current?.focus();
+  }, []);
+
+  const handleKeyDown = async (e: React.KeyboardEvent<HTMLInputElement>) => {
+    if (e.key === "Escape") {
+      invoke("close_quick_add").catch(() => {});
+      return;
+    }
+    if (e.key !== "Enter" || !text.trim()) return;
+
+    try {
+      // The backend closes this window once the task is saved
+      await invoke("quick_add_task", { text });
+    } catch (err) {
+      setError(errorMessage(err));
+    }
+  };
+
+  return (
+    <div className="quick-add">
+      <input
+        ref={inputRef}
+        className="quick-add-input"
+        value={text}
+        placeholder="Add a task and press Enter"
+        onChange={(e) => {
+          setText(e.target.value);
+          setError(null);
+        }}
+        onKeyDown={handleKeyDown}
+        onBlur={() => invoke("close_quick_add").catch(() => {})}
+      />
+      {error && <p className="quick-add-error">{error}</p>}
+    </div>
+  );
+}
@@ -7,6 +7,8 @@ interface RecordButtonProps {
   recordingTime?: number;
   onStartRecording: () => void;
   onStopRecording: () => void;
+  /** Small button without the hint text, for the compact window */
+  compact?: boolean;
 }
 
 export default function RecordButton({
@@ -15,6 +17,7 @@ export default function RecordButton({
   recordingTime = 0,
   onStartRecording,
   onStopRecording,
+  compact = false,
 }: RecordButtonProps) {
   const isHoldingRef = useRef(false);
   const pointerIdRef = useRef<number | null>(null);
@@ -90,7 +93,7 @@ export default function RecordButton({
   }, []);
 
   return (
-    <div className="record-button-container">
+    <div className={`record-button-container ${compact ? "compact" : ""}`}>
       <button
         className={`record-button ${isRecording ? "recording" : ""} ${isProcessing ? "processing" : ""}`}
         // Pointer events (modern browsers, touch screens)
@@ -136,15 +139,17 @@ export default function RecordButton({
           </svg>
         )}
       </button>
-      <div className="recording-hint">
-        {isProcessing ? null : isRecording ? "● Recording..." : "Hold to record"}
-      </div>
+      {!compact && (
+        <div className="recording-hint">
+          {isProcessing ? null : isRecording ? "● Recording..." : "Hold to record"}
+        </div>
+      )}
       {isRecording && !isProcessing && (
         <div className="recording-time">
           {formatTime(recordingTime)}
         </div>
       )}
-      {isProcessing && (
+      {isProcessing && !compact && (
         <div className="processing-indicator">
           <span>Processing...</span>
         </div>
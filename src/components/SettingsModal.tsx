@@ -1,13 +1,348 @@
 import { useState, useEffect, useRef } from "react";
 import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import { open } from "@tauri-apps/plugin-dialog";
+import { open as openUrl } from "@tauri-apps/plugin-shell";
+import { installUpdate, UpdateInfo } from "./UpdateBanner";
 import ModelManager from "./ModelManager";
+import { HabitStatus } from "./HabitDots";
+import { useAudioRecorder } from "../hooks/useAudioRecorder";
+import { errorMessage } from "../errors";
 import "./SettingsModal.css";
 
+// Mirrors diagnostics::DiagnosticsReport on the Rust side
+interface DiagnosticCheck {
+  id: string;
+  label: string;
+  status: "ok" | "warning" | "error";
+  detail: string;
+}
+
+interface DiagnosticsReport {
+  version: string;
+  os: string;
+  checks: DiagnosticCheck[];
+}
+
 interface AudioDevice {
   deviceId: string;
   label: string;
 }
 
+interface TimerSchedule {
+  enabled: boolean;
+  days: number[]; // 0 = Monday ... 6 = Sunday
+  start: string; // "HH:MM"
+  end: string;
+}
+
+interface BreakSettings {
+  enabled: boolean;
+  minutes: number;
+}
+
+interface EscalationSettings {
+  enabled: boolean;
+  after_minutes: number;
+  flash_taskbar: boolean;
+}
+
+interface DndSettings {
+  respect_system_dnd: boolean;
+  fullscreen_apps: string[];
+}
+
+interface MarkdownSyncSettings {
+  enabled: boolean;
+  path: string;
+  two_way: boolean;
+}
+
+interface CalendarExportSettings {
+  enabled: boolean;
+  path: string;
+}
+
+interface GoogleCalendarSettings {
+  enabled: boolean;
+  client_id: string;
+  client_secret: string;
+  // Set by Connect; empty until the account is signed in
+  refresh_token: string;
+  calendar_id: string;
+}
+
+interface TodoistSettings {
+  enabled: boolean;
+  token: string;
+  interval_minutes: number;
+}
+
+interface OpenAiSettings {
+  enabled: boolean;
+  api_key: string;
+  model: string;
+  // API root; another OpenAI-compatible server can go here
+  url: string;
+  timeout_secs: number;
+}
+
+// Mirrors parser::ParserKind on the Rust side
+type ParserKind = "rules" | "ollama" | "openai";
+
+interface GithubSettings {
+  enabled: boolean;
+  token: string;
+  // "owner/name"
+  repo: string;
+}
+
+interface MqttSettings {
+  enabled: boolean;
+  host: string;
+  port: number;
+  username: string;
+  password: string;
+  topic_prefix: string;
+}
+
+interface ReportSettings {
+  // Empty means "FlowState" in the Documents folder
+  folder: string;
+  pdf: boolean;
+}
+
+interface RolloverSettings {
+  enabled: boolean;
+  // Local "HH:MM" the day ends at
+  time: string;
+  archive_after_days: number;
+}
+
+interface WorkloadSettings {
+  // Minutes of estimated work a day before the workload warns
+  capacity_minutes: number;
+}
+
+interface PunctuationSettings {
+  enabled: boolean;
+  // Ask Ollama first (when it's on); the rules are the fallback
+  ollama: boolean;
+}
+
+interface VoiceProfile {
+  pitch_hz: number;
+  voiced_secs: number;
+}
+
+interface SpeakerGateSettings {
+  enabled: boolean;
+  // Enrolled with enroll_speaker; the gate can't be on without one
+  profile: VoiceProfile | null;
+  tolerance_semitones: number;
+}
+
+interface ContentFilterSettings {
+  enabled: boolean;
+  // "mask" swaps each letter for "*", "drop" leaves the word out
+  mode: "mask" | "drop";
+  // Built-in English and Russian swear words
+  profanity: boolean;
+  // Extra words or phrases, like client names
+  words: string[];
+}
+
+interface WeeklyReport {
+  markdown_path: string;
+  pdf_path: string | null;
+}
+
+interface ClipboardSettings {
+  enabled: boolean;
+  // Prefix that marks copied text as a task, e.g. "todo:"
+  trigger: string;
+}
+
+interface TelegramSettings {
+  enabled: boolean;
+  token: string;
+  // Sender whose messages become tasks
+  username: string;
+}
+
+// Mirrors dnd::DndState on the Rust side
+type DndState =
+  | { reason: "off" }
+  | { reason: "system" }
+  | { reason: "fullscreen_app"; app: string };
+
+// Mirrors settings::Profile on the Rust side
+interface Profile {
+  name: string;
+  // Without the "+" / "#"; tasks with no project or tag always show
+  projects: string[];
+  tags: string[];
+  // Whisper language code, or null for the default
+  language: string | null;
+  // Added to the Ollama prompt when parsing voice memos
+  prompt: string;
+  // null keeps the global working hours
+  schedule: TimerSchedule | null;
+}
+
+interface ProfileSettings {
+  active: string | null;
+  profiles: Profile[];
+}
+
+// Mirrors settings::Settings on the Rust side
+interface Settings {
+  timer_duration_minutes: number;
+  // 0 keeps the Whisper model loaded
+  model_unload_minutes: number;
+  // Whisper language code, or "auto" to detect it
+  transcription_language: string;
+  ollama_enabled: boolean;
+  openai: OpenAiSettings;
+  // Tried in order until one answers; turned-off ones are skipped
+  parser_chain: ParserKind[];
+  punctuation: PunctuationSettings;
+  earcons_muted: boolean;
+  wake_word_enabled: boolean;
+  alert_mode: string;
+  alert_sound: string;
+  auto_pause_enabled: boolean;
+  journal_prompt_enabled: boolean;
+  speak_tasks_on_alert: boolean;
+  voice_inbox: boolean;
+  // Commands only after "command:"; everything else is one task as said
+  action_mode: boolean;
+  speaker_gate: SpeakerGateSettings;
+  content_filter: ContentFilterSettings;
+  focus_monitor_enabled: boolean;
+  schedule: TimerSchedule;
+  escalation: EscalationSettings;
+  break_mode: BreakSettings;
+  dnd: DndSettings;
+  markdown_sync: MarkdownSyncSettings;
+  calendar_export: CalendarExportSettings;
+  google_calendar: GoogleCalendarSettings;
+  todoist: TodoistSettings;
+  github: GithubSettings;
+  telegram: TelegramSettings;
+  clipboard: ClipboardSettings;
+  mqtt: MqttSettings;
+  report: ReportSettings;
+  rollover: RolloverSettings;
+  workload: WorkloadSettings;
+  profiles: ProfileSettings;
+  quick_add_shortcut: string;
+  compact_mode_shortcut: string;
+  close_behavior: "hide" | "quit";
+  auto_check_updates: boolean;
+  // null follows the system language
+  ui_language: "en" | "ru" | null;
+}
+
+interface FocusDay {
+  date: string;
+  checkpoints: number;
+  acknowledged: number;
+  focus_seconds: number;
+}
+
+const WEEKDAYS = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+
+// Schedules offered when adding a habit, as stored by habits::normalize_schedule
+const HABIT_SCHEDULES = [
+  { value: "daily", label: "Every day" },
+  { value: "mon,tue,wed,thu,fri", label: "Weekdays" },
+  { value: "sat,sun", label: "Weekends" },
+];
+
+// Mirrors macros::MacroAction on the Rust side
+type MacroAction =
+  | { type: "add_task"; text: string }
+  | { type: "archive_completed" }
+  | { type: "timer"; command: "reset" | "pause" | "resume" | { set_minutes: number } };
+
+interface VoiceMacro {
+  id: number;
+  phrase: string;
+  actions: MacroAction[];
+}
+
+// Mirrors api_tokens::Scope on the Rust side
+type TokenScope = "read" | "write" | "voice";
+
+const TOKEN_SCOPES: { value: TokenScope; label: string }[] = [
+  { value: "read", label: "Read tasks" },
+  { value: "write", label: "Change tasks" },
+  { value: "voice", label: "Send recordings" },
+];
+
+// Mirrors api_tokens::ApiToken; the secret only comes back once, from issue_api_token
+interface ApiToken {
+  id: number;
+  name: string;
+  prefix: string;
+  scopes: TokenScope[];
+  created_at: string;
+  last_used_at: string | null;
+}
+
+// Mirrors history::HistoryAction on the Rust side
+interface HistoryAction {
+  kind: "add" | "complete";
+  text: string;
+  due_at: string | null;
+  task_id: number | null;
+}
+
+// Mirrors history::ReparseDiff on the Rust side
+interface ReparseDiff {
+  transcription_id: number;
+  source: string;
+  // UTC "YYYY-MM-DD HH:MM:SS"
+  created_at: string;
+  transcript: string;
+  parser: ParserKind;
+  added: HistoryAction[];
+  removed: HistoryAction[];
+  unchanged: number;
+}
+
+// Timer actions offered in the macro form
+const MACRO_TIMER_COMMANDS = [
+  { value: "", label: "No timer action" },
+  { value: "reset", label: "Restart the timer" },
+  { value: "pause", label: "Pause the timer" },
+  { value: "resume", label: "Resume the timer" },
+];
+
+// "3 tasks, archive completed" for the macro list
+const macroSummary = (actions: MacroAction[]) => {
+  const tasks = actions.filter((action) => action.type === "add_task").length;
+  const parts = tasks > 0 ? [`${tasks} task${tasks === 1 ? "" : "s"}`] : [];
+  for (const action of actions) {
+    if (action.type === "archive_completed") parts.push("archive completed");
+    if (action.type === "timer") {
+      parts.push(typeof action.command === "string"
+        ? `${action.command} the timer`
+        : `set the timer to ${action.command.set_minutes} min`);
+    }
+  }
+  return parts.join(", ");
+};
+
+// Parser orders offered in settings
+const PARSER_CHAINS: { value: ParserKind[]; label: string }[] = [
+  { value: ["ollama", "openai", "rules"], label: "Ollama, then OpenAI, then the rules" },
+  { value: ["openai", "ollama", "rules"], label: "OpenAI, then Ollama, then the rules" },
+  { value: ["ollama", "openai"], label: "Ollama, then OpenAI; report an error if both fail" },
+  { value: ["rules"], label: "Only the rules (instant, offline)" },
+];
+
 interface SettingsModalProps {
   isOpen: boolean;
   onClose: () => void;
@@ -33,173 +368,2205 @@ export default function SettingsModal({
   const [timerDuration, setTimerDuration] = useState<number>(15);
   const [ollamaEnabled, setOllamaEnabled] = useState<boolean>(false);
   const [ollamaEnabledLoading, setOllamaEnabledLoading] = useState<boolean>(false);
+  const [earconsMuted, setEarconsMuted] = useState<boolean>(false);
+  const [alertMode, setAlertMode] = useState<string>("notification");
+  const [alertSound, setAlertSound] = useState<string>("chime");
+  const [autoPauseEnabled, setAutoPauseEnabled] = useState<boolean>(true);
+  const [journalPromptEnabled, setJournalPromptEnabled] = useState<boolean>(true);
+  const [speakTasksOnAlert, setSpeakTasksOnAlert] = useState<boolean>(false);
+  const [voiceInbox, setVoiceInbox] = useState<boolean>(false);
+  const [actionMode, setActionMode] = useState<boolean>(false);
+  const [focusMonitorEnabled, setFocusMonitorEnabled] = useState<boolean>(false);
+  const [schedule, setSchedule] = useState<TimerSchedule>({
+    enabled: false,
+    days: [0, 1, 2, 3, 4],
+    start: "09:00",
+    end: "18:00",
+  });
+  const [scheduleError, setScheduleError] = useState<string | null>(null);
+  const [focusHistory, setFocusHistory] = useState<FocusDay[]>([]);
+  const [breakSettings, setBreakSettings] = useState<BreakSettings>({ enabled: false, minutes: 5 });
+  const [escalation, setEscalation] = useState<EscalationSettings>({
+    enabled: true,
+    after_minutes: 2,
+    flash_taskbar: true,
+  });
+  const [wakeWordEnabled, setWakeWordEnabled] = useState<boolean>(false);
+  const [wakeWordError, setWakeWordError] = useState<string | null>(null);
+  const [diagnosticsCopied, setDiagnosticsCopied] = useState<boolean>(false);
+  const [diagnostics, setDiagnostics] = useState<DiagnosticsReport | null>(null);
+  const [diagnosticsRunning, setDiagnosticsRunning] = useState<boolean>(false);
+  const [quickAddShortcut, setQuickAddShortcut] = useState<string>("");
+  const [compactShortcut, setCompactShortcut] = useState<string>("");
+  const [compactShortcutError, setCompactShortcutError] = useState<string | null>(null);
+  const [dnd, setDnd] = useState<DndSettings>({ respect_system_dnd: true, fullscreen_apps: [] });
+  // Comma-separated while editing, saved on blur
+  const [dndApps, setDndApps] = useState<string>("");
+  const [dndState, setDndState] = useState<DndState | null>(null);
+  const [markdownSync, setMarkdownSync] = useState<MarkdownSyncSettings>({ enabled: false, path: "", two_way: false });
+  // Typed path, saved on blur
+  const [markdownPath, setMarkdownPath] = useState<string>("");
+  const [markdownError, setMarkdownError] = useState<string | null>(null);
+  const [calendarExport, setCalendarExport] = useState<CalendarExportSettings>({ enabled: false, path: "" });
+  const [calendarPath, setCalendarPath] = useState<string>("");
+  const [calendarError, setCalendarError] = useState<string | null>(null);
+  const [googleCalendar, setGoogleCalendar] = useState<GoogleCalendarSettings>({ enabled: false, client_id: "", client_secret: "", refresh_token: "", calendar_id: "primary" });
+  const [googleConnecting, setGoogleConnecting] = useState(false);
+  const [googleError, setGoogleError] = useState<string | null>(null);
+  const [todoist, setTodoist] = useState<TodoistSettings>({ enabled: false, token: "", interval_minutes: 5 });
+  // Typed token, saved on blur
+  const [todoistToken, setTodoistToken] = useState<string>("");
+  const [todoistError, setTodoistError] = useState<string | null>(null);
+  const [openai, setOpenai] = useState<OpenAiSettings>({ enabled: false, api_key: "", model: "gpt-4o-mini", url: "https://api.openai.com/v1", timeout_secs: 15 });
+  // Typed key and model, saved on blur
+  const [openaiKey, setOpenaiKey] = useState<string>("");
+  const [openaiModel, setOpenaiModel] = useState<string>("");
+  const [openaiError, setOpenaiError] = useState<string | null>(null);
+  const [parserChain, setParserChain] = useState<ParserKind[]>(["ollama", "openai", "rules"]);
+  const [punctuation, setPunctuation] = useState<PunctuationSettings>({ enabled: true, ollama: false });
+  const [github, setGithub] = useState<GithubSettings>({ enabled: false, token: "", repo: "" });
+  // Typed token and repository, saved on blur
+  const [githubToken, setGithubToken] = useState<string>("");
+  const [githubRepo, setGithubRepo] = useState<string>("");
+  const [githubError, setGithubError] = useState<string | null>(null);
+  // Edited locally and saved on blur, since each save reconnects
+  const [mqtt, setMqtt] = useState<MqttSettings>({ enabled: false, host: "localhost", port: 1883, username: "", password: "", topic_prefix: "flowstate" });
+  const [mqttError, setMqttError] = useState<string | null>(null);
+  const [report, setReport] = useState<ReportSettings>({ folder: "", pdf: false });
+  // Typed folder, saved on blur
+  const [reportFolder, setReportFolder] = useState<string>("");
+  // Path of the last report written, or why it failed
+  const [reportStatus, setReportStatus] = useState<string | null>(null);
+  const [rollover, setRollover] = useState<RolloverSettings>({ enabled: false, time: "23:30", archive_after_days: 7 });
+  const [rolloverError, setRolloverError] = useState<string | null>(null);
+  const [workload, setWorkload] = useState<WorkloadSettings>({ capacity_minutes: 360 });
+  const [workloadError, setWorkloadError] = useState<string | null>(null);
+  const [speakerGate, setSpeakerGate] = useState<SpeakerGateSettings>({ enabled: false, profile: null, tolerance_semitones: 4 });
+  const [speakerStatus, setSpeakerStatus] = useState<string | null>(null);
+  const [contentFilter, setContentFilter] = useState<ContentFilterSettings>({ enabled: false, mode: "mask", profanity: true, words: [] });
+  // Comma-separated while editing, saved on blur
+  const [filterWords, setFilterWords] = useState<string>("");
+  // Records the enrollment sample, apart from the main recorder
+  const enrollRecorder = useAudioRecorder();
+  const [modelUnloadMinutes, setModelUnloadMinutes] = useState<number>(10);
+  const [modelUnloadStatus, setModelUnloadStatus] = useState<string | null>(null);
+  const [transcriptionLanguage, setTranscriptionLanguage] = useState<string>("ru");
+  const [transcriptionLanguageError, setTranscriptionLanguageError] = useState<string | null>(null);
+  // Edited locally and saved on blur, keeping invalid edits on screen with the error
+  const [profiles, setProfiles] = useState<ProfileSettings>({ active: null, profiles: [] });
+  const [newProfileName, setNewProfileName] = useState<string>("");
+  const [profileError, setProfileError] = useState<string | null>(null);
+  const [habits, setHabits] = useState<HabitStatus[]>([]);
+  const [newHabitName, setNewHabitName] = useState<string>("");
+  const [newHabitSchedule, setNewHabitSchedule] = useState<string>("daily");
+  const [habitError, setHabitError] = useState<string | null>(null);
+  const [macros, setMacros] = useState<VoiceMacro[]>([]);
+  const [apiTokens, setApiTokens] = useState<ApiToken[]>([]);
+  const [tokenName, setTokenName] = useState<string>("");
+  const [tokenScopes, setTokenScopes] = useState<TokenScope[]>(["read"]);
+  // The secret of the token just issued, shown until the next change
+  const [newSecret, setNewSecret] = useState<string | null>(null);
+  const [tokenError, setTokenError] = useState<string | null>(null);
+  // The add/edit form: tasks one per line; editingMacro is the id being edited
+  const [macroPhrase, setMacroPhrase] = useState<string>("");
+  const [macroTasks, setMacroTasks] = useState<string>("");
+  const [macroArchive, setMacroArchive] = useState<boolean>(false);
+  const [macroTimer, setMacroTimer] = useState<string>("");
+  const [editingMacro, setEditingMacro] = useState<number | null>(null);
+  const [macroError, setMacroError] = useState<string | null>(null);
+  const [reparseFrom, setReparseFrom] = useState<string>("");
+  const [reparseTo, setReparseTo] = useState<string>("");
+  const [reparseDiffs, setReparseDiffs] = useState<ReparseDiff[] | null>(null);
+  const [reparseSelected, setReparseSelected] = useState<number[]>([]);
+  const [reparseRunning, setReparseRunning] = useState<boolean>(false);
+  const [reparseStatus, setReparseStatus] = useState<string | null>(null);
+  const [clipboard, setClipboard] = useState<ClipboardSettings>({ enabled: false, trigger: "todo:" });
+  // Typed trigger, saved on blur
+  const [clipboardTrigger, setClipboardTrigger] = useState<string>("todo:");
+  const [clipboardError, setClipboardError] = useState<string | null>(null);
+  const [telegram, setTelegram] = useState<TelegramSettings>({ enabled: false, token: "", username: "" });
+  // Typed token and username, saved on blur
+  const [telegramToken, setTelegramToken] = useState<string>("");
+  const [telegramUsername, setTelegramUsername] = useState<string>("");
+  const [telegramError, setTelegramError] = useState<string | null>(null);
+  const [closeBehavior, setCloseBehavior] = useState<"hide" | "quit">("hide");
+  const [autoCheckUpdates, setAutoCheckUpdates] = useState<boolean>(true);
+  const [uiLanguage, setUiLanguage] = useState<"en" | "ru" | null>(null);
+  // null = not checked yet, false = up to date
+  const [availableUpdate, setAvailableUpdate] = useState<UpdateInfo | false | null>(null);
+  const [updateStatus, setUpdateStatus] = useState<string | null>(null);
+  const [quickAddError, setQuickAddError] = useState<string | null>(null);
   const timerSaveTimeoutRef = useRef<ReturnType<typeof setTimeout> | null>(null);
 
   useEffect(() => {
     if (isOpen) {
       checkOllamaStatus();
       checkAutoStartStatus();
-      loadTimerDuration();
-      checkOllamaEnabled();
+      loadSettings();
+      loadFocusHistory();
+      loadHabits();
+      loadMacros();
+      loadApiTokens();
+      invoke<DndState>("get_dnd_state").then(setDndState).catch(() => setDndState(null));
     }
   }, [isOpen]);
 
-  const loadTimerDuration = async () => {
+  const applySettings = (settings: Settings) => {
+    setTimerDuration(settings.timer_duration_minutes);
+    setOllamaEnabled(settings.ollama_enabled);
+    setEarconsMuted(settings.earcons_muted);
+    setWakeWordEnabled(settings.wake_word_enabled);
+    setAlertMode(settings.alert_mode);
+    setAlertSound(settings.alert_sound);
+    setAutoPauseEnabled(settings.auto_pause_enabled);
+    setJournalPromptEnabled(settings.journal_prompt_enabled);
+    setSpeakTasksOnAlert(settings.speak_tasks_on_alert);
+    setVoiceInbox(settings.voice_inbox);
+    setActionMode(settings.action_mode);
+    setFocusMonitorEnabled(settings.focus_monitor_enabled);
+    setSchedule(settings.schedule);
+    setEscalation(settings.escalation);
+    setBreakSettings(settings.break_mode);
+    setQuickAddShortcut(settings.quick_add_shortcut);
+    setCompactShortcut(settings.compact_mode_shortcut);
+    setDnd(settings.dnd);
+    setDndApps(settings.dnd.fullscreen_apps.join(", "));
+    setMarkdownSync(settings.markdown_sync);
+    setMarkdownPath(settings.markdown_sync.path);
+    setCalendarExport(settings.calendar_export);
+    setCalendarPath(settings.calendar_export.path);
+    setGoogleCalendar(settings.google_calendar);
+    setTodoist(settings.todoist);
+    setTodoistToken(settings.todoist.token);
+    setOpenai(settings.openai);
+    setOpenaiKey(settings.openai.api_key);
+    setOpenaiModel(settings.openai.model);
+    setParserChain(settings.parser_chain);
+    setPunctuation(settings.punctuation);
+    setGithub(settings.github);
+    setGithubToken(settings.github.token);
+    setGithubRepo(settings.github.repo);
+    setMqtt(settings.mqtt);
+    setReport(settings.report);
+    setRollover(settings.rollover);
+    setWorkload(settings.workload);
+    setSpeakerGate(settings.speaker_gate);
+    setContentFilter(settings.content_filter);
+    setFilterWords(settings.content_filter.words.join(", "));
+    setModelUnloadMinutes(settings.model_unload_minutes);
+    setTranscriptionLanguage(settings.transcription_language);
+    setProfiles(settings.profiles);
+    setReportFolder(settings.report.folder);
+    setClipboard(settings.clipboard);
+    setClipboardTrigger(settings.clipboard.trigger);
+    setTelegram(settings.telegram);
+    setTelegramToken(settings.telegram.token);
+    setTelegramUsername(settings.telegram.username);
+    setCloseBehavior(settings.close_behavior);
+    setAutoCheckUpdates(settings.auto_check_updates);
+    setUiLanguage(settings.ui_language);
+  };
+
+  const loadSettings = async () => {
     try {
-      const duration = await invoke<number>("get_timer_duration");
-      setTimerDuration(duration);
+      applySettings(await invoke<Settings>("get_settings"));
     } catch (error) {
-      console.error("Failed to load timer duration:", error);
+      console.error("Failed to load settings:", error);
     }
   };
 
-  const handleTimerDurationChange = (minutes: number) => {
-    // Update local state immediately for smooth slider movement
-    setTimerDuration(minutes);
-    
-    // Clear any pending save operation
-    if (timerSaveTimeoutRef.current) {
-      clearTimeout(timerSaveTimeoutRef.current);
+  // Save a subset of settings; the backend merges, validates and returns the full set
+  const updateSettings = async (patch: Partial<Settings>) => {
+    const settings = await invoke<Settings>("update_settings", { patch });
+    applySettings(settings);
+    return settings;
+  };
+
+  const checkForUpdates = async () => {
+    setUpdateStatus("Checking...");
+    try {
+      const update = await invoke<UpdateInfo | null>("check_for_updates");
+      setAvailableUpdate(update ?? false);
+      setUpdateStatus(null);
+    } catch (error) {
+      setUpdateStatus(errorMessage(error));
     }
-    
-    // Debounce the save operation - only save after user stops sliding
-    // Don't set loading state - keep slider enabled for smooth interaction
-    timerSaveTimeoutRef.current = setTimeout(async () => {
+  };
+
+  const handleInstallUpdate = async (update: UpdateInfo) => {
+    setUpdateStatus("Downloading...");
+    try {
+      await installUpdate(update);
+      setUpdateStatus(null);
+    } catch (error) {
+      setUpdateStatus(errorMessage(error));
+    }
+  };
+
+  const saveQuickAddShortcut = async () => {
+    try {
+      setQuickAddError(null);
+      await updateSettings({ quick_add_shortcut: quickAddShortcut.trim() });
+    } catch (error) {
+      console.error("Failed to update quick-add shortcut:", error);
+      setQuickAddError(errorMessage(error));
+      loadSettings();
+    }
+  };
+
+  const saveCompactShortcut = async () => {
+    try {
+      setCompactShortcutError(null);
+      await updateSettings({ compact_mode_shortcut: compactShortcut.trim() });
+    } catch (error) {
+      console.error("Failed to update compact mode shortcut:", error);
+      setCompactShortcutError(errorMessage(error));
+      loadSettings();
+    }
+  };
+
+  const updateDnd = async (next: DndSettings) => {
+    try {
+      await updateSettings({ dnd: next });
+      setDndState(await invoke<DndState>("get_dnd_state"));
+    } catch (error) {
+      console.error("Failed to update do-not-disturb settings:", error);
+    }
+  };
+
+  const saveDndApps = () => {
+    const apps = dndApps.split(",").map(app => app.trim()).filter(app => app.length > 0);
+    updateDnd({ ...dnd, fullscreen_apps: apps });
+  };
+
+  const updateMarkdownSync = async (next: MarkdownSyncSettings) => {
+    // Wait for a path before turning the sync on
+    if (next.enabled && next.path.trim() === "") {
+      setMarkdownSync(next);
+      return;
+    }
+    try {
+      setMarkdownError(null);
+      await updateSettings({ markdown_sync: next });
+    } catch (error) {
+      console.error("Failed to update Markdown sync:", error);
+      setMarkdownError(errorMessage(error));
+      loadSettings();
+    }
+  };
+
+  const updateCalendarExport = async (next: CalendarExportSettings) => {
+    // Wait for a path before turning the export on
+    if (next.enabled && next.path.trim() === "") {
+      setCalendarExport(next);
+      return;
+    }
+    try {
+      setCalendarError(null);
+      await updateSettings({ calendar_export: next });
+    } catch (error) {
+      console.error("Failed to update the calendar export:", error);
+      setCalendarError(errorMessage(error));
+      loadSettings();
+    }
+  };
+
+  const updateGoogleCalendar = async (next: GoogleCalendarSettings) => {
+    // Turning the push on waits for Connect
+    if (next.enabled && next.refresh_token === "") {
+      setGoogleCalendar(next);
+      return;
+    }
+    try {
+      setGoogleError(null);
+      await updateSettings({ google_calendar: next });
+    } catch (error) {
+      console.error("Failed to update the Google Calendar push:", error);
+      setGoogleError(errorMessage(error));
+      loadSettings();
+    }
+  };
+
+  const connectGoogleCalendar = async () => {
+    // The backend sends the consent page's URL and waits for the browser to come back
+    const unlisten = await listen<string>("google-sign-in", (event) => openUrl(event.payload));
+    try {
+      setGoogleError(null);
+      setGoogleConnecting(true);
+      // Connect saves the typed client along with the token
+      applySettings(await invoke<Settings>("connect_google_calendar", {
+        clientId: googleCalendar.client_id,
+        clientSecret: googleCalendar.client_secret,
+      }));
+    } catch (error) {
+      console.error("Failed to connect Google Calendar:", error);
+      setGoogleError(errorMessage(error));
+    } finally {
+      unlisten();
+      setGoogleConnecting(false);
+    }
+  };
+
+  const updateTodoist = async (next: TodoistSettings) => {
+    // Wait for a token before turning the sync on
+    if (next.enabled && next.token.trim() === "") {
+      setTodoist(next);
+      return;
+    }
+    try {
+      setTodoistError(null);
+      await updateSettings({ todoist: next });
+    } catch (error) {
+      console.error("Failed to update Todoist sync:", error);
+      setTodoistError(errorMessage(error));
+      loadSettings();
+    }
+  };
+
+  const updateOpenai = async (next: OpenAiSettings) => {
+    // Wait for an API key before turning OpenAI on
+    if (next.enabled && next.api_key.trim() === "") {
+      setOpenai(next);
+      return;
+    }
+    try {
+      setOpenaiError(null);
+      await updateSettings({ openai: next });
+    } catch (error) {
+      console.error("Failed to update OpenAI parsing:", error);
+      setOpenaiError(errorMessage(error));
+      loadSettings();
+    }
+  };
+
+  const updateGithub = async (next: GithubSettings) => {
+    // Wait for a token and repository before turning capture on
+    if (next.enabled && (next.token.trim() === "" || next.repo.trim() === "")) {
+      setGithub(next);
+      return;
+    }
+    try {
+      setGithubError(null);
+      await updateSettings({ github: next });
+    } catch (error) {
+      console.error("Failed to update GitHub capture:", error);
+      setGithubError(errorMessage(error));
+      loadSettings();
+    }
+  };
+
+  const updateMqtt = async (next: MqttSettings) => {
+    try {
+      setMqttError(null);
+      await updateSettings({ mqtt: next });
+    } catch (error) {
+      console.error("Failed to update MQTT publishing:", error);
+      setMqttError(errorMessage(error));
+      loadSettings();
+    }
+  };
+
+  const updateReport = async (next: ReportSettings) => {
+    try {
+      setReportStatus(null);
+      await updateSettings({ report: next });
+    } catch (error) {
+      console.error("Failed to update report settings:", error);
+      setReportStatus(errorMessage(error));
+      loadSettings();
+    }
+  };
+
+  const chooseReportFolder = async () => {
+    const folder = await open({ directory: true, multiple: false });
+    if (!folder || Array.isArray(folder)) return;
+    setReportFolder(folder);
+    await updateReport({ ...report, folder });
+  };
+
+  const generateReport = async (weeksAgo: number) => {
+    try {
+      const written = await invoke<WeeklyReport>("generate_weekly_report", { weeksAgo });
+      setReportStatus(`Saved to ${written.pdf_path ?? written.markdown_path}`);
+    } catch (error) {
+      setReportStatus(errorMessage(error));
+    }
+  };
+
+  const updateRollover = async (next: RolloverSettings) => {
+    setRollover(next);
+    try {
+      setRolloverError(null);
+      await updateSettings({ rollover: next });
+    } catch (error) {
+      console.error("Failed to update rollover settings:", error);
+      setRolloverError(errorMessage(error));
+      loadSettings();
+    }
+  };
+
+  const updateWorkload = async (next: WorkloadSettings) => {
+    setWorkload(next);
+    try {
+      setWorkloadError(null);
+      await updateSettings({ workload: next });
+    } catch (error) {
+      console.error("Failed to update workload settings:", error);
+      setWorkloadError(errorMessage(error));
+      loadSettings();
+    }
+  };
+
+  const updateSpeakerGate = async (next: SpeakerGateSettings) => {
+    setSpeakerGate(next);
+    try {
+      setSpeakerStatus(null);
+      await updateSettings({ speaker_gate: next });
+    } catch (error) {
+      console.error("Failed to update the speaker gate:", error);
+      setSpeakerStatus(errorMessage(error));
+      loadSettings();
+    }
+  };
+
+  const updateContentFilter = async (next: ContentFilterSettings) => {
+    setContentFilter(next);
+    try {
+      await updateSettings({ content_filter: next });
+    } catch (error) {
+      console.error("Failed to update the content filter:", error);
+      loadSettings();
+    }
+  };
+
+  const saveFilterWords = () => {
+    const words = filterWords.split(",").map(word => word.trim()).filter(word => word.length > 0);
+    updateContentFilter({ ...contentFilter, words });
+  };
+
+  // First click starts the sample, the second stops and enrolls it
+  const toggleEnrollment = async () => {
+    if (!enrollRecorder.state.isRecording) {
+      setSpeakerStatus("Recording... read a sentence or two aloud, then click Stop.");
       try {
-        await invoke("set_timer_duration", { minutes });
-        // Reset timer with new duration
-        await invoke("reset_timer");
+        await enrollRecorder.startRecording();
       } catch (error) {
-        console.error("Failed to set timer duration:", error);
+        setSpeakerStatus(errorMessage(error));
       }
-    }, 500); // Wait 500ms after user stops sliding
+      return;
+    }
+    try {
+      const blob = await enrollRecorder.stopRecording();
+      const audioData = Array.from(new Uint8Array(await blob.arrayBuffer()));
+      const profile = await invoke<VoiceProfile>("enroll_speaker", { audioData });
+      setSpeakerStatus(`Enrolled: your voice sits around ${Math.round(profile.pitch_hz)} Hz.`);
+    } catch (error) {
+      console.error("Failed to enroll the speaker:", error);
+      setSpeakerStatus(errorMessage(error));
+    }
   };
 
-  const checkAutoStartStatus = async () => {
+  const updateModelUnload = async (minutes: number) => {
+    setModelUnloadMinutes(minutes);
     try {
-      const enabled = await invoke<boolean>("get_autostart_enabled");
-      setAutoStartEnabled(enabled);
+      setModelUnloadStatus(null);
+      await updateSettings({ model_unload_minutes: minutes });
     } catch (error) {
-      console.error("Failed to check auto-start status:", error);
+      console.error("Failed to update model unload settings:", error);
+      setModelUnloadStatus(errorMessage(error));
+      loadSettings();
     }
   };
 
-  const toggleAutoStart = async () => {
-    setAutoStartLoading(true);
+  const updateTranscriptionLanguage = async (language: string) => {
     try {
-      await invoke("set_autostart_enabled", { enabled: !autoStartEnabled });
-      setAutoStartEnabled(!autoStartEnabled);
+      setTranscriptionLanguageError(null);
+      await updateSettings({ transcription_language: language.trim().toLowerCase() });
     } catch (error) {
-      console.error("Failed to toggle auto-start:", error);
+      console.error("Failed to update the transcription language:", error);
+      setTranscriptionLanguageError(errorMessage(error));
+      loadSettings();
     }
-    setAutoStartLoading(false);
   };
 
-  const checkOllamaEnabled = async () => {
+  const unloadModel = async () => {
     try {
-      const enabled = await invoke<boolean>("get_ollama_enabled");
-      setOllamaEnabled(enabled);
+      await invoke("unload_whisper_model");
+      setModelUnloadStatus("Unloaded; the next recording loads it again");
     } catch (error) {
-      console.error("Failed to check Ollama enabled status:", error);
+      setModelUnloadStatus(errorMessage(error));
     }
   };
 
-  const toggleOllamaEnabled = async () => {
-    setOllamaEnabledLoading(true);
+  const updateProfiles = async (next: ProfileSettings) => {
+    setProfiles(next);
     try {
-      await invoke("set_ollama_enabled", { enabled: !ollamaEnabled });
-      setOllamaEnabled(!ollamaEnabled);
+      await invoke("update_settings", { patch: { profiles: next } });
+      setProfileError(null);
     } catch (error) {
-      console.error("Failed to toggle Ollama enabled:", error);
+      setProfileError(errorMessage(error));
     }
-    setOllamaEnabledLoading(false);
   };
 
-  const checkOllamaStatus = async () => {
+  const updateProfile = (index: number, changes: Partial<Profile>) => {
+    const list = profiles.profiles.map((profile, i) => (i === index ? { ...profile, ...changes } : profile));
+    updateProfiles({ ...profiles, profiles: list });
+  };
+
+  const addProfile = () => {
+    const name = newProfileName.trim();
+    if (!name) return;
+    const profile: Profile = { name, projects: [], tags: [], language: null, prompt: "", schedule: null };
+    setNewProfileName("");
+    updateProfiles({ ...profiles, profiles: [...profiles.profiles, profile] });
+  };
+
+  const deleteProfile = (index: number) => {
+    const removed = profiles.profiles[index];
+    const active = profiles.active?.toLowerCase() === removed.name.toLowerCase() ? null : profiles.active;
+    updateProfiles({ active, profiles: profiles.profiles.filter((_, i) => i !== index) });
+  };
+
+  const splitList = (text: string) => text.split(",").map((word) => word.trim()).filter(Boolean);
+
+  const loadHabits = async () => {
     try {
-      // Try to connect to Ollama
-      const response = await fetch("http://localhost:11434/api/tags");
-      if (response.ok) {
-        setOllamaStatus("connected");
-        const data = await response.json();
-        if (data.models && data.models.length > 0) {
-          setOllamaModel(data.models[0].name);
-        }
-      } else {
-        setOllamaStatus("disconnected");
-      }
+      setHabits(await invoke<HabitStatus[]>("get_habits"));
     } catch (error) {
-      setOllamaStatus("disconnected");
+      console.error("Failed to load habits:", error);
     }
   };
 
-  if (!isOpen) return null;
+  const addHabit = async () => {
+    if (!newHabitName.trim()) return;
+    try {
+      setHabitError(null);
+      await invoke("add_habit", { name: newHabitName, schedule: newHabitSchedule });
+      setNewHabitName("");
+    } catch (error) {
+      setHabitError(errorMessage(error));
+    }
+    loadHabits();
+  };
+
+  const deleteHabit = async (id: number) => {
+    try {
+      setHabitError(null);
+      await invoke("delete_habit", { id });
+    } catch (error) {
+      setHabitError(errorMessage(error));
+    }
+    loadHabits();
+  };
+
+  const loadApiTokens = async () => {
+    try {
+      setApiTokens(await invoke<ApiToken[]>("get_api_tokens"));
+    } catch (error) {
+      console.error("Failed to load API tokens:", error);
+    }
+  };
+
+  const toggleTokenScope = (scope: TokenScope) => {
+    setTokenScopes(tokenScopes.includes(scope) ? tokenScopes.filter((s) => s !== scope) : [...tokenScopes, scope]);
+  };
+
+  const issueApiToken = async () => {
+    try {
+      setTokenError(null);
+      const issued = await invoke<{ token: ApiToken; secret: string }>("issue_api_token", { name: tokenName, scopes: tokenScopes });
+      setNewSecret(issued.secret);
+      setTokenName("");
+    } catch (error) {
+      setNewSecret(null);
+      setTokenError(errorMessage(error));
+    }
+    loadApiTokens();
+  };
+
+  const revokeApiToken = async (id: number) => {
+    try {
+      setTokenError(null);
+      setNewSecret(null);
+      await invoke("revoke_api_token", { id });
+    } catch (error) {
+      setTokenError(errorMessage(error));
+    }
+    loadApiTokens();
+  };
+
+  const loadMacros = async () => {
+    try {
+      setMacros(await invoke<VoiceMacro[]>("get_macros"));
+    } catch (error) {
+      console.error("Failed to load macros:", error);
+    }
+  };
+
+  const resetMacroForm = () => {
+    setMacroPhrase("");
+    setMacroTasks("");
+    setMacroArchive(false);
+    setMacroTimer("");
+    setEditingMacro(null);
+  };
+
+  const editMacro = (voiceMacro: VoiceMacro) => {
+    const timer = voiceMacro.actions.find((action) => action.type === "timer");
+    setMacroPhrase(voiceMacro.phrase);
+    setMacroTasks(voiceMacro.actions.flatMap((action) => (action.type === "add_task" ? [action.text] : [])).join("\n"));
+    setMacroArchive(voiceMacro.actions.some((action) => action.type === "archive_completed"));
+    setMacroTimer(timer?.type === "timer" && typeof timer.command === "string" ? timer.command : "");
+    setEditingMacro(voiceMacro.id);
+  };
+
+  const saveMacro = async () => {
+    const actions: MacroAction[] = [];
+    if (macroArchive) actions.push({ type: "archive_completed" });
+    if (macroTimer) actions.push({ type: "timer", command: macroTimer as "reset" | "pause" | "resume" });
+    for (const line of macroTasks.split("\n")) {
+      if (line.trim()) actions.push({ type: "add_task", text: line.trim() });
+    }
+    try {
+      setMacroError(null);
+      if (editingMacro === null) {
+        await invoke("add_macro", { phrase: macroPhrase, actions });
+      } else {
+        await invoke("update_macro", { id: editingMacro, phrase: macroPhrase, actions });
+      }
+      resetMacroForm();
+    } catch (error) {
+      setMacroError(errorMessage(error));
+    }
+    loadMacros();
+  };
+
+  const deleteMacro = async (id: number) => {
+    try {
+      setMacroError(null);
+      await invoke("delete_macro", { id });
+      if (editingMacro === id) resetMacroForm();
+    } catch (error) {
+      setMacroError(errorMessage(error));
+    }
+    loadMacros();
+  };
+
+  const checkReparse = async () => {
+    setReparseRunning(true);
+    try {
+      setReparseStatus(null);
+      const range = { from: reparseFrom || null, to: reparseTo || null };
+      const diffs = await invoke<ReparseDiff[]>("reparse_history", { range });
+      setReparseDiffs(diffs);
+      setReparseSelected(diffs.map((diff) => diff.transcription_id));
+      if (diffs.length === 0) setReparseStatus("The current parser reads every note the same way");
+    } catch (error) {
+      setReparseStatus(errorMessage(error));
+    }
+    setReparseRunning(false);
+  };
+
+  const toggleReparse = (id: number) => {
+    setReparseSelected((selected) =>
+      selected.includes(id) ? selected.filter((other) => other !== id) : [...selected, id]);
+  };
+
+  const applyReparse = async () => {
+    setReparseRunning(true);
+    try {
+      const tasks = await invoke<unknown[]>("apply_reparse", { ids: reparseSelected });
+      setReparseDiffs(null);
+      setReparseSelected([]);
+      setReparseStatus(`Applied to ${reparseSelected.length} note(s), ${tasks.length} task(s) added or completed`);
+    } catch (error) {
+      setReparseStatus(errorMessage(error));
+    }
+    setReparseRunning(false);
+  };
+
+  const updateClipboard = async (next: ClipboardSettings) => {
+    try {
+      setClipboardError(null);
+      await updateSettings({ clipboard: next });
+    } catch (error) {
+      console.error("Failed to update clipboard capture:", error);
+      setClipboardError(errorMessage(error));
+      loadSettings();
+    }
+  };
+
+  const updateTelegram = async (next: TelegramSettings) => {
+    // Wait for a token and username before starting the bridge
+    if (next.enabled && (next.token.trim() === "" || next.username.trim() === "")) {
+      setTelegram(next);
+      return;
+    }
+    try {
+      setTelegramError(null);
+      await updateSettings({ telegram: next });
+    } catch (error) {
+      console.error("Failed to update Telegram bridge:", error);
+      setTelegramError(errorMessage(error));
+      loadSettings();
+    }
+  };
+
+  const toggleWakeWordEnabled = async () => {
+    try {
+      setWakeWordError(null);
+      await updateSettings({ wake_word_enabled: !wakeWordEnabled });
+    } catch (error) {
+      console.error("Failed to toggle hands-free mode:", error);
+      setWakeWordError(errorMessage(error));
+    }
+  };
+
+  const toggleEarconsMuted = async () => {
+    try {
+      await updateSettings({ earcons_muted: !earconsMuted });
+    } catch (error) {
+      console.error("Failed to toggle sound feedback:", error);
+    }
+  };
+
+  const handleAlertModeChange = async (mode: string) => {
+    try {
+      await updateSettings({ alert_mode: mode });
+    } catch (error) {
+      console.error("Failed to set alert mode:", error);
+    }
+  };
+
+  const handleAlertSoundChange = async (value: string) => {
+    let sound = value;
+    if (value === "custom") {
+      const filePath = await open({
+        multiple: false,
+        filters: [{ name: "Sound", extensions: ["wav", "mp3", "ogg"] }],
+      });
+      if (!filePath || Array.isArray(filePath)) return;
+      sound = `file:${filePath}`;
+    }
+    try {
+      await updateSettings({ alert_sound: sound });
+      await invoke("preview_alert_sound", { sound });
+    } catch (error) {
+      console.error("Failed to set alert sound:", error);
+    }
+  };
+
+  const toggleAutoPauseEnabled = async () => {
+    try {
+      await updateSettings({ auto_pause_enabled: !autoPauseEnabled });
+    } catch (error) {
+      console.error("Failed to toggle auto-pause:", error);
+    }
+  };
+
+  const toggleJournalPromptEnabled = async () => {
+    try {
+      await updateSettings({ journal_prompt_enabled: !journalPromptEnabled });
+    } catch (error) {
+      console.error("Failed to toggle journal prompt:", error);
+    }
+  };
+
+  const toggleSpeakTasksOnAlert = async () => {
+    try {
+      await updateSettings({ speak_tasks_on_alert: !speakTasksOnAlert });
+    } catch (error) {
+      console.error("Failed to toggle read-aloud:", error);
+    }
+  };
+
+  const toggleVoiceInbox = async () => {
+    try {
+      await updateSettings({ voice_inbox: !voiceInbox });
+    } catch (error) {
+      console.error("Failed to toggle the voice inbox:", error);
+    }
+  };
+
+  const toggleActionMode = async () => {
+    try {
+      await updateSettings({ action_mode: !actionMode });
+    } catch (error) {
+      console.error("Failed to toggle action mode:", error);
+    }
+  };
+
+  const toggleFocusMonitorEnabled = async () => {
+    try {
+      await updateSettings({ focus_monitor_enabled: !focusMonitorEnabled });
+    } catch (error) {
+      console.error("Failed to toggle focus monitor:", error);
+    }
+  };
+
+  const updateBreakSettings = async (next: BreakSettings) => {
+    setBreakSettings(next);
+    try {
+      await updateSettings({ break_mode: next });
+    } catch (error) {
+      console.error("Failed to save break settings:", error);
+    }
+  };
+
+  const updateEscalation = async (next: EscalationSettings) => {
+    setEscalation(next);
+    try {
+      await updateSettings({ escalation: next });
+    } catch (error) {
+      console.error("Failed to save escalation settings:", error);
+    }
+  };
+
+  const loadFocusHistory = async () => {
+    try {
+      const history = await invoke<FocusDay[]>("get_focus_history", { range: "week" });
+      setFocusHistory(history);
+    } catch (error) {
+      console.error("Failed to load focus history:", error);
+    }
+  };
+
+  const updateSchedule = async (next: TimerSchedule) => {
+    setSchedule(next);
+    try {
+      // Keep the edited (possibly invalid) times on screen rather than the stored ones
+      await invoke("update_settings", { patch: { schedule: next } });
+      setScheduleError(null);
+    } catch (error) {
+      setScheduleError(errorMessage(error));
+    }
+  };
+
+  const toggleScheduleDay = (day: number) => {
+    const days = schedule.days.includes(day)
+      ? schedule.days.filter(d => d !== day)
+      : [...schedule.days, day].sort();
+    updateSchedule({ ...schedule, days });
+  };
+
+  const handleTimerDurationChange = (minutes: number) => {
+    // Update local state immediately for smooth slider movement
+    setTimerDuration(minutes);
+    
+    // Clear any pending save operation
+    if (timerSaveTimeoutRef.current) {
+      clearTimeout(timerSaveTimeoutRef.current);
+    }
+    
+    // Debounce the save operation - only save after user stops sliding
+    // Don't set loading state - keep slider enabled for smooth interaction
+    timerSaveTimeoutRef.current = setTimeout(async () => {
+      try {
+        // Backend restarts the interval when the duration changes
+        await updateSettings({ timer_duration_minutes: minutes });
+      } catch (error) {
+        console.error("Failed to set timer duration:", error);
+      }
+    }, 500); // Wait 500ms after user stops sliding
+  };
+
+  const copyDiagnostics = async () => {
+    try {
+      const report = await invoke<string>("get_recent_logs", { maxLines: 500 });
+      await navigator.clipboard.writeText(report);
+      setDiagnosticsCopied(true);
+      setTimeout(() => setDiagnosticsCopied(false), 2000);
+    } catch (error) {
+      console.error("Failed to copy diagnostics:", error);
+    }
+  };
+
+  const runDiagnostics = async () => {
+    setDiagnosticsRunning(true);
+    try {
+      setDiagnostics(await invoke<DiagnosticsReport>("run_diagnostics"));
+    } catch (error) {
+      console.error("Failed to run diagnostics:", error);
+    } finally {
+      setDiagnosticsRunning(false);
+    }
+  };
+
+  const checkAutoStartStatus = async () => {
+    try {
+      const enabled = await invoke<boolean>("get_autostart_enabled");
+      setAutoStartEnabled(enabled);
+    } catch (error) {
+      console.error("Failed to check auto-start status:", error);
+    }
+  };
+
+  const toggleAutoStart = async () => {
+    setAutoStartLoading(true);
+    try {
+      await invoke("set_autostart_enabled", { enabled: !autoStartEnabled });
+      setAutoStartEnabled(!autoStartEnabled);
+    } catch (error) {
+      console.error("Failed to toggle auto-start:", error);
+    }
+    setAutoStartLoading(false);
+  };
+
+  const toggleOllamaEnabled = async () => {
+    setOllamaEnabledLoading(true);
+    try {
+      await updateSettings({ ollama_enabled: !ollamaEnabled });
+    } catch (error) {
+      console.error("Failed to toggle Ollama enabled:", error);
+    }
+    setOllamaEnabledLoading(false);
+  };
+
+  const checkOllamaStatus = async () => {
+    try {
+      // Try to connect to Ollama
+      const response = await fetch("http://localhost:11434/api/tags");
+      if (response.ok) {
+        setOllamaStatus("connected");
+        const data = await response.json();
+        if (data.models && data.models.length > 0) {
+          setOllamaModel(data.models[0].name);
+        }
+      } else {
+        setOllamaStatus("disconnected");
+      }
+    } catch (error) {
+      setOllamaStatus("disconnected");
+    }
+  };
+
+  if (!isOpen) return null;
+
+  return (
+    <div className="settings-modal-overlay" onClick={onClose}>
+      <div className="settings-modal" onClick={(e) => e.stopPropagation()}>
+        <div className="settings-header">
+          <h2>Settings</h2>
+          <button className="close-button" onClick={onClose}>×</button>
+        </div>
+
+        <div className="settings-content">
+          <div className="settings-section">
+            <h3>Microphone</h3>
+            <div className="microphone-settings">
+              <div className="device-select-container">
+                <select
+                  className="device-select"
+                  value={selectedDeviceId || ''}
+                  onChange={(e) => onDeviceChange?.(e.target.value)}
+                >
+                  {availableDevices.length === 0 ? (
+                    <option value="">No microphones found</option>
+                  ) : (
+                    availableDevices.map((device) => (
+                      <option key={device.deviceId} value={device.deviceId}>
+                        {device.label}
+                      </option>
+                    ))
+                  )}
+                </select>
+                <button 
+                  className="refresh-devices-button"
+                  onClick={onRefreshDevices}
+                  title="Refresh device list"
+                >
+                  🔄
+                </button>
+              </div>
+              {availableDevices.length === 0 && (
+                <p className="status-detail">
+                  No microphones detected. Please connect a microphone and click refresh.
+                </p>
+              )}
+            </div>
+          </div>
+
+          <div className="settings-section">
+            <h3>Timer</h3>
+            <div className="timer-setting">
+              <label className="slider-label">
+                <span className="slider-text">Awareness Timer Duration</span>
+                <span className="slider-value">{timerDuration} min</span>
+              </label>
+              <input
+                type="range"
+                min="0"
+                max="60"
+                value={timerDuration}
+                onInput={(e) => {
+                  const value = parseInt((e.target as HTMLInputElement).value);
+                  setTimerDuration(value);
+                }}
+                onChange={(e) => handleTimerDurationChange(parseInt(e.target.value))}
+                className="timer-slider"
+              />
+              <p className="status-detail">
+                Set the timer duration (0-60 minutes). Timer alerts you when it expires.
+              </p>
+              <label className="toggle-label">
+                <input
+                  type="checkbox"
+                  checked={autoPauseEnabled}
+                  onChange={toggleAutoPauseEnabled}
+                />
+                <span className="toggle-text">
+                  Pause when locked or idle
+                </span>
+              </label>
+              <label className="toggle-label">
+                <input
+                  type="checkbox"
+                  checked={breakSettings.enabled}
+                  onChange={() => updateBreakSettings({ ...breakSettings, enabled: !breakSettings.enabled })}
+                />
+                <span className="toggle-text">
+                  Hard break: cover the screen when the timer expires
+                </span>
+              </label>
+              {breakSettings.enabled && (
+                <>
+                  <label className="slider-label">
+                    <span className="slider-text">Break length</span>
+                    <span className="slider-value">{breakSettings.minutes} min</span>
+                  </label>
+                  <input
+                    type="range"
+                    min="1"
+                    max="30"
+                    value={breakSettings.minutes}
+                    onChange={(e) => updateBreakSettings({ ...breakSettings, minutes: parseInt(e.target.value) })}
+                    className="timer-slider"
+                  />
+                </>
+              )}
+              <label className="toggle-label">
+                <input
+                  type="checkbox"
+                  checked={escalation.enabled}
+                  onChange={() => updateEscalation({ ...escalation, enabled: !escalation.enabled })}
+                />
+                <span className="toggle-text">
+                  Escalate ignored alerts
+                </span>
+              </label>
+              {escalation.enabled && (
+                <>
+                  <label className="slider-label">
+                    <span className="slider-text">Repeat after</span>
+                    <span className="slider-value">{escalation.after_minutes} min</span>
+                  </label>
+                  <input
+                    type="range"
+                    min="1"
+                    max="15"
+                    value={escalation.after_minutes}
+                    onChange={(e) => updateEscalation({ ...escalation, after_minutes: parseInt(e.target.value) })}
+                    className="timer-slider"
+                  />
+                  <label className="toggle-label">
+                    <input
+                      type="checkbox"
+                      checked={escalation.flash_taskbar}
+                      onChange={() => updateEscalation({ ...escalation, flash_taskbar: !escalation.flash_taskbar })}
+                    />
+                    <span className="toggle-text">
+                      Flash the taskbar icon
+                    </span>
+                  </label>
+                </>
+              )}
+              <label className="toggle-label">
+                <input
+                  type="checkbox"
+                  checked={journalPromptEnabled}
+                  onChange={toggleJournalPromptEnabled}
+                />
+                <span className="toggle-text">
+                  Ask "what did you just do?" when the timer expires
+                </span>
+              </label>
+              <label className="toggle-label">
+                <input
+                  type="checkbox"
+                  checked={speakTasksOnAlert}
+                  onChange={toggleSpeakTasksOnAlert}
+                />
+                <span className="toggle-text">
+                  Read open tasks aloud when the timer expires (unless the journal prompt is on)
+                </span>
+                <button
+                  className="refresh-devices-button"
+                  onClick={() => invoke("speak_tasks")}
+                  title="Read the open tasks now"
+                >
+                  ▶
+                </button>
+              </label>
+              <label className="toggle-label" title="They stay out of the list until you keep (✔), schedule (📅) or discard (×) them">
+                <input
+                  type="checkbox"
+                  checked={voiceInbox}
+                  onChange={toggleVoiceInbox}
+                />
+                <span className="toggle-text">
+                  Put tasks captured by voice in the inbox first
+                </span>
+              </label>
+              <label className="toggle-label" title="Say &quot;command: pause the timer&quot; or &quot;command: complete buy milk&quot;. A recording without the prefix is added as a single task, exactly as said.">
+                <input
+                  type="checkbox"
+                  checked={actionMode}
+                  onChange={toggleActionMode}
+                />
+                <span className="toggle-text">
+                  Only run commands after "command:"
+                </span>
+              </label>
+              <label className="toggle-label" title="Samples which app is in front every few seconds and saves the switch count and top apps with the session. Window titles are not stored.">
+                <input
+                  type="checkbox"
+                  checked={focusMonitorEnabled}
+                  onChange={toggleFocusMonitorEnabled}
+                />
+                <span className="toggle-text">
+                  Track app switches during focus sessions (Windows)
+                </span>
+              </label>
+              <label className="slider-label">
+                <span className="slider-text">When the timer expires</span>
+              </label>
+              <select
+                className="device-select"
+                value={alertMode}
+                onChange={(e) => handleAlertModeChange(e.target.value)}
+              >
+                <option value="notification">Show a notification</option>
+                <option value="focus">Bring window to front</option>
+                <option value="both">Both</option>
+              </select>
+              <label className="slider-label">
+                <span className="slider-text">Alert sound</span>
+              </label>
+              <div className="device-select-container">
+                <select
+                  className="device-select"
+                  value={alertSound}
+                  onChange={(e) => handleAlertSoundChange(e.target.value)}
+                >
+                  <option value="none">None</option>
+                  <option value="chime">Chime</option>
+                  <option value="bell">Bell</option>
+                  <option value="digital">Digital</option>
+                  {alertSound.startsWith("file:") && (
+                    <option value={alertSound}>
+                      {alertSound.slice(5).split(/[\\/]/).pop()}
+                    </option>
+                  )}
+                  <option value="custom">Custom file...</option>
+                </select>
+                <button
+                  className="refresh-devices-button"
+                  onClick={() => invoke("preview_alert_sound", { sound: alertSound })}
+                  title="Preview sound"
+                >
+                  ▶
+                </button>
+              </div>
+            </div>
+          </div>
+
+          <div className="settings-section">
+            <h3>Working hours</h3>
+            <div className="autostart-setting">
+              <label className="toggle-label">
+                <input
+                  type="checkbox"
+                  checked={schedule.enabled}
+                  onChange={() => updateSchedule({ ...schedule, enabled: !schedule.enabled })}
+                />
+                <span className="toggle-text">
+                  Only run the timer during working hours
+                </span>
+              </label>
+              {schedule.enabled && (
+                <>
+                  <div className="schedule-days">
+                    {WEEKDAYS.map((name, day) => (
+                      <label key={name} className="toggle-label">
+                        <input
+                          type="checkbox"
+                          checked={schedule.days.includes(day)}
+                          onChange={() => toggleScheduleDay(day)}
+                        />
+                        <span className="toggle-text">{name}</span>
+                      </label>
+                    ))}
+                  </div>
+                  <div className="schedule-hours">
+                    <input
+                      type="time"
+                      value={schedule.start}
+                      onChange={(e) => updateSchedule({ ...schedule, start: e.target.value })}
+                    />
+                    <span className="toggle-text">to</span>
+                    <input
+                      type="time"
+                      value={schedule.end}
+                      onChange={(e) => updateSchedule({ ...schedule, end: e.target.value })}
+                    />
+                  </div>
+                </>
+              )}
+              <p className="status-detail">
+                {scheduleError ?? "Outside these hours the timer pauses and starts fresh the next working day."}
+              </p>
+            </div>
+          </div>
+
+          <div className="settings-section">
+            <h3>Do not disturb</h3>
+            <div className="autostart-setting">
+              <label className="toggle-label">
+                <input
+                  type="checkbox"
+                  checked={dnd.respect_system_dnd}
+                  onChange={() => updateDnd({ ...dnd, respect_system_dnd: !dnd.respect_system_dnd })}
+                />
+                <span className="toggle-text">
+                  Stay quiet while the system is in do-not-disturb or Focus Assist
+                </span>
+              </label>
+              <label className="slider-label">
+                <span className="slider-text">Also while these apps are full screen</span>
+              </label>
+              <input
+                className="shortcut-input"
+                value={dndApps}
+                placeholder="zoom, obs64"
+                onChange={(e) => setDndApps(e.target.value)}
+                onBlur={saveDndApps}
+                onKeyDown={(e) => e.key === "Enter" && e.currentTarget.blur()}
+              />
+              <p className="status-detail">
+                {dndState?.reason === "system" && "Alerts are paused right now (do-not-disturb is on)"}
+                {dndState?.reason === "fullscreen_app" && `Alerts are paused right now (${dndState.app} is full screen)`}
+                {(!dndState || dndState.reason === "off") && "Skipped alerts are not repeated; the timer just starts the next interval."}
+              </p>
+            </div>
+          </div>
+
+          <div className="settings-section">
+            <h3>Markdown note</h3>
+            <div className="autostart-setting">
+              <label className="toggle-label">
+                <input
+                  type="checkbox"
+                  checked={markdownSync.enabled}
+                  onChange={() => updateMarkdownSync({ ...markdownSync, enabled: !markdownSync.enabled })}
+                />
+                <span className="toggle-text">Sync tasks to a Markdown note (e.g. in an Obsidian vault)</span>
+              </label>
+              {markdownSync.enabled && (
+                <>
+                  <input
+                    className="shortcut-input"
+                    value={markdownPath}
+                    placeholder={"C:\\Vault\\FlowState.md"}
+                    onChange={(e) => setMarkdownPath(e.target.value)}
+                    onBlur={() => updateMarkdownSync({ ...markdownSync, path: markdownPath.trim() })}
+                    onKeyDown={(e) => e.key === "Enter" && e.currentTarget.blur()}
+                  />
+                  <label className="toggle-label">
+                    <input
+                      type="checkbox"
+                      checked={markdownSync.two_way}
+                      onChange={() => updateMarkdownSync({ ...markdownSync, two_way: !markdownSync.two_way })}
+                    />
+                    <span className="toggle-text">Import ticks and new lines from the note</span>
+                  </label>
+                </>
+              )}
+              <p className="status-detail">
+                {markdownError ?? "FlowState owns this note: use a file of its own, since other edits are overwritten."}
+              </p>
+            </div>
+          </div>
+
+          <div className="settings-section">
+            <h3>Calendar</h3>
+            <div className="autostart-setting">
+              <label className="toggle-label">
+                <input
+                  type="checkbox"
+                  checked={calendarExport.enabled}
+                  onChange={() => updateCalendarExport({ ...calendarExport, enabled: !calendarExport.enabled })}
+                />
+                <span className="toggle-text">Export dated tasks to a calendar file</span>
+              </label>
+              {calendarExport.enabled && (
+                <input
+                  className="shortcut-input"
+                  value={calendarPath}
+                  placeholder={"C:\\Calendars\\FlowState.ics"}
+                  onChange={(e) => setCalendarPath(e.target.value)}
+                  onBlur={() => updateCalendarExport({ ...calendarExport, path: calendarPath.trim() })}
+                  onKeyDown={(e) => e.key === "Enter" && e.currentTarget.blur()}
+                />
+              )}
+              <p className="status-detail">
+                {calendarError ?? "An .ics file with an event per open task that has a due date. Subscribe to it from your calendar app."}
+              </p>
+            </div>
+          </div>
+
+          <div className="settings-section">
+            <h3>Google Calendar</h3>
+            <div className="autostart-setting">
+              <label className="toggle-label">
+                <input
+                  type="checkbox"
+                  checked={googleCalendar.enabled}
+                  onChange={() => updateGoogleCalendar({ ...googleCalendar, enabled: !googleCalendar.enabled })}
+                />
+                <span className="toggle-text">Push dated tasks to Google Calendar</span>
+              </label>
+              {googleCalendar.enabled && (
+                <>
+                  <input
+                    className="shortcut-input"
+                    value={googleCalendar.client_id}
+                    placeholder="OAuth client ID"
+                    onChange={(e) => setGoogleCalendar({ ...googleCalendar, client_id: e.target.value })}
+                  />
+                  <input
+                    className="shortcut-input"
+                    type="password"
+                    value={googleCalendar.client_secret}
+                    placeholder="OAuth client secret"
+                    onChange={(e) => setGoogleCalendar({ ...googleCalendar, client_secret: e.target.value })}
+                  />
+                  <input
+                    className="shortcut-input"
+                    value={googleCalendar.calendar_id}
+                    placeholder="Calendar ID"
+                    onChange={(e) => setGoogleCalendar({ ...googleCalendar, calendar_id: e.target.value })}
+                    onBlur={() => updateGoogleCalendar({ ...googleCalendar, calendar_id: googleCalendar.calendar_id.trim() })}
+                    onKeyDown={(e) => e.key === "Enter" && e.currentTarget.blur()}
+                  />
+                  <button className="diagnostics-button" onClick={connectGoogleCalendar} disabled={googleConnecting}>
+                    {googleConnecting ? "Waiting for the browser..." : googleCalendar.refresh_token ? "Connected — connect again" : "Connect"}
+                  </button>
+                </>
+              )}
+              <p className="status-detail">
+                {googleError ?? "Uses an OAuth client of type \"Desktop app\" from the Google Cloud console. Each open task with a due date gets an event; completing or undating the task removes it."}
+              </p>
+            </div>
+          </div>
+
+          <div className="settings-section">
+            <h3>Todoist</h3>
+            <div className="autostart-setting">
+              <label className="toggle-label">
+                <input
+                  type="checkbox"
+                  checked={todoist.enabled}
+                  onChange={() => updateTodoist({ ...todoist, enabled: !todoist.enabled })}
+                />
+                <span className="toggle-text">Sync with Todoist</span>
+              </label>
+              {todoist.enabled && (
+                <>
+                  <input
+                    className="shortcut-input"
+                    type="password"
+                    value={todoistToken}
+                    placeholder="API token"
+                    onChange={(e) => setTodoistToken(e.target.value)}
+                    onBlur={() => updateTodoist({ ...todoist, token: todoistToken.trim() })}
+                    onKeyDown={(e) => e.key === "Enter" && e.currentTarget.blur()}
+                  />
+                  <label className="slider-label">
+                    <span className="slider-text">Sync every</span>
+                    <span className="slider-value">{todoist.interval_minutes} min</span>
+                  </label>
+                  {/* Saved on release: every save restarts the sync with a fresh run */}
+                  <input
+                    type="range"
+                    min="1"
+                    max="60"
+                    value={todoist.interval_minutes}
+                    onChange={(e) => setTodoist({ ...todoist, interval_minutes: parseInt(e.target.value) })}
+                    onMouseUp={() => updateTodoist(todoist)}
+                    onKeyUp={() => updateTodoist(todoist)}
+                    className="timer-slider"
+                  />
+                </>
+              )}
+              <p className="status-detail">
+                {todoistError ?? "New open tasks go to your Todoist inbox; completions and edits sync both ways. The token is under Todoist Settings → Integrations → Developer."}
+              </p>
+            </div>
+          </div>
+
+          <div className="settings-section">
+            <h3>GitHub</h3>
+            <div className="autostart-setting">
+              <label className="toggle-label">
+                <input
+                  type="checkbox"
+                  checked={github.enabled}
+                  onChange={() => updateGithub({ ...github, enabled: !github.enabled })}
+                />
+                <span className="toggle-text">Create GitHub issues by voice</span>
+              </label>
+              {github.enabled && (
+                <>
+                  <input
+                    className="shortcut-input"
+                    type="password"
+                    value={githubToken}
+                    placeholder="Token"
+                    onChange={(e) => setGithubToken(e.target.value)}
+                    onBlur={() => updateGithub({ ...github, token: githubToken.trim() })}
+                    onKeyDown={(e) => e.key === "Enter" && e.currentTarget.blur()}
+                  />
+                  <input
+                    className="shortcut-input"
+                    value={githubRepo}
+                    placeholder="owner/name"
+                    onChange={(e) => setGithubRepo(e.target.value)}
+                    onBlur={() => updateGithub({ ...github, repo: githubRepo.trim() })}
+                    onKeyDown={(e) => e.key === "Enter" && e.currentTarget.blur()}
+                  />
+                </>
+              )}
+              <p className="status-detail">
+                {githubError ?? 'Say "github: fix the login crash" to file an issue and add it as a task. The token needs the Issues write permission.'}
+              </p>
+            </div>
+          </div>
+
+          <div className="settings-section">
+            <h3>MQTT</h3>
+            <div className="autostart-setting">
+              <label className="toggle-label">
+                <input
+                  type="checkbox"
+                  checked={mqtt.enabled}
+                  onChange={() => updateMqtt({ ...mqtt, enabled: !mqtt.enabled })}
+                />
+                <span className="toggle-text">Publish state to MQTT</span>
+              </label>
+              {mqtt.enabled && (
+                <>
+                  <input
+                    className="shortcut-input"
+                    value={mqtt.host}
+                    placeholder="Broker host"
+                    onChange={(e) => setMqtt({ ...mqtt, host: e.target.value })}
+                    onBlur={() => updateMqtt(mqtt)}
+                    onKeyDown={(e) => e.key === "Enter" && e.currentTarget.blur()}
+                  />
+                  <input
+                    className="shortcut-input"
+                    type="number"
+                    min="1"
+                    max="65535"
+                    value={mqtt.port}
+                    onChange={(e) => setMqtt({ ...mqtt, port: parseInt(e.target.value) || 0 })}
+                    onBlur={() => updateMqtt(mqtt)}
+                  />
+                  <input
+                    className="shortcut-input"
+                    value={mqtt.username}
+                    placeholder="Username (optional)"
+                    onChange={(e) => setMqtt({ ...mqtt, username: e.target.value })}
+                    onBlur={() => updateMqtt(mqtt)}
+                    onKeyDown={(e) => e.key === "Enter" && e.currentTarget.blur()}
+                  />
+                  <input
+                    className="shortcut-input"
+                    type="password"
+                    value={mqtt.password}
+                    placeholder="Password"
+                    onChange={(e) => setMqtt({ ...mqtt, password: e.target.value })}
+                    onBlur={() => updateMqtt(mqtt)}
+                    onKeyDown={(e) => e.key === "Enter" && e.currentTarget.blur()}
+                  />
+                  <input
+                    className="shortcut-input"
+                    value={mqtt.topic_prefix}
+                    placeholder="flowstate"
+                    onChange={(e) => setMqtt({ ...mqtt, topic_prefix: e.target.value })}
+                    onBlur={() => updateMqtt(mqtt)}
+                    onKeyDown={(e) => e.key === "Enter" && e.currentTarget.blur()}
+                  />
+                </>
+              )}
+              <p className="status-detail">
+                {mqttError ?? `Publishes ${mqtt.topic_prefix}/recording, ${mqtt.topic_prefix}/timer, ${mqtt.topic_prefix}/timer/remaining and ${mqtt.topic_prefix}/tasks/open for Home Assistant or other dashboards.`}
+              </p>
+            </div>
+          </div>
+
+          <div className="settings-section">
+            <h3>Weekly report</h3>
+            <div className="autostart-setting">
+              <label className="toggle-label">
+                <input
+                  className="shortcut-input"
+                  value={reportFolder}
+                  placeholder="Documents\FlowState"
+                  onChange={(e) => setReportFolder(e.target.value)}
+                  onBlur={() => updateReport({ ...report, folder: reportFolder.trim() })}
+                  onKeyDown={(e) => e.key === "Enter" && e.currentTarget.blur()}
+                />
+                <button className="refresh-devices-button" onClick={chooseReportFolder} title="Choose folder">
+                  📁
+                </button>
+              </label>
+              <label className="toggle-label">
+                <input
+                  type="checkbox"
+                  checked={report.pdf}
+                  onChange={() => updateReport({ ...report, pdf: !report.pdf })}
+                />
+                <span className="toggle-text">Also save as PDF</span>
+              </label>
+              <button className="diagnostics-button" onClick={() => generateReport(0)}>
+                This week
+              </button>
+              <button className="diagnostics-button" onClick={() => generateReport(1)}>
+                Last week
+              </button>
+              <p className="status-detail">
+                {reportStatus ?? "Completed tasks, focus time, checkpoints and transcriptions for the week, as Markdown."}
+              </p>
+            </div>
+          </div>
+
+          <div className="settings-section">
+            <h3>End of day</h3>
+            <div className="autostart-setting">
+              <label className="toggle-label">
+                <input
+                  type="checkbox"
+                  checked={rollover.enabled}
+                  onChange={() => updateRollover({ ...rollover, enabled: !rollover.enabled })}
+                />
+                <span className="toggle-text">Roll over at the end of the day</span>
+              </label>
+              {rollover.enabled && (
+                <>
+                  <label className="toggle-label">
+                    <span className="toggle-text">Time</span>
+                    <input
+                      type="time"
+                      value={rollover.time}
+                      onChange={(e) => updateRollover({ ...rollover, time: e.target.value })}
+                    />
+                  </label>
+                  <label className="toggle-label">
+                    <span className="toggle-text">Archive completed tasks after (days)</span>
+                    <input
+                      className="shortcut-input"
+                      type="number"
+                      min="1"
+                      max="365"
+                      value={rollover.archive_after_days}
+                      onChange={(e) => setRollover({ ...rollover, archive_after_days: parseInt(e.target.value) || 0 })}
+                      onBlur={() => updateRollover(rollover)}
+                    />
+                  </label>
+                </>
+              )}
+              <p className="status-detail">
+                {rolloverError ?? "Moves reminders on unfinished tasks to the next day, archives old completed tasks and offers a daily review."}
+              </p>
+            </div>
+          </div>
+
+          <div className="settings-section">
+            <h3>Workload</h3>
+            <div className="autostart-setting">
+              <label className="toggle-label">
+                <span className="toggle-text">Daily capacity (minutes)</span>
+                <input
+                  className="shortcut-input"
+                  type="number"
+                  min="30"
+                  max="960"
+                  step="30"
+                  value={workload.capacity_minutes}
+                  onChange={(e) => setWorkload({ capacity_minutes: parseInt(e.target.value) || 0 })}
+                  onBlur={() => updateWorkload(workload)}
+                />
+              </label>
+              <p className="status-detail">
+                {workloadError ?? "Say or type an estimate at the end of a task (\"write blog post, about an hour\", \"~30m\"). When today's estimates add up to more than this, the list warns you."}
+              </p>
+            </div>
+          </div>
+
+          <div className="settings-section">
+            <h3>Speaker gate</h3>
+            <div className="autostart-setting">
+              <label className="toggle-label">
+                <button className="diagnostics-button" onClick={toggleEnrollment}>
+                  {enrollRecorder.state.isRecording
+                    ? `Stop (${enrollRecorder.state.recordingTime}s)`
+                    : speakerGate.profile ? "Re-enroll my voice" : "Enroll my voice"}
+                </button>
+                {speakerGate.profile && (
+                  <span className="toggle-text">{Math.round(speakerGate.profile.pitch_hz)} Hz</span>
+                )}
+              </label>
+              <label className="toggle-label">
+                <input
+                  type="checkbox"
+                  checked={speakerGate.enabled}
+                  disabled={!speakerGate.profile}
+                  onChange={() => updateSpeakerGate({ ...speakerGate, enabled: !speakerGate.enabled })}
+                />
+                <span className="toggle-text">Drop speech that doesn't sound like me</span>
+              </label>
+              <label className="toggle-label">
+                <span className="toggle-text">Tolerance (semitones)</span>
+                <input
+                  className="shortcut-input"
+                  type="number"
+                  min="1"
+                  max="12"
+                  step="0.5"
+                  value={speakerGate.tolerance_semitones}
+                  onChange={(e) => setSpeakerGate({ ...speakerGate, tolerance_semitones: parseFloat(e.target.value) || 0 })}
+                  onBlur={() => updateSpeakerGate(speakerGate)}
+                />
+              </label>
+              <p className="status-detail">
+                {speakerStatus ?? "Before a recording is transcribed, stretches of speech pitched too far from your enrolled voice are cut out, so colleagues talking nearby don't end up as tasks. It tells voices apart by pitch only: similar voices get through."}
+              </p>
+            </div>
+          </div>
+
+          <div className="settings-section">
+            <h3>Content filter</h3>
+            <div className="autostart-setting">
+              <label className="toggle-label">
+                <input
+                  type="checkbox"
+                  checked={contentFilter.enabled}
+                  onChange={() => updateContentFilter({ ...contentFilter, enabled: !contentFilter.enabled })}
+                />
+                <span className="toggle-text">Filter words out of transcripts</span>
+              </label>
+              <label className="toggle-label">
+                <span className="toggle-text">Filtered words are</span>
+                <select
+                  className="device-select"
+                  value={contentFilter.mode}
+                  disabled={!contentFilter.enabled}
+                  onChange={(e) => updateContentFilter({ ...contentFilter, mode: e.target.value as ContentFilterSettings["mode"] })}
+                >
+                  <option value="mask">Masked (****)</option>
+                  <option value="drop">Left out</option>
+                </select>
+              </label>
+              <label className="toggle-label">
+                <input
+                  type="checkbox"
+                  checked={contentFilter.profanity}
+                  disabled={!contentFilter.enabled}
+                  onChange={() => updateContentFilter({ ...contentFilter, profanity: !contentFilter.profanity })}
+                />
+                <span className="toggle-text">Include common swear words</span>
+              </label>
+              <label className="toggle-label">
+                <span className="toggle-text">Also filter</span>
+                <input
+                  className="shortcut-input"
+                  value={filterWords}
+                  disabled={!contentFilter.enabled}
+                  placeholder="Acme, project falcon"
+                  onChange={(e) => setFilterWords(e.target.value)}
+                  onBlur={saveFilterWords}
+                />
+              </label>
+              <p className="status-detail">
+                Applied right after transcription, so the words never reach your tasks, the journal or the kept transcripts. Handy when you share your screen with the task list open.
+              </p>
+            </div>
+          </div>
 
-  return (
-    <div className="settings-modal-overlay" onClick={onClose}>
-      <div className="settings-modal" onClick={(e) => e.stopPropagation()}>
-        <div className="settings-header">
-          <h2>Settings</h2>
-          <button className="close-button" onClick={onClose}>×</button>
-        </div>
+          <div className="settings-section">
+            <h3>Profiles</h3>
+            <div className="autostart-setting">
+              {profiles.profiles.map((profile, index) => (
+                <details key={index} className="profile-details">
+                  <summary className="toggle-text">{profile.name}</summary>
+                  <label className="toggle-label">
+                    <span className="toggle-text">Projects</span>
+                    <input
+                      key={profile.projects.join(",")}
+                      className="shortcut-input"
+                      defaultValue={profile.projects.join(", ")}
+                      placeholder="work, website"
+                      onBlur={(e) => updateProfile(index, { projects: splitList(e.target.value) })}
+                    />
+                  </label>
+                  <label className="toggle-label">
+                    <span className="toggle-text">Tags</span>
+                    <input
+                      key={profile.tags.join(",")}
+                      className="shortcut-input"
+                      defaultValue={profile.tags.join(", ")}
+                      placeholder="urgent, calls"
+                      onBlur={(e) => updateProfile(index, { tags: splitList(e.target.value) })}
+                    />
+                  </label>
+                  <label className="toggle-label">
+                    <span className="toggle-text">Language</span>
+                    <input
+                      key={profile.language ?? ""}
+                      className="shortcut-input"
+                      defaultValue={profile.language ?? ""}
+                      placeholder="en / ru / auto"
+                      onBlur={(e) => updateProfile(index, { language: e.target.value.trim().toLowerCase() || null })}
+                    />
+                  </label>
+                  <textarea
+                    key={profile.prompt}
+                    className="shortcut-input"
+                    defaultValue={profile.prompt}
+                    placeholder="Extra instructions for Ollama, e.g. Add +work to every task"
+                    rows={2}
+                    onBlur={(e) => updateProfile(index, { prompt: e.target.value })}
+                  />
+                  <label className="toggle-label">
+                    <input
+                      type="checkbox"
+                      checked={profile.schedule !== null}
+                      onChange={() => updateProfile(index, { schedule: profile.schedule ? null : { ...schedule } })}
+                    />
+                    <span className="toggle-text">Own working hours</span>
+                  </label>
+                  {profile.schedule && (
+                    <>
+                      <label className="toggle-label">
+                        <input
+                          type="checkbox"
+                          checked={profile.schedule.enabled}
+                          onChange={() => updateProfile(index, { schedule: { ...profile.schedule!, enabled: !profile.schedule!.enabled } })}
+                        />
+                        <span className="toggle-text">Only run the timer during working hours</span>
+                      </label>
+                      {profile.schedule.enabled && (
+                        <>
+                          <div className="schedule-days">
+                            {WEEKDAYS.map((name, day) => (
+                              <label key={name} className="toggle-label">
+                                <input
+                                  type="checkbox"
+                                  checked={profile.schedule!.days.includes(day)}
+                                  onChange={() => {
+                                    const days = profile.schedule!.days.includes(day)
+                                      ? profile.schedule!.days.filter(d => d !== day)
+                                      : [...profile.schedule!.days, day].sort();
+                                    updateProfile(index, { schedule: { ...profile.schedule!, days } });
+                                  }}
+                                />
+                                <span className="toggle-text">{name}</span>
+                              </label>
+                            ))}
+                          </div>
+                          <div className="schedule-hours">
+                            <input
+                              type="time"
+                              value={profile.schedule.start}
+                              onChange={(e) => updateProfile(index, { schedule: { ...profile.schedule!, start: e.target.value } })}
+                            />
+                            <span className="toggle-text">to</span>
+                            <input
+                              type="time"
+                              value={profile.schedule.end}
+                              onChange={(e) => updateProfile(index, { schedule: { ...profile.schedule!, end: e.target.value } })}
+                            />
+                          </div>
+                        </>
+                      )}
+                    </>
+                  )}
+                  <button className="diagnostics-button" onClick={() => deleteProfile(index)}>
+                    Delete profile
+                  </button>
+                </details>
+              ))}
+              <label className="toggle-label">
+                <input
+                  className="shortcut-input"
+                  value={newProfileName}
+                  placeholder="New profile name"
+                  onChange={(e) => setNewProfileName(e.target.value)}
+                  onKeyDown={(e) => e.key === "Enter" && addProfile()}
+                />
+                <button className="refresh-devices-button" onClick={addProfile} title="Add profile">
+                  ＋
+                </button>
+              </label>
+              <p className="status-detail">
+                {profileError ?? 'Pick a profile in the header or say "switch to work mode". Tasks with other projects or tags are hidden while it is active; tasks with none always show.'}
+              </p>
+            </div>
+          </div>
 
-        <div className="settings-content">
           <div className="settings-section">
-            <h3>Microphone</h3>
-            <div className="microphone-settings">
-              <div className="device-select-container">
+            <h3>Habits</h3>
+            <div className="autostart-setting">
+              {habits.map((habit) => (
+                <label key={habit.id} className="toggle-label">
+                  <span className="toggle-text">
+                    {habit.name} · {HABIT_SCHEDULES.find((s) => s.value === habit.schedule)?.label ?? habit.schedule}
+                  </span>
+                  <button className="refresh-devices-button" onClick={() => deleteHabit(habit.id)} title="Delete habit">
+                    🗑
+                  </button>
+                </label>
+              ))}
+              <label className="toggle-label">
+                <input
+                  className="shortcut-input"
+                  value={newHabitName}
+                  placeholder="New habit, e.g. meditation"
+                  onChange={(e) => setNewHabitName(e.target.value)}
+                  onKeyDown={(e) => e.key === "Enter" && addHabit()}
+                />
                 <select
                   className="device-select"
-                  value={selectedDeviceId || ''}
-                  onChange={(e) => onDeviceChange?.(e.target.value)}
+                  value={newHabitSchedule}
+                  onChange={(e) => setNewHabitSchedule(e.target.value)}
                 >
-                  {availableDevices.length === 0 ? (
-                    <option value="">No microphones found</option>
-                  ) : (
-                    availableDevices.map((device) => (
-                      <option key={device.deviceId} value={device.deviceId}>
-                        {device.label}
-                      </option>
-                    ))
-                  )}
+                  {HABIT_SCHEDULES.map((s) => (
+                    <option key={s.value} value={s.value}>{s.label}</option>
+                  ))}
                 </select>
-                <button 
-                  className="refresh-devices-button"
-                  onClick={onRefreshDevices}
-                  title="Refresh device list"
-                >
-                  🔄
+              </label>
+              <button className="diagnostics-button" onClick={addHabit}>
+                Add habit
+              </button>
+              <p className="status-detail">
+                {habitError ?? 'Tick today off from the dots above the task list, or say "log habit meditation".'}
+              </p>
+            </div>
+          </div>
+
+          <div className="settings-section">
+            <h3>Voice macros</h3>
+            <div className="autostart-setting">
+              {macros.map((voiceMacro) => (
+                <label key={voiceMacro.id} className="toggle-label">
+                  <span className="toggle-text">
+                    "{voiceMacro.phrase}" · {macroSummary(voiceMacro.actions)}
+                  </span>
+                  <button className="refresh-devices-button" onClick={() => editMacro(voiceMacro)} title="Edit macro">
+                    ✎
+                  </button>
+                  <button className="refresh-devices-button" onClick={() => deleteMacro(voiceMacro.id)} title="Delete macro">
+                    🗑
+                  </button>
+                </label>
+              ))}
+              <label className="toggle-label">
+                <input
+                  className="shortcut-input"
+                  value={macroPhrase}
+                  placeholder="Phrase, e.g. standup"
+                  onChange={(e) => setMacroPhrase(e.target.value)}
+                />
+              </label>
+              <textarea
+                className="shortcut-input"
+                value={macroTasks}
+                placeholder="Tasks to add, one per line"
+                rows={3}
+                onChange={(e) => setMacroTasks(e.target.value)}
+              />
+              <label className="toggle-label">
+                <input type="checkbox" checked={macroArchive} onChange={(e) => setMacroArchive(e.target.checked)} />
+                <span className="toggle-text">Archive completed tasks</span>
+              </label>
+              <select className="device-select" value={macroTimer} onChange={(e) => setMacroTimer(e.target.value)}>
+                {MACRO_TIMER_COMMANDS.map((command) => (
+                  <option key={command.value} value={command.value}>{command.label}</option>
+                ))}
+              </select>
+              <button className="diagnostics-button" onClick={saveMacro}>
+                {editingMacro === null ? "Add macro" : "Save macro"}
+              </button>
+              {editingMacro !== null && (
+                <button className="diagnostics-button" onClick={resetMacroForm}>
+                  Cancel
                 </button>
-              </div>
-              {availableDevices.length === 0 && (
-                <p className="status-detail">
-                  No microphones detected. Please connect a microphone and click refresh.
-                </p>
               )}
+              <p className="status-detail">
+                {macroError ?? "Saying just the phrase runs the macro instead of adding it as a task. Macros go before the other voice commands."}
+              </p>
             </div>
           </div>
 
           <div className="settings-section">
-            <h3>Timer</h3>
-            <div className="timer-setting">
-              <label className="slider-label">
-                <span className="slider-text">Awareness Timer Duration</span>
-                <span className="slider-value">{timerDuration} min</span>
+            <h3>Re-parse history</h3>
+            <div className="autostart-setting">
+              <label className="toggle-label">
+                <input type="date" className="shortcut-input" value={reparseFrom} onChange={(e) => setReparseFrom(e.target.value)} />
+                <span className="toggle-text">to</span>
+                <input type="date" className="shortcut-input" value={reparseTo} onChange={(e) => setReparseTo(e.target.value)} />
+              </label>
+              <button className="diagnostics-button" onClick={checkReparse} disabled={reparseRunning}>
+                {reparseRunning ? "Parsing..." : "Preview changes"}
+              </button>
+              {reparseDiffs && reparseDiffs.length > 0 && (
+                <>
+                  <ul className="diagnostics-checks">
+                    {reparseDiffs.map((diff) => (
+                      <li key={diff.transcription_id} className="diagnostics-check warning">
+                        <label className="toggle-label">
+                          <input
+                            type="checkbox"
+                            checked={reparseSelected.includes(diff.transcription_id)}
+                            onChange={() => toggleReparse(diff.transcription_id)}
+                          />
+                          <span className="diagnostics-check-label">"{diff.transcript}"</span>
+                        </label>
+                        {diff.added.map((action, index) => (
+                          <span key={`+${index}`} className="status-detail">
+                            + {action.kind === "add" ? "Add" : "Complete"} "{action.text}"
+                          </span>
+                        ))}
+                        {diff.removed.map((action, index) => (
+                          <span key={`-${index}`} className="status-detail">
+                            − {action.kind === "add" ? "Add" : "Complete"} "{action.text}"
+                          </span>
+                        ))}
+                      </li>
+                    ))}
+                  </ul>
+                  <button className="diagnostics-button" onClick={applyReparse} disabled={reparseRunning || reparseSelected.length === 0}>
+                    Apply to {reparseSelected.length} note(s)
+                  </button>
+                </>
+              )}
+              <p className="status-detail">
+                {reparseStatus ?? "Runs your past voice notes through the current parser and shows what it would do differently. Applying adds and completes the new tasks and deletes open ones only the old reading added."}
+              </p>
+            </div>
+          </div>
+
+          <div className="settings-section">
+            <h3>Clipboard</h3>
+            <div className="autostart-setting">
+              <label className="toggle-label">
+                <input
+                  type="checkbox"
+                  checked={clipboard.enabled}
+                  onChange={() => updateClipboard({ ...clipboard, enabled: !clipboard.enabled })}
+                />
+                <span className="toggle-text">Add copied text as a task</span>
+              </label>
+              {clipboard.enabled && (
+                <input
+                  className="shortcut-input"
+                  value={clipboardTrigger}
+                  placeholder="todo:"
+                  onChange={(e) => setClipboardTrigger(e.target.value)}
+                  onBlur={() => updateClipboard({ ...clipboard, trigger: clipboardTrigger.trim() })}
+                  onKeyDown={(e) => e.key === "Enter" && e.currentTarget.blur()}
+                />
+              )}
+              <p className="status-detail">
+                {clipboardError ?? `Copy something like "${clipboard.trigger || "todo:"} call the bank" anywhere to add it as a task.`}
+              </p>
+            </div>
+          </div>
+
+          <div className="settings-section">
+            <h3>Telegram</h3>
+            <div className="autostart-setting">
+              <label className="toggle-label">
+                <input
+                  type="checkbox"
+                  checked={telegram.enabled}
+                  onChange={() => updateTelegram({ ...telegram, enabled: !telegram.enabled })}
+                />
+                <span className="toggle-text">Capture tasks from a Telegram bot</span>
+              </label>
+              {telegram.enabled && (
+                <>
+                  <input
+                    className="shortcut-input"
+                    type="password"
+                    value={telegramToken}
+                    placeholder="Bot token"
+                    onChange={(e) => setTelegramToken(e.target.value)}
+                    onBlur={() => updateTelegram({ ...telegram, token: telegramToken.trim() })}
+                    onKeyDown={(e) => e.key === "Enter" && e.currentTarget.blur()}
+                  />
+                  <input
+                    className="shortcut-input"
+                    value={telegramUsername}
+                    placeholder="@username"
+                    onChange={(e) => setTelegramUsername(e.target.value)}
+                    onBlur={() => updateTelegram({ ...telegram, username: telegramUsername.trim() })}
+                    onKeyDown={(e) => e.key === "Enter" && e.currentTarget.blur()}
+                  />
+                </>
+              )}
+              <p className="status-detail">
+                {telegramError ?? "Create a bot with @BotFather, then send it text or voice notes from your phone; they become tasks while FlowState is running. Messages from other users are ignored."}
+              </p>
+            </div>
+          </div>
+
+          <div className="settings-section">
+            <h3>API tokens</h3>
+            <div className="autostart-setting">
+              {apiTokens.map((token) => (
+                <label key={token.id} className="toggle-label">
+                  <span className="toggle-text">
+                    {token.name} · {token.prefix}… · {token.scopes.join(", ")}
+                    {token.last_used_at ? ` · last used ${token.last_used_at}` : " · never used"}
+                  </span>
+                  <button className="refresh-devices-button" onClick={() => revokeApiToken(token.id)} title="Revoke token">
+                    🗑
+                  </button>
+                </label>
+              ))}
+              <label className="toggle-label">
+                <input
+                  className="shortcut-input"
+                  value={tokenName}
+                  placeholder="Client name, e.g. phone shortcut"
+                  onChange={(e) => setTokenName(e.target.value)}
+                  onKeyDown={(e) => e.key === "Enter" && issueApiToken()}
+                />
+              </label>
+              {TOKEN_SCOPES.map((scope) => (
+                <label key={scope.value} className="toggle-label">
+                  <input
+                    type="checkbox"
+                    checked={tokenScopes.includes(scope.value)}
+                    onChange={() => toggleTokenScope(scope.value)}
+                  />
+                  <span className="toggle-text">{scope.label}</span>
+                </label>
+              ))}
+              <button className="diagnostics-button" onClick={issueApiToken}>
+                Issue token
+              </button>
+              {newSecret && (
+                <label className="toggle-label">
+                  <input className="shortcut-input" readOnly value={newSecret} onFocus={(e) => e.currentTarget.select()} />
+                  <button className="refresh-devices-button" onClick={() => navigator.clipboard.writeText(newSecret)} title="Copy token">
+                    📋
+                  </button>
+                </label>
+              )}
+              <p className="status-detail">
+                {tokenError ?? (newSecret
+                  ? "Copy the token now: only its hash is kept, so it can't be shown again."
+                  : "Each client that talks to FlowState over the network gets its own token, limited to what it needs. Revoking one cuts its client off at once.")}
+              </p>
+            </div>
+          </div>
+
+          <div className="settings-section">
+            <h3>Hands-free</h3>
+            <div className="autostart-setting">
+              <label className="toggle-label">
+                <input
+                  type="checkbox"
+                  checked={wakeWordEnabled}
+                  onChange={toggleWakeWordEnabled}
+                />
+                <span className="toggle-text">
+                  Start recording when I say "hey flow"
+                </span>
               </label>
+              <p className="status-detail">
+                {wakeWordError
+                  ? wakeWordError
+                  : "Keeps the microphone open in the background. Requires the Tiny model."}
+              </p>
+            </div>
+          </div>
+
+          <div className="settings-section">
+            <h3>Quick add</h3>
+            <div className="autostart-setting">
               <input
-                type="range"
-                min="0"
-                max="60"
-                value={timerDuration}
-                onInput={(e) => {
-                  const value = parseInt((e.target as HTMLInputElement).value);
-                  setTimerDuration(value);
-                }}
-                onChange={(e) => handleTimerDurationChange(parseInt(e.target.value))}
-                className="timer-slider"
+                className="shortcut-input"
+                value={quickAddShortcut}
+                onChange={(e) => setQuickAddShortcut(e.target.value)}
+                onBlur={saveQuickAddShortcut}
+                onKeyDown={(e) => e.key === "Enter" && e.currentTarget.blur()}
               />
               <p className="status-detail">
-                Set the timer duration (0-60 minutes). Timer alerts you when it expires.
+                {quickAddError ?? "Global shortcut for a small box to type a task into, e.g. CommandOrControl+Alt+N"}
+              </p>
+            </div>
+          </div>
+
+          <div className="settings-section">
+            <h3>Compact mode</h3>
+            <div className="autostart-setting">
+              <input
+                className="shortcut-input"
+                value={compactShortcut}
+                onChange={(e) => setCompactShortcut(e.target.value)}
+                onBlur={saveCompactShortcut}
+                onKeyDown={(e) => e.key === "Enter" && e.currentTarget.blur()}
+              />
+              <p className="status-detail">
+                {compactShortcutError ?? "Global shortcut that shrinks the window to a small always-on-top strip with the timer, record button and top task"}
+              </p>
+            </div>
+          </div>
+
+          <div className="settings-section">
+            <h3>Focus history (7 days)</h3>
+            <div className="autostart-setting">
+              {focusHistory.length === 0 ? (
+                <p className="status-detail">No checkpoints yet.</p>
+              ) : (
+                focusHistory.map((day) => (
+                  <p key={day.date} className="status-detail">
+                    {day.date}: {day.acknowledged}/{day.checkpoints} checkpoints answered, {Math.round(day.focus_seconds / 60)} min focused
+                  </p>
+                ))
+              )}
+            </div>
+          </div>
+
+          <div className="settings-section">
+            <h3>Sound</h3>
+            <div className="autostart-setting">
+              <label className="toggle-label">
+                <input
+                  type="checkbox"
+                  checked={earconsMuted}
+                  onChange={toggleEarconsMuted}
+                />
+                <span className="toggle-text">
+                  Mute sound feedback
+                </span>
+              </label>
+              <p className="status-detail">
+                Short tones when recording starts, stops, and when tasks are added
               </p>
             </div>
           </div>
@@ -268,9 +2635,224 @@ export default function SettingsModal({
             </div>
           </div>
 
+          <div className="settings-section">
+            <h3>OpenAI</h3>
+            <div className="autostart-setting">
+              <label className="toggle-label">
+                <input
+                  type="checkbox"
+                  checked={openai.enabled}
+                  onChange={() => updateOpenai({ ...openai, enabled: !openai.enabled })}
+                />
+                <span className="toggle-text">Use OpenAI for parsing</span>
+              </label>
+              {openai.enabled && (
+                <>
+                  <input
+                    className="shortcut-input"
+                    type="password"
+                    value={openaiKey}
+                    placeholder="API key"
+                    onChange={(e) => setOpenaiKey(e.target.value)}
+                    onBlur={() => updateOpenai({ ...openai, api_key: openaiKey.trim() })}
+                    onKeyDown={(e) => e.key === "Enter" && e.currentTarget.blur()}
+                  />
+                  <input
+                    className="shortcut-input"
+                    value={openaiModel}
+                    placeholder="gpt-4o-mini"
+                    onChange={(e) => setOpenaiModel(e.target.value)}
+                    onBlur={() => updateOpenai({ ...openai, model: openaiModel.trim() })}
+                    onKeyDown={(e) => e.key === "Enter" && e.currentTarget.blur()}
+                  />
+                </>
+              )}
+              <p className="status-detail">
+                {openaiError ?? "Transcripts are sent to OpenAI to be split into tasks."}
+              </p>
+            </div>
+            <div className="autostart-setting">
+              <span className="toggle-text">Parser order</span>
+              <select
+                className="device-select"
+                value={parserChain.join(",")}
+                onChange={(e) => updateSettings({ parser_chain: e.target.value.split(",") as ParserKind[] })
+                  .catch(error => console.error("Failed to update parser order:", error))}
+              >
+                {!PARSER_CHAINS.some(chain => chain.value.join(",") === parserChain.join(",")) && (
+                  <option value={parserChain.join(",")}>{parserChain.join(", then ")}</option>
+                )}
+                {PARSER_CHAINS.map(chain => (
+                  <option key={chain.value.join(",")} value={chain.value.join(",")}>{chain.label}</option>
+                ))}
+              </select>
+              <p className="status-detail">
+                Each voice memo goes to the first parser that answers. Ollama and OpenAI are skipped while they're off.
+              </p>
+            </div>
+            <div className="autostart-setting">
+              <label className="toggle-label">
+                <input
+                  type="checkbox"
+                  checked={punctuation.enabled}
+                  onChange={() => updateSettings({ punctuation: { ...punctuation, enabled: !punctuation.enabled } })
+                    .catch(error => console.error("Failed to toggle punctuation:", error))}
+                />
+                <span className="toggle-text">Restore punctuation in run-on transcripts</span>
+              </label>
+              {punctuation.enabled && ollamaEnabled && (
+                <label className="toggle-label">
+                  <input
+                    type="checkbox"
+                    checked={punctuation.ollama}
+                    onChange={() => updateSettings({ punctuation: { ...punctuation, ollama: !punctuation.ollama } })
+                      .catch(error => console.error("Failed to toggle Ollama punctuation:", error))}
+                  />
+                  <span className="toggle-text">Let Ollama punctuate them</span>
+                </label>
+              )}
+              <p className="status-detail">
+                Turns "buy milk then call mom" into "Buy milk. Call mom." before the built-in rules split it into tasks.
+              </p>
+            </div>
+          </div>
+
           <div className="settings-section">
             <h3>Whisper (Speech-to-Text)</h3>
             <ModelManager />
+            <div className="autostart-setting">
+              <label className="toggle-label">
+                <span className="toggle-text">Unload after idle (minutes, 0 = never)</span>
+                <input
+                  className="shortcut-input"
+                  type="number"
+                  min="0"
+                  max="240"
+                  value={modelUnloadMinutes}
+                  onChange={(e) => setModelUnloadMinutes(parseInt(e.target.value) || 0)}
+                  onBlur={() => updateModelUnload(modelUnloadMinutes)}
+                />
+              </label>
+              <button className="diagnostics-button" onClick={unloadModel}>
+                Unload now
+              </button>
+              <p className="status-detail">
+                {modelUnloadStatus ?? "Frees the model's memory (up to 1.5 GB) when you haven't recorded for a while"}
+              </p>
+            </div>
+            <div className="autostart-setting">
+              <label className="toggle-label">
+                <span className="toggle-text">Transcription language</span>
+                <input
+                  className="shortcut-input"
+                  value={transcriptionLanguage}
+                  placeholder="ru"
+                  onChange={(e) => setTranscriptionLanguage(e.target.value)}
+                  onBlur={() => updateTranscriptionLanguage(transcriptionLanguage)}
+                  onKeyDown={(e) => e.key === "Enter" && e.currentTarget.blur()}
+                />
+              </label>
+              <p className="status-detail">
+                {transcriptionLanguageError ?? "Language code Whisper listens for (\"en\", \"ru\", ...), or \"auto\" to detect it. A profile's language wins over this."}
+              </p>
+            </div>
+          </div>
+
+          <div className="settings-section">
+            <h3>Updates</h3>
+            <div className="autostart-setting">
+              <label className="toggle-label">
+                <input
+                  type="checkbox"
+                  checked={autoCheckUpdates}
+                  onChange={() => updateSettings({ auto_check_updates: !autoCheckUpdates })
+                    .catch(error => console.error("Failed to update settings:", error))}
+                />
+                <span className="toggle-text">Check for updates on startup</span>
+              </label>
+              <button className="diagnostics-button" onClick={checkForUpdates}>
+                Check now
+              </button>
+              {availableUpdate && (
+                <>
+                  <p className="status-detail">
+                    Version {availableUpdate.version} is available
+                  </p>
+                  {availableUpdate.notes && <pre className="update-notes">{availableUpdate.notes}</pre>}
+                  <button className="diagnostics-button" onClick={() => handleInstallUpdate(availableUpdate)}>
+                    {availableUpdate.installable ? "Install and restart" : "Open release page"}
+                  </button>
+                </>
+              )}
+              <p className="status-detail">
+                {updateStatus ?? (availableUpdate === false ? "FlowState is up to date" : "Checks the GitHub releases page")}
+              </p>
+            </div>
+          </div>
+
+          <div className="settings-section">
+            <h3>Diagnostics</h3>
+            <div className="autostart-setting">
+              <button className="diagnostics-button" onClick={runDiagnostics} disabled={diagnosticsRunning}>
+                {diagnosticsRunning ? "Running checks..." : "Run checks"}
+              </button>
+              {diagnostics && (
+                <ul className="diagnostics-checks">
+                  {diagnostics.checks.map(check => (
+                    <li key={check.id} className={`diagnostics-check ${check.status}`}>
+                      <span className="diagnostics-check-label">{check.label}</span>
+                      <span className="status-detail">{check.detail}</span>
+                    </li>
+                  ))}
+                </ul>
+              )}
+              <button className="diagnostics-button" onClick={copyDiagnostics}>
+                {diagnosticsCopied ? "Copied!" : "Copy diagnostics"}
+              </button>
+              <p className="status-detail">
+                Copies the app version and recent log lines to attach to a bug report
+              </p>
+            </div>
+          </div>
+
+          <div className="settings-section">
+            <h3>Language</h3>
+            <div className="autostart-setting">
+              <select
+                className="device-select"
+                value={uiLanguage ?? "system"}
+                onChange={(e) => updateSettings({ ui_language: e.target.value === "system" ? null : e.target.value })
+                  .catch(error => console.error("Failed to update language:", error))}
+              >
+                <option value="system">System default</option>
+                <option value="en">English</option>
+                <option value="ru">Русский</option>
+              </select>
+              <p className="status-detail">
+                Language of error and status messages from FlowState
+              </p>
+            </div>
+          </div>
+
+          <div className="settings-section">
+            <h3>Closing</h3>
+            <div className="autostart-setting">
+              <select
+                className="device-select close-behavior-select"
+                value={closeBehavior}
+                onChange={(e) => updateSettings({ close_behavior: e.target.value as "hide" | "quit" })
+                  .catch(error => console.error("Failed to update close behavior:", error))}
+              >
+                <option value="hide">Closing the window keeps FlowState in the tray</option>
+                <option value="quit">Closing the window quits FlowState</option>
+              </select>
+              <button className="diagnostics-button" onClick={() => invoke("exit_app")}>
+                Quit FlowState
+              </button>
+              <p className="status-detail">
+                Quitting waits for any recording in progress to be processed. The tray menu has Quit too.
+              </p>
+            </div>
           </div>
         </div>
       </div>
@@ -0,0 +1,51 @@
+import { useState, useEffect } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import "./StartupBanner.css";
+
+// Mirrors diagnostics::DiagnosticsReport on the Rust side (startup::report)
+interface StartupCheck {
+  id: string;
+  label: string;
+  status: "ok" | "warning" | "error";
+  detail: string;
+}
+
+interface StartupReport {
+  checks: StartupCheck[];
+}
+
+// What went wrong (or was repaired) at launch; hidden when every check passed
+export default function StartupBanner() {
+  const [problems, setProblems] = useState<StartupCheck[]>([]);
+
+  useEffect(() => {
+    const show = (report: StartupReport | null) => {
+      if (report) setProblems(report.checks.filter(check => check.status !== "ok"));
+    };
+    // The report may have gone out before this listener was registered
+    invoke<StartupReport | null>("get_startup_report").then(show).catch(console.error);
+    const unlisten = listen<StartupReport>("startup-report", (event) => show(event.payload));
+    return () => {
+      unlisten.then(fn => fn());
+    };
+  }, []);
+
+  if (problems.length === 0) return null;
+
+  const isError = problems.some(check => check.status === "error");
+  return (
+    <div className={`startup-banner ${isError ? "error" : ""}`}>
+      <ul className="startup-problems">
+        {problems.map(check => (
+          <li key={check.id}>
+            <strong>{check.label}:</strong> {check.detail}
+          </li>
+        ))}
+      </ul>
+      <div className="startup-actions">
+        <button onClick={() => setProblems([])}>Dismiss</button>
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,53 @@
+import "./TaskFilterBar.css";
+
+// Matches database::TaskStatus values the list can be loaded with
+export type StatusFilter = "recent" | "open" | "completed" | "inbox";
+
+interface TaskFilterBarProps {
+  status: StatusFilter;
+  // +project picked by voice ("switch to work project")
+  project: string | null;
+  // Voice tasks waiting to be triaged; the Inbox chip only shows while there are some
+  inboxCount: number;
+  onChange: (status: StatusFilter, project: string | null) => void;
+}
+
+const STATUS_CHIPS: Array<[StatusFilter, string]> = [
+  ["open", "Open"],
+  ["completed", "Done"],
+];
+
+// Open/Done/Inbox chips and the voice-picked project; clicking a selected chip clears it
+export default function TaskFilterBar({ status, project, inboxCount, onChange }: TaskFilterBarProps) {
+  return (
+    <div className="task-filter-bar">
+      {STATUS_CHIPS.map(([value, label]) => (
+        <button
+          key={value}
+          className={`task-filter-chip ${status === value ? "selected" : ""}`}
+          onClick={() => onChange(status === value ? "recent" : value, project)}
+        >
+          {label}
+        </button>
+      ))}
+      {(inboxCount > 0 || status === "inbox") && (
+        <button
+          className={`task-filter-chip ${status === "inbox" ? "selected" : ""}`}
+          onClick={() => onChange(status === "inbox" ? "recent" : "inbox", project)}
+          title="Voice tasks waiting to be kept, scheduled or discarded"
+        >
+          📥 Inbox <span className="task-filter-count">{inboxCount}</span>
+        </button>
+      )}
+      {project && (
+        <button
+          className="task-filter-chip selected"
+          onClick={() => onChange(status, null)}
+          title="Show every project"
+        >
+          +{project} ✕
+        </button>
+      )}
+    </div>
+  );
+}
@@ -1,4 +1,6 @@
 import { useState } from "react";
+import { open } from "@tauri-apps/plugin-shell";
+import { formatEstimate } from "./WorkloadBar";
 import "./TaskList.css";
 
 interface Task {
@@ -7,6 +9,16 @@ interface Task {
   completed: boolean;
   created_at: string;
   completed_at: string | null;
+  // GitHub issue filed for the task
+  issue_url: string | null;
+  // GTD context without the "@"
+  context: string | null;
+  // Estimated minutes, from "about an hour" or "~30m" at the end of the text
+  estimate_minutes: number | null;
+  // When it's due, UTC "YYYY-MM-DD HH:MM:SS" ("tomorrow", "by Friday" in the memo)
+  due_at: string | null;
+  // Captured by voice and waiting to be triaged (settings.voice_inbox)
+  inbox: boolean;
 }
 
 interface TaskListProps {
@@ -14,11 +26,105 @@ interface TaskListProps {
   onToggle: (id: number) => void;
   onDelete: (id: number) => void;
   onUpdate: (id: number, text: string) => void;
+  onFocus?: (id: number) => void;
+  // Pending reminders by task id, local "YYYY-MM-DD HH:MM"
+  reminders?: Record<number, string>;
+  onRemind?: (id: number, when: string) => void;
+  onSetDue?: (id: number, dueAt: string | null) => void;
+  // Inbox triage: keep the task as is, or keep it due `when` (× discards it)
+  onKeep?: (id: number) => void;
+  onSchedule?: (id: number, when: string) => void;
 }
 
-export default function TaskList({ tasks, onToggle, onDelete, onUpdate }: TaskListProps) {
+// "18:30" for today, the full date and time otherwise
+function reminderLabel(remindAt: string): string {
+  const today = new Date();
+  const pad = (n: number) => String(n).padStart(2, "0");
+  const todayPrefix = `${today.getFullYear()}-${pad(today.getMonth() + 1)}-${pad(today.getDate())}`;
+  return remindAt.startsWith(todayPrefix) ? remindAt.slice(11) : remindAt;
+}
+
+const pad = (n: number) => String(n).padStart(2, "0");
+
+// A picked day is due by its end: 23:59 local, stored in UTC
+function dueFromDay(day: string): string {
+  const [year, month, date] = day.split("-").map(Number);
+  return new Date(year, month - 1, date, 23, 59).toISOString().slice(0, 19).replace("T", " ");
+}
+
+function localDue(dueAt: string): Date {
+  return new Date(dueAt.replace(" ", "T") + "Z");
+}
+
+function dueDay(dueAt: string): string {
+  const due = localDue(dueAt);
+  return `${due.getFullYear()}-${pad(due.getMonth() + 1)}-${pad(due.getDate())}`;
+}
+
+// "Tomorrow", "Fri 15:00", "12 May"; a date said without a time is stored as 23:59
+function dueLabel(dueAt: string): { label: string; overdue: boolean } {
+  const due = localDue(dueAt);
+  const now = new Date();
+  const startOfDay = (d: Date) => new Date(d.getFullYear(), d.getMonth(), d.getDate()).getTime();
+  const days = Math.round((startOfDay(due) - startOfDay(now)) / 86_400_000);
+  const date =
+    days === 0
+      ? "Today"
+      : days === 1
+        ? "Tomorrow"
+        : days > 1 && days < 7
+          ? due.toLocaleDateString(undefined, { weekday: "short" })
+          : due.toLocaleDateString(undefined, { day: "numeric", month: "short" });
+  const hasTime = !(due.getHours() === 23 && due.getMinutes() === 59);
+  return {
+    label: hasTime ? `${date} ${pad(due.getHours())}:${pad(due.getMinutes())}` : date,
+    overdue: due < now,
+  };
+}
+
+export default function TaskList({ tasks, onToggle, onDelete, onUpdate, onFocus, reminders = {}, onRemind, onSetDue, onKeep, onSchedule }: TaskListProps) {
   const [editingId, setEditingId] = useState<number | null>(null);
   const [editText, setEditText] = useState("");
+  // Task whose reminder time is being typed
+  const [remindingId, setRemindingId] = useState<number | null>(null);
+  const [remindText, setRemindText] = useState("");
+  // Task whose due date is being picked
+  const [dueId, setDueId] = useState<number | null>(null);
+  // Inbox task whose due date is being typed
+  const [schedulingId, setSchedulingId] = useState<number | null>(null);
+  const [scheduleText, setScheduleText] = useState("");
+
+  const handleScheduleSubmit = (id: number) => {
+    if (scheduleText.trim()) {
+      onSchedule?.(id, scheduleText.trim());
+    }
+    setSchedulingId(null);
+    setScheduleText("");
+  };
+
+  const handleScheduleKeyDown = (e: React.KeyboardEvent, id: number) => {
+    if (e.key === "Enter") {
+      handleScheduleSubmit(id);
+    } else if (e.key === "Escape") {
+      setSchedulingId(null);
+      setScheduleText("");
+    }
+  };
+
+  const handleRemindSubmit = (id: number) => {
+    onRemind?.(id, remindText.trim());
+    setRemindingId(null);
+    setRemindText("");
+  };
+
+  const handleRemindKeyDown = (e: React.KeyboardEvent, id: number) => {
+    if (e.key === "Enter") {
+      handleRemindSubmit(id);
+    } else if (e.key === "Escape") {
+      setRemindingId(null);
+      setRemindText("");
+    }
+  };
 
   const handleDoubleClick = (task: Task) => {
     setEditingId(task.id);
@@ -84,8 +190,109 @@ export default function TaskList({ tasks, onToggle, onDelete, onUpdate }: TaskLi
               onDoubleClick={() => handleDoubleClick(task)}
             >
               {task.text}
+              {task.context && <span className="task-context"> @{task.context}</span>}
+              {task.estimate_minutes != null && (
+                <span className="task-estimate"> ~{formatEstimate(task.estimate_minutes)}</span>
+              )}
+              {task.due_at && !task.completed && (() => {
+                const { label, overdue } = dueLabel(task.due_at);
+                return (
+                  <span className={overdue ? "task-due overdue" : "task-due"} title={`Due ${label}`}>
+                    {" "}📅 {label}
+                  </span>
+                );
+              })()}
             </span>
           )}
+          {task.issue_url && (
+            <button
+              className="task-delete"
+              onClick={() => open(task.issue_url!)}
+              title={task.issue_url}
+            >
+              🐙
+            </button>
+          )}
+          {task.inbox && onSchedule && (schedulingId === task.id ? (
+            <input
+              type="text"
+              value={scheduleText}
+              onChange={(e) => setScheduleText(e.target.value)}
+              onBlur={() => handleScheduleSubmit(task.id)}
+              onKeyDown={(e) => handleScheduleKeyDown(e, task.id)}
+              className="task-remind-input"
+              placeholder="tomorrow, friday"
+              autoFocus
+            />
+          ) : (
+            <button
+              className="task-delete"
+              onClick={() => setSchedulingId(task.id)}
+              title="Keep it with a due date"
+            >
+              📅
+            </button>
+          ))}
+          {task.inbox && onKeep && (
+            <button
+              className="task-delete"
+              onClick={() => onKeep(task.id)}
+              title="Keep it in the list"
+            >
+              ✔
+            </button>
+          )}
+          {onRemind && (remindingId === task.id ? (
+            <input
+              type="text"
+              value={remindText}
+              onChange={(e) => setRemindText(e.target.value)}
+              onBlur={() => handleRemindSubmit(task.id)}
+              onKeyDown={(e) => handleRemindKeyDown(e, task.id)}
+              className="task-remind-input"
+              placeholder="18:30, in 1 hour"
+              autoFocus
+            />
+          ) : (
+            <button
+              className="task-delete"
+              onClick={() => setRemindingId(task.id)}
+              title="Remind me about this task (an empty time clears the reminder)"
+            >
+              ⏰{reminders[task.id] && <span className="task-reminder"> {reminderLabel(reminders[task.id])}</span>}
+            </button>
+          ))}
+          {onSetDue && (dueId === task.id ? (
+            <input
+              type="date"
+              className="task-due-input"
+              defaultValue={task.due_at ? dueDay(task.due_at) : ""}
+              onChange={(e) => {
+                onSetDue(task.id, e.target.value ? dueFromDay(e.target.value) : null);
+                setDueId(null);
+              }}
+              onBlur={() => setDueId(null)}
+              onKeyDown={(e) => e.key === "Escape" && setDueId(null)}
+              autoFocus
+            />
+          ) : (
+            <button
+              className="task-delete"
+              onClick={() => setDueId(task.id)}
+              title="Set a due date (clear it to remove)"
+            >
+              📅
+            </button>
+          ))}
+          {onFocus && (
+            <button
+              className="task-delete"
+              onClick={() => onFocus(task.id)}
+              title="Focus on this task for 25 minutes"
+            >
+              ⏱
+            </button>
+          )}
           <button
             className="task-delete"
             onClick={() => onDelete(task.id)}
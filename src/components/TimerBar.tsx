@@ -3,9 +3,10 @@ import "./TimerBar.css";
 interface TimerBarProps {
   remaining: number;
   duration: number; // in minutes
+  label?: string | null;
 }
 
-export default function TimerBar({ remaining, duration }: TimerBarProps) {
+export default function TimerBar({ remaining, duration, label }: TimerBarProps) {
   const totalSeconds = duration * 60;
   const progress = totalSeconds > 0 ? (remaining / totalSeconds) * 100 : 0;
   const minutes = Math.floor(remaining / 60);
@@ -15,7 +16,7 @@ export default function TimerBar({ remaining, duration }: TimerBarProps) {
     <div className="timer-bar">
       <div className="timer-progress" style={{ width: `${progress}%` }} />
       <div className="timer-text">
-        {minutes}:{seconds.toString().padStart(2, "0")}
+        {label ? `${label} · ` : ""}{minutes}:{seconds.toString().padStart(2, "0")}
       </div>
     </div>
   );
@@ -0,0 +1,82 @@
+import { useState, useEffect } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { open } from "@tauri-apps/plugin-shell";
+import { errorMessage } from "../errors";
+import "./UpdateBanner.css";
+
+// Mirrors updates::UpdateInfo on the Rust side
+export interface UpdateInfo {
+  version: string;
+  current_version: string;
+  notes: string;
+  release_url: string;
+  // A signed installer this build can verify; otherwise only the release page
+  installable: boolean;
+}
+
+// Install the latest release (the backend looks it up again and checks the
+// signature; the app quits), or open the release page when it can't
+export async function installUpdate(update: UpdateInfo) {
+  if (update.installable) {
+    await invoke("install_update");
+  } else {
+    await open(update.release_url);
+  }
+}
+
+// Startup check for a newer release (when enabled in Settings)
+export default function UpdateBanner() {
+  const [update, setUpdate] = useState<UpdateInfo | null>(null);
+  const [showNotes, setShowNotes] = useState(false);
+  const [installing, setInstalling] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    const check = async () => {
+      try {
+        const { auto_check_updates } = await invoke<{ auto_check_updates: boolean }>("get_settings");
+        if (auto_check_updates) {
+          setUpdate(await invoke<UpdateInfo | null>("check_for_updates"));
+        }
+      } catch (err) {
+        console.error("Failed to check for updates:", err);
+      }
+    };
+    check();
+  }, []);
+
+  if (!update) return null;
+
+  const handleInstall = async () => {
+    setInstalling(true);
+    setError(null);
+    try {
+      await installUpdate(update);
+    } catch (err) {
+      setError(errorMessage(err));
+    } finally {
+      setInstalling(false);
+    }
+  };
+
+  return (
+    <div className="update-banner">
+      <p>
+        FlowState {update.version} is available (you have {update.current_version})
+      </p>
+      <div className="update-actions">
+        <button onClick={handleInstall} disabled={installing}>
+          {installing ? "Downloading..." : update.installable ? "Install" : "Open release"}
+        </button>
+        {update.notes && (
+          <button onClick={() => setShowNotes(!showNotes)}>
+            {showNotes ? "Hide changes" : "What's new"}
+          </button>
+        )}
+        <button onClick={() => setUpdate(null)}>Later</button>
+      </div>
+      {showNotes && <pre className="update-notes">{update.notes}</pre>}
+      {error && <p className="update-error">{error}</p>}
+    </div>
+  );
+}
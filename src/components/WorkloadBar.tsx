@@ -0,0 +1,71 @@
+import { useState, useEffect } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import "./WorkloadBar.css";
+
+// Mirrors estimates::Workload on the Rust side
+interface Workload {
+  planned_minutes: number;
+  done_minutes: number;
+  capacity_minutes: number;
+  over_minutes: number;
+  unestimated: number;
+}
+
+interface WorkloadBarProps {
+  // Any change to the list re-reads the workload
+  tasks: unknown[];
+}
+
+// Same form as estimates::format_estimate: "45m", "1h", "1h30m"
+export function formatEstimate(minutes: number): string {
+  const hours = Math.floor(minutes / 60);
+  const rest = minutes % 60;
+  if (hours === 0) return `${rest}m`;
+  return rest === 0 ? `${hours}h` : `${hours}h${rest}m`;
+}
+
+// Today's estimated work against the daily capacity; red once it's over
+export default function WorkloadBar({ tasks }: WorkloadBarProps) {
+  const [workload, setWorkload] = useState<Workload | null>(null);
+
+  useEffect(() => {
+    invoke<Workload>("get_workload")
+      .then(setWorkload)
+      .catch((e) => console.error("Failed to load workload:", e));
+  }, [tasks]);
+
+  useEffect(() => {
+    // A spoken "what's my workload" and a new capacity both bring fresh numbers
+    const unlistenWorkload = listen<Workload>("workload", (event) => {
+      setWorkload(event.payload);
+    });
+    const unlistenSettings = listen("settings-changed", () => {
+      invoke<Workload>("get_workload").then(setWorkload).catch(() => {});
+    });
+    return () => {
+      unlistenWorkload.then(fn => fn());
+      unlistenSettings.then(fn => fn());
+    };
+  }, []);
+
+  if (!workload || workload.planned_minutes === 0) return null;
+
+  const over = workload.over_minutes > 0;
+  const percent = Math.min(100, (workload.planned_minutes / workload.capacity_minutes) * 100);
+
+  return (
+    <div className={`workload-bar${over ? " over" : ""}`}>
+      <div className="workload-label">
+        Planned today: {formatEstimate(workload.planned_minutes)} of {formatEstimate(workload.capacity_minutes)}
+        {over && <span> · {formatEstimate(workload.over_minutes)} over</span>}
+        {workload.unestimated > 0 && (
+          <span className="workload-unestimated"> · {workload.unestimated} without estimate</span>
+        )}
+      </div>
+      <div className="workload-track">
+        <div className="workload-fill" style={{ width: `${percent}%` }} />
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,38 @@
+// Errors from backend commands. Commands that can fail in ways the UI can do
+// something about reject with `{ code, message }` (see src-tauri/src/error.rs);
+// the rest still reject with a plain string.
+
+export type ErrorCode =
+  | "model_missing"
+  | "invalid_model"
+  | "model_corrupt"
+  | "microphone_unavailable"
+  | "duplicate_recording"
+  | "processing_busy"
+  | "database_locked"
+  | "database"
+  | "other";
+
+export interface FlowStateError {
+  code: ErrorCode;
+  message: string;
+  /** Set for model_missing, invalid_model and model_corrupt */
+  model?: string;
+}
+
+export function isFlowStateError(error: unknown): error is FlowStateError {
+  return typeof error === "object" && error !== null && "code" in error && "message" in error;
+}
+
+/** Text to show for any rejected invoke or thrown error */
+export function errorMessage(error: unknown): string {
+  if (isFlowStateError(error) || error instanceof Error) {
+    return error.message;
+  }
+  return String(error);
+}
+
+/** The error's code, if the backend sent one */
+export function errorCode(error: unknown): ErrorCode | null {
+  return isFlowStateError(error) ? error.code : null;
+}
@@ -1,10 +1,15 @@
 import React from "react";
 import ReactDOM from "react-dom/client";
 import App from "./App";
+import BreakOverlay from "./components/BreakOverlay";
+import QuickAdd from "./components/QuickAdd";
 import "./styles.css";
 
+// The hard-break overlay and the quick-add box are extra windows loading the same bundle
+const view = new URLSearchParams(window.location.search).get("view");
+
 ReactDOM.createRoot(document.getElementById("root")!).render(
   <React.StrictMode>
-    <App />
+    {view === "break" ? <BreakOverlay /> : view === "quick-add" ? <QuickAdd /> : <App />}
   </React.StrictMode>,
 );